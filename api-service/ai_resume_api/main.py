@@ -166,15 +166,18 @@ async def health_check() -> HealthResponse:
     try:
         memvid_client = await get_memvid_client()
         memvid_health = await memvid_client.health_check()
-        memvid_connected = memvid_health.status == "SERVING"
+        # DEGRADED: serving a stale index because reloads are failing
+        memvid_connected = memvid_health.status in ("SERVING", "DEGRADED")
+        memvid_degraded = memvid_health.status == "DEGRADED"
         frame_count = memvid_health.frame_count
     except Exception:
         memvid_connected = False
+        memvid_degraded = False
         frame_count = None
 
     # Determine overall status
     status: str = "healthy"
-    if memvid_connected and frame_count and frame_count > 0:
+    if memvid_connected and not memvid_degraded and frame_count and frame_count > 0:
         status = "healthy"
     elif memvid_connected:
         status = "degraded"  # Connected but no data (frame_count == 0)
@@ -394,7 +394,7 @@ class MemvidClient:
                 timeout=self._timeout,
             )
 
-            status_map = {0: "UNKNOWN", 1: "SERVING", 2: "NOT_SERVING"}
+            status_map = {0: "UNKNOWN", 1: "SERVING", 2: "NOT_SERVING", 3: "DEGRADED"}
             status_str = status_map.get(response.status, "UNKNOWN")
             return MemvidHealthResponse(
                 status=cast(str, status_str),  # type: ignore[arg-type]
@@ -413,7 +413,8 @@ class MemvidClient:
         """Check if the memvid service is healthy."""
         try:
             response = await self.health_check()
-            return response.status == "SERVING"
+            # DEGRADED still serves the last good index
+            return response.status in ("SERVING", "DEGRADED")
         except Exception:
             return False
 
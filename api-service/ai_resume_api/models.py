@@ -207,7 +207,7 @@ class MemvidSearchResponse(BaseModel):
 class MemvidHealthResponse(BaseModel):
     """Response from memvid health check."""
 
-    status: Literal["SERVING", "NOT_SERVING", "UNKNOWN"]
+    status: Literal["SERVING", "NOT_SERVING", "DEGRADED", "UNKNOWN"]
     frame_count: int
     memvid_file: str
 
@@ -1,7 +1,7 @@
 use std::io::Result;
 
 fn main() -> Result<()> {
-    // Compile the proto files for the memvid gRPC service
+    // Compile the proto files for the memvid gRPC service (v1 and v2)
     // Support both local development (proto in parent) and container builds (proto in manifest_dir)
     let manifest_dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR"));
     let out_dir = std::path::PathBuf::from(std::env::var("OUT_DIR").unwrap());
@@ -37,23 +37,27 @@ fn main() -> Result<()> {
         )
     });
 
-    let proto_file = proto_dir.join("memvid/v1/memvid.proto");
+    let proto_files = [
+        proto_dir.join("memvid/v1/memvid.proto"),
+        proto_dir.join("memvid/v2/memvid.proto"),
+    ];
 
-    if !proto_file.exists() {
-        panic!("Proto file not found at: {}", proto_file.display());
+    for proto_file in &proto_files {
+        if !proto_file.exists() {
+            panic!("Proto file not found at: {}", proto_file.display());
+        }
     }
 
     tonic_build::configure()
         .build_server(true)
         .build_client(true)
         .out_dir(&out_dir)
-        .compile_protos(
-            &[proto_file.to_str().unwrap()],
-            &[proto_dir.to_str().unwrap()],
-        )?;
+        .compile_protos(&proto_files, &[&proto_dir])?;
 
     // Re-run if proto files change
-    println!("cargo:rerun-if-changed={}", proto_file.display());
+    for proto_file in &proto_files {
+        println!("cargo:rerun-if-changed={}", proto_file.display());
+    }
     println!("cargo:rerun-if-changed={}", proto_dir.display());
 
     Ok(())
@@ -0,0 +1,112 @@
+//! Operational alerts posted to a webhook.
+//!
+//! Alerts go to `ALERT_WEBHOOK` (a plain `http://` URL, e.g. a local relay
+//! into chat or paging) as JSON. Delivery is best-effort and off the async
+//! runtime: a failure to deliver is logged and never affects serving.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tracing::{info, warn};
+
+use crate::crash::post_json;
+
+/// An alert about the service's state.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Alert {
+    /// What happened (e.g., "reload_failed", "reload_recovered")
+    pub kind: &'static str,
+    /// Human-readable detail
+    pub message: String,
+    /// Index file the alert is about
+    pub memvid_file: String,
+    /// When the alert was raised
+    pub at: DateTime<Utc>,
+}
+
+impl Alert {
+    /// Alert of `kind` about `memvid_file`, raised now.
+    pub fn new(kind: &'static str, memvid_file: String, message: String) -> Self {
+        Self {
+            kind,
+            message,
+            memvid_file,
+            at: Utc::now(),
+        }
+    }
+}
+
+/// Delivers alerts to the configured webhook.
+#[derive(Debug, Clone, Default)]
+pub struct AlertSender {
+    webhook: Option<String>,
+}
+
+impl AlertSender {
+    /// Post alerts to `webhook`; without one alerts are only logged.
+    pub fn new(webhook: Option<String>) -> Self {
+        Self { webhook }
+    }
+
+    /// Log `alert` and post it to the webhook, if any.
+    pub async fn send(&self, alert: Alert) {
+        info!(kind = alert.kind, message = %alert.message, "Raising alert");
+        let Some(url) = self.webhook.clone() else {
+            return;
+        };
+        let json = serde_json::to_string(&alert).unwrap_or_else(|_| "{}".to_string());
+        let delivered = tokio::task::spawn_blocking(move || post_json(&url, &json)).await;
+        match delivered {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => warn!(error = %e, kind = alert.kind, "Failed to post alert to webhook"),
+            Err(e) => warn!(error = %e, kind = alert.kind, "Alert delivery task failed"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, Read, Write};
+    use std::net::TcpListener;
+
+    #[tokio::test]
+    async fn test_posts_alert_json() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/alerts", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = std::io::BufReader::new(stream);
+            let mut content_length = 0;
+            loop {
+                let mut header = String::new();
+                reader.read_line(&mut header).unwrap();
+                if header == "\r\n" {
+                    break;
+                }
+                if let Some(len) = header.strip_prefix("Content-Length: ") {
+                    content_length = len.trim().parse().unwrap();
+                }
+            }
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+            reader
+                .get_mut()
+                .write_all(b"HTTP/1.1 204 No Content\r\n\r\n")
+                .unwrap();
+            body
+        });
+
+        AlertSender::new(Some(url))
+            .send(Alert::new(
+                "reload_failed",
+                "/data/resume.mv2".to_string(),
+                "corrupt file".to_string(),
+            ))
+            .await;
+
+        let json: serde_json::Value = serde_json::from_slice(&server.join().unwrap()).unwrap();
+        assert_eq!(json["kind"], "reload_failed");
+        assert_eq!(json["memvid_file"], "/data/resume.mv2");
+        assert_eq!(json["message"], "corrupt file");
+    }
+}
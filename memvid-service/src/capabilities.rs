@@ -0,0 +1,247 @@
+//! Effective capability report for the running service.
+//!
+//! The report is logged once at startup and served verbatim by the admin
+//! `GetCapabilities` RPC, so operators see exactly how an instance is configured.
+
+use serde::Serialize;
+
+use crate::config::Config;
+use crate::memvid::{AnswerCacheBackend, IndexFeatures, Searcher};
+
+/// A network listener opened by the service.
+#[derive(Debug, Clone, Serialize)]
+pub struct ListenerInfo {
+    /// Listener name (e.g., "grpc", "metrics")
+    pub name: String,
+    /// Bound socket address or bind host
+    pub address: String,
+    /// Listen port
+    pub port: u16,
+    /// Whether the listener terminates TLS
+    pub tls: bool,
+}
+
+/// Snapshot of the effective service configuration and detected capabilities.
+#[derive(Debug, Clone, Serialize)]
+pub struct CapabilityReport {
+    /// Service version from Cargo metadata
+    pub version: String,
+    /// Searcher backend in use ("mock" or "memvid-core")
+    pub searcher: String,
+    /// Path to the loaded memvid file
+    pub memvid_file: String,
+    /// Number of frames in the loaded index
+    pub frame_count: i32,
+    /// Optional index structures detected in the loaded file
+    pub index_features: IndexFeatures,
+    /// Searcher decorators wrapping the backend, outermost first
+    pub decorators: Vec<String>,
+    /// Client authentication in force: "jwt" (tokens required),
+    /// "jwt_optional" and/or "api_key" joined by "+", or "none"
+    pub auth_mode: String,
+    /// Whether the public demo profile is active
+    pub public_demo: bool,
+    /// Requests per minute allowed per client (0 = unlimited)
+    pub rate_limit_per_minute: u32,
+    /// LLM provider used for answer synthesis ("none" when synthesis is
+    /// off or no chat model is configured)
+    pub llm_provider: String,
+    /// Network listeners opened by the service
+    pub listeners: Vec<ListenerInfo>,
+}
+
+impl CapabilityReport {
+    /// Build a report from the loaded configuration and searcher.
+    pub fn new(config: &Config, searcher: &dyn Searcher, listeners: Vec<ListenerInfo>) -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            searcher: if config.mock_memvid {
+                "mock".to_string()
+            } else {
+                "memvid-core".to_string()
+            },
+            memvid_file: searcher.memvid_file(),
+            frame_count: searcher.frame_count(),
+            index_features: searcher.index_features(),
+            decorators: decorators(config),
+            auth_mode: auth_mode(config),
+            public_demo: config.public_demo,
+            rate_limit_per_minute: config.rate_limit_per_minute,
+            llm_provider: llm_provider(config),
+            listeners,
+        }
+    }
+
+    /// Serialize the report as a JSON document.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_else(|_| "{}".to_string())
+    }
+}
+
+/// Client authentication enabled by the configuration.
+fn auth_mode(config: &Config) -> String {
+    let mut modes = Vec::new();
+    if config.jwt_issuer.is_some() {
+        modes.push(if config.jwt_required {
+            "jwt"
+        } else {
+            "jwt_optional"
+        });
+    }
+    if !config.authenticated_api_keys.is_empty() {
+        modes.push("api_key");
+    }
+    if modes.is_empty() {
+        "none".to_string()
+    } else {
+        modes.join("+")
+    }
+}
+
+/// LLM provider answers are synthesized with, if any.
+fn llm_provider(config: &Config) -> String {
+    let configured = config.llm_base_url.is_some() && config.llm_model.is_some();
+    if config.llm_synthesis && configured {
+        config.llm_provider.clone()
+    } else {
+        "none".to_string()
+    }
+}
+
+/// Searcher decorators enabled by the configuration, outermost first.
+fn decorators(config: &Config) -> Vec<String> {
+    let mut decorators = Vec::new();
+    let answer_cache = config
+        .answer_cache
+        .as_deref()
+        .and_then(|value| AnswerCacheBackend::parse(value).ok().flatten());
+    if answer_cache.is_some() {
+        decorators.push("answer_cache".to_string());
+    }
+    if config.anonymize {
+        decorators.push("anonymize".to_string());
+    }
+    if config.retrieval_pipeline.is_some() {
+        decorators.push("pipeline".to_string());
+    }
+    if config.shadow_memvid_file_path.is_some() {
+        decorators.push("shadow".to_string());
+    }
+    decorators
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memvid::MockSearcher;
+
+    fn test_config() -> Config {
+        Config {
+            mock_memvid: true,
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn test_report_reflects_searcher() {
+        let searcher = MockSearcher::new();
+        let report = CapabilityReport::new(&test_config(), &searcher, Vec::new());
+
+        assert_eq!(report.searcher, "mock");
+        assert_eq!(report.frame_count, 42);
+        assert!(report.index_features.lexical);
+        assert_eq!(report.auth_mode, "none");
+        assert!(report.decorators.is_empty());
+    }
+
+    #[test]
+    fn test_report_derives_auth_mode() {
+        let config = Config {
+            jwt_issuer: Some("https://auth.example.com/".to_string()),
+            jwt_required: true,
+            ..test_config()
+        };
+        let report = CapabilityReport::new(&config, &MockSearcher::new(), Vec::new());
+        assert_eq!(report.auth_mode, "jwt");
+
+        let config = Config {
+            jwt_required: false,
+            authenticated_api_keys: vec!["partner-key".to_string()],
+            ..config
+        };
+        let report = CapabilityReport::new(&config, &MockSearcher::new(), Vec::new());
+        assert_eq!(report.auth_mode, "jwt_optional+api_key");
+    }
+
+    #[test]
+    fn test_report_names_llm_provider_when_synthesizing() {
+        let config = Config {
+            llm_provider: "ollama".to_string(),
+            llm_base_url: Some("http://localhost:11434".to_string()),
+            llm_model: Some("llama3".to_string()),
+            ..test_config()
+        };
+        let report = CapabilityReport::new(&config, &MockSearcher::new(), Vec::new());
+        assert_eq!(report.llm_provider, "ollama");
+
+        let config = Config {
+            llm_synthesis: false,
+            ..config
+        };
+        let report = CapabilityReport::new(&config, &MockSearcher::new(), Vec::new());
+        assert_eq!(report.llm_provider, "none");
+    }
+
+    #[test]
+    fn test_report_lists_shadow_decorator() {
+        let config = Config {
+            shadow_memvid_file_path: Some("candidate.mv2".to_string()),
+            ..test_config()
+        };
+        let report = CapabilityReport::new(&config, &MockSearcher::new(), Vec::new());
+
+        assert_eq!(report.decorators, vec!["shadow"]);
+    }
+
+    #[test]
+    fn test_report_lists_pipeline_outermost() {
+        let config = Config {
+            shadow_memvid_file_path: Some("candidate.mv2".to_string()),
+            retrieval_pipeline: Some(r#"[{"stage": "retrieve"}]"#.to_string()),
+            ..test_config()
+        };
+        let report = CapabilityReport::new(&config, &MockSearcher::new(), Vec::new());
+
+        assert_eq!(report.decorators, vec!["pipeline", "shadow"]);
+    }
+
+    #[test]
+    fn test_report_reflects_public_demo() {
+        let mut config = test_config();
+        config.apply_public_demo();
+        let report = CapabilityReport::new(&config, &MockSearcher::new(), Vec::new());
+
+        assert!(report.public_demo);
+        assert_eq!(report.rate_limit_per_minute, 30);
+        assert_eq!(report.decorators, vec!["anonymize"]);
+    }
+
+    #[test]
+    fn test_report_json_includes_listeners() {
+        let searcher = MockSearcher::new();
+        let listeners = vec![ListenerInfo {
+            name: "grpc".to_string(),
+            address: "[::]:50051".to_string(),
+            port: 50051,
+            tls: true,
+        }];
+        let report = CapabilityReport::new(&test_config(), &searcher, listeners);
+        let json = report.to_json();
+
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed["listeners"][0]["name"], "grpc");
+        assert_eq!(parsed["listeners"][0]["port"], 50051);
+        assert_eq!(parsed["listeners"][0]["tls"], true);
+        assert_eq!(parsed["index_features"]["vector"], true);
+    }
+}
@@ -0,0 +1,1245 @@
+//! Command-line subcommands run instead of the server.
+//!
+//! Each opens an index file directly with [`RealSearcher`], so an index can
+//! be sanity-checked without starting the server and querying it with
+//! grpcurl:
+//!
+//! ```text
+//! memvid-service search --file data/.memvid/resume.mv2 --top-k 3 rust experience
+//! memvid-service search --json kubernetes
+//! memvid-service inspect --samples 5 --file data/.memvid/resume.mv2
+//! memvid-service validate --file data/.memvid/resume.mv2
+//! memvid-service ingest --output data/.memvid/resume.mv2 data/example_resume.md
+//! memvid-service export --output resume.jsonl --file data/.memvid/resume.mv2
+//! ```
+//!
+//! `search` runs one query and prints the hits. The query goes to the
+//! searcher as typed: no acronym expansion, visibility filtering or
+//! re-ranking is applied. `inspect` prints what the index holds (frame
+//! count, index structures, tags, memory cards and a few frames) and
+//! points out what would make searches come back empty. `validate` runs
+//! the integrity checks of [`validate_index`] and fails when one does.
+//! `ingest` goes the other way: it builds an index from a resume source
+//! (see [`crate::ingest`]). `export` dumps the frames, their metadata and
+//! the memory cards as JSON Lines (see [`IndexDump`]).
+//!
+//! Without `--file`, the index is `MEMVID_FILE_PATH` (or its default).
+//!
+//! The `healthcheck` command (the binary invoked as `healthcheck`, as the
+//! container's health probe does) asks the running service's Health/Check
+//! instead; see [`HEALTHCHECK_USAGE`].
+
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::config::Config;
+use crate::error::ServiceError;
+use crate::generated::memvid::v1::health_check_response::Status;
+use crate::generated::memvid::v1::HealthCheckResponse;
+use crate::ingest::{write_index, IngestPlan, SourceFormat};
+use crate::memvid::{
+    truncate_snippet, validate_index, CheckStatus, EntitySummary, IndexDump, IndexFeatures,
+    RealSearcher, SearchRequest, SearchResponse, Searcher, ValidationReport,
+    DEFAULT_VALIDATION_SAMPLES, MAX_VALIDATION_SAMPLES,
+};
+use crate::runtime_config::{DEFAULT_SNIPPET_CHARS, DEFAULT_TOP_K};
+
+/// Usage of the `search` subcommand.
+pub const SEARCH_USAGE: &str = "\
+usage: memvid-service search [--file PATH] [--top-k N] [--snippet-chars N] [--json] QUERY...
+
+  --file PATH          .mv2 index to open (default: MEMVID_FILE_PATH)
+  --top-k N            Hits to print (default: 5)
+  --snippet-chars N    Characters per snippet (default: 200)
+  --json               Print JSON instead of a table";
+
+/// Usage of the `inspect` subcommand.
+pub const INSPECT_USAGE: &str = "\
+usage: memvid-service inspect [--file PATH] [--samples N] [--json]
+
+  --file PATH          .mv2 index to open (default: MEMVID_FILE_PATH)
+  --samples N          Frames to print (default: 3)
+  --json               Print JSON instead of text";
+
+/// Usage of the `healthcheck` command.
+pub const HEALTHCHECK_USAGE: &str = "\
+usage: healthcheck [--timeout SECS] [--deep]
+
+  --timeout SECS       Seconds to wait for each address (default: 5 for
+                       GRPC_URL, 2 per local address)
+  --deep               Also require a loaded index with at least one frame
+
+The service is reached at GRPC_URL, or at [::1] and then 127.0.0.1 on
+GRPC_PORT (default: 50051).";
+
+/// Usage of the `validate` subcommand.
+pub const VALIDATE_USAGE: &str = "\
+usage: memvid-service validate [--file PATH] [--samples N] [--sha256 HEX] [--json]
+
+  --file PATH          .mv2 index to open (default: MEMVID_FILE_PATH)
+  --samples N          Frames to decode (default: 16)
+  --sha256 HEX         Expected checksum (default: PATH.sha256 if present)
+  --json               Print JSON instead of text
+
+Exits 1 when a check fails.";
+
+/// Usage of the `ingest` subcommand.
+pub const INGEST_USAGE: &str = "\
+usage: memvid-service ingest [--output PATH] [--format FORMAT] [--dry-run] [--json] SOURCE
+
+  SOURCE               Resume to index: markdown with YAML frontmatter, JSON
+                       or YAML
+  --output PATH        .mv2 index to write (default: MEMVID_FILE_PATH)
+  --format FORMAT      markdown, json or yaml (default: from the extension)
+  --dry-run            Print the frames and memory cards without writing
+  --json               Print JSON instead of text
+
+An existing index at PATH is replaced once the new one is complete.";
+
+/// Usage of the `export` subcommand.
+pub const EXPORT_USAGE: &str = "\
+usage: memvid-service export [--file PATH] [--output FILE] [--no-text]
+
+  --file PATH          .mv2 index to open (default: MEMVID_FILE_PATH)
+  --output FILE        JSON Lines file to write (default: standard output)
+  --no-text            Leave frame texts out";
+
+/// Frames `inspect` prints unless asked otherwise.
+const DEFAULT_SAMPLE_FRAMES: usize = 3;
+
+/// Longest memory-card value or frame text printed by `inspect`, in
+/// characters.
+const INSPECT_TEXT_CHARS: usize = 160;
+
+/// Longest title printed in a table row, in characters.
+const TABLE_TITLE_CHARS: usize = 40;
+
+/// How search results are printed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Aligned columns, one hit per row with its snippet below
+    Table,
+    /// The response as a JSON document
+    Json,
+}
+
+/// Arguments of the `search` subcommand.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchArgs {
+    /// Index file (None = `MEMVID_FILE_PATH`)
+    pub file: Option<String>,
+    /// Query, the remaining arguments joined by spaces
+    pub query: String,
+    /// Maximum number of hits
+    pub top_k: i32,
+    /// Maximum characters per snippet
+    pub snippet_chars: i32,
+    /// Output format
+    pub format: OutputFormat,
+}
+
+impl SearchArgs {
+    /// Parse the arguments following `search`.
+    ///
+    /// # Errors
+    /// What `parse_args` reports, or a missing query.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut parsed = Self {
+            file: None,
+            query: String::new(),
+            top_k: DEFAULT_TOP_K,
+            snippet_chars: DEFAULT_SNIPPET_CHARS,
+            format: OutputFormat::Table,
+        };
+        let mut words = Vec::new();
+        parse_args(args, |arg, values| {
+            match arg {
+                "--file" => parsed.file = Some(values.value(arg)?),
+                "--top-k" => parsed.top_k = values.positive(arg)?,
+                "--snippet-chars" => parsed.snippet_chars = values.positive(arg)?,
+                "--json" => parsed.format = OutputFormat::Json,
+                word if !word.starts_with("--") => words.push(word.to_string()),
+                _ => return Ok(false),
+            }
+            Ok(true)
+        })?;
+        parsed.query = words.join(" ");
+        if parsed.query.trim().is_empty() {
+            return Err("a query is required".to_string());
+        }
+        Ok(parsed)
+    }
+
+    /// The index file to open.
+    pub fn index_path(&self) -> String {
+        index_path(self.file.as_deref())
+    }
+}
+
+/// Arguments of the `inspect` subcommand.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InspectArgs {
+    /// Index file (None = `MEMVID_FILE_PATH`)
+    pub file: Option<String>,
+    /// Frames to print
+    pub samples: usize,
+    /// Output format
+    pub format: OutputFormat,
+}
+
+impl InspectArgs {
+    /// Parse the arguments following `inspect`.
+    ///
+    /// # Errors
+    /// What `parse_args` reports.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut parsed = Self {
+            file: None,
+            samples: DEFAULT_SAMPLE_FRAMES,
+            format: OutputFormat::Table,
+        };
+        parse_args(args, |arg, values| {
+            match arg {
+                "--file" => parsed.file = Some(values.value(arg)?),
+                "--samples" => {
+                    let value = values.value(arg)?;
+                    parsed.samples = value
+                        .parse()
+                        .map_err(|_| format!("--samples expects a number, got '{}'", value))?;
+                }
+                "--json" => parsed.format = OutputFormat::Json,
+                _ => return Ok(false),
+            }
+            Ok(true)
+        })?;
+        Ok(parsed)
+    }
+
+    /// The index file to open.
+    pub fn index_path(&self) -> String {
+        index_path(self.file.as_deref())
+    }
+}
+
+/// Arguments of the `validate` subcommand.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidateArgs {
+    /// Index file (None = `MEMVID_FILE_PATH`)
+    pub file: Option<String>,
+    /// Frames to decode
+    pub samples: usize,
+    /// Expected SHA-256 (None = the sidecar)
+    pub sha256: Option<String>,
+    /// Output format
+    pub format: OutputFormat,
+}
+
+impl ValidateArgs {
+    /// Parse the arguments following `validate`.
+    ///
+    /// # Errors
+    /// What `parse_args` reports, or more `--samples` than a validation
+    /// decodes.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut parsed = Self {
+            file: None,
+            samples: DEFAULT_VALIDATION_SAMPLES,
+            sha256: None,
+            format: OutputFormat::Table,
+        };
+        parse_args(args, |arg, values| {
+            match arg {
+                "--file" => parsed.file = Some(values.value(arg)?),
+                "--samples" => parsed.samples = values.positive(arg)? as usize,
+                "--sha256" => parsed.sha256 = Some(values.value(arg)?),
+                "--json" => parsed.format = OutputFormat::Json,
+                _ => return Ok(false),
+            }
+            Ok(true)
+        })?;
+        if parsed.samples > MAX_VALIDATION_SAMPLES {
+            return Err(format!(
+                "--samples is at most {}, got {}",
+                MAX_VALIDATION_SAMPLES, parsed.samples
+            ));
+        }
+        Ok(parsed)
+    }
+
+    /// The index file to open.
+    pub fn index_path(&self) -> String {
+        index_path(self.file.as_deref())
+    }
+}
+
+/// Arguments of the `ingest` subcommand.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IngestArgs {
+    /// Resume source
+    pub source: String,
+    /// Index file to write (None = `MEMVID_FILE_PATH`)
+    pub output: Option<String>,
+    /// Source format (None = from the extension)
+    pub format: Option<SourceFormat>,
+    /// Print the plan instead of writing it
+    pub dry_run: bool,
+    /// Output format
+    pub output_format: OutputFormat,
+}
+
+impl IngestArgs {
+    /// Parse the arguments following `ingest`.
+    ///
+    /// # Errors
+    /// What `parse_args` reports, or a missing SOURCE or one whose format
+    /// is unknown.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut source = None;
+        let mut output = None;
+        let mut format = None;
+        let mut dry_run = false;
+        let mut output_format = OutputFormat::Table;
+        parse_args(args, |arg, values| {
+            match arg {
+                "--output" => output = Some(values.value(arg)?),
+                "--format" => {
+                    let name = values.value(arg)?;
+                    format = Some(SourceFormat::parse(&name).ok_or_else(|| {
+                        format!("--format expects markdown, json or yaml, got '{}'", name)
+                    })?);
+                }
+                "--dry-run" => dry_run = true,
+                "--json" => output_format = OutputFormat::Json,
+                path if !path.starts_with("--") && source.is_none() => {
+                    source = Some(path.to_string())
+                }
+                _ => return Ok(false),
+            }
+            Ok(true)
+        })?;
+        let source = source.ok_or_else(|| "missing SOURCE".to_string())?;
+        if format.is_none() && SourceFormat::from_path(Path::new(&source)).is_none() {
+            return Err(format!(
+                "cannot tell the format of {} from its extension, pass --format",
+                source
+            ));
+        }
+        Ok(Self {
+            source,
+            output,
+            format,
+            dry_run,
+            output_format,
+        })
+    }
+
+    /// The source's format.
+    pub fn source_format(&self) -> SourceFormat {
+        self.format
+            .or_else(|| SourceFormat::from_path(Path::new(&self.source)))
+            .unwrap_or(SourceFormat::Markdown)
+    }
+
+    /// The index file to write.
+    pub fn index_path(&self) -> String {
+        index_path(self.output.as_deref())
+    }
+}
+
+/// Arguments of the `export` subcommand.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportArgs {
+    /// Index file (None = `MEMVID_FILE_PATH`)
+    pub file: Option<String>,
+    /// JSON Lines file to write (None = standard output)
+    pub output: Option<String>,
+    /// Whether frame texts are exported
+    pub include_text: bool,
+}
+
+impl ExportArgs {
+    /// Parse the arguments following `export`.
+    ///
+    /// # Errors
+    /// What `parse_args` reports.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut parsed = Self {
+            file: None,
+            output: None,
+            include_text: true,
+        };
+        parse_args(args, |arg, values| {
+            match arg {
+                "--file" => parsed.file = Some(values.value(arg)?),
+                "--output" => parsed.output = Some(values.value(arg)?),
+                "--no-text" => parsed.include_text = false,
+                _ => return Ok(false),
+            }
+            Ok(true)
+        })?;
+        Ok(parsed)
+    }
+
+    /// The index file to open.
+    pub fn index_path(&self) -> String {
+        index_path(self.file.as_deref())
+    }
+}
+
+/// Arguments of the `healthcheck` command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HealthcheckArgs {
+    /// Wait per address (None = 5s for `GRPC_URL`, 2s per local address)
+    pub timeout: Option<Duration>,
+    /// Also require frames in the loaded index
+    pub deep: bool,
+}
+
+impl HealthcheckArgs {
+    /// Parse the arguments following `healthcheck`.
+    ///
+    /// # Errors
+    /// What `parse_args` reports.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut parsed = Self {
+            timeout: None,
+            deep: false,
+        };
+        parse_args(args, |arg, values| {
+            match arg {
+                "--timeout" => {
+                    let secs = values.positive(arg)?;
+                    parsed.timeout = Some(Duration::from_secs(secs as u64));
+                }
+                "--deep" => parsed.deep = true,
+                _ => return Ok(false),
+            }
+            Ok(true)
+        })?;
+        Ok(parsed)
+    }
+
+    /// Addresses to try in order, each with its timeout: `GRPC_URL` when
+    /// set, otherwise IPv6 and IPv4 localhost on `GRPC_PORT`.
+    pub fn targets(&self) -> Vec<(String, Duration)> {
+        if let Ok(url) = std::env::var("GRPC_URL") {
+            return vec![(url, self.timeout.unwrap_or(Duration::from_secs(5)))];
+        }
+        let port = std::env::var("GRPC_PORT")
+            .ok()
+            .and_then(|port| port.parse().ok())
+            .unwrap_or(Config::default().grpc_port);
+        let timeout = self.timeout.unwrap_or(Duration::from_secs(2));
+        vec![
+            (format!("http://[::1]:{}", port), timeout),
+            (format!("http://127.0.0.1:{}", port), timeout),
+        ]
+    }
+}
+
+/// Whether a health response passes: SERVING or DEGRADED (still serving a
+/// stale index), and with `deep`, at least one frame loaded.
+///
+/// # Errors
+/// Why the check fails.
+pub fn check_health(response: &HealthCheckResponse, deep: bool) -> Result<(), String> {
+    let status = Status::try_from(response.status).unwrap_or(Status::Unknown);
+    if !matches!(status, Status::Serving | Status::Degraded) {
+        return Err(format!("service is {}", status.as_str_name()));
+    }
+    if deep && response.frame_count <= 0 {
+        return Err(format!("no frames loaded from {}", response.memvid_file));
+    }
+    Ok(())
+}
+
+/// Hand each argument of a subcommand to `accept`, which takes the value of
+/// an option from `values` and returns false for an argument it does not
+/// take.
+///
+/// # Errors
+/// A message naming the unknown option, unexpected argument, or missing or
+/// invalid value.
+fn parse_args(
+    args: impl IntoIterator<Item = String>,
+    mut accept: impl FnMut(&str, &mut Values) -> Result<bool, String>,
+) -> Result<(), String> {
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if !accept(&arg, &mut Values(&mut args))? {
+            return Err(if arg.starts_with("--") {
+                format!("unknown option {}", arg)
+            } else {
+                format!("unexpected argument {}", arg)
+            });
+        }
+    }
+    Ok(())
+}
+
+/// The arguments after an option, which it takes its value from.
+struct Values<'a>(&'a mut dyn Iterator<Item = String>);
+
+impl Values<'_> {
+    /// The value of `option`.
+    fn value(&mut self, option: &str) -> Result<String, String> {
+        self.0
+            .next()
+            .ok_or_else(|| format!("{} requires a value", option))
+    }
+
+    /// The value of `option`, which must be a positive number.
+    fn positive(&mut self, option: &str) -> Result<i32, String> {
+        positive(option, &self.value(option)?)
+    }
+}
+
+/// `file`, else `MEMVID_FILE_PATH`, else the configured default.
+fn index_path(file: Option<&str>) -> String {
+    file.map(str::to_string)
+        .or_else(|| std::env::var("MEMVID_FILE_PATH").ok())
+        .unwrap_or_else(|| Config::default().memvid_file_path)
+}
+
+fn positive(name: &str, value: &str) -> Result<i32, String> {
+    match value.parse::<i32>() {
+        Ok(n) if n > 0 => Ok(n),
+        _ => Err(format!(
+            "{} expects a positive number, got '{}'",
+            name, value
+        )),
+    }
+}
+
+/// Open the index, run the search and render the response.
+///
+/// # Errors
+/// Failures opening the index or searching it.
+pub async fn run_search(args: &SearchArgs) -> Result<String, ServiceError> {
+    let searcher = RealSearcher::new(args.index_path()).await?;
+    let response = searcher
+        .search(SearchRequest::new(
+            args.query.clone(),
+            args.top_k,
+            args.snippet_chars,
+        ))
+        .await?;
+    Ok(match args.format {
+        OutputFormat::Table => render_table(&response),
+        OutputFormat::Json => render_json(&args.query, &response),
+    })
+}
+
+/// A frame printed by `inspect`.
+#[derive(Debug, Clone)]
+pub struct SampleFrame {
+    /// Frame identifier in the index
+    pub frame_id: u64,
+    /// Source URI
+    pub uri: String,
+    /// Title or heading
+    pub title: String,
+    /// Tags
+    pub tags: Vec<String>,
+    /// Start of the indexed text
+    pub text: String,
+}
+
+/// What an index holds, as printed by `inspect`.
+#[derive(Debug, Clone)]
+pub struct IndexReport {
+    /// Index file
+    pub path: String,
+    /// Number of frames
+    pub frame_count: i32,
+    /// Optional index structures present
+    pub features: IndexFeatures,
+    /// Frames per tag
+    pub tags: BTreeMap<String, i32>,
+    /// Memory-card entities with their slots (values shortened)
+    pub memory_cards: Vec<(EntitySummary, BTreeMap<String, String>)>,
+    /// The first frames
+    pub samples: Vec<SampleFrame>,
+}
+
+impl IndexReport {
+    /// Read the report of `searcher`'s index with up to `samples` frames.
+    ///
+    /// # Errors
+    /// Failures reading entities or frames.
+    pub async fn read(searcher: &dyn Searcher, samples: usize) -> Result<Self, ServiceError> {
+        let mut memory_cards = Vec::new();
+        for entity in searcher.list_entities().await? {
+            let state = searcher.get_state(&entity.name, None).await?;
+            let slots = state
+                .slots
+                .into_iter()
+                .map(|(slot, value)| (slot, truncate_snippet(&value, INSPECT_TEXT_CHARS)))
+                .collect();
+            memory_cards.push((entity, slots));
+        }
+
+        let texts = searcher.frame_texts(None, samples).await?;
+        let samples = searcher
+            .export_frames(None, samples)
+            .await?
+            .into_iter()
+            .map(|frame| {
+                let text = texts
+                    .iter()
+                    .find(|text| text.frame_id == frame.frame_id)
+                    .map(|text| text.text.split_whitespace().collect::<Vec<_>>().join(" "))
+                    .unwrap_or_default();
+                SampleFrame {
+                    frame_id: frame.frame_id,
+                    uri: frame.uri,
+                    title: frame.title,
+                    tags: frame.tags,
+                    text: truncate_snippet(&text, INSPECT_TEXT_CHARS),
+                }
+            })
+            .collect();
+
+        Ok(Self {
+            path: searcher.memvid_file(),
+            frame_count: searcher.frame_count(),
+            features: searcher.index_features(),
+            tags: searcher.section_counts(),
+            memory_cards,
+            samples,
+        })
+    }
+
+    /// Conditions that make searches come back empty or degraded.
+    pub fn warnings(&self) -> Vec<&'static str> {
+        let mut warnings = Vec::new();
+        if self.frame_count == 0 {
+            warnings.push("the index has no frames: every search returns nothing");
+        }
+        if !self.features.lexical {
+            warnings.push("no lexical index: Search and lexical Ask return nothing");
+        }
+        if !self.features.vector {
+            warnings.push("no vector index: semantic Ask falls back to lexical retrieval");
+        }
+        if self.frame_count > 0 && self.tags.is_empty() {
+            warnings.push("no frame carries tags: metadata filters match nothing");
+        }
+        if self.memory_cards.is_empty() {
+            warnings.push("no memory cards: GetState finds no profile");
+        }
+        warnings
+    }
+}
+
+/// Open the index and render its report.
+///
+/// # Errors
+/// Failures opening or reading the index.
+pub async fn run_inspect(args: &InspectArgs) -> Result<String, ServiceError> {
+    let searcher = RealSearcher::new(args.index_path()).await?;
+    let report = IndexReport::read(&searcher, args.samples).await?;
+    Ok(match args.format {
+        OutputFormat::Table => render_report(&report),
+        OutputFormat::Json => render_report_json(&report),
+    })
+}
+
+/// Build the plan from the source and write it, or with `dry_run` only
+/// render it.
+///
+/// The index is written next to the output as `<PATH>.tmp` and renamed over
+/// it when complete, so a running service never reloads a partial index.
+///
+/// # Errors
+/// Unreadable or invalid sources and failures writing the index.
+pub async fn run_ingest(args: &IngestArgs) -> Result<String, ServiceError> {
+    let text = tokio::fs::read_to_string(&args.source).await.map_err(|e| {
+        ServiceError::InvalidRequest(format!("Failed to read {}: {}", args.source, e))
+    })?;
+    let plan = IngestPlan::parse(&text, args.source_format())?;
+    if args.dry_run {
+        return Ok(match args.output_format {
+            OutputFormat::Table => render_plan(&plan),
+            OutputFormat::Json => render_plan_json(&plan),
+        });
+    }
+
+    let output = PathBuf::from(args.index_path());
+    let partial = PathBuf::from(format!("{}.tmp", output.display()));
+    if let Some(dir) = output.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        tokio::fs::create_dir_all(dir).await.map_err(|e| {
+            ServiceError::Internal(format!("Failed to create {}: {}", dir.display(), e))
+        })?;
+    }
+    let _ = tokio::fs::remove_file(&partial).await;
+    let written = {
+        let (plan, partial) = (plan.clone(), partial.clone());
+        tokio::task::spawn_blocking(move || write_index(&plan, &partial))
+            .await
+            .map_err(|e| ServiceError::Internal(format!("Ingest task error: {}", e)))?
+    };
+    if let Err(e) = written {
+        let _ = tokio::fs::remove_file(&partial).await;
+        return Err(e);
+    }
+    tokio::fs::rename(&partial, &output).await.map_err(|e| {
+        ServiceError::Internal(format!(
+            "Failed to move {} to {}: {}",
+            partial.display(),
+            output.display(),
+            e
+        ))
+    })?;
+
+    let summary = json!({
+        "file": output.display().to_string(),
+        "frames": plan.frames.len(),
+        "memory_cards": plan.cards.len(),
+        "source_version": plan.version,
+    });
+    Ok(match args.output_format {
+        OutputFormat::Table => format!(
+            "wrote {} frames and {} memory cards to {}\n",
+            plan.frames.len(),
+            plan.cards.len(),
+            output.display()
+        ),
+        OutputFormat::Json => serde_json::to_string_pretty(&summary).unwrap_or_default(),
+    })
+}
+
+/// The plan as one line per frame and memory card.
+pub fn render_plan(plan: &IngestPlan) -> String {
+    let mut out = format!("frames ({}):\n", plan.frames.len());
+    for frame in &plan.frames {
+        out.push_str(&format!(
+            "  {} [{}]\n    {} ({} chars)\n",
+            frame.title,
+            frame.tags.join(","),
+            frame.uri(),
+            frame.text.chars().count()
+        ));
+    }
+    out.push_str(&format!("\nmemory cards ({}):\n", plan.cards.len()));
+    for card in &plan.cards {
+        out.push_str(&format!(
+            "  {}/{}: {}\n",
+            card.entity,
+            card.slot,
+            truncate_snippet(&card.value, INSPECT_TEXT_CHARS)
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+        ));
+    }
+    if let Some(version) = &plan.version {
+        out.push_str(&format!("\nsource version: {}\n", version));
+    }
+    out
+}
+
+/// The plan as a pretty-printed JSON document.
+pub fn render_plan_json(plan: &IngestPlan) -> String {
+    let frames: Vec<_> = plan
+        .frames
+        .iter()
+        .map(|frame| {
+            json!({
+                "uri": frame.uri(),
+                "title": frame.title,
+                "section": frame.section,
+                "tags": frame.tags,
+                "keywords": frame.keywords,
+                "text": frame.text,
+            })
+        })
+        .collect();
+    let cards: Vec<_> = plan
+        .cards
+        .iter()
+        .map(|card| json!({"entity": card.entity, "slot": card.slot, "value": card.value}))
+        .collect();
+    let document = json!({
+        "frames": frames,
+        "memory_cards": cards,
+        "source_version": plan.version,
+    });
+    serde_json::to_string_pretty(&document).unwrap_or_default()
+}
+
+/// Open the index and dump it as JSON Lines: the dump itself, or a summary
+/// once it is written to `--output`.
+///
+/// # Errors
+/// Failures opening or reading the index, or writing the output file.
+pub async fn run_export(args: &ExportArgs) -> Result<String, ServiceError> {
+    let searcher = RealSearcher::new(args.index_path()).await?;
+    let dump = render_dump(Arc::new(searcher), args.include_text).await?;
+    let Some(output) = &args.output else {
+        return Ok(dump);
+    };
+    tokio::fs::write(output, &dump)
+        .await
+        .map_err(|e| ServiceError::Internal(format!("Failed to write {}: {}", output, e)))?;
+    Ok(format!(
+        "wrote {} lines to {}\n",
+        dump.lines().count(),
+        output
+    ))
+}
+
+/// The whole dump of `searcher`'s index, one JSON object per line.
+///
+/// # Errors
+/// Failures reading frames or memory cards.
+pub async fn render_dump(
+    searcher: Arc<dyn Searcher>,
+    include_text: bool,
+) -> Result<String, ServiceError> {
+    let mut dump = IndexDump::new(searcher, include_text);
+    let mut out = String::new();
+    while let Some(lines) = dump.next_batch().await? {
+        for line in lines {
+            out.push_str(&line);
+            out.push('\n');
+        }
+    }
+    Ok(out)
+}
+
+/// Open the index, validate it and render the report, with whether every
+/// check passed.
+///
+/// # Errors
+/// Failures opening the index or reading it for its checksum.
+pub async fn run_validate(args: &ValidateArgs) -> Result<(String, bool), ServiceError> {
+    let searcher = RealSearcher::new(args.index_path()).await?;
+    let report = validate_index(&searcher, args.samples, args.sha256.as_deref()).await?;
+    let output = match args.format {
+        OutputFormat::Table => render_validation(&report),
+        OutputFormat::Json => render_validation_json(&report),
+    };
+    Ok((output, report.passed()))
+}
+
+/// The validation report as one line per check.
+pub fn render_validation(report: &ValidationReport) -> String {
+    let mut out = format!(
+        "file:      {}\nsha256:    {}\nsampled:   {} frames\n\n",
+        report.memvid_file,
+        report.sha256.as_deref().unwrap_or("-"),
+        report.frames_sampled
+    );
+    for check in &report.checks {
+        out.push_str(&format!(
+            "{:<7} {:<13} {}\n",
+            status_label(check.status),
+            check.name,
+            check.detail
+        ));
+    }
+    out.push_str(if report.passed() {
+        "\nvalid\n"
+    } else {
+        "\nINVALID\n"
+    });
+    out
+}
+
+/// The validation report as a pretty-printed JSON document.
+pub fn render_validation_json(report: &ValidationReport) -> String {
+    let checks: Vec<_> = report
+        .checks
+        .iter()
+        .map(|check| {
+            json!({
+                "name": check.name,
+                "status": status_label(check.status),
+                "detail": check.detail,
+            })
+        })
+        .collect();
+    let document = json!({
+        "file": report.memvid_file,
+        "sha256": report.sha256,
+        "frames_sampled": report.frames_sampled,
+        "passed": report.passed(),
+        "checks": checks,
+    });
+    serde_json::to_string_pretty(&document).unwrap_or_default()
+}
+
+fn status_label(status: CheckStatus) -> &'static str {
+    match status {
+        CheckStatus::Pass => "pass",
+        CheckStatus::Warn => "warn",
+        CheckStatus::Fail => "FAIL",
+        CheckStatus::Skipped => "skipped",
+    }
+}
+
+/// The report as text sections.
+pub fn render_report(report: &IndexReport) -> String {
+    let yes_no = |present: bool| if present { "yes" } else { "no" };
+    let mut out = format!(
+        "file:      {}\nframes:    {}\nlexical:   {}\nvector:    {}\ntemporal:  {}\n",
+        report.path,
+        report.frame_count,
+        yes_no(report.features.lexical),
+        yes_no(report.features.vector),
+        yes_no(report.features.temporal),
+    );
+
+    out.push_str(&format!("\ntags ({}):\n", report.tags.len()));
+    for (tag, count) in &report.tags {
+        out.push_str(&format!("  {:<24} {}\n", tag, count));
+    }
+
+    out.push_str(&format!(
+        "\nmemory cards ({}):\n",
+        report.memory_cards.len()
+    ));
+    for (entity, slots) in &report.memory_cards {
+        out.push_str(&format!(
+            "  {} ({} slots)\n",
+            entity.name, entity.slot_count
+        ));
+        for (slot, value) in slots {
+            out.push_str(&format!("    {}: {}\n", slot, value));
+        }
+    }
+
+    out.push_str(&format!("\nsample frames ({}):\n", report.samples.len()));
+    for frame in &report.samples {
+        out.push_str(&format!(
+            "  #{} {} [{}]\n    {}\n    {}\n",
+            frame.frame_id,
+            frame.title,
+            frame.tags.join(","),
+            frame.uri,
+            frame.text
+        ));
+    }
+
+    let warnings = report.warnings();
+    if !warnings.is_empty() {
+        out.push_str("\nwarnings:\n");
+        for warning in warnings {
+            out.push_str(&format!("  - {}\n", warning));
+        }
+    }
+    out
+}
+
+/// The report as a pretty-printed JSON document.
+pub fn render_report_json(report: &IndexReport) -> String {
+    let memory_cards: BTreeMap<&str, &BTreeMap<String, String>> = report
+        .memory_cards
+        .iter()
+        .map(|(entity, slots)| (entity.name.as_str(), slots))
+        .collect();
+    let samples: Vec<_> = report
+        .samples
+        .iter()
+        .map(|frame| {
+            json!({
+                "frame_id": frame.frame_id,
+                "uri": frame.uri,
+                "title": frame.title,
+                "tags": frame.tags,
+                "text": frame.text,
+            })
+        })
+        .collect();
+    let document = json!({
+        "file": report.path,
+        "frame_count": report.frame_count,
+        "features": report.features,
+        "tags": report.tags,
+        "memory_cards": memory_cards,
+        "samples": samples,
+        "warnings": report.warnings(),
+    });
+    serde_json::to_string_pretty(&document).unwrap_or_default()
+}
+
+/// Hits as aligned rows of rank, score, frame, title and tags, each
+/// followed by its snippet.
+pub fn render_table(response: &SearchResponse) -> String {
+    let mut out = format!(
+        "{:>3}  {:>5}  {:>6}  {:<width$}  {}\n",
+        "#",
+        "score",
+        "frame",
+        "title",
+        "tags",
+        width = TABLE_TITLE_CHARS
+    );
+    for (rank, hit) in response.hits.iter().enumerate() {
+        let title: String = hit.title.chars().take(TABLE_TITLE_CHARS).collect();
+        out.push_str(&format!(
+            "{:>3}  {:>5.3}  {:>6}  {:<width$}  {}\n",
+            rank + 1,
+            hit.score,
+            hit.frame_id
+                .map_or_else(|| "-".to_string(), |id| id.to_string()),
+            title,
+            hit.tags.join(","),
+            width = TABLE_TITLE_CHARS
+        ));
+        let snippet = hit.snippet.split_whitespace().collect::<Vec<_>>().join(" ");
+        out.push_str(&format!("{:>17}{}\n", "", snippet));
+    }
+    out.push_str(&format!(
+        "{} of {} hits in {} ms{}\n",
+        response.hits.len(),
+        response.total_hits,
+        response.took_ms,
+        if response.partial { " (partial)" } else { "" }
+    ));
+    out
+}
+
+/// The response as a pretty-printed JSON document.
+pub fn render_json(query: &str, response: &SearchResponse) -> String {
+    let document = json!({
+        "query": query,
+        "expanded_query": response.expanded_query,
+        "total_hits": response.total_hits,
+        "took_ms": response.took_ms,
+        "partial": response.partial,
+        "hits": response.hits,
+    });
+    // Plain data never fails to serialize
+    serde_json::to_string_pretty(&document).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memvid::{MockSearcher, SearchResult};
+
+    fn args(args: &[&str]) -> Result<SearchArgs, String> {
+        SearchArgs::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    fn response() -> SearchResponse {
+        SearchResponse {
+            hits: vec![SearchResult {
+                frame_id: Some(7),
+                title: "Senior Platform Engineer".to_string(),
+                score: 0.875,
+                snippet: "Built Kubernetes\noperators in Rust.".to_string(),
+                tags: vec!["experience".to_string(), "platform".to_string()],
+                uri: None,
+                timestamp: None,
+                ingested_at: None,
+                source_version: None,
+            }],
+            total_hits: 3,
+            took_ms: 4,
+            partial: false,
+            expanded_query: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_search_args() {
+        let parsed = args(&["--top-k", "3", "rust", "--json", "experience"]).unwrap();
+        assert_eq!(parsed.query, "rust experience");
+        assert_eq!(parsed.top_k, 3);
+        assert_eq!(parsed.snippet_chars, DEFAULT_SNIPPET_CHARS);
+        assert_eq!(parsed.format, OutputFormat::Json);
+        assert_eq!(
+            args(&["--file", "/tmp/a.mv2", "k8s"]).unwrap().index_path(),
+            "/tmp/a.mv2"
+        );
+
+        assert!(args(&[]).is_err());
+        assert!(args(&["--top-k", "0", "rust"]).is_err());
+        assert!(args(&["rust", "--file"]).is_err());
+        assert!(args(&["--verbose", "rust"]).is_err());
+    }
+
+    #[test]
+    fn test_healthcheck() {
+        let parse = |args: &[&str]| HealthcheckArgs::parse(args.iter().map(|arg| arg.to_string()));
+        let parsed = parse(&["--deep", "--timeout", "7"]).unwrap();
+        assert!(parsed.deep);
+        assert_eq!(parsed.timeout, Some(Duration::from_secs(7)));
+        assert_eq!(parse(&[]).unwrap().timeout, None);
+        assert!(parse(&["--timeout", "0"]).is_err());
+        assert!(parse(&["--verbose"]).is_err());
+
+        let mut response = HealthCheckResponse {
+            status: Status::Serving as i32,
+            memvid_file: "/data/resume.mv2".to_string(),
+            ..Default::default()
+        };
+        assert!(check_health(&response, false).is_ok());
+        assert_eq!(
+            check_health(&response, true).unwrap_err(),
+            "no frames loaded from /data/resume.mv2"
+        );
+        response.frame_count = 12;
+        response.status = Status::Degraded as i32;
+        assert!(check_health(&response, true).is_ok());
+        response.status = Status::NotServing as i32;
+        assert_eq!(
+            check_health(&response, false).unwrap_err(),
+            "service is NOT_SERVING"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_validate_command() {
+        let parse = |args: &[&str]| ValidateArgs::parse(args.iter().map(|arg| arg.to_string()));
+        let parsed = parse(&["--sha256", "abc", "--file", "a.mv2", "--samples", "4"]).unwrap();
+        assert_eq!(parsed.sha256.as_deref(), Some("abc"));
+        assert_eq!(parsed.samples, 4);
+        assert_eq!(parsed.file.as_deref(), Some("a.mv2"));
+        assert!(parse(&["--samples", "5000"]).is_err());
+        assert!(parse(&["a.mv2"]).is_err());
+
+        let report = validate_index(&MockSearcher::new(), 2, None).await.unwrap();
+        let text = render_validation(&report);
+        assert!(text.contains("FAIL    frames"));
+        assert!(text.ends_with("INVALID\n"));
+        let json: serde_json::Value =
+            serde_json::from_str(&render_validation_json(&report)).unwrap();
+        assert_eq!(json["passed"], false);
+        assert_eq!(json["checks"][0]["status"], "skipped");
+    }
+
+    #[test]
+    fn test_parse_inspect_args() {
+        let parse = |args: &[&str]| InspectArgs::parse(args.iter().map(|arg| arg.to_string()));
+        let parsed = parse(&["--samples", "5", "--file", "/tmp/a.mv2", "--json"]).unwrap();
+        assert_eq!(parsed.file.as_deref(), Some("/tmp/a.mv2"));
+        assert_eq!(parsed.samples, 5);
+        assert_eq!(parsed.format, OutputFormat::Json);
+        assert_eq!(parse(&[]).unwrap().samples, DEFAULT_SAMPLE_FRAMES);
+
+        assert_eq!(parse(&["a.mv2"]).unwrap_err(), "unexpected argument a.mv2");
+        assert_eq!(
+            parse(&["--verbose"]).unwrap_err(),
+            "unknown option --verbose"
+        );
+        assert!(parse(&["--samples", "many"]).is_err());
+        assert!(parse(&["--file"]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_inspect_report() {
+        let report = IndexReport::read(&MockSearcher::new(), 2).await.unwrap();
+        assert!(report.frame_count > 0);
+        assert_eq!(report.samples.len(), 2);
+        assert_eq!(report.samples[0].frame_id, 1);
+        assert!(!report.samples[0].text.is_empty());
+        assert_eq!(report.memory_cards[0].0.name, "__profile__");
+        assert!(report.tags.contains_key("skills"));
+
+        let text = render_report(&report);
+        assert!(text.contains("sample frames (2):"));
+        assert!(text.contains("__profile__ (1 slots)"));
+
+        let json: serde_json::Value = serde_json::from_str(&render_report_json(&report)).unwrap();
+        assert_eq!(json["frame_count"], report.frame_count);
+        assert_eq!(json["samples"].as_array().unwrap().len(), 2);
+
+        let empty = IndexReport {
+            frame_count: 0,
+            tags: BTreeMap::new(),
+            memory_cards: Vec::new(),
+            ..report
+        };
+        assert!(empty.warnings()[0].starts_with("the index has no frames"));
+        assert!(render_report(&empty).contains("warnings:"));
+    }
+
+    #[test]
+    fn test_render_table_and_json() {
+        let table = render_table(&response());
+        let lines: Vec<&str> = table.lines().collect();
+        assert!(lines[0].starts_with("  #  score   frame  title"));
+        assert!(lines[1].starts_with("  1  0.875       7  Senior Platform Engineer"));
+        assert!(lines[1].ends_with("experience,platform"));
+        assert_eq!(lines[2].trim(), "Built Kubernetes operators in Rust.");
+        assert_eq!(lines[3], "1 of 3 hits in 4 ms");
+
+        let json: serde_json::Value =
+            serde_json::from_str(&render_json("rust", &response())).unwrap();
+        assert_eq!(json["query"], "rust");
+        assert_eq!(json["hits"][0]["frame_id"], 7);
+        assert_eq!(json["total_hits"], 3);
+    }
+
+    #[tokio::test]
+    async fn test_export_command() {
+        let parse = |args: &[&str]| ExportArgs::parse(args.iter().map(|arg| arg.to_string()));
+        let parsed = parse(&["--no-text", "--file", "a.mv2", "--output", "a.jsonl"]).unwrap();
+        assert_eq!(parsed.file.as_deref(), Some("a.mv2"));
+        assert_eq!(parsed.output.as_deref(), Some("a.jsonl"));
+        assert!(!parsed.include_text);
+        assert!(parse(&[]).unwrap().include_text);
+        assert!(parse(&["--output"]).is_err());
+        assert!(parse(&["a.mv2"]).is_err());
+
+        let dump = render_dump(Arc::new(MockSearcher::synthetic(2, 20)), true)
+            .await
+            .unwrap();
+        let records: Vec<serde_json::Value> = dump
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(records[0]["type"], "index");
+        assert_eq!(records.iter().filter(|r| r["type"] == "frame").count(), 20);
+        assert!(records
+            .iter()
+            .any(|r| r["type"] == "memory_card" && r["entity"] == "__profile__"));
+    }
+
+    #[tokio::test]
+    async fn test_ingest_command() {
+        let parse = |args: &[&str]| IngestArgs::parse(args.iter().map(|arg| arg.to_string()));
+        assert!(parse(&[]).is_err());
+        assert!(parse(&["resume.txt"]).is_err());
+        assert!(parse(&["--format", "pdf", "resume.md"]).is_err());
+        let parsed = parse(&["--format", "yml", "--dry-run", "resume.txt"]).unwrap();
+        assert_eq!(parsed.source_format(), SourceFormat::Yaml);
+        assert!(parsed.dry_run);
+
+        let dir = std::env::temp_dir().join(format!("ingest-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let source = dir.join("resume.json");
+        std::fs::write(
+            &source,
+            r#"{"name": "Jane", "summary": "Platform engineer.", "version": 3}"#,
+        )
+        .unwrap();
+        let source = source.display().to_string();
+
+        let plan = run_ingest(&parse(&["--dry-run", &source]).unwrap())
+            .await
+            .unwrap();
+        assert!(plan.contains("Professional Summary [summary,overview]"));
+        assert!(plan.contains("__profile__/data"));
+        assert!(plan.contains("source version: 3"));
+
+        let output = dir.join("index").join("resume.mv2");
+        let written = run_ingest(
+            &parse(&["--json", "--output", &output.display().to_string(), &source]).unwrap(),
+        )
+        .await
+        .unwrap();
+        let written: serde_json::Value = serde_json::from_str(&written).unwrap();
+        assert_eq!(written["frames"], 1);
+        assert!(output.exists());
+        assert!(!dir.join("index").join("resume.mv2.tmp").exists());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -2,14 +2,35 @@
 //!
 //! All configuration is loaded from environment variables with sensible defaults.
 
+use serde::Serialize;
 use std::env;
 
+use crate::egress::Proxy;
+use crate::grpc::DEFAULT_REQUEST_LOG_CAPACITY;
+use crate::llm::{LlmProvider, DEFAULT_LLM_MAX_RETRIES, DEFAULT_OLLAMA_URL};
+use crate::memvid::{
+    parse_index_paths, AclMode, CitationPolicy, RetrievalPipeline, DEFAULT_ASK_ASPECTS,
+    DEFAULT_DETERMINISTIC_NOW, DEFAULT_MAX_CONCURRENT_SEARCHES, DEFAULT_MAX_QUEUED_SEARCHES,
+    DEFAULT_SEARCH_CACHE_CAPACITY, DEFAULT_SEARCH_CACHE_TTL, MAX_ASPECTS, MAX_READ_POOL_SIZE,
+};
+use crate::readiness::ReadinessPolicy;
+use crate::report::{parse_report_schedule, ReportFormat};
+use crate::runtime_config::{
+    DEFAULT_MAX_SNIPPET_CHARS, DEFAULT_MAX_TOP_K, DEFAULT_SNIPPET_CHARS, DEFAULT_TOP_K,
+    MIN_SNIPPET_CHARS,
+};
+use crate::schedule::CronSchedule;
+use crate::session::{DEFAULT_SESSION_MAX_TURNS, DEFAULT_SESSION_TTL};
+
 /// Service configuration loaded from environment variables.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 #[allow(dead_code)]
 pub struct Config {
-    /// Path to the .mv2 memvid file
+    /// Path to the .mv2 memvid file (the default index)
     pub memvid_file_path: String,
+    /// Named indexes served side by side as (name, path), default first
+    /// (empty = `memvid_file_path` only)
+    pub memvid_file_paths: Vec<(String, String)>,
     /// gRPC server port
     pub grpc_port: u16,
     /// Prometheus metrics HTTP port
@@ -20,31 +41,347 @@ pub struct Config {
     pub mock_memvid: bool,
     /// Log level (trace, debug, info, warn, error)
     pub log_level: String,
+    /// Cron expression (UTC) for scheduled index re-check and reload
+    pub reload_schedule: Option<String>,
+    /// Maximum random delay added to each scheduled reload, in seconds
+    pub reload_jitter_secs: u64,
+    /// Delay before retrying a failed scheduled reload, doubling per failure
+    pub reload_retry_initial_secs: u64,
+    /// Longest delay between reload retries
+    pub reload_retry_max_secs: u64,
+    /// Allowed clock skew for future timestamps in temporal queries, in seconds
+    pub clock_skew_tolerance_secs: u64,
+    /// Maximum serialized response size in bytes; larger responses are trimmed
+    pub max_response_bytes: usize,
+    /// Candidate .mv2 file receiving mirrored traffic for comparison (canary mode)
+    pub shadow_memvid_file_path: Option<String>,
+    /// Percentage of requests mirrored to the shadow candidate (0-100)
+    pub shadow_sample_percent: u8,
+    /// Frames in the synthetic mock corpus (0 = fixed sample entries)
+    pub synthetic_frames: usize,
+    /// Seed for the synthetic mock corpus
+    pub synthetic_seed: u64,
+    /// Bandwidth cap shared by all export streams, in bytes per second (0 = unlimited)
+    pub export_max_bytes_per_sec: u64,
+    /// How long an export waits for a client acknowledgment before aborting, in seconds
+    pub export_ack_timeout_secs: u64,
+    /// Estimated LLM prompt price in USD per million tokens
+    pub llm_prompt_cost_per_mtok: f64,
+    /// Estimated LLM completion price in USD per million tokens
+    pub llm_completion_cost_per_mtok: f64,
+    /// Daily LLM spend after which synthesis is disabled (0 = unlimited)
+    pub llm_daily_cost_cap_usd: f64,
+    /// Retrieval pipeline definition as a JSON list of stages (None = retrieve only)
+    pub retrieval_pipeline: Option<String>,
+    /// Rolling window for the frame coverage report, in hours
+    pub coverage_window_hours: u64,
+    /// Public demo profile: forces the safe settings below (see `apply_public_demo`)
+    pub public_demo: bool,
+    /// Deterministic mode: reproducible responses (see `apply_deterministic`)
+    pub deterministic: bool,
+    /// Unix time deterministic mode uses as "now"
+    pub deterministic_now: i64,
+    /// Redact contact details (emails, phone numbers, profile URLs) from responses
+    pub anonymize: bool,
+    /// Requests per minute allowed per client on the query APIs (0 = unlimited)
+    pub rate_limit_per_minute: u32,
+    /// Burst size of the per-client rate limit (None = one minute's quota)
+    pub rate_limit_burst: Option<u32>,
+    /// `top_k` of requests that leave it unset
+    pub default_top_k: i32,
+    /// Largest `top_k` served; larger requests are capped
+    pub max_top_k: i32,
+    /// `snippet_chars` of requests that leave it unset
+    pub default_snippet_chars: i32,
+    /// Largest `snippet_chars` served; larger requests are capped
+    pub max_snippet_chars: i32,
+    /// Serve the Admin gRPC service
+    pub admin_rpcs: bool,
+    /// Bearer token every Admin RPC requires (None = Admin RPCs refused)
+    pub admin_token: Option<String>,
+    /// Let Admin RPCs write to the serving index file
+    pub index_writes: bool,
+    /// Directory Admin/SnapshotIndex copies the serving index to (None = disabled)
+    pub backup_dir: Option<String>,
+    /// Directory Admin/StageIndex may load index files from, besides
+    /// `backup_dir` (None = staging refused)
+    pub index_dir: Option<String>,
+    /// Metrics listener bind address ("auto" = same detection as gRPC)
+    pub metrics_bind_address: String,
+    /// Allow LLM answer synthesis
+    pub llm_synthesis: bool,
+    /// Wire format of the LLM API (openai, ollama)
+    pub llm_provider: String,
+    /// Base URL of the LLM API (None = no LLM)
+    pub llm_base_url: Option<String>,
+    /// Model answers are synthesized with
+    pub llm_model: Option<String>,
+    /// Bearer token for the LLM API
+    pub llm_api_key: Option<String>,
+    /// Seconds an LLM request may take before it is retried or the context answer is used
+    pub llm_timeout_secs: u64,
+    /// Retries of a failed LLM request
+    pub llm_max_retries: u32,
+    /// How synthesized answers are held to their citations (off, lenient, strict)
+    pub citation_policy: String,
+    /// Read the whole .mv2 into memory at load
+    pub preload_index: bool,
+    /// mlock the preloaded .mv2 (implies `preload_index`)
+    pub mlock_index: bool,
+    /// Largest share of available memory a preloaded index may take, in percent
+    pub preload_max_memory_percent: u8,
+    /// Blocking searches and asks running at once (0 = unlimited)
+    pub max_concurrent_searches: usize,
+    /// Searches waiting for a slot before new ones fail with RESOURCE_EXHAUSTED
+    pub max_queued_searches: usize,
+    /// Read-only instances opened per index for concurrent queries
+    pub memvid_read_pool_size: usize,
+    /// Local ONNX embedding model (directory or .onnx file) for semantic retrieval
+    pub embed_model_path: Option<String>,
+    /// Directory receiving crash reports (None = not written)
+    pub crash_report_path: Option<String>,
+    /// Plain-HTTP webhook receiving crash reports (None = not posted)
+    pub crash_report_webhook: Option<String>,
+    /// Plain http:// URL receiving operational alerts as JSON
+    pub alert_webhook: Option<String>,
+    /// Recent gRPC requests kept for crash reports and the debug endpoint
+    pub request_log_capacity: usize,
+    /// Bearer token for the /debug endpoints (None = disabled)
+    pub debug_token: Option<String>,
+    /// Key signing page cursors (None = random per process)
+    pub cursor_secret: Option<String>,
+    /// JSON file persisting frame visibility overrides (None = in memory)
+    pub visibility_file: Option<String>,
+    /// API keys whose callers may see authenticated-only frames
+    pub authenticated_api_keys: Vec<String>,
+    /// Consul or etcd key prefix holding runtime settings (None = environment only)
+    pub config_source: Option<String>,
+    /// Token for the runtime config store
+    pub config_source_token: Option<String>,
+    /// Interval between runtime config reads, in seconds
+    pub config_poll_secs: u64,
+    /// Answer cache: "memory" or a redis:// URL shared by replicas (None = off)
+    pub answer_cache: Option<String>,
+    /// Lifetime of a cached answer, in seconds
+    pub answer_cache_ttl_secs: u64,
+    /// File of `term → expansion` synonym rules for search queries
+    pub synonyms_file: Option<String>,
+    /// Aspects a decomposed Ask retrieves for
+    pub ask_aspects: Vec<String>,
+    /// Have the LLM write the sub-questions of a decomposed Ask
+    pub ask_aspect_llm: bool,
+    /// Retry searches without hits with their misspelled words corrected
+    pub spell_correction: bool,
+    /// Session store: "memory", a directory or a redis:// URL shared by replicas
+    pub session_store: String,
+    /// Seconds a conversation session is kept after its last turn
+    pub session_ttl_secs: u64,
+    /// Most recent turns kept per session
+    pub session_max_turns: usize,
+    /// File of common questions WarmCache runs, one per line (None = none)
+    pub warm_cache_corpus: Option<String>,
+    /// Warm answer cache snapshot, restored at startup (None = not kept)
+    pub warm_cache_snapshot: Option<String>,
+    /// Lifetime of a cached search result, in seconds
+    pub search_cache_ttl_secs: u64,
+    /// Search results cached at most (0 = off)
+    pub search_cache_capacity: usize,
+    /// Pod `terminationGracePeriodSeconds`; a drain ends before it runs out
+    pub termination_grace_period_secs: u64,
+    /// Seconds between reporting NOT_SERVING and no longer accepting requests
+    pub drain_delay_secs: u64,
+    /// Serve the preStop-compatible `/quitquitquit` endpoint on the metrics listener
+    pub lifecycle_endpoint: bool,
+    /// Readiness gate policy overrides, `gate=required|degrade|ignore` pairs
+    pub readiness_policy: String,
+    /// Comma-separated queries run at startup before reporting SERVING
+    pub warmup_queries: Option<String>,
+    /// File of startup warm-up queries, one per line
+    pub warmup_queries_file: Option<String>,
+    /// Proxy for outbound plain HTTP requests (None = direct)
+    pub http_proxy: Option<String>,
+    /// Proxy for outbound HTTPS requests (None = direct)
+    pub https_proxy: Option<String>,
+    /// Hosts reached directly despite a proxy, comma-separated
+    pub no_proxy: Option<String>,
+    /// Hosts outbound connections may reach, comma-separated (None = any)
+    pub egress_allowlist: Option<String>,
+    /// PEM CA certificates trusted for outbound HTTPS besides the web PKI roots
+    pub ca_bundle_path: Option<String>,
+    /// Directory of the sled store persisting per-frame serve counts (in-memory when unset)
+    pub frame_stats_path: Option<String>,
+    /// PEM certificate chain for TLS on the gRPC listener (set with `tls_key_path`)
+    pub tls_cert_path: Option<String>,
+    /// PEM private key for TLS on the gRPC listener (set with `tls_cert_path`)
+    pub tls_key_path: Option<String>,
+    /// When to deliver the analytics report: "daily", "weekly" or a cron expression
+    pub analytics_report_schedule: Option<String>,
+    /// Analytics report file format: "markdown" or "json"
+    pub analytics_report_format: String,
+    /// Directory analytics reports are written to
+    pub analytics_report_path: Option<String>,
+    /// Plain `http://` URL analytics reports are posted to as JSON
+    pub analytics_report_webhook: Option<String>,
+    /// Required `iss` of bearer tokens; enables JWT validation with `jwt_jwks_url`
+    pub jwt_issuer: Option<String>,
+    /// URL of the issuer's JWKS (http:// or https://)
+    pub jwt_jwks_url: Option<String>,
+    /// Required `aud` of bearer tokens (unchecked when unset)
+    pub jwt_audience: Option<String>,
+    /// Reject query requests without a bearer token
+    pub jwt_required: bool,
+    /// Seconds between JWKS refreshes
+    pub jwt_jwks_refresh_secs: u64,
+    /// How memvid-core applies frame ACLs (audit, enforce)
+    pub acl_enforcement: String,
+    /// Drop in-memory request records and query statistics older than this
+    pub retention_max_age_hours: Option<u64>,
+    /// Records kept per retained store (request log, distinct queries)
+    pub retention_max_entries: Option<usize>,
+    /// Log and count query text only as a keyed hash
+    pub retention_hash_queries: bool,
 }
 
+/// Per-client rate limit enforced by the public demo profile.
+pub const DEMO_RATE_LIMIT_PER_MINUTE: u32 = 30;
+
+/// Page cursor key of deterministic mode when `CURSOR_SECRET` is unset.
+const DETERMINISTIC_CURSOR_SECRET: &str = "memvid-deterministic";
+
 impl Config {
     /// Load configuration from environment variables.
     ///
     /// # Environment Variables
     /// - `MEMVID_FILE_PATH` - Path to .mv2 file (required unless MOCK_MEMVID=true)
+    /// - `MEMVID_FILE_PATHS` - Named indexes as `name=path,...` or a TOML map; the first is the default (optional)
     /// - `GRPC_PORT` - gRPC listen port (default: 50051)
     /// - `METRICS_PORT` - Prometheus metrics port (default: 9090)
     /// - `BIND_ADDRESS` - Bind address (default: auto-detect [::]  or 0.0.0.0)
     /// - `MOCK_MEMVID` - Use mock searcher for testing (default: false)
     /// - `RUST_LOG` - Log level (default: info)
+    /// - `RELOAD_SCHEDULE` - Cron expression for scheduled reloads, e.g. "0 3 * * *" (default: off)
+    /// - `RELOAD_JITTER_SECS` - Max random delay per scheduled reload (default: 300)
+    /// - `RELOAD_RETRY_INITIAL_SECS` - Delay before retrying a failed scheduled reload (default: 30)
+    /// - `RELOAD_RETRY_MAX_SECS` - Longest delay between reload retries (default: 900)
+    /// - `CLOCK_SKEW_TOLERANCE_SECS` - Allowed future skew for temporal queries (default: 300)
+    /// - `MAX_RESPONSE_BYTES` - Maximum serialized response size (default: 4000000)
+    /// - `SHADOW_MEMVID_FILE_PATH` - Candidate .mv2 file for canary comparison (default: off)
+    /// - `SHADOW_SAMPLE_PERCENT` - Percentage of requests mirrored to the candidate (default: 100)
+    /// - `SYNTHETIC_FRAMES` - Serve a generated corpus of this size in mock mode (default: 0 = off)
+    /// - `SYNTHETIC_SEED` - Seed for the synthetic corpus (default: 42)
+    /// - `EXPORT_MAX_BYTES_PER_SEC` - Bandwidth cap shared by export streams (default: 1048576, 0 = unlimited)
+    /// - `EXPORT_ACK_TIMEOUT_SECS` - Max wait for an export acknowledgment (default: 30)
+    /// - `LLM_PROMPT_COST_PER_MTOK` - LLM prompt price, USD per million tokens (default: 0)
+    /// - `LLM_COMPLETION_COST_PER_MTOK` - LLM completion price, USD per million tokens (default: 0)
+    /// - `LLM_DAILY_COST_CAP_USD` - Daily LLM cost cap in USD, 0 = unlimited (default: 0)
+    /// - `RETRIEVAL_PIPELINE` - JSON list of retrieval stages (default: retrieve only)
+    /// - `COVERAGE_WINDOW_HOURS` - Rolling window for the frame coverage report (default: 168)
+    /// - `PUBLIC_DEMO` - Enable the public demo profile (default: false)
+    /// - `DETERMINISTIC` - Byte-identical responses run after run (default: false)
+    /// - `DETERMINISTIC_NOW` - Unix time used as "now" in deterministic mode (default: 2025-01-01)
+    /// - `ANONYMIZE` - Redact contact details from responses (default: false)
+    /// - `RATE_LIMIT_PER_MINUTE` - Requests per minute per client, 0 = unlimited (default: 0)
+    /// - `RATE_LIMIT_RPS` - Requests per second per client, instead of `RATE_LIMIT_PER_MINUTE` (optional)
+    /// - `RATE_LIMIT_BURST` - Requests a client may burst (default: `RATE_LIMIT_RPS` rounded up, else one minute's quota)
+    /// - `DEFAULT_TOP_K` - `top_k` of requests that leave it unset (default: 5)
+    /// - `MAX_TOP_K` - Largest `top_k` a request is served, larger ones are capped (default: 20)
+    /// - `DEFAULT_SNIPPET_CHARS` - `snippet_chars` of requests that leave it unset (default: 200)
+    /// - `MAX_SNIPPET_CHARS` - Largest `snippet_chars` a request is served, at least 50 (default: 1000)
+    /// - `ADMIN_RPCS` - Serve the Admin gRPC service (default: true)
+    /// - `ADMIN_TOKEN` - Bearer token every Admin RPC requires (default: none, Admin RPCs refused)
+    /// - `INDEX_WRITES` - Let Admin/AppendFrames, SetState and DeleteFrames write to the serving index file (default: false)
+    /// - `BACKUP_DIR` - Directory Admin/SnapshotIndex copies the serving index to (optional)
+    /// - `INDEX_DIR` - Directory Admin/StageIndex may load index files from (default: the directory of `MEMVID_FILE_PATH`)
+    /// - `METRICS_BIND_ADDRESS` - Metrics listener bind address (default: auto)
+    /// - `LLM_SYNTHESIS` - Allow LLM answer synthesis (default: true)
+    /// - `LLM_PROVIDER` - openai or ollama (default: openai)
+    /// - `LLM_BASE_URL` - LLM API base URL, e.g. https://api.openai.com/v1 (default: off; ollama: http://localhost:11434)
+    /// - `LLM_MODEL` - Chat model, required with an LLM backend
+    /// - `LLM_API_KEY` - Bearer token for the LLM API (optional)
+    /// - `LLM_TIMEOUT_SECS` - Seconds an LLM request may take (default: 30)
+    /// - `LLM_MAX_RETRIES` - Retries of a failed LLM request (default: 2)
+    /// - `CITATION_POLICY` - off, lenient or strict (default: lenient)
+    /// - `PRELOAD_INDEX` - Read the whole .mv2 into memory at load (default: false)
+    /// - `MLOCK_INDEX` - Preload and mlock the .mv2 (default: false)
+    /// - `PRELOAD_MAX_MEMORY_PERCENT` - Max share of available memory for preloading, 1-100 (default: 50)
+    /// - `MAX_CONCURRENT_SEARCHES` - Blocking searches and asks at once, 0 = unlimited (default: 16)
+    /// - `MAX_QUEUED_SEARCHES` - Searches waiting for a slot before rejection (default: 64)
+    /// - `MEMVID_READ_POOL_SIZE` - Read-only instances per index, 1-32 (default: 1)
+    /// - `EMBED_MODEL_PATH` - Local ONNX embedding model; needs the `onnx` feature (default: off)
+    /// - `CRASH_REPORT_PATH` - Directory receiving crash reports (default: off)
+    /// - `CRASH_REPORT_WEBHOOK` - http:// URL receiving crash reports (default: off)
+    /// - `ALERT_WEBHOOK` - http:// URL receiving operational alerts (default: off)
+    /// - `REQUEST_LOG_CAPACITY` - Recent requests kept for crash reports and debugging (default: 50)
+    /// - `DEBUG_TOKEN` - Bearer token enabling /debug/last-requests (default: off)
+    /// - `CURSOR_SECRET` - Key signing page cursors (default: random per process)
+    /// - `VISIBILITY_FILE` - JSON file persisting frame visibility overrides (default: in memory)
+    /// - `AUTHENTICATED_API_KEYS` - Comma-separated API keys that may see authenticated-only frames
+    /// - `CONFIG_SOURCE` - consul://host:port/prefix or etcd://host:port/prefix with runtime settings (default: off)
+    /// - `CONFIG_SOURCE_TOKEN` - Consul ACL token or etcd Authorization header value (default: none)
+    /// - `CONFIG_POLL_SECS` - Interval between runtime config reads (default: 10)
+    /// - `ANSWER_CACHE` - off, memory or a redis:// URL shared by replicas (default: off)
+    /// - `ANSWER_CACHE_TTL_SECS` - Lifetime of a cached answer (default: 300)
+    /// - `SYNONYMS_FILE` - File of `term → expansion` rules appended to search queries (optional)
+    /// - `ASK_ASPECTS` - Comma-separated aspects a decomposed Ask retrieves for (default: experience,skills,leadership,education)
+    /// - `ASK_ASPECT_LLM` - Have the LLM write the sub-questions of a decomposed Ask (default: false)
+    /// - `SPELL_CORRECTION` - Retry searches without hits with corrected spelling (default: true)
+    /// - `SESSION_STORE` - memory, a directory or a redis:// URL shared by replicas (default: memory)
+    /// - `SESSION_TTL_SECS` - Lifetime of a session after its last turn (default: 3600)
+    /// - `SESSION_MAX_TURNS` - Most recent turns kept per session (default: 20)
+    /// - `WARM_CACHE_CORPUS` - File of common questions for WarmCache (optional)
+    /// - `WARM_CACHE_SNAPSHOT` - Warm answer cache snapshot file (optional)
+    /// - `SEARCH_CACHE_TTL_SECS` - Lifetime of a cached search result (default: 60)
+    /// - `SEARCH_CACHE_CAPACITY` - Search results cached at most, 0 = off (default: 1024)
+    /// - `TERMINATION_GRACE_PERIOD_SECS` - Pod termination grace period (default: 30)
+    /// - `DRAIN_DELAY_SECS` - NOT_SERVING time before the server stops accepting requests (default: 5)
+    /// - `LIFECYCLE_ENDPOINT` - Serve /quitquitquit on the metrics listener (default: false)
+    /// - `READINESS_POLICY` - Readiness gate policies, e.g. `llm_healthy=degrade` (default: index and warm-up required)
+    /// - `WARMUP_QUERIES` - Comma-separated startup warm-up queries (default: one generic query)
+    /// - `WARMUP_QUERIES_FILE` - File of startup warm-up queries, one per line (optional)
+    /// - `HTTP_PROXY` / `http_proxy` - Proxy for outbound HTTP (optional)
+    /// - `HTTPS_PROXY` / `https_proxy` - Proxy for outbound HTTPS (optional)
+    /// - `NO_PROXY` / `no_proxy` - Hosts reached without the proxy (optional)
+    /// - `EGRESS_ALLOWLIST` - Hosts outbound connections may reach (default: any)
+    /// - `CA_BUNDLE_PATH` - Extra PEM CA certificates for outbound HTTPS (optional)
+    /// - `FRAME_STATS_PATH` - Directory persisting per-frame serve counts (optional)
+    /// - `TLS_CERT_PATH` - PEM certificate chain; serve gRPC over TLS (optional)
+    /// - `TLS_KEY_PATH` - PEM private key, required with `TLS_CERT_PATH` (optional)
+    /// - `ANALYTICS_REPORT_SCHEDULE` - daily, weekly or a cron expression (optional)
+    /// - `ANALYTICS_REPORT_FORMAT` - markdown or json (default: markdown)
+    /// - `ANALYTICS_REPORT_PATH` - Directory for analytics reports (optional)
+    /// - `ANALYTICS_REPORT_WEBHOOK` - http:// URL analytics reports are posted to (optional)
+    /// - `JWT_ISSUER` - Required token issuer; enables bearer-token validation (optional)
+    /// - `JWT_JWKS_URL` - Issuer JWKS URL, required with `JWT_ISSUER` (optional)
+    /// - `JWT_AUDIENCE` - Required token audience (optional)
+    /// - `JWT_REQUIRED` - Reject query requests without a token (default: false)
+    /// - `JWT_JWKS_REFRESH_SECS` - Seconds between JWKS refreshes (default: 600)
+    /// - `ACL_ENFORCEMENT` - audit or enforce frame ACLs (default: audit)
+    /// - `RETENTION_MAX_AGE_HOURS` - Maximum age of request records and query statistics (default: unlimited)
+    /// - `RETENTION_MAX_ENTRIES` - Records kept per retained store (default: store limits)
+    /// - `RETENTION_HASH_QUERIES` - Log and count query text only as a keyed hash (default: false)
     pub fn from_env() -> Result<Self, ConfigError> {
         let mock_memvid = env::var("MOCK_MEMVID")
             .map(|v| v.to_lowercase() == "true" || v == "1")
             .unwrap_or(false);
 
-        let memvid_file_path = env::var("MEMVID_FILE_PATH").unwrap_or_else(|_| {
-            if mock_memvid {
-                String::new()
-            } else {
-                // Default path for development
-                "data/.memvid/resume.mv2".to_string()
-            }
-        });
+        let memvid_file_paths = match env::var("MEMVID_FILE_PATHS") {
+            Ok(v) if !v.trim().is_empty() => parse_index_paths(&v)
+                .map_err(|e| ConfigError::InvalidValue("MEMVID_FILE_PATHS", e))?,
+            _ => Vec::new(),
+        };
+
+        // The first named index takes the place of MEMVID_FILE_PATH
+        let memvid_file_path = match memvid_file_paths.first() {
+            Some((_, path)) => path.clone(),
+            None => env::var("MEMVID_FILE_PATH").unwrap_or_else(|_| {
+                if mock_memvid {
+                    String::new()
+                } else {
+                    // Default path for development
+                    "data/.memvid/resume.mv2".to_string()
+                }
+            }),
+        };
 
         // Validate memvid file path is set when not in mock mode
         if !mock_memvid && memvid_file_path.is_empty() {
@@ -67,22 +404,867 @@ impl Config {
         // Try dual-stack (::) first, fall back to IPv4-only (0.0.0.0) if needed
         let bind_address = env::var("BIND_ADDRESS").unwrap_or_else(|_| "auto".to_string());
 
-        Ok(Config {
+        let reload_schedule = env::var("RELOAD_SCHEDULE")
+            .ok()
+            .filter(|v| !v.trim().is_empty());
+        if let Some(expr) = &reload_schedule {
+            CronSchedule::parse(expr)
+                .map_err(|e| ConfigError::InvalidValue("RELOAD_SCHEDULE", e))?;
+        }
+
+        let reload_jitter_secs = env::var("RELOAD_JITTER_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(300);
+
+        let reload_retry_initial_secs = env::var("RELOAD_RETRY_INITIAL_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(30);
+
+        let reload_retry_max_secs = env::var("RELOAD_RETRY_MAX_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(900);
+
+        let clock_skew_tolerance_secs = env::var("CLOCK_SKEW_TOLERANCE_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(300);
+
+        let max_response_bytes = env::var("MAX_RESPONSE_BYTES")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(4_000_000);
+
+        let shadow_memvid_file_path = env::var("SHADOW_MEMVID_FILE_PATH")
+            .ok()
+            .filter(|v| !v.trim().is_empty());
+
+        let shadow_sample_percent = match env::var("SHADOW_SAMPLE_PERCENT") {
+            Ok(v) => v.parse::<u8>().ok().filter(|p| *p <= 100).ok_or_else(|| {
+                ConfigError::InvalidValue(
+                    "SHADOW_SAMPLE_PERCENT",
+                    format!("expected 0-100, got '{}'", v),
+                )
+            })?,
+            Err(_) => 100,
+        };
+
+        let synthetic_frames = env::var("SYNTHETIC_FRAMES")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(0);
+
+        let synthetic_seed = env::var("SYNTHETIC_SEED")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(42);
+
+        let export_max_bytes_per_sec = env::var("EXPORT_MAX_BYTES_PER_SEC")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(1_048_576);
+
+        let export_ack_timeout_secs = env::var("EXPORT_ACK_TIMEOUT_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(30);
+
+        let llm_prompt_cost_per_mtok = env::var("LLM_PROMPT_COST_PER_MTOK")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(0.0);
+
+        let llm_completion_cost_per_mtok = env::var("LLM_COMPLETION_COST_PER_MTOK")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(0.0);
+
+        let llm_daily_cost_cap_usd = env::var("LLM_DAILY_COST_CAP_USD")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(0.0);
+
+        let retrieval_pipeline = env::var("RETRIEVAL_PIPELINE")
+            .ok()
+            .filter(|v| !v.trim().is_empty());
+        if let Some(json) = &retrieval_pipeline {
+            RetrievalPipeline::parse(json)
+                .map_err(|e| ConfigError::InvalidValue("RETRIEVAL_PIPELINE", e))?;
+        }
+
+        let coverage_window_hours = env::var("COVERAGE_WINDOW_HOURS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(168);
+
+        let public_demo = env_flag("PUBLIC_DEMO", false);
+        let deterministic = env_flag("DETERMINISTIC", false);
+        let deterministic_now = env::var("DETERMINISTIC_NOW")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_DETERMINISTIC_NOW);
+        let anonymize = env_flag("ANONYMIZE", false);
+
+        let mut rate_limit_per_minute = env::var("RATE_LIMIT_PER_MINUTE")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(0);
+        let mut rate_limit_burst = match env::var("RATE_LIMIT_BURST") {
+            Ok(v) if !v.trim().is_empty() => match v.trim().parse::<u32>() {
+                Ok(burst) if burst > 0 => Some(burst),
+                _ => {
+                    return Err(ConfigError::InvalidValue(
+                        "RATE_LIMIT_BURST",
+                        format!("expected a positive number of requests, got '{}'", v),
+                    ))
+                }
+            },
+            _ => None,
+        };
+        if let Ok(v) = env::var("RATE_LIMIT_RPS") {
+            if !v.trim().is_empty() {
+                if env::var("RATE_LIMIT_PER_MINUTE").is_ok() {
+                    return Err(ConfigError::InvalidValue(
+                        "RATE_LIMIT_RPS",
+                        "set either RATE_LIMIT_RPS or RATE_LIMIT_PER_MINUTE".to_string(),
+                    ));
+                }
+                let rps = v
+                    .trim()
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|rps| rps.is_finite() && *rps > 0.0 && *rps <= u32::MAX as f64 / 60.0)
+                    .ok_or_else(|| {
+                        ConfigError::InvalidValue(
+                            "RATE_LIMIT_RPS",
+                            format!("expected a positive number of requests, got '{}'", v),
+                        )
+                    })?;
+                // Buckets refill per minute; whole requests per minute are
+                // precise enough for any practical rate
+                rate_limit_per_minute = ((rps * 60.0).round() as u32).max(1);
+                rate_limit_burst = rate_limit_burst.or(Some(rps.ceil() as u32));
+            }
+        }
+
+        let default_top_k = env_positive("DEFAULT_TOP_K", DEFAULT_TOP_K)?;
+        let max_top_k = env_positive("MAX_TOP_K", DEFAULT_MAX_TOP_K)?;
+        if default_top_k > max_top_k {
+            return Err(ConfigError::InvalidValue(
+                "DEFAULT_TOP_K",
+                format!("{} exceeds MAX_TOP_K ({})", default_top_k, max_top_k),
+            ));
+        }
+        let default_snippet_chars = env_positive("DEFAULT_SNIPPET_CHARS", DEFAULT_SNIPPET_CHARS)?;
+        let max_snippet_chars = env_positive("MAX_SNIPPET_CHARS", DEFAULT_MAX_SNIPPET_CHARS)?;
+        if max_snippet_chars < MIN_SNIPPET_CHARS {
+            return Err(ConfigError::InvalidValue(
+                "MAX_SNIPPET_CHARS",
+                format!(
+                    "expected at least {}, got {}",
+                    MIN_SNIPPET_CHARS, max_snippet_chars
+                ),
+            ));
+        }
+        if !(MIN_SNIPPET_CHARS..=max_snippet_chars).contains(&default_snippet_chars) {
+            return Err(ConfigError::InvalidValue(
+                "DEFAULT_SNIPPET_CHARS",
+                format!(
+                    "expected {} to MAX_SNIPPET_CHARS ({}), got {}",
+                    MIN_SNIPPET_CHARS, max_snippet_chars, default_snippet_chars
+                ),
+            ));
+        }
+
+        let admin_rpcs = env_flag("ADMIN_RPCS", true);
+        let admin_token = env::var("ADMIN_TOKEN")
+            .ok()
+            .filter(|v| !v.trim().is_empty());
+        let index_writes = env_flag("INDEX_WRITES", false);
+        let backup_dir = env::var("BACKUP_DIR").ok().filter(|v| !v.trim().is_empty());
+        let index_dir = env::var("INDEX_DIR")
+            .ok()
+            .filter(|v| !v.trim().is_empty())
+            .or_else(|| default_index_dir(&memvid_file_path));
+        let metrics_bind_address =
+            env::var("METRICS_BIND_ADDRESS").unwrap_or_else(|_| "auto".to_string());
+        let llm_synthesis = env_flag("LLM_SYNTHESIS", true);
+        let llm_provider = env::var("LLM_PROVIDER").unwrap_or_else(|_| "openai".to_string());
+        let provider = LlmProvider::parse(&llm_provider)
+            .map_err(|e| ConfigError::InvalidValue("LLM_PROVIDER", e))?;
+        // A local Ollama instance needs no URL
+        let llm_base_url = env::var("LLM_BASE_URL")
+            .ok()
+            .filter(|v| !v.trim().is_empty())
+            .or_else(|| (provider == LlmProvider::Ollama).then(|| DEFAULT_OLLAMA_URL.to_string()));
+        let llm_model = env::var("LLM_MODEL").ok().filter(|v| !v.trim().is_empty());
+        match (&llm_base_url, &llm_model) {
+            (Some(url), _) if !url.starts_with("http://") && !url.starts_with("https://") => {
+                return Err(ConfigError::InvalidValue(
+                    "LLM_BASE_URL",
+                    format!("expected an http:// or https:// URL, got '{}'", url),
+                ))
+            }
+            (Some(_), None) => {
+                return Err(ConfigError::InvalidValue(
+                    "LLM_MODEL",
+                    "required when LLM_BASE_URL or LLM_PROVIDER=ollama is set".to_string(),
+                ))
+            }
+            _ => {}
+        }
+        let llm_api_key = env::var("LLM_API_KEY")
+            .ok()
+            .filter(|v| !v.trim().is_empty());
+        let llm_timeout_secs = env::var("LLM_TIMEOUT_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .filter(|secs| *secs > 0)
+            .unwrap_or(30);
+        let llm_max_retries = env::var("LLM_MAX_RETRIES")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_LLM_MAX_RETRIES);
+        let citation_policy = env::var("CITATION_POLICY").unwrap_or_else(|_| "lenient".to_string());
+        CitationPolicy::parse(&citation_policy)
+            .map_err(|e| ConfigError::InvalidValue("CITATION_POLICY", e))?;
+
+        let mlock_index = env_flag("MLOCK_INDEX", false);
+        let preload_index = env_flag("PRELOAD_INDEX", false) || mlock_index;
+        let preload_max_memory_percent = match env::var("PRELOAD_MAX_MEMORY_PERCENT") {
+            Ok(v) => v
+                .parse::<u8>()
+                .ok()
+                .filter(|p| (1..=100).contains(p))
+                .ok_or_else(|| {
+                    ConfigError::InvalidValue(
+                        "PRELOAD_MAX_MEMORY_PERCENT",
+                        format!("expected 1-100, got '{}'", v),
+                    )
+                })?,
+            Err(_) => 50,
+        };
+
+        let max_concurrent_searches = env::var("MAX_CONCURRENT_SEARCHES")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_MAX_CONCURRENT_SEARCHES);
+        let max_queued_searches = env::var("MAX_QUEUED_SEARCHES")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_MAX_QUEUED_SEARCHES);
+        let memvid_read_pool_size = match env::var("MEMVID_READ_POOL_SIZE") {
+            Ok(v) => v
+                .parse::<usize>()
+                .ok()
+                .filter(|size| (1..=MAX_READ_POOL_SIZE).contains(size))
+                .ok_or_else(|| {
+                    ConfigError::InvalidValue(
+                        "MEMVID_READ_POOL_SIZE",
+                        format!("expected 1-{}, got '{}'", MAX_READ_POOL_SIZE, v),
+                    )
+                })?,
+            Err(_) => 1,
+        };
+        let embed_model_path = env::var("EMBED_MODEL_PATH")
+            .ok()
+            .filter(|v| !v.trim().is_empty());
+        if embed_model_path.is_some() && !cfg!(feature = "onnx") {
+            return Err(ConfigError::InvalidValue(
+                "EMBED_MODEL_PATH",
+                "this build lacks the onnx feature".to_string(),
+            ));
+        }
+
+        let crash_report_path = env::var("CRASH_REPORT_PATH")
+            .ok()
+            .filter(|v| !v.trim().is_empty());
+        let crash_report_webhook = env::var("CRASH_REPORT_WEBHOOK")
+            .ok()
+            .filter(|v| !v.trim().is_empty());
+        if let Some(url) = &crash_report_webhook {
+            crate::crash::validate_webhook(url)
+                .map_err(|e| ConfigError::InvalidValue("CRASH_REPORT_WEBHOOK", e))?;
+        }
+        let alert_webhook = env::var("ALERT_WEBHOOK")
+            .ok()
+            .filter(|v| !v.trim().is_empty());
+        if let Some(url) = &alert_webhook {
+            crate::crash::validate_webhook(url)
+                .map_err(|e| ConfigError::InvalidValue("ALERT_WEBHOOK", e))?;
+        }
+        let request_log_capacity = env::var("REQUEST_LOG_CAPACITY")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_REQUEST_LOG_CAPACITY);
+        let debug_token = env::var("DEBUG_TOKEN")
+            .ok()
+            .filter(|v| !v.trim().is_empty());
+        let cursor_secret = env::var("CURSOR_SECRET")
+            .ok()
+            .filter(|v| !v.trim().is_empty());
+        let visibility_file = env::var("VISIBILITY_FILE")
+            .ok()
+            .filter(|v| !v.trim().is_empty());
+        let authenticated_api_keys = env::var("AUTHENTICATED_API_KEYS")
+            .map(|v| {
+                v.split(',')
+                    .map(str::trim)
+                    .filter(|key| !key.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+        let config_source = env::var("CONFIG_SOURCE")
+            .ok()
+            .filter(|v| !v.trim().is_empty());
+        if let Some(url) = &config_source {
+            crate::runtime_config::ConfigSource::parse(url)
+                .map_err(|e| ConfigError::InvalidValue("CONFIG_SOURCE", e))?;
+        }
+        let config_source_token = env::var("CONFIG_SOURCE_TOKEN")
+            .ok()
+            .filter(|v| !v.trim().is_empty());
+        let config_poll_secs = env::var("CONFIG_POLL_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(10);
+        let answer_cache = env::var("ANSWER_CACHE")
+            .ok()
+            .filter(|v| !v.trim().is_empty());
+        if let Some(value) = &answer_cache {
+            crate::memvid::AnswerCacheBackend::parse(value)
+                .map_err(|e| ConfigError::InvalidValue("ANSWER_CACHE", e))?;
+        }
+        let answer_cache_ttl_secs = env::var("ANSWER_CACHE_TTL_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(300);
+        let synonyms_file = env::var("SYNONYMS_FILE")
+            .ok()
+            .filter(|v| !v.trim().is_empty());
+        let ask_aspects: Vec<String> = env::var("ASK_ASPECTS")
+            .map(|v| {
+                v.split(',')
+                    .map(str::trim)
+                    .filter(|aspect| !aspect.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_else(|_| DEFAULT_ASK_ASPECTS.map(String::from).to_vec());
+        if ask_aspects.is_empty() || ask_aspects.len() > MAX_ASPECTS {
+            return Err(ConfigError::InvalidValue(
+                "ASK_ASPECTS",
+                format!("expected 1 to {} aspects", MAX_ASPECTS),
+            ));
+        }
+        let ask_aspect_llm = env_flag("ASK_ASPECT_LLM", false);
+        if ask_aspect_llm && llm_base_url.is_none() {
+            return Err(ConfigError::InvalidValue(
+                "ASK_ASPECT_LLM",
+                "requires LLM_BASE_URL or LLM_PROVIDER=ollama".to_string(),
+            ));
+        }
+        let spell_correction = env_flag("SPELL_CORRECTION", true);
+        let session_store = env::var("SESSION_STORE").unwrap_or_else(|_| "memory".to_string());
+        let session_ttl_secs = env::var("SESSION_TTL_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_SESSION_TTL.as_secs());
+        let session_max_turns = env::var("SESSION_MAX_TURNS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_SESSION_MAX_TURNS);
+        let warm_cache_corpus = env::var("WARM_CACHE_CORPUS")
+            .ok()
+            .filter(|v| !v.trim().is_empty());
+        let warm_cache_snapshot = env::var("WARM_CACHE_SNAPSHOT")
+            .ok()
+            .filter(|v| !v.trim().is_empty());
+        let search_cache_ttl_secs = env::var("SEARCH_CACHE_TTL_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_SEARCH_CACHE_TTL.as_secs());
+        let search_cache_capacity = env::var("SEARCH_CACHE_CAPACITY")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_SEARCH_CACHE_CAPACITY);
+        let termination_grace_period_secs = env::var("TERMINATION_GRACE_PERIOD_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(30);
+        let drain_delay_secs = env::var("DRAIN_DELAY_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(5);
+        let lifecycle_endpoint = env_flag("LIFECYCLE_ENDPOINT", false);
+        let readiness_policy = env::var("READINESS_POLICY").unwrap_or_default();
+        ReadinessPolicy::parse(&readiness_policy)
+            .map_err(|e| ConfigError::InvalidValue("READINESS_POLICY", e))?;
+        let warmup_queries = env::var("WARMUP_QUERIES")
+            .ok()
+            .filter(|v| !v.trim().is_empty());
+        let warmup_queries_file = env::var("WARMUP_QUERIES_FILE")
+            .ok()
+            .filter(|v| !v.trim().is_empty());
+        // Proxy variables are conventionally set in either case
+        let proxy_var = |upper: &str, lower: &str| {
+            env::var(upper)
+                .or_else(|_| env::var(lower))
+                .ok()
+                .filter(|v| !v.trim().is_empty())
+        };
+        let http_proxy = proxy_var("HTTP_PROXY", "http_proxy");
+        if let Some(url) = &http_proxy {
+            Proxy::parse(url).map_err(|e| ConfigError::InvalidValue("HTTP_PROXY", e))?;
+        }
+        let https_proxy = proxy_var("HTTPS_PROXY", "https_proxy");
+        if let Some(url) = &https_proxy {
+            Proxy::parse(url).map_err(|e| ConfigError::InvalidValue("HTTPS_PROXY", e))?;
+        }
+        let no_proxy = proxy_var("NO_PROXY", "no_proxy");
+        let egress_allowlist = env::var("EGRESS_ALLOWLIST")
+            .ok()
+            .filter(|v| !v.trim().is_empty());
+        let ca_bundle_path = env::var("CA_BUNDLE_PATH")
+            .ok()
+            .filter(|v| !v.trim().is_empty());
+        let frame_stats_path = env::var("FRAME_STATS_PATH")
+            .ok()
+            .filter(|v| !v.trim().is_empty());
+        let tls_cert_path = env::var("TLS_CERT_PATH")
+            .ok()
+            .filter(|v| !v.trim().is_empty());
+        let tls_key_path = env::var("TLS_KEY_PATH")
+            .ok()
+            .filter(|v| !v.trim().is_empty());
+        match (&tls_cert_path, &tls_key_path) {
+            (Some(_), None) => {
+                return Err(ConfigError::InvalidValue(
+                    "TLS_KEY_PATH",
+                    "required when TLS_CERT_PATH is set".to_string(),
+                ))
+            }
+            (None, Some(_)) => {
+                return Err(ConfigError::InvalidValue(
+                    "TLS_CERT_PATH",
+                    "required when TLS_KEY_PATH is set".to_string(),
+                ))
+            }
+            _ => {}
+        }
+
+        let analytics_report_schedule = env::var("ANALYTICS_REPORT_SCHEDULE")
+            .ok()
+            .filter(|v| !v.trim().is_empty());
+        let analytics_report_format =
+            env::var("ANALYTICS_REPORT_FORMAT").unwrap_or_else(|_| "markdown".to_string());
+        ReportFormat::parse(&analytics_report_format)
+            .map_err(|e| ConfigError::InvalidValue("ANALYTICS_REPORT_FORMAT", e))?;
+        let analytics_report_path = env::var("ANALYTICS_REPORT_PATH")
+            .ok()
+            .filter(|v| !v.trim().is_empty());
+        let analytics_report_webhook = env::var("ANALYTICS_REPORT_WEBHOOK")
+            .ok()
+            .filter(|v| !v.trim().is_empty());
+        if let Some(url) = &analytics_report_webhook {
+            crate::crash::validate_webhook(url)
+                .map_err(|e| ConfigError::InvalidValue("ANALYTICS_REPORT_WEBHOOK", e))?;
+        }
+        if let Some(expr) = &analytics_report_schedule {
+            parse_report_schedule(expr)
+                .map_err(|e| ConfigError::InvalidValue("ANALYTICS_REPORT_SCHEDULE", e))?;
+            if analytics_report_path.is_none() && analytics_report_webhook.is_none() {
+                return Err(ConfigError::InvalidValue(
+                    "ANALYTICS_REPORT_SCHEDULE",
+                    "requires ANALYTICS_REPORT_PATH or ANALYTICS_REPORT_WEBHOOK".to_string(),
+                ));
+            }
+        }
+
+        let jwt_issuer = env::var("JWT_ISSUER").ok().filter(|v| !v.trim().is_empty());
+        let jwt_jwks_url = env::var("JWT_JWKS_URL")
+            .ok()
+            .filter(|v| !v.trim().is_empty());
+        match (&jwt_issuer, &jwt_jwks_url) {
+            (Some(_), None) => {
+                return Err(ConfigError::InvalidValue(
+                    "JWT_JWKS_URL",
+                    "required when JWT_ISSUER is set".to_string(),
+                ))
+            }
+            (None, Some(_)) => {
+                return Err(ConfigError::InvalidValue(
+                    "JWT_ISSUER",
+                    "required when JWT_JWKS_URL is set".to_string(),
+                ))
+            }
+            (Some(_), Some(url)) if !url.starts_with("http://") && !url.starts_with("https://") => {
+                return Err(ConfigError::InvalidValue(
+                    "JWT_JWKS_URL",
+                    format!("expected an http:// or https:// URL, got '{}'", url),
+                ))
+            }
+            _ => {}
+        }
+        let jwt_audience = env::var("JWT_AUDIENCE")
+            .ok()
+            .filter(|v| !v.trim().is_empty());
+        let jwt_required = env_flag("JWT_REQUIRED", false);
+        if jwt_required && jwt_issuer.is_none() {
+            return Err(ConfigError::InvalidValue(
+                "JWT_REQUIRED",
+                "requires JWT_ISSUER and JWT_JWKS_URL".to_string(),
+            ));
+        }
+        let jwt_jwks_refresh_secs = env::var("JWT_JWKS_REFRESH_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .filter(|secs| *secs > 0)
+            .unwrap_or(600);
+        let acl_enforcement = env::var("ACL_ENFORCEMENT").unwrap_or_else(|_| "audit".to_string());
+        AclMode::parse(&acl_enforcement)
+            .map_err(|e| ConfigError::InvalidValue("ACL_ENFORCEMENT", e))?;
+
+        let retention_max_age_hours = match env::var("RETENTION_MAX_AGE_HOURS") {
+            Ok(v) if !v.trim().is_empty() => match v.trim().parse::<u64>() {
+                Ok(hours) if hours > 0 => Some(hours),
+                _ => {
+                    return Err(ConfigError::InvalidValue(
+                        "RETENTION_MAX_AGE_HOURS",
+                        format!("expected a positive number of hours, got '{}'", v),
+                    ))
+                }
+            },
+            _ => None,
+        };
+        let retention_max_entries = match env::var("RETENTION_MAX_ENTRIES") {
+            Ok(v) if !v.trim().is_empty() => Some(v.trim().parse::<usize>().map_err(|_| {
+                ConfigError::InvalidValue(
+                    "RETENTION_MAX_ENTRIES",
+                    format!("expected a number, got '{}'", v),
+                )
+            })?),
+            _ => None,
+        };
+        let retention_hash_queries = env_flag("RETENTION_HASH_QUERIES", false);
+
+        let mut config = Config {
             memvid_file_path,
+            memvid_file_paths,
             grpc_port,
             metrics_port,
             bind_address,
             mock_memvid,
             log_level,
-        })
+            reload_schedule,
+            reload_jitter_secs,
+            reload_retry_initial_secs,
+            reload_retry_max_secs,
+            clock_skew_tolerance_secs,
+            max_response_bytes,
+            shadow_memvid_file_path,
+            shadow_sample_percent,
+            synthetic_frames,
+            synthetic_seed,
+            export_max_bytes_per_sec,
+            export_ack_timeout_secs,
+            llm_prompt_cost_per_mtok,
+            llm_completion_cost_per_mtok,
+            llm_daily_cost_cap_usd,
+            retrieval_pipeline,
+            coverage_window_hours,
+            public_demo,
+            deterministic,
+            deterministic_now,
+            anonymize,
+            rate_limit_per_minute,
+            rate_limit_burst,
+            default_top_k,
+            max_top_k,
+            default_snippet_chars,
+            max_snippet_chars,
+            admin_rpcs,
+            admin_token,
+            index_writes,
+            backup_dir,
+            index_dir,
+            metrics_bind_address,
+            llm_synthesis,
+            llm_provider,
+            llm_base_url,
+            llm_model,
+            llm_api_key,
+            llm_timeout_secs,
+            llm_max_retries,
+            citation_policy,
+            preload_index,
+            mlock_index,
+            preload_max_memory_percent,
+            max_concurrent_searches,
+            max_queued_searches,
+            memvid_read_pool_size,
+            embed_model_path,
+            crash_report_path,
+            crash_report_webhook,
+            alert_webhook,
+            request_log_capacity,
+            debug_token,
+            cursor_secret,
+            visibility_file,
+            authenticated_api_keys,
+            config_source,
+            config_source_token,
+            config_poll_secs,
+            answer_cache,
+            answer_cache_ttl_secs,
+            synonyms_file,
+            ask_aspects,
+            ask_aspect_llm,
+            spell_correction,
+            session_store,
+            session_ttl_secs,
+            session_max_turns,
+            warm_cache_corpus,
+            warm_cache_snapshot,
+            search_cache_ttl_secs,
+            search_cache_capacity,
+            termination_grace_period_secs,
+            drain_delay_secs,
+            lifecycle_endpoint,
+            readiness_policy,
+            warmup_queries,
+            warmup_queries_file,
+            http_proxy,
+            https_proxy,
+            no_proxy,
+            egress_allowlist,
+            ca_bundle_path,
+            frame_stats_path,
+            tls_cert_path,
+            tls_key_path,
+            analytics_report_schedule,
+            analytics_report_format,
+            analytics_report_path,
+            analytics_report_webhook,
+            jwt_issuer,
+            jwt_jwks_url,
+            jwt_audience,
+            jwt_required,
+            jwt_jwks_refresh_secs,
+            acl_enforcement,
+            retention_max_age_hours,
+            retention_max_entries,
+            retention_hash_queries,
+        };
+        if config.public_demo {
+            config.apply_public_demo();
+        }
+        if config.deterministic {
+            config.apply_deterministic();
+        }
+        Ok(config)
+    }
+
+    /// Force the settings required to expose the service publicly.
+    ///
+    /// Contact details are redacted, clients are rate limited (the configured
+    /// limit is kept if it is stricter), the Admin service is not served,
+    /// metrics listen on localhost only, and LLM synthesis is off.
+    pub fn apply_public_demo(&mut self) {
+        self.public_demo = true;
+        self.anonymize = true;
+        if self.rate_limit_per_minute == 0
+            || self.rate_limit_per_minute > DEMO_RATE_LIMIT_PER_MINUTE
+        {
+            self.rate_limit_per_minute = DEMO_RATE_LIMIT_PER_MINUTE;
+        }
+        if let Some(burst) = &mut self.rate_limit_burst {
+            *burst = (*burst).min(DEMO_RATE_LIMIT_PER_MINUTE);
+        }
+        self.admin_rpcs = false;
+        self.index_writes = false;
+        self.metrics_bind_address = "127.0.0.1".to_string();
+        self.llm_synthesis = false;
+    }
+
+    /// Force the settings that make responses reproducible.
+    ///
+    /// LLM synthesis is off, since memvid-core offers no way to pin its
+    /// sampling (temperature or seed); page cursors are signed with a fixed
+    /// key unless `CURSOR_SECRET` is set; and the runtime config store is
+    /// not followed, so request defaults cannot change mid-run. Ranking
+    /// ties, time budgets, timings, the clock and ensemble paraphrases are
+    /// handled by the searcher and services (see `DeterministicSearcher`).
+    pub fn apply_deterministic(&mut self) {
+        self.deterministic = true;
+        self.llm_synthesis = false;
+        self.cursor_secret
+            .get_or_insert_with(|| DETERMINISTIC_CURSOR_SECRET.to_string());
+        self.config_source = None;
+    }
+}
+
+/// Read a boolean flag ("true"/"1" or "false"/"0", case-insensitive).
+fn env_flag(name: &str, default: bool) -> bool {
+    match env::var(name) {
+        Ok(v) if v.eq_ignore_ascii_case("true") || v == "1" => true,
+        Ok(v) if v.eq_ignore_ascii_case("false") || v == "0" => false,
+        _ => default,
     }
 }
 
+/// Read a positive integer, `default` when unset.
+fn env_positive(name: &'static str, default: i32) -> Result<i32, ConfigError> {
+    match env::var(name) {
+        Ok(v) if !v.trim().is_empty() => match v.trim().parse::<i32>() {
+            Ok(value) if value > 0 => Ok(value),
+            _ => Err(ConfigError::InvalidValue(
+                name,
+                format!("expected a positive number, got '{}'", v),
+            )),
+        },
+        _ => Ok(default),
+    }
+}
+
+impl Default for Config {
+    /// Defaults matching `from_env()` with no environment variables set.
+    fn default() -> Self {
+        Self {
+            memvid_file_path: "data/.memvid/resume.mv2".to_string(),
+            memvid_file_paths: Vec::new(),
+            grpc_port: 50051,
+            metrics_port: 9090,
+            bind_address: "auto".to_string(),
+            mock_memvid: false,
+            log_level: "info".to_string(),
+            reload_schedule: None,
+            reload_jitter_secs: 300,
+            reload_retry_initial_secs: 30,
+            reload_retry_max_secs: 900,
+            clock_skew_tolerance_secs: 300,
+            max_response_bytes: 4_000_000,
+            shadow_memvid_file_path: None,
+            shadow_sample_percent: 100,
+            synthetic_frames: 0,
+            synthetic_seed: 42,
+            export_max_bytes_per_sec: 1_048_576,
+            export_ack_timeout_secs: 30,
+            llm_prompt_cost_per_mtok: 0.0,
+            llm_completion_cost_per_mtok: 0.0,
+            llm_daily_cost_cap_usd: 0.0,
+            retrieval_pipeline: None,
+            coverage_window_hours: 168,
+            public_demo: false,
+            deterministic: false,
+            deterministic_now: DEFAULT_DETERMINISTIC_NOW,
+            anonymize: false,
+            rate_limit_per_minute: 0,
+            rate_limit_burst: None,
+            default_top_k: DEFAULT_TOP_K,
+            max_top_k: DEFAULT_MAX_TOP_K,
+            default_snippet_chars: DEFAULT_SNIPPET_CHARS,
+            max_snippet_chars: DEFAULT_MAX_SNIPPET_CHARS,
+            admin_rpcs: true,
+            admin_token: None,
+            index_writes: false,
+            backup_dir: None,
+            index_dir: None,
+            metrics_bind_address: "auto".to_string(),
+            llm_synthesis: true,
+            llm_provider: "openai".to_string(),
+            llm_base_url: None,
+            llm_model: None,
+            llm_api_key: None,
+            llm_timeout_secs: 30,
+            llm_max_retries: DEFAULT_LLM_MAX_RETRIES,
+            citation_policy: "lenient".to_string(),
+            preload_index: false,
+            mlock_index: false,
+            preload_max_memory_percent: 50,
+            max_concurrent_searches: DEFAULT_MAX_CONCURRENT_SEARCHES,
+            max_queued_searches: DEFAULT_MAX_QUEUED_SEARCHES,
+            memvid_read_pool_size: 1,
+            embed_model_path: None,
+            crash_report_path: None,
+            crash_report_webhook: None,
+            alert_webhook: None,
+            request_log_capacity: DEFAULT_REQUEST_LOG_CAPACITY,
+            debug_token: None,
+            cursor_secret: None,
+            visibility_file: None,
+            authenticated_api_keys: Vec::new(),
+            config_source: None,
+            config_source_token: None,
+            config_poll_secs: 10,
+            answer_cache: None,
+            answer_cache_ttl_secs: 300,
+            synonyms_file: None,
+            ask_aspects: DEFAULT_ASK_ASPECTS.map(String::from).to_vec(),
+            ask_aspect_llm: false,
+            spell_correction: true,
+            session_store: "memory".to_string(),
+            session_ttl_secs: DEFAULT_SESSION_TTL.as_secs(),
+            session_max_turns: DEFAULT_SESSION_MAX_TURNS,
+            warm_cache_corpus: None,
+            warm_cache_snapshot: None,
+            search_cache_ttl_secs: DEFAULT_SEARCH_CACHE_TTL.as_secs(),
+            search_cache_capacity: DEFAULT_SEARCH_CACHE_CAPACITY,
+            termination_grace_period_secs: 30,
+            drain_delay_secs: 5,
+            lifecycle_endpoint: false,
+            readiness_policy: String::new(),
+            warmup_queries: None,
+            warmup_queries_file: None,
+            http_proxy: None,
+            https_proxy: None,
+            no_proxy: None,
+            egress_allowlist: None,
+            ca_bundle_path: None,
+            frame_stats_path: None,
+            tls_cert_path: None,
+            tls_key_path: None,
+            analytics_report_schedule: None,
+            analytics_report_format: "markdown".to_string(),
+            analytics_report_path: None,
+            analytics_report_webhook: None,
+            jwt_issuer: None,
+            jwt_jwks_url: None,
+            jwt_audience: None,
+            jwt_required: false,
+            jwt_jwks_refresh_secs: 600,
+            acl_enforcement: "audit".to_string(),
+            retention_max_age_hours: None,
+            retention_max_entries: None,
+            retention_hash_queries: false,
+        }
+    }
+}
+
+/// The directory holding `memvid_file_path`, None without a path.
+fn default_index_dir(memvid_file_path: &str) -> Option<String> {
+    if memvid_file_path.is_empty() {
+        return None;
+    }
+    let dir = std::path::Path::new(memvid_file_path).parent()?;
+    Some(if dir.as_os_str().is_empty() {
+        ".".to_string()
+    } else {
+        dir.display().to_string()
+    })
+}
+
 /// Configuration errors.
 #[derive(Debug, thiserror::Error)]
 pub enum ConfigError {
     #[error("Missing required environment variable: {0}")]
     MissingRequired(&'static str),
+
+    #[error("Invalid value for {0}: {1}")]
+    InvalidValue(&'static str, String),
 }
 
 #[cfg(test)]
@@ -104,4 +1286,60 @@ mod tests {
 
         env::remove_var("MOCK_MEMVID");
     }
+
+    #[test]
+    fn test_default_index_dir() {
+        assert_eq!(
+            default_index_dir("/data/memvid/resume.mv2").as_deref(),
+            Some("/data/memvid")
+        );
+        assert_eq!(default_index_dir("resume.mv2").as_deref(), Some("."));
+        assert_eq!(default_index_dir(""), None);
+    }
+
+    #[test]
+    fn test_public_demo_profile() {
+        let mut config = Config {
+            rate_limit_per_minute: 10,
+            ..Config::default()
+        };
+        config.apply_public_demo();
+
+        assert!(config.anonymize);
+        assert!(!config.admin_rpcs);
+        assert!(!config.index_writes);
+        assert!(!config.llm_synthesis);
+        assert_eq!(config.metrics_bind_address, "127.0.0.1");
+        // A stricter configured limit is kept
+        assert_eq!(config.rate_limit_per_minute, 10);
+
+        let mut config = Config::default();
+        config.apply_public_demo();
+        assert_eq!(config.rate_limit_per_minute, DEMO_RATE_LIMIT_PER_MINUTE);
+    }
+
+    #[test]
+    fn test_deterministic_profile() {
+        let mut config = Config {
+            config_source: Some("consul://consul:8500/memvid".to_string()),
+            ..Config::default()
+        };
+        config.apply_deterministic();
+
+        assert!(config.deterministic);
+        assert!(!config.llm_synthesis);
+        assert!(config.config_source.is_none());
+        assert_eq!(
+            config.cursor_secret.as_deref(),
+            Some(DETERMINISTIC_CURSOR_SECRET)
+        );
+
+        // A configured cursor key is kept
+        let mut config = Config {
+            cursor_secret: Some("secret".to_string()),
+            ..Config::default()
+        };
+        config.apply_deterministic();
+        assert_eq!(config.cursor_secret.as_deref(), Some("secret"));
+    }
 }
@@ -0,0 +1,395 @@
+//! Structured crash reports.
+//!
+//! When a panic or fatal startup error occurs, a JSON report with build
+//! info, the effective configuration (secrets redacted) and the most recent
+//! requests is written to `CRASH_REPORT_PATH` and/or posted to
+//! `CRASH_REPORT_WEBHOOK`, so a post-mortem starts with the context instead
+//! of a bare stack trace.
+//!
+//! Delivery is synchronous and best-effort: it runs inside the panic hook,
+//! and a failure to deliver is logged to stderr without masking the crash.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::PathBuf;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+use crate::config::Config;
+use crate::egress;
+use crate::grpc::{RequestLog, RequestRecord};
+
+/// Config keys whose values are replaced with [`REDACTED`].
+const SECRET_KEY_PARTS: &[&str] = &["webhook", "key", "token", "secret", "password"];
+
+/// Replacement for redacted config values.
+pub const REDACTED: &str = "[redacted]";
+
+/// Connect and I/O timeout for webhook delivery.
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(5);
+
+static REPORTER: OnceLock<CrashReporter> = OnceLock::new();
+
+/// Identity of the running binary.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BuildInfo {
+    /// Package name
+    pub name: &'static str,
+    /// Package version
+    pub version: &'static str,
+    /// Source commit, when `GIT_COMMIT` was set at build time
+    pub git_commit: Option<&'static str>,
+    /// "debug" or "release"
+    pub profile: &'static str,
+    /// Target OS and architecture (e.g., "linux-x86_64")
+    pub target: String,
+}
+
+impl BuildInfo {
+    /// Build info of this binary.
+    pub fn current() -> Self {
+        Self {
+            name: env!("CARGO_PKG_NAME"),
+            version: env!("CARGO_PKG_VERSION"),
+            git_commit: option_env!("GIT_COMMIT"),
+            profile: if cfg!(debug_assertions) {
+                "debug"
+            } else {
+                "release"
+            },
+            target: format!("{}-{}", std::env::consts::OS, std::env::consts::ARCH),
+        }
+    }
+}
+
+/// A crash report.
+#[derive(Debug, Clone, Serialize)]
+pub struct CrashReport {
+    /// "panic" or "fatal"
+    pub kind: &'static str,
+    /// Panic payload or error message
+    pub message: String,
+    /// Source location of a panic
+    pub location: Option<String>,
+    /// Thread that panicked
+    pub thread: Option<String>,
+    /// When the crash occurred
+    pub at: DateTime<Utc>,
+    /// Process ID
+    pub pid: u32,
+    /// Build info
+    pub build: BuildInfo,
+    /// Effective configuration with secrets redacted
+    pub config: serde_json::Value,
+    /// Most recent requests, oldest first
+    pub recent_requests: Vec<RequestRecord>,
+}
+
+/// Builds and delivers crash reports.
+#[derive(Debug)]
+pub struct CrashReporter {
+    path: Option<PathBuf>,
+    webhook: Option<String>,
+    config: serde_json::Value,
+    requests: Arc<RequestLog>,
+}
+
+impl CrashReporter {
+    /// Create a reporter for `config`, attaching requests from `requests`.
+    pub fn new(config: &Config, requests: Arc<RequestLog>) -> Self {
+        Self {
+            path: config.crash_report_path.as_ref().map(PathBuf::from),
+            webhook: config.crash_report_webhook.clone(),
+            config: redacted_config(config),
+            requests,
+        }
+    }
+
+    /// Whether reports have anywhere to go.
+    pub fn is_enabled(&self) -> bool {
+        self.path.is_some() || self.webhook.is_some()
+    }
+
+    /// Install as the process-wide reporter and report panics.
+    ///
+    /// The previous panic hook still runs after the report is delivered.
+    /// Only the first installed reporter takes effect.
+    pub fn install(self) {
+        if REPORTER.set(self).is_err() {
+            return;
+        }
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            if let Some(reporter) = REPORTER.get() {
+                let message = info
+                    .payload()
+                    .downcast_ref::<&str>()
+                    .map(|s| s.to_string())
+                    .or_else(|| info.payload().downcast_ref::<String>().cloned())
+                    .unwrap_or_else(|| "Box<dyn Any>".to_string());
+                let mut report = reporter.report("panic", message);
+                report.location = info.location().map(|l| l.to_string());
+                report.thread = std::thread::current().name().map(str::to_string);
+                reporter.deliver(&report);
+            }
+            previous(info);
+        }));
+    }
+
+    /// Build a report of the given kind.
+    pub fn report(&self, kind: &'static str, message: String) -> CrashReport {
+        CrashReport {
+            kind,
+            message,
+            location: None,
+            thread: None,
+            at: Utc::now(),
+            pid: std::process::id(),
+            build: BuildInfo::current(),
+            config: self.config.clone(),
+            recent_requests: self.requests.recent(),
+        }
+    }
+
+    /// Write the report to the configured path and webhook.
+    pub fn deliver(&self, report: &CrashReport) {
+        let json = serde_json::to_string_pretty(report).unwrap_or_else(|_| "{}".to_string());
+
+        if let Some(dir) = &self.path {
+            let file = dir.join(format!(
+                "crash-{}-{}.json",
+                report.at.format("%Y%m%dT%H%M%S%.3fZ"),
+                report.pid
+            ));
+            match std::fs::create_dir_all(dir).and_then(|()| std::fs::write(&file, &json)) {
+                Ok(()) => eprintln!("Crash report written to {}", file.display()),
+                Err(e) => eprintln!("Failed to write crash report to {}: {}", file.display(), e),
+            }
+        }
+        if let Some(url) = &self.webhook {
+            if let Err(e) = post_json(url, &json) {
+                eprintln!("Failed to post crash report to webhook: {}", e);
+            }
+        }
+    }
+}
+
+/// Report a fatal error through the installed reporter, if any.
+pub fn report_fatal(message: &str) {
+    if let Some(reporter) = REPORTER.get() {
+        reporter.deliver(&reporter.report("fatal", message.to_string()));
+    }
+}
+
+/// Whether a webhook URL can be delivered to (plain `http://` only).
+pub fn validate_webhook(url: &str) -> Result<(), String> {
+    let authority = url
+        .strip_prefix("http://")
+        .ok_or_else(|| format!("expected an http:// URL, got '{}'", url))?
+        .split('/')
+        .next()
+        .unwrap_or_default();
+    if authority.is_empty() {
+        return Err(format!("missing host in '{}'", url));
+    }
+    Ok(())
+}
+
+/// Config as JSON, with values of secret-looking keys and credentials in
+/// URLs redacted.
+fn redacted_config(config: &Config) -> serde_json::Value {
+    let mut value = serde_json::to_value(config).unwrap_or_default();
+    if let Some(fields) = value.as_object_mut() {
+        for (key, field) in fields.iter_mut() {
+            let secret = SECRET_KEY_PARTS.iter().any(|part| key.contains(part));
+            if secret && !field.is_null() {
+                *field = serde_json::Value::String(REDACTED.to_string());
+            } else if let Some(url) = field.as_str().and_then(redacted_url) {
+                *field = serde_json::Value::String(url);
+            }
+        }
+    }
+    value
+}
+
+/// `url` with its userinfo (e.g., `redis://:password@host`) redacted, if it
+/// has any.
+fn redacted_url(url: &str) -> Option<String> {
+    let (scheme, rest) = url.split_once("://")?;
+    let authority = rest.split('/').next().unwrap_or_default();
+    let (_, host) = authority.rsplit_once('@')?;
+    Some(format!(
+        "{}://{}@{}{}",
+        scheme,
+        REDACTED,
+        host,
+        &rest[authority.len()..]
+    ))
+}
+
+/// POST a JSON body over plain HTTP/1.1 and require a 2xx response, through
+/// the installed egress settings.
+pub(crate) fn post_json(url: &str, body: &str) -> std::io::Result<()> {
+    let invalid = |msg: String| std::io::Error::new(std::io::ErrorKind::InvalidInput, msg);
+    validate_webhook(url).map_err(invalid)?;
+
+    let rest = &url["http://".len()..];
+    let (authority, path) = match rest.find('/') {
+        Some(i) => (&rest[..i], &rest[i..]),
+        None => (rest, "/"),
+    };
+    let host = authority.split(':').next().unwrap_or_default();
+    let egress = egress::current();
+    egress.check(host).map_err(invalid)?;
+    // A forwarding proxy takes the absolute URL as the request target
+    let (address, target, proxy_authorization) = match egress.proxy_for(false, host) {
+        Some(proxy) => (
+            proxy.address(),
+            url,
+            egress
+                .proxy_authorization(host)
+                .map(|value| format!("Proxy-Authorization: {}\r\n", value))
+                .unwrap_or_default(),
+        ),
+        None if authority.contains(':') => (authority.to_string(), path, String::new()),
+        None => (format!("{}:80", authority), path, String::new()),
+    };
+    let socket = address
+        .to_socket_addrs()?
+        .next()
+        .ok_or_else(|| invalid(format!("cannot resolve '{}'", authority)))?;
+
+    let mut stream = TcpStream::connect_timeout(&socket, WEBHOOK_TIMEOUT)?;
+    stream.set_read_timeout(Some(WEBHOOK_TIMEOUT))?;
+    stream.set_write_timeout(Some(WEBHOOK_TIMEOUT))?;
+    write!(
+        stream,
+        "POST {} HTTP/1.1\r\nHost: {}\r\n{}Content-Type: application/json\r\n\
+         Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+        target,
+        authority,
+        proxy_authorization,
+        body.len(),
+        body
+    )?;
+
+    let mut status_line = [0u8; 12];
+    stream.read_exact(&mut status_line)?;
+    // "HTTP/1.1 2xx"
+    if status_line.starts_with(b"HTTP/1.") && status_line[9] == b'2' {
+        Ok(())
+    } else {
+        Err(std::io::Error::other(format!(
+            "webhook responded {}",
+            String::from_utf8_lossy(&status_line)
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::BufRead;
+    use std::net::TcpListener;
+
+    fn config() -> Config {
+        Config {
+            crash_report_webhook: Some("http://hooks.internal/T0KEN".to_string()),
+            debug_token: Some("d3bug".to_string()),
+            answer_cache: Some("redis://:hunter2@cache:6379/0".to_string()),
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn test_config_secrets_are_redacted() {
+        let report = CrashReporter::new(&config(), Arc::default()).report("fatal", "boom".into());
+
+        assert_eq!(report.config["crash_report_webhook"], REDACTED);
+        assert_eq!(report.config["debug_token"], REDACTED);
+        assert_eq!(report.config["grpc_port"], 50051);
+        assert!(!report.config.to_string().contains("T0KEN"));
+        assert_eq!(
+            report.config["answer_cache"],
+            format!("redis://{}@cache:6379/0", REDACTED)
+        );
+        assert_eq!(report.build.version, env!("CARGO_PKG_VERSION"));
+    }
+
+    #[test]
+    fn test_report_includes_recent_requests() {
+        let requests = Arc::new(RequestLog::new(2));
+        requests.record(RequestRecord {
+            at: Utc::now(),
+            method: "/memvid.v1.MemvidService/Ask".to_string(),
+            client: "anonymous".to_string(),
+            status: "Ok".to_string(),
+            latency_ms: 12,
+        });
+        let report = CrashReporter::new(&Config::default(), requests).report("panic", "x".into());
+
+        assert_eq!(report.recent_requests.len(), 1);
+        assert_eq!(report.recent_requests[0].latency_ms, 12);
+    }
+
+    #[test]
+    fn test_writes_report_file() {
+        let dir = std::env::temp_dir().join(format!("memvid-crash-{}", std::process::id()));
+        let config = Config {
+            crash_report_path: Some(dir.display().to_string()),
+            ..Config::default()
+        };
+        let reporter = CrashReporter::new(&config, Arc::default());
+        reporter.deliver(&reporter.report("fatal", "failed to bind".into()));
+
+        let file = std::fs::read_dir(&dir).unwrap().next().unwrap().unwrap();
+        let json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(file.path()).unwrap()).unwrap();
+        assert_eq!(json["kind"], "fatal");
+        assert_eq!(json["message"], "failed to bind");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_posts_report_to_webhook() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/crash", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = std::io::BufReader::new(stream);
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            // Read the whole request so closing the socket cannot reset it
+            let mut content_length = 0;
+            loop {
+                let mut header = String::new();
+                reader.read_line(&mut header).unwrap();
+                if header == "\r\n" {
+                    break;
+                }
+                if let Some(len) = header.strip_prefix("Content-Length: ") {
+                    content_length = len.trim().parse().unwrap();
+                }
+            }
+            reader.read_exact(&mut vec![0; content_length]).unwrap();
+            reader
+                .get_mut()
+                .write_all(b"HTTP/1.1 204 No Content\r\n\r\n")
+                .unwrap();
+            request_line
+        });
+
+        post_json(&url, r#"{"kind":"panic"}"#).unwrap();
+        assert_eq!(server.join().unwrap(), "POST /crash HTTP/1.1\r\n");
+    }
+
+    #[test]
+    fn test_webhook_must_be_plain_http() {
+        assert!(validate_webhook("http://relay:8080/crash").is_ok());
+        assert!(validate_webhook("https://hooks.example.com/x").is_err());
+        assert!(validate_webhook("http:///x").is_err());
+    }
+}
@@ -0,0 +1,162 @@
+//! Authenticated debug endpoints on the metrics listener.
+//!
+//! `GET /debug/last-requests` returns the request ring buffer (see
+//! [`RequestLog`]) as JSON, so transient production issues can be inspected
+//! without enabling verbose logging. Callers must send
+//! `Authorization: Bearer <DEBUG_TOKEN>`; without a configured token the
+//! routes are not mounted.
+
+use axum::extract::{Query, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::grpc::{RequestLog, RequestRecord};
+
+#[derive(Clone)]
+struct DebugState {
+    token: Arc<str>,
+    requests: Arc<RequestLog>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LastRequestsQuery {
+    /// Most recent requests to return (default: all retained)
+    limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+struct LastRequestsResponse {
+    /// Recorded requests, most recent first
+    requests: Vec<RequestRecord>,
+}
+
+/// Debug routes guarded by `token`; an empty router when `token` is None.
+pub fn debug_router(token: Option<&str>, requests: Arc<RequestLog>) -> Router {
+    let Some(token) = token else {
+        return Router::new();
+    };
+    Router::new()
+        .route("/debug/last-requests", get(last_requests))
+        .with_state(DebugState {
+            token: Arc::from(token),
+            requests,
+        })
+}
+
+async fn last_requests(
+    State(state): State<DebugState>,
+    headers: HeaderMap,
+    Query(query): Query<LastRequestsQuery>,
+) -> Response {
+    if !authorized(&headers, &state.token) {
+        return (
+            StatusCode::UNAUTHORIZED,
+            [(header::WWW_AUTHENTICATE, "Bearer")],
+        )
+            .into_response();
+    }
+
+    let mut requests = state.requests.recent();
+    requests.reverse();
+    if let Some(limit) = query.limit {
+        requests.truncate(limit);
+    }
+    Json(LastRequestsResponse { requests }).into_response()
+}
+
+/// Whether the request carries the bearer token (compared in constant time).
+fn authorized(headers: &HeaderMap, token: &str) -> bool {
+    let Some(presented) = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+    else {
+        return false;
+    };
+    tokens_match(presented, token)
+}
+
+/// Whether `presented` equals `token`, compared in constant time.
+pub fn tokens_match(presented: &str, token: &str) -> bool {
+    let (a, b) = (presented.as_bytes(), token.as_bytes());
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::Request;
+    use chrono::Utc;
+    use http_body_util::BodyExt;
+    use tower::ServiceExt;
+
+    fn log() -> Arc<RequestLog> {
+        let log = Arc::new(RequestLog::default());
+        for method in ["/a", "/b", "/c"] {
+            log.record(RequestRecord {
+                at: Utc::now(),
+                method: method.to_string(),
+                client: "anonymous".to_string(),
+                status: "Ok".to_string(),
+                latency_ms: 3,
+            });
+        }
+        log
+    }
+
+    fn get(uri: &str, token: Option<&str>) -> Request<Body> {
+        let mut request = Request::builder().uri(uri);
+        if let Some(token) = token {
+            request = request.header("authorization", format!("Bearer {}", token));
+        }
+        request.body(Body::empty()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_last_requests_most_recent_first() {
+        let app = debug_router(Some("s3cret"), log());
+        let response = app
+            .oneshot(get("/debug/last-requests?limit=2", Some("s3cret")))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let methods: Vec<&str> = json["requests"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| r["method"].as_str().unwrap())
+            .collect();
+        assert_eq!(methods, vec!["/c", "/b"]);
+        assert_eq!(json["requests"][0]["latency_ms"], 3);
+    }
+
+    #[tokio::test]
+    async fn test_requires_token() {
+        let app = debug_router(Some("s3cret"), log());
+        for token in [None, Some("wrong"), Some("s3cre")] {
+            let response = app
+                .clone()
+                .oneshot(get("/debug/last-requests", token))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_not_mounted_without_token() {
+        let response = debug_router(None, log())
+            .oneshot(get("/debug/last-requests", Some("")))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
@@ -1,6 +1,13 @@
 //! Error types for the memvid service.
+//!
+//! Statuses converted from a [`ServiceError`] carry its [`reason`] as
+//! google.rpc rich error details (see [`crate::error_details`]).
+//!
+//! [`reason`]: ServiceError::reason
 
-use tonic::Status;
+use tonic::{Code, Status};
+
+use crate::error_details::{rich_status, ErrorInfo, FieldViolation};
 
 /// Service-level errors that can occur during operation.
 #[derive(Debug, thiserror::Error)]
@@ -17,6 +24,24 @@ pub enum ServiceError {
     #[error("Invalid request: {0}")]
     InvalidRequest(String),
 
+    #[error("Invalid request: {message}")]
+    InvalidField { field: String, message: String },
+
+    #[error("Precondition failed: {0}")]
+    FailedPrecondition(String),
+
+    #[error("Unauthenticated: {0}")]
+    Unauthenticated(String),
+
+    #[error("Permission denied: {0}")]
+    PermissionDenied(String),
+
+    #[error("Deadline exceeded: {0}")]
+    DeadlineExceeded(String),
+
+    #[error("Resource exhausted: {0}")]
+    ResourceExhausted(String),
+
     #[error("Service not ready")]
     NotReady,
 
@@ -24,16 +49,61 @@ pub enum ServiceError {
     Internal(String),
 }
 
+impl ServiceError {
+    /// An invalid value of request field `field`, reported to the caller
+    /// as a field violation.
+    pub fn invalid_field(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::InvalidField {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+
+    /// Machine-readable reason reported in the status's ErrorInfo.
+    pub fn reason(&self) -> &'static str {
+        match self {
+            ServiceError::MemvidFileNotFound(_) => "INDEX_NOT_FOUND",
+            ServiceError::MemvidLoadError(_) => "INDEX_LOAD_FAILED",
+            ServiceError::SearchError(_) => "SEARCH_FAILED",
+            ServiceError::InvalidRequest(_) | ServiceError::InvalidField { .. } => {
+                "INVALID_ARGUMENT"
+            }
+            ServiceError::FailedPrecondition(_) => "FAILED_PRECONDITION",
+            ServiceError::Unauthenticated(_) => "UNAUTHENTICATED",
+            ServiceError::PermissionDenied(_) => "PERMISSION_DENIED",
+            ServiceError::DeadlineExceeded(_) => "DEADLINE_EXCEEDED",
+            ServiceError::ResourceExhausted(_) => "RESOURCE_EXHAUSTED",
+            ServiceError::NotReady => "INDEX_NOT_LOADED",
+            ServiceError::Internal(_) => "INTERNAL",
+        }
+    }
+}
+
 impl From<ServiceError> for Status {
     fn from(err: ServiceError) -> Self {
-        match err {
-            ServiceError::MemvidFileNotFound(msg) => Status::not_found(msg),
-            ServiceError::MemvidLoadError(msg) => Status::internal(msg),
-            ServiceError::SearchError(msg) => Status::internal(msg),
-            ServiceError::InvalidRequest(msg) => Status::invalid_argument(msg),
-            ServiceError::NotReady => Status::unavailable("Service not ready"),
-            ServiceError::Internal(msg) => Status::internal(msg),
-        }
+        let info = ErrorInfo::new(err.reason());
+        let (code, msg) = match err {
+            ServiceError::MemvidFileNotFound(msg) => (Code::NotFound, msg),
+            ServiceError::MemvidLoadError(msg) => (Code::Internal, msg),
+            ServiceError::SearchError(msg) => (Code::Internal, msg),
+            ServiceError::InvalidRequest(msg) => (Code::InvalidArgument, msg),
+            ServiceError::InvalidField { field, message } => {
+                let info = info.with("field", &field);
+                let violation = FieldViolation {
+                    field,
+                    description: message.clone(),
+                };
+                return rich_status(Code::InvalidArgument, message, info, vec![violation]);
+            }
+            ServiceError::FailedPrecondition(msg) => (Code::FailedPrecondition, msg),
+            ServiceError::Unauthenticated(msg) => (Code::Unauthenticated, msg),
+            ServiceError::PermissionDenied(msg) => (Code::PermissionDenied, msg),
+            ServiceError::DeadlineExceeded(msg) => (Code::DeadlineExceeded, msg),
+            ServiceError::ResourceExhausted(msg) => (Code::ResourceExhausted, msg),
+            ServiceError::NotReady => (Code::Unavailable, "Service not ready".to_string()),
+            ServiceError::Internal(msg) => (Code::Internal, msg),
+        };
+        rich_status(code, msg, info, Vec::new())
     }
 }
 
@@ -74,6 +144,38 @@ mod tests {
         assert!(status.message().contains("empty query"));
     }
 
+    #[test]
+    fn test_failed_precondition_converts_to_failed_precondition() {
+        let err = ServiceError::FailedPrecondition("nothing staged".into());
+        let status: Status = err.into();
+        assert_eq!(status.code(), Code::FailedPrecondition);
+        assert!(status.message().contains("nothing staged"));
+    }
+
+    #[test]
+    fn test_unauthenticated_converts_to_unauthenticated() {
+        let err = ServiceError::Unauthenticated("admin token required".into());
+        let status: Status = err.into();
+        assert_eq!(status.code(), Code::Unauthenticated);
+        assert!(status.message().contains("admin token required"));
+    }
+
+    #[test]
+    fn test_deadline_exceeded_converts_to_deadline_exceeded() {
+        let err = ServiceError::DeadlineExceeded("no ack".into());
+        let status: Status = err.into();
+        assert_eq!(status.code(), Code::DeadlineExceeded);
+        assert!(status.message().contains("no ack"));
+    }
+
+    #[test]
+    fn test_resource_exhausted_converts_to_resource_exhausted() {
+        let err = ServiceError::ResourceExhausted("queue full".into());
+        let status: Status = err.into();
+        assert_eq!(status.code(), Code::ResourceExhausted);
+        assert!(status.message().contains("queue full"));
+    }
+
     #[test]
     fn test_not_ready_converts_to_unavailable() {
         let err = ServiceError::NotReady;
@@ -82,6 +184,30 @@ mod tests {
         assert!(status.message().contains("not ready"));
     }
 
+    #[test]
+    fn test_statuses_carry_reason_and_field_violations() {
+        use crate::error_details::{status_details, ERROR_DOMAIN};
+
+        let status: Status = ServiceError::NotReady.into();
+        let info = status_details(&status).unwrap().error_info().unwrap();
+        assert_eq!(info.reason, "INDEX_NOT_LOADED");
+        assert_eq!(info.domain, ERROR_DOMAIN);
+
+        let err = ServiceError::invalid_field("max_words", "max_words must not be negative");
+        assert_eq!(
+            err.to_string(),
+            "Invalid request: max_words must not be negative"
+        );
+        let status: Status = err.into();
+        assert_eq!(status.code(), Code::InvalidArgument);
+        assert_eq!(status.message(), "max_words must not be negative");
+        let details = status_details(&status).unwrap();
+        assert_eq!(details.error_info().unwrap().reason, "INVALID_ARGUMENT");
+        let violations = details.bad_request().unwrap().field_violations;
+        assert_eq!(violations[0].field, "max_words");
+        assert_eq!(violations[0].description, "max_words must not be negative");
+    }
+
     #[test]
     fn test_error_display() {
         let err = ServiceError::MemvidFileNotFound("missing.mv2".into());
@@ -0,0 +1,183 @@
+//! google.rpc rich error details on error statuses.
+//!
+//! Statuses built from a [`ServiceError`](crate::error::ServiceError) carry
+//! a `google.rpc.Status` in their `grpc-status-details-bin` trailer, with:
+//!
+//! - an `ErrorInfo` whose `reason` is a stable code (`INDEX_NOT_LOADED`,
+//!   `INVALID_ARGUMENT`, `RATE_LIMITED`, ...) in the [`ERROR_DOMAIN`] domain
+//! - a `BadRequest` naming the offending field, for field validation errors
+//!
+//! Clients branch on the reason instead of parsing messages; the Python
+//! layer reads the details with `grpc_status.rpc_status.from_call`. The
+//! messages below are the wire-compatible subset of `google/rpc/status.proto`
+//! and `google/rpc/error_details.proto` the service sends.
+
+use prost::Message;
+use std::collections::BTreeMap;
+use tonic::{Code, Status};
+
+/// `ErrorInfo.domain` of every error the service reports.
+pub const ERROR_DOMAIN: &str = "memvid.ai-resume";
+
+const ERROR_INFO_TYPE_URL: &str = "type.googleapis.com/google.rpc.ErrorInfo";
+const BAD_REQUEST_TYPE_URL: &str = "type.googleapis.com/google.rpc.BadRequest";
+
+/// `google.rpc.Status`: the status with its details.
+#[derive(Clone, PartialEq, Message)]
+pub struct RpcStatus {
+    #[prost(int32, tag = "1")]
+    pub code: i32,
+    #[prost(string, tag = "2")]
+    pub message: String,
+    #[prost(message, repeated, tag = "3")]
+    pub details: Vec<Any>,
+}
+
+/// `google.protobuf.Any`: a detail message and its type.
+#[derive(Clone, PartialEq, Message)]
+pub struct Any {
+    #[prost(string, tag = "1")]
+    pub type_url: String,
+    #[prost(bytes = "vec", tag = "2")]
+    pub value: Vec<u8>,
+}
+
+/// `google.rpc.ErrorInfo`: why the request failed, machine-readable.
+#[derive(Clone, PartialEq, Message)]
+pub struct ErrorInfo {
+    /// Stable code of the failure, UPPER_SNAKE_CASE
+    #[prost(string, tag = "1")]
+    pub reason: String,
+    /// Service the reason belongs to
+    #[prost(string, tag = "2")]
+    pub domain: String,
+    /// Further facts about the failure
+    #[prost(btree_map = "string, string", tag = "3")]
+    pub metadata: BTreeMap<String, String>,
+}
+
+/// `google.rpc.BadRequest`: the request fields that were invalid.
+#[derive(Clone, PartialEq, Message)]
+pub struct BadRequest {
+    #[prost(message, repeated, tag = "1")]
+    pub field_violations: Vec<FieldViolation>,
+}
+
+/// `google.rpc.BadRequest.FieldViolation`.
+#[derive(Clone, PartialEq, Message)]
+pub struct FieldViolation {
+    /// Path of the field, e.g. `adaptive_options.min_score`
+    #[prost(string, tag = "1")]
+    pub field: String,
+    #[prost(string, tag = "2")]
+    pub description: String,
+}
+
+impl ErrorInfo {
+    /// Error info for `reason` in the service's domain.
+    pub fn new(reason: &str) -> Self {
+        Self {
+            reason: reason.to_string(),
+            domain: ERROR_DOMAIN.to_string(),
+            metadata: BTreeMap::new(),
+        }
+    }
+
+    /// Add a metadata entry.
+    pub fn with(mut self, key: &str, value: impl ToString) -> Self {
+        self.metadata.insert(key.to_string(), value.to_string());
+        self
+    }
+}
+
+impl RpcStatus {
+    /// The ErrorInfo detail, if any.
+    pub fn error_info(&self) -> Option<ErrorInfo> {
+        self.detail(ERROR_INFO_TYPE_URL)
+    }
+
+    /// The BadRequest detail, if any.
+    pub fn bad_request(&self) -> Option<BadRequest> {
+        self.detail(BAD_REQUEST_TYPE_URL)
+    }
+
+    fn detail<M: Message + Default>(&self, type_url: &str) -> Option<M> {
+        self.details
+            .iter()
+            .find(|detail| detail.type_url == type_url)
+            .and_then(|detail| M::decode(detail.value.as_slice()).ok())
+    }
+}
+
+/// A status carrying `info` and, when there are any, the field
+/// `violations` as a BadRequest.
+pub fn rich_status(
+    code: Code,
+    message: impl Into<String>,
+    info: ErrorInfo,
+    violations: Vec<FieldViolation>,
+) -> Status {
+    let message = message.into();
+    let mut details = vec![Any {
+        type_url: ERROR_INFO_TYPE_URL.to_string(),
+        value: info.encode_to_vec(),
+    }];
+    if !violations.is_empty() {
+        details.push(Any {
+            type_url: BAD_REQUEST_TYPE_URL.to_string(),
+            value: BadRequest {
+                field_violations: violations,
+            }
+            .encode_to_vec(),
+        });
+    }
+    let status = RpcStatus {
+        code: code as i32,
+        message: message.clone(),
+        details,
+    };
+    Status::with_details(code, message, status.encode_to_vec().into())
+}
+
+/// The details of `status`, when it carries any.
+pub fn status_details(status: &Status) -> Option<RpcStatus> {
+    if status.details().is_empty() {
+        return None;
+    }
+    RpcStatus::decode(status.details()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rich_status_round_trips() {
+        let status = rich_status(
+            Code::InvalidArgument,
+            "max_words must not be negative",
+            ErrorInfo::new("INVALID_ARGUMENT").with("field", "max_words"),
+            vec![FieldViolation {
+                field: "max_words".to_string(),
+                description: "max_words must not be negative".to_string(),
+            }],
+        );
+        assert_eq!(status.code(), Code::InvalidArgument);
+        assert_eq!(status.message(), "max_words must not be negative");
+
+        let details = status_details(&status).unwrap();
+        assert_eq!(details.code, Code::InvalidArgument as i32);
+        let info = details.error_info().unwrap();
+        assert_eq!(info.reason, "INVALID_ARGUMENT");
+        assert_eq!(info.domain, ERROR_DOMAIN);
+        assert_eq!(info.metadata["field"], "max_words");
+        assert_eq!(
+            details.bad_request().unwrap().field_violations[0].field,
+            "max_words"
+        );
+
+        let status = rich_status(Code::Internal, "boom", ErrorInfo::new("INTERNAL"), vec![]);
+        assert!(status_details(&status).unwrap().bad_request().is_none());
+        assert!(status_details(&Status::internal("plain")).is_none());
+    }
+}
@@ -0,0 +1,140 @@
+//! ACL context of query requests.
+//!
+//! [`AclInterceptor`] builds the [`AclContext`] memvid-core matches frame
+//! ACLs against from the request metadata: roles, groups and tenant from the
+//! `x-acl-*` headers, and the subject of a validated bearer token. Handlers
+//! pass it on with every search and ask, so with `ACL_ENFORCEMENT=enforce`
+//! restricted frames are dropped for callers without a matching role.
+//!
+//! The headers are taken at face value: they must be set by a trusted hop
+//! (e.g. the API gateway), which strips any sent by external callers.
+
+use tonic::service::Interceptor;
+use tonic::{Request, Status};
+
+use crate::error::ServiceError;
+use crate::memvid::{AclContext, AclMode};
+
+use super::jwt::CallerClaims;
+
+/// Metadata key listing the caller's roles (comma-separated).
+pub const ROLES_HEADER: &str = "x-acl-roles";
+
+/// Metadata key listing the caller's groups (comma-separated).
+pub const GROUPS_HEADER: &str = "x-acl-groups";
+
+/// Metadata key naming the caller's tenant.
+pub const TENANT_HEADER: &str = "x-acl-tenant";
+
+/// Tonic interceptor attaching an [`AclContext`] to each request.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AclInterceptor {
+    mode: AclMode,
+}
+
+impl AclInterceptor {
+    /// Attach contexts applied in `mode`.
+    pub fn new(mode: AclMode) -> Self {
+        Self { mode }
+    }
+}
+
+impl Interceptor for AclInterceptor {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        let header = |key: &str| {
+            request
+                .metadata()
+                .get(key)
+                .and_then(|v| v.to_str().ok())
+                .unwrap_or_default()
+        };
+        let tenant = header(TENANT_HEADER).trim();
+        let context = AclContext {
+            tenant_id: (!tenant.is_empty()).then(|| tenant.to_string()),
+            subject_id: request
+                .extensions()
+                .get::<CallerClaims>()
+                .map(|claims| claims.subject.clone()),
+            roles: list(header(ROLES_HEADER)),
+            group_ids: list(header(GROUPS_HEADER)),
+            mode: self.mode,
+        };
+        request.extensions_mut().insert(context);
+        Ok(request)
+    }
+}
+
+/// Sorted, deduplicated entries of a comma-separated list.
+fn list(value: &str) -> Vec<String> {
+    let mut entries: Vec<String> = value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(String::from)
+        .collect();
+    entries.sort();
+    entries.dedup();
+    entries
+}
+
+/// ACL context of the caller, when the interceptor attached one.
+pub fn caller_acl<T>(request: &Request<T>) -> Option<AclContext> {
+    request.extensions().get::<AclContext>().cloned()
+}
+
+/// Refuse `rpc` when frame ACLs are enforced: it serves frame content
+/// without memvid-core matching it against the caller's context.
+pub fn refuse_when_enforced<T>(request: &Request<T>, rpc: &str) -> Result<(), ServiceError> {
+    match caller_acl(request) {
+        Some(context) if context.mode == AclMode::Enforce => Err(ServiceError::PermissionDenied(
+            format!("{} is unavailable with ACL_ENFORCEMENT=enforce", rpc),
+        )),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builds_context_from_metadata_and_claims() {
+        let mut request = Request::new(());
+        let metadata = request.metadata_mut();
+        metadata.insert(ROLES_HEADER, "recruiter, admin,,recruiter".parse().unwrap());
+        metadata.insert(TENANT_HEADER, " acme ".parse().unwrap());
+        request.extensions_mut().insert(CallerClaims {
+            subject: "user-1".to_string(),
+            issuer: "https://auth.example.com/".to_string(),
+            expires_at: 0,
+        });
+
+        let request = AclInterceptor::new(AclMode::Enforce).call(request).unwrap();
+        let context = caller_acl(&request).unwrap();
+        assert_eq!(context.roles, ["admin", "recruiter"]);
+        assert!(context.group_ids.is_empty());
+        assert_eq!(context.tenant_id.as_deref(), Some("acme"));
+        assert_eq!(context.subject_id.as_deref(), Some("user-1"));
+        assert_eq!(context.mode, AclMode::Enforce);
+    }
+
+    #[test]
+    fn test_refuse_when_enforced() {
+        let enforced = AclInterceptor::new(AclMode::Enforce)
+            .call(Request::new(()))
+            .unwrap();
+        let err = refuse_when_enforced(&enforced, "ExportFrames").unwrap_err();
+        assert!(matches!(err, ServiceError::PermissionDenied(_)));
+
+        let audited = AclInterceptor::default().call(Request::new(())).unwrap();
+        assert!(refuse_when_enforced(&audited, "ExportFrames").is_ok());
+        assert!(refuse_when_enforced(&Request::new(()), "ExportFrames").is_ok());
+    }
+
+    #[test]
+    fn test_anonymous_caller_gets_empty_context() {
+        let request = AclInterceptor::default().call(Request::new(())).unwrap();
+        assert_eq!(caller_acl(&request), Some(AclContext::default()));
+        assert_eq!(caller_acl(&Request::new(())), None);
+    }
+}
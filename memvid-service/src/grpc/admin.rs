@@ -0,0 +1,1686 @@
+//! gRPC implementation of the Admin service.
+
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{Request, Response, Status};
+use tracing::{info, warn};
+
+use super::coverage::CoverageTracker;
+use super::profile::{PROFILE_ENTITY, PROFILE_SLOT};
+use super::query_stats::QueryStats;
+use super::request_log::RequestLog;
+use super::service::caller_audience;
+use super::warm::CacheWarmer;
+use crate::capabilities::CapabilityReport;
+use crate::error::ServiceError;
+use crate::generated::memvid::v1::{
+    admin_server::Admin, AppendFramesRequest, AppendFramesResponse, ConfirmIndexRequest,
+    CoverageReport, CutoverStatusResponse, DarkFrame, DeleteFramesRequest, DeleteFramesResponse,
+    DuplicateCluster, ExportIndexChunk, ExportIndexRequest, FrameServeCount, GetAnalyticsRequest,
+    GetAnalyticsResponse, GetCapabilitiesRequest, GetCapabilitiesResponse,
+    GetDuplicateReportRequest, GetDuplicateReportResponse, GetIndexStatsRequest,
+    GetIndexStatsResponse, GetLockDiagnosticsRequest, GetLockDiagnosticsResponse,
+    PromoteIndexRequest, PurgeDataRequest, PurgeDataResponse, RollbackIndexRequest,
+    SetFrameVisibilityRequest, SetFrameVisibilityResponse, SetLogLevelRequest, SetLogLevelResponse,
+    SetStateRequest, SetStateResponse, SnapshotIndexRequest, SnapshotIndexResponse,
+    StageIndexRequest, ValidateIndexRequest, ValidateIndexResponse, WarmCacheRequest,
+    WarmCacheResponse,
+};
+use crate::generated::memvid::v1::{
+    CheckStatus as ProtoCheckStatus, DataStore, FrameVisibility, FrameVisibilityOverride,
+    ValidationCheck as ProtoValidationCheck,
+};
+use crate::ingest::{
+    append_frames, delete_frames, slug, write_memory_cards, IngestCard, IngestFrame,
+};
+use crate::log_level::LogLevelControl;
+use crate::memvid::{
+    scan_duplicates, snapshot_index, validate_index, BackupManifest, CheckStatus, CutoverStatus,
+    IndexDump, ReloadableSearcher, Searcher, ValidationReport, Visibility, VisibilityStore,
+    DEFAULT_VALIDATION_SAMPLES, MAX_VALIDATION_SAMPLES,
+};
+
+/// Similarity threshold used when a duplicate report request leaves it unset.
+const DEFAULT_DUPLICATE_SIMILARITY: f32 = 0.8;
+
+/// Most frames one AppendFrames request may add.
+pub const MAX_APPEND_FRAMES: usize = 100;
+
+/// Most frame IDs and URIs one DeleteFrames request may list.
+pub const MAX_DELETE_FRAMES: usize = 100;
+
+/// Frames listed per export call when resolving DeleteFrames targets.
+const EXPORT_BATCH: usize = 1_000;
+
+/// ExportIndex chunks buffered ahead of a slow client.
+const EXPORT_STREAM_BUFFER: usize = 4;
+
+/// gRPC implementation of the Admin service.
+pub struct AdminService {
+    report: Arc<CapabilityReport>,
+    searcher: Arc<dyn Searcher>,
+    reloadable: Option<Arc<ReloadableSearcher>>,
+    coverage: Arc<CoverageTracker>,
+    visibility: Arc<VisibilityStore>,
+    request_log: Option<Arc<RequestLog>>,
+    query_stats: Option<Arc<QueryStats>>,
+    log_level: Option<Arc<LogLevelControl>>,
+    warmer: Option<Arc<CacheWarmer>>,
+    index_writes: bool,
+    backup_dir: Option<PathBuf>,
+    index_dir: Option<PathBuf>,
+    write_lock: tokio::sync::Mutex<()>,
+}
+
+impl AdminService {
+    /// Create a new AdminService serving the given capability report and
+    /// reporting stats for the given searcher.
+    pub fn new(report: Arc<CapabilityReport>, searcher: Arc<dyn Searcher>) -> Self {
+        Self {
+            report,
+            searcher,
+            reloadable: None,
+            coverage: Arc::new(CoverageTracker::default()),
+            visibility: Arc::new(VisibilityStore::new()),
+            request_log: None,
+            query_stats: None,
+            log_level: None,
+            warmer: None,
+            index_writes: false,
+            backup_dir: None,
+            index_dir: None,
+            write_lock: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    /// Enable blue/green cutover RPCs against the given reloadable searcher.
+    pub fn with_reloadable(mut self, reloadable: Arc<ReloadableSearcher>) -> Self {
+        self.reloadable = Some(reloadable);
+        self
+    }
+
+    /// Report coverage from the tracker the query services record into.
+    pub fn with_coverage_tracker(mut self, tracker: Arc<CoverageTracker>) -> Self {
+        self.coverage = tracker;
+        self
+    }
+
+    /// Update frame visibility in the store the query services filter by.
+    pub fn with_visibility_store(mut self, store: Arc<VisibilityStore>) -> Self {
+        self.visibility = store;
+        self
+    }
+
+    /// Purge the request log the gRPC server records into.
+    pub fn with_request_log(mut self, log: Arc<RequestLog>) -> Self {
+        self.request_log = Some(log);
+        self
+    }
+
+    /// Purge the query statistics the query services record into.
+    pub fn with_query_stats(mut self, stats: Arc<QueryStats>) -> Self {
+        self.query_stats = Some(stats);
+        self
+    }
+
+    /// Enable SetLogLevel against the installed log filter.
+    pub fn with_log_level(mut self, control: Arc<LogLevelControl>) -> Self {
+        self.log_level = Some(control);
+        self
+    }
+
+    /// Enable WarmCache against the answer cache `warmer` fills.
+    pub fn with_cache_warmer(mut self, warmer: Arc<CacheWarmer>) -> Self {
+        self.warmer = Some(warmer);
+        self
+    }
+
+    /// Let AppendFrames, SetState and DeleteFrames write to the serving
+    /// index file.
+    pub fn with_index_writes(mut self, enabled: bool) -> Self {
+        self.index_writes = enabled;
+        self
+    }
+
+    /// Enable SnapshotIndex, copying the serving index file into `dir`.
+    pub fn with_backup_dir(mut self, dir: Option<PathBuf>) -> Self {
+        self.backup_dir = dir;
+        self
+    }
+
+    /// Let StageIndex load index files within `dir` (and the backup
+    /// directory); without either, staging is refused.
+    pub fn with_index_dir(mut self, dir: Option<PathBuf>) -> Self {
+        self.index_dir = dir;
+        self
+    }
+
+    /// `file` resolved to its canonical path, which must lie within the
+    /// index or backup directory, so StageIndex cannot load arbitrary files.
+    fn stageable_path(&self, file: &str) -> Result<PathBuf, ServiceError> {
+        let roots: Vec<&PathBuf> = self.index_dir.iter().chain(&self.backup_dir).collect();
+        if roots.is_empty() {
+            return Err(ServiceError::FailedPrecondition(
+                "staging an index needs INDEX_DIR".to_string(),
+            ));
+        }
+        let path = std::fs::canonicalize(file).map_err(|e| {
+            ServiceError::invalid_field("memvid_file", format!("cannot resolve {}: {}", file, e))
+        })?;
+        let allowed = roots
+            .iter()
+            .filter_map(|root| std::fs::canonicalize(root).ok())
+            .any(|root| path.starts_with(root));
+        if !allowed {
+            return Err(ServiceError::invalid_field(
+                "memvid_file",
+                format!("{} is outside INDEX_DIR and BACKUP_DIR", file),
+            ));
+        }
+        Ok(path)
+    }
+
+    /// The searcher whose index file RPCs may write to.
+    fn writable(&self) -> Result<&ReloadableSearcher, ServiceError> {
+        if !self.index_writes {
+            return Err(ServiceError::FailedPrecondition(
+                "the index is served read-only (INDEX_WRITES=false)".to_string(),
+            ));
+        }
+        self.reloadable()
+    }
+
+    fn reloadable(&self) -> Result<&ReloadableSearcher, ServiceError> {
+        self.reloadable.as_deref().ok_or_else(|| {
+            ServiceError::FailedPrecondition(
+                "index cutover requires the real searcher (MOCK_MEMVID=false)".to_string(),
+            )
+        })
+    }
+}
+
+/// ID and URI of every frame of `searcher`'s index.
+async fn list_frames(searcher: &dyn Searcher) -> Result<Vec<(u64, String)>, ServiceError> {
+    let mut frames: Vec<(u64, String)> = Vec::new();
+    loop {
+        let after = frames.last().map(|(frame_id, _)| *frame_id);
+        let batch = searcher.export_frames(after, EXPORT_BATCH).await?;
+        if batch.is_empty() {
+            return Ok(frames);
+        }
+        frames.extend(batch.into_iter().map(|frame| (frame.frame_id, frame.uri)));
+    }
+}
+
+fn cutover_response(status: CutoverStatus) -> Response<CutoverStatusResponse> {
+    Response::new(CutoverStatusResponse {
+        active_file: status.active,
+        staged_file: status.staged.unwrap_or_default(),
+        previous_file: status.previous.unwrap_or_default(),
+        generation: status.generation,
+    })
+}
+
+impl From<FrameVisibility> for Visibility {
+    fn from(visibility: FrameVisibility) -> Self {
+        match visibility {
+            FrameVisibility::Public => Visibility::Public,
+            FrameVisibility::Authenticated => Visibility::Authenticated,
+            FrameVisibility::Hidden => Visibility::Hidden,
+        }
+    }
+}
+
+impl From<Visibility> for FrameVisibility {
+    fn from(visibility: Visibility) -> Self {
+        match visibility {
+            Visibility::Public => FrameVisibility::Public,
+            Visibility::Authenticated => FrameVisibility::Authenticated,
+            Visibility::Hidden => FrameVisibility::Hidden,
+        }
+    }
+}
+
+impl From<CheckStatus> for ProtoCheckStatus {
+    fn from(status: CheckStatus) -> Self {
+        match status {
+            CheckStatus::Pass => ProtoCheckStatus::Pass,
+            CheckStatus::Warn => ProtoCheckStatus::Warn,
+            CheckStatus::Fail => ProtoCheckStatus::Fail,
+            CheckStatus::Skipped => ProtoCheckStatus::Skipped,
+        }
+    }
+}
+
+impl From<ValidationReport> for ValidateIndexResponse {
+    fn from(report: ValidationReport) -> Self {
+        Self {
+            passed: report.passed(),
+            memvid_file: report.memvid_file,
+            sha256: report.sha256.unwrap_or_default(),
+            frames_sampled: report.frames_sampled as i32,
+            checks: report
+                .checks
+                .into_iter()
+                .map(|check| ProtoValidationCheck {
+                    name: check.name.to_string(),
+                    status: ProtoCheckStatus::from(check.status) as i32,
+                    detail: check.detail,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<BackupManifest> for SnapshotIndexResponse {
+    fn from(manifest: BackupManifest) -> Self {
+        Self {
+            manifest_path: manifest.manifest_path().display().to_string(),
+            backup_id: manifest.backup_id,
+            source: manifest.source,
+            path: manifest.path,
+            sha256: manifest.sha256,
+            size_bytes: manifest.size_bytes as i64,
+            frame_count: manifest.frame_count,
+            generation: manifest.generation,
+            created_at: manifest.created_at,
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl Admin for AdminService {
+    type ExportIndexStream = ReceiverStream<Result<ExportIndexChunk, Status>>;
+
+    async fn get_capabilities(
+        &self,
+        _request: Request<GetCapabilitiesRequest>,
+    ) -> Result<Response<GetCapabilitiesResponse>, Status> {
+        info!("Processing get_capabilities request");
+
+        Ok(Response::new(GetCapabilitiesResponse {
+            report_json: self.report.to_json(),
+        }))
+    }
+
+    async fn get_index_stats(
+        &self,
+        _request: Request<GetIndexStatsRequest>,
+    ) -> Result<Response<GetIndexStatsResponse>, Status> {
+        info!("Processing get_index_stats request");
+
+        Ok(Response::new(GetIndexStatsResponse {
+            memvid_file: self.searcher.memvid_file(),
+            frame_count: self.searcher.frame_count(),
+            section_frame_counts: self.searcher.section_counts().into_iter().collect(),
+        }))
+    }
+
+    async fn get_lock_diagnostics(
+        &self,
+        _request: Request<GetLockDiagnosticsRequest>,
+    ) -> Result<Response<GetLockDiagnosticsResponse>, Status> {
+        info!("Processing get_lock_diagnostics request");
+
+        let diag = self.searcher.lock_diagnostics();
+        Ok(Response::new(GetLockDiagnosticsResponse {
+            read_acquisitions: diag.read_acquisitions,
+            read_wait_us_total: diag.read_wait_us_total,
+            read_wait_us_max: diag.read_wait_us_max,
+            write_acquisitions: diag.write_acquisitions,
+            write_wait_us_total: diag.write_wait_us_total,
+            write_wait_us_max: diag.write_wait_us_max,
+            write_hold_us_max: diag.write_hold_us_max,
+            blocking_tasks: diag.blocking_tasks,
+            blocking_queue_us_total: diag.blocking_queue_us_total,
+            blocking_queue_us_max: diag.blocking_queue_us_max,
+        }))
+    }
+
+    async fn get_duplicate_report(
+        &self,
+        request: Request<GetDuplicateReportRequest>,
+    ) -> Result<Response<GetDuplicateReportResponse>, Status> {
+        let req = request.into_inner();
+        info!(
+            min_similarity = req.min_similarity,
+            "Processing get_duplicate_report request"
+        );
+
+        let min_similarity = if req.min_similarity == 0.0 {
+            DEFAULT_DUPLICATE_SIMILARITY
+        } else {
+            req.min_similarity
+        };
+        if !(min_similarity > 0.0 && min_similarity <= 1.0) {
+            return Err(ServiceError::InvalidRequest(format!(
+                "min_similarity must be in (0, 1], got {}",
+                req.min_similarity
+            ))
+            .into());
+        }
+
+        let report = scan_duplicates(self.searcher.as_ref(), min_similarity).await?;
+        let limit = if req.max_clusters > 0 {
+            req.max_clusters as usize
+        } else {
+            usize::MAX
+        };
+        Ok(Response::new(GetDuplicateReportResponse {
+            frames_scanned: report.frames_scanned as i32,
+            truncated: report.truncated,
+            clusters: report
+                .clusters
+                .into_iter()
+                .take(limit)
+                .map(|cluster| DuplicateCluster {
+                    frame_ids: cluster.frame_ids,
+                    titles: cluster.titles,
+                    similarity: cluster.similarity,
+                })
+                .collect(),
+        }))
+    }
+
+    async fn get_analytics(
+        &self,
+        request: Request<GetAnalyticsRequest>,
+    ) -> Result<Response<GetAnalyticsResponse>, Status> {
+        let req = request.into_inner();
+        info!("Processing get_analytics request");
+
+        let report = self.coverage.report(self.searcher.as_ref()).await?;
+        let limit = |max: i32| if max > 0 { max as usize } else { usize::MAX };
+        Ok(Response::new(GetAnalyticsResponse {
+            coverage: Some(CoverageReport {
+                window_secs: report.window.as_secs() as i64,
+                tracking_since: report.tracking_since.to_rfc3339(),
+                frames_total: report.frames_total as i32,
+                frames_surfaced: report.frames_surfaced as i32,
+                dark_frames: report
+                    .dark_frames
+                    .into_iter()
+                    .take(limit(req.max_dark_frames))
+                    .map(|frame| DarkFrame {
+                        frame_id: frame.frame_id,
+                        title: frame.title,
+                        tags: frame.tags,
+                    })
+                    .collect(),
+            }),
+            top_frames: report
+                .top_frames
+                .into_iter()
+                .take(limit(req.max_top_frames))
+                .map(|(frame, serve_count)| FrameServeCount {
+                    frame_id: frame.frame_id,
+                    title: frame.title,
+                    tags: frame.tags,
+                    serve_count,
+                })
+                .collect(),
+        }))
+    }
+
+    async fn stage_index(
+        &self,
+        request: Request<StageIndexRequest>,
+    ) -> Result<Response<CutoverStatusResponse>, Status> {
+        let req = request.into_inner();
+        info!(memvid_file = %req.memvid_file, "Processing stage_index request");
+
+        if req.memvid_file.is_empty() {
+            return Err(
+                ServiceError::invalid_field("memvid_file", "memvid_file is required").into(),
+            );
+        }
+        let path = self.stageable_path(&req.memvid_file)?;
+        let status = self.reloadable()?.stage(path.display().to_string()).await?;
+        Ok(cutover_response(status))
+    }
+
+    async fn promote_index(
+        &self,
+        _request: Request<PromoteIndexRequest>,
+    ) -> Result<Response<CutoverStatusResponse>, Status> {
+        info!("Processing promote_index request");
+        Ok(cutover_response(self.reloadable()?.promote().await?))
+    }
+
+    async fn rollback_index(
+        &self,
+        _request: Request<RollbackIndexRequest>,
+    ) -> Result<Response<CutoverStatusResponse>, Status> {
+        info!("Processing rollback_index request");
+        Ok(cutover_response(self.reloadable()?.rollback().await?))
+    }
+
+    async fn confirm_index(
+        &self,
+        _request: Request<ConfirmIndexRequest>,
+    ) -> Result<Response<CutoverStatusResponse>, Status> {
+        info!("Processing confirm_index request");
+        Ok(cutover_response(self.reloadable()?.confirm().await?))
+    }
+
+    async fn set_frame_visibility(
+        &self,
+        request: Request<SetFrameVisibilityRequest>,
+    ) -> Result<Response<SetFrameVisibilityResponse>, Status> {
+        let req = request.into_inner();
+        info!(
+            frames = req.frame_ids.len(),
+            visibility = req.visibility,
+            "Processing set_frame_visibility request"
+        );
+
+        if req.frame_ids.is_empty() {
+            return Err(ServiceError::invalid_field("frame_ids", "frame_ids is required").into());
+        }
+        let visibility = FrameVisibility::try_from(req.visibility).map_err(|_| {
+            ServiceError::InvalidRequest(format!("Unknown visibility: {}", req.visibility))
+        })?;
+        self.visibility.set(&req.frame_ids, visibility.into())?;
+
+        Ok(Response::new(SetFrameVisibilityResponse {
+            overrides: self
+                .visibility
+                .overrides()
+                .into_iter()
+                .map(|(frame_id, visibility)| FrameVisibilityOverride {
+                    frame_id,
+                    visibility: FrameVisibility::from(visibility) as i32,
+                })
+                .collect(),
+        }))
+    }
+
+    async fn purge_data(
+        &self,
+        request: Request<PurgeDataRequest>,
+    ) -> Result<Response<PurgeDataResponse>, Status> {
+        let req = request.into_inner();
+        let mut stores = Vec::with_capacity(req.stores.len());
+        for store in &req.stores {
+            let store = DataStore::try_from(*store)
+                .map_err(|_| ServiceError::InvalidRequest(format!("Unknown store: {}", store)))?;
+            stores.push(store);
+        }
+        let selected = |store: DataStore| {
+            stores.is_empty() || stores.contains(&DataStore::All) || stores.contains(&store)
+        };
+
+        let mut response = PurgeDataResponse::default();
+        if selected(DataStore::RequestLog) {
+            if let Some(log) = &self.request_log {
+                response.request_log_entries = log.purge() as u64;
+            }
+        }
+        if selected(DataStore::QueryStats) {
+            if let Some(stats) = &self.query_stats {
+                response.query_stats_queries = stats.purge();
+            }
+        }
+        if selected(DataStore::FrameServeCounts) {
+            response.frame_serve_counts = self.coverage.purge()? as u64;
+        }
+
+        info!(
+            request_log_entries = response.request_log_entries,
+            query_stats_queries = response.query_stats_queries,
+            frame_serve_counts = response.frame_serve_counts,
+            "Purged retained data"
+        );
+        Ok(Response::new(response))
+    }
+
+    async fn set_log_level(
+        &self,
+        request: Request<SetLogLevelRequest>,
+    ) -> Result<Response<SetLogLevelResponse>, Status> {
+        let req = request.into_inner();
+        info!(
+            level = %req.level,
+            duration_secs = req.duration_secs,
+            "Processing set_log_level request"
+        );
+
+        let control = self.log_level.as_ref().ok_or_else(|| {
+            ServiceError::FailedPrecondition("log level control is not installed".to_string())
+        })?;
+        let applied = control.set(&req.level, Duration::from_secs(req.duration_secs.into()))?;
+        Ok(Response::new(match applied {
+            Some(applied) => SetLogLevelResponse {
+                level: applied.filter,
+                default_level: control.default_filter().to_string(),
+                reverts_at: applied.reverts_at,
+            },
+            None => SetLogLevelResponse {
+                level: control.default_filter().to_string(),
+                default_level: control.default_filter().to_string(),
+                reverts_at: 0,
+            },
+        }))
+    }
+
+    async fn warm_cache(
+        &self,
+        request: Request<WarmCacheRequest>,
+    ) -> Result<Response<WarmCacheResponse>, Status> {
+        let req = request.into_inner();
+        info!(
+            questions = req.questions.len(),
+            use_llm = req.use_llm,
+            "Processing warm_cache request"
+        );
+
+        let warmer = self.warmer.as_ref().ok_or_else(|| {
+            ServiceError::FailedPrecondition(
+                "warming needs the answer cache (ANSWER_CACHE)".to_string(),
+            )
+        })?;
+        let report = warmer.warm(&req.questions, req.use_llm).await?;
+        Ok(Response::new(WarmCacheResponse {
+            questions: report.questions as i32,
+            cached: report.cached as i32,
+            failed: report.failed as i32,
+            snapshot_path: report
+                .snapshot_path
+                .map(|path| path.display().to_string())
+                .unwrap_or_default(),
+        }))
+    }
+
+    async fn validate_index(
+        &self,
+        request: Request<ValidateIndexRequest>,
+    ) -> Result<Response<ValidateIndexResponse>, Status> {
+        let req = request.into_inner();
+        info!(
+            sample_frames = req.sample_frames,
+            "Processing validate_index request"
+        );
+
+        let samples = match req.sample_frames {
+            0 => DEFAULT_VALIDATION_SAMPLES,
+            n if n > 0 && n as usize <= MAX_VALIDATION_SAMPLES => n as usize,
+            n => {
+                return Err(ServiceError::invalid_field(
+                    "sample_frames",
+                    format!(
+                        "sample_frames must be in [0, {}], got {}",
+                        MAX_VALIDATION_SAMPLES, n
+                    ),
+                )
+                .into())
+            }
+        };
+        let expected = Some(req.expected_sha256.as_str()).filter(|s| !s.is_empty());
+        let report = validate_index(self.searcher.as_ref(), samples, expected).await?;
+        if !report.passed() {
+            warn!(
+                memvid_file = %report.memvid_file,
+                "Index validation failed"
+            );
+        }
+        Ok(Response::new(report.into()))
+    }
+
+    async fn append_frames(
+        &self,
+        request: Request<AppendFramesRequest>,
+    ) -> Result<Response<AppendFramesResponse>, Status> {
+        let req = request.into_inner();
+        info!(
+            frames = req.frames.len(),
+            "Processing append_frames request"
+        );
+
+        let reloadable = self.writable()?;
+        if req.frames.is_empty() || req.frames.len() > MAX_APPEND_FRAMES {
+            return Err(ServiceError::InvalidRequest(format!(
+                "frames must list 1 to {} frames, got {}",
+                MAX_APPEND_FRAMES,
+                req.frames.len()
+            ))
+            .into());
+        }
+        let mut frames = Vec::with_capacity(req.frames.len());
+        for (index, frame) in req.frames.into_iter().enumerate() {
+            let title = frame.title.trim().to_string();
+            if title.is_empty() || frame.text.trim().is_empty() {
+                return Err(ServiceError::InvalidRequest(format!(
+                    "frames[{}] needs a title and a text",
+                    index
+                ))
+                .into());
+            }
+            let section = match slug(&frame.section) {
+                section if section.is_empty() => slug(&title),
+                section => section,
+            };
+            let mut new_frame = IngestFrame::new(title, &section, frame.text, frame.tags);
+            new_frame.metadata = frame.metadata.into_iter().collect();
+            frames.push(new_frame);
+        }
+        let version = Some(req.source_version).filter(|v| !v.trim().is_empty());
+
+        // One writer at a time; the reload serves the frames and starts a new
+        // generation, which drops cached answers
+        let _guard = self.write_lock.lock().await;
+        let path = reloadable.cutover_status().active;
+        let frame_ids = tokio::task::spawn_blocking({
+            let path = path.clone();
+            move || append_frames(Path::new(&path), &frames, version.as_deref())
+        })
+        .await
+        .map_err(|e| ServiceError::Internal(format!("Append task error: {}", e)))??;
+        reloadable.reload().await?;
+
+        info!(
+            memvid_file = %path,
+            frames = frame_ids.len(),
+            generation = reloadable.generation(),
+            "Appended frames to the index"
+        );
+        Ok(Response::new(AppendFramesResponse {
+            frame_ids,
+            frame_count: reloadable.current().frame_count(),
+            generation: reloadable.generation(),
+        }))
+    }
+
+    async fn delete_frames(
+        &self,
+        request: Request<DeleteFramesRequest>,
+    ) -> Result<Response<DeleteFramesResponse>, Status> {
+        let req = request.into_inner();
+        info!(
+            frame_ids = req.frame_ids.len(),
+            uris = req.uris.len(),
+            "Processing delete_frames request"
+        );
+
+        let reloadable = self.writable()?;
+        let requested = req.frame_ids.len() + req.uris.len();
+        if requested == 0 || requested > MAX_DELETE_FRAMES {
+            return Err(ServiceError::InvalidRequest(format!(
+                "frame_ids and uris must list 1 to {} frames, got {}",
+                MAX_DELETE_FRAMES, requested
+            ))
+            .into());
+        }
+
+        let _guard = self.write_lock.lock().await;
+        let frames = list_frames(reloadable.current().as_ref()).await?;
+        let mut deleted = BTreeSet::new();
+        let mut unknown_frame_ids = Vec::new();
+        for frame_id in req.frame_ids {
+            if frames.iter().any(|(id, _)| *id == frame_id) {
+                deleted.insert(frame_id);
+            } else {
+                unknown_frame_ids.push(frame_id);
+            }
+        }
+        let mut unmatched_uris = Vec::new();
+        for uri in req.uris {
+            let matched: Vec<u64> = frames
+                .iter()
+                .filter(|(_, frame_uri)| *frame_uri == uri)
+                .map(|(id, _)| *id)
+                .collect();
+            if matched.is_empty() {
+                unmatched_uris.push(uri);
+            }
+            deleted.extend(matched);
+        }
+
+        let deleted: Vec<u64> = deleted.into_iter().collect();
+        if !deleted.is_empty() {
+            let path = reloadable.cutover_status().active;
+            tokio::task::spawn_blocking({
+                let (path, deleted) = (path.clone(), deleted.clone());
+                move || delete_frames(Path::new(&path), &deleted)
+            })
+            .await
+            .map_err(|e| ServiceError::Internal(format!("Delete task error: {}", e)))??;
+            // The new generation clears cached searches and answers
+            reloadable.reload().await?;
+            info!(
+                memvid_file = %path,
+                frames = deleted.len(),
+                generation = reloadable.generation(),
+                "Tombstoned frames in the index"
+            );
+        }
+        Ok(Response::new(DeleteFramesResponse {
+            deleted_frame_ids: deleted,
+            unknown_frame_ids,
+            unmatched_uris,
+            frame_count: reloadable.current().frame_count(),
+            generation: reloadable.generation(),
+        }))
+    }
+
+    async fn export_index(
+        &self,
+        request: Request<ExportIndexRequest>,
+    ) -> Result<Response<Self::ExportIndexStream>, Status> {
+        let audience = caller_audience(&self.visibility, request.metadata());
+        let req = request.into_inner();
+        info!(omit_text = req.omit_text, "Processing export_index request");
+
+        let mut dump = IndexDump::new(Arc::clone(&self.searcher), !req.omit_text)
+            .with_visibility(Arc::clone(&self.visibility), audience);
+        let (tx, rx) = mpsc::channel(EXPORT_STREAM_BUFFER);
+        tokio::spawn(async move {
+            loop {
+                let chunk = match dump.next_batch().await {
+                    Ok(Some(lines)) => Ok(ExportIndexChunk { lines }),
+                    Ok(None) => break,
+                    Err(e) => {
+                        warn!(error = %e, "Index export failed");
+                        Err(e.into())
+                    }
+                };
+                let failed = chunk.is_err();
+                // A closed channel means the client went away
+                if tx.send(chunk).await.is_err() || failed {
+                    break;
+                }
+            }
+        });
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+
+    async fn snapshot_index(
+        &self,
+        _request: Request<SnapshotIndexRequest>,
+    ) -> Result<Response<SnapshotIndexResponse>, Status> {
+        info!("Processing snapshot_index request");
+
+        let dir = self.backup_dir.as_deref().ok_or_else(|| {
+            ServiceError::FailedPrecondition("snapshots need BACKUP_DIR".to_string())
+        })?;
+        let reloadable = self.reloadable()?;
+        // Writers wait, so the copy matches the frame count and generation
+        let _guard = self.write_lock.lock().await;
+        let source = reloadable.cutover_status().active;
+        let manifest =
+            snapshot_index(reloadable.current().as_ref(), Path::new(&source), dir).await?;
+        info!(
+            backup_id = %manifest.backup_id,
+            path = %manifest.path,
+            sha256 = %manifest.sha256,
+            "Index snapshot written"
+        );
+        Ok(Response::new(manifest.into()))
+    }
+
+    async fn set_state(
+        &self,
+        request: Request<SetStateRequest>,
+    ) -> Result<Response<SetStateResponse>, Status> {
+        let req = request.into_inner();
+        info!(
+            entity = %req.entity,
+            slot = %req.slot,
+            "Processing set_state request"
+        );
+
+        let reloadable = self.writable()?;
+        let (entity, slot) = (req.entity.trim(), req.slot.trim());
+        if entity.is_empty() || slot.is_empty() {
+            return Err(
+                ServiceError::InvalidRequest("entity and slot are required".to_string()).into(),
+            );
+        }
+
+        // Profile fields are read, changed and written back under the lock
+        let _guard = self.write_lock.lock().await;
+        let card = if entity != PROFILE_ENTITY {
+            IngestCard {
+                entity: entity.to_string(),
+                slot: slot.to_string(),
+                value: req.value,
+            }
+        } else {
+            let profile = if slot == PROFILE_SLOT {
+                match serde_json::from_str(&req.value) {
+                    Ok(Value::Object(profile)) => profile,
+                    _ => {
+                        return Err(ServiceError::InvalidRequest(
+                            "the profile must be a JSON object".to_string(),
+                        )
+                        .into())
+                    }
+                }
+            } else {
+                let state = reloadable
+                    .current()
+                    .get_state(PROFILE_ENTITY, Some(PROFILE_SLOT))
+                    .await?;
+                let mut profile = match state
+                    .slots
+                    .get(PROFILE_SLOT)
+                    .map(|data| serde_json::from_str(data))
+                {
+                    Some(Ok(Value::Object(profile))) => profile,
+                    None => Map::new(),
+                    Some(_) => {
+                        return Err(ServiceError::FailedPrecondition(
+                            "the stored profile is not a JSON object".to_string(),
+                        )
+                        .into())
+                    }
+                };
+                let field = serde_json::from_str(&req.value).unwrap_or(Value::String(req.value));
+                profile.insert(slot.to_string(), field);
+                profile
+            };
+            IngestCard {
+                entity: PROFILE_ENTITY.to_string(),
+                slot: PROFILE_SLOT.to_string(),
+                value: serde_json::to_string_pretty(&profile).map_err(|e| {
+                    ServiceError::Internal(format!("Failed to serialize the profile: {}", e))
+                })?,
+            }
+        };
+
+        let path = reloadable.cutover_status().active;
+        tokio::task::spawn_blocking({
+            let (path, card) = (path.clone(), card.clone());
+            move || write_memory_cards(Path::new(&path), &[card])
+        })
+        .await
+        .map_err(|e| ServiceError::Internal(format!("Write task error: {}", e)))??;
+        reloadable.reload().await?;
+
+        info!(
+            memvid_file = %path,
+            entity = %card.entity,
+            slot = %card.slot,
+            generation = reloadable.generation(),
+            "Wrote memory card to the index"
+        );
+        Ok(Response::new(SetStateResponse {
+            entity: card.entity,
+            slot: card.slot,
+            value: card.value,
+            generation: reloadable.generation(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::grpc::MemvidGrpcService;
+    use crate::memvid::{CachingSearcher, MemoryAnswerStore, MockSearcher, DEFAULT_ANSWER_TTL};
+    use std::path::PathBuf;
+
+    #[tokio::test]
+    async fn test_get_capabilities_returns_report() {
+        let config = Config {
+            mock_memvid: true,
+            ..Config::default()
+        };
+        let searcher = Arc::new(MockSearcher::new());
+        let report = Arc::new(CapabilityReport::new(
+            &config,
+            searcher.as_ref(),
+            Vec::new(),
+        ));
+        let service = AdminService::new(Arc::clone(&report), searcher);
+
+        let response = service
+            .get_capabilities(Request::new(GetCapabilitiesRequest {}))
+            .await
+            .unwrap();
+        let inner = response.into_inner();
+
+        assert_eq!(inner.report_json, report.to_json());
+        assert!(inner.report_json.contains("\"searcher\":\"mock\""));
+    }
+
+    #[tokio::test]
+    async fn test_get_index_stats_reports_sections() {
+        let config = Config {
+            mock_memvid: true,
+            ..Config::default()
+        };
+        let searcher = Arc::new(MockSearcher::new());
+        let report = Arc::new(CapabilityReport::new(
+            &config,
+            searcher.as_ref(),
+            Vec::new(),
+        ));
+        let service = AdminService::new(report, searcher);
+
+        let response = service
+            .get_index_stats(Request::new(GetIndexStatsRequest {}))
+            .await
+            .unwrap();
+        let inner = response.into_inner();
+
+        assert_eq!(inner.frame_count, 42);
+        assert_eq!(inner.section_frame_counts.get("experience"), Some(&18));
+    }
+
+    #[tokio::test]
+    async fn test_validate_index() {
+        let config = Config {
+            mock_memvid: true,
+            ..Config::default()
+        };
+        let searcher = Arc::new(MockSearcher::synthetic(7, 30));
+        let report = Arc::new(CapabilityReport::new(
+            &config,
+            searcher.as_ref(),
+            Vec::new(),
+        ));
+        let service = AdminService::new(report, searcher);
+
+        let inner = service
+            .validate_index(Request::new(ValidateIndexRequest::default()))
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(inner.passed);
+        assert_eq!(inner.frames_sampled, 16);
+        assert!(inner.sha256.is_empty());
+        let names: Vec<&str> = inner.checks.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["checksum", "frames", "frame_decode", "embeddings"]);
+        assert_eq!(inner.checks[2].status, ProtoCheckStatus::Pass as i32);
+
+        let status = service
+            .validate_index(Request::new(ValidateIndexRequest {
+                sample_frames: -1,
+                ..Default::default()
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+    }
+
+    #[tokio::test]
+    async fn test_get_duplicate_report() {
+        let config = Config {
+            mock_memvid: true,
+            ..Config::default()
+        };
+        let searcher = Arc::new(MockSearcher::new());
+        let report = Arc::new(CapabilityReport::new(
+            &config,
+            searcher.as_ref(),
+            Vec::new(),
+        ));
+        let service = AdminService::new(report, searcher);
+
+        let inner = service
+            .get_duplicate_report(Request::new(GetDuplicateReportRequest::default()))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(inner.frames_scanned, 6);
+        assert!(inner.clusters.is_empty());
+
+        let status = service
+            .get_duplicate_report(Request::new(GetDuplicateReportRequest {
+                min_similarity: 1.5,
+                ..Default::default()
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+    }
+
+    #[tokio::test]
+    async fn test_get_analytics_reports_dark_frames() {
+        let config = Config {
+            mock_memvid: true,
+            ..Config::default()
+        };
+        let searcher = Arc::new(MockSearcher::new());
+        let report = Arc::new(CapabilityReport::new(
+            &config,
+            searcher.as_ref(),
+            Vec::new(),
+        ));
+        let coverage = Arc::new(CoverageTracker::default());
+        coverage.record([1, 3]);
+        coverage.record([3]);
+        let service = AdminService::new(report, searcher).with_coverage_tracker(coverage);
+
+        let inner = service
+            .get_analytics(Request::new(GetAnalyticsRequest {
+                max_dark_frames: 2,
+                max_top_frames: 1,
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        let coverage = inner.coverage.unwrap();
+
+        assert_eq!(coverage.frames_total, 6);
+        assert_eq!(coverage.frames_surfaced, 2);
+        let dark: Vec<u64> = coverage.dark_frames.iter().map(|f| f.frame_id).collect();
+        assert_eq!(dark, vec![2, 4]);
+        assert_eq!(coverage.window_secs, 7 * 24 * 3600);
+        assert_eq!(inner.top_frames.len(), 1);
+        assert_eq!(inner.top_frames[0].frame_id, 3);
+        assert_eq!(inner.top_frames[0].serve_count, 2);
+    }
+
+    #[tokio::test]
+    async fn test_cutover_requires_reloadable_searcher() {
+        let config = Config {
+            mock_memvid: true,
+            ..Config::default()
+        };
+        let searcher = Arc::new(MockSearcher::new());
+        let report = Arc::new(CapabilityReport::new(
+            &config,
+            searcher.as_ref(),
+            Vec::new(),
+        ));
+        let service = AdminService::new(report, searcher);
+
+        let status = service
+            .promote_index(Request::new(PromoteIndexRequest {}))
+            .await
+            .unwrap_err();
+
+        assert_eq!(status.code(), tonic::Code::FailedPrecondition);
+    }
+
+    #[tokio::test]
+    async fn test_append_frames() {
+        use crate::generated::memvid::v1::NewFrame;
+
+        let (service, reloadable, path) = writable_service("append").await;
+
+        let append = |frames: Vec<NewFrame>| {
+            service.append_frames(Request::new(AppendFramesRequest {
+                frames,
+                source_version: "v2".to_string(),
+            }))
+        };
+        let project = || NewFrame {
+            title: "Project: Operator".to_string(),
+            text: "A Kubernetes operator written in Rust.".to_string(),
+            tags: vec!["projects".to_string()],
+            ..Default::default()
+        };
+
+        // The index is read-only
+        let status = append(vec![project()]).await.unwrap_err();
+        assert_eq!(status.code(), tonic::Code::FailedPrecondition);
+
+        let service = service.with_index_writes(true);
+        let append = |frames: Vec<NewFrame>| {
+            service.append_frames(Request::new(AppendFramesRequest {
+                frames,
+                source_version: String::new(),
+            }))
+        };
+        let status = append(vec![NewFrame {
+            text: String::new(),
+            ..project()
+        }])
+        .await
+        .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+
+        let inner = append(vec![project(), project()])
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(inner.frame_ids.len(), 2);
+        assert_eq!(inner.generation, 2);
+        assert_eq!(reloadable.generation(), 2);
+        std::fs::remove_file(path).ok();
+    }
+
+    #[tokio::test]
+    async fn test_append_frames_while_serving() {
+        use crate::generated::memvid::v1::NewFrame;
+        use crate::memvid::SearchRequest;
+
+        let (service, reloadable, path) = serving_service("append-serving").await;
+        let service = service.with_index_writes(true);
+        let frame_count = reloadable.current().frame_count();
+
+        for generation in [2, 3] {
+            let inner = service
+                .append_frames(Request::new(AppendFramesRequest {
+                    frames: vec![NewFrame {
+                        title: "Project: Operator".to_string(),
+                        text: "A Kubernetes operator written in Rust.".to_string(),
+                        ..Default::default()
+                    }],
+                    source_version: String::new(),
+                }))
+                .await
+                .unwrap()
+                .into_inner();
+            assert_eq!(inner.generation, generation);
+        }
+        assert_eq!(reloadable.current().frame_count(), frame_count + 2);
+        let response = reloadable
+            .search(SearchRequest::new("Kubernetes operator", 5, 200))
+            .await
+            .unwrap();
+        assert!(response
+            .hits
+            .iter()
+            .any(|hit| hit.title == "Project: Operator"));
+        assert!(!PathBuf::from(format!("{}.tmp", path.display())).exists());
+        std::fs::remove_file(path).ok();
+    }
+
+    /// A real index file at a temporary path named after `name`.
+    fn index_file(name: &str) -> PathBuf {
+        use crate::ingest::{write_index, IngestPlan, SourceFormat};
+
+        let path = std::env::temp_dir().join(format!("memvid-{}-{}.mv2", name, std::process::id()));
+        let plan =
+            IngestPlan::parse(r#"{"summary": "Platform engineer."}"#, SourceFormat::Json).unwrap();
+        write_index(&plan, &path).unwrap();
+        path
+    }
+
+    /// An Admin service over a mock searcher reloadable from a real index
+    /// file at the returned path.
+    async fn writable_service(name: &str) -> (AdminService, Arc<ReloadableSearcher>, PathBuf) {
+        use crate::memvid::{LoadFuture, SearcherLoader};
+
+        let path = index_file(name);
+        let loader: SearcherLoader = Arc::new(|_path: String| {
+            Box::pin(async { Ok(Arc::new(MockSearcher::new()) as Arc<dyn Searcher>) }) as LoadFuture
+        });
+        let reloadable = Arc::new(
+            ReloadableSearcher::open_with(path.to_string_lossy(), loader)
+                .await
+                .unwrap(),
+        );
+        (admin_service(&reloadable), reloadable, path)
+    }
+
+    /// An Admin service serving the real index file at the returned path
+    /// through a read pool, which holds shared locks on the file like the
+    /// running service does.
+    async fn serving_service(name: &str) -> (AdminService, Arc<ReloadableSearcher>, PathBuf) {
+        use crate::memvid::QueryEmbeddingCache;
+
+        let path = index_file(name);
+        let reloadable = Arc::new(
+            ReloadableSearcher::open_preloaded(
+                path.to_string_lossy(),
+                None,
+                None,
+                2,
+                None,
+                Arc::new(QueryEmbeddingCache::default()),
+                None,
+            )
+            .await
+            .unwrap(),
+        );
+        (admin_service(&reloadable), reloadable, path)
+    }
+
+    /// An Admin service over `reloadable`.
+    fn admin_service(reloadable: &Arc<ReloadableSearcher>) -> AdminService {
+        let config = Config {
+            mock_memvid: true,
+            ..Config::default()
+        };
+        let searcher = reloadable.current();
+        let report = Arc::new(CapabilityReport::new(
+            &config,
+            searcher.as_ref(),
+            Vec::new(),
+        ));
+        AdminService::new(report, searcher).with_reloadable(Arc::clone(reloadable))
+    }
+
+    #[tokio::test]
+    async fn test_set_state() {
+        let (service, reloadable, path) = writable_service("set-state").await;
+        let service = service.with_index_writes(true);
+        let set = |entity: &str, slot: &str, value: &str| {
+            service.set_state(Request::new(SetStateRequest {
+                entity: entity.to_string(),
+                slot: slot.to_string(),
+                value: value.to_string(),
+            }))
+        };
+
+        // A profile field is set within the stored profile
+        let inner = set("__profile__", "suggested_questions", r#"["Why Rust?"]"#)
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(inner.slot, "data");
+        let profile: Value = serde_json::from_str(&inner.value).unwrap();
+        assert_eq!(
+            profile["suggested_questions"],
+            serde_json::json!(["Why Rust?"])
+        );
+        assert_eq!(profile["name"], "Frank Schwichtenberg");
+        assert_eq!(inner.generation, 2);
+
+        let inner = set("__profile__", "status", "Open to CTO roles")
+            .await
+            .unwrap()
+            .into_inner();
+        let profile: Value = serde_json::from_str(&inner.value).unwrap();
+        assert_eq!(profile["status"], "Open to CTO roles");
+
+        let inner = set("availability", "notice", "4 weeks")
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(
+            (inner.slot.as_str(), inner.value.as_str()),
+            ("notice", "4 weeks")
+        );
+        assert_eq!(reloadable.generation(), 4);
+
+        for (entity, slot, value) in [
+            ("__profile__", "data", "[1]"),
+            ("", "notice", "4 weeks"),
+            ("availability", " ", "4 weeks"),
+        ] {
+            let status = set(entity, slot, value).await.unwrap_err();
+            assert_eq!(status.code(), tonic::Code::InvalidArgument);
+        }
+        std::fs::remove_file(path).ok();
+    }
+
+    #[tokio::test]
+    async fn test_set_state_while_serving() {
+        let (service, reloadable, path) = serving_service("set-state-serving").await;
+        let service = service.with_index_writes(true);
+        let set = |entity: &str, slot: &str, value: &str| {
+            service.set_state(Request::new(SetStateRequest {
+                entity: entity.to_string(),
+                slot: slot.to_string(),
+                value: value.to_string(),
+            }))
+        };
+
+        let inner = set("__profile__", "status", "Open to CTO roles")
+            .await
+            .unwrap()
+            .into_inner();
+        let profile: Value = serde_json::from_str(&inner.value).unwrap();
+        assert_eq!(profile["status"], "Open to CTO roles");
+        assert_eq!(profile["summary"], "Platform engineer.");
+
+        set("availability", "notice", "4 weeks").await.unwrap();
+        assert_eq!(reloadable.generation(), 3);
+        let state = reloadable
+            .current()
+            .get_state("availability", Some("notice"))
+            .await
+            .unwrap();
+        assert_eq!(state.slots["notice"], "4 weeks");
+        std::fs::remove_file(path).ok();
+    }
+
+    #[tokio::test]
+    async fn test_delete_frames() {
+        use crate::memvid::SearchRequest;
+
+        let (service, reloadable, path) = serving_service("delete").await;
+        let service = service.with_index_writes(true);
+        let delete = |frame_ids: Vec<u64>, uris: Vec<&str>| {
+            service.delete_frames(Request::new(DeleteFramesRequest {
+                frame_ids,
+                uris: uris.into_iter().map(String::from).collect(),
+            }))
+        };
+        let search = || reloadable.search(SearchRequest::new("Platform engineer", 5, 200));
+        let frame_id = search().await.unwrap().hits[0].frame_id.unwrap();
+        let frames = list_frames(reloadable.current().as_ref()).await.unwrap();
+        let (_, uri) = frames.iter().find(|(id, _)| *id == frame_id).unwrap();
+
+        let inner = delete(vec![frame_id, 99], vec![uri, "mv2://resume/gone"])
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(inner.deleted_frame_ids, [frame_id]);
+        assert_eq!(inner.unknown_frame_ids, [99]);
+        assert_eq!(inner.unmatched_uris, ["mv2://resume/gone"]);
+        assert_eq!(inner.generation, 2);
+        // Tombstoned frames stay in the file but are no longer retrieved
+        assert!(search()
+            .await
+            .unwrap()
+            .hits
+            .iter()
+            .all(|hit| hit.frame_id != Some(frame_id)));
+
+        // Nothing to delete: the index is not reloaded
+        let inner = delete(vec![99], Vec::new()).await.unwrap().into_inner();
+        assert!(inner.deleted_frame_ids.is_empty());
+        assert_eq!(reloadable.generation(), 2);
+
+        let status = delete(Vec::new(), Vec::new()).await.unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+        std::fs::remove_file(path).ok();
+    }
+
+    #[tokio::test]
+    async fn test_stage_index_within_index_dir() {
+        let (service, reloadable, path) = writable_service("stage").await;
+        let stage = |memvid_file: String| {
+            service.stage_index(Request::new(StageIndexRequest { memvid_file }))
+        };
+        let status = stage(path.display().to_string()).await.unwrap_err();
+        assert_eq!(status.code(), tonic::Code::FailedPrecondition);
+
+        let dir = std::env::temp_dir().join(format!("memvid-stage-dir-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::copy(&path, dir.join("next.mv2")).unwrap();
+        let service = service.with_index_dir(Some(dir.clone()));
+        let stage = |memvid_file: String| {
+            service.stage_index(Request::new(StageIndexRequest { memvid_file }))
+        };
+
+        // Files outside the directory, also through `..`, are refused
+        let escaped = dir.join("..").join(path.file_name().unwrap());
+        for outside in [path.clone(), escaped, dir.join("missing.mv2")] {
+            let status = stage(outside.display().to_string()).await.unwrap_err();
+            assert_eq!(status.code(), tonic::Code::InvalidArgument);
+        }
+        assert!(reloadable.cutover_status().staged.is_none());
+
+        let inner = stage(dir.join("next.mv2").display().to_string())
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(inner.staged_file.ends_with("next.mv2"));
+        std::fs::remove_dir_all(dir).ok();
+        std::fs::remove_file(path).ok();
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_index() {
+        let (service, _reloadable, path) = writable_service("snapshot").await;
+        let request = || Request::new(SnapshotIndexRequest {});
+        let status = service.snapshot_index(request()).await.unwrap_err();
+        assert_eq!(status.code(), tonic::Code::FailedPrecondition);
+
+        let dir = std::env::temp_dir().join(format!("memvid-snapshots-{}", std::process::id()));
+        let service = service.with_backup_dir(Some(dir.clone()));
+
+        let inner = service
+            .snapshot_index(request())
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(inner.source, path.display().to_string());
+        assert_eq!(
+            std::fs::read(&inner.path).unwrap(),
+            std::fs::read(&path).unwrap()
+        );
+        assert!(Path::new(&inner.manifest_path).exists());
+        assert_eq!(inner.generation, 1);
+        std::fs::remove_dir_all(dir).ok();
+        std::fs::remove_file(path).ok();
+    }
+
+    #[tokio::test]
+    async fn test_export_index() {
+        use tokio_stream::StreamExt;
+
+        let config = Config {
+            mock_memvid: true,
+            ..Config::default()
+        };
+        let searcher = Arc::new(MockSearcher::synthetic(5, 120));
+        let report = Arc::new(CapabilityReport::new(
+            &config,
+            searcher.as_ref(),
+            Vec::new(),
+        ));
+        let visibility = Arc::new(VisibilityStore::new());
+        visibility.set(&[7], Visibility::Hidden).unwrap();
+        let service = AdminService::new(report, searcher).with_visibility_store(visibility);
+
+        let mut stream = service
+            .export_index(Request::new(ExportIndexRequest { omit_text: true }))
+            .await
+            .unwrap()
+            .into_inner();
+        let mut lines = Vec::new();
+        while let Some(chunk) = stream.next().await {
+            lines.extend(chunk.unwrap().lines);
+        }
+        let records: Vec<Value> = lines
+            .iter()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(records[0]["type"], "index");
+        // The hidden frame is left out
+        assert_eq!(records.iter().filter(|r| r["type"] == "frame").count(), 119);
+        assert!(records.iter().all(|r| r["frame_id"] != 7));
+        assert!(records.iter().all(|r| r.get("text").is_none()));
+        assert_eq!(records.last().unwrap()["type"], "memory_card");
+    }
+
+    #[tokio::test]
+    async fn test_write_rpcs_refused_over_the_mock_searcher() {
+        let config = Config {
+            mock_memvid: true,
+            ..Config::default()
+        };
+        let searcher = Arc::new(MockSearcher::new());
+        let report = Arc::new(CapabilityReport::new(
+            &config,
+            searcher.as_ref(),
+            Vec::new(),
+        ));
+        let service = AdminService::new(report, searcher).with_index_writes(true);
+
+        let status = service
+            .append_frames(Request::new(AppendFramesRequest::default()))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::FailedPrecondition);
+        assert!(status.message().contains("MOCK_MEMVID=false"));
+
+        let status = service
+            .set_state(Request::new(SetStateRequest::default()))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::FailedPrecondition);
+
+        let status = service
+            .delete_frames(Request::new(DeleteFramesRequest::default()))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::FailedPrecondition);
+    }
+
+    #[tokio::test]
+    async fn test_set_frame_visibility() {
+        let config = Config {
+            mock_memvid: true,
+            ..Config::default()
+        };
+        let searcher = Arc::new(MockSearcher::new());
+        let report = Arc::new(CapabilityReport::new(
+            &config,
+            searcher.as_ref(),
+            Vec::new(),
+        ));
+        let store = Arc::new(VisibilityStore::new());
+        let service = AdminService::new(report, searcher).with_visibility_store(Arc::clone(&store));
+
+        let set = |frame_ids: Vec<u64>, visibility: FrameVisibility| {
+            service.set_frame_visibility(Request::new(SetFrameVisibilityRequest {
+                frame_ids,
+                visibility: visibility as i32,
+            }))
+        };
+        set(vec![2, 3], FrameVisibility::Hidden).await.unwrap();
+        let inner = set(vec![3], FrameVisibility::Public)
+            .await
+            .unwrap()
+            .into_inner();
+
+        assert_eq!(
+            inner.overrides,
+            vec![FrameVisibilityOverride {
+                frame_id: 2,
+                visibility: FrameVisibility::Hidden as i32,
+            }]
+        );
+        assert_eq!(store.visibility(Some(2), &[]), Visibility::Hidden);
+
+        let status = set(Vec::new(), FrameVisibility::Hidden).await.unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+    }
+
+    #[tokio::test]
+    async fn test_purge_data() {
+        let config = Config {
+            mock_memvid: true,
+            ..Config::default()
+        };
+        let searcher = Arc::new(MockSearcher::new());
+        let report = Arc::new(CapabilityReport::new(
+            &config,
+            searcher.as_ref(),
+            Vec::new(),
+        ));
+        let coverage = Arc::new(CoverageTracker::default());
+        coverage.record([1, 3]);
+        let stats = Arc::new(QueryStats::default());
+        stats.record("What about Rust?", 1, std::time::Duration::from_millis(5));
+        let service = AdminService::new(report, searcher)
+            .with_coverage_tracker(Arc::clone(&coverage))
+            .with_query_stats(Arc::clone(&stats));
+
+        let inner = service
+            .purge_data(Request::new(PurgeDataRequest {
+                stores: vec![DataStore::QueryStats as i32],
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(inner.query_stats_queries, 1);
+        assert_eq!(inner.frame_serve_counts, 0);
+        assert_eq!(coverage.serve_count(3), 1);
+
+        let inner = service
+            .purge_data(Request::new(PurgeDataRequest::default()))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(inner.frame_serve_counts, 2);
+        assert_eq!(coverage.serve_count(3), 0);
+
+        let status = service
+            .purge_data(Request::new(PurgeDataRequest { stores: vec![9] }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+    }
+
+    #[tokio::test]
+    async fn test_set_log_level() {
+        use tracing_subscriber::{layer::SubscriberExt, reload, EnvFilter};
+
+        let config = Config {
+            mock_memvid: true,
+            ..Config::default()
+        };
+        let searcher = Arc::new(MockSearcher::new());
+        let report = Arc::new(CapabilityReport::new(
+            &config,
+            searcher.as_ref(),
+            Vec::new(),
+        ));
+        let set = |level: &str| {
+            Request::new(SetLogLevelRequest {
+                level: level.to_string(),
+                duration_secs: 60,
+            })
+        };
+
+        let service = AdminService::new(Arc::clone(&report), searcher.clone());
+        let status = service.set_log_level(set("debug")).await.unwrap_err();
+        assert_eq!(status.code(), tonic::Code::FailedPrecondition);
+
+        let (layer, handle) = reload::Layer::new(EnvFilter::new("info"));
+        let _subscriber = tracing_subscriber::registry().with(layer);
+        let service = AdminService::new(report, searcher)
+            .with_log_level(Arc::new(LogLevelControl::new(handle, "info")));
+        let inner = service
+            .set_log_level(set("debug"))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(inner.level, "debug");
+        assert_eq!(inner.default_level, "info");
+        assert!(inner.reverts_at > chrono::Utc::now().timestamp());
+
+        let inner = service.set_log_level(set("")).await.unwrap().into_inner();
+        assert_eq!(inner.level, "info");
+        assert_eq!(inner.reverts_at, 0);
+    }
+
+    #[tokio::test]
+    async fn test_warm_cache() {
+        let config = Config {
+            mock_memvid: true,
+            ..Config::default()
+        };
+        let cache = Arc::new(CachingSearcher::new(
+            Arc::new(MockSearcher::new()),
+            Arc::new(MemoryAnswerStore::default()),
+            DEFAULT_ANSWER_TTL,
+        ));
+        let searcher = Arc::clone(&cache) as Arc<dyn Searcher>;
+        let report = Arc::new(CapabilityReport::new(
+            &config,
+            searcher.as_ref(),
+            Vec::new(),
+        ));
+        let warm = |questions: &[&str]| {
+            Request::new(WarmCacheRequest {
+                questions: questions.iter().map(|q| q.to_string()).collect(),
+                use_llm: false,
+            })
+        };
+
+        let service = AdminService::new(Arc::clone(&report), Arc::clone(&searcher));
+        let status = service.warm_cache(warm(&["skills"])).await.unwrap_err();
+        assert_eq!(status.code(), tonic::Code::FailedPrecondition);
+
+        let warmer = CacheWarmer::new(Arc::new(MemvidGrpcService::new(searcher.clone())), cache);
+        let service = AdminService::new(report, searcher).with_cache_warmer(Arc::new(warmer));
+        let inner = service
+            .warm_cache(warm(&["skills", "Rust experience"]))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(inner.questions, 2);
+        assert_eq!(inner.cached, 2);
+        assert!(inner.snapshot_path.is_empty());
+
+        let status = service.warm_cache(warm(&[])).await.unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+    }
+}
@@ -0,0 +1,87 @@
+//! Bearer-token authentication of the Admin service.
+//!
+//! Admin RPCs switch the serving index, change frame visibility, write to
+//! the index file, purge stored data and dump the whole index, so
+//! [`AdminAuth`] guards every one of them rather than individual handlers:
+//! callers must send `authorization: Bearer <ADMIN_TOKEN>`. Without a
+//! configured token every Admin RPC is refused.
+
+use std::sync::Arc;
+use tonic::service::Interceptor;
+use tonic::{Request, Status};
+
+use crate::debug::tokens_match;
+use crate::error::ServiceError;
+
+/// Tonic interceptor admitting Admin requests that carry the admin token.
+#[derive(Clone)]
+pub struct AdminAuth {
+    token: Option<Arc<str>>,
+}
+
+impl AdminAuth {
+    /// Admit callers presenting `token`; None refuses every request.
+    pub fn new(token: Option<String>) -> Self {
+        Self {
+            token: token.map(Arc::from),
+        }
+    }
+}
+
+impl Interceptor for AdminAuth {
+    fn call(&mut self, request: Request<()>) -> Result<Request<()>, Status> {
+        let Some(token) = self.token.as_deref() else {
+            return Err(ServiceError::FailedPrecondition(
+                "Admin RPCs need ADMIN_TOKEN".to_string(),
+            )
+            .into());
+        };
+        let presented = request
+            .metadata()
+            .get("authorization")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "));
+        match presented {
+            Some(presented) if tokens_match(presented, token) => Ok(request),
+            _ => Err(ServiceError::Unauthenticated(
+                "Admin RPCs need the admin bearer token".to_string(),
+            )
+            .into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tonic::Code;
+
+    fn request(authorization: Option<&str>) -> Request<()> {
+        let mut request = Request::new(());
+        if let Some(value) = authorization {
+            request
+                .metadata_mut()
+                .insert("authorization", value.parse().unwrap());
+        }
+        request
+    }
+
+    #[test]
+    fn test_admits_only_the_admin_token() {
+        let mut auth = AdminAuth::new(Some("s3cret".to_string()));
+        assert!(auth.call(request(Some("Bearer s3cret"))).is_ok());
+        for authorization in [None, Some("Bearer wrong"), Some("s3cret")] {
+            let status = auth.call(request(authorization)).unwrap_err();
+            assert_eq!(status.code(), Code::Unauthenticated);
+        }
+    }
+
+    #[test]
+    fn test_refuses_everything_without_a_token() {
+        let status = AdminAuth::new(None)
+            .call(request(Some("Bearer s3cret")))
+            .unwrap_err();
+        assert_eq!(status.code(), Code::FailedPrecondition);
+        assert!(status.message().contains("ADMIN_TOKEN"));
+    }
+}
@@ -0,0 +1,297 @@
+//! Response size budgeting.
+//!
+//! Large `top_k` x `snippet_chars` combinations can exceed the gRPC message
+//! size limit. Responses over budget are trimmed progressively (shorter
+//! snippets, then fewer hits, then a shorter answer) and the trimming is
+//! reported back to the client. Snippets are shortened in their source text
+//! and only then encoded and highlighted, so a cut never lands inside an
+//! HTML entity or a highlight marker.
+
+use prost::encoding::message::encoded_len_repeated;
+use prost::Message;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::generated::memvid::v1::TrimInfo;
+use crate::generated::memvid::{v1, v2};
+use crate::memvid::truncate_snippet;
+
+/// Snippets are never shortened below this many graphemes.
+const MIN_SNIPPET_CHARS: usize = 32;
+
+/// Bytes reserved for the `TrimInfo` message itself.
+const TRIM_INFO_RESERVE: usize = 32;
+
+/// A hit whose snippet can be shortened.
+pub trait TrimmableHit: Message {
+    fn snippet(&self) -> &str;
+    fn set_snippet(&mut self, snippet: String);
+}
+
+/// A response with ranked hits (and optionally an answer) that can be trimmed.
+pub trait TrimmableResponse: Message {
+    type Hit: TrimmableHit;
+
+    /// Field number of the repeated hits field.
+    const HITS_TAG: u32;
+
+    fn hits(&self) -> &[Self::Hit];
+    fn hits_mut(&mut self) -> &mut Vec<Self::Hit>;
+    fn set_trimmed(&mut self, info: TrimInfo);
+
+    /// The answer text, for responses that have one.
+    fn answer_mut(&mut self) -> Option<&mut String> {
+        None
+    }
+}
+
+/// Render the hit snippets of a response with `render` and trim it to fit
+/// within `max_bytes`: shorter snippets first, then fewer hits, then a
+/// shorter answer.
+///
+/// Hit snippets must still hold their source text; everything else in the
+/// response is measured as is. Returns true if anything was trimmed.
+pub fn fit_response<R: TrimmableResponse>(
+    response: &mut R,
+    max_bytes: usize,
+    render: impl Fn(&str) -> String,
+) -> bool {
+    let sources: Vec<String> = response
+        .hits()
+        .iter()
+        .map(|hit| hit.snippet().to_string())
+        .collect();
+    for hit in response.hits_mut() {
+        let rendered = render(hit.snippet());
+        hit.set_snippet(rendered);
+    }
+
+    let original = response.encoded_len();
+    if original <= max_bytes {
+        return false;
+    }
+    let limit = max_bytes.saturating_sub(TRIM_INFO_RESERVE);
+    let overhead = original - encoded_len_repeated(R::HITS_TAG, response.hits());
+
+    let mut info = TrimInfo {
+        original_bytes: original as i32,
+        ..Default::default()
+    };
+    info.snippets_truncated = shorten_snippets(response.hits_mut(), &sources, render, |hits| {
+        overhead + encoded_len_repeated(R::HITS_TAG, hits) > limit
+    });
+    while response.encoded_len() > limit && response.hits_mut().pop().is_some() {
+        info.hits_dropped += 1;
+    }
+
+    let over = response.encoded_len().saturating_sub(limit);
+    if over > 0 {
+        if let Some(answer) = response.answer_mut() {
+            let keep = answer.len().saturating_sub(over + 3);
+            *answer = format!("{}...", truncate_chars_to_bytes(answer, keep));
+            info.answer_truncated = true;
+        }
+    }
+
+    response.set_trimmed(info);
+    true
+}
+
+/// Halve the maximum snippet length until `over_budget` returns false or the
+/// minimum length is reached, re-rendering each shortened source snippet.
+/// Returns how many snippets were shortened.
+fn shorten_snippets<H: TrimmableHit>(
+    hits: &mut [H],
+    sources: &[String],
+    render: impl Fn(&str) -> String,
+    over_budget: impl Fn(&[H]) -> bool,
+) -> i32 {
+    let mut truncated = vec![false; hits.len()];
+    let mut max_chars = sources
+        .iter()
+        .map(|source| source.graphemes(true).count())
+        .max()
+        .unwrap_or(0);
+
+    while over_budget(hits) && max_chars > MIN_SNIPPET_CHARS {
+        max_chars = (max_chars / 2).max(MIN_SNIPPET_CHARS);
+        let shortened = hits.iter_mut().zip(sources).zip(truncated.iter_mut());
+        for ((hit, source), was_truncated) in shortened {
+            let snippet = truncate_snippet(source, max_chars);
+            if snippet != *source {
+                hit.set_snippet(render(&snippet));
+                *was_truncated = true;
+            }
+        }
+    }
+
+    truncated.iter().filter(|t| **t).count() as i32
+}
+
+macro_rules! impl_trimmable_hit {
+    ($hit:ty) => {
+        impl TrimmableHit for $hit {
+            fn snippet(&self) -> &str {
+                &self.snippet
+            }
+
+            fn set_snippet(&mut self, snippet: String) {
+                self.snippet = snippet;
+            }
+        }
+    };
+}
+
+macro_rules! impl_trimmable_response {
+    ($response:ty, $hit:ty, $hits:ident = $tag:literal $(, $answer:ident)?) => {
+        impl TrimmableResponse for $response {
+            type Hit = $hit;
+
+            const HITS_TAG: u32 = $tag;
+
+            fn hits(&self) -> &[Self::Hit] {
+                &self.$hits
+            }
+
+            fn hits_mut(&mut self) -> &mut Vec<Self::Hit> {
+                &mut self.$hits
+            }
+
+            fn set_trimmed(&mut self, info: TrimInfo) {
+                self.trimmed = Some(info);
+            }
+
+            $(
+                fn answer_mut(&mut self) -> Option<&mut String> {
+                    Some(&mut self.$answer)
+                }
+            )?
+        }
+    };
+}
+
+impl_trimmable_hit!(v1::SearchHit);
+impl_trimmable_hit!(v2::SearchHit);
+impl_trimmable_response!(v1::SearchResponse, v1::SearchHit, hits = 1);
+impl_trimmable_response!(v1::AskResponse, v1::SearchHit, evidence = 2, answer);
+impl_trimmable_response!(v2::SearchResponse, v2::SearchHit, hits = 1);
+impl_trimmable_response!(v2::AskResponse, v2::SearchHit, evidence = 2, answer);
+
+/// Longest prefix of `text` that is at most `max_bytes` and ends on a char boundary.
+fn truncate_chars_to_bytes(text: &str, max_bytes: usize) -> &str {
+    let mut end = max_bytes.min(text.len());
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[..end]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generated::memvid::v1::{AskResponse, AskStats, SearchHit, SearchResponse};
+
+    fn hit(i: usize, snippet_chars: usize) -> SearchHit {
+        SearchHit {
+            title: format!("Hit {}", i),
+            score: 1.0 - i as f32 * 0.01,
+            snippet: "é".repeat(snippet_chars),
+            tags: vec!["experience".to_string()],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_small_search_response_untouched() {
+        let mut response = SearchResponse {
+            hits: vec![hit(0, 100)],
+            total_hits: 1,
+            ..Default::default()
+        };
+        assert!(!fit_response(&mut response, 4096, str::to_string));
+        assert!(response.trimmed.is_none());
+    }
+
+    #[test]
+    fn test_search_snippets_shortened_first() {
+        let mut response = SearchResponse {
+            hits: (0..10).map(|i| hit(i, 500)).collect(),
+            total_hits: 10,
+            ..Default::default()
+        };
+        assert!(fit_response(&mut response, 4096, str::to_string));
+
+        let info = response.trimmed.unwrap();
+        assert!(response.encoded_len() <= 4096);
+        assert_eq!(response.hits.len(), 10);
+        assert!(info.snippets_truncated > 0);
+        assert_eq!(info.hits_dropped, 0);
+        assert!(info.original_bytes > 4096);
+    }
+
+    #[test]
+    fn test_search_hits_dropped_when_snippets_cannot_shrink_enough() {
+        let mut response = SearchResponse {
+            hits: (0..50).map(|i| hit(i, 200)).collect(),
+            total_hits: 50,
+            ..Default::default()
+        };
+        assert!(fit_response(&mut response, 1024, str::to_string));
+
+        let info = response.trimmed.unwrap();
+        assert!(response.encoded_len() <= 1024);
+        assert!(info.hits_dropped > 0);
+        // Lowest-ranked hits are dropped first
+        assert_eq!(response.hits[0].title, "Hit 0");
+    }
+
+    #[test]
+    fn test_ask_answer_truncated_as_last_resort() {
+        let mut response = AskResponse {
+            answer: "a".repeat(10_000),
+            evidence: (0..5).map(|i| hit(i, 300)).collect(),
+            stats: Some(AskStats::default()),
+            ..Default::default()
+        };
+        assert!(fit_response(&mut response, 2048, str::to_string));
+
+        let info = response.trimmed.unwrap();
+        assert!(response.encoded_len() <= 2048);
+        assert!(info.answer_truncated);
+        assert!(response.answer.ends_with("..."));
+    }
+
+    #[test]
+    fn test_snippets_shortened_before_rendering() {
+        // "é" as "e" plus a combining accent, so a char-based cut could split it
+        let source = "Led e\u{301}quipe teams across re\u{301}gions. ".repeat(40);
+        let mut response = SearchResponse {
+            hits: (0..10)
+                .map(|i| SearchHit {
+                    snippet: source.clone(),
+                    ..hit(i, 0)
+                })
+                .collect(),
+            total_hits: 10,
+            ..Default::default()
+        };
+        let render = |snippet: &str| snippet.replace("teams", "<em>teams</em>");
+        assert!(fit_response(&mut response, 2048, render));
+
+        assert!(response.trimmed.unwrap().snippets_truncated > 0);
+        for hit in &response.hits {
+            let opened = hit.snippet.matches("<em>").count();
+            assert_eq!(opened, hit.snippet.matches("</em>").count());
+            let plain = hit.snippet.replace("<em>", "").replace("</em>", "");
+            let kept = plain.strip_suffix("...").unwrap();
+            assert!(source.starts_with(kept));
+            assert!(source[kept.len()..].starts_with(' '));
+        }
+    }
+
+    #[test]
+    fn test_truncate_respects_char_boundaries() {
+        assert_eq!(truncate_chars_to_bytes("héllo", 2), "h");
+        assert_eq!(truncate_chars_to_bytes("héllo", 3), "hé");
+        assert_eq!(truncate_chars_to_bytes("hi", 10), "hi");
+    }
+}
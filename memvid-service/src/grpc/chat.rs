@@ -0,0 +1,212 @@
+//! Follow-up question rewriting for Chat.
+//!
+//! Retrieval sees one question at a time, so a follow-up that leans on the
+//! conversation ("what about at Siemens?", "how long were you there?") is
+//! rewritten into a standalone question from the previous user question,
+//! itself contextualized the same way:
+//!
+//! - Elliptical follow-ups ("what about X", "how about X", "and X") swap X
+//!   into the previous question: for its last phrase starting with the same
+//!   preposition, for its last capitalized name when X is one, and otherwise
+//!   as the topic ("..., regarding X").
+//! - The first of "it", "them" or "there" is replaced with the last
+//!   capitalized name of the previous question ("there" becomes "at Name").
+//!
+//! Anything else is taken as a standalone question.
+
+use std::ops::Range;
+
+use crate::session::{Role, Turn};
+
+/// Earlier turns a Chat message is read against.
+pub const CHAT_HISTORY_TURNS: usize = 10;
+
+/// Openings of an elliptical follow-up, longest first.
+const FOLLOW_UP_PREFIXES: [&str; 5] = [
+    "and what about ",
+    "and how about ",
+    "what about ",
+    "how about ",
+    "and ",
+];
+
+/// Prepositions a follow-up topic may start with.
+const PREPOSITIONS: [&str; 8] = ["at", "in", "with", "for", "on", "during", "from", "as"];
+
+/// Words before which "there" is existential ("is there ...").
+const EXISTENTIAL: [&str; 4] = ["is", "are", "was", "were"];
+
+/// `question` as a standalone question given the conversation `history`
+/// (oldest first), or None when it does not depend on it.
+pub fn contextualize(question: &str, history: &[Turn]) -> Option<String> {
+    let position = history.iter().rposition(|turn| turn.role == Role::User)?;
+    let earlier = &history[position].content;
+    let previous = contextualize(earlier, &history[..position]).unwrap_or_else(|| earlier.clone());
+
+    let (body, end) = split_terminal(question.trim());
+    let rewritten = swap_topic(body, &previous).or_else(|| resolve_pronoun(body, &previous))?;
+    Some(format!("{}{}", rewritten, end))
+}
+
+/// `text` without and with only its closing punctuation.
+fn split_terminal(text: &str) -> (&str, &str) {
+    let body = text.trim_end_matches(['?', '.', '!']).trim_end();
+    (body, text[body.len()..].trim_start())
+}
+
+/// `previous` about the topic of an elliptical follow-up `body`.
+fn swap_topic(body: &str, previous: &str) -> Option<String> {
+    let lower = body.to_ascii_lowercase();
+    let prefix = FOLLOW_UP_PREFIXES
+        .iter()
+        .find(|prefix| lower.starts_with(*prefix))?;
+    let topic = body[prefix.len()..].trim();
+    if topic.is_empty() {
+        return None;
+    }
+    let (previous, _) = split_terminal(previous.trim());
+
+    let first = topic.split_whitespace().next()?.to_ascii_lowercase();
+    if PREPOSITIONS.contains(&first.as_str()) {
+        let lower = previous.to_ascii_lowercase();
+        if let Some(at) = lower.rfind(&format!(" {} ", first)) {
+            return Some(format!("{} {}", &previous[..at], topic));
+        }
+    }
+    if topic.starts_with(char::is_uppercase) {
+        if let Some(name) = last_name(previous) {
+            return Some(format!(
+                "{}{}{}",
+                &previous[..name.start],
+                topic,
+                &previous[name.end..]
+            ));
+        }
+    }
+    Some(format!("{}, regarding {}", previous, topic))
+}
+
+/// `body` with its first "it", "them" or "there" replaced by the last name
+/// in `previous`.
+fn resolve_pronoun(body: &str, previous: &str) -> Option<String> {
+    let name = last_name(previous).map(|range| &previous[range])?;
+    let mut resolved = false;
+    let mut before = String::new();
+    let words: Vec<String> = body
+        .split_whitespace()
+        .map(|token| {
+            let word = token.trim_end_matches(|c: char| !c.is_alphanumeric());
+            let lower = word.to_ascii_lowercase();
+            let replacement = match lower.as_str() {
+                _ if resolved => None,
+                "it" | "them" => Some(name.to_string()),
+                "there" if !EXISTENTIAL.contains(&before.as_str()) => Some(format!("at {}", name)),
+                _ => None,
+            };
+            before = lower;
+            match replacement {
+                Some(replacement) => {
+                    resolved = true;
+                    format!("{}{}", replacement, &token[word.len()..])
+                }
+                None => token.to_string(),
+            }
+        })
+        .collect();
+    resolved.then(|| words.join(" "))
+}
+
+/// Byte range of the last run of capitalized words in `text`, not counting
+/// its first word or "I".
+fn last_name(text: &str) -> Option<Range<usize>> {
+    let mut current: Option<Range<usize>> = None;
+    let mut last = None;
+    for (i, token) in text.split_whitespace().enumerate() {
+        let start = token.as_ptr() as usize - text.as_ptr() as usize;
+        let word = token.trim_end_matches(|c: char| !c.is_alphanumeric());
+        if i > 0 && word != "I" && word.starts_with(char::is_uppercase) {
+            let range = current.map_or(start, |r| r.start)..start + word.len();
+            last = Some(range.clone());
+            // Punctuation after a word ends the name
+            current = (word.len() == token.len()).then_some(range);
+        } else {
+            current = None;
+        }
+    }
+    last
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history(questions: &[&str]) -> Vec<Turn> {
+        questions
+            .iter()
+            .flat_map(|question| {
+                [
+                    Turn {
+                        role: Role::User,
+                        content: question.to_string(),
+                        created_at: 0,
+                    },
+                    Turn {
+                        role: Role::Assistant,
+                        content: "An answer mentioning Acme Corp.".to_string(),
+                        created_at: 0,
+                    },
+                ]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_elliptical_follow_ups() {
+        let turns = history(&["What did you do at Google?"]);
+        assert_eq!(
+            contextualize("What about at Siemens?", &turns).as_deref(),
+            Some("What did you do at Siemens?")
+        );
+        assert_eq!(
+            contextualize("and leadership", &turns).as_deref(),
+            Some("What did you do at Google, regarding leadership")
+        );
+
+        let turns = history(&["What Python experience do you have?"]);
+        assert_eq!(
+            contextualize("how about Rust?", &turns).as_deref(),
+            Some("What Rust experience do you have?")
+        );
+    }
+
+    #[test]
+    fn test_pronouns_resolve_to_the_last_name() {
+        let turns = history(&["What did you do at Siemens Healthineers?"]);
+        assert_eq!(
+            contextualize("How long did you work there?", &turns).as_deref(),
+            Some("How long did you work at Siemens Healthineers?")
+        );
+        let turns = history(&["Tell me about Kubernetes."]);
+        assert_eq!(
+            contextualize("Where did you use it?", &turns).as_deref(),
+            Some("Where did you use Kubernetes?")
+        );
+        assert_eq!(contextualize("Is there a portfolio?", &turns), None);
+    }
+
+    #[test]
+    fn test_follow_ups_chain() {
+        let turns = history(&["What did you do at Google?", "what about at Siemens?"]);
+        assert_eq!(
+            contextualize("How long were you there?", &turns).as_deref(),
+            Some("How long were you at Siemens?")
+        );
+    }
+
+    #[test]
+    fn test_standalone_questions_are_kept() {
+        let turns = history(&["What did you do at Google?"]);
+        assert_eq!(contextualize("What is your education?", &turns), None);
+        assert_eq!(contextualize("What about at Siemens?", &[]), None);
+    }
+}
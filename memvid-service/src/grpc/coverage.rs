@@ -0,0 +1,303 @@
+//! Coverage of the loaded index by served responses.
+//!
+//! Records when each frame last appeared in a Search or Ask response. Frames
+//! that have not surfaced within the rolling window are "dark content":
+//! candidates for re-chunking or re-tagging. Frame IDs refer to the active
+//! index, so the report is only meaningful between index swaps.
+//!
+//! It also counts how often each frame was served. The counts are all-time
+//! rather than windowed and, with a store configured, persisted in a sled
+//! database so they survive restarts; the most-served frames show which
+//! resume sections visitors care about.
+
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::error::ServiceError;
+use crate::memvid::{FrameMetadata, Searcher};
+
+/// Default rolling window: one week.
+pub const DEFAULT_COVERAGE_WINDOW: Duration = Duration::from_secs(7 * 24 * 3600);
+
+/// Frames read per `export_frames` call while building a report.
+const REPORT_BATCH: usize = 256;
+
+/// Frames surfaced and never surfaced within the window.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoverageReport {
+    /// Rolling window length
+    pub window: Duration,
+    /// When tracking started (window start is the later of this and now - window)
+    pub tracking_since: DateTime<Utc>,
+    /// Frames in the loaded index
+    pub frames_total: usize,
+    /// Frames that appeared in at least one response within the window
+    pub frames_surfaced: usize,
+    /// Frames that did not, in ID order
+    pub dark_frames: Vec<FrameMetadata>,
+    /// Frames served at least once with their all-time serve count, most
+    /// served first
+    pub top_frames: Vec<(FrameMetadata, u64)>,
+}
+
+/// Tracks when each frame last appeared in a response.
+#[derive(Debug)]
+pub struct CoverageTracker {
+    window: Duration,
+    started: DateTime<Utc>,
+    last_surfaced: Mutex<HashMap<u64, DateTime<Utc>>>,
+    serve_counts: Mutex<HashMap<u64, u64>>,
+    store: Option<sled::Db>,
+}
+
+impl Default for CoverageTracker {
+    fn default() -> Self {
+        Self::new(DEFAULT_COVERAGE_WINDOW)
+    }
+}
+
+impl CoverageTracker {
+    /// Create a tracker with the given rolling window.
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            started: Utc::now(),
+            last_surfaced: Mutex::new(HashMap::new()),
+            serve_counts: Mutex::new(HashMap::new()),
+            store: None,
+        }
+    }
+
+    /// Persist serve counts in a sled database at `path`, continuing from
+    /// the counts saved there before.
+    pub fn with_store(mut self, path: impl AsRef<Path>) -> Result<Self, ServiceError> {
+        let path = path.as_ref();
+        let open_error = |e: sled::Error| {
+            ServiceError::Internal(format!(
+                "Failed to open frame stats store {}: {}",
+                path.display(),
+                e
+            ))
+        };
+        let db = sled::open(path).map_err(open_error)?;
+        let mut counts = HashMap::new();
+        for entry in db.iter() {
+            let (key, value) = entry.map_err(open_error)?;
+            if let (Ok(key), Ok(value)) = (<[u8; 8]>::try_from(&*key), <[u8; 8]>::try_from(&*value))
+            {
+                counts.insert(u64::from_be_bytes(key), u64::from_be_bytes(value));
+            }
+        }
+        info!(
+            path = %path.display(),
+            frames = counts.len(),
+            "Loaded frame serve counts"
+        );
+        self.serve_counts = Mutex::new(counts);
+        self.store = Some(db);
+        Ok(self)
+    }
+
+    /// All-time serve count of a frame.
+    pub fn serve_count(&self, frame_id: u64) -> u64 {
+        self.counts().get(&frame_id).copied().unwrap_or(0)
+    }
+
+    /// Record that these frames appeared in a response.
+    pub fn record(&self, frame_ids: impl IntoIterator<Item = u64>) {
+        self.record_at(frame_ids, Utc::now());
+    }
+
+    /// Delete all serve counts, including persisted ones, and when each
+    /// frame last surfaced. Returns the number of frames that had a count.
+    pub fn purge(&self) -> Result<usize, ServiceError> {
+        // Same lock order as record_at
+        let mut last_surfaced = self.lock();
+        let mut counts = self.counts();
+        if let Some(db) = &self.store {
+            db.clear().map_err(|e| {
+                ServiceError::Internal(format!("Failed to purge frame stats store: {}", e))
+            })?;
+        }
+        let purged = counts.len();
+        counts.clear();
+        last_surfaced.clear();
+        Ok(purged)
+    }
+
+    /// Build a coverage report over every frame in the searcher's index.
+    pub async fn report(&self, searcher: &dyn Searcher) -> Result<CoverageReport, ServiceError> {
+        let mut frames = Vec::new();
+        let mut after = None;
+        loop {
+            let batch = searcher.export_frames(after, REPORT_BATCH).await?;
+            let Some(last) = batch.last() else {
+                break;
+            };
+            after = Some(last.frame_id);
+            frames.extend(batch);
+        }
+        Ok(self.report_at(frames, Utc::now()))
+    }
+
+    fn record_at(&self, frame_ids: impl IntoIterator<Item = u64>, at: DateTime<Utc>) {
+        let mut last_surfaced = self.lock();
+        let mut counts = self.counts();
+        for frame_id in frame_ids {
+            last_surfaced.insert(frame_id, at);
+            let count = counts.entry(frame_id).or_insert(0);
+            *count += 1;
+            if let Some(db) = &self.store {
+                // sled flushes to disk in the background
+                if let Err(e) = db.insert(frame_id.to_be_bytes(), &count.to_be_bytes()) {
+                    warn!(error = %e, frame_id, "Failed to persist frame serve count");
+                }
+            }
+        }
+    }
+
+    fn report_at(&self, frames: Vec<FrameMetadata>, now: DateTime<Utc>) -> CoverageReport {
+        let cutoff = chrono::Duration::from_std(self.window)
+            .ok()
+            .and_then(|window| now.checked_sub_signed(window))
+            .unwrap_or(DateTime::<Utc>::MIN_UTC);
+
+        let mut last_surfaced = self.lock();
+        // Forget frames that fell out of the window
+        last_surfaced.retain(|_, at| *at >= cutoff);
+
+        let counts = self.counts();
+        let mut top_frames: Vec<(FrameMetadata, u64)> = frames
+            .iter()
+            .filter_map(|frame| {
+                let count = counts.get(&frame.frame_id).copied()?;
+                Some((frame.clone(), count))
+            })
+            .collect();
+        top_frames.sort_by_key(|(frame, count)| (std::cmp::Reverse(*count), frame.frame_id));
+
+        let frames_total = frames.len();
+        let dark_frames: Vec<FrameMetadata> = frames
+            .into_iter()
+            .filter(|frame| !last_surfaced.contains_key(&frame.frame_id))
+            .collect();
+
+        CoverageReport {
+            window: self.window,
+            tracking_since: self.started,
+            frames_total,
+            frames_surfaced: frames_total - dark_frames.len(),
+            dark_frames,
+            top_frames,
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<u64, DateTime<Utc>>> {
+        self.last_surfaced.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn counts(&self) -> std::sync::MutexGuard<'_, HashMap<u64, u64>> {
+        self.serve_counts.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memvid::MockSearcher;
+
+    fn frames(ids: &[u64]) -> Vec<FrameMetadata> {
+        ids.iter()
+            .map(|&frame_id| FrameMetadata {
+                frame_id,
+                uri: String::new(),
+                title: format!("Frame {}", frame_id),
+                tags: Vec::new(),
+                labels: Vec::new(),
+                metadata: Default::default(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_dark_frames_outside_window() {
+        let tracker = CoverageTracker::new(Duration::from_secs(3600));
+        let now = Utc::now();
+
+        tracker.record_at([1], now - chrono::Duration::hours(2));
+        tracker.record_at([2, 3], now - chrono::Duration::minutes(5));
+
+        let report = tracker.report_at(frames(&[1, 2, 3, 4]), now);
+        assert_eq!(report.frames_total, 4);
+        assert_eq!(report.frames_surfaced, 2);
+        let dark: Vec<u64> = report.dark_frames.iter().map(|f| f.frame_id).collect();
+        assert_eq!(dark, vec![1, 4]);
+    }
+
+    #[test]
+    fn test_frames_not_in_index_are_ignored() {
+        let tracker = CoverageTracker::default();
+        tracker.record([99]);
+
+        let report = tracker.report_at(frames(&[1]), Utc::now());
+        assert_eq!(report.frames_surfaced, 0);
+        assert_eq!(report.dark_frames.len(), 1);
+    }
+
+    #[test]
+    fn test_top_frames_by_serve_count() {
+        let tracker = CoverageTracker::default();
+        tracker.record([2, 3]);
+        tracker.record([3]);
+        tracker.record([99]);
+
+        let report = tracker.report_at(frames(&[1, 2, 3]), Utc::now());
+        let top: Vec<(u64, u64)> = report
+            .top_frames
+            .iter()
+            .map(|(frame, count)| (frame.frame_id, *count))
+            .collect();
+        assert_eq!(top, vec![(3, 2), (2, 1)]);
+    }
+
+    #[test]
+    fn test_serve_counts_persist() {
+        let path = std::env::temp_dir().join(format!("memvid-frame-stats-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&path);
+
+        {
+            let tracker = CoverageTracker::default().with_store(&path).unwrap();
+            tracker.record([1, 2]);
+            tracker.record([1]);
+        }
+        let reopened = CoverageTracker::default().with_store(&path).unwrap();
+        assert_eq!(reopened.serve_count(1), 2);
+        assert_eq!(reopened.serve_count(2), 1);
+        reopened.record([2]);
+        assert_eq!(reopened.serve_count(2), 2);
+
+        assert_eq!(reopened.purge().unwrap(), 2);
+        assert_eq!(reopened.serve_count(1), 0);
+        drop(reopened);
+        let purged = CoverageTracker::default().with_store(&path).unwrap();
+        assert_eq!(purged.serve_count(2), 0);
+
+        drop(purged);
+        std::fs::remove_dir_all(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_report_covers_whole_index() {
+        let tracker = CoverageTracker::default();
+        tracker.record([1, 2]);
+
+        let report = tracker.report(&MockSearcher::new()).await.unwrap();
+        assert_eq!(report.frames_total, 6);
+        assert_eq!(report.frames_surfaced, 2);
+        assert_eq!(report.dark_frames[0].frame_id, 3);
+    }
+}
@@ -0,0 +1,285 @@
+//! Signed, opaque page cursors.
+//!
+//! Search, Ask and ExportFrames hand out cursors in one format: URL-safe
+//! base64 of a small protobuf holding the index generation, the position to
+//! resume from, a hash of the request parameters that define the result set,
+//! and an HMAC-SHA256 signature over those fields. Clients cannot forge or
+//! edit positions, a cursor replayed against a different query is rejected
+//! with INVALID_ARGUMENT, and a cursor issued before an index reload is
+//! rejected with FAILED_PRECONDITION, since its positions refer to the
+//! previous index.
+//!
+//! Set `CURSOR_SECRET` to the same value on every replica so cursors stay
+//! valid across instances and restarts; without it each process signs with
+//! a random key.
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use prost::Message;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+
+use crate::error::ServiceError;
+use crate::memvid::{AskRequest, Boosts};
+use crate::random::random_bytes;
+
+/// Signature bytes kept per cursor (truncated HMAC-SHA256).
+const SIGNATURE_LEN: usize = 16;
+
+/// Wire format of a cursor before base64 encoding.
+#[derive(Clone, PartialEq, Message)]
+struct PageCursor {
+    /// Index generation the position refers to
+    #[prost(uint64, tag = "1")]
+    generation: u64,
+    /// Position to resume from (meaning depends on the RPC)
+    #[prost(uint64, tag = "2")]
+    offset: u64,
+    /// Hash of the parameters defining the result set
+    #[prost(uint64, tag = "3")]
+    filter_hash: u64,
+    /// Truncated HMAC over fields 1-3
+    #[prost(bytes = "vec", tag = "4")]
+    signature: Vec<u8>,
+}
+
+/// Issues and verifies page cursors.
+pub struct CursorCodec {
+    key: Vec<u8>,
+}
+
+impl std::fmt::Debug for CursorCodec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CursorCodec").finish_non_exhaustive()
+    }
+}
+
+impl Default for CursorCodec {
+    fn default() -> Self {
+        Self::ephemeral()
+    }
+}
+
+impl CursorCodec {
+    /// Sign cursors with `secret`.
+    pub fn new(secret: &[u8]) -> Self {
+        Self {
+            key: secret.to_vec(),
+        }
+    }
+
+    /// Sign cursors with a random key; cursors die with the process.
+    pub fn ephemeral() -> Self {
+        Self {
+            key: random_bytes::<32>().to_vec(),
+        }
+    }
+
+    /// Cursor resuming at `offset` in the result set `filter_hash` of index
+    /// `generation`.
+    pub fn encode(&self, generation: u64, offset: u64, filter_hash: u64) -> String {
+        let mut cursor = PageCursor {
+            generation,
+            offset,
+            filter_hash,
+            signature: Vec::new(),
+        };
+        cursor.signature = self.mac(&cursor).finalize().into_bytes()[..SIGNATURE_LEN].to_vec();
+        URL_SAFE_NO_PAD.encode(cursor.encode_to_vec())
+    }
+
+    /// Offset carried by `cursor` (0 when empty).
+    ///
+    /// Fails with `InvalidRequest` for malformed or tampered cursors and
+    /// cursors from another result set, and with `FailedPrecondition` for
+    /// cursors issued against another index generation.
+    pub fn decode(
+        &self,
+        cursor: &str,
+        generation: u64,
+        filter_hash: u64,
+    ) -> Result<u64, ServiceError> {
+        if cursor.is_empty() {
+            return Ok(0);
+        }
+        let invalid = || ServiceError::InvalidRequest(format!("Invalid cursor: {}", cursor));
+
+        let bytes = URL_SAFE_NO_PAD.decode(cursor).map_err(|_| invalid())?;
+        let mut decoded = PageCursor::decode(bytes.as_slice()).map_err(|_| invalid())?;
+        let signature = std::mem::take(&mut decoded.signature);
+        if signature.len() != SIGNATURE_LEN
+            || self
+                .mac(&decoded)
+                .verify_truncated_left(&signature)
+                .is_err()
+        {
+            return Err(invalid());
+        }
+
+        if decoded.filter_hash != filter_hash {
+            return Err(ServiceError::InvalidRequest(
+                "Cursor belongs to a different query".to_string(),
+            ));
+        }
+        if decoded.generation != generation {
+            return Err(ServiceError::FailedPrecondition(format!(
+                "Cursor was issued for index generation {}, current is {}; restart from the first page",
+                decoded.generation, generation
+            )));
+        }
+        Ok(decoded.offset)
+    }
+
+    /// MAC over a cursor's unsigned fields.
+    fn mac(&self, cursor: &PageCursor) -> Hmac<Sha256> {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(&self.key).expect("HMAC accepts keys of any length");
+        mac.update(&cursor.generation.to_le_bytes());
+        mac.update(&cursor.offset.to_le_bytes());
+        mac.update(&cursor.filter_hash.to_le_bytes());
+        mac
+    }
+}
+
+/// Hash of the parameters that define an RPC's result set.
+///
+/// Parts are length-prefixed and filters sorted, so the hash is stable
+/// across processes and map iteration orders.
+pub fn scope_hash(rpc: &str, parts: &[&str], filters: &HashMap<String, String>) -> u64 {
+    let mut sorted: Vec<(&String, &String)> = filters.iter().collect();
+    sorted.sort();
+
+    let mut hasher = Sha256::new();
+    let fields = std::iter::once(rpc).chain(parts.iter().copied()).chain(
+        sorted
+            .into_iter()
+            .flat_map(|(k, v)| [k.as_str(), v.as_str()]),
+    );
+    for field in fields {
+        hasher.update((field.len() as u64).to_le_bytes());
+        hasher.update(field.as_bytes());
+    }
+    u64::from_le_bytes(hasher.finalize()[..8].try_into().expect("8 bytes"))
+}
+
+/// Hash of an Ask result set.
+///
+/// Time bounds are left out: clients may express them relative to now, so
+/// they legitimately shift between pages.
+pub fn ask_scope(
+    request: &AskRequest,
+    language: Option<&str>,
+    strict: bool,
+    boosts: Option<&Boosts>,
+    index: &str,
+) -> u64 {
+    let mode = format!("{:?}", request.mode);
+    let as_of_frame = request
+        .as_of_frame
+        .map(|frame| frame.to_string())
+        .unwrap_or_default();
+    let boosts = boosts.map(Boosts::scope_key);
+    let mut parts = vec![
+        request.question.as_str(),
+        &mode,
+        request.uri.as_deref().unwrap_or_default(),
+        &as_of_frame,
+        language.unwrap_or_default(),
+        if strict { "strict" } else { "" },
+    ];
+    // Only boosted requests and requests to a named index get extra parts,
+    // so other cursors stay valid
+    parts.extend(boosts.as_deref());
+    if !index.is_empty() {
+        parts.push(index);
+    }
+    scope_hash("ask", &parts, &request.filters)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let codec = CursorCodec::new(b"secret");
+        let cursor = codec.encode(3, 20, 77);
+
+        assert_eq!(codec.decode(&cursor, 3, 77).unwrap(), 20);
+        assert_eq!(codec.decode("", 3, 77).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_rejects_tampered_and_foreign_cursors() {
+        let codec = CursorCodec::new(b"secret");
+        let cursor = codec.encode(1, 4, 9);
+
+        // Same payload, different key
+        let forged = CursorCodec::new(b"other").encode(1, 4, 9);
+        assert!(matches!(
+            codec.decode(&forged, 1, 9),
+            Err(ServiceError::InvalidRequest(_))
+        ));
+
+        // Edited offset, original signature
+        let mut edited =
+            PageCursor::decode(URL_SAFE_NO_PAD.decode(&cursor).unwrap().as_slice()).unwrap();
+        edited.offset = 400;
+        let edited = URL_SAFE_NO_PAD.encode(edited.encode_to_vec());
+        assert!(matches!(
+            codec.decode(&edited, 1, 9),
+            Err(ServiceError::InvalidRequest(_))
+        ));
+
+        // Ephemeral keys differ per codec
+        let ephemeral = CursorCodec::ephemeral().encode(1, 4, 9);
+        assert!(CursorCodec::ephemeral().decode(&ephemeral, 1, 9).is_err());
+
+        for garbage in ["2", "not a cursor", "AAAA"] {
+            assert!(matches!(
+                codec.decode(garbage, 1, 9),
+                Err(ServiceError::InvalidRequest(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_rejects_other_query_and_generation() {
+        let codec = CursorCodec::new(b"secret");
+        let cursor = codec.encode(1, 4, 9);
+
+        assert!(matches!(
+            codec.decode(&cursor, 1, 10),
+            Err(ServiceError::InvalidRequest(_))
+        ));
+        assert!(matches!(
+            codec.decode(&cursor, 2, 9),
+            Err(ServiceError::FailedPrecondition(_))
+        ));
+    }
+
+    #[test]
+    fn test_scope_hash_is_order_independent() {
+        let pairs = [
+            ("section".to_string(), "experience".to_string()),
+            ("company".to_string(), "acme".to_string()),
+        ];
+        let a = HashMap::from(pairs.clone());
+        let b: HashMap<String, String> = pairs.into_iter().rev().collect();
+
+        assert_eq!(
+            scope_hash("search", &["rust"], &a),
+            scope_hash("search", &["rust"], &b)
+        );
+        assert_ne!(
+            scope_hash("search", &["rust"], &a),
+            scope_hash("search", &["go"], &a)
+        );
+        // Length prefixes keep part boundaries distinct
+        assert_ne!(
+            scope_hash("search", &["ab", "c"], &HashMap::new()),
+            scope_hash("search", &["a", "bc"], &HashMap::new())
+        );
+    }
+}
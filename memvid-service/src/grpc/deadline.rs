@@ -0,0 +1,125 @@
+//! Propagation of the client's gRPC deadline.
+//!
+//! `DeadlineLayer` reads the `grpc-timeout` header of every call and runs
+//! the call within [`deadline::scope`], so the searcher can abandon blocking
+//! work once the client has stopped waiting (see [`deadline`]). Tonic
+//! itself drops the call's future at the deadline; without this the
+//! blocking task it spawned would still take the index lock and run.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::time::Instant;
+use tonic::codegen::http;
+use tower::{Layer, Service};
+
+use crate::memvid::deadline;
+
+/// Header carrying the client's remaining time.
+pub const GRPC_TIMEOUT_HEADER: &str = "grpc-timeout";
+
+/// Parse a `grpc-timeout` value: up to 8 digits and a unit (H, M, S, m, u
+/// or n).
+pub fn parse_grpc_timeout(value: &str) -> Option<Duration> {
+    if value.len() < 2 || value.len() > 9 || !value.is_ascii() {
+        return None;
+    }
+    let (digits, unit) = value.split_at(value.len() - 1);
+    if !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let amount: u64 = digits.parse().ok()?;
+    match unit {
+        "H" => Some(Duration::from_secs(amount * 3600)),
+        "M" => Some(Duration::from_secs(amount * 60)),
+        "S" => Some(Duration::from_secs(amount)),
+        "m" => Some(Duration::from_millis(amount)),
+        "u" => Some(Duration::from_micros(amount)),
+        "n" => Some(Duration::from_nanos(amount)),
+        _ => None,
+    }
+}
+
+/// Tower layer running every call within its client's deadline.
+#[derive(Debug, Clone, Default)]
+pub struct DeadlineLayer;
+
+impl<S> Layer<S> for DeadlineLayer {
+    type Service = DeadlineService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        DeadlineService { inner }
+    }
+}
+
+/// Service produced by [`DeadlineLayer`].
+#[derive(Debug, Clone)]
+pub struct DeadlineService<S> {
+    inner: S,
+}
+
+impl<S, B> Service<http::Request<B>> for DeadlineService<S>
+where
+    S: Service<http::Request<B>>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: http::Request<B>) -> Self::Future {
+        // Invalid values are ignored, as tonic does
+        let deadline = request
+            .headers()
+            .get(GRPC_TIMEOUT_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(parse_grpc_timeout)
+            .and_then(|timeout| Instant::now().checked_add(timeout));
+        Box::pin(deadline::scope(deadline, self.inner.call(request)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::Infallible;
+    use tower::ServiceExt;
+
+    #[test]
+    fn test_parse_grpc_timeout() {
+        assert_eq!(parse_grpc_timeout("250m"), Some(Duration::from_millis(250)));
+        assert_eq!(parse_grpc_timeout("2S"), Some(Duration::from_secs(2)));
+        assert_eq!(parse_grpc_timeout("1H"), Some(Duration::from_secs(3600)));
+        assert_eq!(
+            parse_grpc_timeout("99999999n"),
+            Some(Duration::from_nanos(99_999_999))
+        );
+        assert_eq!(parse_grpc_timeout("100"), None);
+        assert_eq!(parse_grpc_timeout("m"), None);
+        assert_eq!(parse_grpc_timeout("-5S"), None);
+        assert_eq!(parse_grpc_timeout("123456789S"), None);
+    }
+
+    #[tokio::test]
+    async fn test_layer_scopes_the_deadline() {
+        let service = DeadlineLayer.layer(tower::service_fn(|_: http::Request<()>| async {
+            Ok::<_, Infallible>(deadline::current())
+        }));
+
+        let request = http::Request::builder()
+            .header(GRPC_TIMEOUT_HEADER, "5S")
+            .body(())
+            .unwrap();
+        let seen = service.clone().oneshot(request).await.unwrap().unwrap();
+        let remaining = seen - Instant::now();
+        assert!(remaining > Duration::from_secs(4) && remaining <= Duration::from_secs(5));
+
+        let request = http::Request::builder().body(()).unwrap();
+        assert_eq!(service.oneshot(request).await.unwrap(), None);
+    }
+}
@@ -0,0 +1,185 @@
+//! Entity linking between evidence snippets and profile memory cards.
+//!
+//! Companies, schools, and technologies listed in the `__profile__` memory
+//! card are matched (whole word, ASCII case-insensitive) against hit
+//! snippets. Each link names the memory card and a JSON pointer into its
+//! slot value, so the UI can render a hovercard from `GetState`.
+
+use super::profile::{Profile, PROFILE_ENTITY, PROFILE_SLOT};
+use crate::generated::memvid::v1::EntityLink;
+
+/// A profile record that mentions can link to.
+#[derive(Debug, Clone)]
+struct Target {
+    name: String,
+    kind: &'static str,
+    pointer: String,
+}
+
+/// Links snippet mentions to profile records.
+#[derive(Debug, Clone, Default)]
+pub struct EntityLinker {
+    /// Longest names first, so "Rust Async" wins over "Rust"
+    targets: Vec<Target>,
+}
+
+impl EntityLinker {
+    /// Build a linker from the profile JSON.
+    ///
+    /// Malformed JSON yields a linker that links nothing.
+    pub fn from_profile(json: &str) -> Self {
+        Profile::parse(json).linker().clone()
+    }
+
+    /// Build a linker over the profile's `experience[].company`,
+    /// `education[].school` (or `.institution`), and every `skills`
+    /// category.
+    pub fn for_profile(profile: &Profile) -> Self {
+        let mut targets = Vec::new();
+
+        let mut push = |name: Option<&str>, kind, pointer: String| {
+            if let Some(name) = name.map(str::trim).filter(|n| !n.is_empty()) {
+                targets.push(Target {
+                    name: name.to_string(),
+                    kind,
+                    pointer,
+                });
+            }
+        };
+
+        for (i, entry) in profile.experience().iter().enumerate() {
+            push(
+                entry.company.as_deref(),
+                "company",
+                format!("/experience/{}", i),
+            );
+        }
+        for (i, entry) in profile.education().iter().enumerate() {
+            push(
+                entry.school.as_deref(),
+                "school",
+                format!("/education/{}", i),
+            );
+        }
+        for (category, names) in profile.skills() {
+            for (i, name) in names.iter().enumerate() {
+                push(
+                    name.as_deref(),
+                    "technology",
+                    format!("/skills/{}/{}", category, i),
+                );
+            }
+        }
+
+        targets.sort_by_key(|t| std::cmp::Reverse(t.name.len()));
+        Self { targets }
+    }
+
+    /// Link mentions in `text`, in order of appearance.
+    ///
+    /// Each profile record is linked at most once per text, and mentions
+    /// never overlap.
+    pub fn link(&self, text: &str) -> Vec<EntityLink> {
+        // ASCII lowercasing keeps byte offsets aligned with `text`
+        let haystack = text.to_ascii_lowercase();
+        let mut taken: Vec<(usize, usize)> = Vec::new();
+        let mut links: Vec<(usize, EntityLink)> = Vec::new();
+
+        for target in &self.targets {
+            let needle = target.name.to_ascii_lowercase();
+            let found = haystack
+                .match_indices(&needle)
+                .map(|(i, _)| i)
+                .find(|&start| {
+                    let end = start + needle.len();
+                    is_word_boundary(text, start, end)
+                        && !taken.iter().any(|&(s, e)| start < e && s < end)
+                });
+            if let Some(start) = found {
+                let end = start + needle.len();
+                taken.push((start, end));
+                links.push((
+                    start,
+                    EntityLink {
+                        mention: text[start..end].to_string(),
+                        kind: target.kind.to_string(),
+                        entity: PROFILE_ENTITY.to_string(),
+                        slot: PROFILE_SLOT.to_string(),
+                        pointer: target.pointer.clone(),
+                    },
+                ));
+            }
+        }
+
+        links.sort_by_key(|(start, _)| *start);
+        links.into_iter().map(|(_, link)| link).collect()
+    }
+}
+
+/// Whether `text[start..end]` is not part of a longer word.
+fn is_word_boundary(text: &str, start: usize, end: usize) -> bool {
+    let before = text[..start].chars().next_back();
+    let after = text[end..].chars().next();
+    !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memvid::MockSearcher;
+
+    const PROFILE: &str = r#"{
+        "experience": [
+            {"company": "Siemens", "role": "Engineering Manager"},
+            {"company": "Acme Robotics"}
+        ],
+        "education": [{"school": "TU Munich"}],
+        "skills": {"strong": ["Rust", "Rust Async"], "moderate": ["Go"], "gaps": []}
+    }"#;
+
+    #[test]
+    fn test_links_in_order_of_appearance() {
+        let linker = EntityLinker::from_profile(PROFILE);
+        let links = linker.link("Led rust async teams at SIEMENS after TU Munich.");
+
+        let found: Vec<(&str, &str, &str)> = links
+            .iter()
+            .map(|l| (l.mention.as_str(), l.kind.as_str(), l.pointer.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("rust async", "technology", "/skills/strong/1"),
+                ("SIEMENS", "company", "/experience/0"),
+                ("TU Munich", "school", "/education/0"),
+            ]
+        );
+        assert!(links.iter().all(|l| l.entity == PROFILE_ENTITY));
+    }
+
+    #[test]
+    fn test_requires_whole_words() {
+        let linker = EntityLinker::from_profile(PROFILE);
+
+        // "Go" inside "Google" and "good" is not a mention
+        assert!(linker.link("Worked at Google on good things").is_empty());
+        assert_eq!(linker.link("Wrote Go, then Rust.").len(), 2);
+    }
+
+    #[test]
+    fn test_malformed_profile_links_nothing() {
+        assert!(EntityLinker::from_profile("not json")
+            .link("Siemens")
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_load_from_mock_profile() {
+        let profile = Profile::load(&MockSearcher::new()).await.unwrap();
+        let links = profile.linker().link("Platform work at Siemens using Rust");
+
+        assert_eq!(links.len(), 2);
+        assert_eq!(links[0].kind, "company");
+        assert_eq!(links[1].mention, "Rust");
+    }
+}
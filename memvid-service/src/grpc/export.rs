@@ -0,0 +1,385 @@
+//! Flow control for bulk export streams.
+//!
+//! Exports are paced two ways: each stream may only have a small window of
+//! batches awaiting client acknowledgment, and all exports share one
+//! bandwidth cap. Batches are produced one at a time, so a large export
+//! holds the blocking pool and index lock only briefly between sends.
+
+use prost::Message;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, Mutex};
+use tokio_stream::{Stream, StreamExt};
+use tonic::Status;
+use tracing::{info, warn};
+
+use crate::error::ServiceError;
+use crate::metrics;
+
+/// Default items per export batch.
+const DEFAULT_BATCH_SIZE: u32 = 64;
+
+/// Upper bound on items per export batch.
+const MAX_BATCH_SIZE: u32 = 256;
+
+/// Default unacknowledged batches in flight.
+const DEFAULT_WINDOW: u32 = 2;
+
+/// Upper bound on unacknowledged batches in flight.
+const MAX_WINDOW: u32 = 8;
+
+/// Default bandwidth cap shared by all exports (1 MiB/s).
+pub const DEFAULT_MAX_BYTES_PER_SEC: u64 = 1_048_576;
+
+/// Default wait for a client acknowledgment.
+pub const DEFAULT_ACK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Items per batch for a requested size (0 = default).
+pub fn batch_size(requested: u32) -> usize {
+    let size = match requested {
+        0 => DEFAULT_BATCH_SIZE,
+        n => n.min(MAX_BATCH_SIZE),
+    };
+    size as usize
+}
+
+/// Batches in flight for a requested window (0 = default).
+pub fn window(requested: u32) -> u64 {
+    let window = match requested {
+        0 => DEFAULT_WINDOW,
+        n => n.min(MAX_WINDOW),
+    };
+    window as u64
+}
+
+/// Token bucket shared by every export stream.
+///
+/// Waiters queue on an async mutex, so streams are served in FIFO order and
+/// none can starve the others.
+pub struct BandwidthLimiter {
+    bytes_per_sec: u64,
+    bucket: Mutex<Bucket>,
+}
+
+struct Bucket {
+    available: f64,
+    refilled: Instant,
+}
+
+impl BandwidthLimiter {
+    /// Create a limiter allowing `bytes_per_sec` with one second of burst
+    /// (0 = unlimited).
+    pub fn new(bytes_per_sec: u64) -> Self {
+        Self {
+            bytes_per_sec,
+            bucket: Mutex::new(Bucket {
+                available: bytes_per_sec as f64,
+                refilled: Instant::now(),
+            }),
+        }
+    }
+
+    /// Wait until `bytes` may be sent and return how long that took.
+    ///
+    /// A batch larger than the burst is let through once the bucket has
+    /// paid off the deficit, so oversized batches slow down but never stall.
+    pub async fn acquire(&self, bytes: usize) -> Duration {
+        if self.bytes_per_sec == 0 {
+            return Duration::ZERO;
+        }
+        let started = Instant::now();
+        let rate = self.bytes_per_sec as f64;
+
+        let mut bucket = self.bucket.lock().await;
+        let now = Instant::now();
+        bucket.available =
+            (bucket.available + now.duration_since(bucket.refilled).as_secs_f64() * rate).min(rate);
+        bucket.refilled = now;
+        bucket.available -= bytes as f64;
+
+        if bucket.available < 0.0 {
+            tokio::time::sleep(Duration::from_secs_f64(-bucket.available / rate)).await;
+        }
+        started.elapsed()
+    }
+}
+
+/// Acknowledgment window for one export stream.
+pub struct AckWindow<S, M> {
+    acks: S,
+    ack_of: fn(M) -> Option<u64>,
+    window: u64,
+    timeout: Duration,
+    sent: u64,
+    acked: u64,
+}
+
+impl<S, M> AckWindow<S, M>
+where
+    S: Stream<Item = Result<M, Status>> + Unpin,
+{
+    /// Track acknowledgments read from `acks`; `ack_of` extracts the acked
+    /// sequence from a client message (None = not an acknowledgment).
+    pub fn new(acks: S, ack_of: fn(M) -> Option<u64>, window: u64, timeout: Duration) -> Self {
+        Self {
+            acks,
+            ack_of,
+            window,
+            timeout,
+            sent: 0,
+            acked: 0,
+        }
+    }
+
+    /// Allocate the sequence number for the next batch.
+    pub fn next_sequence(&mut self) -> u64 {
+        self.sent += 1;
+        self.sent
+    }
+
+    /// Wait until another batch may be sent.
+    ///
+    /// Returns false if the client closed its side of the stream.
+    pub async fn wait_for_credit(&mut self) -> Result<bool, ServiceError> {
+        while self.sent - self.acked >= self.window {
+            let message = tokio::time::timeout(self.timeout, self.acks.next())
+                .await
+                .map_err(|_| {
+                    ServiceError::DeadlineExceeded(format!(
+                        "no export acknowledgment within {}s",
+                        self.timeout.as_secs()
+                    ))
+                })?;
+            let message = match message {
+                None => return Ok(false),
+                Some(Err(status)) => {
+                    return Err(ServiceError::Internal(format!(
+                        "export stream error: {}",
+                        status.message()
+                    )))
+                }
+                Some(Ok(message)) => message,
+            };
+            match (self.ack_of)(message) {
+                Some(sequence) if sequence <= self.sent => {
+                    self.acked = self.acked.max(sequence);
+                }
+                Some(sequence) => {
+                    return Err(ServiceError::InvalidRequest(format!(
+                        "acknowledgment for unsent batch {}",
+                        sequence
+                    )))
+                }
+                None => {
+                    return Err(ServiceError::InvalidRequest(
+                        "expected an export acknowledgment".into(),
+                    ))
+                }
+            }
+        }
+        Ok(true)
+    }
+}
+
+/// Produces the batches of one export.
+#[tonic::async_trait]
+pub trait ExportSource: Send {
+    type Batch: Message + Send + 'static;
+
+    /// Produce the batch numbered `sequence` and whether it is the last.
+    async fn next_batch(&mut self, sequence: u64) -> Result<(Self::Batch, bool), ServiceError>;
+}
+
+/// Send batches from `source` to `tx`, paced by the client's
+/// acknowledgments and the shared bandwidth cap.
+///
+/// Runs until the export completes, the client goes away, or an error is
+/// sent to the client as the final stream item.
+pub async fn run_export<E, S, M>(
+    rpc: &'static str,
+    mut source: E,
+    mut acks: AckWindow<S, M>,
+    limiter: Arc<BandwidthLimiter>,
+    tx: mpsc::Sender<Result<E::Batch, Status>>,
+) where
+    E: ExportSource,
+    S: Stream<Item = Result<M, Status>> + Unpin,
+{
+    match pump(rpc, &mut source, &mut acks, &limiter, &tx).await {
+        Ok(batches) => info!(rpc, batches, "Export finished"),
+        Err(e) => {
+            warn!(rpc, error = %e, "Export aborted");
+            let _ = tx.send(Err(e.into())).await;
+        }
+    }
+}
+
+async fn pump<E, S, M>(
+    rpc: &'static str,
+    source: &mut E,
+    acks: &mut AckWindow<S, M>,
+    limiter: &BandwidthLimiter,
+    tx: &mpsc::Sender<Result<E::Batch, Status>>,
+) -> Result<u64, ServiceError>
+where
+    E: ExportSource,
+    S: Stream<Item = Result<M, Status>> + Unpin,
+{
+    loop {
+        let waiting = Instant::now();
+        if !acks.wait_for_credit().await? {
+            info!(rpc, "Client closed export stream");
+            return Ok(acks.sent);
+        }
+        metrics::record_export_wait(rpc, "ack", waiting.elapsed());
+
+        let sequence = acks.next_sequence();
+        let (batch, done) = source.next_batch(sequence).await?;
+        let bytes = batch.encoded_len();
+        metrics::record_export_wait(rpc, "bandwidth", limiter.acquire(bytes).await);
+
+        if tx.send(Ok(batch)).await.is_err() {
+            info!(rpc, "Client went away during export");
+            return Ok(sequence);
+        }
+        metrics::record_export_batch(rpc, bytes);
+
+        if done {
+            return Ok(sequence);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generated::memvid::v1::TrimInfo;
+    use tokio_stream::wrappers::ReceiverStream;
+
+    /// Emits `total` batches whose payload is the sequence number.
+    struct Counter {
+        total: u64,
+    }
+
+    #[tonic::async_trait]
+    impl ExportSource for Counter {
+        type Batch = TrimInfo;
+
+        async fn next_batch(&mut self, sequence: u64) -> Result<(TrimInfo, bool), ServiceError> {
+            let batch = TrimInfo {
+                original_bytes: sequence as i32,
+                ..Default::default()
+            };
+            Ok((batch, sequence >= self.total))
+        }
+    }
+
+    type Acks = ReceiverStream<Result<u64, Status>>;
+
+    fn ack_window(
+        window: u64,
+        timeout: Duration,
+    ) -> (mpsc::Sender<Result<u64, Status>>, AckWindow<Acks, u64>) {
+        let (tx, rx) = mpsc::channel(16);
+        (
+            tx,
+            AckWindow::new(ReceiverStream::new(rx), Some, window, timeout),
+        )
+    }
+
+    #[test]
+    fn test_requested_sizes_are_capped() {
+        assert_eq!(batch_size(0), 64);
+        assert_eq!(batch_size(10_000), 256);
+        assert_eq!(window(0), 2);
+        assert_eq!(window(100), 8);
+    }
+
+    #[tokio::test]
+    async fn test_unlimited_bandwidth_never_waits() {
+        let limiter = BandwidthLimiter::new(0);
+        assert_eq!(limiter.acquire(usize::MAX).await, Duration::ZERO);
+    }
+
+    #[tokio::test]
+    async fn test_bandwidth_cap_paces_sends() {
+        let limiter = BandwidthLimiter::new(10_000);
+
+        // The first second of burst goes straight through, the next 500 bytes
+        // cost 50ms
+        assert!(limiter.acquire(10_000).await < Duration::from_millis(10));
+        let waited = limiter.acquire(500).await;
+        assert!(waited >= Duration::from_millis(45), "waited {:?}", waited);
+    }
+
+    #[tokio::test]
+    async fn test_window_blocks_until_acked() {
+        let (ack_tx, mut acks) = ack_window(2, Duration::from_secs(5));
+
+        assert!(acks.wait_for_credit().await.unwrap());
+        acks.next_sequence();
+        assert!(acks.wait_for_credit().await.unwrap());
+        acks.next_sequence();
+
+        // Window full: the ack for batch 1 frees one slot
+        ack_tx.send(Ok(1)).await.unwrap();
+        assert!(acks.wait_for_credit().await.unwrap());
+        assert_eq!(acks.acked, 1);
+    }
+
+    #[tokio::test]
+    async fn test_missing_ack_times_out() {
+        let (_ack_tx, mut acks) = ack_window(1, Duration::from_millis(20));
+        acks.next_sequence();
+
+        let err = acks.wait_for_credit().await.unwrap_err();
+        assert!(matches!(err, ServiceError::DeadlineExceeded(_)));
+    }
+
+    #[tokio::test]
+    async fn test_ack_for_unsent_batch_rejected() {
+        let (ack_tx, mut acks) = ack_window(1, Duration::from_secs(5));
+        acks.next_sequence();
+        ack_tx.send(Ok(7)).await.unwrap();
+
+        let err = acks.wait_for_credit().await.unwrap_err();
+        assert!(matches!(err, ServiceError::InvalidRequest(_)));
+    }
+
+    #[tokio::test]
+    async fn test_run_export_delivers_all_batches() {
+        let (ack_tx, acks) = ack_window(1, Duration::from_secs(5));
+        let (tx, mut rx) = mpsc::channel(1);
+        let limiter = Arc::new(BandwidthLimiter::new(0));
+        tokio::spawn(run_export("test", Counter { total: 3 }, acks, limiter, tx));
+
+        let mut received = Vec::new();
+        while let Some(batch) = rx.recv().await {
+            let sequence = batch.unwrap().original_bytes as u64;
+            received.push(sequence);
+            // The export stops reading acks after the final batch
+            let _ = ack_tx.send(Ok(sequence)).await;
+        }
+        assert_eq!(received, vec![1, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_run_export_stops_when_client_closes() {
+        let (ack_tx, acks) = ack_window(1, Duration::from_secs(5));
+        let (tx, mut rx) = mpsc::channel(1);
+        let limiter = Arc::new(BandwidthLimiter::new(0));
+        let export = tokio::spawn(run_export(
+            "test",
+            Counter { total: 100 },
+            acks,
+            limiter,
+            tx,
+        ));
+
+        assert!(rx.recv().await.unwrap().is_ok());
+        drop(ack_tx);
+
+        export.await.unwrap();
+        assert!(rx.recv().await.is_none());
+    }
+}
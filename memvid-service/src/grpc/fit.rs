@@ -0,0 +1,517 @@
+//! Job description matching for FitAssessment.
+//!
+//! A job description is split into requirements: its bulleted or numbered
+//! lines, and the prose sentences asking for experience, skills or
+//! knowledge (every sentence, when none does). Each requirement falls under
+//! one aspect (skills, experience or leadership), and evidence is retrieved
+//! once per aspect from that aspect's requirements.
+//!
+//! A requirement is rated by the share of its terms that the best matching
+//! hit mentions, compared by stem so "managed" covers "management". This is
+//! term overlap rather than judgment: a resume describing a skill in other
+//! words than the description rates lower than it deserves.
+
+use std::collections::HashSet;
+
+use super::highlight::{stem, words};
+use super::topics::STOPWORDS;
+use crate::generated::memvid::v1::{
+    AspectScore, FitAssessmentResponse, FitLevel, RequirementMatch,
+};
+use crate::memvid::SearchResult;
+
+/// Requirements rated per description; later ones are ignored.
+pub const MAX_REQUIREMENTS: usize = 25;
+
+/// Best-hit coverage from which a requirement is a strong match.
+pub const STRONG_COVERAGE: f32 = 0.75;
+
+/// Best-hit coverage from which a requirement is a moderate match.
+pub const MODERATE_COVERAGE: f32 = 0.5;
+
+/// Longest retrieval query per aspect, in characters.
+const MAX_QUERY_CHARS: usize = 500;
+
+/// Supporting hits listed per requirement.
+const MAX_SUPPORTING_HITS: usize = 3;
+
+/// Job-ad wording that says nothing about what is asked for.
+const FILLER_WORDS: &[&str] = &[
+    "ability",
+    "able",
+    "background",
+    "candidate",
+    "deep",
+    "demonstrated",
+    "excellent",
+    "expert",
+    "expertise",
+    "experience",
+    "experienced",
+    "familiar",
+    "familiarity",
+    "good",
+    "hands",
+    "ideal",
+    "ideally",
+    "including",
+    "knowledge",
+    "least",
+    "minimum",
+    "must",
+    "nice",
+    "plus",
+    "preferred",
+    "proficiency",
+    "proficient",
+    "proven",
+    "required",
+    "skills",
+    "solid",
+    "strong",
+    "understanding",
+    "using",
+    "we",
+    "working",
+    "year",
+    "years",
+];
+
+/// Word prefixes marking a prose sentence as a requirement.
+const REQUIREMENT_CUES: &[&str] = &[
+    "ability",
+    "background",
+    "degree",
+    "experience",
+    "expert",
+    "familiar",
+    "knowledge",
+    "must",
+    "prefer",
+    "proficien",
+    "require",
+    "skill",
+    "understanding",
+    "year",
+];
+
+/// Word prefixes of leadership requirements.
+const LEADERSHIP_CUES: &[&str] = &[
+    "coach",
+    "director",
+    "executive",
+    "hire",
+    "hiring",
+    "lead",
+    "led",
+    "manag",
+    "mentor",
+    "people",
+    "stakeholder",
+    "strateg",
+    "team",
+];
+
+/// Word prefixes of experience requirements (checked after leadership).
+const EXPERIENCE_CUES: &[&str] = &[
+    "built",
+    "delivered",
+    "experience",
+    "history",
+    "industry",
+    "production",
+    "shipped",
+    "track",
+    "worked",
+    "year",
+];
+
+/// Part of the resume a requirement is matched against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FitAspect {
+    /// Languages, technologies, tools
+    Skills,
+    /// Years, domains, delivered work
+    Experience,
+    /// Teams, management, strategy
+    Leadership,
+}
+
+impl FitAspect {
+    /// Every aspect, in retrieval order.
+    pub const ALL: [Self; 3] = [Self::Skills, Self::Experience, Self::Leadership];
+
+    /// Name reported in RequirementMatch.aspect and AspectScore.aspect.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Skills => "skills",
+            Self::Experience => "experience",
+            Self::Leadership => "leadership",
+        }
+    }
+
+    /// Words steering the aspect's retrieval towards its resume sections;
+    /// on their own, a query for the aspect in general.
+    pub fn hint(self) -> &'static str {
+        match self {
+            Self::Skills => "skills technologies",
+            Self::Experience => "experience projects",
+            Self::Leadership => "leadership team management",
+        }
+    }
+
+    /// Aspect of a requirement, by its wording.
+    fn of(requirement: &str) -> Self {
+        let cued = |cues: &[&str]| {
+            words(requirement).any(|(_, word)| {
+                let word = word.to_lowercase();
+                cues.iter().any(|cue| word.starts_with(cue))
+            })
+        };
+        if cued(LEADERSHIP_CUES) {
+            Self::Leadership
+        } else if cued(EXPERIENCE_CUES) {
+            Self::Experience
+        } else {
+            Self::Skills
+        }
+    }
+}
+
+/// One requirement of a job description.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Requirement {
+    /// The requirement as written
+    pub text: String,
+    /// Aspect it is retrieved under
+    pub aspect: FitAspect,
+    /// Lowercased terms rated against the evidence, without filler
+    terms: Vec<String>,
+}
+
+impl Requirement {
+    fn new(text: &str) -> Option<Self> {
+        let text = text.trim().trim_end_matches(['.', ';', '!', ',']).trim();
+        let mut terms: Vec<String> = Vec::new();
+        for (_, word) in words(text) {
+            let word = word.to_lowercase();
+            let filler = word.chars().count() < 2
+                || word.chars().all(|c| c.is_ascii_digit())
+                || STOPWORDS.contains(&word.as_str())
+                || FILLER_WORDS.contains(&word.as_str());
+            if !filler && !terms.contains(&word) {
+                terms.push(word);
+            }
+        }
+        (!terms.is_empty()).then(|| Self {
+            text: text.to_string(),
+            aspect: FitAspect::of(text),
+            terms,
+        })
+    }
+}
+
+/// The requirements of `job_description`, in order, at most
+/// [`MAX_REQUIREMENTS`].
+pub fn requirements(job_description: &str) -> Vec<Requirement> {
+    // (text, whether it is a list item)
+    let mut candidates: Vec<(&str, bool)> = Vec::new();
+    for line in job_description.lines().map(str::trim) {
+        // Section headings ("Requirements:") introduce requirements
+        if line.is_empty() || line.ends_with(':') {
+            continue;
+        }
+        match strip_bullet(line) {
+            Some(item) => candidates.push((item, true)),
+            None => candidates.extend(sentences(line).map(|sentence| (sentence, false))),
+        }
+    }
+    let cued = |sentence: &str| {
+        words(sentence).any(|(_, word)| {
+            let word = word.to_lowercase();
+            REQUIREMENT_CUES.iter().any(|cue| word.starts_with(cue))
+        })
+    };
+    let selective = candidates
+        .iter()
+        .any(|&(text, listed)| listed || cued(text));
+
+    let mut seen = HashSet::new();
+    candidates
+        .into_iter()
+        .filter(|&(text, listed)| !selective || listed || cued(text))
+        .filter_map(|(text, _)| Requirement::new(text))
+        .filter(|requirement| seen.insert(requirement.text.to_lowercase()))
+        .take(MAX_REQUIREMENTS)
+        .collect()
+}
+
+/// Retrieval queries for `requirements`, one per aspect that has any.
+pub fn aspect_queries(requirements: &[Requirement]) -> Vec<String> {
+    FitAspect::ALL
+        .iter()
+        .filter_map(|aspect| aspect_query(*aspect, requirements))
+        .collect()
+}
+
+/// Stems of the terms of all `requirements`.
+pub fn term_stems(requirements: &[Requirement]) -> HashSet<String> {
+    requirements
+        .iter()
+        .flat_map(|requirement| &requirement.terms)
+        .map(|term| stem(term))
+        .collect()
+}
+
+/// Retrieval query for the requirements under `aspect`, or None when it
+/// has none.
+pub fn aspect_query(aspect: FitAspect, requirements: &[Requirement]) -> Option<String> {
+    let texts: Vec<&str> = requirements
+        .iter()
+        .filter(|requirement| requirement.aspect == aspect)
+        .map(|requirement| requirement.text.as_str())
+        .collect();
+    if texts.is_empty() {
+        return None;
+    }
+    let query = format!("{} {}", aspect.hint(), texts.join("; "));
+    Some(query.chars().take(MAX_QUERY_CHARS).collect())
+}
+
+/// Rate `requirements` against `evidence`. The response's evidence is left
+/// for the caller to fill with the encoded hits.
+pub fn assess(requirements: &[Requirement], evidence: &[SearchResult]) -> FitAssessmentResponse {
+    let stems: Vec<HashSet<String>> = evidence.iter().map(hit_stems).collect();
+    let mut response = FitAssessmentResponse::default();
+    let mut aspects = [(0.0, 0usize); 3];
+    for requirement in requirements {
+        let rated = rate(requirement, &stems);
+        let credit = credit(rated.level());
+        let aspect = FitAspect::ALL
+            .iter()
+            .position(|aspect| *aspect == requirement.aspect)
+            .unwrap_or_default();
+        aspects[aspect].0 += credit;
+        aspects[aspect].1 += 1;
+        response.overall_score += credit;
+        match rated.level() {
+            FitLevel::Strong => response.strong.push(rated),
+            FitLevel::Moderate => response.moderate.push(rated),
+            _ => response.gaps.push(rated),
+        }
+    }
+    if !requirements.is_empty() {
+        response.overall_score /= requirements.len() as f32;
+    }
+    response.aspects = FitAspect::ALL
+        .iter()
+        .zip(aspects)
+        .filter(|(_, (_, count))| *count > 0)
+        .map(|(aspect, (credit, count))| AspectScore {
+            aspect: aspect.name().to_string(),
+            score: credit / count as f32,
+            requirements: count as i32,
+        })
+        .collect();
+    response
+}
+
+/// Score a requirement at `level` contributes.
+fn credit(level: FitLevel) -> f32 {
+    match level {
+        FitLevel::Strong => 1.0,
+        FitLevel::Moderate => 0.5,
+        _ => 0.0,
+    }
+}
+
+/// Rate one requirement against the word stems of each evidence hit.
+fn rate(requirement: &Requirement, stems: &[HashSet<String>]) -> RequirementMatch {
+    let terms: Vec<String> = requirement.terms.iter().map(|term| stem(term)).collect();
+    let mut coverages: Vec<(usize, f32)> = stems
+        .iter()
+        .enumerate()
+        .map(|(index, hit)| {
+            let matched = terms.iter().filter(|term| hit.contains(*term)).count();
+            (index, matched as f32 / terms.len() as f32)
+        })
+        .filter(|(_, coverage)| *coverage > 0.0)
+        .collect();
+    coverages.sort_by(|a, b| b.1.total_cmp(&a.1));
+    let coverage = coverages.first().map_or(0.0, |(_, coverage)| *coverage);
+    let level = if coverage >= STRONG_COVERAGE {
+        FitLevel::Strong
+    } else if coverage >= MODERATE_COVERAGE {
+        FitLevel::Moderate
+    } else {
+        FitLevel::Gap
+    };
+
+    RequirementMatch {
+        requirement: requirement.text.clone(),
+        aspect: requirement.aspect.name().to_string(),
+        level: level as i32,
+        coverage,
+        evidence: coverages
+            .iter()
+            .take(MAX_SUPPORTING_HITS)
+            .map(|(index, _)| *index as i32 + 1)
+            .collect(),
+        missing_terms: requirement
+            .terms
+            .iter()
+            .zip(&terms)
+            .filter(|(_, stem)| !stems.iter().any(|hit| hit.contains(*stem)))
+            .map(|(term, _)| term.clone())
+            .collect(),
+    }
+}
+
+/// Word stems of a hit's title, snippet and tags.
+fn hit_stems(hit: &SearchResult) -> HashSet<String> {
+    std::iter::once(hit.title.as_str())
+        .chain(std::iter::once(hit.snippet.as_str()))
+        .chain(hit.tags.iter().map(String::as_str))
+        .flat_map(words)
+        .map(|(_, word)| stem(word))
+        .collect()
+}
+
+/// The item of a bulleted ("- ", "* ", "• ") or numbered ("1. ", "2) ")
+/// line.
+fn strip_bullet(line: &str) -> Option<&str> {
+    let rest = match line.strip_prefix(['-', '*', '+', '•', '·', '–', '—']) {
+        Some(rest) => rest,
+        None => {
+            let digits = line.trim_start_matches(|c: char| c.is_ascii_digit());
+            if digits.len() == line.len() {
+                return None;
+            }
+            digits.strip_prefix(['.', ')'])?
+        }
+    };
+    rest.starts_with(char::is_whitespace).then(|| rest.trim())
+}
+
+/// Sentences of a prose line.
+pub(super) fn sentences(line: &str) -> impl Iterator<Item = &str> {
+    let mut rest = line;
+    std::iter::from_fn(move || {
+        let rest_trimmed = rest.trim_start();
+        if rest_trimmed.is_empty() {
+            return None;
+        }
+        let end = rest_trimmed
+            .char_indices()
+            .find(|&(i, c)| {
+                matches!(c, '.' | '!' | '?' | ';')
+                    && rest_trimmed[i + c.len_utf8()..]
+                        .chars()
+                        .next()
+                        .is_none_or(char::is_whitespace)
+            })
+            .map_or(rest_trimmed.len(), |(i, c)| i + c.len_utf8());
+        let sentence = &rest_trimmed[..end];
+        rest = &rest_trimmed[end..];
+        Some(sentence)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hit(title: &str, snippet: &str) -> SearchResult {
+        SearchResult {
+            title: title.to_string(),
+            score: 0.5,
+            snippet: snippet.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn texts(requirements: &[Requirement]) -> Vec<&str> {
+        requirements.iter().map(|r| r.text.as_str()).collect()
+    }
+
+    #[test]
+    fn test_listed_requirements_and_cued_prose() {
+        let description = "We are a fast-growing startup in Berlin. You bring 5+ years of \
+                           backend experience.\n\nRequirements:\n- Proficiency in Rust\n\
+                           * Kubernetes and Terraform;\n2) Led teams of 10+ engineers\n\
+                           - Proficiency in Rust";
+        let requirements = requirements(description);
+        assert_eq!(
+            texts(&requirements),
+            [
+                "You bring 5+ years of backend experience",
+                "Proficiency in Rust",
+                "Kubernetes and Terraform",
+                "Led teams of 10+ engineers",
+            ]
+        );
+        let aspects: Vec<FitAspect> = requirements.iter().map(|r| r.aspect).collect();
+        assert_eq!(
+            aspects,
+            [
+                FitAspect::Experience,
+                FitAspect::Skills,
+                FitAspect::Skills,
+                FitAspect::Leadership,
+            ]
+        );
+        assert_eq!(requirements[1].terms, ["rust"]);
+    }
+
+    #[test]
+    fn test_uncued_prose_is_rated_sentence_by_sentence() {
+        let requirements = requirements("Rust and Go. Kafka pipelines!");
+        assert_eq!(texts(&requirements), ["Rust and Go", "Kafka pipelines"]);
+        assert!(super::requirements("Requirements:\n\n").is_empty());
+    }
+
+    #[test]
+    fn test_aspect_query_joins_requirements() {
+        let requirements = requirements("- Rust\n- Go\n- Mentored engineers");
+        assert_eq!(
+            aspect_query(FitAspect::Skills, &requirements).unwrap(),
+            "skills technologies Rust; Go"
+        );
+        assert!(aspect_query(FitAspect::Experience, &requirements).is_none());
+    }
+
+    #[test]
+    fn test_assess_rates_by_best_hit_coverage() {
+        let evidence = [
+            hit("Skills", "Proficient in Rust, Python and Go."),
+            hit("Siemens", "Managed a team building Kafka pipelines."),
+        ];
+        let requirements =
+            requirements("- Rust and Go\n- Kafka and Flink\n- Haskell\n- Managing teams");
+        let report = assess(&requirements, &evidence);
+
+        let strong: Vec<&str> = report
+            .strong
+            .iter()
+            .map(|m| m.requirement.as_str())
+            .collect();
+        assert_eq!(strong, ["Rust and Go", "Managing teams"]);
+        assert_eq!(report.strong[0].evidence, [1]);
+        assert_eq!(report.strong[1].evidence, [2]);
+        assert_eq!(report.moderate[0].requirement, "Kafka and Flink");
+        assert_eq!(report.moderate[0].missing_terms, ["flink"]);
+        assert_eq!(report.gaps[0].requirement, "Haskell");
+        assert!(report.gaps[0].evidence.is_empty());
+        assert_eq!(report.gaps[0].level(), FitLevel::Gap);
+
+        // (1 + 0.5 + 0 + 1) / 4
+        assert!((report.overall_score - 0.625).abs() < 1e-6);
+        let aspects: Vec<(&str, i32)> = report
+            .aspects
+            .iter()
+            .map(|a| (a.aspect.as_str(), a.requirements))
+            .collect();
+        assert_eq!(aspects, [("skills", 3), ("leadership", 1)]);
+        assert!((report.aspects[0].score - 0.5).abs() < 1e-6);
+    }
+}
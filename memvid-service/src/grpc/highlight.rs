@@ -0,0 +1,176 @@
+//! Query-term highlighting in search snippets.
+//!
+//! With `highlight` set on a Search request, every snippet word sharing a
+//! stem with a query word (or one of its acronym expansions) is wrapped in
+//! the requested markers, so "managed" and "management" both match a query
+//! for "manage". Stems come from a light suffix-stripping stemmer, not a
+//! full morphological one. Markers are inserted after output encoding, so
+//! they reach the client verbatim.
+
+use std::collections::HashSet;
+
+use crate::generated::memvid::v1::{Highlight, OutputEncoding};
+
+use super::sanitize::encode;
+use super::topics::STOPWORDS;
+
+/// Marker inserted before a match when the request leaves it empty.
+pub const DEFAULT_PRE_TAG: &str = "<em>";
+
+/// Marker inserted after a match when the request leaves it empty.
+pub const DEFAULT_POST_TAG: &str = "</em>";
+
+/// Suffixes stripped by [`stem`], tried in order.
+const SUFFIXES: &[&str] = &[
+    "ations", "ation", "ments", "ment", "ings", "ing", "ies", "ers", "er", "es", "ed", "ly", "s",
+    "e",
+];
+
+/// Suffixes are only stripped when at least this many characters remain.
+const MIN_STEM_CHARS: usize = 3;
+
+/// Wraps snippet words matching a query in markers.
+#[derive(Debug, Clone)]
+pub struct Highlighter {
+    stems: HashSet<String>,
+    pre_tag: String,
+    post_tag: String,
+}
+
+impl Highlighter {
+    /// Highlighter for `query` and extra `terms` (e.g. acronym expansions),
+    /// or None when the request did not ask for highlighting.
+    pub fn new(options: Option<&Highlight>, query: &str, terms: &[String]) -> Option<Self> {
+        let options = options?;
+        let stems = std::iter::once(query)
+            .chain(terms.iter().map(String::as_str))
+            .flat_map(words)
+            .map(|(_, word)| word.to_lowercase())
+            .filter(|word| !STOPWORDS.contains(&word.as_str()))
+            .map(|word| stem(&word))
+            .collect();
+        let marker =
+            |tag: &str, default: &str| if tag.is_empty() { default } else { tag }.to_string();
+        Some(Self {
+            stems,
+            pre_tag: marker(&options.pre_tag, DEFAULT_PRE_TAG),
+            post_tag: marker(&options.post_tag, DEFAULT_POST_TAG),
+        })
+    }
+
+    /// `text` with every matching word wrapped in the markers.
+    pub fn apply(&self, text: &str) -> String {
+        let mut out = String::with_capacity(text.len());
+        let mut last = 0;
+        for (start, word) in words(text) {
+            // Skip the names of HTML entities left by output encoding
+            let entity = text[..start].ends_with('&') || text[..start].ends_with("&#");
+            if entity || !self.stems.contains(&stem(word)) {
+                continue;
+            }
+            out.push_str(&text[last..start]);
+            out.push_str(&self.pre_tag);
+            out.push_str(word);
+            out.push_str(&self.post_tag);
+            last = start + word.len();
+        }
+        out.push_str(&text[last..]);
+        out
+    }
+}
+
+/// Turns a snippet's source text into what the client sees: output
+/// encoding first, then highlight markers when requested.
+#[derive(Debug, Clone)]
+pub struct SnippetRenderer {
+    encoding: OutputEncoding,
+    highlighter: Option<Highlighter>,
+}
+
+impl SnippetRenderer {
+    pub fn new(encoding: OutputEncoding, highlighter: Option<Highlighter>) -> Self {
+        Self {
+            encoding,
+            highlighter,
+        }
+    }
+
+    /// `snippet` encoded and highlighted.
+    pub fn render(&self, snippet: &str) -> String {
+        let encoded = encode(snippet, self.encoding);
+        match &self.highlighter {
+            Some(highlighter) => highlighter.apply(&encoded),
+            None => encoded,
+        }
+    }
+}
+
+/// Alphanumeric runs of `text` with their byte offsets.
+pub(super) fn words(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(move |word| (word.as_ptr() as usize - text.as_ptr() as usize, word))
+}
+
+/// Lowercased `word` with common English suffixes stripped.
+pub(super) fn stem(word: &str) -> String {
+    let mut stem = word.to_lowercase();
+    'strip: loop {
+        for suffix in SUFFIXES {
+            if let Some(rest) = stem.strip_suffix(suffix) {
+                if rest.chars().count() >= MIN_STEM_CHARS {
+                    stem = if *suffix == "ies" {
+                        format!("{}y", rest)
+                    } else {
+                        rest.to_string()
+                    };
+                    continue 'strip;
+                }
+            }
+        }
+        return stem;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn highlighter(query: &str) -> Highlighter {
+        Highlighter::new(Some(&Highlight::default()), query, &[]).unwrap()
+    }
+
+    #[test]
+    fn test_wraps_stemmed_matches() {
+        let text = highlighter("manage teams").apply("Managed two teams; team management.");
+        assert_eq!(
+            text,
+            "<em>Managed</em> two <em>teams</em>; <em>team</em> <em>management</em>."
+        );
+    }
+
+    #[test]
+    fn test_skips_stopwords_and_entities() {
+        let text = highlighter("what is your amp experience")
+            .apply("Tom &amp; Jerry: experienced, amp tuning");
+        assert_eq!(
+            text,
+            "Tom &amp; Jerry: <em>experienced</em>, <em>amp</em> tuning"
+        );
+    }
+
+    #[test]
+    fn test_custom_markers_and_extra_terms() {
+        let options = Highlight {
+            pre_tag: "[".to_string(),
+            post_tag: "]".to_string(),
+        };
+        let highlighter =
+            Highlighter::new(Some(&options), "IIoT", &["Industrial IoT".to_string()]).unwrap();
+        assert_eq!(
+            highlighter.apply("Industrial IoT (IIoT) platforms"),
+            "[Industrial] [IoT] ([IIoT]) platforms"
+        );
+        assert!(Highlighter::new(None, "IIoT", &[]).is_none());
+    }
+}
@@ -0,0 +1,143 @@
+//! Usage tracking for legacy v1 request fields.
+//!
+//! Some v1 fields are ignored, superseded, or used with sentinel values that
+//! v2 expresses differently. Each use is counted per field so the v1 → v2
+//! migration can be driven by data, and the caller gets a deprecation
+//! warning in the `x-deprecation-warning` response header.
+
+use tonic::metadata::MetadataValue;
+use tonic::Response;
+
+use crate::generated::memvid::v1::{AskRequest, SearchRequest};
+use crate::metrics;
+
+/// Response header carrying the deprecation warning.
+pub const DEPRECATION_HEADER: &str = "x-deprecation-warning";
+
+/// A legacy field found in a request, with what to send instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LegacyField {
+    /// Field name as it appears in the proto
+    pub field: &'static str,
+    /// Replacement guidance for clients
+    pub advice: &'static str,
+}
+
+const fn legacy(field: &'static str, advice: &'static str) -> LegacyField {
+    LegacyField { field, advice }
+}
+
+/// Legacy fields used by a v1 Search request.
+pub fn search_fields(req: &SearchRequest) -> Vec<LegacyField> {
+    let mut fields = Vec::new();
+    if req.min_relevance != 0.0 {
+        fields.push(legacy("min_relevance", "ignored by Search; remove it"));
+    }
+    if req.mode != 0 {
+        fields.push(legacy("mode", "ignored by Search; remove it"));
+    }
+    if req.budget_ms == Some(0) {
+        fields.push(legacy("budget_ms", "omit budget_ms instead of sending 0"));
+    }
+    fields
+}
+
+/// Legacy fields used by a v1 Ask request.
+pub fn ask_fields(req: &AskRequest) -> Vec<LegacyField> {
+    let mut fields = Vec::new();
+    if req.start != 0 {
+        fields.push(legacy("start", "use start_expr (v2: start)"));
+    }
+    if req.end != 0 {
+        fields.push(legacy("end", "use end_expr (v2: end)"));
+    }
+    if req.as_of_ts.is_some() {
+        fields.push(legacy("as_of_ts", "use as_of_expr (v2: as_of)"));
+    }
+    if req.as_of_frame.is_some_and(|frame| frame < 0) {
+        fields.push(legacy(
+            "as_of_frame",
+            "omit as_of_frame instead of a negative value",
+        ));
+    }
+    fields
+}
+
+/// Count each legacy field and attach a deprecation warning to the response.
+pub fn report<T>(rpc: &'static str, fields: &[LegacyField], response: &mut Response<T>) {
+    if fields.is_empty() {
+        return;
+    }
+    for field in fields {
+        metrics::record_legacy_field(rpc, field.field);
+    }
+    let warning = fields
+        .iter()
+        .map(|f| format!("{} is deprecated ({})", f.field, f.advice))
+        .collect::<Vec<_>>()
+        .join("; ");
+    if let Ok(value) = MetadataValue::try_from(warning) {
+        response.metadata_mut().insert(DEPRECATION_HEADER, value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clean_requests_have_no_legacy_fields() {
+        let search = SearchRequest {
+            query: "rust".to_string(),
+            budget_ms: Some(50),
+            ..Default::default()
+        };
+        assert!(search_fields(&search).is_empty());
+
+        let ask = AskRequest {
+            question: "rust".to_string(),
+            start_expr: "-30d".to_string(),
+            as_of_frame: Some(7),
+            ..Default::default()
+        };
+        assert!(ask_fields(&ask).is_empty());
+    }
+
+    #[test]
+    fn test_search_sentinels_detected() {
+        let req = SearchRequest {
+            min_relevance: 0.5,
+            budget_ms: Some(0),
+            ..Default::default()
+        };
+        let names: Vec<_> = search_fields(&req).iter().map(|f| f.field).collect();
+        assert_eq!(names, vec!["min_relevance", "budget_ms"]);
+    }
+
+    #[test]
+    fn test_ask_integer_timestamps_detected() {
+        let req = AskRequest {
+            start: 1_700_000_000,
+            as_of_ts: Some(1_700_000_000),
+            ..Default::default()
+        };
+        let names: Vec<_> = ask_fields(&req).iter().map(|f| f.field).collect();
+        assert_eq!(names, vec!["start", "as_of_ts"]);
+    }
+
+    #[test]
+    fn test_report_sets_header() {
+        let mut response = Response::new(());
+        report("ask", &[legacy("start", "use start_expr")], &mut response);
+
+        let header = response.metadata().get(DEPRECATION_HEADER).unwrap();
+        assert_eq!(header, "start is deprecated (use start_expr)");
+    }
+
+    #[test]
+    fn test_report_without_fields_leaves_headers_alone() {
+        let mut response = Response::new(());
+        report("ask", &[], &mut response);
+        assert!(response.metadata().get(DEPRECATION_HEADER).is_none());
+    }
+}
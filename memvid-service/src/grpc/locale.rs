@@ -0,0 +1,366 @@
+//! Locale-aware rendering of dates and durations in answers.
+//!
+//! The locale comes from the Ask `locale` field or, when that is empty, from
+//! `accept-language` request metadata forwarded by the REST gateway. ISO dates
+//! (`2021-03-15`, `2021-03`) and year counts ("5 years of experience") in the
+//! answer are rewritten for the requested locale.
+
+use chrono::NaiveDate;
+
+use crate::error::ServiceError;
+
+/// Locales supported for answer rendering.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Locale {
+    En,
+    De,
+    Fr,
+    Es,
+}
+
+impl Locale {
+    /// Parse a BCP 47 tag by its primary language subtag (e.g., "de-AT" -> De).
+    pub fn parse(tag: &str) -> Option<Self> {
+        let primary = tag.trim().split(['-', '_']).next()?.to_ascii_lowercase();
+        match primary.as_str() {
+            "en" => Some(Self::En),
+            "de" => Some(Self::De),
+            "fr" => Some(Self::Fr),
+            "es" => Some(Self::Es),
+            _ => None,
+        }
+    }
+
+    /// Pick the supported locale with the highest q-value from an
+    /// Accept-Language header.
+    pub fn from_accept_language(header: &str) -> Option<Self> {
+        let mut best: Option<(Self, f32)> = None;
+        for entry in header.split(',') {
+            let mut parts = entry.split(';');
+            let tag = parts.next().unwrap_or_default();
+            let q = parts
+                .find_map(|p| p.trim().strip_prefix("q="))
+                .and_then(|q| q.parse::<f32>().ok())
+                .unwrap_or(1.0);
+            if let Some(locale) = Self::parse(tag) {
+                if q > 0.0 && best.is_none_or(|(_, best_q)| q > best_q) {
+                    best = Some((locale, q));
+                }
+            }
+        }
+        best.map(|(locale, _)| locale)
+    }
+
+    /// Resolve the request locale: an explicit field wins over the header.
+    ///
+    /// Returns `Ok(None)` when neither is set; an unsupported explicit locale is
+    /// an error, an unsupported header is ignored.
+    pub fn resolve(
+        field: &str,
+        accept_language: Option<&str>,
+    ) -> Result<Option<Self>, ServiceError> {
+        if !field.trim().is_empty() {
+            return Self::parse(field).map(Some).ok_or_else(|| {
+                ServiceError::InvalidRequest(format!(
+                    "unsupported locale '{}' (supported: en, de, fr, es)",
+                    field
+                ))
+            });
+        }
+        Ok(accept_language.and_then(Self::from_accept_language))
+    }
+
+    /// Resolve the `preferred_language` request field (empty = no preference).
+    pub fn preferred_language(field: &str) -> Result<Option<Self>, ServiceError> {
+        if field.trim().is_empty() {
+            return Ok(None);
+        }
+        Self::parse(field).map(Some).ok_or_else(|| {
+            ServiceError::InvalidRequest(format!(
+                "unsupported preferred_language '{}' (supported: en, de, fr, es)",
+                field
+            ))
+        })
+    }
+
+    /// ISO 639-1 language code, as used in frame language tags.
+    pub fn code(self) -> &'static str {
+        match self {
+            Self::En => "en",
+            Self::De => "de",
+            Self::Fr => "fr",
+            Self::Es => "es",
+        }
+    }
+
+    fn month_name(self, month: u32) -> &'static str {
+        const EN: [&str; 12] = [
+            "January",
+            "February",
+            "March",
+            "April",
+            "May",
+            "June",
+            "July",
+            "August",
+            "September",
+            "October",
+            "November",
+            "December",
+        ];
+        const DE: [&str; 12] = [
+            "Januar",
+            "Februar",
+            "März",
+            "April",
+            "Mai",
+            "Juni",
+            "Juli",
+            "August",
+            "September",
+            "Oktober",
+            "November",
+            "Dezember",
+        ];
+        const FR: [&str; 12] = [
+            "janvier",
+            "février",
+            "mars",
+            "avril",
+            "mai",
+            "juin",
+            "juillet",
+            "août",
+            "septembre",
+            "octobre",
+            "novembre",
+            "décembre",
+        ];
+        const ES: [&str; 12] = [
+            "enero",
+            "febrero",
+            "marzo",
+            "abril",
+            "mayo",
+            "junio",
+            "julio",
+            "agosto",
+            "septiembre",
+            "octubre",
+            "noviembre",
+            "diciembre",
+        ];
+        let names = match self {
+            Self::En => &EN,
+            Self::De => &DE,
+            Self::Fr => &FR,
+            Self::Es => &ES,
+        };
+        names[(month as usize - 1) % 12]
+    }
+
+    /// Format a calendar date ("March 15, 2021", "15. März 2021", ...).
+    pub fn format_date(self, date: NaiveDate) -> String {
+        use chrono::Datelike;
+        let (day, month, year) = (date.day(), self.month_name(date.month()), date.year());
+        match self {
+            Self::En => format!("{} {}, {}", month, day, year),
+            Self::De => format!("{}. {} {}", day, month, year),
+            Self::Fr => format!("{} {} {}", day, month, year),
+            Self::Es => format!("{} de {} de {}", day, month, year),
+        }
+    }
+
+    /// Format a month of a year ("March 2021", "März 2021", ...).
+    pub fn format_month(self, year: i32, month: u32) -> String {
+        match self {
+            Self::Es => format!("{} de {}", self.month_name(month), year),
+            _ => format!("{} {}", self.month_name(month), year),
+        }
+    }
+
+    /// Format a year count such as "3" or "10+", optionally as experience
+    /// ("3 Jahre Erfahrung").
+    pub fn format_years(self, count: &str, experience: bool) -> String {
+        let one = count == "1";
+        let unit = match (self, one) {
+            (Self::En, true) => "year",
+            (Self::En, false) => "years",
+            (Self::De, true) => "Jahr",
+            (Self::De, false) => "Jahre",
+            (Self::Fr, true) => "an",
+            (Self::Fr, false) => "ans",
+            (Self::Es, true) => "año",
+            (Self::Es, false) => "años",
+        };
+        if !experience {
+            return format!("{} {}", count, unit);
+        }
+        match self {
+            Self::En => format!("{} {} of experience", count, unit),
+            Self::De => format!("{} {} Erfahrung", count, unit),
+            Self::Fr => format!("{} {} d'expérience", count, unit),
+            Self::Es => format!("{} {} de experiencia", count, unit),
+        }
+    }
+}
+
+/// Rewrite ISO dates and year counts in `text` for `locale`.
+pub fn localize_answer(text: &str, locale: Locale) -> String {
+    let bytes = text.as_bytes();
+    let mut out = String::with_capacity(text.len());
+    let mut copied = 0;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let at_boundary = i == 0 || !bytes[i - 1].is_ascii_alphanumeric();
+        if at_boundary && bytes[i].is_ascii_digit() {
+            if let Some((len, rendered)) =
+                match_iso_date(&bytes[i..], locale).or_else(|| match_years(&text[i..], locale))
+            {
+                out.push_str(&text[copied..i]);
+                out.push_str(&rendered);
+                i += len;
+                copied = i;
+                continue;
+            }
+        }
+        i += 1;
+    }
+    out.push_str(&text[copied..]);
+    out
+}
+
+/// Match `YYYY-MM-DD` or `YYYY-MM` at the start of `s`.
+fn match_iso_date(s: &[u8], locale: Locale) -> Option<(usize, String)> {
+    let digits = |range: std::ops::Range<usize>| -> Option<u32> {
+        let part = s.get(range)?;
+        if !part.iter().all(u8::is_ascii_digit) {
+            return None;
+        }
+        std::str::from_utf8(part).ok()?.parse().ok()
+    };
+    let ends_at = |n: usize| s.get(n).is_none_or(|b| !b.is_ascii_alphanumeric());
+
+    let year = digits(0..4)? as i32;
+    if s.get(4) != Some(&b'-') {
+        return None;
+    }
+    let month = digits(5..7)?;
+    if !(1..=12).contains(&month) {
+        return None;
+    }
+
+    if s.get(7) == Some(&b'-') {
+        if let Some(day) = digits(8..10) {
+            if !ends_at(10) {
+                return None;
+            }
+            let date = NaiveDate::from_ymd_opt(year, month, day)?;
+            return Some((10, locale.format_date(date)));
+        }
+    }
+    ends_at(7).then(|| (7, locale.format_month(year, month)))
+}
+
+/// Match "N years", "N+ years", or "N years of experience" at the start of `s`.
+fn match_years(s: &str, locale: Locale) -> Option<(usize, String)> {
+    let digits = s.bytes().take_while(u8::is_ascii_digit).count();
+    let mut count_len = digits;
+    if s[digits..].starts_with('+') {
+        count_len += 1;
+    }
+    let count = &s[..count_len];
+    let rest = s[count_len..].strip_prefix(' ')?;
+
+    for (phrase, experience) in [
+        ("years of experience", true),
+        ("year of experience", true),
+        ("years", false),
+        ("year", false),
+    ] {
+        let matched = rest
+            .get(..phrase.len())
+            .is_some_and(|p| p.eq_ignore_ascii_case(phrase));
+        let at_word_end = rest[phrase.len().min(rest.len())..]
+            .chars()
+            .next()
+            .is_none_or(|c| !c.is_alphanumeric());
+        if matched && at_word_end {
+            let len = count_len + 1 + phrase.len();
+            return Some((len, locale.format_years(count, experience)));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_locale_tags() {
+        assert_eq!(Locale::parse("de-DE"), Some(Locale::De));
+        assert_eq!(Locale::parse("FR"), Some(Locale::Fr));
+        assert_eq!(Locale::parse("ja"), None);
+    }
+
+    #[test]
+    fn test_preferred_language_codes() {
+        assert_eq!(Locale::preferred_language("").unwrap(), None);
+        assert_eq!(
+            Locale::preferred_language("de-CH")
+                .unwrap()
+                .map(Locale::code),
+            Some("de")
+        );
+        assert!(Locale::preferred_language("ja").is_err());
+    }
+
+    #[test]
+    fn test_accept_language_prefers_highest_q() {
+        assert_eq!(
+            Locale::from_accept_language("ja;q=1.0, de;q=0.8, en;q=0.5"),
+            Some(Locale::De)
+        );
+        assert_eq!(
+            Locale::from_accept_language("es-MX,es;q=0.9"),
+            Some(Locale::Es)
+        );
+        assert_eq!(Locale::from_accept_language("ja, zh;q=0.5"), None);
+    }
+
+    #[test]
+    fn test_resolve_field_overrides_header() {
+        assert_eq!(Locale::resolve("fr", Some("de")).unwrap(), Some(Locale::Fr));
+        assert_eq!(Locale::resolve("", Some("de")).unwrap(), Some(Locale::De));
+        assert_eq!(Locale::resolve("", None).unwrap(), None);
+        assert!(Locale::resolve("xx", None).is_err());
+    }
+
+    #[test]
+    fn test_localize_german_answer() {
+        let text = "Joined Siemens on 2019-03-15 with 10+ years of experience; led the team since 2021-06.";
+        assert_eq!(
+            localize_answer(text, Locale::De),
+            "Joined Siemens on 15. März 2019 with 10+ Jahre Erfahrung; led the team since Juni 2021."
+        );
+    }
+
+    #[test]
+    fn test_localize_leaves_non_matches_alone() {
+        let text = "Version 2021-13 and id A2019-03-15, 3 yearsago, über 5 years.";
+        assert_eq!(
+            localize_answer(text, Locale::Es),
+            "Version 2021-13 and id A2019-03-15, 3 yearsago, über 5 años."
+        );
+    }
+
+    #[test]
+    fn test_localize_english_dates() {
+        assert_eq!(
+            localize_answer("Since 2020-01-02, 1 year", Locale::En),
+            "Since January 2, 2020, 1 year"
+        );
+    }
+}
@@ -1,5 +1,53 @@
 //! gRPC service implementations for the memvid service.
 
+mod acl;
+mod admin;
+mod admin_auth;
+mod budget;
+mod chat;
+mod coverage;
+mod cursor;
+mod deadline;
+mod entities;
+mod export;
+mod fit;
+mod highlight;
+mod jwt;
+mod legacy;
+mod locale;
+mod profile;
+mod query_stats;
+mod rate_limit;
+mod request_log;
+mod retention;
+mod sanitize;
 mod service;
+mod summary;
+mod temporal;
+mod topics;
+mod usage;
+mod v2;
+mod voice;
+mod warm;
 
+pub use acl::{caller_acl, AclInterceptor, GROUPS_HEADER, ROLES_HEADER, TENANT_HEADER};
+pub use admin::AdminService;
+pub use admin_auth::AdminAuth;
+pub use coverage::CoverageTracker;
+pub use cursor::CursorCodec;
+pub use deadline::{parse_grpc_timeout, DeadlineLayer, DeadlineService, GRPC_TIMEOUT_HEADER};
+pub use jwt::{
+    caller_subject, refresh_jwks, CallerClaims, JwksClient, JwtValidator, DEFAULT_JWKS_REFRESH,
+};
+pub use profile::{Education, Experience, Profile, ProfileCache, PROFILE_ENTITY, PROFILE_SLOT};
+pub use query_stats::{QueryCount, QueryStats, QuerySummary};
+pub use rate_limit::RateLimiter;
+pub use request_log::{
+    RequestLog, RequestLogLayer, RequestLogService, RequestRecord, DEFAULT_REQUEST_LOG_CAPACITY,
+};
+pub use retention::{RetentionPolicy, HASHED_QUERY_PREFIX};
 pub use service::{HealthService, MemvidGrpcService};
+pub use topics::{HashingEmbedder, Topic, TopicClassifier, MIN_TOPIC_SIMILARITY};
+pub use usage::{LlmPricing, UsageLedger};
+pub use v2::MemvidV2Service;
+pub use warm::{read_corpus, CacheWarmer, WarmReport};
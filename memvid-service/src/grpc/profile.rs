@@ -0,0 +1,288 @@
+//! Typed, cached view of the `__profile__` memory card.
+//!
+//! The profile JSON is parsed once per loaded index into a [`Profile`] with
+//! typed accessors. [`ProfileCache`] reads it through from the searcher and
+//! parses it again only when the searcher's generation changes (index
+//! reload or cutover), per index file when several are served, so consumers such as entity linking no longer fetch
+//! and parse the slot on every request. Missing or mistyped fields read as
+//! empty: a malformed profile degrades to an empty one instead of failing
+//! requests.
+
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+use tracing::debug;
+
+use super::entities::EntityLinker;
+use crate::error::ServiceError;
+use crate::memvid::Searcher;
+
+/// Memory-card entity holding the candidate profile.
+pub const PROFILE_ENTITY: &str = "__profile__";
+
+/// Profile slot holding the profile JSON.
+pub const PROFILE_SLOT: &str = "data";
+
+/// One `experience[]` entry.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Experience {
+    pub company: Option<String>,
+    pub role: Option<String>,
+    pub period: Option<String>,
+}
+
+/// One `education[]` entry.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Education {
+    /// `school`, or `institution` when the card uses that key
+    pub school: Option<String>,
+    pub degree: Option<String>,
+}
+
+/// The candidate profile.
+#[derive(Debug, Clone, Default)]
+pub struct Profile {
+    name: Option<String>,
+    title: Option<String>,
+    email: Option<String>,
+    linkedin: Option<String>,
+    location: Option<String>,
+    status: Option<String>,
+    suggested_questions: Vec<String>,
+    tags: Vec<String>,
+    system_prompt: Option<String>,
+    experience: Vec<Experience>,
+    education: Vec<Education>,
+    skills: BTreeMap<String, Vec<Option<String>>>,
+    linker: EntityLinker,
+}
+
+impl Profile {
+    /// Parse the profile JSON; malformed JSON yields an empty profile.
+    pub fn parse(json: &str) -> Self {
+        let Ok(value) = serde_json::from_str::<Value>(json) else {
+            return Self::default();
+        };
+        let mut profile = Self {
+            name: string(&value["name"]),
+            title: string(&value["title"]),
+            email: string(&value["email"]),
+            linkedin: string(&value["linkedin"]),
+            location: string(&value["location"]),
+            status: string(&value["status"]),
+            suggested_questions: strings(&value["suggested_questions"]),
+            tags: strings(&value["tags"]),
+            system_prompt: string(&value["system_prompt"]),
+            experience: array(&value["experience"])
+                .iter()
+                .map(|entry| Experience {
+                    company: string(&entry["company"]),
+                    role: string(&entry["role"]),
+                    period: string(&entry["period"]),
+                })
+                .collect(),
+            education: array(&value["education"])
+                .iter()
+                .map(|entry| Education {
+                    school: string(&entry["school"]).or_else(|| string(&entry["institution"])),
+                    degree: string(&entry["degree"]),
+                })
+                .collect(),
+            skills: value["skills"]
+                .as_object()
+                .map(|skills| {
+                    skills
+                        .iter()
+                        .map(|(category, names)| {
+                            let names = array(names).iter().map(string).collect();
+                            (category.clone(), names)
+                        })
+                        .collect()
+                })
+                .unwrap_or_default(),
+            linker: EntityLinker::default(),
+        };
+        profile.linker = EntityLinker::for_profile(&profile);
+        profile
+    }
+
+    /// Read the profile from the searcher's memory card; empty when the
+    /// index has none.
+    pub async fn load(searcher: &dyn Searcher) -> Result<Self, ServiceError> {
+        let state = searcher
+            .get_state(PROFILE_ENTITY, Some(PROFILE_SLOT))
+            .await?;
+        Ok(state
+            .slots
+            .get(PROFILE_SLOT)
+            .map(|json| Self::parse(json))
+            .unwrap_or_default())
+    }
+
+    /// Candidate name.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Current or target job title.
+    pub fn title(&self) -> Option<&str> {
+        self.title.as_deref()
+    }
+
+    /// Contact email (redacted in anonymized mode).
+    pub fn email(&self) -> Option<&str> {
+        self.email.as_deref()
+    }
+
+    /// LinkedIn profile URL (redacted in anonymized mode).
+    pub fn linkedin(&self) -> Option<&str> {
+        self.linkedin.as_deref()
+    }
+
+    /// Where the candidate is based.
+    pub fn location(&self) -> Option<&str> {
+        self.location.as_deref()
+    }
+
+    /// Availability, e.g. "Open to opportunities"
+    pub fn status(&self) -> Option<&str> {
+        self.status.as_deref()
+    }
+
+    /// Questions the UI offers as starting points.
+    pub fn suggested_questions(&self) -> &[String] {
+        &self.suggested_questions
+    }
+
+    /// Topic tags of the resume.
+    pub fn tags(&self) -> &[String] {
+        &self.tags
+    }
+
+    /// Instructions for answer synthesis.
+    pub fn system_prompt(&self) -> Option<&str> {
+        self.system_prompt.as_deref()
+    }
+
+    /// Work history, as listed.
+    pub fn experience(&self) -> &[Experience] {
+        &self.experience
+    }
+
+    /// Schools, as listed.
+    pub fn education(&self) -> &[Education] {
+        &self.education
+    }
+
+    /// Skill names by category (e.g., "strong"). Entries that are not
+    /// strings are None, so positions match the JSON.
+    pub fn skills(&self) -> &BTreeMap<String, Vec<Option<String>>> {
+        &self.skills
+    }
+
+    /// Entity linker over this profile's companies, schools and skills.
+    pub fn linker(&self) -> &EntityLinker {
+        &self.linker
+    }
+}
+
+/// Read-through cache of the profile of each searcher's current index.
+#[derive(Debug, Default)]
+pub struct ProfileCache {
+    /// Generation and profile per index file
+    cached: Mutex<HashMap<String, (u64, Arc<Profile>)>>,
+}
+
+impl ProfileCache {
+    /// The profile of the searcher's current index, parsed at most once
+    /// per index generation.
+    pub async fn get(&self, searcher: &dyn Searcher) -> Result<Arc<Profile>, ServiceError> {
+        let file = searcher.memvid_file();
+        let generation = searcher.generation();
+        if let Some((cached, profile)) = self.lock().get(&file) {
+            if *cached == generation {
+                return Ok(Arc::clone(profile));
+            }
+        }
+
+        debug!(file = %file, generation, "Loading profile memory card");
+        let profile = Arc::new(Profile::load(searcher).await?);
+        self.lock().insert(file, (generation, Arc::clone(&profile)));
+        Ok(profile)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, (u64, Arc<Profile>)>> {
+        self.cached.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+fn array(value: &Value) -> &[Value] {
+    value.as_array().map(Vec::as_slice).unwrap_or_default()
+}
+
+fn string(value: &Value) -> Option<String> {
+    value.as_str().map(str::to_string)
+}
+
+fn strings(value: &Value) -> Vec<String> {
+    array(value).iter().filter_map(string).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memvid::{LoadFuture, MockSearcher, ReloadableSearcher, SearcherLoader};
+
+    #[test]
+    fn test_typed_accessors() {
+        let profile = Profile::parse(
+            r#"{
+                "name": "Frank",
+                "suggested_questions": ["What about Rust?", 7],
+                "system_prompt": "Answer as Frank's resume.",
+                "experience": [{"company": "Siemens", "period": "2020-2024"}, "oops"],
+                "education": [{"institution": "TU Munich"}],
+                "skills": {"strong": ["Rust", 3]},
+                "title": 42
+            }"#,
+        );
+        assert_eq!(profile.name(), Some("Frank"));
+        assert_eq!(profile.title(), None);
+        assert_eq!(profile.suggested_questions(), ["What about Rust?"]);
+        assert_eq!(profile.system_prompt(), Some("Answer as Frank's resume."));
+        assert_eq!(profile.experience()[0].company.as_deref(), Some("Siemens"));
+        assert_eq!(profile.experience()[1], Experience::default());
+        assert_eq!(profile.education()[0].school.as_deref(), Some("TU Munich"));
+        assert_eq!(
+            profile.skills()["strong"],
+            vec![Some("Rust".to_string()), None]
+        );
+        assert_eq!(profile.linker().link("Rust at Siemens").len(), 2);
+
+        assert!(Profile::parse("not json").experience().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_cache_reloads_with_index_generation() {
+        let path = std::env::temp_dir().join(format!("memvid-profile-{}.mv2", std::process::id()));
+        std::fs::write(&path, "v1").unwrap();
+        let loader: SearcherLoader = Arc::new(|_path: String| {
+            Box::pin(async { Ok(Arc::new(MockSearcher::new()) as Arc<dyn Searcher>) }) as LoadFuture
+        });
+        let searcher = ReloadableSearcher::open_with(path.to_string_lossy(), loader)
+            .await
+            .unwrap();
+        let cache = ProfileCache::default();
+
+        let first = cache.get(&searcher).await.unwrap();
+        assert_eq!(first.name(), Some("Frank Schwichtenberg"));
+        assert!(Arc::ptr_eq(&first, &cache.get(&searcher).await.unwrap()));
+
+        std::fs::write(&path, "version two").unwrap();
+        searcher.reload_if_changed().await.unwrap();
+        let reloaded = cache.get(&searcher).await.unwrap();
+        assert!(!Arc::ptr_eq(&first, &reloaded));
+        assert_eq!(reloaded.suggested_questions().len(), 2);
+        std::fs::remove_file(path).ok();
+    }
+}
@@ -0,0 +1,281 @@
+//! Query statistics for the periodic analytics report.
+//!
+//! Search and Ask record each query with its result count and end-to-end
+//! latency. [`QueryStats::take_summary`] condenses everything recorded since
+//! the previous summary (query volume, top questions, zero-result queries,
+//! p95 latency) and starts a new period. Memory is bounded: past
+//! [`MAX_DISTINCT_QUERIES`] distinct queries new ones are only counted
+//! towards the volume, and latency samples beyond [`MAX_LATENCY_SAMPLES`]
+//! overwrite the oldest. Under a [`RetentionPolicy`] queries are counted by
+//! their hash, the distinct-query limit may be lower, and a period older
+//! than the maximum age is discarded and restarted.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use tracing::info;
+
+use super::retention::RetentionPolicy;
+
+/// Distinct query texts tracked per period.
+pub const MAX_DISTINCT_QUERIES: usize = 10_000;
+
+/// Latency samples kept per period.
+pub const MAX_LATENCY_SAMPLES: usize = 100_000;
+
+/// A query text and how often it was asked.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct QueryCount {
+    /// Normalized query text (trimmed, lowercased, single-spaced)
+    pub query: String,
+    /// Times asked within the period
+    pub count: u64,
+}
+
+/// Statistics for one reporting period.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct QuerySummary {
+    /// Start of the period
+    pub period_start: DateTime<Utc>,
+    /// End of the period
+    pub period_end: DateTime<Utc>,
+    /// Search and Ask requests
+    pub queries: u64,
+    /// Requests that returned no results
+    pub zero_result_queries: u64,
+    /// 95th percentile end-to-end latency in milliseconds (None without queries)
+    pub p95_latency_ms: Option<u64>,
+    /// Most frequent queries, most asked first
+    pub top_questions: Vec<QueryCount>,
+    /// Most frequent queries that returned no results
+    pub top_zero_result_queries: Vec<QueryCount>,
+}
+
+#[derive(Debug)]
+struct Period {
+    start: DateTime<Utc>,
+    queries: u64,
+    zero_results: u64,
+    counts: HashMap<String, (u64, u64)>,
+    latencies_ms: Vec<u64>,
+}
+
+impl Period {
+    fn new(start: DateTime<Utc>) -> Self {
+        Self {
+            start,
+            queries: 0,
+            zero_results: 0,
+            counts: HashMap::new(),
+            latencies_ms: Vec::new(),
+        }
+    }
+}
+
+/// Per-period query statistics shared by the query services.
+#[derive(Debug)]
+pub struct QueryStats {
+    period: Mutex<Period>,
+    retention: RetentionPolicy,
+}
+
+impl Default for QueryStats {
+    fn default() -> Self {
+        Self {
+            period: Mutex::new(Period::new(Utc::now())),
+            retention: RetentionPolicy::default(),
+        }
+    }
+}
+
+impl QueryStats {
+    /// Keep statistics within `retention`'s limits.
+    pub fn with_retention(mut self, retention: RetentionPolicy) -> Self {
+        self.retention = retention;
+        self
+    }
+
+    /// Record a query that returned `results` results after `latency`.
+    pub fn record(&self, query: &str, results: usize, latency: Duration) {
+        let latency_ms = latency.as_millis() as u64;
+        let normalized = normalize(query);
+        let key = if normalized.is_empty() {
+            normalized
+        } else {
+            self.retention.query_text(&normalized).into_owned()
+        };
+        let mut period = self.lock();
+        self.expire(&mut period);
+
+        let slot = (period.queries % MAX_LATENCY_SAMPLES as u64) as usize;
+        period.queries += 1;
+        if period.latencies_ms.len() < MAX_LATENCY_SAMPLES {
+            period.latencies_ms.push(latency_ms);
+        } else {
+            period.latencies_ms[slot] = latency_ms;
+        }
+        if results == 0 {
+            period.zero_results += 1;
+        }
+
+        let full = period.counts.len() >= self.retention.capacity(MAX_DISTINCT_QUERIES);
+        if key.is_empty() || (full && !period.counts.contains_key(&key)) {
+            return;
+        }
+        let (count, zero) = period.counts.entry(key).or_default();
+        *count += 1;
+        if results == 0 {
+            *zero += 1;
+        }
+    }
+
+    /// Summarize the period so far with up to `top` queries per list, and
+    /// start a new period.
+    pub fn take_summary(&self, top: usize) -> QuerySummary {
+        let now = Utc::now();
+        let period = {
+            let mut period = self.lock();
+            self.expire(&mut period);
+            std::mem::replace(&mut *period, Period::new(now))
+        };
+
+        let mut latencies = period.latencies_ms;
+        latencies.sort_unstable();
+        let p95_latency_ms = (!latencies.is_empty()).then(|| {
+            // Nearest-rank percentile
+            let rank = (latencies.len() * 95).div_ceil(100);
+            latencies[rank.saturating_sub(1)]
+        });
+
+        let ranked = |select: fn(&(u64, u64)) -> u64| {
+            let mut counts: Vec<QueryCount> = period
+                .counts
+                .iter()
+                .map(|(query, counts)| QueryCount {
+                    query: query.clone(),
+                    count: select(counts),
+                })
+                .filter(|entry| entry.count > 0)
+                .collect();
+            counts.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.query.cmp(&b.query)));
+            counts.truncate(top);
+            counts
+        };
+
+        QuerySummary {
+            period_start: period.start,
+            period_end: now,
+            queries: period.queries,
+            zero_result_queries: period.zero_results,
+            p95_latency_ms,
+            top_questions: ranked(|(count, _)| *count),
+            top_zero_result_queries: ranked(|(_, zero)| *zero),
+        }
+    }
+
+    /// Discard the current period; returns the number of queries it held.
+    pub fn purge(&self) -> u64 {
+        std::mem::replace(&mut *self.lock(), Period::new(Utc::now())).queries
+    }
+
+    /// Restart a period that has outlived the maximum age.
+    fn expire(&self, period: &mut Period) {
+        let now = Utc::now();
+        if self.retention.expired(period.start, now) {
+            info!(
+                queries = period.queries,
+                "Discarding query statistics past the retention age"
+            );
+            *period = Period::new(now);
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Period> {
+        self.period.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Query text as counted: trimmed, lowercased, single-spaced, without
+/// trailing punctuation.
+fn normalize(query: &str) -> String {
+    query
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_end_matches(['?', '.', '!'])
+        .to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grpc::retention::HASHED_QUERY_PREFIX;
+
+    #[test]
+    fn test_summary_ranks_queries_and_resets() {
+        let stats = QueryStats::default();
+        stats.record("What is his Rust experience?", 3, Duration::from_millis(10));
+        stats.record("what is his  rust experience", 2, Duration::from_millis(20));
+        stats.record("Does he know COBOL?", 0, Duration::from_millis(30));
+        stats.record("", 0, Duration::from_millis(40));
+
+        let summary = stats.take_summary(5);
+        assert_eq!(summary.queries, 4);
+        assert_eq!(summary.zero_result_queries, 2);
+        assert_eq!(summary.p95_latency_ms, Some(40));
+        assert_eq!(
+            summary.top_questions,
+            vec![
+                QueryCount {
+                    query: "what is his rust experience".to_string(),
+                    count: 2
+                },
+                QueryCount {
+                    query: "does he know cobol".to_string(),
+                    count: 1
+                },
+            ]
+        );
+        assert_eq!(summary.top_zero_result_queries.len(), 1);
+        assert_eq!(
+            summary.top_zero_result_queries[0].query,
+            "does he know cobol"
+        );
+
+        let next = stats.take_summary(5);
+        assert_eq!(next.queries, 0);
+        assert_eq!(next.p95_latency_ms, None);
+        assert_eq!(next.period_start, summary.period_end);
+    }
+
+    #[test]
+    fn test_p95_latency_uses_nearest_rank() {
+        let stats = QueryStats::default();
+        for ms in 1..=100 {
+            stats.record("q", 1, Duration::from_millis(ms));
+        }
+        assert_eq!(stats.take_summary(1).p95_latency_ms, Some(95));
+    }
+
+    #[test]
+    fn test_retention_hashes_and_purges_queries() {
+        let stats = QueryStats::default().with_retention(RetentionPolicy::new(None, Some(1), true));
+        stats.record("What about Rust?", 1, Duration::from_millis(5));
+        stats.record("what about rust", 1, Duration::from_millis(5));
+        stats.record("Does he know Go?", 0, Duration::from_millis(5));
+
+        let summary = stats.take_summary(5);
+        assert_eq!(summary.queries, 3);
+        assert_eq!(summary.top_questions.len(), 1);
+        assert_eq!(summary.top_questions[0].count, 2);
+        assert!(summary.top_questions[0]
+            .query
+            .starts_with(HASHED_QUERY_PREFIX));
+
+        stats.record("q", 1, Duration::from_millis(5));
+        assert_eq!(stats.purge(), 1);
+        assert_eq!(stats.take_summary(5).queries, 0);
+    }
+}
@@ -0,0 +1,355 @@
+//! Per-client request rate limiting.
+//!
+//! A token bucket per client refills continuously and allows bursts of up
+//! to the configured burst size (by default one minute's quota). Clients are
+//! identified by the subject of their validated bearer token (see
+//! [`jwt`](super::jwt)), else by their API key id (see
+//! [`usage::key_id`](super::usage::key_id)) when the key is one of the
+//! authenticated API keys, otherwise by peer IP address: made-up keys do
+//! not buy a fresh bucket. At most `MAX_TRACKED_CLIENTS` buckets are kept;
+//! the least recently used one makes room for a new client. Requests over
+//! the limit fail with `RESOURCE_EXHAUSTED` and tell the client when to
+//! retry: `retry-after` (whole seconds) and `grpc-retry-pushback-ms`, which
+//! gRPC clients with a retry policy honor. The status's ErrorInfo has
+//! reason `RATE_LIMITED`.
+//!
+//! `RateLimiter` is a tonic interceptor; clones share the same buckets, so
+//! one limiter can guard several services with a single per-client budget.
+//! With a runtime config channel, the limit follows its current value.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tonic::metadata::MetadataValue;
+use tonic::service::Interceptor;
+use tonic::{Code, Request, Status};
+
+use super::jwt::caller_subject;
+use super::usage::{self, ANONYMOUS_KEY};
+use crate::error_details::{rich_status, ErrorInfo};
+use crate::metrics;
+use crate::runtime_config::RuntimeConfigReceiver;
+
+/// Most buckets kept; the least recently used is evicted beyond.
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+/// Buckets by client, with their clients ordered by last use.
+#[derive(Debug, Default)]
+struct Buckets {
+    by_client: HashMap<String, Bucket>,
+    by_use: BTreeSet<(Instant, String)>,
+}
+
+/// Token-bucket rate limiter keyed by client.
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    per_minute: u32,
+    burst: Option<u32>,
+    runtime: Option<RuntimeConfigReceiver>,
+    authenticated_keys: Arc<HashSet<String>>,
+    max_clients: usize,
+    buckets: Arc<Mutex<Buckets>>,
+}
+
+impl RateLimiter {
+    /// Allow `per_minute` requests per client (0 = unlimited).
+    pub fn new(per_minute: u32) -> Self {
+        Self {
+            per_minute,
+            burst: None,
+            runtime: None,
+            authenticated_keys: Arc::new(HashSet::new()),
+            max_clients: MAX_TRACKED_CLIENTS,
+            buckets: Arc::new(Mutex::new(Buckets::default())),
+        }
+    }
+
+    /// Allow bursts of `burst` requests instead of one minute's quota.
+    pub fn with_burst(mut self, burst: Option<u32>) -> Self {
+        self.burst = burst.filter(|burst| *burst > 0);
+        self
+    }
+
+    /// Give callers presenting one of `keys` a budget per key; other
+    /// callers are limited per peer IP whatever key they send.
+    pub fn with_authenticated_keys(mut self, keys: impl IntoIterator<Item = String>) -> Self {
+        self.authenticated_keys = Arc::new(keys.into_iter().collect());
+        self
+    }
+
+    /// Take the limit from the runtime config instead of the fixed value.
+    pub fn with_runtime_config(mut self, runtime: RuntimeConfigReceiver) -> Self {
+        self.runtime = Some(runtime);
+        self
+    }
+
+    /// Current requests per minute per client (0 = unlimited).
+    fn per_minute(&self) -> u32 {
+        match &self.runtime {
+            Some(runtime) => runtime.borrow().rate_limit_per_minute,
+            None => self.per_minute,
+        }
+    }
+
+    /// Take a token from `client`'s bucket, or return how long until one is
+    /// available.
+    fn try_acquire(&self, client: &str, per_minute: u32, now: Instant) -> Result<(), Duration> {
+        let capacity = self.burst.unwrap_or(per_minute) as f64;
+        let refill_per_sec = per_minute as f64 / 60.0;
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        let Buckets { by_client, by_use } = &mut *buckets;
+
+        if by_client.len() >= self.max_clients && !by_client.contains_key(client) {
+            if let Some((_, evicted)) = by_use.pop_first() {
+                by_client.remove(&evicted);
+            }
+        }
+
+        let bucket = by_client.entry(client.to_string()).or_insert(Bucket {
+            tokens: capacity,
+            updated: now,
+        });
+        by_use.remove(&(bucket.updated, client.to_string()));
+        let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * refill_per_sec).min(capacity);
+        bucket.updated = now;
+        by_use.insert((bucket.updated, client.to_string()));
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64(
+                (1.0 - bucket.tokens) / refill_per_sec,
+            ))
+        }
+    }
+
+    /// Token subject, or the id of an authenticated API key, otherwise the
+    /// peer IP.
+    fn client_key(&self, request: &Request<()>) -> String {
+        if let Some(subject) = caller_subject(request) {
+            return format!("sub:{}", subject);
+        }
+        let authenticated = request
+            .metadata()
+            .get(usage::API_KEY_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|key| self.authenticated_keys.contains(key));
+        if authenticated {
+            return usage::key_id(request.metadata());
+        }
+        request
+            .remote_addr()
+            .map(|addr| addr.ip().to_string())
+            .unwrap_or_else(|| ANONYMOUS_KEY.to_string())
+    }
+}
+
+impl Interceptor for RateLimiter {
+    /// Admit or reject a request.
+    fn call(&mut self, request: Request<()>) -> Result<Request<()>, Status> {
+        let per_minute = self.per_minute();
+        if per_minute == 0 {
+            return Ok(request);
+        }
+        let client = self.client_key(&request);
+        match self.try_acquire(&client, per_minute, Instant::now()) {
+            Ok(()) => Ok(request),
+            Err(wait) => {
+                metrics::increment_rate_limited();
+                let retry_ms = wait.as_millis().max(1) as u64;
+                let mut status = rich_status(
+                    Code::ResourceExhausted,
+                    format!("Rate limit of {} requests per minute exceeded", per_minute),
+                    ErrorInfo::new("RATE_LIMITED").with("retry_after_ms", retry_ms),
+                    Vec::new(),
+                );
+                let metadata = status.metadata_mut();
+                metadata.insert("retry-after", MetadataValue::from(retry_ms.div_ceil(1000)));
+                metadata.insert("grpc-retry-pushback-ms", MetadataValue::from(retry_ms));
+                Err(status)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grpc::jwt::CallerClaims;
+    use crate::runtime_config::RuntimeConfig;
+    use std::time::Duration;
+    use tonic::metadata::MetadataValue;
+
+    #[test]
+    fn test_bucket_refills_over_time() {
+        let limiter = RateLimiter::new(2);
+        let start = Instant::now();
+
+        assert!(limiter.try_acquire("a", 2, start).is_ok());
+        assert!(limiter.try_acquire("a", 2, start).is_ok());
+        assert_eq!(
+            limiter.try_acquire("a", 2, start),
+            Err(Duration::from_secs(30))
+        );
+        // Other clients have their own bucket
+        assert!(limiter.try_acquire("b", 2, start).is_ok());
+
+        // Two per minute refills one token every 30 seconds
+        assert!(limiter
+            .try_acquire("a", 2, start + Duration::from_secs(20))
+            .is_err());
+        assert!(limiter
+            .try_acquire("a", 2, start + Duration::from_secs(31))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_burst_caps_bucket() {
+        // 10 per second with bursts of 3
+        let limiter = RateLimiter::new(600).with_burst(Some(3));
+        let start = Instant::now();
+        for _ in 0..3 {
+            assert!(limiter.try_acquire("a", 600, start).is_ok());
+        }
+        assert!(limiter.try_acquire("a", 600, start).is_err());
+        assert!(limiter
+            .try_acquire("a", 600, start + Duration::from_millis(110))
+            .is_ok());
+
+        // An idle second refills to the burst, not to a minute's quota
+        let later = start + Duration::from_secs(60);
+        for _ in 0..3 {
+            assert!(limiter.try_acquire("a", 600, later).is_ok());
+        }
+        assert!(limiter.try_acquire("a", 600, later).is_err());
+    }
+
+    #[test]
+    fn test_rejects_over_limit() {
+        let mut limiter = RateLimiter::new(1);
+        let request = || {
+            let mut request = Request::new(());
+            request
+                .metadata_mut()
+                .insert("x-api-key", MetadataValue::from_static("demo"));
+            request
+        };
+
+        assert!(limiter.call(request()).is_ok());
+        // Clones share buckets
+        let status = limiter.clone().call(request()).unwrap_err();
+        assert_eq!(status.code(), tonic::Code::ResourceExhausted);
+        assert_eq!(status.metadata().get("retry-after").unwrap(), "60");
+        let info = crate::error_details::status_details(&status)
+            .and_then(|details| details.error_info())
+            .unwrap();
+        assert_eq!(info.reason, "RATE_LIMITED");
+        let pushback: u64 = status
+            .metadata()
+            .get("grpc-retry-pushback-ms")
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!(pushback > 59_000 && pushback <= 60_000);
+    }
+
+    #[test]
+    fn test_only_authenticated_api_keys_have_own_budget() {
+        let mut limiter = RateLimiter::new(1).with_authenticated_keys(["partner-key".to_string()]);
+        let request = |key: &'static str| {
+            let mut request = Request::new(());
+            request
+                .metadata_mut()
+                .insert("x-api-key", MetadataValue::from_static(key));
+            request
+        };
+
+        // Made-up keys share the caller's peer budget
+        assert!(limiter.call(request("made-up-1")).is_ok());
+        assert!(limiter.call(request("made-up-2")).is_err());
+        assert!(limiter.call(request("partner-key")).is_ok());
+        assert!(limiter.call(request("partner-key")).is_err());
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used_bucket() {
+        let mut limiter = RateLimiter::new(1);
+        limiter.max_clients = 2;
+        let start = Instant::now();
+
+        assert!(limiter.try_acquire("a", 1, start).is_ok());
+        assert!(limiter
+            .try_acquire("b", 1, start + Duration::from_secs(1))
+            .is_ok());
+        // "a" is used again, so "b" is the least recently used
+        assert!(limiter
+            .try_acquire("a", 1, start + Duration::from_secs(2))
+            .is_err());
+        assert!(limiter
+            .try_acquire("c", 1, start + Duration::from_secs(3))
+            .is_ok());
+
+        let buckets = limiter.buckets.lock().unwrap();
+        assert_eq!(buckets.by_client.len(), 2);
+        assert_eq!(buckets.by_use.len(), 2);
+        assert!(!buckets.by_client.contains_key("b"));
+        drop(buckets);
+        // "a" kept its drained bucket
+        assert!(limiter
+            .try_acquire("a", 1, start + Duration::from_secs(4))
+            .is_err());
+    }
+
+    #[test]
+    fn test_token_subjects_have_own_budget() {
+        let mut limiter = RateLimiter::new(1);
+        let request = |subject: &str| {
+            let mut request = Request::new(());
+            request.extensions_mut().insert(CallerClaims {
+                subject: subject.to_string(),
+                issuer: "https://auth.example.com/".to_string(),
+                expires_at: 0,
+            });
+            request
+        };
+
+        assert!(limiter.call(request("alice")).is_ok());
+        assert!(limiter.call(request("bob")).is_ok());
+        assert!(limiter.call(request("alice")).is_err());
+    }
+
+    #[test]
+    fn test_follows_runtime_config() {
+        let (sender, receiver) = tokio::sync::watch::channel(RuntimeConfig {
+            rate_limit_per_minute: 0,
+            ..RuntimeConfig::default()
+        });
+        let mut limiter = RateLimiter::new(100).with_runtime_config(receiver);
+        for _ in 0..5 {
+            assert!(limiter.call(Request::new(())).is_ok());
+        }
+
+        sender.send_modify(|config| config.rate_limit_per_minute = 1);
+        assert!(limiter.call(Request::new(())).is_ok());
+        let status = limiter.call(Request::new(())).unwrap_err();
+        assert!(status.message().contains("Rate limit of 1 "));
+    }
+
+    #[test]
+    fn test_zero_is_unlimited() {
+        let mut limiter = RateLimiter::new(0);
+        for _ in 0..100 {
+            assert!(limiter.call(Request::new(())).is_ok());
+        }
+    }
+}
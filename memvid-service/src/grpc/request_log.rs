@@ -0,0 +1,275 @@
+//! Ring buffer of recent gRPC requests.
+//!
+//! `RequestLogLayer` wraps the gRPC server and records every call's method,
+//! caller, status and latency. Payloads are never recorded, and callers are
+//! identified only by their API key id (see
+//! [`usage::key_id`](super::usage::key_id)). Latency is measured to the
+//! response headers, so a streaming call that fails mid-stream is recorded
+//! with the status it started with. A [`RetentionPolicy`] lowers the
+//! capacity and drops records past their maximum age.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Instant;
+use tonic::codegen::http;
+use tonic::metadata::MetadataMap;
+use tower::{Layer, Service};
+
+use super::retention::RetentionPolicy;
+use super::usage;
+
+/// Requests kept by default.
+pub const DEFAULT_REQUEST_LOG_CAPACITY: usize = 50;
+
+/// One recorded gRPC call.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RequestRecord {
+    /// When the call started
+    pub at: DateTime<Utc>,
+    /// Full gRPC method path (e.g., "/memvid.v1.MemvidService/Ask")
+    pub method: String,
+    /// Caller's API key id, or "anonymous"
+    pub client: String,
+    /// gRPC status code name (e.g., "Ok", "InvalidArgument")
+    pub status: String,
+    /// Time to response headers in milliseconds
+    pub latency_ms: u64,
+}
+
+/// Fixed-capacity log of the most recent requests.
+#[derive(Debug)]
+pub struct RequestLog {
+    capacity: usize,
+    retention: RetentionPolicy,
+    entries: Mutex<VecDeque<RequestRecord>>,
+}
+
+impl Default for RequestLog {
+    fn default() -> Self {
+        Self::new(DEFAULT_REQUEST_LOG_CAPACITY)
+    }
+}
+
+impl RequestLog {
+    /// Keep the last `capacity` requests (0 = record nothing).
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            retention: RetentionPolicy::default(),
+            entries: Mutex::new(VecDeque::with_capacity(capacity)),
+        }
+    }
+
+    /// Keep records within `retention`'s size and age limits.
+    pub fn with_retention(mut self, retention: RetentionPolicy) -> Self {
+        self.capacity = retention.capacity(self.capacity);
+        self.retention = retention;
+        self
+    }
+
+    /// Append a record, evicting the oldest when full.
+    pub fn record(&self, record: RequestRecord) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut entries = self.lock();
+        if entries.len() >= self.capacity {
+            entries.pop_front();
+        }
+        entries.push_back(record);
+    }
+
+    /// Recorded requests, oldest first.
+    pub fn recent(&self) -> Vec<RequestRecord> {
+        let mut entries = self.lock();
+        let now = Utc::now();
+        while entries
+            .front()
+            .is_some_and(|record| self.retention.expired(record.at, now))
+        {
+            entries.pop_front();
+        }
+        entries.iter().cloned().collect()
+    }
+
+    /// Delete every record; returns how many there were.
+    pub fn purge(&self) -> usize {
+        let mut entries = self.lock();
+        let purged = entries.len();
+        entries.clear();
+        purged
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, VecDeque<RequestRecord>> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Tower layer recording every call into a [`RequestLog`].
+#[derive(Debug, Clone)]
+pub struct RequestLogLayer {
+    log: Arc<RequestLog>,
+}
+
+impl RequestLogLayer {
+    /// Record calls into `log`.
+    pub fn new(log: Arc<RequestLog>) -> Self {
+        Self { log }
+    }
+}
+
+impl<S> Layer<S> for RequestLogLayer {
+    type Service = RequestLogService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RequestLogService {
+            inner,
+            log: Arc::clone(&self.log),
+        }
+    }
+}
+
+/// Service produced by [`RequestLogLayer`].
+#[derive(Debug, Clone)]
+pub struct RequestLogService<S> {
+    inner: S,
+    log: Arc<RequestLog>,
+}
+
+impl<S, B, RB> Service<http::Request<B>> for RequestLogService<S>
+where
+    S: Service<http::Request<B>, Response = http::Response<RB>>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: http::Request<B>) -> Self::Future {
+        let at = Utc::now();
+        let started = Instant::now();
+        let method = request.uri().path().to_string();
+        let client = usage::key_id(&MetadataMap::from_headers(request.headers().clone()));
+        let log = Arc::clone(&self.log);
+        let response = self.inner.call(request);
+
+        Box::pin(async move {
+            let response = response.await;
+            // Errors are sent trailers-only, so the status is in the headers
+            let status = match &response {
+                Ok(response) => response
+                    .headers()
+                    .get("grpc-status")
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|v| v.parse::<i32>().ok())
+                    .map_or(tonic::Code::Ok, tonic::Code::from_i32),
+                Err(_) => tonic::Code::Unknown,
+            };
+            log.record(RequestRecord {
+                at,
+                method,
+                client,
+                status: format!("{:?}", status),
+                latency_ms: started.elapsed().as_millis() as u64,
+            });
+            response
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::Infallible;
+    use tower::ServiceExt;
+
+    fn record(method: &str) -> RequestRecord {
+        RequestRecord {
+            at: Utc::now(),
+            method: method.to_string(),
+            client: "anonymous".to_string(),
+            status: "Ok".to_string(),
+            latency_ms: 1,
+        }
+    }
+
+    #[test]
+    fn test_keeps_most_recent_requests() {
+        let log = RequestLog::new(2);
+        log.record(record("/a"));
+        log.record(record("/b"));
+        log.record(record("/c"));
+
+        let methods: Vec<String> = log.recent().into_iter().map(|r| r.method).collect();
+        assert_eq!(methods, vec!["/b", "/c"]);
+
+        let disabled = RequestLog::new(0);
+        disabled.record(record("/a"));
+        assert!(disabled.recent().is_empty());
+    }
+
+    #[test]
+    fn test_retention_limits_and_purge() {
+        let log = RequestLog::new(50).with_retention(RetentionPolicy::new(
+            Some(std::time::Duration::from_secs(3600)),
+            Some(2),
+            false,
+        ));
+        let mut old = record("/old");
+        old.at = Utc::now() - chrono::Duration::hours(2);
+        log.record(old);
+        log.record(record("/a"));
+        let methods: Vec<String> = log.recent().into_iter().map(|r| r.method).collect();
+        assert_eq!(methods, vec!["/a"]);
+
+        log.record(record("/b"));
+        log.record(record("/c"));
+        assert_eq!(log.recent().len(), 2);
+        assert_eq!(log.purge(), 2);
+        assert!(log.recent().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_layer_records_status_and_caller() {
+        let log = Arc::new(RequestLog::default());
+        let service = RequestLogLayer::new(Arc::clone(&log)).layer(tower::service_fn(
+            |request: http::Request<()>| async move {
+                let mut response = http::Response::new(());
+                if request.uri().path().ends_with("/Ask") {
+                    response
+                        .headers_mut()
+                        .insert("grpc-status", http::HeaderValue::from_static("3"));
+                }
+                Ok::<_, Infallible>(response)
+            },
+        ));
+
+        let request = http::Request::builder()
+            .uri("/memvid.v1.MemvidService/Ask")
+            .header("x-api-key", "secret")
+            .body(())
+            .unwrap();
+        service.clone().oneshot(request).await.unwrap();
+        let request = http::Request::builder()
+            .uri("/memvid.v1.MemvidService/Search")
+            .body(())
+            .unwrap();
+        service.oneshot(request).await.unwrap();
+
+        let recent = log.recent();
+        assert_eq!(recent[0].method, "/memvid.v1.MemvidService/Ask");
+        assert_eq!(recent[0].status, "InvalidArgument");
+        assert!(recent[0].client.starts_with("key-"));
+        assert_eq!(recent[1].status, "Ok");
+        assert_eq!(recent[1].client, "anonymous");
+    }
+}
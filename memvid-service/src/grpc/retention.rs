@@ -0,0 +1,117 @@
+//! Data-retention controls for records derived from callers.
+//!
+//! Three places keep caller-derived data: the recent requests ring (see
+//! [`RequestLog`](super::RequestLog)), which is the audit trail of calls;
+//! the query statistics behind the analytics report (see
+//! [`QueryStats`](super::QueryStats)); and the query text logged with each
+//! Search and Ask. A [`RetentionPolicy`] bounds them: in-memory records
+//! older than `max_age` are dropped, each store keeps at most `max_entries`,
+//! and with `hash_queries` query text is replaced by a keyed hash before it
+//! is logged or counted. The key is random per process, so repeated
+//! questions still count together but cannot be recovered by hashing
+//! guesses. The Admin `PurgeData` RPC empties the stores on demand.
+
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::borrow::Cow;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::random::random_bytes;
+
+/// Prefix of hashed query text.
+pub const HASHED_QUERY_PREFIX: &str = "sha256:";
+
+/// Limits on how long and how much caller-derived data is kept.
+#[derive(Debug, Clone)]
+pub struct RetentionPolicy {
+    /// Drop in-memory records older than this (None = keep until evicted)
+    pub max_age: Option<Duration>,
+    /// Records kept per store (None = the store's own limit)
+    pub max_entries: Option<usize>,
+    /// Log and count query text only as a keyed hash
+    pub hash_queries: bool,
+    key: Arc<[u8]>,
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self::new(None, None, false)
+    }
+}
+
+impl RetentionPolicy {
+    /// Policy keeping records for `max_age`, at most `max_entries` per store.
+    pub fn new(max_age: Option<Duration>, max_entries: Option<usize>, hash_queries: bool) -> Self {
+        let key = random_bytes::<32>().to_vec();
+        Self {
+            max_age,
+            max_entries,
+            hash_queries,
+            key: key.into(),
+        }
+    }
+
+    /// Query text as it may be logged or counted.
+    pub fn query_text<'a>(&self, query: &'a str) -> Cow<'a, str> {
+        if !self.hash_queries {
+            return Cow::Borrowed(query);
+        }
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(&self.key).expect("HMAC accepts keys of any length");
+        mac.update(query.as_bytes());
+        let digest = mac.finalize().into_bytes();
+        let hex: String = digest[..8].iter().map(|b| format!("{:02x}", b)).collect();
+        Cow::Owned(format!("{}{}", HASHED_QUERY_PREFIX, hex))
+    }
+
+    /// `limit` lowered to `max_entries`.
+    pub fn capacity(&self, limit: usize) -> usize {
+        self.max_entries.map_or(limit, |max| max.min(limit))
+    }
+
+    /// Whether a record made `at` has outlived `max_age` at `now`.
+    pub fn expired(&self, at: DateTime<Utc>, now: DateTime<Utc>) -> bool {
+        self.max_age
+            .and_then(|age| chrono::Duration::from_std(age).ok())
+            .is_some_and(|age| now.signed_duration_since(at) > age)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hashes_query_text_when_enabled() {
+        let plain = RetentionPolicy::default();
+        assert_eq!(plain.query_text("What about Rust?"), "What about Rust?");
+
+        let hashed = RetentionPolicy::new(None, None, true);
+        let first = hashed.query_text("What about Rust?");
+        assert!(first.starts_with(HASHED_QUERY_PREFIX));
+        assert!(!first.contains("Rust"));
+        assert_eq!(first, hashed.query_text("What about Rust?"));
+        assert_ne!(first, hashed.query_text("What about Go?"));
+        // Another process (key) hashes differently
+        assert_ne!(
+            first,
+            RetentionPolicy::new(None, None, true).query_text("What about Rust?")
+        );
+    }
+
+    #[test]
+    fn test_age_and_size_limits() {
+        let policy = RetentionPolicy::new(Some(Duration::from_secs(3600)), Some(10), false);
+        let now = Utc::now();
+        assert!(policy.expired(now - chrono::Duration::hours(2), now));
+        assert!(!policy.expired(now - chrono::Duration::minutes(30), now));
+        assert_eq!(policy.capacity(50), 10);
+        assert_eq!(policy.capacity(5), 5);
+
+        let unbounded = RetentionPolicy::default();
+        assert!(!unbounded.expired(DateTime::<Utc>::MIN_UTC, now));
+        assert_eq!(unbounded.capacity(50), 50);
+    }
+}
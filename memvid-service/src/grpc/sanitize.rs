@@ -0,0 +1,225 @@
+//! Output encoding for snippets and answers.
+//!
+//! Frames ingested from web sources can contain markup. When the frontend
+//! renders snippets as rich text, raw HTML (or a Markdown link to a
+//! `javascript:` URL) is an XSS vector, so clients can ask for HTML to be
+//! escaped or stripped before it leaves the service.
+
+use crate::generated::memvid::v1::{OutputEncoding, SearchHit};
+
+/// URL schemes neutralized in Markdown link targets.
+const UNSAFE_SCHEMES: [&str; 3] = ["javascript:", "vbscript:", "data:"];
+
+/// Elements whose content is dropped along with the tags when stripping.
+const RAW_TEXT_ELEMENTS: [&str; 2] = ["script", "style"];
+
+/// Encode `text` for the requested output encoding.
+pub fn encode(text: &str, encoding: OutputEncoding) -> String {
+    match encoding {
+        OutputEncoding::Raw => text.to_string(),
+        OutputEncoding::EscapeHtml => neutralize_links(&escape_html(text)),
+        OutputEncoding::StripHtml => neutralize_links(&strip_html(text)),
+    }
+}
+
+/// Encode the user-visible text fields of each hit in place.
+pub fn encode_hits(hits: &mut [SearchHit], encoding: OutputEncoding) {
+    encode_hit_labels(hits, encoding);
+    if encoding == OutputEncoding::Raw {
+        return;
+    }
+    for hit in hits {
+        hit.snippet = encode(&hit.snippet, encoding);
+    }
+}
+
+/// Encode the title and link mentions of each hit in place, leaving the
+/// snippet to be rendered once it fits the response budget.
+pub fn encode_hit_labels(hits: &mut [SearchHit], encoding: OutputEncoding) {
+    if encoding == OutputEncoding::Raw {
+        return;
+    }
+    for hit in hits {
+        hit.title = encode(&hit.title, encoding);
+        for link in &mut hit.links {
+            link.mention = encode(&link.mention, encoding);
+        }
+    }
+}
+
+/// Escape the HTML special characters `& < > " '`.
+pub fn escape_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Remove HTML tags, comments, and the content of `<script>`/`<style>` elements.
+///
+/// A `<` that does not start a tag (e.g., "a < b") is kept.
+pub fn strip_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(open) = rest.find('<') {
+        out.push_str(&rest[..open]);
+        let tag = &rest[open..];
+
+        let starts_tag = tag[1..]
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || matches!(c, '/' | '!' | '?'));
+        if !starts_tag {
+            out.push('<');
+            rest = &tag[1..];
+            continue;
+        }
+
+        let end = if tag.starts_with("<!--") {
+            tag.find("-->").map(|i| i + 3)
+        } else {
+            tag.find('>').map(|i| i + 1)
+        };
+        let Some(end) = end else {
+            // Unterminated tag: drop the remainder
+            rest = "";
+            break;
+        };
+
+        let name = tag[1..end]
+            .trim_start_matches('/')
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        rest = &tag[end..];
+
+        if !tag.starts_with("</") && RAW_TEXT_ELEMENTS.contains(&name.as_str()) {
+            let close = format!("</{}", name);
+            rest = match find_ignore_ascii_case(rest, &close) {
+                Some(i) => rest[i..].find('>').map_or("", |j| &rest[i + j + 1..]),
+                None => "",
+            };
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Replace unsafe schemes in Markdown link targets (`[x](javascript:...)`) with `#`.
+fn neutralize_links(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(i) = rest.find("](") {
+        out.push_str(&rest[..i + 2]);
+        rest = &rest[i + 2..];
+
+        let target = rest.trim_start();
+        if let Some(scheme) = UNSAFE_SCHEMES.iter().find(|s| {
+            target
+                .get(..s.len())
+                .is_some_and(|t| t.eq_ignore_ascii_case(s))
+        }) {
+            out.push('#');
+            rest = &target[scheme.len()..];
+            // Drop the rest of the target up to its closing parenthesis
+            rest = closing_paren(rest).map_or("", |j| &rest[j..]);
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Byte offset of the `)` closing a link target, honoring nested parentheses.
+fn closing_paren(text: &str) -> Option<usize> {
+    let mut depth = 0usize;
+    for (i, c) in text.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth == 0 => return Some(i),
+            ')' => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+fn find_ignore_ascii_case(haystack: &str, needle: &str) -> Option<usize> {
+    haystack
+        .to_ascii_lowercase()
+        .find(&needle.to_ascii_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_raw_is_unchanged() {
+        let text = "<b>Rust</b> & Go";
+        assert_eq!(encode(text, OutputEncoding::Raw), text);
+    }
+
+    #[test]
+    fn test_escape_html() {
+        assert_eq!(
+            encode(
+                "<img src=x onerror=\"alert('x')\"> & more",
+                OutputEncoding::EscapeHtml
+            ),
+            "&lt;img src=x onerror=&quot;alert(&#39;x&#39;)&quot;&gt; &amp; more"
+        );
+    }
+
+    #[test]
+    fn test_strip_html_removes_tags_and_scripts() {
+        let text = "Led <b>platform</b> team<script>alert(1)</SCRIPT>.<!-- note --> a < b";
+        assert_eq!(
+            encode(text, OutputEncoding::StripHtml),
+            "Led platform team. a < b"
+        );
+    }
+
+    #[test]
+    fn test_strip_html_unterminated_tag() {
+        assert_eq!(strip_html("safe <img src=x onerror=alert(1)"), "safe ");
+    }
+
+    #[test]
+    fn test_unsafe_markdown_links_neutralized() {
+        assert_eq!(
+            encode(
+                "[cv](JavaScript:alert(1)) and [site](https://example.com)",
+                OutputEncoding::StripHtml
+            ),
+            "[cv](#) and [site](https://example.com)"
+        );
+    }
+
+    #[test]
+    fn test_encode_hits() {
+        let mut hits = vec![SearchHit {
+            title: "<i>Title</i>".to_string(),
+            score: 0.9,
+            snippet: "<p>Body</p>".to_string(),
+            tags: vec!["skills".to_string()],
+            links: Vec::new(),
+            ingested_at: None,
+            source_version: None,
+        }];
+        encode_hits(&mut hits, OutputEncoding::StripHtml);
+
+        assert_eq!(hits[0].title, "Title");
+        assert_eq!(hits[0].snippet, "Body");
+    }
+}
@@ -1,178 +1,1214 @@
 //! gRPC service implementations for MemvidService and Health.
 
+use prost::Message;
+use std::collections::BTreeMap;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::StreamExt;
+use tonic::metadata::MetadataMap;
 use tonic::{Request, Response, Status};
-use tracing::{info, instrument};
+use tracing::{info, instrument, warn};
 
+use crate::error::ServiceError;
 use crate::generated::memvid::v1::{
-    health_check_response::Status as HealthStatus, health_server::Health,
-    memvid_service_server::MemvidService, AskMode as ProtoAskMode, AskRequest, AskResponse,
-    AskStats, GetStateRequest, GetStateResponse, HealthCheckRequest, HealthCheckResponse,
-    SearchHit, SearchRequest, SearchResponse,
+    ask_stream_chunk::Chunk, health_check_response::Status as HealthStatus, health_server::Health,
+    memvid_service_server::MemvidService, AdaptiveOptions as ProtoAdaptiveOptions,
+    AppendTurnRequest, AskEvidence, AskMode as ProtoAskMode, AskRequest, AskResponse, AskStats,
+    AskStreamChunk, AskStreamSummary, AspectStats as ProtoAspectStats, BackendHealth, ChatRequest,
+    ChatResponse, CreateSessionRequest, EnsembleStats, EntitySummary, FitAssessmentRequest,
+    FitAssessmentResponse, GenerateSummaryRequest, GenerateSummaryResponse, GetSessionRequest,
+    GetStateRequest, GetStateResponse, HealthCheckRequest, HealthCheckResponse,
+    ListEntitiesRequest, ListEntitiesResponse, ListTagsRequest, ListTagsResponse, OutputEncoding,
+    ParaphraseStats as ProtoParaphraseStats, RankingBoosts, ReadinessGate, SearchHit,
+    SearchRequest, SearchResponse, SessionResponse, SuggestRequest, SuggestResponse,
+    Suggestion as ProtoSuggestion, SuggestionKind as ProtoSuggestionKind, SummaryKind, TagCount,
+    Turn as ProtoTurn, TurnRole,
+};
+use crate::lifecycle::Drain;
+use crate::llm::LlmClient;
+use crate::memvid::{
+    apply_language_preference, aspect_ask, check_citations, context_answer, ensemble_ask,
+    AclContext, AdaptiveOptions, AskEvent, AskMode as SearcherAskMode,
+    AskRequest as SearcherAskRequest, AskStats as SearcherAskStats, AspectPlanner, AspectStats,
+    Audience, Boosts, CitationCheck, CitationPolicy, CitationStream, DeepSearchStore,
+    EmbedderChain, ParaphraseStats, ReloadableSearcher, SearchRequest as SearcherSearchRequest,
+    SearchResult, Searcher, SearcherRegistry, SuggestionKind, VisibilityStore, BOOST_OVERFETCH,
+    DEFAULT_INDEX, DEFAULT_SUGGESTIONS, LANGUAGE_OVERFETCH, MAX_SUGGESTIONS, VISIBILITY_OVERFETCH,
+    VISIBILITY_TAG_PREFIX,
 };
-use crate::memvid::{AskMode as SearcherAskMode, AskRequest as SearcherAskRequest, Searcher};
 use crate::metrics;
+use crate::readiness::{verdict, Gate, GateStatus, Readiness, Verdict};
+use crate::runtime_config::{RuntimeConfig, RuntimeConfigReceiver};
+use crate::session::{Role, Session, SessionManager, MAX_TURN_CHARS};
+
+use super::acl::{caller_acl, refuse_when_enforced};
+use super::budget::fit_response;
+use super::chat::{self, CHAT_HISTORY_TURNS};
+use super::coverage::CoverageTracker;
+use super::cursor::{ask_scope, CursorCodec};
+use super::entities::EntityLinker;
+use super::fit::{self, FitAspect};
+use super::highlight::{Highlighter, SnippetRenderer};
+use super::jwt::caller_subject;
+use super::legacy;
+use super::locale::{localize_answer, Locale};
+use super::profile::{Profile, ProfileCache};
+use super::query_stats::QueryStats;
+use super::retention::RetentionPolicy;
+use super::sanitize::{encode, encode_hit_labels, encode_hits};
+use super::summary::{self, DEFAULT_SUMMARY_WORDS, MAX_SUMMARY_WORDS};
+use super::temporal::{TemporalInput, TemporalValidator};
+use super::topics::TopicClassifier;
+use super::usage::{self, LlmUsage, UsageLedger};
+use super::voice;
+
+/// How long an unclaimed two-tier deep search result is kept.
+const DEEP_SEARCH_TTL: Duration = Duration::from_secs(60);
+
+/// Default allowed clock skew for future timestamps.
+pub(super) const DEFAULT_CLOCK_SKEW_TOLERANCE: Duration = Duration::from_secs(300);
+
+/// Default maximum serialized response size (just under tonic's 4 MiB decode limit).
+pub(super) const DEFAULT_MAX_RESPONSE_BYTES: usize = 4_000_000;
 
 /// gRPC implementation of the MemvidService.
 pub struct MemvidGrpcService {
     searcher: Arc<dyn Searcher>,
+    indexes: Arc<SearcherRegistry>,
+    deep_searches: DeepSearchStore,
+    clock_skew_tolerance: Duration,
+    max_response_bytes: usize,
+    usage: Arc<UsageLedger>,
+    coverage: Arc<CoverageTracker>,
+    query_stats: Arc<QueryStats>,
+    profiles: Arc<ProfileCache>,
+    retention: RetentionPolicy,
+    cursors: Arc<CursorCodec>,
+    visibility: Arc<VisibilityStore>,
+    topics: Option<Arc<TopicClassifier>>,
+    runtime: RuntimeConfigReceiver,
+    citations: CitationPolicy,
+    /// Chat model writing GenerateSummary texts (None = extractive only)
+    llm: Option<Arc<LlmClient>>,
+    sessions: Arc<SessionManager>,
+    /// Retry searches without hits with a spelling-corrected query
+    spell_correction: bool,
+    /// Plans the aspects of decomposed asks
+    aspects: Arc<AspectPlanner>,
+    /// Pinned "now" in deterministic mode (None = the wall clock)
+    deterministic_now: Option<i64>,
 }
 
 impl MemvidGrpcService {
     /// Create a new MemvidGrpcService with the given searcher implementation.
     pub fn new(searcher: Arc<dyn Searcher>) -> Self {
-        Self { searcher }
+        Self {
+            indexes: Arc::new(SearcherRegistry::new(DEFAULT_INDEX, Arc::clone(&searcher))),
+            searcher,
+            deep_searches: DeepSearchStore::new(DEEP_SEARCH_TTL),
+            clock_skew_tolerance: DEFAULT_CLOCK_SKEW_TOLERANCE,
+            max_response_bytes: DEFAULT_MAX_RESPONSE_BYTES,
+            usage: Arc::new(UsageLedger::default()),
+            coverage: Arc::new(CoverageTracker::default()),
+            query_stats: Arc::new(QueryStats::default()),
+            profiles: Arc::new(ProfileCache::default()),
+            retention: RetentionPolicy::default(),
+            cursors: Arc::new(CursorCodec::default()),
+            visibility: Arc::new(VisibilityStore::default()),
+            topics: None,
+            runtime: RuntimeConfig::default().fixed(),
+            citations: CitationPolicy::default(),
+            llm: None,
+            sessions: Arc::new(SessionManager::default()),
+            spell_correction: true,
+            aspects: Arc::new(AspectPlanner::default()),
+            deterministic_now: None,
+        }
+    }
+
+    /// Serve Search and Ask from the registry's index named in the
+    /// request; its default index replaces the searcher given to `new`.
+    pub fn with_registry(mut self, registry: Arc<SearcherRegistry>) -> Self {
+        self.searcher = Arc::clone(registry.default_searcher());
+        self.indexes = registry;
+        self
+    }
+
+    /// Set how far in the future temporal request fields may be.
+    pub fn with_clock_skew_tolerance(mut self, tolerance: Duration) -> Self {
+        self.clock_skew_tolerance = tolerance;
+        self
+    }
+
+    /// Set the maximum serialized response size; larger responses are trimmed.
+    pub fn with_max_response_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_response_bytes = max_bytes;
+        self
+    }
+
+    /// Account LLM usage in a ledger shared with other services.
+    pub fn with_usage_ledger(mut self, ledger: Arc<UsageLedger>) -> Self {
+        self.usage = ledger;
+        self
+    }
+
+    /// Record surfaced frames in a tracker shared with other services.
+    pub fn with_coverage_tracker(mut self, tracker: Arc<CoverageTracker>) -> Self {
+        self.coverage = tracker;
+        self
+    }
+
+    /// Record queries for the analytics report in stats shared with other
+    /// services.
+    pub fn with_query_stats(mut self, stats: Arc<QueryStats>) -> Self {
+        self.query_stats = stats;
+        self
+    }
+
+    /// Read the profile through a cache shared with other services.
+    pub fn with_profile_cache(mut self, cache: Arc<ProfileCache>) -> Self {
+        self.profiles = cache;
+        self
+    }
+
+    /// Log query text as `retention` allows (hashed with `hash_queries`).
+    pub fn with_retention(mut self, retention: RetentionPolicy) -> Self {
+        self.retention = retention;
+        self
+    }
+
+    /// Verify page cursors with a codec shared with other services.
+    pub fn with_cursor_codec(mut self, codec: Arc<CursorCodec>) -> Self {
+        self.cursors = codec;
+        self
+    }
+
+    /// Filter frames by the visibility set through the Admin service.
+    pub fn with_visibility_store(mut self, store: Arc<VisibilityStore>) -> Self {
+        self.visibility = store;
+        self
+    }
+
+    /// Record the topic of every Ask question with `classifier`.
+    pub fn with_topic_classifier(mut self, classifier: Arc<TopicClassifier>) -> Self {
+        self.topics = Some(classifier);
+        self
+    }
+
+    /// Take LLM synthesis and request defaults from the runtime config;
+    /// with synthesis off, Ask always returns context-only answers.
+    pub fn with_runtime_config(mut self, runtime: RuntimeConfigReceiver) -> Self {
+        self.runtime = runtime;
+        self
+    }
+
+    /// Set how synthesized answers are held to their citation markers.
+    pub fn with_citation_policy(mut self, policy: CitationPolicy) -> Self {
+        self.citations = policy;
+        self
+    }
+
+    /// Let `client` write GenerateSummary texts. Ask synthesis is wired
+    /// into the searcher stack instead.
+    pub fn with_llm_client(mut self, client: Arc<LlmClient>) -> Self {
+        self.llm = Some(client);
+        self
+    }
+
+    /// Enable or disable retrying searches without hits with their
+    /// misspelled words corrected.
+    pub fn with_spell_correction(mut self, enabled: bool) -> Self {
+        self.spell_correction = enabled;
+        self
+    }
+
+    /// Plan the aspects of decomposed asks with `planner`.
+    pub fn with_aspect_planner(mut self, planner: Arc<AspectPlanner>) -> Self {
+        self.aspects = planner;
+        self
+    }
+
+    /// Keep conversation sessions in a manager shared with other services.
+    pub fn with_session_manager(mut self, sessions: Arc<SessionManager>) -> Self {
+        self.sessions = sessions;
+        self
+    }
+
+    /// Answer reproducibly: resolve relative times and freshness against
+    /// `now` (Unix seconds) instead of the clock, and run ensemble and
+    /// decomposed asks as plain asks, without paraphrases or aspects.
+    pub fn with_deterministic(mut self, now: i64) -> Self {
+        self.deterministic_now = Some(now);
+        self
+    }
+
+    /// Current Unix time, pinned in deterministic mode.
+    fn now(&self) -> i64 {
+        self.deterministic_now
+            .unwrap_or_else(|| chrono::Utc::now().timestamp())
+    }
+
+    /// The searcher request a plain public Ask of `question` resolves to
+    /// on the default index, as the answer cache keys it.
+    pub fn warm_request(
+        &self,
+        question: &str,
+        use_llm: bool,
+    ) -> Result<SearcherAskRequest, ServiceError> {
+        let req = AskRequest {
+            question: question.to_string(),
+            use_llm,
+            ..Default::default()
+        };
+        let prepared = self.prepare_ask(&*self.searcher, &req, None, Audience::Public, None)?;
+        Ok(prepared.request)
+    }
+
+    /// `session` as returned by the session RPCs.
+    fn session_response(&self, session: Session) -> SessionResponse {
+        SessionResponse {
+            expires_at: session.updated_at + self.sessions.ttl().as_secs() as i64,
+            session_id: session.id,
+            turns: session
+                .turns
+                .into_iter()
+                .map(|turn| ProtoTurn {
+                    role: match turn.role {
+                        Role::User => TurnRole::User,
+                        Role::Assistant => TurnRole::Assistant,
+                    } as i32,
+                    content: turn.content,
+                    created_at: turn.created_at,
+                })
+                .collect(),
+            created_at: session.created_at,
+        }
+    }
+
+    /// Evidence for each of `queries` (`top_k` hits each, 0 for the default),
+    /// visible to the caller and without duplicates, in query order.
+    ///
+    /// Retrieval for FitAssessment and GenerateSummary; queries run one
+    /// after another so each keeps the request's deadline and search limit.
+    async fn aspect_evidence(
+        &self,
+        searcher: &dyn Searcher,
+        queries: Vec<String>,
+        top_k: i32,
+        audience: Audience,
+        acl: Option<AclContext>,
+    ) -> Result<Vec<SearchResult>, Status> {
+        let runtime = self.runtime.borrow().clone();
+        let top_k = runtime.top_k(top_k);
+        // Retrieve extra candidates to make up for frames the caller may not see
+        let overfetch = if self
+            .visibility
+            .restricts(audience, &searcher.section_counts())
+        {
+            VISIBILITY_OVERFETCH
+        } else {
+            1
+        };
+
+        let acronyms = searcher.acronyms();
+        let mut evidence: Vec<SearchResult> = Vec::new();
+        for query in queries {
+            let mut result = searcher
+                .ask(SearcherAskRequest {
+                    question: acronyms.expand_query(&query),
+                    use_llm: false,
+                    top_k: top_k.saturating_mul(overfetch),
+                    filters: Default::default(),
+                    start: 0,
+                    end: 0,
+                    snippet_chars: runtime.default_snippet_chars,
+                    mode: SearcherAskMode::Hybrid,
+                    uri: None,
+                    cursor: None,
+                    as_of_frame: None,
+                    as_of_ts: None,
+                    adaptive: None,
+                    acl: acl.clone(),
+                })
+                .await
+                .map_err(Status::from)?;
+            self.visibility
+                .retain_visible(&mut result.evidence, audience);
+            for hit in result.evidence.into_iter().take(top_k.max(0) as usize) {
+                let seen = evidence
+                    .iter()
+                    .any(|e| e.frame_id == hit.frame_id && e.title == hit.title);
+                if !seen {
+                    evidence.push(hit);
+                }
+            }
+        }
+        self.coverage
+            .record(evidence.iter().filter_map(|e| e.frame_id));
+        Ok(evidence)
+    }
+
+    fn observe_topic(&self, question: &str) {
+        if let Some(topics) = &self.topics {
+            topics.observe(question);
+        }
+    }
+
+    /// Validate an Ask request and resolve it into a searcher request.
+    ///
+    /// Shared by Ask and AskStream so both apply the same defaults,
+    /// temporal validation and cost gating.
+    fn prepare_ask(
+        &self,
+        searcher: &dyn Searcher,
+        req: &AskRequest,
+        accept_language: Option<&str>,
+        audience: Audience,
+        acl: Option<AclContext>,
+    ) -> Result<PreparedAsk, ServiceError> {
+        // Record the question in span
+        let logged_question = self.retention.query_text(&req.question);
+        tracing::Span::current().record("question", &*logged_question);
+
+        info!(
+            question = %logged_question,
+            mode = ?req.mode,
+            top_k = req.top_k,
+            "Processing ask request"
+        );
+
+        // Apply defaults
+        let runtime = self.runtime.borrow().clone();
+        let top_k = runtime.top_k(req.top_k);
+        let snippet_chars = runtime.snippet_chars(req.snippet_chars);
+
+        // Map proto AskMode to searcher AskMode
+        let mode = match ProtoAskMode::try_from(req.mode) {
+            Ok(ProtoAskMode::Sem) => SearcherAskMode::Sem,
+            Ok(ProtoAskMode::Lex) => SearcherAskMode::Lex,
+            _ => SearcherAskMode::Hybrid, // Default to Hybrid
+        };
+
+        let locale = Locale::resolve(&req.locale, accept_language)?;
+        let language = Locale::preferred_language(&req.preferred_language)?.map(Locale::code);
+        let boosts = ranking_boosts(req.boosts.as_ref())?;
+        let adaptive = adaptive_options(req.adaptive, req.adaptive_options.as_ref())?;
+        check_ensemble(req.ensemble, &req.cursor)?;
+        if req.decompose && (req.ensemble || !req.cursor.is_empty()) {
+            return Err(ServiceError::InvalidRequest(
+                "decompose cannot be combined with ensemble or cursor".to_string(),
+            ));
+        }
+
+        // Resolve and validate temporal bounds
+        let validator = TemporalValidator {
+            now: self.now(),
+            tolerance_secs: self.clock_skew_tolerance.as_secs() as i64,
+        };
+        let bounds = validator.normalize(&TemporalInput {
+            start: req.start,
+            start_expr: &req.start_expr,
+            end: req.end,
+            end_expr: &req.end_expr,
+            as_of_ts: req.as_of_ts,
+            as_of_expr: &req.as_of_expr,
+        })?;
+
+        // Skip synthesis once the daily LLM cost cap is reached
+        let wants_llm = req.use_llm && runtime.llm_synthesis;
+        let llm_capped = wants_llm && !self.usage.synthesis_allowed();
+        let use_llm = wants_llm && !llm_capped;
+        if llm_capped {
+            metrics::increment_llm_capped();
+        }
+
+        let acronyms = searcher.acronyms();
+        let highlight_terms = acronyms.highlight_terms(&req.question);
+
+        // Build searcher request
+        let mut request = SearcherAskRequest {
+            question: acronyms.expand_query(&req.question),
+            use_llm,
+            // Retrieve extra candidates to re-rank
+            top_k: ranking_window(top_k, language, boosts.as_ref()),
+            filters: req.filters.clone(),
+            start: bounds.start,
+            end: bounds.end,
+            snippet_chars,
+            mode,
+            uri: (!req.uri.is_empty()).then(|| req.uri.clone()),
+            cursor: None,
+            as_of_frame: req.as_of_frame,
+            as_of_ts: bounds.as_of_ts,
+            adaptive,
+            acl,
+        };
+        let offset = self.cursors.decode(
+            &req.cursor,
+            searcher.generation(),
+            ask_scope(
+                &request,
+                language,
+                req.strict_language,
+                boosts.as_ref(),
+                &req.index,
+            ),
+        )?;
+        request.cursor = (offset > 0).then(|| offset.to_string());
+        // Retrieve extra candidates to make up for frames the caller may not see
+        if self
+            .visibility
+            .restricts(audience, &searcher.section_counts())
+        {
+            request.top_k = request.top_k.saturating_mul(VISIBILITY_OVERFETCH);
+        }
+
+        Ok(PreparedAsk {
+            request,
+            top_k,
+            locale,
+            language,
+            strict_language: req.strict_language,
+            boosts,
+            link_entities: req.link_entities,
+            encoding: OutputEncoding::try_from(req.output_encoding).unwrap_or_default(),
+            use_llm,
+            llm_capped,
+            highlight_terms,
+            visibility: Arc::clone(&self.visibility),
+            audience,
+            citations: self.citations,
+            now: self.now(),
+        })
+    }
+}
+
+/// Buffered AskStream chunks per call before the producer waits on the client.
+const ASK_STREAM_BUFFER: usize = 16;
+
+/// Visibility audience of the caller, from its API key.
+pub(super) fn caller_audience(store: &VisibilityStore, metadata: &MetadataMap) -> Audience {
+    store.audience(
+        metadata
+            .get(usage::API_KEY_HEADER)
+            .and_then(|v| v.to_str().ok()),
+    )
+}
+
+/// Validated ranking boosts of a request; None when unset or empty.
+pub(super) fn ranking_boosts(
+    boosts: Option<&RankingBoosts>,
+) -> Result<Option<Boosts>, ServiceError> {
+    match boosts {
+        Some(boosts) => Boosts::new(
+            &boosts.tag_boosts,
+            &boosts.uri_boosts,
+            boosts.recency_weight,
+        ),
+        None => Ok(None),
+    }
+}
+
+/// Adaptive retrieval options of a request: enabled by `adaptive`, or by
+/// `options` unless `adaptive` is false.
+///
+/// # Errors
+/// `InvalidField` for options [`AdaptiveOptions::validate`] rejects.
+pub(super) fn adaptive_options(
+    adaptive: Option<bool>,
+    options: Option<&ProtoAdaptiveOptions>,
+) -> Result<Option<AdaptiveOptions>, ServiceError> {
+    if adaptive == Some(false) || (adaptive.is_none() && options.is_none()) {
+        return Ok(None);
+    }
+    let options = options.map_or_else(AdaptiveOptions::default, |options| AdaptiveOptions {
+        min_score: options.min_score,
+        relative_threshold: options.relative_threshold,
+        max_score_drop: options.max_score_drop,
+        min_results: options.min_results.map(|n| n as usize),
+        max_results: options.max_results.map(|n| n as usize),
+    });
+    options.validate()?;
+    Ok(Some(options))
+}
+
+/// Hold a synthesized answer to the evidence returned with it under
+/// `policy`, falling back to the context answer when every claim is
+/// stripped. Returns what the check changed.
+pub(super) fn enforce_citations(
+    policy: CitationPolicy,
+    answer: &mut String,
+    stats: &mut SearcherAskStats,
+    synthesized: &[SearchResult],
+    evidence: &[SearchResult],
+) -> CitationCheck {
+    let mut check = check_citations(answer, synthesized, evidence, policy);
+    metrics::record_citation_check(check.invalid_markers, check.claims_removed);
+    if check.answer.is_empty() && !answer.is_empty() {
+        *answer = context_answer(evidence);
+        stats.used_fallback = true;
+    } else {
+        *answer = std::mem::take(&mut check.answer);
+    }
+    check
+}
+
+/// Reject an ensemble ask that continues from a cursor: fused evidence has
+/// no page order.
+pub(super) fn check_ensemble(ensemble: bool, cursor: &str) -> Result<(), ServiceError> {
+    if ensemble && !cursor.is_empty() {
+        return Err(ServiceError::InvalidRequest(
+            "ensemble cannot be combined with cursor".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Per-query statistics of an ensemble ask.
+pub(super) fn ensemble_stats(stats: Vec<ParaphraseStats>) -> EnsembleStats {
+    EnsembleStats {
+        queries: stats
+            .into_iter()
+            .map(|stats| ProtoParaphraseStats {
+                query: stats.query,
+                candidates: stats.candidates as i32,
+                new_candidates: stats.new_candidates as i32,
+                retrieval_ms: stats.retrieval_ms,
+            })
+            .collect(),
+    }
+}
+
+/// Per-aspect statistics of a decomposed ask.
+fn aspect_stats(stats: Vec<AspectStats>) -> Vec<ProtoAspectStats> {
+    stats
+        .into_iter()
+        .map(|stats| ProtoAspectStats {
+            aspect: stats.aspect,
+            query: stats.query,
+            candidates: stats.candidates as i32,
+            new_candidates: stats.new_candidates as i32,
+            retrieval_ms: stats.retrieval_ms,
+        })
+        .collect()
+}
+
+/// Candidates to retrieve for `top_k` hits re-ranked by language and
+/// boosts.
+pub(super) fn ranking_window(top_k: i32, language: Option<&str>, boosts: Option<&Boosts>) -> i32 {
+    let mut window = top_k;
+    if language.is_some() {
+        window = window.saturating_mul(LANGUAGE_OVERFETCH);
+    }
+    if boosts.is_some() {
+        window = window.saturating_mul(BOOST_OVERFETCH);
+    }
+    window
+}
+
+/// The `accept-language` header of a request, if present.
+fn accept_language<T>(request: &Request<T>) -> Option<String> {
+    request
+        .metadata()
+        .get("accept-language")
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string)
+}
+
+/// A validated Ask request and the options for rendering its response.
+struct PreparedAsk {
+    request: SearcherAskRequest,
+    top_k: i32,
+    locale: Option<Locale>,
+    language: Option<&'static str>,
+    strict_language: bool,
+    boosts: Option<Boosts>,
+    link_entities: bool,
+    encoding: OutputEncoding,
+    use_llm: bool,
+    llm_capped: bool,
+    highlight_terms: Vec<String>,
+    visibility: Arc<VisibilityStore>,
+    audience: Audience,
+    citations: CitationPolicy,
+    /// Time freshness boosts are measured from
+    now: i64,
+}
+
+impl PreparedAsk {
+    /// Load the profile (and its entity linker) when the request asks for
+    /// links.
+    async fn profile(
+        &self,
+        profiles: &ProfileCache,
+        searcher: &dyn Searcher,
+    ) -> Result<Option<Arc<Profile>>, Status> {
+        if !self.link_entities {
+            return Ok(None);
+        }
+        profiles.get(searcher).await.map(Some).map_err(Status::from)
+    }
+
+    /// Drop evidence the caller may not see, apply the language preference
+    /// and boosts, and trim the overfetched candidates.
+    ///
+    /// Returns whether restricted evidence was dropped.
+    fn rank_evidence(&self, evidence: &mut Vec<SearchResult>) -> bool {
+        let hidden = self.visibility.retain_visible(evidence, self.audience) > 0;
+        if let Some(language) = self.language {
+            apply_language_preference(evidence, language, self.strict_language);
+        }
+        if let Some(boosts) = &self.boosts {
+            boosts.apply(evidence, self.now);
+        }
+        evidence.truncate(self.top_k.max(0) as usize);
+        hidden
+    }
+
+    /// Convert evidence to hits with encoded titles, and entity links when
+    /// requested. Snippets keep their source text until they are rendered.
+    fn evidence_hits(
+        &self,
+        evidence: Vec<SearchResult>,
+        linker: Option<&EntityLinker>,
+    ) -> Vec<SearchHit> {
+        let mut hits: Vec<SearchHit> = evidence
+            .into_iter()
+            .map(|e| SearchHit {
+                links: linker
+                    .map(|linker| linker.link(&e.snippet))
+                    .unwrap_or_default(),
+                title: e.title,
+                score: e.score,
+                snippet: e.snippet,
+                tags: e.tags,
+                ingested_at: e.ingested_at,
+                source_version: e.source_version,
+            })
+            .collect();
+        encode_hit_labels(&mut hits, self.encoding);
+        hits
+    }
+
+    /// Localize and encode the answer.
+    fn render_answer(&self, answer: &str) -> String {
+        match self.locale {
+            Some(locale) => encode(&localize_answer(answer, locale), self.encoding),
+            None => encode(answer, self.encoding),
+        }
+    }
+
+    /// Encoded highlight terms.
+    fn highlight_terms(&self) -> Vec<String> {
+        self.highlight_terms
+            .iter()
+            .map(|term| encode(term, self.encoding))
+            .collect()
+    }
+
+    /// Response statistics, including LLM usage.
+    fn stats(
+        &self,
+        stats: &SearcherAskStats,
+        llm_usage: &LlmUsage,
+        citations: &CitationCheck,
+    ) -> AskStats {
+        AskStats {
+            candidates_retrieved: stats.candidates_retrieved,
+            results_returned: stats.results_returned,
+            retrieval_ms: stats.retrieval_ms,
+            reranking_ms: stats.reranking_ms,
+            used_fallback: stats.used_fallback,
+            prompt_tokens: llm_usage.prompt_tokens as i32,
+            completion_tokens: llm_usage.completion_tokens as i32,
+            estimated_cost_usd: llm_usage.cost_usd,
+            llm_capped: self.llm_capped,
+            invalid_citations: citations.invalid_markers as i32,
+            uncited_claims_removed: citations.claims_removed as i32,
+            aspects: Vec::new(),
+        }
+    }
+}
+
+impl From<Chunk> for AskStreamChunk {
+    fn from(chunk: Chunk) -> Self {
+        Self { chunk: Some(chunk) }
     }
 }
 
 #[tonic::async_trait]
 impl MemvidService for MemvidGrpcService {
-    #[instrument(skip(self, request), fields(query))]
+    #[instrument(skip(self, request), fields(query, caller = caller_subject(&request)))]
     async fn search(
         &self,
         request: Request<SearchRequest>,
     ) -> Result<Response<SearchResponse>, Status> {
-        let req = request.into_inner();
+        let started = Instant::now();
+        let audience = caller_audience(&self.visibility, request.metadata());
+        let acl = caller_acl(&request);
+        let mut req = request.into_inner();
+        let request_bytes = req.encoded_len();
+        let legacy_fields = legacy::search_fields(&req);
+        voice::normalize_query(&mut req.query, req.source);
+        let searcher = Arc::clone(self.indexes.get(&req.index).map_err(Status::from)?);
 
         // Record the query in span
-        tracing::Span::current().record("query", &req.query);
+        let logged_query = self.retention.query_text(&req.query);
+        tracing::Span::current().record("query", &*logged_query);
 
         info!(
-            query = %req.query,
+            query = %logged_query,
             top_k = req.top_k,
             "Processing search request"
         );
 
         // Apply defaults
-        let top_k = if req.top_k == 0 { 5 } else { req.top_k };
-        let snippet_chars = if req.snippet_chars == 0 {
-            200
+        let runtime = self.runtime.borrow().clone();
+        let top_k = runtime.top_k(req.top_k);
+        let snippet_chars = runtime.snippet_chars(req.snippet_chars);
+
+        // Retrieve extra candidates to re-rank by language and boosts
+        let language = Locale::preferred_language(&req.preferred_language)
+            .map_err(Status::from)?
+            .map(Locale::code);
+        let boosts = ranking_boosts(req.boosts.as_ref()).map_err(Status::from)?;
+        let mut window = ranking_window(top_k, language, boosts.as_ref());
+        // and to make up for frames the caller may not see
+        if self
+            .visibility
+            .restricts(audience, &searcher.section_counts())
+        {
+            window = window.saturating_mul(VISIBILITY_OVERFETCH);
+        }
+
+        // Expand acronyms learned from the corpus in both directions
+        let acronyms = searcher.acronyms();
+
+        // Build searcher request (a zero budget means unbounded)
+        let mut search_request = SearcherSearchRequest {
+            query: acronyms.expand_query(&req.query),
+            top_k: window,
+            snippet_chars,
+            budget_ms: req.budget_ms.filter(|&budget| budget > 0),
+            filters: req.filters.clone(),
+            uri: Some(req.uri.clone()).filter(|uri| !uri.is_empty()),
+            acl,
+        };
+
+        // Perform search: claim a background deep pass, or run the first pass
+        let mut deep_cursor = String::new();
+        let mut corrected_query = None;
+        let mut result = if !req.deep_cursor.is_empty() {
+            self.deep_searches
+                .take(&req.deep_cursor, search_request.acl.as_ref())
+                .await
+                .unwrap_or_else(|| {
+                    Err(ServiceError::InvalidRequest(format!(
+                        "Unknown or expired deep_cursor: {}",
+                        req.deep_cursor
+                    )))
+                })
+                .map_err(Status::from)?
         } else {
-            req.snippet_chars
+            let mut result = searcher
+                .search(search_request.clone())
+                .await
+                .map_err(Status::from)?;
+
+            // Retry a query without hits with its misspellings corrected
+            let correction = if result.hits.is_empty() && self.spell_correction {
+                searcher.spelling().correct_query(&req.query)
+            } else {
+                None
+            };
+            if let Some(corrected) = correction {
+                let request = SearcherSearchRequest {
+                    query: acronyms.expand_query(&corrected),
+                    ..search_request.clone()
+                };
+                let retried = searcher
+                    .search(request.clone())
+                    .await
+                    .map_err(Status::from)?;
+                metrics::record_spell_correction(!retried.hits.is_empty());
+                if !retried.hits.is_empty() {
+                    info!(corrected = %self.retention.query_text(&corrected), "Corrected query spelling");
+                    result = retried;
+                    search_request = request;
+                    corrected_query = Some(corrected);
+                }
+            }
+
+            if req.two_tier {
+                deep_cursor = self
+                    .deep_searches
+                    .start(Arc::clone(&searcher), search_request)
+                    .unwrap_or_default();
+            }
+            result
         };
 
-        // Perform search
-        let result = self
-            .searcher
-            .search(&req.query, top_k, snippet_chars)
-            .await
-            .map_err(Status::from)?;
+        // Restricted frames never leave the service
+        let hidden = self.visibility.retain_visible(&mut result.hits, audience);
+        result.total_hits = result.total_hits.saturating_sub(hidden as i32);
+        if let Some(language) = language {
+            apply_language_preference(&mut result.hits, language, req.strict_language);
+            if req.strict_language {
+                result.total_hits = result.hits.len() as i32;
+            }
+        }
+        if let Some(boosts) = &boosts {
+            boosts.apply(&mut result.hits, self.now());
+        }
+        result.hits.truncate(top_k.max(0) as usize);
 
         // Record metrics
         metrics::record_search_latency(result.took_ms as f64);
         metrics::increment_search_count();
+        if result.partial {
+            metrics::increment_search_partial();
+        }
+
+        self.coverage
+            .record(result.hits.iter().filter_map(|h| h.frame_id));
+        if req.deep_cursor.is_empty() {
+            self.query_stats
+                .record(&req.query, result.hits.len(), started.elapsed());
+        }
 
         // Convert to gRPC response
-        let hits: Vec<SearchHit> = result
+        let profile = if req.link_entities {
+            Some(self.profiles.get(&*searcher).await.map_err(Status::from)?)
+        } else {
+            None
+        };
+        let mut hits: Vec<SearchHit> = result
             .hits
             .into_iter()
             .map(|h| SearchHit {
+                links: profile
+                    .as_ref()
+                    .map(|profile| profile.linker().link(&h.snippet))
+                    .unwrap_or_default(),
                 title: h.title,
                 score: h.score,
                 snippet: h.snippet,
                 tags: h.tags,
+                ingested_at: h.ingested_at,
+                source_version: h.source_version,
             })
             .collect();
+        let encoding = OutputEncoding::try_from(req.output_encoding).unwrap_or_default();
+        encode_hit_labels(&mut hits, encoding);
+        let query = corrected_query.as_deref().unwrap_or(&req.query);
+        let highlight_terms = acronyms.highlight_terms(query);
+        let renderer = SnippetRenderer::new(
+            encoding,
+            Highlighter::new(req.highlight.as_ref(), query, &highlight_terms),
+        );
 
-        let response = SearchResponse {
+        let mut response = SearchResponse {
             hits,
             total_hits: result.total_hits,
             took_ms: result.took_ms,
+            partial: result.partial,
+            deep_cursor,
+            trimmed: None,
+            highlight_terms: highlight_terms
+                .iter()
+                .map(|term| encode(term, encoding))
+                .collect(),
+            corrected_query: corrected_query.map(|query| encode(&query, encoding)),
+            expanded_query: result.expanded_query.map(|query| encode(&query, encoding)),
         };
 
-        Ok(Response::new(response))
+        if fit_response(&mut response, self.max_response_bytes, |snippet| {
+            renderer.render(snippet)
+        }) {
+            metrics::increment_response_trimmed("search");
+        }
+        metrics::record_message_sizes("search", request_bytes, response.encoded_len());
+
+        let mut response = Response::new(response);
+        legacy::report("search", &legacy_fields, &mut response);
+        Ok(response)
     }
 
-    #[instrument(skip(self, request), fields(question))]
+    #[instrument(skip(self, request), fields(question, caller = caller_subject(&request)))]
     async fn ask(&self, request: Request<AskRequest>) -> Result<Response<AskResponse>, Status> {
-        let req = request.into_inner();
-
-        // Record the question in span
-        tracing::Span::current().record("question", &req.question);
-
-        info!(
-            question = %req.question,
-            mode = ?req.mode,
-            top_k = req.top_k,
-            "Processing ask request"
-        );
-
-        // Apply defaults
-        let top_k = if req.top_k == 0 { 5 } else { req.top_k };
-        let snippet_chars = if req.snippet_chars == 0 {
-            200
+        let started = Instant::now();
+        let accept_language = accept_language(&request);
+        let audience = caller_audience(&self.visibility, request.metadata());
+        let acl = caller_acl(&request);
+        let api_key = usage::key_id(request.metadata());
+        let mut req = request.into_inner();
+        let request_bytes = req.encoded_len();
+        let legacy_fields = legacy::ask_fields(&req);
+        voice::normalize_query(&mut req.question, req.source);
+        let searcher = Arc::clone(self.indexes.get(&req.index).map_err(Status::from)?);
+
+        self.observe_topic(&req.question);
+        let prepared = self
+            .prepare_ask(&*searcher, &req, accept_language.as_deref(), audience, acl)
+            .map_err(Status::from)?;
+        let profile = prepared.profile(&self.profiles, &*searcher).await?;
+
+        // Perform ask operation, with paraphrases or aspects when asked to
+        let mut aspects = Vec::new();
+        let (mut result, ensemble) = if req.ensemble && self.deterministic_now.is_none() {
+            let (result, stats) = ensemble_ask(&*searcher, prepared.request.clone())
+                .await
+                .map_err(Status::from)?;
+            (result, Some(ensemble_stats(stats)))
+        } else if req.decompose && self.deterministic_now.is_none() {
+            let acronyms = searcher.acronyms();
+            let mut planned = self.aspects.plan(&req.question, prepared.use_llm).await;
+            for aspect in &mut planned {
+                aspect.query = acronyms.expand_query(&aspect.query);
+            }
+            let (result, stats) = aspect_ask(
+                Arc::clone(&searcher),
+                prepared.request.clone(),
+                planned,
+                self.llm.as_deref(),
+            )
+            .await
+            .map_err(Status::from)?;
+            aspects = aspect_stats(stats);
+            (result, None)
         } else {
-            req.snippet_chars
-        };
-
-        // Map proto AskMode to searcher AskMode
-        let mode = match ProtoAskMode::try_from(req.mode) {
-            Ok(ProtoAskMode::Sem) => SearcherAskMode::Sem,
-            Ok(ProtoAskMode::Lex) => SearcherAskMode::Lex,
-            _ => SearcherAskMode::Hybrid, // Default to Hybrid
+            let result = searcher
+                .ask(prepared.request.clone())
+                .await
+                .map_err(Status::from)?;
+            (result, None)
         };
-
-        // Build searcher request
-        let ask_request = SearcherAskRequest {
-            question: req.question.clone(),
-            use_llm: req.use_llm,
-            top_k,
-            filters: req.filters,
-            start: req.start,
-            end: req.end,
-            snippet_chars,
-            mode,
-            uri: if req.uri.is_empty() {
-                None
-            } else {
-                Some(req.uri)
-            },
-            cursor: if req.cursor.is_empty() {
-                None
-            } else {
-                Some(req.cursor)
-            },
-            as_of_frame: req.as_of_frame,
-            as_of_ts: req.as_of_ts,
-            adaptive: req.adaptive,
+        let synthesized = prepared.use_llm.then(|| result.evidence.clone());
+        let mut citations = CitationCheck::default();
+        if prepared.rank_evidence(&mut result.evidence) {
+            // The answer may draw on restricted evidence
+            result.answer = context_answer(&result.evidence);
+            result.stats.used_fallback = true;
+        } else if let Some(synthesized) = synthesized {
+            citations = enforce_citations(
+                prepared.citations,
+                &mut result.answer,
+                &mut result.stats,
+                &synthesized,
+                &result.evidence,
+            );
+        }
+        result.stats.results_returned = result.evidence.len() as i32;
+        let llm_usage = if prepared.use_llm {
+            self.usage
+                .record_ask(&api_key, &req.question, &result.evidence, &result.answer)
+        } else {
+            LlmUsage::default()
         };
-
-        // Perform ask operation
-        let result = self.searcher.ask(ask_request).await.map_err(Status::from)?;
+        self.coverage
+            .record(result.evidence.iter().filter_map(|e| e.frame_id));
+        if req.cursor.is_empty() {
+            self.query_stats
+                .record(&req.question, result.evidence.len(), started.elapsed());
+        }
 
         // Convert to gRPC response
-        let evidence: Vec<SearchHit> = result
-            .evidence
-            .into_iter()
-            .map(|e| SearchHit {
-                title: e.title,
-                score: e.score,
-                snippet: e.snippet,
-                tags: e.tags,
-            })
-            .collect();
-
-        let response = AskResponse {
-            answer: result.answer,
-            evidence,
+        let mut response = AskResponse {
+            answer: prepared.render_answer(&result.answer),
+            evidence: prepared
+                .evidence_hits(result.evidence, profile.as_deref().map(Profile::linker)),
             stats: Some(AskStats {
-                candidates_retrieved: result.stats.candidates_retrieved,
-                results_returned: result.stats.results_returned,
-                retrieval_ms: result.stats.retrieval_ms,
-                reranking_ms: result.stats.reranking_ms,
-                used_fallback: result.stats.used_fallback,
+                aspects,
+                ..prepared.stats(&result.stats, &llm_usage, &citations)
             }),
+            trimmed: None,
+            highlight_terms: prepared.highlight_terms(),
+            ensemble,
         };
 
-        Ok(Response::new(response))
+        if fit_response(&mut response, self.max_response_bytes, |snippet| {
+            encode(snippet, prepared.encoding)
+        }) {
+            metrics::increment_response_trimmed("ask");
+        }
+        metrics::record_message_sizes("ask", request_bytes, response.encoded_len());
+
+        let mut response = Response::new(response);
+        legacy::report("ask", &legacy_fields, &mut response);
+        Ok(response)
+    }
+
+    type AskStreamStream = ReceiverStream<Result<AskStreamChunk, Status>>;
+
+    #[instrument(skip(self, request), fields(question, caller = caller_subject(&request)))]
+    async fn ask_stream(
+        &self,
+        request: Request<AskRequest>,
+    ) -> Result<Response<Self::AskStreamStream>, Status> {
+        let started = Instant::now();
+        let accept_language = accept_language(&request);
+        let audience = caller_audience(&self.visibility, request.metadata());
+        let acl = caller_acl(&request);
+        let api_key = usage::key_id(request.metadata());
+        let mut req = request.into_inner();
+        let legacy_fields = legacy::ask_fields(&req);
+        voice::normalize_query(&mut req.question, req.source);
+        let searcher = Arc::clone(self.indexes.get(&req.index).map_err(Status::from)?);
+        if req.ensemble {
+            return Err(Status::invalid_argument(
+                "ensemble is only supported by Ask, not AskStream",
+            ));
+        }
+        if req.decompose {
+            return Err(Status::invalid_argument(
+                "decompose is only supported by Ask, not AskStream",
+            ));
+        }
+
+        self.observe_topic(&req.question);
+        let prepared = self
+            .prepare_ask(&*searcher, &req, accept_language.as_deref(), audience, acl)
+            .map_err(Status::from)?;
+        let profile = prepared.profile(&self.profiles, &*searcher).await?;
+        let mut events = searcher
+            .ask_stream(prepared.request.clone())
+            .await
+            .map_err(Status::from)?;
+
+        let usage = Arc::clone(&self.usage);
+        let coverage = Arc::clone(&self.coverage);
+        let query_stats = req.cursor.is_empty().then(|| Arc::clone(&self.query_stats));
+        let question = req.question;
+        let (tx, rx) = mpsc::channel(ASK_STREAM_BUFFER);
+        tokio::spawn(async move {
+            // Localization, encoding and strict citation checks rewrite the
+            // whole answer, so it is sent as a single delta once complete;
+            // lenient checks only rewrite markers, as the answer streams
+            let buffered = prepared.locale.is_some()
+                || prepared.encoding != OutputEncoding::Raw
+                || (prepared.use_llm && prepared.citations == CitationPolicy::Strict);
+            let check_stream =
+                !buffered && prepared.use_llm && prepared.citations == CitationPolicy::Lenient;
+            let mut citation_stream = None;
+            let mut synthesized = Vec::new();
+            let mut evidence = Vec::new();
+            let mut answer = String::new();
+            let mut replacement = None;
+
+            while let Some(event) = events.next().await {
+                let chunk = match event {
+                    Ok(AskEvent::Evidence(mut hits)) => {
+                        synthesized = hits.clone();
+                        if prepared.rank_evidence(&mut hits) {
+                            // The answer may draw on restricted evidence
+                            replacement = Some(context_answer(&hits));
+                        }
+                        coverage.record(hits.iter().filter_map(|e| e.frame_id));
+                        evidence = hits.clone();
+                        if check_stream {
+                            citation_stream =
+                                Some(CitationStream::new(synthesized.clone(), hits.clone()));
+                        }
+                        let mut hits =
+                            prepared.evidence_hits(hits, profile.as_deref().map(Profile::linker));
+                        // Chunks are not budgeted, so snippets are encoded as they are
+                        for hit in &mut hits {
+                            hit.snippet = encode(&hit.snippet, prepared.encoding);
+                        }
+                        Chunk::Evidence(AskEvidence { hits })
+                    }
+                    Ok(AskEvent::AnswerDelta(delta)) => {
+                        if buffered || replacement.is_some() {
+                            answer.push_str(&delta);
+                            continue;
+                        }
+                        let delta = match &mut citation_stream {
+                            Some(citation_stream) => citation_stream.push(&delta),
+                            None => delta,
+                        };
+                        if delta.is_empty() {
+                            continue;
+                        }
+                        answer.push_str(&delta);
+                        Chunk::AnswerDelta(delta)
+                    }
+                    Ok(AskEvent::Done { mut stats, .. }) => {
+                        let replaced = replacement.take().map(|context| {
+                            answer = context;
+                            stats.used_fallback = true;
+                        });
+                        let citations = if replaced.is_some() || !prepared.use_llm {
+                            CitationCheck::default()
+                        } else if let Some(mut citation_stream) = citation_stream.take() {
+                            // Markers were checked as the answer streamed
+                            let rest = citation_stream.finish();
+                            if !rest.is_empty() {
+                                answer.push_str(&rest);
+                                let delta = Chunk::AnswerDelta(rest);
+                                if tx.send(Ok(delta.into())).await.is_err() {
+                                    return;
+                                }
+                            }
+                            metrics::record_citation_check(citation_stream.invalid_markers(), 0);
+                            CitationCheck {
+                                invalid_markers: citation_stream.invalid_markers(),
+                                ..Default::default()
+                            }
+                        } else {
+                            enforce_citations(
+                                prepared.citations,
+                                &mut answer,
+                                &mut stats,
+                                &synthesized,
+                                &evidence,
+                            )
+                        };
+                        if (buffered || replaced.is_some()) && !answer.is_empty() {
+                            let delta = Chunk::AnswerDelta(prepared.render_answer(&answer));
+                            if tx.send(Ok(delta.into())).await.is_err() {
+                                return;
+                            }
+                        }
+                        stats.results_returned = evidence.len() as i32;
+                        if let Some(query_stats) = &query_stats {
+                            query_stats.record(&question, evidence.len(), started.elapsed());
+                        }
+                        let llm_usage = if prepared.use_llm {
+                            usage.record_ask(&api_key, &question, &evidence, &answer)
+                        } else {
+                            LlmUsage::default()
+                        };
+                        Chunk::Summary(AskStreamSummary {
+                            stats: Some(prepared.stats(&stats, &llm_usage, &citations)),
+                            highlight_terms: prepared.highlight_terms(),
+                        })
+                    }
+                    Err(e) => {
+                        let _ = tx.send(Err(Status::from(e))).await;
+                        return;
+                    }
+                };
+                if tx.send(Ok(chunk.into())).await.is_err() {
+                    // Client went away
+                    return;
+                }
+            }
+        });
+
+        let mut response = Response::new(ReceiverStream::new(rx));
+        legacy::report("ask_stream", &legacy_fields, &mut response);
+        Ok(response)
     }
 
-    #[instrument(skip(self, request), fields(entity))]
+    #[instrument(skip(self, request), fields(entity, caller = caller_subject(&request)))]
     async fn get_state(
         &self,
         request: Request<GetStateRequest>,
     ) -> Result<Response<GetStateResponse>, Status> {
         let req = request.into_inner();
+        let request_bytes = req.encoded_len();
 
         // Record the entity in span
         tracing::Span::current().record("entity", &req.entity);
@@ -203,21 +1239,484 @@ impl MemvidService for MemvidGrpcService {
             entity: result.entity,
             slots: result.slots,
         };
+        metrics::record_message_sizes("get_state", request_bytes, response.encoded_len());
 
         Ok(Response::new(response))
     }
-}
 
-/// gRPC implementation of the Health service.
-pub struct HealthService {
-    searcher: Arc<dyn Searcher>,
-}
+    #[instrument(skip(self, request), fields(caller = caller_subject(&request)))]
+    async fn list_entities(
+        &self,
+        request: Request<ListEntitiesRequest>,
+    ) -> Result<Response<ListEntitiesResponse>, Status> {
+        let request_bytes = request.get_ref().encoded_len();
+        let entities = self.searcher.list_entities().await.map_err(Status::from)?;
 
-impl HealthService {
-    /// Create a new HealthService with the given searcher implementation.
-    pub fn new(searcher: Arc<dyn Searcher>) -> Self {
-        Self { searcher }
-    }
+        let response = ListEntitiesResponse {
+            entities: entity_summaries(entities),
+        };
+        metrics::record_message_sizes("list_entities", request_bytes, response.encoded_len());
+
+        Ok(Response::new(response))
+    }
+
+    #[instrument(skip(self, request), fields(caller = caller_subject(&request)))]
+    async fn list_tags(
+        &self,
+        request: Request<ListTagsRequest>,
+    ) -> Result<Response<ListTagsResponse>, Status> {
+        let request_bytes = request.get_ref().encoded_len();
+        let response = ListTagsResponse {
+            tags: tag_counts(&self.searcher.section_counts()),
+        };
+        metrics::record_message_sizes("list_tags", request_bytes, response.encoded_len());
+
+        Ok(Response::new(response))
+    }
+
+    #[instrument(skip(self, request), fields(caller = caller_subject(&request)))]
+    async fn fit_assessment(
+        &self,
+        request: Request<FitAssessmentRequest>,
+    ) -> Result<Response<FitAssessmentResponse>, Status> {
+        let audience = caller_audience(&self.visibility, request.metadata());
+        let acl = caller_acl(&request);
+        let req = request.into_inner();
+        let request_bytes = req.encoded_len();
+        let searcher = Arc::clone(self.indexes.get(&req.index).map_err(Status::from)?);
+
+        let requirements = fit::requirements(&req.job_description);
+        if requirements.is_empty() {
+            return Err(ServiceError::invalid_field(
+                "job_description",
+                "job_description lists no requirements",
+            )
+            .into());
+        }
+        let evidence = self
+            .aspect_evidence(
+                &*searcher,
+                fit::aspect_queries(&requirements),
+                req.top_k,
+                audience,
+                acl,
+            )
+            .await?;
+
+        let mut response = fit::assess(&requirements, &evidence);
+        response.evidence = evidence.into_iter().map(plain_hit).collect();
+        info!(
+            requirements = requirements.len(),
+            strong = response.strong.len(),
+            gaps = response.gaps.len(),
+            overall_score = response.overall_score,
+            "Assessed fit"
+        );
+        metrics::record_message_sizes("fit_assessment", request_bytes, response.encoded_len());
+
+        Ok(Response::new(response))
+    }
+
+    async fn create_session(
+        &self,
+        request: Request<CreateSessionRequest>,
+    ) -> Result<Response<SessionResponse>, Status> {
+        let request_bytes = request.get_ref().encoded_len();
+        let session = self.sessions.create().await.map_err(Status::from)?;
+        let response = self.session_response(session);
+        metrics::record_message_sizes("create_session", request_bytes, response.encoded_len());
+
+        Ok(Response::new(response))
+    }
+
+    async fn append_turn(
+        &self,
+        request: Request<AppendTurnRequest>,
+    ) -> Result<Response<SessionResponse>, Status> {
+        let req = request.into_inner();
+        let request_bytes = req.encoded_len();
+        let role = match req.role() {
+            TurnRole::User => Role::User,
+            TurnRole::Assistant => Role::Assistant,
+            TurnRole::Unspecified => {
+                return Err(ServiceError::invalid_field("role", "role is required").into())
+            }
+        };
+        let session = self
+            .sessions
+            .append(&req.session_id, role, &req.content)
+            .await
+            .map_err(Status::from)?
+            .ok_or_else(|| session_not_found(&req.session_id))?;
+        let response = self.session_response(session);
+        metrics::record_message_sizes("append_turn", request_bytes, response.encoded_len());
+
+        Ok(Response::new(response))
+    }
+
+    async fn get_session(
+        &self,
+        request: Request<GetSessionRequest>,
+    ) -> Result<Response<SessionResponse>, Status> {
+        let req = request.into_inner();
+        let request_bytes = req.encoded_len();
+        let session = self
+            .sessions
+            .get(&req.session_id)
+            .await
+            .map_err(Status::from)?
+            .ok_or_else(|| session_not_found(&req.session_id))?;
+        let response = self.session_response(session);
+        metrics::record_message_sizes("get_session", request_bytes, response.encoded_len());
+
+        Ok(Response::new(response))
+    }
+
+    #[instrument(skip(self, request), fields(caller = caller_subject(&request)))]
+    async fn chat(&self, request: Request<ChatRequest>) -> Result<Response<ChatResponse>, Status> {
+        let (metadata, extensions, req) = request.into_parts();
+        let request_bytes = req.encoded_len();
+        let message = req.message.trim();
+        if message.is_empty() {
+            return Err(ServiceError::invalid_field("message", "message is empty").into());
+        }
+        if message.chars().count() > MAX_TURN_CHARS {
+            return Err(ServiceError::invalid_field(
+                "message",
+                format!("message exceeds {} characters", MAX_TURN_CHARS),
+            )
+            .into());
+        }
+        let session = if req.session_id.is_empty() {
+            self.sessions.create().await.map_err(Status::from)?
+        } else {
+            self.sessions
+                .get(&req.session_id)
+                .await
+                .map_err(Status::from)?
+                .ok_or_else(|| session_not_found(&req.session_id))?
+        };
+
+        let question = chat::contextualize(message, session.history(CHAT_HISTORY_TURNS))
+            .unwrap_or_else(|| message.to_string());
+        if question != message {
+            info!(rewritten = %self.retention.query_text(&question), "Contextualized follow-up question");
+        }
+        let ask = AskRequest {
+            question: question.clone(),
+            ..req.options.unwrap_or_default()
+        };
+        // The caller's metadata carries its API key, audience and ACL over
+        let answer = self
+            .ask(Request::from_parts(metadata, extensions, ask))
+            .await?
+            .into_inner();
+
+        // A session expiring meanwhile loses the turn rather than the answer
+        self.sessions
+            .append(&session.id, Role::User, message)
+            .await
+            .map_err(Status::from)?;
+        if !answer.answer.trim().is_empty() {
+            let recorded: String = answer.answer.chars().take(MAX_TURN_CHARS).collect();
+            self.sessions
+                .append(&session.id, Role::Assistant, &recorded)
+                .await
+                .map_err(Status::from)?;
+        }
+
+        let response = ChatResponse {
+            session_id: session.id,
+            question,
+            answer: Some(answer),
+        };
+        metrics::record_message_sizes("chat", request_bytes, response.encoded_len());
+
+        Ok(Response::new(response))
+    }
+
+    #[instrument(skip(self, request), fields(caller = caller_subject(&request)))]
+    async fn generate_summary(
+        &self,
+        request: Request<GenerateSummaryRequest>,
+    ) -> Result<Response<GenerateSummaryResponse>, Status> {
+        let audience = caller_audience(&self.visibility, request.metadata());
+        let acl = caller_acl(&request);
+        let api_key = usage::key_id(request.metadata());
+        let req = request.into_inner();
+        let request_bytes = req.encoded_len();
+        let searcher = Arc::clone(self.indexes.get(&req.index).map_err(Status::from)?);
+        let max_words = match req.max_words {
+            0 => DEFAULT_SUMMARY_WORDS,
+            words if words < 0 => {
+                return Err(ServiceError::invalid_field(
+                    "max_words",
+                    "max_words must not be negative",
+                )
+                .into())
+            }
+            words => (words as usize).min(MAX_SUMMARY_WORDS),
+        };
+        let kind = SummaryKind::try_from(req.kind).unwrap_or_default();
+        let encoding = OutputEncoding::try_from(req.output_encoding).unwrap_or_default();
+
+        // Without a job description, retrieve for each aspect in general
+        let requirements = fit::requirements(&req.job_description);
+        let queries = if requirements.is_empty() {
+            FitAspect::ALL
+                .iter()
+                .map(|aspect| aspect.hint().to_string())
+                .collect()
+        } else {
+            fit::aspect_queries(&requirements)
+        };
+        let evidence = self
+            .aspect_evidence(&*searcher, queries, req.top_k, audience, acl)
+            .await?;
+
+        // Skip synthesis once the daily LLM cost cap is reached
+        let wants_llm =
+            self.llm.is_some() && self.runtime.borrow().llm_synthesis && !evidence.is_empty();
+        let llm_capped = wants_llm && !self.usage.synthesis_allowed();
+        if llm_capped {
+            metrics::increment_llm_capped();
+        }
+        let mut synthesized = None;
+        let mut used_fallback = false;
+        if let Some(client) = self.llm.as_ref().filter(|_| wants_llm && !llm_capped) {
+            let messages =
+                summary::summary_messages(kind, &req.job_description, &evidence, max_words);
+            match client.generate(&messages).await {
+                Ok(text) => {
+                    self.usage
+                        .record_ask(&api_key, &req.job_description, &evidence, &text);
+                    synthesized = Some(text);
+                }
+                Err(e) => {
+                    warn!(
+                        error = %e,
+                        model = client.model(),
+                        "LLM summary failed, composing it from the evidence"
+                    );
+                    used_fallback = true;
+                }
+            }
+        }
+        let text = match &synthesized {
+            Some(text) => text.trim().to_string(),
+            None => summary::extractive_summary(
+                kind,
+                &evidence,
+                &fit::term_stems(&requirements),
+                max_words,
+            ),
+        };
+
+        let mut hits: Vec<SearchHit> = evidence.into_iter().map(plain_hit).collect();
+        encode_hits(&mut hits, encoding);
+        let response = GenerateSummaryResponse {
+            text: encode(&text, encoding),
+            evidence: hits,
+            synthesized: synthesized.is_some(),
+            used_fallback,
+            llm_capped,
+        };
+        metrics::record_message_sizes("generate_summary", request_bytes, response.encoded_len());
+
+        Ok(Response::new(response))
+    }
+
+    #[instrument(skip(self, request), fields(caller = caller_subject(&request)))]
+    async fn suggest(
+        &self,
+        request: Request<SuggestRequest>,
+    ) -> Result<Response<SuggestResponse>, Status> {
+        // The trie holds terms of every frame, readable or not
+        refuse_when_enforced(&request, "Suggest")?;
+        let audience = caller_audience(&self.visibility, request.metadata());
+        let req = request.into_inner();
+        let request_bytes = req.encoded_len();
+        let searcher = Arc::clone(self.indexes.get(&req.index).map_err(Status::from)?);
+        let limit = match req.limit {
+            0 => DEFAULT_SUGGESTIONS,
+            limit if limit < 0 => {
+                return Err(
+                    ServiceError::invalid_field("limit", "limit must not be negative").into(),
+                )
+            }
+            limit => (limit as usize).min(MAX_SUGGESTIONS),
+        };
+
+        let suggestions = searcher
+            .suggestions()
+            .complete(&req.prefix, limit, |frame_id, tags| {
+                self.visibility
+                    .visibility(Some(frame_id), tags)
+                    .visible_to(audience)
+            })
+            .into_iter()
+            .map(|suggestion| ProtoSuggestion {
+                text: suggestion.text,
+                kind: match suggestion.kind {
+                    SuggestionKind::Title => ProtoSuggestionKind::Title,
+                    SuggestionKind::Tag => ProtoSuggestionKind::Tag,
+                    SuggestionKind::Term => ProtoSuggestionKind::Term,
+                } as i32,
+                frames: suggestion.frames as i32,
+            })
+            .collect();
+        let response = SuggestResponse { suggestions };
+        metrics::record_message_sizes("suggest", request_bytes, response.encoded_len());
+
+        Ok(Response::new(response))
+    }
+}
+
+/// A hit without entity links.
+fn plain_hit(e: SearchResult) -> SearchHit {
+    SearchHit {
+        title: e.title,
+        score: e.score,
+        snippet: e.snippet,
+        tags: e.tags,
+        links: Vec::new(),
+        ingested_at: e.ingested_at,
+        source_version: e.source_version,
+    }
+}
+
+fn session_not_found(id: &str) -> Status {
+    Status::not_found(format!("session '{}' not found or expired", id))
+}
+
+/// Tag counts as returned by ListTags: most common first, ties by name.
+///
+/// Visibility tags control access rather than describe content, so they are
+/// left out.
+pub(super) fn tag_counts(section_counts: &BTreeMap<String, i32>) -> Vec<TagCount> {
+    let mut tags: Vec<TagCount> = section_counts
+        .iter()
+        .filter(|(tag, &count)| count > 0 && !tag.starts_with(VISIBILITY_TAG_PREFIX))
+        .map(|(tag, &count)| TagCount {
+            tag: tag.clone(),
+            count,
+        })
+        .collect();
+    tags.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.tag.cmp(&b.tag)));
+    tags
+}
+
+/// Memory-card entities as returned by ListEntities.
+pub(super) fn entity_summaries(entities: Vec<crate::memvid::EntitySummary>) -> Vec<EntitySummary> {
+    entities
+        .into_iter()
+        .map(|entity| EntitySummary {
+            entity: entity.name,
+            slot_count: entity.slot_count,
+        })
+        .collect()
+}
+
+/// gRPC implementation of the Health service.
+pub struct HealthService {
+    searcher: Arc<dyn Searcher>,
+    embedder_chain: Option<Arc<EmbedderChain>>,
+    reloadable: Option<Arc<ReloadableSearcher>>,
+    drain: Option<Arc<Drain>>,
+    readiness: Arc<Readiness>,
+    usage_ledger: Option<Arc<UsageLedger>>,
+}
+
+impl HealthService {
+    /// Create a new HealthService with the given searcher implementation.
+    pub fn new(searcher: Arc<dyn Searcher>) -> Self {
+        Self {
+            searcher,
+            embedder_chain: None,
+            reloadable: None,
+            drain: None,
+            readiness: Arc::new(Readiness::default()),
+            usage_ledger: None,
+        }
+    }
+
+    /// Combine readiness gates under `readiness`'s policy, including the
+    /// gates its startup tasks open.
+    pub fn with_readiness(mut self, readiness: Arc<Readiness>) -> Self {
+        self.readiness = readiness;
+        self
+    }
+
+    /// Close the `llm_healthy` gate while `ledger`'s daily cost cap is spent.
+    pub fn with_usage_ledger(mut self, ledger: Arc<UsageLedger>) -> Self {
+        self.usage_ledger = Some(ledger);
+        self
+    }
+
+    /// State of every readiness gate; gates no task owns are open.
+    fn gates(&self) -> Vec<GateStatus> {
+        let policy = self.readiness.policy();
+        let closed_unless = |open: bool, detail: &str| {
+            (
+                open,
+                if open {
+                    String::new()
+                } else {
+                    detail.to_string()
+                },
+            )
+        };
+        Gate::ALL
+            .into_iter()
+            .map(|gate| {
+                let (open, detail) = match gate {
+                    Gate::IndexLoaded => {
+                        closed_unless(self.searcher.is_ready(), "index is not loaded")
+                    }
+                    Gate::EmbedderHealthy => closed_unless(
+                        self.embedder_chain
+                            .as_ref()
+                            .is_none_or(|chain| chain.has_healthy_tier()),
+                        "no healthy embedder tier, retrieval is lexical-only",
+                    ),
+                    Gate::LlmHealthy => closed_unless(
+                        self.usage_ledger
+                            .as_ref()
+                            .is_none_or(|ledger| ledger.synthesis_allowed()),
+                        "daily LLM cost cap reached",
+                    ),
+                    Gate::WarmupDone | Gate::CachesRestored => {
+                        self.readiness.get(gate).unwrap_or((true, String::new()))
+                    }
+                };
+                metrics::set_readiness_gate(gate.name(), open);
+                GateStatus {
+                    gate,
+                    open,
+                    detail,
+                    policy: policy.get(gate),
+                }
+            })
+            .collect()
+    }
+
+    /// Report NOT_SERVING once a drain has started.
+    pub fn with_drain(mut self, drain: Arc<Drain>) -> Self {
+        self.drain = Some(drain);
+        self
+    }
+
+    /// Report DEGRADED while reloads of the given searcher are failing.
+    pub fn with_reloadable(mut self, reloadable: Arc<ReloadableSearcher>) -> Self {
+        self.reloadable = Some(reloadable);
+        self
+    }
+
+    /// Report the health of the embedder fallback chain in readiness detail.
+    pub fn with_embedder_chain(mut self, chain: Arc<EmbedderChain>) -> Self {
+        self.embedder_chain = Some(chain);
+        self
+    }
 }
 
 #[tonic::async_trait]
@@ -226,16 +1725,60 @@ impl Health for HealthService {
         &self,
         _request: Request<HealthCheckRequest>,
     ) -> Result<Response<HealthCheckResponse>, Status> {
-        let status = if self.searcher.is_ready() {
-            HealthStatus::Serving
+        // A failed reload leaves the last good index serving
+        let failure = self.reloadable.as_ref().and_then(|r| r.reload_failure());
+        let gates = self.gates();
+        let mut reasons: Vec<String> = failure
+            .map(|f| {
+                format!(
+                    "index reload failing since {} ({} attempts): {}",
+                    f.since.to_rfc3339(),
+                    f.attempts,
+                    f.reason
+                )
+            })
+            .into_iter()
+            .collect();
+        let status = match verdict(&gates) {
+            _ if self.drain.as_ref().is_some_and(|d| d.is_draining()) => HealthStatus::NotServing,
+            Verdict::NotServing => HealthStatus::NotServing,
+            Verdict::Degraded(reason) => {
+                reasons.push(reason);
+                HealthStatus::Degraded
+            }
+            Verdict::Serving if reasons.is_empty() => HealthStatus::Serving,
+            Verdict::Serving => HealthStatus::Degraded,
+        };
+        let degraded_reason = if status == HealthStatus::Degraded {
+            reasons.join("; ")
         } else {
-            HealthStatus::NotServing
+            String::new()
         };
 
         let response = HealthCheckResponse {
             status: status.into(),
             frame_count: self.searcher.frame_count(),
-            memvid_file: self.searcher.memvid_file().to_string(),
+            memvid_file: self.searcher.memvid_file(),
+            backends: self
+                .embedder_chain
+                .iter()
+                .flat_map(|chain| chain.health())
+                .map(|tier| BackendHealth {
+                    name: tier.name,
+                    healthy: tier.healthy,
+                    detail: tier.detail,
+                })
+                .collect(),
+            degraded_reason,
+            gates: gates
+                .into_iter()
+                .map(|g| ReadinessGate {
+                    name: g.gate.name().to_string(),
+                    open: g.open,
+                    detail: g.detail,
+                    policy: g.policy.name().to_string(),
+                })
+                .collect(),
         };
 
         Ok(Response::new(response))
@@ -245,7 +1788,9 @@ impl Health for HealthService {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::memvid::MockSearcher;
+    use crate::generated::memvid::v1::{EntityLink, Highlight};
+    use crate::grpc::LlmPricing;
+    use crate::memvid::{AclMode, DeterministicSearcher, MockSearcher, Visibility, MAX_BOOST};
     use std::sync::Once;
 
     // Global metrics initialization - only happens once across all tests
@@ -258,75 +1803,914 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_search_with_defaults() {
+    async fn test_search_with_defaults() {
+        init_test_metrics();
+
+        let searcher = Arc::new(MockSearcher::new());
+        let service = MemvidGrpcService::new(searcher);
+
+        let request = Request::new(SearchRequest {
+            query: "Python experience".to_string(),
+            top_k: 0,           // Should default to 5
+            snippet_chars: 0,   // Should default to 200
+            min_relevance: 0.0, // No relevance filter
+            mode: 0,            // ASK_MODE_HYBRID (default)
+            ..Default::default()
+        });
+
+        let response = service.search(request).await.unwrap();
+        let inner = response.into_inner();
+
+        assert!(!inner.hits.is_empty());
+        assert!(inner.hits.len() <= 5);
+        assert!(inner.took_ms >= 0);
+    }
+
+    #[tokio::test]
+    async fn test_search_highlights_query_terms() {
+        init_test_metrics();
+
+        let service = MemvidGrpcService::new(Arc::new(MockSearcher::new()));
+        let search = |highlight| {
+            Request::new(SearchRequest {
+                query: "Python".to_string(),
+                top_k: 3,
+                highlight,
+                ..Default::default()
+            })
+        };
+
+        let plain = service.search(search(None)).await.unwrap().into_inner();
+        assert!(plain.hits.iter().all(|h| !h.snippet.contains("<em>")));
+
+        let highlighted = service
+            .search(search(Some(Highlight::default())))
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(highlighted
+            .hits
+            .iter()
+            .any(|h| h.snippet.contains("<em>Python</em>")));
+    }
+
+    #[tokio::test]
+    async fn test_search_links_entities_on_request() {
+        init_test_metrics();
+
+        let service = MemvidGrpcService::new(Arc::new(MockSearcher::new()));
+        let search = |link_entities| {
+            Request::new(SearchRequest {
+                query: "Rust Python".to_string(),
+                top_k: 10,
+                link_entities,
+                ..Default::default()
+            })
+        };
+
+        let plain = service.search(search(false)).await.unwrap().into_inner();
+        assert!(plain.hits.iter().all(|hit| hit.links.is_empty()));
+
+        let linked = service.search(search(true)).await.unwrap().into_inner();
+        let links: Vec<&EntityLink> = linked.hits.iter().flat_map(|hit| &hit.links).collect();
+        assert!(links
+            .iter()
+            .any(|link| link.mention == "Rust" && link.kind == "technology"));
+        assert!(links.iter().all(|link| link.entity == "__profile__"));
+    }
+
+    #[tokio::test]
+    async fn test_search_highlights_learned_acronyms() {
+        init_test_metrics();
+
+        let service = MemvidGrpcService::new(Arc::new(MockSearcher::new()));
+        let response = service
+            .search(Request::new(SearchRequest {
+                query: "IIoT".to_string(),
+                ..Default::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+
+        // The mock corpus defines "industrial IoT (IIoT)"
+        assert_eq!(response.highlight_terms, vec!["IIoT", "industrial IoT"]);
+        assert!(!response.hits.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_search_preferred_language() {
+        init_test_metrics();
+
+        let service = MemvidGrpcService::new(Arc::new(MockSearcher::new()));
+        let search = |language: &str, strict: bool| {
+            service.search(Request::new(SearchRequest {
+                query: "engineering".to_string(),
+                preferred_language: language.to_string(),
+                strict_language: strict,
+                ..Default::default()
+            }))
+        };
+
+        // The mock corpus is English; hits carry the detected language
+        let english = search("en", true).await.unwrap().into_inner();
+        assert!(!english.hits.is_empty());
+        assert!(english
+            .hits
+            .iter()
+            .all(|hit| hit.tags.contains(&"lang:en".to_string())));
+
+        let german = search("de", true).await.unwrap().into_inner();
+        assert!(german.hits.is_empty());
+        assert_eq!(german.total_hits, 0);
+        // Without strict, other languages are kept
+        let boosted = search("de", false).await.unwrap().into_inner();
+        assert_eq!(boosted.hits.len(), english.hits.len());
+
+        let status = search("ja", false).await.unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+    }
+
+    #[tokio::test]
+    async fn test_search_ranking_boosts() {
+        init_test_metrics();
+
+        let service = MemvidGrpcService::new(Arc::new(MockSearcher::new()));
+        let search = |boosts: Option<RankingBoosts>| {
+            service.search(Request::new(SearchRequest {
+                query: "engineering".to_string(),
+                boosts,
+                ..Default::default()
+            }))
+        };
+
+        let plain = search(None).await.unwrap().into_inner();
+        let top = &plain.hits[0].tags;
+        let tag = plain
+            .hits
+            .last()
+            .unwrap()
+            .tags
+            .iter()
+            .find(|tag| !top.contains(tag))
+            .unwrap();
+
+        // A strong enough tag boost moves a lower-ranked hit first
+        let boosted = search(Some(RankingBoosts {
+            tag_boosts: [(tag.clone(), MAX_BOOST)].into(),
+            ..Default::default()
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+        assert_eq!(boosted.hits.len(), plain.hits.len());
+        assert!(boosted.hits[0].tags.contains(tag));
+
+        let status = search(Some(RankingBoosts {
+            recency_weight: -1.0,
+            ..Default::default()
+        }))
+        .await
+        .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+    }
+
+    #[tokio::test]
+    async fn test_search_records_surfaced_frames() {
+        init_test_metrics();
+
+        let searcher = Arc::new(MockSearcher::new());
+        let coverage = Arc::new(CoverageTracker::default());
+        let service =
+            MemvidGrpcService::new(searcher.clone()).with_coverage_tracker(Arc::clone(&coverage));
+
+        let before = coverage.report(searcher.as_ref()).await.unwrap();
+        assert_eq!(before.frames_surfaced, 0);
+
+        service
+            .search(Request::new(SearchRequest {
+                query: "Rust".to_string(),
+                top_k: 2,
+                ..Default::default()
+            }))
+            .await
+            .unwrap();
+
+        let after = coverage.report(searcher.as_ref()).await.unwrap();
+        assert_eq!(after.frames_surfaced, 2);
+    }
+
+    #[tokio::test]
+    async fn test_queries_recorded_for_analytics_report() {
+        init_test_metrics();
+
+        let stats = Arc::new(QueryStats::default());
+        let service = MemvidGrpcService::new(Arc::new(MockSearcher::new()))
+            .with_query_stats(Arc::clone(&stats));
+
+        for question in ["Rust?", "rust"] {
+            service
+                .ask(Request::new(AskRequest {
+                    question: question.to_string(),
+                    ..Default::default()
+                }))
+                .await
+                .unwrap();
+        }
+        service
+            .search(Request::new(SearchRequest {
+                query: "xyzzy".to_string(),
+                // No mock frame is German
+                preferred_language: "de".to_string(),
+                strict_language: true,
+                ..Default::default()
+            }))
+            .await
+            .unwrap();
+
+        let summary = stats.take_summary(5);
+        assert_eq!(summary.queries, 3);
+        assert_eq!(summary.top_questions[0].query, "rust");
+        assert_eq!(summary.top_questions[0].count, 2);
+        assert_eq!(summary.zero_result_queries, 1);
+        assert_eq!(summary.top_zero_result_queries[0].query, "xyzzy");
+    }
+
+    #[tokio::test]
+    async fn test_search_with_custom_params() {
+        init_test_metrics();
+
+        let searcher = Arc::new(MockSearcher::new());
+        let service = MemvidGrpcService::new(searcher);
+
+        let request = Request::new(SearchRequest {
+            query: "Rust programming".to_string(),
+            top_k: 3,
+            snippet_chars: 100,
+            min_relevance: 0.0,
+            mode: 0,
+            ..Default::default()
+        });
+
+        let response = service.search(request).await.unwrap();
+        let inner = response.into_inner();
+
+        assert!(inner.hits.len() <= 3);
+        for hit in &inner.hits {
+            assert!(hit.score > 0.0);
+            assert!(hit.score <= 1.0);
+            assert!(!hit.title.is_empty());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_search_returns_tags() {
+        init_test_metrics();
+
+        let searcher = Arc::new(MockSearcher::new());
+        let service = MemvidGrpcService::new(searcher);
+
+        let request = Request::new(SearchRequest {
+            query: "skills".to_string(),
+            top_k: 5,
+            snippet_chars: 200,
+            min_relevance: 0.0,
+            mode: 0,
+            ..Default::default()
+        });
+
+        let response = service.search(request).await.unwrap();
+        let inner = response.into_inner();
+
+        // At least one hit should have tags
+        let has_tags = inner.hits.iter().any(|h| !h.tags.is_empty());
+        assert!(has_tags);
+    }
+
+    #[tokio::test]
+    async fn test_search_selects_named_index() {
+        init_test_metrics();
+
+        let registry = SearcherRegistry::new("v12", Arc::new(MockSearcher::new()))
+            .with_index("synthetic", Arc::new(MockSearcher::synthetic(7, 20)));
+        let service =
+            MemvidGrpcService::new(Arc::new(MockSearcher::new())).with_registry(Arc::new(registry));
+        let search = |index: &str| {
+            service.search(Request::new(SearchRequest {
+                query: "engineering".to_string(),
+                index: index.to_string(),
+                ..Default::default()
+            }))
+        };
+
+        let default = search("").await.unwrap().into_inner();
+        assert_eq!(default.hits, search("v12").await.unwrap().into_inner().hits);
+        let synthetic = search("synthetic").await.unwrap().into_inner();
+        assert_ne!(default.hits, synthetic.hits);
+
+        let err = search("v11").await.unwrap_err();
+        assert_eq!(err.code(), tonic::Code::InvalidArgument);
+        let err = service
+            .ask(Request::new(AskRequest {
+                question: "What are your skills?".to_string(),
+                index: "v11".to_string(),
+                ..Default::default()
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), tonic::Code::InvalidArgument);
+    }
+
+    #[tokio::test]
+    async fn test_ask_ensemble_reports_paraphrases() {
+        init_test_metrics();
+
+        let service = MemvidGrpcService::new(Arc::new(MockSearcher::new()));
+        let request = || AskRequest {
+            question: "What leadership experience do you have?".to_string(),
+            top_k: 3,
+            ensemble: true,
+            ..Default::default()
+        };
+
+        let response = service
+            .ask(Request::new(request()))
+            .await
+            .unwrap()
+            .into_inner();
+        let queries = response.ensemble.unwrap().queries;
+        assert_eq!(queries[0].query, "What leadership experience do you have?");
+        assert!(queries.len() > 1);
+        assert_eq!(response.evidence.len(), 3);
+
+        let plain = AskRequest {
+            ensemble: false,
+            ..request()
+        };
+        let plain = service.ask(Request::new(plain)).await.unwrap().into_inner();
+        assert!(plain.ensemble.is_none());
+
+        let err = service
+            .ask(Request::new(AskRequest {
+                cursor: "abc".to_string(),
+                ..request()
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), tonic::Code::InvalidArgument);
+    }
+
+    #[tokio::test]
+    async fn test_ask_decompose_reports_aspects() {
+        init_test_metrics();
+
+        let service = MemvidGrpcService::new(Arc::new(MockSearcher::new()))
+            .with_aspect_planner(Arc::new(AspectPlanner::new(["skills", "education"])));
+        let request = || AskRequest {
+            question: "Overview of the candidate".to_string(),
+            top_k: 3,
+            decompose: true,
+            ..Default::default()
+        };
+
+        let response = service
+            .ask(Request::new(request()))
+            .await
+            .unwrap()
+            .into_inner();
+        let aspects = response.stats.unwrap().aspects;
+        let names: Vec<&str> = aspects.iter().map(|a| a.aspect.as_str()).collect();
+        assert_eq!(names, ["skills", "education"]);
+        assert_eq!(aspects[0].query, "overview candidate skills");
+        assert_eq!(response.evidence.len(), 3);
+        assert!(response.ensemble.is_none());
+
+        for request in [
+            AskRequest {
+                ensemble: true,
+                ..request()
+            },
+            AskRequest {
+                cursor: "abc".to_string(),
+                ..request()
+            },
+        ] {
+            let err = service.ask(Request::new(request)).await.unwrap_err();
+            assert_eq!(err.code(), tonic::Code::InvalidArgument);
+        }
+        let err = service
+            .ask_stream(Request::new(request()))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), tonic::Code::InvalidArgument);
+    }
+
+    #[tokio::test]
+    async fn test_ask_adaptive_options_tune_the_cutoff() {
+        init_test_metrics();
+
+        let service = MemvidGrpcService::new(Arc::new(MockSearcher::new()));
+        let ask = |adaptive: Option<bool>, options: Option<ProtoAdaptiveOptions>| {
+            service.ask(Request::new(AskRequest {
+                question: "What leadership experience do you have?".to_string(),
+                top_k: 10,
+                adaptive,
+                adaptive_options: options,
+                ..Default::default()
+            }))
+        };
+        let evidence = |response: Response<AskResponse>| response.into_inner().evidence.len();
+
+        let plain = evidence(ask(None, None).await.unwrap());
+        let strict = ProtoAdaptiveOptions {
+            relative_threshold: Some(1.0),
+            ..Default::default()
+        };
+        // Only the hits tied with the best one are kept
+        let cut = evidence(ask(None, Some(strict)).await.unwrap());
+        assert!(cut >= 1 && cut < plain);
+        assert_eq!(
+            evidence(ask(Some(false), Some(strict)).await.unwrap()),
+            plain
+        );
+        let loose = ProtoAdaptiveOptions {
+            relative_threshold: Some(0.0),
+            max_results: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(evidence(ask(None, Some(loose)).await.unwrap()), 2);
+
+        for invalid in [
+            ProtoAdaptiveOptions {
+                min_score: Some(1.5),
+                ..Default::default()
+            },
+            ProtoAdaptiveOptions {
+                min_results: Some(5),
+                max_results: Some(2),
+                ..Default::default()
+            },
+        ] {
+            let err = ask(Some(true), Some(invalid)).await.unwrap_err();
+            assert_eq!(err.code(), tonic::Code::InvalidArgument);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_deterministic_mode_repeats_responses() {
+        init_test_metrics();
+
+        let service = MemvidGrpcService::new(Arc::new(DeterministicSearcher::new(Arc::new(
+            MockSearcher::new(),
+        ))))
+        .with_deterministic(1_735_689_600);
+        let ask = || AskRequest {
+            question: "What leadership experience do you have?".to_string(),
+            top_k: 3,
+            ensemble: true,
+            ..Default::default()
+        };
+        let first = service.ask(Request::new(ask())).await.unwrap().into_inner();
+        let second = service.ask(Request::new(ask())).await.unwrap().into_inner();
+        // No paraphrases, and nothing measured
+        assert!(first.ensemble.is_none());
+        assert_eq!(first.encode_to_vec(), second.encode_to_vec());
+
+        let search = || SearchRequest {
+            query: "Python experience".to_string(),
+            top_k: 5,
+            ..Default::default()
+        };
+        let first = service.search(Request::new(search())).await.unwrap();
+        let second = service.search(Request::new(search())).await.unwrap();
+        assert_eq!(first.get_ref().took_ms, 0);
+        assert_eq!(
+            first.into_inner().encode_to_vec(),
+            second.into_inner().encode_to_vec()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_hits_carry_freshness_hints() {
+        init_test_metrics();
+
+        let service = MemvidGrpcService::new(Arc::new(MockSearcher::new()));
+        let search = service
+            .search(Request::new(SearchRequest {
+                query: "skills".to_string(),
+                ..Default::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        let ask = service
+            .ask(Request::new(AskRequest {
+                question: "What are your skills?".to_string(),
+                ..Default::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+
+        assert!(!ask.evidence.is_empty());
+        for hit in search.hits.iter().chain(&ask.evidence) {
+            assert!(hit.ingested_at.is_some());
+            assert_eq!(hit.source_version.as_deref(), Some("v1"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_search_with_exhausted_budget_is_partial() {
+        init_test_metrics();
+
+        let searcher = Arc::new(MockSearcher::new());
+        let service = MemvidGrpcService::new(searcher);
+
+        let request = Request::new(SearchRequest {
+            query: "Python experience".to_string(),
+            top_k: 5,
+            snippet_chars: 200,
+            min_relevance: 0.0,
+            mode: 0,
+            budget_ms: Some(1),
+            ..Default::default()
+        });
+
+        let response = service.search(request).await.unwrap();
+        let inner = response.into_inner();
+
+        assert!(inner.partial);
+        assert!(inner.hits.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_two_tier_search_returns_claimable_deep_cursor() {
+        init_test_metrics();
+
+        let searcher = Arc::new(MockSearcher::new());
+        let service = MemvidGrpcService::new(searcher);
+
+        let request = Request::new(SearchRequest {
+            query: "leadership".to_string(),
+            top_k: 3,
+            two_tier: true,
+            ..Default::default()
+        });
+
+        let first_page = service.search(request).await.unwrap().into_inner();
+        assert!(!first_page.hits.is_empty());
+        assert!(!first_page.deep_cursor.is_empty());
+
+        let request = Request::new(SearchRequest {
+            deep_cursor: first_page.deep_cursor.clone(),
+            ..Default::default()
+        });
+
+        let deep_page = service.search(request).await.unwrap().into_inner();
+        assert!(!deep_page.hits.is_empty());
+        assert!(deep_page.deep_cursor.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_two_tier_deep_pass_keeps_filters() {
+        init_test_metrics();
+
+        let service = MemvidGrpcService::new(Arc::new(MockSearcher::new()));
+        let first_page = service
+            .search(Request::new(SearchRequest {
+                query: "leadership".to_string(),
+                top_k: 3,
+                filters: std::collections::HashMap::from([(
+                    "section".to_string(),
+                    "education".to_string(),
+                )]),
+                two_tier: true,
+                ..Default::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+
+        let deep_page = service
+            .search(Request::new(SearchRequest {
+                deep_cursor: first_page.deep_cursor,
+                ..Default::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(!deep_page.hits.is_empty());
+        assert!(deep_page
+            .hits
+            .iter()
+            .all(|hit| hit.tags.contains(&"education".to_string())));
+    }
+
+    #[tokio::test]
+    async fn test_two_tier_deep_pass_keeps_uri_scope() {
+        init_test_metrics();
+
+        let service = MemvidGrpcService::new(Arc::new(MockSearcher::new()));
+        let first_page = service
+            .search(Request::new(SearchRequest {
+                query: "leadership".to_string(),
+                top_k: 3,
+                uri: "mv2://resume/skills".to_string(),
+                two_tier: true,
+                ..Default::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+
+        let deep_page = service
+            .search(Request::new(SearchRequest {
+                deep_cursor: first_page.deep_cursor,
+                ..Default::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        // Mock frames of mv2://resume/skills have "skills" as their first tag
+        assert!(!deep_page.hits.is_empty());
+        assert!(deep_page
+            .hits
+            .iter()
+            .all(|hit| hit.tags.first().map(String::as_str) == Some("skills")));
+    }
+
+    #[tokio::test]
+    async fn test_unknown_deep_cursor_is_invalid_argument() {
+        init_test_metrics();
+
+        let searcher = Arc::new(MockSearcher::new());
+        let service = MemvidGrpcService::new(searcher);
+
+        let request = Request::new(SearchRequest {
+            deep_cursor: "deep-does-not-exist".to_string(),
+            ..Default::default()
+        });
+
+        let status = service.search(request).await.unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+    }
+
+    #[tokio::test]
+    async fn test_search_response_trimmed_to_budget() {
+        init_test_metrics();
+
+        let searcher = Arc::new(MockSearcher::new());
+        let service = MemvidGrpcService::new(searcher).with_max_response_bytes(600);
+
+        let request = Request::new(SearchRequest {
+            query: "experience".to_string(),
+            top_k: 20,
+            snippet_chars: 1000,
+            ..Default::default()
+        });
+
+        let inner = service.search(request).await.unwrap().into_inner();
+
+        assert!(prost::Message::encoded_len(&inner) <= 600);
+        assert!(inner.trimmed.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_legacy_fields_add_deprecation_header() {
         init_test_metrics();
 
         let searcher = Arc::new(MockSearcher::new());
         let service = MemvidGrpcService::new(searcher);
 
         let request = Request::new(SearchRequest {
-            query: "Python experience".to_string(),
-            top_k: 0,           // Should default to 5
-            snippet_chars: 0,   // Should default to 200
-            min_relevance: 0.0, // No relevance filter
-            mode: 0,            // ASK_MODE_HYBRID (default)
+            query: "experience".to_string(),
+            min_relevance: 0.3,
+            ..Default::default()
         });
-
         let response = service.search(request).await.unwrap();
-        let inner = response.into_inner();
+        let warning = response.metadata().get("x-deprecation-warning").unwrap();
+        assert!(warning.to_str().unwrap().starts_with("min_relevance"));
 
-        assert!(!inner.hits.is_empty());
-        assert!(inner.hits.len() <= 5);
-        assert!(inner.took_ms >= 0);
+        let request = Request::new(AskRequest {
+            question: "experience".to_string(),
+            start_expr: "-30d".to_string(),
+            ..Default::default()
+        });
+        let response = service.ask(request).await.unwrap();
+        assert!(response.metadata().get("x-deprecation-warning").is_none());
     }
 
     #[tokio::test]
-    async fn test_search_with_custom_params() {
+    async fn test_ask_rejects_unsupported_locale() {
+        let searcher = Arc::new(MockSearcher::new());
+        let service = MemvidGrpcService::new(searcher);
+
+        let request = Request::new(AskRequest {
+            question: "experience".to_string(),
+            locale: "xx".to_string(),
+            ..Default::default()
+        });
+
+        let status = service.ask(request).await.unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+    }
+
+    #[tokio::test]
+    async fn test_ask_locale_from_accept_language_metadata() {
         init_test_metrics();
 
         let searcher = Arc::new(MockSearcher::new());
         let service = MemvidGrpcService::new(searcher);
 
-        let request = Request::new(SearchRequest {
-            query: "Rust programming".to_string(),
-            top_k: 3,
-            snippet_chars: 100,
-            min_relevance: 0.0,
-            mode: 0,
+        let mut request = Request::new(AskRequest {
+            question: "leadership".to_string(),
+            top_k: 10,
+            snippet_chars: 1000,
+            ..Default::default()
         });
+        request
+            .metadata_mut()
+            .insert("accept-language", "de-DE,de;q=0.9".parse().unwrap());
 
-        let response = service.search(request).await.unwrap();
-        let inner = response.into_inner();
+        let inner = service.ask(request).await.unwrap().into_inner();
+        assert!(inner.answer.contains("10+ Jahre of engineering leadership"));
+    }
 
-        assert!(inner.hits.len() <= 3);
-        for hit in &inner.hits {
-            assert!(hit.score > 0.0);
-            assert!(hit.score <= 1.0);
-            assert!(!hit.title.is_empty());
-        }
+    #[tokio::test]
+    async fn test_ask_stream_matches_ask() {
+        init_test_metrics();
+
+        let service = MemvidGrpcService::new(Arc::new(MockSearcher::new()));
+        let request = || AskRequest {
+            question: "leadership".to_string(),
+            top_k: 3,
+            ..Default::default()
+        };
+        let unary = service
+            .ask(Request::new(request()))
+            .await
+            .unwrap()
+            .into_inner();
+
+        let chunks: Vec<Chunk> = service
+            .ask_stream(Request::new(request()))
+            .await
+            .unwrap()
+            .into_inner()
+            .map(|chunk| chunk.unwrap().chunk.unwrap())
+            .collect()
+            .await;
+
+        let Some(Chunk::Evidence(evidence)) = chunks.first() else {
+            panic!("evidence must come first");
+        };
+        assert_eq!(evidence.hits, unary.evidence);
+        let Some(Chunk::Summary(summary)) = chunks.last() else {
+            panic!("summary must come last");
+        };
+        assert_eq!(summary.stats.as_ref().unwrap().results_returned, 3);
+
+        let deltas: Vec<&str> = chunks[1..chunks.len() - 1]
+            .iter()
+            .map(|chunk| match chunk {
+                Chunk::AnswerDelta(delta) => delta.as_str(),
+                other => panic!("unexpected chunk {:?}", other),
+            })
+            .collect();
+        assert!(deltas.len() > 1);
+        assert_eq!(deltas.concat(), unary.answer);
     }
 
     #[tokio::test]
-    async fn test_search_returns_tags() {
+    async fn test_ask_stream_sends_localized_answer_whole() {
+        init_test_metrics();
+
+        let service = MemvidGrpcService::new(Arc::new(MockSearcher::new()));
+        let chunks: Vec<Chunk> = service
+            .ask_stream(Request::new(AskRequest {
+                question: "leadership".to_string(),
+                top_k: 10,
+                snippet_chars: 1000,
+                locale: "de".to_string(),
+                ..Default::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner()
+            .map(|chunk| chunk.unwrap().chunk.unwrap())
+            .collect()
+            .await;
+
+        assert_eq!(chunks.len(), 3);
+        let Chunk::AnswerDelta(answer) = &chunks[1] else {
+            panic!("expected the answer second");
+        };
+        assert!(answer.contains("10+ Jahre of engineering leadership"));
+    }
+
+    /// Frame ID of the mock frame titled `title`.
+    async fn frame_id_of(searcher: &MockSearcher, title: &str) -> u64 {
+        searcher
+            .export_frames(None, 100)
+            .await
+            .unwrap()
+            .into_iter()
+            .find(|frame| frame.title == title)
+            .unwrap()
+            .frame_id
+    }
+
+    #[tokio::test]
+    async fn test_search_honors_frame_visibility() {
         init_test_metrics();
 
         let searcher = Arc::new(MockSearcher::new());
-        let service = MemvidGrpcService::new(searcher);
+        let store = Arc::new(VisibilityStore::new().with_authenticated_keys(["k1".to_string()]));
+        let service = MemvidGrpcService::new(Arc::clone(&searcher) as Arc<dyn Searcher>)
+            .with_visibility_store(Arc::clone(&store));
+        let search = |api_key: Option<&str>| {
+            let mut request = Request::new(SearchRequest {
+                query: "leadership".to_string(),
+                top_k: 3,
+                ..Default::default()
+            });
+            if let Some(key) = api_key {
+                request
+                    .metadata_mut()
+                    .insert(usage::API_KEY_HEADER, key.parse().unwrap());
+            }
+            let service = &service;
+            async move {
+                let hits = service.search(request).await.unwrap().into_inner().hits;
+                hits.into_iter().map(|hit| hit.title).collect::<Vec<_>>()
+            }
+        };
 
-        let request = Request::new(SearchRequest {
-            query: "skills".to_string(),
-            top_k: 5,
-            snippet_chars: 200,
-            min_relevance: 0.0,
-            mode: 0,
-        });
+        let before = search(None).await;
+        let top = before[0].clone();
+        let frame_id = frame_id_of(&searcher, &top).await;
 
-        let response = service.search(request).await.unwrap();
-        let inner = response.into_inner();
+        store.set(&[frame_id], Visibility::Hidden).unwrap();
+        let hidden = search(Some("k1")).await;
+        assert!(!hidden.contains(&top));
+        // Overfetching keeps the page full
+        assert_eq!(hidden.len(), 3);
 
-        // At least one hit should have tags
-        let has_tags = inner.hits.iter().any(|h| !h.tags.is_empty());
-        assert!(has_tags);
+        store.set(&[frame_id], Visibility::Authenticated).unwrap();
+        assert!(!search(None).await.contains(&top));
+        assert!(!search(Some("k2")).await.contains(&top));
+        assert_eq!(search(Some("k1")).await, before);
+    }
+
+    #[tokio::test]
+    async fn test_ask_rebuilds_answer_without_hidden_evidence() {
+        init_test_metrics();
+
+        let searcher = Arc::new(MockSearcher::new());
+        let store = Arc::new(VisibilityStore::new());
+        let service = MemvidGrpcService::new(Arc::clone(&searcher) as Arc<dyn Searcher>)
+            .with_visibility_store(Arc::clone(&store));
+        let request = || AskRequest {
+            question: "leadership".to_string(),
+            top_k: 3,
+            use_llm: true,
+            ..Default::default()
+        };
+
+        let before = service
+            .ask(Request::new(request()))
+            .await
+            .unwrap()
+            .into_inner();
+        let top = before.evidence[0].title.clone();
+        store
+            .set(&[frame_id_of(&searcher, &top).await], Visibility::Hidden)
+            .unwrap();
+
+        let after = service
+            .ask(Request::new(request()))
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(after.evidence.iter().all(|hit| hit.title != top));
+        assert!(!after.answer.starts_with("Based on the resume"));
+        assert!(after
+            .answer
+            .starts_with(&format!("**{}**", after.evidence[0].title)));
+        assert!(after.stats.unwrap().used_fallback);
+
+        let deltas: Vec<String> = service
+            .ask_stream(Request::new(request()))
+            .await
+            .unwrap()
+            .into_inner()
+            .filter_map(|chunk| match chunk.unwrap().chunk.unwrap() {
+                Chunk::AnswerDelta(delta) => Some(delta),
+                _ => None,
+            })
+            .collect()
+            .await;
+        assert_eq!(deltas.concat(), after.answer);
     }
 
     #[tokio::test]
@@ -344,6 +2728,153 @@ mod tests {
         assert_eq!(inner.status, HealthStatus::Serving as i32);
         assert!(inner.frame_count > 0);
         assert!(!inner.memvid_file.is_empty());
+        assert!(inner.backends.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_health_check_degraded_while_reloads_fail() {
+        use crate::memvid::{LoadFuture, SearcherLoader};
+
+        let path = std::env::temp_dir().join(format!("memvid-health-{}.mv2", std::process::id()));
+        std::fs::write(&path, "v1").unwrap();
+        let loads = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let loader: SearcherLoader = {
+            let loads = Arc::clone(&loads);
+            Arc::new(move |_path: String| {
+                let first = loads.fetch_add(1, std::sync::atomic::Ordering::SeqCst) == 0;
+                Box::pin(async move {
+                    if first {
+                        Ok(Arc::new(MockSearcher::new()) as Arc<dyn Searcher>)
+                    } else {
+                        Err(ServiceError::MemvidLoadError("truncated file".to_string()))
+                    }
+                }) as LoadFuture
+            })
+        };
+        let reloadable = Arc::new(
+            ReloadableSearcher::open_with(path.to_string_lossy(), loader)
+                .await
+                .unwrap(),
+        );
+        let service = HealthService::new(Arc::clone(&reloadable) as Arc<dyn Searcher>)
+            .with_reloadable(Arc::clone(&reloadable));
+        let check = || service.check(Request::new(HealthCheckRequest::default()));
+
+        assert_eq!(
+            check().await.unwrap().into_inner().status,
+            HealthStatus::Serving as i32
+        );
+
+        assert!(reloadable.reload().await.is_err());
+        let inner = check().await.unwrap().into_inner();
+        assert_eq!(inner.status, HealthStatus::Degraded as i32);
+        assert!(inner.degraded_reason.contains("truncated file"));
+        assert!(inner.frame_count > 0);
+
+        std::fs::remove_file(path).ok();
+    }
+
+    #[tokio::test]
+    async fn test_health_check_not_serving_while_draining() {
+        let drain = Arc::new(Drain::new(Duration::from_secs(5), Duration::from_secs(30)));
+        let service =
+            HealthService::new(Arc::new(MockSearcher::new())).with_drain(Arc::clone(&drain));
+        let check = || service.check(Request::new(HealthCheckRequest::default()));
+
+        assert_eq!(
+            check().await.unwrap().into_inner().status,
+            HealthStatus::Serving as i32
+        );
+        drain.start("test");
+        assert_eq!(
+            check().await.unwrap().into_inner().status,
+            HealthStatus::NotServing as i32
+        );
+    }
+
+    #[tokio::test]
+    async fn test_health_check_reports_embedder_backends() {
+        struct FixedEmbedder;
+
+        impl memvid_core::VecEmbedder for FixedEmbedder {
+            fn embed_query(&self, _text: &str) -> memvid_core::Result<Vec<f32>> {
+                Ok(vec![0.5; 4])
+            }
+
+            fn embedding_dimension(&self) -> usize {
+                4
+            }
+        }
+
+        let chain =
+            EmbedderChain::new(vec![("onnx".to_string(), Arc::new(FixedEmbedder) as _)]).unwrap();
+        let service =
+            HealthService::new(Arc::new(MockSearcher::new())).with_embedder_chain(Arc::new(chain));
+
+        let inner = service
+            .check(Request::new(HealthCheckRequest {
+                service: String::new(),
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+
+        assert_eq!(inner.status, HealthStatus::Serving as i32);
+        assert_eq!(inner.backends.len(), 1);
+        assert_eq!(inner.backends[0].name, "onnx");
+        assert!(inner.backends[0].healthy);
+    }
+
+    #[tokio::test]
+    async fn test_health_check_combines_readiness_gates() {
+        use crate::readiness::ReadinessPolicy;
+
+        let readiness = Arc::new(Readiness::new(
+            ReadinessPolicy::parse("llm_healthy=degrade").unwrap(),
+        ));
+        let ledger = Arc::new(UsageLedger::new(
+            LlmPricing {
+                prompt_usd_per_mtok: 1_000.0,
+                completion_usd_per_mtok: 1_000.0,
+            },
+            0.000_001,
+        ));
+        let service = HealthService::new(Arc::new(MockSearcher::new()))
+            .with_readiness(Arc::clone(&readiness))
+            .with_usage_ledger(Arc::clone(&ledger));
+        let check = || service.check(Request::new(HealthCheckRequest::default()));
+
+        // A closed required gate keeps the service out of rotation
+        readiness.close(Gate::WarmupDone, "warm-up query running");
+        let inner = check().await.unwrap().into_inner();
+        assert_eq!(inner.status, HealthStatus::NotServing as i32);
+        let names: Vec<&str> = inner.gates.iter().map(|g| g.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "index_loaded",
+                "warmup_done",
+                "embedder_healthy",
+                "llm_healthy",
+                "caches_restored"
+            ]
+        );
+        assert!(!inner.gates[1].open);
+        assert_eq!(inner.gates[1].detail, "warm-up query running");
+        assert_eq!(inner.gates[1].policy, "required");
+
+        readiness.open(Gate::WarmupDone, "");
+        assert_eq!(
+            check().await.unwrap().into_inner().status,
+            HealthStatus::Serving as i32
+        );
+
+        // A closed degrade gate is reported as the degraded reason
+        ledger.record_ask("key", "question", &[], "a synthesized answer");
+        let inner = check().await.unwrap().into_inner();
+        assert_eq!(inner.status, HealthStatus::Degraded as i32);
+        assert!(inner.degraded_reason.contains("llm_healthy closed"));
+        assert!(!inner.gates[3].open);
     }
 
     #[tokio::test]
@@ -435,6 +2966,353 @@ mod tests {
         assert!(inner.slots.is_empty()); // But requested slot doesn't
     }
 
+    #[tokio::test]
+    async fn test_list_entities() {
+        init_test_metrics();
+
+        let service = MemvidGrpcService::new(Arc::new(MockSearcher::new()));
+        let response = service
+            .list_entities(Request::new(ListEntitiesRequest {}))
+            .await
+            .unwrap()
+            .into_inner();
+
+        assert_eq!(response.entities.len(), 1);
+        assert_eq!(response.entities[0].entity, "__profile__");
+        assert_eq!(response.entities[0].slot_count, 1);
+    }
+
+    #[test]
+    fn test_tag_counts_order_and_hide_visibility_tags() {
+        let counts = BTreeMap::from([
+            ("education".to_string(), 2),
+            ("experience".to_string(), 5),
+            ("skills".to_string(), 5),
+            ("visibility:private".to_string(), 1),
+            ("empty".to_string(), 0),
+        ]);
+        let tags: Vec<(String, i32)> = tag_counts(&counts)
+            .into_iter()
+            .map(|t| (t.tag, t.count))
+            .collect();
+        assert_eq!(
+            tags,
+            [
+                ("experience".to_string(), 5),
+                ("skills".to_string(), 5),
+                ("education".to_string(), 2),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_list_tags() {
+        init_test_metrics();
+
+        let searcher = Arc::new(MockSearcher::new());
+        let service = MemvidGrpcService::new(searcher.clone());
+        let response = service
+            .list_tags(Request::new(ListTagsRequest {}))
+            .await
+            .unwrap()
+            .into_inner();
+
+        let total: i32 = response.tags.iter().map(|t| t.count).sum();
+        assert_eq!(total, searcher.section_counts().values().sum::<i32>());
+        assert!(response.tags.iter().any(|t| t.tag == "experience"));
+    }
+
+    #[tokio::test]
+    async fn test_fit_assessment() {
+        init_test_metrics();
+
+        let service = MemvidGrpcService::new(Arc::new(MockSearcher::new()));
+        let response = service
+            .fit_assessment(Request::new(FitAssessmentRequest {
+                job_description: "About us: we build robots.\n\
+                                  - Rust and Python\n\
+                                  - COBOL and Fortran\n\
+                                  - Led teams of engineers"
+                    .to_string(),
+                ..Default::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+
+        let strong: Vec<&str> = response
+            .strong
+            .iter()
+            .map(|m| m.requirement.as_str())
+            .collect();
+        assert_eq!(strong, ["Rust and Python", "Led teams of engineers"]);
+        assert_eq!(response.gaps[0].requirement, "COBOL and Fortran");
+        assert_eq!(response.gaps[0].missing_terms, ["cobol", "fortran"]);
+        for evidence in response.strong.iter().flat_map(|m| &m.evidence) {
+            assert!(*evidence >= 1 && *evidence as usize <= response.evidence.len());
+        }
+        assert!((response.overall_score - 2.0 / 3.0).abs() < 1e-6);
+
+        let status = service
+            .fit_assessment(Request::new(FitAssessmentRequest {
+                job_description: "Requirements:".to_string(),
+                ..Default::default()
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+    }
+
+    #[tokio::test]
+    async fn test_suggest_completes_visible_terms() {
+        init_test_metrics();
+
+        let store = Arc::new(VisibilityStore::new());
+        let service = MemvidGrpcService::new(Arc::new(MockSearcher::new()))
+            .with_visibility_store(Arc::clone(&store));
+        let suggest = |prefix: &str, limit: i32| {
+            let request = Request::new(SuggestRequest {
+                prefix: prefix.to_string(),
+                limit,
+                ..Default::default()
+            });
+            let service = &service;
+            async move { service.suggest(request).await.map(Response::into_inner) }
+        };
+
+        let response = suggest("Sie", 0).await.unwrap();
+        assert_eq!(response.suggestions[0].text, "siemens");
+        assert_eq!(
+            response.suggestions[0].kind,
+            ProtoSuggestionKind::Tag as i32
+        );
+        let response = suggest("senior eng", 0).await.unwrap();
+        assert_eq!(
+            response.suggestions[0].text,
+            "Senior Engineering Manager at Siemens"
+        );
+
+        let response = suggest("rust pi", 0).await.unwrap();
+        assert_eq!(response.suggestions[0].text, "rust pipelines");
+        assert_eq!(suggest("e", 2).await.unwrap().suggestions.len(), 2);
+        assert!(suggest("", 0).await.unwrap().suggestions.is_empty());
+        assert_eq!(
+            suggest("e", -1).await.unwrap_err().code(),
+            tonic::Code::InvalidArgument
+        );
+
+        // Hidden frames are not suggested
+        store.set(&[1], Visibility::Hidden).unwrap();
+        let response = suggest("Sie", 0).await.unwrap();
+        assert!(response.suggestions.is_empty());
+
+        // Nor are any terms while frame ACLs are enforced
+        let mut request = Request::new(SuggestRequest {
+            prefix: "rust".to_string(),
+            ..Default::default()
+        });
+        request.extensions_mut().insert(AclContext {
+            mode: AclMode::Enforce,
+            ..Default::default()
+        });
+        let status = service.suggest(request).await.unwrap_err();
+        assert_eq!(status.code(), tonic::Code::PermissionDenied);
+    }
+
+    #[tokio::test]
+    async fn test_generate_summary() {
+        init_test_metrics();
+
+        let request = || GenerateSummaryRequest {
+            job_description: "- Python\n- Kubernetes".to_string(),
+            max_words: 40,
+            ..Default::default()
+        };
+        let service = MemvidGrpcService::new(Arc::new(MockSearcher::new()));
+        let response = service
+            .generate_summary(Request::new(request()))
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(!response.text.is_empty());
+        assert!(response.text.split_whitespace().count() <= 40);
+        assert!(!response.evidence.is_empty());
+        assert!(!response.synthesized && !response.used_fallback);
+
+        // A failing model falls back to the extractive text
+        let client =
+            LlmClient::new("http://127.0.0.1:9/v1", "test-model", None).with_max_retries(0);
+        let service =
+            MemvidGrpcService::new(Arc::new(MockSearcher::new())).with_llm_client(Arc::new(client));
+        let fallback = service
+            .generate_summary(Request::new(request()))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(fallback.text, response.text);
+        assert!(fallback.used_fallback && !fallback.synthesized);
+
+        let status = service
+            .generate_summary(Request::new(GenerateSummaryRequest {
+                max_words: -1,
+                ..Default::default()
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+    }
+
+    #[tokio::test]
+    async fn test_session_rpcs() {
+        init_test_metrics();
+        let service = MemvidGrpcService::new(Arc::new(MockSearcher::new()));
+
+        let created = service
+            .create_session(Request::new(CreateSessionRequest {}))
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(created.turns.is_empty());
+        assert_eq!(created.expires_at, created.created_at + 3600);
+
+        let appended = service
+            .append_turn(Request::new(AppendTurnRequest {
+                session_id: created.session_id.clone(),
+                role: TurnRole::User as i32,
+                content: "Where did you work?".to_string(),
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(appended.turns.len(), 1);
+
+        let fetched = service
+            .get_session(Request::new(GetSessionRequest {
+                session_id: created.session_id.clone(),
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(fetched.turns[0].role(), TurnRole::User);
+        assert_eq!(fetched.turns[0].content, "Where did you work?");
+
+        let status = service
+            .append_turn(Request::new(AppendTurnRequest {
+                session_id: created.session_id,
+                content: "Hello".to_string(),
+                ..Default::default()
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+        let status = service
+            .get_session(Request::new(GetSessionRequest {
+                session_id: "f".repeat(32),
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::NotFound);
+    }
+
+    #[tokio::test]
+    async fn test_chat_rewrites_follow_ups() {
+        init_test_metrics();
+        let service = MemvidGrpcService::new(Arc::new(MockSearcher::new()));
+
+        let first = service
+            .chat(Request::new(ChatRequest {
+                message: "What did you do at Google?".to_string(),
+                ..Default::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(first.question, "What did you do at Google?");
+        assert!(first.answer.is_some());
+
+        let follow_up = service
+            .chat(Request::new(ChatRequest {
+                session_id: first.session_id.clone(),
+                message: "What about at Siemens?".to_string(),
+                ..Default::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(follow_up.session_id, first.session_id);
+        assert_eq!(follow_up.question, "What did you do at Siemens?");
+
+        // Turns record what the user wrote, with the answers in between
+        let session = service
+            .get_session(Request::new(GetSessionRequest {
+                session_id: first.session_id,
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        let roles: Vec<_> = session.turns.iter().map(|turn| turn.role()).collect();
+        assert_eq!(
+            roles,
+            [
+                TurnRole::User,
+                TurnRole::Assistant,
+                TurnRole::User,
+                TurnRole::Assistant
+            ]
+        );
+        assert_eq!(session.turns[2].content, "What about at Siemens?");
+
+        let status = service
+            .chat(Request::new(ChatRequest {
+                session_id: "0".repeat(32),
+                message: "Hello".to_string(),
+                ..Default::default()
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::NotFound);
+    }
+
+    #[tokio::test]
+    async fn test_search_corrects_spelling_without_hits() {
+        init_test_metrics();
+        let request = || SearchRequest {
+            query: "Pyhton".to_string(),
+            top_k: 5,
+            ..Default::default()
+        };
+
+        let service = MemvidGrpcService::new(Arc::new(MockSearcher::synthetic(7, 50)));
+        let response = service
+            .search(Request::new(request()))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(response.corrected_query.as_deref(), Some("Python"));
+        assert!(!response.hits.is_empty());
+
+        // Queries with hits are left alone
+        let response = service
+            .search(Request::new(SearchRequest {
+                query: "Python".to_string(),
+                ..request()
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(response.corrected_query.is_none());
+
+        let service = MemvidGrpcService::new(Arc::new(MockSearcher::synthetic(7, 50)))
+            .with_spell_correction(false);
+        let response = service
+            .search(Request::new(request()))
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(response.corrected_query.is_none());
+        assert!(response.hits.is_empty());
+    }
+
     #[tokio::test]
     async fn test_ask_with_semantic_mode() {
         init_test_metrics();
@@ -456,6 +3334,7 @@ mod tests {
             as_of_frame: None,
             as_of_ts: None,
             adaptive: None,
+            ..Default::default()
         });
 
         let response = service.ask(request).await.unwrap();
@@ -486,6 +3365,7 @@ mod tests {
             as_of_frame: None,
             as_of_ts: None,
             adaptive: None,
+            ..Default::default()
         });
 
         let response = service.ask(request).await.unwrap();
@@ -513,6 +3393,7 @@ mod tests {
             as_of_frame: None,
             as_of_ts: None,
             adaptive: None,
+            ..Default::default()
         });
 
         let response = service.ask(request).await;
@@ -540,6 +3421,7 @@ mod tests {
             as_of_frame: None,
             as_of_ts: None,
             adaptive: None,
+            ..Default::default()
         });
 
         let response = service.ask(request).await.unwrap();
@@ -549,6 +3431,100 @@ mod tests {
         assert!(inner.answer.contains("Based on"));
     }
 
+    #[tokio::test]
+    async fn test_ask_checks_citations_under_strict_policy() {
+        init_test_metrics();
+
+        let service = MemvidGrpcService::new(Arc::new(MockSearcher::new()))
+            .with_citation_policy(CitationPolicy::Strict);
+        let response = service
+            .ask(Request::new(AskRequest {
+                question: "Summarize experience".to_string(),
+                use_llm: true,
+                top_k: 5,
+                ..Default::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+
+        // Every kept sentence cites returned evidence
+        assert!(response.answer.ends_with("[1]"), "{}", response.answer);
+        let stats = response.stats.unwrap();
+        assert_eq!(stats.invalid_citations, 0);
+        assert!(!stats.used_fallback);
+    }
+
+    #[tokio::test]
+    async fn test_ask_daily_cost_cap_disables_synthesis() {
+        init_test_metrics();
+
+        let ledger = Arc::new(UsageLedger::new(
+            LlmPricing {
+                prompt_usd_per_mtok: 1_000.0,
+                completion_usd_per_mtok: 1_000.0,
+            },
+            0.01,
+        ));
+        let service = MemvidGrpcService::new(Arc::new(MockSearcher::new()))
+            .with_usage_ledger(Arc::clone(&ledger));
+        let ask = || {
+            let mut request = Request::new(AskRequest {
+                question: "Summarize experience".to_string(),
+                use_llm: true,
+                ..Default::default()
+            });
+            request
+                .metadata_mut()
+                .insert(usage::API_KEY_HEADER, "team-key".parse().unwrap());
+            request
+        };
+
+        // The first answer is synthesized and pushes spend past the cap
+        let stats = service
+            .ask(ask())
+            .await
+            .unwrap()
+            .into_inner()
+            .stats
+            .unwrap();
+        assert!(stats.prompt_tokens > 0);
+        assert!(stats.completion_tokens > 0);
+        assert!(stats.estimated_cost_usd >= 0.01);
+        assert!(!stats.llm_capped);
+
+        let inner = service.ask(ask()).await.unwrap().into_inner();
+        let stats = inner.stats.unwrap();
+        assert!(stats.llm_capped);
+        assert_eq!(stats.prompt_tokens, 0);
+        assert!(!inner.answer.contains("Based on"));
+    }
+
+    #[tokio::test]
+    async fn test_ask_llm_synthesis_disabled() {
+        init_test_metrics();
+
+        let (sender, receiver) = tokio::sync::watch::channel(RuntimeConfig::default());
+        let service =
+            MemvidGrpcService::new(Arc::new(MockSearcher::new())).with_runtime_config(receiver);
+        // Synthesis turned off at runtime
+        sender.send_modify(|config| config.llm_synthesis = false);
+        let inner = service
+            .ask(Request::new(AskRequest {
+                question: "Summarize experience".to_string(),
+                use_llm: true,
+                ..Default::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+
+        assert!(!inner.answer.contains("Based on"));
+        let stats = inner.stats.unwrap();
+        assert_eq!(stats.prompt_tokens, 0);
+        assert!(!stats.llm_capped);
+    }
+
     #[tokio::test]
     async fn test_ask_with_filters() {
         init_test_metrics();
@@ -573,12 +3549,49 @@ mod tests {
             as_of_frame: None,
             as_of_ts: None,
             adaptive: None,
+            ..Default::default()
         });
 
         let response = service.ask(request).await.unwrap();
         assert!(response.into_inner().stats.is_some());
     }
 
+    #[tokio::test]
+    async fn test_ask_with_relative_start_expr() {
+        init_test_metrics();
+
+        let searcher = Arc::new(MockSearcher::new());
+        let service = MemvidGrpcService::new(searcher);
+
+        let request = Request::new(AskRequest {
+            question: "Recent projects?".to_string(),
+            start_expr: "-30d".to_string(),
+            end_expr: "now".to_string(),
+            ..Default::default()
+        });
+
+        assert!(service.ask(request).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_ask_with_future_as_of_ts_is_invalid_argument() {
+        init_test_metrics();
+
+        let searcher = Arc::new(MockSearcher::new());
+        let service =
+            MemvidGrpcService::new(searcher).with_clock_skew_tolerance(Duration::from_secs(60));
+
+        let request = Request::new(AskRequest {
+            question: "Experience?".to_string(),
+            as_of_ts: Some(chrono::Utc::now().timestamp() + 3_600),
+            ..Default::default()
+        });
+
+        let status = service.ask(request).await.unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+        assert!(status.message().contains("as_of_ts"));
+    }
+
     #[tokio::test]
     async fn test_ask_with_uri() {
         init_test_metrics();
@@ -595,11 +3608,12 @@ mod tests {
             filters: std::collections::HashMap::new(),
             start: 0,
             end: 0,
-            uri: "resume://skills".to_string(), // Scope to specific URI
+            uri: "mv2://resume/skills".to_string(), // Scope to specific URI
             cursor: String::new(),
             as_of_frame: None,
             as_of_ts: None,
             adaptive: None,
+            ..Default::default()
         });
 
         let response = service.ask(request).await.unwrap();
@@ -0,0 +1,201 @@
+//! Tailored summaries for GenerateSummary.
+//!
+//! Evidence is retrieved like FitAssessment's: once per aspect of the job
+//! description's requirements, or for skills, experience and leadership in
+//! general without one. With an LLM backend the model writes the summary or
+//! cover-letter paragraph from the numbered evidence. Without one, when it
+//! fails, or once the daily cost cap is reached, the text is extractive: the
+//! evidence sentences sharing terms with the job description (all of them,
+//! without one), in retrieval order, up to the word limit.
+
+use std::collections::HashSet;
+
+use super::fit::sentences;
+use super::highlight::{stem, words};
+use crate::generated::memvid::v1::SummaryKind;
+use crate::llm::ChatMessage;
+use crate::memvid::SearchResult;
+
+/// Word limit when the request leaves it unset.
+pub const DEFAULT_SUMMARY_WORDS: usize = 120;
+
+/// Largest word limit a request may ask for.
+pub const MAX_SUMMARY_WORDS: usize = 400;
+
+/// Opening of an extractive cover-letter paragraph.
+const COVER_LETTER_OPENING: &str = "Highlights of my background relevant to this role:";
+
+const SYSTEM_PROMPT: &str = "You write application material for a candidate from \
+their resume. Use only the numbered resume passages and do not invent employers, titles, \
+numbers or skills. Write plain prose without citation markers, headings or lists.";
+
+/// System and user messages asking for a `kind` text of at most
+/// `max_words` words from the numbered `evidence`, tailored to
+/// `job_description` when it is not empty.
+pub fn summary_messages(
+    kind: SummaryKind,
+    job_description: &str,
+    evidence: &[SearchResult],
+    max_words: usize,
+) -> Vec<ChatMessage> {
+    let passages = evidence
+        .iter()
+        .enumerate()
+        .map(|(i, hit)| format!("[{}] {}\n{}", i + 1, hit.title, hit.snippet))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    let text = match kind {
+        SummaryKind::Summary => {
+            "a professional summary in the third person, as at the top of a resume"
+        }
+        SummaryKind::CoverLetter => "one cover-letter paragraph in the first person",
+    };
+    let mut request = format!("Resume passages:\n{}\n\n", passages);
+    if job_description.trim().is_empty() {
+        request.push_str(&format!("Write {}", text));
+    } else {
+        request.push_str(&format!(
+            "Job description:\n{}\n\nWrite {}, tailored to the job description",
+            job_description.trim(),
+            text
+        ));
+    }
+    request.push_str(&format!(", in at most {} words.", max_words));
+
+    vec![
+        ChatMessage {
+            role: "system",
+            content: SYSTEM_PROMPT.to_string(),
+        },
+        ChatMessage {
+            role: "user",
+            content: request,
+        },
+    ]
+}
+
+/// A `kind` text of at most `max_words` words assembled from `evidence`
+/// sentences, preferring those mentioning any of `job_stems` (word stems of
+/// the job description's requirements).
+pub fn extractive_summary(
+    kind: SummaryKind,
+    evidence: &[SearchResult],
+    job_stems: &HashSet<String>,
+    max_words: usize,
+) -> String {
+    let mut seen = HashSet::new();
+    let candidates: Vec<&str> = evidence
+        .iter()
+        .flat_map(|hit| sentences(&hit.snippet))
+        .filter(|sentence| seen.insert(sentence.to_lowercase()))
+        .collect();
+    let relevant =
+        |sentence: &str| words(sentence).any(|(_, word)| job_stems.contains(&stem(word)));
+    let tailored = candidates.iter().any(|sentence| relevant(sentence));
+
+    let mut text = match kind {
+        SummaryKind::Summary => String::new(),
+        SummaryKind::CoverLetter if candidates.is_empty() => String::new(),
+        SummaryKind::CoverLetter => COVER_LETTER_OPENING.to_string(),
+    };
+    let mut budget = max_words;
+    for sentence in candidates {
+        if tailored && !relevant(sentence) {
+            continue;
+        }
+        let count = sentence.split_whitespace().count();
+        if count > budget {
+            // The first sentence is cut rather than left out
+            if budget == max_words {
+                append(
+                    &mut text,
+                    &sentence
+                        .split_whitespace()
+                        .take(budget)
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                );
+            }
+            break;
+        }
+        append(&mut text, sentence);
+        budget -= count;
+    }
+    text
+}
+
+/// Append `sentence` to `text`, terminated and separated by a space.
+fn append(text: &mut String, sentence: &str) {
+    if !text.is_empty() {
+        text.push(' ');
+    }
+    text.push_str(sentence);
+    if !sentence.ends_with(['.', '!', '?']) {
+        text.push('.');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grpc::fit::{requirements, term_stems};
+
+    fn hit(snippet: &str) -> SearchResult {
+        SearchResult {
+            title: "Experience".to_string(),
+            score: 0.5,
+            snippet: snippet.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_extractive_summary_prefers_job_terms() {
+        let evidence = [
+            hit("Led a team of 12 engineers. Shipped Kafka pipelines"),
+            hit("Built Rust services. Led a team of 12 engineers."),
+        ];
+        let general = extractive_summary(SummaryKind::Summary, &evidence, &HashSet::new(), 100);
+        assert_eq!(
+            general,
+            "Led a team of 12 engineers. Shipped Kafka pipelines. Built Rust services."
+        );
+
+        let job = term_stems(&requirements("- Rust\n- Kafka streaming"));
+        let tailored = extractive_summary(SummaryKind::CoverLetter, &evidence, &job, 100);
+        assert_eq!(
+            tailored,
+            format!(
+                "{} Shipped Kafka pipelines. Built Rust services.",
+                COVER_LETTER_OPENING
+            )
+        );
+    }
+
+    #[test]
+    fn test_extractive_summary_respects_word_limit() {
+        let evidence = [hit("Led a team of 12 engineers. Shipped Kafka pipelines.")];
+        let none = HashSet::new();
+        assert_eq!(
+            extractive_summary(SummaryKind::Summary, &evidence, &none, 7),
+            "Led a team of 12 engineers."
+        );
+        assert_eq!(
+            extractive_summary(SummaryKind::Summary, &evidence, &none, 3),
+            "Led a team."
+        );
+        assert!(extractive_summary(SummaryKind::CoverLetter, &[], &none, 50).is_empty());
+    }
+
+    #[test]
+    fn test_summary_messages_number_evidence() {
+        let evidence = [hit("Built Rust services.")];
+        let messages = summary_messages(SummaryKind::CoverLetter, "Rust engineer", &evidence, 80);
+        assert_eq!(messages[0].role, "system");
+        let request = &messages[1].content;
+        assert!(request.contains("[1] Experience\nBuilt Rust services."));
+        assert!(request.contains("Job description:\nRust engineer"));
+        assert!(request.ends_with("tailored to the job description, in at most 80 words."));
+        assert!(request.contains("cover-letter paragraph"));
+    }
+}
@@ -0,0 +1,264 @@
+//! Validation and normalization of temporal request fields.
+//!
+//! Clients can send absolute Unix timestamps or expressions such as `"-30d"`,
+//! `"now"`, or RFC 3339 strings. Timestamps too far in the future (clock skew,
+//! milliseconds sent as seconds) are rejected with INVALID_ARGUMENT instead of
+//! silently producing empty result sets.
+
+use chrono::DateTime;
+
+use crate::error::ServiceError;
+
+/// Raw temporal fields from an Ask request.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TemporalInput<'a> {
+    /// Absolute start timestamp (0 = unset)
+    pub start: i64,
+    /// Start expression, overrides `start` when non-empty
+    pub start_expr: &'a str,
+    /// Absolute end timestamp (0 = unset)
+    pub end: i64,
+    /// End expression, overrides `end` when non-empty
+    pub end_expr: &'a str,
+    /// Absolute as-of timestamp
+    pub as_of_ts: Option<i64>,
+    /// As-of expression, overrides `as_of_ts` when non-empty
+    pub as_of_expr: &'a str,
+}
+
+/// Validated temporal bounds in Unix seconds.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TemporalBounds {
+    /// Start timestamp (0 = unbounded)
+    pub start: i64,
+    /// End timestamp (0 = unbounded)
+    pub end: i64,
+    /// As-of timestamp for time-travel queries
+    pub as_of_ts: Option<i64>,
+}
+
+/// Validates temporal inputs against the current time.
+#[derive(Debug, Clone, Copy)]
+pub struct TemporalValidator {
+    /// Current Unix time in seconds
+    pub now: i64,
+    /// Allowed clock skew for future timestamps in seconds
+    pub tolerance_secs: i64,
+}
+
+impl TemporalValidator {
+    /// Resolve expressions and validate the resulting bounds.
+    pub fn normalize(&self, input: &TemporalInput<'_>) -> Result<TemporalBounds, ServiceError> {
+        let start = self.resolve("start", input.start, input.start_expr)?;
+        let end = self.resolve("end", input.end, input.end_expr)?;
+        let as_of_ts = if input.as_of_expr.is_empty() {
+            input
+                .as_of_ts
+                .map(|ts| self.check("as_of_ts", ts))
+                .transpose()?
+        } else {
+            Some(self.resolve("as_of_ts", 0, input.as_of_expr)?)
+        };
+
+        if start > 0 && end > 0 && start > end {
+            return Err(ServiceError::InvalidRequest(format!(
+                "start ({}) must not be after end ({})",
+                start, end
+            )));
+        }
+
+        Ok(TemporalBounds {
+            start,
+            end,
+            as_of_ts,
+        })
+    }
+
+    /// Resolve a field from its expression (if set) or absolute value.
+    fn resolve(&self, field: &str, value: i64, expr: &str) -> Result<i64, ServiceError> {
+        if expr.is_empty() {
+            if value == 0 {
+                return Ok(0);
+            }
+            return self.check(field, value);
+        }
+
+        let ts = parse_time_expr(expr, self.now).map_err(|e| {
+            ServiceError::InvalidRequest(format!("{}_expr '{}': {}", field, expr, e))
+        })?;
+        self.check(field, ts)
+    }
+
+    /// Reject negative and future timestamps beyond the skew tolerance.
+    fn check(&self, field: &str, ts: i64) -> Result<i64, ServiceError> {
+        if ts < 0 {
+            return Err(ServiceError::InvalidRequest(format!(
+                "{} must be a non-negative Unix timestamp in seconds, got {}",
+                field, ts
+            )));
+        }
+
+        let limit = self.now.saturating_add(self.tolerance_secs);
+        if ts > limit {
+            // Millisecond timestamps are the most common cause
+            let hint = if ts > 100_000_000_000 {
+                " (value looks like milliseconds; expected seconds)"
+            } else {
+                ""
+            };
+            return Err(ServiceError::InvalidRequest(format!(
+                "{} is {}s in the future, beyond the {}s clock skew tolerance{}",
+                field,
+                ts - self.now,
+                self.tolerance_secs,
+                hint
+            )));
+        }
+        Ok(ts)
+    }
+}
+
+/// Parse a time expression into Unix seconds.
+///
+/// Accepts `now`, relative offsets (`-30d`, `-12h`, `-15m`, `-45s`, `-2w`),
+/// absolute Unix seconds, and RFC 3339 timestamps.
+pub fn parse_time_expr(expr: &str, now: i64) -> Result<i64, String> {
+    let expr = expr.trim();
+
+    if expr.eq_ignore_ascii_case("now") {
+        return Ok(now);
+    }
+
+    if let Some(sign) = expr.chars().next().filter(|c| *c == '-' || *c == '+') {
+        let body = &expr[1..];
+        let unit = body
+            .chars()
+            .last()
+            .ok_or_else(|| "missing relative amount".to_string())?;
+        let seconds_per_unit = match unit {
+            's' => 1,
+            'm' => 60,
+            'h' => 3_600,
+            'd' => 86_400,
+            'w' => 604_800,
+            _ => return Err(format!("unknown unit '{}' (use s, m, h, d, or w)", unit)),
+        };
+        let amount: i64 = body[..body.len() - 1]
+            .parse()
+            .map_err(|_| format!("invalid relative amount '{}'", body))?;
+        let offset = amount
+            .checked_mul(seconds_per_unit)
+            .ok_or_else(|| "relative offset overflows".to_string())?;
+        return Ok(if sign == '-' {
+            now.saturating_sub(offset)
+        } else {
+            now.saturating_add(offset)
+        });
+    }
+
+    if let Ok(ts) = expr.parse::<i64>() {
+        return Ok(ts);
+    }
+
+    DateTime::parse_from_rfc3339(expr)
+        .map(|dt| dt.timestamp())
+        .map_err(|_| {
+            "expected 'now', a relative offset like '-30d', Unix seconds, or RFC 3339".into()
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 1_700_000_000;
+
+    fn validator() -> TemporalValidator {
+        TemporalValidator {
+            now: NOW,
+            tolerance_secs: 300,
+        }
+    }
+
+    #[test]
+    fn test_parse_relative_expressions() {
+        assert_eq!(parse_time_expr("-30d", NOW), Ok(NOW - 30 * 86_400));
+        assert_eq!(parse_time_expr("-12h", NOW), Ok(NOW - 12 * 3_600));
+        assert_eq!(parse_time_expr("+5m", NOW), Ok(NOW + 300));
+        assert_eq!(parse_time_expr("now", NOW), Ok(NOW));
+        assert!(parse_time_expr("-30x", NOW).is_err());
+        assert!(parse_time_expr("-d", NOW).is_err());
+    }
+
+    #[test]
+    fn test_parse_absolute_expressions() {
+        assert_eq!(parse_time_expr("1600000000", NOW), Ok(1_600_000_000));
+        assert_eq!(
+            parse_time_expr("2024-06-01T00:00:00Z", NOW),
+            Ok(1_717_200_000)
+        );
+        assert!(parse_time_expr("yesterday", NOW).is_err());
+    }
+
+    #[test]
+    fn test_unset_fields_pass_through() {
+        let bounds = validator().normalize(&TemporalInput::default()).unwrap();
+        assert_eq!(bounds, TemporalBounds::default());
+    }
+
+    #[test]
+    fn test_expression_overrides_absolute_value() {
+        let input = TemporalInput {
+            start: 42,
+            start_expr: "-1d",
+            ..Default::default()
+        };
+        let bounds = validator().normalize(&input).unwrap();
+        assert_eq!(bounds.start, NOW - 86_400);
+    }
+
+    #[test]
+    fn test_future_within_tolerance_is_accepted() {
+        let input = TemporalInput {
+            as_of_ts: Some(NOW + 120),
+            ..Default::default()
+        };
+        assert!(validator().normalize(&input).is_ok());
+    }
+
+    #[test]
+    fn test_future_beyond_tolerance_is_rejected() {
+        let input = TemporalInput {
+            end_expr: "+1h",
+            ..Default::default()
+        };
+        let err = validator().normalize(&input).unwrap_err();
+        assert!(err.to_string().contains("clock skew tolerance"));
+    }
+
+    #[test]
+    fn test_milliseconds_hint() {
+        let input = TemporalInput {
+            start: NOW * 1000,
+            ..Default::default()
+        };
+        let err = validator().normalize(&input).unwrap_err();
+        assert!(err.to_string().contains("milliseconds"));
+    }
+
+    #[test]
+    fn test_negative_and_inverted_bounds_are_rejected() {
+        let negative = TemporalInput {
+            start: -5,
+            ..Default::default()
+        };
+        assert!(validator().normalize(&negative).is_err());
+
+        let inverted = TemporalInput {
+            start: NOW - 10,
+            end: NOW - 20,
+            ..Default::default()
+        };
+        assert!(validator().normalize(&inverted).is_err());
+    }
+}
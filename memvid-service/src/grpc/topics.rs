@@ -0,0 +1,327 @@
+//! Coarse topic classification of visitor questions.
+//!
+//! Every Ask question is embedded and compared with a handful of seed
+//! phrases per topic; the topic of the most similar seed wins, and questions
+//! not close enough to any seed count as off-topic. The result is only
+//! recorded as a metric (`memvid_question_topic_total`), telling the resume
+//! owner what visitors ask about without logging the questions themselves.
+//!
+//! Without a configured model embedder, questions are embedded with
+//! [`HashingEmbedder`], a bag of words and character trigrams that needs no
+//! model and is cheap enough to run on every request.
+
+use memvid_core::VecEmbedder;
+use std::sync::Arc;
+use tracing::{debug, warn};
+
+use crate::error::ServiceError;
+use crate::metrics;
+
+/// Lowest seed similarity for a question to get an on-topic label.
+pub const MIN_TOPIC_SIMILARITY: f32 = 0.3;
+
+/// Dimension of [`HashingEmbedder`] vectors.
+const HASHING_DIMENSION: usize = 512;
+
+/// Weight of a whole word relative to one of its character trigrams.
+const WORD_WEIGHT: f32 = 2.0;
+
+/// Words too common in questions to say anything about the topic.
+pub(super) const STOPWORDS: &[&str] = &[
+    "a", "about", "an", "and", "any", "are", "can", "could", "did", "do", "does", "for", "has",
+    "have", "he", "her", "his", "how", "i", "in", "is", "it", "me", "much", "my", "of", "on", "or",
+    "she", "tell", "the", "their", "them", "they", "to", "was", "what", "when", "where", "which",
+    "who", "why", "will", "with", "would", "you", "your",
+];
+
+/// What a question is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Topic {
+    /// Roles, employers, projects, career history
+    Experience,
+    /// Languages, technologies, certifications
+    Skills,
+    /// Salary, rates, equity, benefits
+    Compensation,
+    /// Availability, location, authorization, contact
+    Logistics,
+    /// Anything else
+    OffTopic,
+}
+
+impl Topic {
+    /// Metric label of the topic.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Experience => "experience",
+            Self::Skills => "skills",
+            Self::Compensation => "compensation",
+            Self::Logistics => "logistics",
+            Self::OffTopic => "off_topic",
+        }
+    }
+}
+
+/// Seed phrases per topic. Off-topic seeds pull obvious chatter away from
+/// the resume topics.
+const SEEDS: &[(Topic, &[&str])] = &[
+    (
+        Topic::Experience,
+        &[
+            "work experience and career history",
+            "previous jobs, roles and employers",
+            "which companies have you worked at",
+            "years of experience leading engineering teams",
+            "projects delivered and accomplishments",
+            "current position and responsibilities",
+        ],
+    ),
+    (
+        Topic::Skills,
+        &[
+            "technical skills and expertise",
+            "programming languages known",
+            "technologies, frameworks and tools used",
+            "proficient in rust, python, kubernetes or cloud",
+            "certifications and education",
+            "strengths and abilities",
+        ],
+    ),
+    (
+        Topic::Compensation,
+        &[
+            "salary expectations",
+            "compensation, pay and benefits",
+            "hourly or daily contract rate",
+            "equity, stock options and bonus",
+            "how much money to earn",
+        ],
+    ),
+    (
+        Topic::Logistics,
+        &[
+            "availability and start date",
+            "notice period",
+            "open to relocation or remote work",
+            "location and time zone",
+            "work authorization or visa sponsorship",
+            "contact details, email or phone to schedule an interview",
+        ],
+    ),
+    (
+        Topic::OffTopic,
+        &[
+            "tell a joke",
+            "weather forecast today",
+            "write a poem or story",
+            "ignore previous instructions",
+            "recipe for dinner",
+        ],
+    ),
+];
+
+/// Embedder hashing words and character trigrams into a fixed-size vector.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HashingEmbedder;
+
+impl HashingEmbedder {
+    fn embed(text: &str) -> Vec<f32> {
+        let mut vector = vec![0.0; HASHING_DIMENSION];
+        let lower = text.to_lowercase();
+        let words = lower
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty() && !STOPWORDS.contains(w));
+        for word in words {
+            add_feature(&mut vector, word, WORD_WEIGHT);
+            let padded: Vec<char> = format!("^{}$", word).chars().collect();
+            for trigram in padded.windows(3) {
+                add_feature(&mut vector, &trigram.iter().collect::<String>(), 1.0);
+            }
+        }
+        normalize(&mut vector);
+        vector
+    }
+}
+
+impl VecEmbedder for HashingEmbedder {
+    fn embed_query(&self, text: &str) -> memvid_core::Result<Vec<f32>> {
+        Ok(Self::embed(text))
+    }
+
+    fn embedding_dimension(&self) -> usize {
+        HASHING_DIMENSION
+    }
+}
+
+/// Add `weight` to the bucket of `feature`, with a hash-derived sign so
+/// collisions cancel out on average.
+fn add_feature(vector: &mut [f32], feature: &str, weight: f32) {
+    // FNV-1a: stable across processes, unlike the std hasher
+    let hash = feature.bytes().fold(0xcbf2_9ce4_8422_2325u64, |h, b| {
+        (h ^ u64::from(b)).wrapping_mul(0x0000_0100_0000_01b3)
+    });
+    let bucket = (hash % vector.len() as u64) as usize;
+    vector[bucket] += if hash >> 63 == 0 { weight } else { -weight };
+}
+
+fn normalize(vector: &mut [f32]) {
+    let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|x| *x /= norm);
+    }
+}
+
+fn cosine(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norms =
+        a.iter().map(|x| x * x).sum::<f32>().sqrt() * b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norms > 0.0 {
+        dot / norms
+    } else {
+        0.0
+    }
+}
+
+/// Classifies questions by their similarity to topic seed phrases.
+pub struct TopicClassifier {
+    embedder: Arc<dyn VecEmbedder + Send + Sync>,
+    seeds: Vec<(Topic, Vec<f32>)>,
+    min_similarity: f32,
+}
+
+impl std::fmt::Debug for TopicClassifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TopicClassifier")
+            .field("seeds", &self.seeds.len())
+            .field("min_similarity", &self.min_similarity)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for TopicClassifier {
+    fn default() -> Self {
+        Self::new(Arc::new(HashingEmbedder)).expect("hashing embedder never fails")
+    }
+}
+
+impl TopicClassifier {
+    /// Classify with `embedder`, embedding the seed phrases up front.
+    pub fn new(embedder: Arc<dyn VecEmbedder + Send + Sync>) -> Result<Self, ServiceError> {
+        let mut seeds = Vec::new();
+        for (topic, phrases) in SEEDS {
+            for phrase in *phrases {
+                let embedding = embedder.embed_query(phrase).map_err(|e| {
+                    ServiceError::Internal(format!("Failed to embed topic seed: {}", e))
+                })?;
+                seeds.push((*topic, embedding));
+            }
+        }
+        Ok(Self {
+            embedder,
+            seeds,
+            min_similarity: MIN_TOPIC_SIMILARITY,
+        })
+    }
+
+    /// Set the lowest seed similarity for an on-topic label.
+    pub fn with_min_similarity(mut self, min_similarity: f32) -> Self {
+        self.min_similarity = min_similarity;
+        self
+    }
+
+    /// Topic of `question` (blocking when the embedder is).
+    pub fn classify(&self, question: &str) -> Result<Topic, ServiceError> {
+        let embedding = self
+            .embedder
+            .embed_query(question)
+            .map_err(|e| ServiceError::Internal(format!("Failed to embed question: {}", e)))?;
+        let best = self
+            .seeds
+            .iter()
+            .map(|(topic, seed)| (*topic, cosine(&embedding, seed)))
+            .fold(
+                None,
+                |best: Option<(Topic, f32)>, (topic, score)| match best {
+                    Some((_, best_score)) if best_score >= score => best,
+                    _ => Some((topic, score)),
+                },
+            );
+        Ok(match best {
+            Some((topic, score)) if score >= self.min_similarity => topic,
+            _ => Topic::OffTopic,
+        })
+    }
+
+    /// Classify `question` off the request path and record its topic.
+    pub fn observe(self: &Arc<Self>, question: &str) {
+        let classifier = Arc::clone(self);
+        let question = question.to_string();
+        tokio::task::spawn_blocking(move || match classifier.classify(&question) {
+            Ok(topic) => {
+                debug!(topic = topic.as_str(), "Classified question");
+                metrics::record_question_topic(topic.as_str());
+            }
+            Err(e) => warn!(error = %e, "Failed to classify question topic"),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classifies_resume_topics() {
+        let classifier = TopicClassifier::default();
+        let cases = [
+            (
+                "How many years of experience do you have?",
+                Topic::Experience,
+            ),
+            ("Which companies has he worked for?", Topic::Experience),
+            ("What programming languages do you know?", Topic::Skills),
+            ("Is she proficient in Kubernetes?", Topic::Skills),
+            ("What are your salary expectations?", Topic::Compensation),
+            ("What is your hourly rate?", Topic::Compensation),
+            ("When could you start?", Topic::Logistics),
+            ("Are you open to relocation?", Topic::Logistics),
+            ("Tell me a joke", Topic::OffTopic),
+            ("xyzzy plugh", Topic::OffTopic),
+        ];
+        for (question, expected) in cases {
+            assert_eq!(
+                classifier.classify(question).unwrap(),
+                expected,
+                "{}",
+                question
+            );
+        }
+    }
+
+    #[test]
+    fn test_hashing_embedder_is_normalized_and_stable() {
+        let a = HashingEmbedder.embed_query("Rust experience").unwrap();
+        let b = HashingEmbedder.embed_query("rust EXPERIENCE").unwrap();
+
+        assert_eq!(a, b);
+        assert_eq!(a.len(), HashingEmbedder.embedding_dimension());
+        let norm = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+        assert!((norm - 1.0).abs() < 1e-5);
+        assert!(HashingEmbedder
+            .embed_query("what is the")
+            .unwrap()
+            .iter()
+            .all(|x| *x == 0.0));
+    }
+
+    #[test]
+    fn test_min_similarity_controls_off_topic() {
+        let strict = TopicClassifier::default().with_min_similarity(1.01);
+        assert_eq!(
+            strict
+                .classify("What are your salary expectations?")
+                .unwrap(),
+            Topic::OffTopic
+        );
+    }
+}
@@ -0,0 +1,274 @@
+//! Token and cost accounting for LLM answer synthesis.
+//!
+//! memvid-core does not report token counts, so usage is estimated from the
+//! synthesis input (question plus evidence snippets) and the generated
+//! answer. Spend is tracked per UTC day, per API key and in total; once the
+//! configured daily cap is reached, Ask falls back to context-only answers.
+
+use chrono::{NaiveDate, Utc};
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::Mutex;
+use tonic::metadata::MetadataMap;
+use tracing::warn;
+
+use crate::memvid::SearchResult;
+use crate::metrics;
+
+/// Request metadata key identifying the calling API key.
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// Key id used for requests without an API key.
+pub const ANONYMOUS_KEY: &str = "anonymous";
+
+/// Approximate characters per token for English text.
+const CHARS_PER_TOKEN: usize = 4;
+
+/// LLM prices in USD per million tokens.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct LlmPricing {
+    /// Price of prompt (input) tokens
+    pub prompt_usd_per_mtok: f64,
+    /// Price of completion (output) tokens
+    pub completion_usd_per_mtok: f64,
+}
+
+/// Estimated LLM usage of one or more requests.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct LlmUsage {
+    /// Estimated prompt tokens
+    pub prompt_tokens: u32,
+    /// Estimated completion tokens
+    pub completion_tokens: u32,
+    /// Estimated cost in USD
+    pub cost_usd: f64,
+}
+
+impl LlmUsage {
+    fn add(&mut self, other: LlmUsage) {
+        self.prompt_tokens = self.prompt_tokens.saturating_add(other.prompt_tokens);
+        self.completion_tokens = self
+            .completion_tokens
+            .saturating_add(other.completion_tokens);
+        self.cost_usd += other.cost_usd;
+    }
+}
+
+/// Estimate the token count of `text`.
+pub fn estimate_tokens(text: &str) -> u32 {
+    text.chars().count().div_ceil(CHARS_PER_TOKEN) as u32
+}
+
+/// Stable, non-reversible id for the caller's API key.
+///
+/// The raw key never reaches logs or metric labels.
+pub fn key_id(metadata: &MetadataMap) -> String {
+    match metadata
+        .get(API_KEY_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .filter(|key| !key.is_empty())
+    {
+        Some(key) => {
+            let mut hasher = DefaultHasher::new();
+            key.hash(&mut hasher);
+            format!("key-{:08x}", hasher.finish() as u32)
+        }
+        None => ANONYMOUS_KEY.to_string(),
+    }
+}
+
+#[derive(Debug)]
+struct DailyUsage {
+    day: NaiveDate,
+    total: LlmUsage,
+    per_key: HashMap<String, LlmUsage>,
+}
+
+/// Daily LLM spend, in total and per API key.
+#[derive(Debug)]
+pub struct UsageLedger {
+    pricing: LlmPricing,
+    daily_cap_usd: f64,
+    usage: Mutex<DailyUsage>,
+}
+
+impl Default for UsageLedger {
+    fn default() -> Self {
+        Self::new(LlmPricing::default(), 0.0)
+    }
+}
+
+impl UsageLedger {
+    /// Create a ledger; a `daily_cap_usd` of zero means unlimited.
+    pub fn new(pricing: LlmPricing, daily_cap_usd: f64) -> Self {
+        Self {
+            pricing,
+            daily_cap_usd,
+            usage: Mutex::new(DailyUsage {
+                day: Utc::now().date_naive(),
+                total: LlmUsage::default(),
+                per_key: HashMap::new(),
+            }),
+        }
+    }
+
+    /// Whether synthesis is still within today's cost cap.
+    pub fn synthesis_allowed(&self) -> bool {
+        self.synthesis_allowed_on(Utc::now().date_naive())
+    }
+
+    /// Account for one synthesized answer and return its estimated usage.
+    pub fn record_ask(
+        &self,
+        api_key: &str,
+        question: &str,
+        evidence: &[SearchResult],
+        answer: &str,
+    ) -> LlmUsage {
+        let prompt_tokens = estimate_tokens(question)
+            + evidence
+                .iter()
+                .map(|hit| estimate_tokens(&hit.snippet))
+                .sum::<u32>();
+        let completion_tokens = estimate_tokens(answer);
+        let usage = LlmUsage {
+            prompt_tokens,
+            completion_tokens,
+            cost_usd: (prompt_tokens as f64 * self.pricing.prompt_usd_per_mtok
+                + completion_tokens as f64 * self.pricing.completion_usd_per_mtok)
+                / 1_000_000.0,
+        };
+        self.record_on(Utc::now().date_naive(), api_key, usage);
+        usage
+    }
+
+    /// Today's usage for one API key id.
+    pub fn key_usage(&self, api_key: &str) -> LlmUsage {
+        let daily = self.lock(Utc::now().date_naive());
+        daily.per_key.get(api_key).copied().unwrap_or_default()
+    }
+
+    fn synthesis_allowed_on(&self, day: NaiveDate) -> bool {
+        self.daily_cap_usd <= 0.0 || self.lock(day).total.cost_usd < self.daily_cap_usd
+    }
+
+    fn record_on(&self, day: NaiveDate, api_key: &str, usage: LlmUsage) {
+        let mut daily = self.lock(day);
+        let was_allowed = self.daily_cap_usd <= 0.0 || daily.total.cost_usd < self.daily_cap_usd;
+        daily.total.add(usage);
+        let key_usage = daily.per_key.entry(api_key.to_string()).or_default();
+        key_usage.add(usage);
+
+        metrics::record_llm_usage(api_key, usage.prompt_tokens, usage.completion_tokens);
+        metrics::set_llm_daily_cost(api_key, key_usage.cost_usd);
+        if was_allowed && self.daily_cap_usd > 0.0 && daily.total.cost_usd >= self.daily_cap_usd {
+            warn!(
+                spent_usd = daily.total.cost_usd,
+                cap_usd = self.daily_cap_usd,
+                "Daily LLM cost cap reached, disabling synthesis until midnight UTC"
+            );
+        }
+    }
+
+    /// Lock today's usage, resetting it when the UTC day has changed.
+    fn lock(&self, day: NaiveDate) -> std::sync::MutexGuard<'_, DailyUsage> {
+        let mut daily = self.usage.lock().unwrap_or_else(|e| e.into_inner());
+        if daily.day != day {
+            for key in daily.per_key.keys() {
+                metrics::set_llm_daily_cost(key, 0.0);
+            }
+            *daily = DailyUsage {
+                day,
+                total: LlmUsage::default(),
+                per_key: HashMap::new(),
+            };
+        }
+        daily
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tonic::metadata::MetadataValue;
+
+    fn pricing() -> LlmPricing {
+        LlmPricing {
+            prompt_usd_per_mtok: 3.0,
+            completion_usd_per_mtok: 15.0,
+        }
+    }
+
+    fn hit(snippet: &str) -> SearchResult {
+        SearchResult {
+            title: "Experience".to_string(),
+            score: 0.9,
+            snippet: snippet.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_estimate_tokens() {
+        assert_eq!(estimate_tokens(""), 0);
+        assert_eq!(estimate_tokens("abcd"), 1);
+        assert_eq!(estimate_tokens("abcde"), 2);
+    }
+
+    #[test]
+    fn test_record_ask_estimates_cost_per_key() {
+        let ledger = UsageLedger::new(pricing(), 0.0);
+
+        // 8 + 12 prompt characters, 40 completion characters
+        let usage = ledger.record_ask(
+            "key-a",
+            "question",
+            &[hit(&"x".repeat(12))],
+            &"y".repeat(40),
+        );
+        assert_eq!(usage.prompt_tokens, 5);
+        assert_eq!(usage.completion_tokens, 10);
+        assert!((usage.cost_usd - (5.0 * 3.0 + 10.0 * 15.0) / 1_000_000.0).abs() < 1e-12);
+
+        ledger.record_ask("key-a", "question", &[], "");
+        assert_eq!(ledger.key_usage("key-a").prompt_tokens, 7);
+        assert_eq!(ledger.key_usage("key-b"), LlmUsage::default());
+    }
+
+    #[test]
+    fn test_daily_cap_disables_synthesis_until_next_day() {
+        let ledger = UsageLedger::new(pricing(), 0.0001);
+        let today = NaiveDate::from_ymd_opt(2024, 6, 1).unwrap();
+        let tomorrow = today.succ_opt().unwrap();
+        let usage = LlmUsage {
+            prompt_tokens: 10,
+            completion_tokens: 10,
+            cost_usd: 0.00006,
+        };
+
+        ledger.record_on(today, "key-a", usage);
+        assert!(ledger.synthesis_allowed_on(today));
+        ledger.record_on(today, "key-b", usage);
+        assert!(!ledger.synthesis_allowed_on(today));
+        assert!(ledger.synthesis_allowed_on(tomorrow));
+    }
+
+    #[test]
+    fn test_zero_cap_is_unlimited() {
+        let ledger = UsageLedger::default();
+        ledger.record_ask("key-a", &"q".repeat(4000), &[], &"a".repeat(4000));
+        assert!(ledger.synthesis_allowed());
+    }
+
+    #[test]
+    fn test_key_id_hides_raw_key() {
+        let mut metadata = MetadataMap::new();
+        assert_eq!(key_id(&metadata), ANONYMOUS_KEY);
+
+        metadata.insert(API_KEY_HEADER, MetadataValue::from_static("secret-123"));
+        let id = key_id(&metadata);
+        assert!(id.starts_with("key-"));
+        assert!(!id.contains("secret"));
+        assert_eq!(id, key_id(&metadata));
+    }
+}
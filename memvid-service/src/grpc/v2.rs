@@ -0,0 +1,1177 @@
+//! memvid.v2 adapter over the shared `Searcher`.
+//!
+//! v2 adds frame IDs, metadata filters and page cursors to search, plus
+//! streaming search. It is served next to v1 from the same searcher so
+//! clients can migrate independently; messages unchanged since v1 are
+//! reused from the v1 package.
+
+use prost::Message;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{Request, Response, Status, Streaming};
+use tracing::{info, instrument};
+
+use crate::error::ServiceError;
+use crate::generated::memvid::v1::{
+    AskMode as ProtoAskMode, AskStats, EntityLink, GetStateRequest, GetStateResponse,
+    ListEntitiesRequest, ListEntitiesResponse, ListTagsRequest, ListTagsResponse, OutputEncoding,
+};
+use crate::generated::memvid::v2::{
+    export_frames_request, export_state_request, memvid_service_server::MemvidService, AskRequest,
+    AskResponse, ExportFramesRequest, ExportStateRequest, ExportedFrame, FrameBatch, SearchHit,
+    SearchRequest, SearchResponse, StateBatch,
+};
+use crate::memvid::{
+    apply_language_preference, context_answer, ensemble_ask, AclContext,
+    AskMode as SearcherAskMode, AskRequest as SearcherAskRequest, Audience, Boosts, CitationCheck,
+    CitationPolicy, SearchRequest as SearcherSearchRequest, SearchResult, Searcher,
+    SearcherRegistry, VisibilityStore, BOOST_OVERFETCH, DEFAULT_INDEX, LANGUAGE_OVERFETCH,
+    VISIBILITY_OVERFETCH,
+};
+use crate::metrics;
+use crate::runtime_config::{RuntimeConfig, RuntimeConfigReceiver};
+
+use super::acl::{caller_acl, refuse_when_enforced};
+use super::budget::fit_response;
+use super::coverage::CoverageTracker;
+use super::cursor::{ask_scope, scope_hash, CursorCodec};
+use super::entities::EntityLinker;
+use super::export::{
+    self, run_export, AckWindow, BandwidthLimiter, ExportSource, DEFAULT_ACK_TIMEOUT,
+    DEFAULT_MAX_BYTES_PER_SEC,
+};
+use super::highlight::{Highlighter, SnippetRenderer};
+use super::jwt::caller_subject;
+use super::locale::{localize_answer, Locale};
+use super::profile::{Profile, ProfileCache};
+use super::query_stats::QueryStats;
+use super::retention::RetentionPolicy;
+use super::sanitize::encode;
+use super::service::{
+    adaptive_options, caller_audience, check_ensemble, enforce_citations, ensemble_stats,
+    entity_summaries, ranking_boosts, ranking_window, tag_counts, DEFAULT_CLOCK_SKEW_TOLERANCE,
+    DEFAULT_MAX_RESPONSE_BYTES,
+};
+use super::temporal::{TemporalInput, TemporalValidator};
+use super::topics::TopicClassifier;
+use super::usage::{self, LlmUsage, UsageLedger};
+use super::voice;
+
+/// With filters set, retrieve this many times the requested window so that
+/// filtering still leaves a full page in most cases.
+const FILTER_OVERFETCH: i32 = 4;
+
+/// gRPC implementation of memvid.v2 MemvidService.
+pub struct MemvidV2Service {
+    searcher: Arc<dyn Searcher>,
+    indexes: Arc<SearcherRegistry>,
+    clock_skew_tolerance: Duration,
+    max_response_bytes: usize,
+    export_limiter: Arc<BandwidthLimiter>,
+    export_ack_timeout: Duration,
+    usage: Arc<UsageLedger>,
+    coverage: Arc<CoverageTracker>,
+    query_stats: Arc<QueryStats>,
+    profiles: Arc<ProfileCache>,
+    retention: RetentionPolicy,
+    cursors: Arc<CursorCodec>,
+    visibility: Arc<VisibilityStore>,
+    topics: Option<Arc<TopicClassifier>>,
+    runtime: RuntimeConfigReceiver,
+    citations: CitationPolicy,
+    /// Pinned "now" in deterministic mode (None = the wall clock)
+    deterministic_now: Option<i64>,
+}
+
+impl MemvidV2Service {
+    /// Create a new v2 service over the given searcher implementation.
+    pub fn new(searcher: Arc<dyn Searcher>) -> Self {
+        Self {
+            indexes: Arc::new(SearcherRegistry::new(DEFAULT_INDEX, Arc::clone(&searcher))),
+            searcher,
+            clock_skew_tolerance: DEFAULT_CLOCK_SKEW_TOLERANCE,
+            max_response_bytes: DEFAULT_MAX_RESPONSE_BYTES,
+            export_limiter: Arc::new(BandwidthLimiter::new(DEFAULT_MAX_BYTES_PER_SEC)),
+            export_ack_timeout: DEFAULT_ACK_TIMEOUT,
+            usage: Arc::new(UsageLedger::default()),
+            coverage: Arc::new(CoverageTracker::default()),
+            query_stats: Arc::new(QueryStats::default()),
+            profiles: Arc::new(ProfileCache::default()),
+            retention: RetentionPolicy::default(),
+            cursors: Arc::new(CursorCodec::default()),
+            visibility: Arc::new(VisibilityStore::default()),
+            topics: None,
+            runtime: RuntimeConfig::default().fixed(),
+            citations: CitationPolicy::default(),
+            deterministic_now: None,
+        }
+    }
+
+    /// Serve Search, SearchStream and Ask from the registry's index named
+    /// in the request; its default index replaces the searcher given to
+    /// `new`.
+    pub fn with_registry(mut self, registry: Arc<SearcherRegistry>) -> Self {
+        self.searcher = Arc::clone(registry.default_searcher());
+        self.indexes = registry;
+        self
+    }
+
+    /// Set how far in the future temporal request fields may be.
+    pub fn with_clock_skew_tolerance(mut self, tolerance: Duration) -> Self {
+        self.clock_skew_tolerance = tolerance;
+        self
+    }
+
+    /// Set the maximum serialized response size; larger responses are trimmed.
+    pub fn with_max_response_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_response_bytes = max_bytes;
+        self
+    }
+
+    /// Set the bandwidth cap shared by all exports (0 = unlimited) and how
+    /// long an export waits for a client acknowledgment.
+    pub fn with_export_limits(mut self, bytes_per_sec: u64, ack_timeout: Duration) -> Self {
+        self.export_limiter = Arc::new(BandwidthLimiter::new(bytes_per_sec));
+        self.export_ack_timeout = ack_timeout;
+        self
+    }
+
+    /// Account LLM usage in a ledger shared with other services.
+    pub fn with_usage_ledger(mut self, ledger: Arc<UsageLedger>) -> Self {
+        self.usage = ledger;
+        self
+    }
+
+    /// Record surfaced frames in a tracker shared with other services.
+    pub fn with_coverage_tracker(mut self, tracker: Arc<CoverageTracker>) -> Self {
+        self.coverage = tracker;
+        self
+    }
+
+    /// Record queries for the analytics report in stats shared with other
+    /// services.
+    pub fn with_query_stats(mut self, stats: Arc<QueryStats>) -> Self {
+        self.query_stats = stats;
+        self
+    }
+
+    /// Read the profile through a cache shared with other services.
+    pub fn with_profile_cache(mut self, cache: Arc<ProfileCache>) -> Self {
+        self.profiles = cache;
+        self
+    }
+
+    /// Log query text as `retention` allows (hashed with `hash_queries`).
+    pub fn with_retention(mut self, retention: RetentionPolicy) -> Self {
+        self.retention = retention;
+        self
+    }
+
+    /// Sign and verify page cursors with a codec shared with other services.
+    pub fn with_cursor_codec(mut self, codec: Arc<CursorCodec>) -> Self {
+        self.cursors = codec;
+        self
+    }
+
+    /// Filter frames by the visibility set through the Admin service.
+    pub fn with_visibility_store(mut self, store: Arc<VisibilityStore>) -> Self {
+        self.visibility = store;
+        self
+    }
+
+    /// Record the topic of every Ask question with `classifier`.
+    pub fn with_topic_classifier(mut self, classifier: Arc<TopicClassifier>) -> Self {
+        self.topics = Some(classifier);
+        self
+    }
+
+    /// Take LLM synthesis and request defaults from the runtime config;
+    /// with synthesis off, Ask always returns context-only answers.
+    pub fn with_runtime_config(mut self, runtime: RuntimeConfigReceiver) -> Self {
+        self.runtime = runtime;
+        self
+    }
+
+    /// Set how synthesized answers are held to their citation markers.
+    pub fn with_citation_policy(mut self, policy: CitationPolicy) -> Self {
+        self.citations = policy;
+        self
+    }
+
+    /// Answer reproducibly: resolve relative times and freshness against
+    /// `now` (Unix seconds) instead of the clock, and run ensemble asks as
+    /// plain asks, without paraphrases.
+    pub fn with_deterministic(mut self, now: i64) -> Self {
+        self.deterministic_now = Some(now);
+        self
+    }
+
+    /// Current Unix time, pinned in deterministic mode.
+    fn now(&self) -> i64 {
+        self.deterministic_now
+            .unwrap_or_else(|| chrono::Utc::now().timestamp())
+    }
+
+    /// Run a search and cut out the page selected by the request cursor.
+    ///
+    /// Hit snippets keep their source text; the returned renderer encodes
+    /// and highlights them.
+    async fn search_page(
+        &self,
+        req: &SearchRequest,
+        audience: Audience,
+        acl: Option<AclContext>,
+    ) -> Result<(SearchResponse, SnippetRenderer), ServiceError> {
+        let started = Instant::now();
+        let searcher = &**self.indexes.get(&req.index)?;
+        let runtime = self.runtime.borrow().clone();
+        let top_k = runtime.top_k(req.top_k);
+        let snippet_chars = runtime.snippet_chars(req.snippet_chars);
+        let language = Locale::preferred_language(&req.preferred_language)?.map(Locale::code);
+        let boosts = ranking_boosts(req.boosts.as_ref())?;
+        let generation = searcher.generation();
+        let boosts_key = boosts.as_ref().map(Boosts::scope_key);
+        let mut scope_parts = vec![
+            req.query.as_str(),
+            language.unwrap_or_default(),
+            if req.strict_language { "strict" } else { "" },
+        ];
+        // Only boosted searches and searches of a named index get extra
+        // parts, so other cursors stay valid
+        scope_parts.extend(boosts_key.as_deref());
+        if !req.index.is_empty() {
+            scope_parts.push(&req.index);
+        }
+        let scope = scope_hash("search", &scope_parts, &req.filters);
+        let offset = self.cursors.decode(&req.cursor, generation, scope)? as usize;
+
+        // The searcher has no offsets and v2 filters are matched against hit
+        // tags here, so retrieve everything up to the end of the page (plus
+        // one hit to detect a next page)
+        let mut window = (offset as i32).saturating_add(top_k).saturating_add(1);
+        if !req.filters.is_empty() {
+            window = window.saturating_mul(FILTER_OVERFETCH);
+        }
+        if language.is_some() {
+            window = window.saturating_mul(LANGUAGE_OVERFETCH);
+        }
+        if boosts.is_some() {
+            window = window.saturating_mul(BOOST_OVERFETCH);
+        }
+        if self
+            .visibility
+            .restricts(audience, &searcher.section_counts())
+        {
+            window = window.saturating_mul(VISIBILITY_OVERFETCH);
+        }
+
+        let acronyms = searcher.acronyms();
+        let mut result = searcher
+            .search(SearcherSearchRequest {
+                query: acronyms.expand_query(&req.query),
+                top_k: window,
+                snippet_chars,
+                budget_ms: req.budget_ms.filter(|&budget| budget > 0),
+                filters: HashMap::new(),
+                uri: None,
+                acl,
+            })
+            .await?;
+
+        metrics::record_search_latency(result.took_ms as f64);
+        metrics::increment_search_count();
+        if result.partial {
+            metrics::increment_search_partial();
+        }
+        // Restricted frames never leave the service
+        self.visibility.retain_visible(&mut result.hits, audience);
+        if let Some(language) = language {
+            apply_language_preference(&mut result.hits, language, req.strict_language);
+        }
+        if let Some(boosts) = &boosts {
+            boosts.apply(&mut result.hits, self.now());
+        }
+
+        let matching: Vec<SearchResult> = result
+            .hits
+            .into_iter()
+            .filter(|hit| matches_filters(&hit.tags, &req.filters))
+            .collect();
+        let total_hits = matching.len();
+        let encoding = OutputEncoding::try_from(req.output_encoding).unwrap_or_default();
+        let profile = if req.link_entities {
+            Some(self.profiles.get(searcher).await?)
+        } else {
+            None
+        };
+        let highlight_terms = acronyms.highlight_terms(&req.query);
+        let renderer = SnippetRenderer::new(
+            encoding,
+            Highlighter::new(req.highlight.as_ref(), &req.query, &highlight_terms),
+        );
+        let hits: Vec<SearchHit> = matching
+            .into_iter()
+            .skip(offset)
+            .take(top_k as usize)
+            .map(|hit| to_hit(hit, encoding, profile.as_deref().map(Profile::linker)))
+            .collect();
+        self.coverage
+            .record(hits.iter().filter_map(|hit| hit.frame_id));
+        if req.cursor.is_empty() {
+            self.query_stats
+                .record(&req.query, hits.len(), started.elapsed());
+        }
+
+        let next_offset = offset + hits.len();
+        let response = SearchResponse {
+            hits,
+            total_hits: total_hits as i32,
+            took_ms: result.took_ms,
+            partial: result.partial,
+            next_cursor: if next_offset < total_hits {
+                self.cursors.encode(generation, next_offset as u64, scope)
+            } else {
+                String::new()
+            },
+            trimmed: None,
+            highlight_terms: highlight_terms
+                .iter()
+                .map(|term| encode(term, encoding))
+                .collect(),
+            expanded_query: result.expanded_query.map(|query| encode(&query, encoding)),
+        };
+        Ok((response, renderer))
+    }
+}
+
+/// A hit matches when every filter value is one of its tags.
+fn matches_filters(tags: &[String], filters: &HashMap<String, String>) -> bool {
+    filters
+        .values()
+        .all(|v| tags.iter().any(|t| t.eq_ignore_ascii_case(v)))
+}
+
+/// Convert a searcher result to a v2 hit, encoding its title and link
+/// mentions. The snippet keeps its source text until it is rendered.
+fn to_hit(
+    result: SearchResult,
+    encoding: OutputEncoding,
+    linker: Option<&EntityLinker>,
+) -> SearchHit {
+    SearchHit {
+        links: linker
+            .map(|linker| linker.link(&result.snippet))
+            .unwrap_or_default()
+            .into_iter()
+            .map(|link| EntityLink {
+                mention: encode(&link.mention, encoding),
+                ..link
+            })
+            .collect(),
+        frame_id: result.frame_id,
+        title: encode(&result.title, encoding),
+        score: result.score,
+        snippet: result.snippet,
+        tags: result.tags,
+        ingested_at: result.ingested_at,
+        source_version: result.source_version,
+    }
+}
+
+/// Frame metadata export, resuming after the last exported frame.
+struct FrameExport {
+    searcher: Arc<dyn Searcher>,
+    after: Option<u64>,
+    batch_size: usize,
+    cursors: Arc<CursorCodec>,
+    generation: u64,
+    visibility: Arc<VisibilityStore>,
+    audience: Audience,
+}
+
+#[tonic::async_trait]
+impl ExportSource for FrameExport {
+    type Batch = FrameBatch;
+
+    async fn next_batch(&mut self, sequence: u64) -> Result<(FrameBatch, bool), ServiceError> {
+        let frames = self
+            .searcher
+            .export_frames(self.after, self.batch_size)
+            .await?;
+        let done = frames.len() < self.batch_size;
+        if let Some(last) = frames.last() {
+            self.after = Some(last.frame_id);
+        }
+
+        // Cursor offsets count frames from 1 so that 0 means "from the start"
+        let next_cursor = match self.after {
+            Some(after) if !done => {
+                self.cursors
+                    .encode(self.generation, after + 1, export_frames_scope())
+            }
+            _ => String::new(),
+        };
+        let batch = FrameBatch {
+            sequence,
+            frames: frames
+                .into_iter()
+                .filter(|f| {
+                    self.visibility
+                        .visibility(Some(f.frame_id), &f.tags)
+                        .visible_to(self.audience)
+                })
+                .map(|f| ExportedFrame {
+                    frame_id: f.frame_id,
+                    uri: f.uri,
+                    title: f.title,
+                    tags: f.tags,
+                    labels: f.labels,
+                })
+                .collect(),
+            done,
+            next_cursor,
+        };
+        Ok((batch, done))
+    }
+}
+
+/// Cursor scope of frame exports (the full frame list).
+fn export_frames_scope() -> u64 {
+    scope_hash("export_frames", &[], &HashMap::new())
+}
+
+/// Memory card export for a fixed list of entities.
+struct StateExport {
+    searcher: Arc<dyn Searcher>,
+    entities: std::vec::IntoIter<String>,
+    batch_size: usize,
+}
+
+#[tonic::async_trait]
+impl ExportSource for StateExport {
+    type Batch = StateBatch;
+
+    async fn next_batch(&mut self, sequence: u64) -> Result<(StateBatch, bool), ServiceError> {
+        let mut states = Vec::new();
+        for entity in self.entities.by_ref().take(self.batch_size) {
+            let state = self.searcher.get_state(&entity, None).await?;
+            states.push(GetStateResponse {
+                found: state.found,
+                entity: state.entity,
+                slots: state.slots,
+            });
+        }
+        let done = self.entities.len() == 0;
+        Ok((
+            StateBatch {
+                sequence,
+                states,
+                done,
+            },
+            done,
+        ))
+    }
+}
+
+fn frames_ack(message: ExportFramesRequest) -> Option<u64> {
+    match message.request {
+        Some(export_frames_request::Request::Ack(ack)) => Some(ack.sequence),
+        _ => None,
+    }
+}
+
+fn state_ack(message: ExportStateRequest) -> Option<u64> {
+    match message.request {
+        Some(export_state_request::Request::Ack(ack)) => Some(ack.sequence),
+        _ => None,
+    }
+}
+
+/// The first message of an export stream must start it.
+fn expect_start<T>(start: Option<T>) -> Result<T, ServiceError> {
+    start.ok_or_else(|| {
+        ServiceError::InvalidRequest("the first export message must be a start message".into())
+    })
+}
+
+#[tonic::async_trait]
+impl MemvidService for MemvidV2Service {
+    type SearchStreamStream = tokio_stream::Iter<std::vec::IntoIter<Result<SearchHit, Status>>>;
+    type ExportFramesStream = ReceiverStream<Result<FrameBatch, Status>>;
+    type ExportStateStream = ReceiverStream<Result<StateBatch, Status>>;
+
+    #[instrument(skip(self, request), fields(query, caller = caller_subject(&request)))]
+    async fn search(
+        &self,
+        request: Request<SearchRequest>,
+    ) -> Result<Response<SearchResponse>, Status> {
+        let audience = caller_audience(&self.visibility, request.metadata());
+        let acl = caller_acl(&request);
+        let mut req = request.into_inner();
+        voice::normalize_query(&mut req.query, req.source);
+        let logged_query = self.retention.query_text(&req.query);
+        tracing::Span::current().record("query", &*logged_query);
+
+        info!(
+            query = %logged_query,
+            top_k = req.top_k,
+            cursor = %req.cursor,
+            filters = req.filters.len(),
+            "Processing v2 search request"
+        );
+
+        let (mut response, renderer) = self.search_page(&req, audience, acl).await?;
+
+        if fit_response(&mut response, self.max_response_bytes, |snippet| {
+            renderer.render(snippet)
+        }) {
+            metrics::increment_response_trimmed("v2_search");
+        }
+        metrics::record_message_sizes("v2_search", req.encoded_len(), response.encoded_len());
+
+        Ok(Response::new(response))
+    }
+
+    #[instrument(skip(self, request), fields(query, caller = caller_subject(&request)))]
+    async fn search_stream(
+        &self,
+        request: Request<SearchRequest>,
+    ) -> Result<Response<Self::SearchStreamStream>, Status> {
+        let audience = caller_audience(&self.visibility, request.metadata());
+        let acl = caller_acl(&request);
+        let mut req = request.into_inner();
+        voice::normalize_query(&mut req.query, req.source);
+        let logged_query = self.retention.query_text(&req.query);
+        tracing::Span::current().record("query", &*logged_query);
+
+        info!(
+            query = %logged_query,
+            top_k = req.top_k,
+            cursor = %req.cursor,
+            "Processing v2 streaming search request"
+        );
+
+        // Hits are sent one per message, so no response trimming is needed
+        let (response, renderer) = self.search_page(&req, audience, acl).await?;
+        let hits: Vec<Result<SearchHit, Status>> = response
+            .hits
+            .into_iter()
+            .map(|mut hit| {
+                hit.snippet = renderer.render(&hit.snippet);
+                hit
+            })
+            .map(Ok)
+            .collect();
+
+        Ok(Response::new(tokio_stream::iter(hits)))
+    }
+
+    #[instrument(skip(self, request), fields(question, caller = caller_subject(&request)))]
+    async fn ask(&self, request: Request<AskRequest>) -> Result<Response<AskResponse>, Status> {
+        let started = Instant::now();
+        let accept_language = request
+            .metadata()
+            .get("accept-language")
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let audience = caller_audience(&self.visibility, request.metadata());
+        let acl = caller_acl(&request);
+        let api_key = usage::key_id(request.metadata());
+        let mut req = request.into_inner();
+        let request_bytes = req.encoded_len();
+        voice::normalize_query(&mut req.question, req.source);
+        let searcher = Arc::clone(self.indexes.get(&req.index).map_err(Status::from)?);
+        let logged_question = self.retention.query_text(&req.question);
+        tracing::Span::current().record("question", &*logged_question);
+
+        info!(
+            question = %logged_question,
+            mode = ?req.mode,
+            top_k = req.top_k,
+            cursor = %req.cursor,
+            "Processing v2 ask request"
+        );
+        if let Some(topics) = &self.topics {
+            topics.observe(&req.question);
+        }
+
+        let runtime = self.runtime.borrow().clone();
+        let top_k = runtime.top_k(req.top_k);
+        let snippet_chars = runtime.snippet_chars(req.snippet_chars);
+        let mode = match ProtoAskMode::try_from(req.mode) {
+            Ok(ProtoAskMode::Sem) => SearcherAskMode::Sem,
+            Ok(ProtoAskMode::Lex) => SearcherAskMode::Lex,
+            _ => SearcherAskMode::Hybrid,
+        };
+
+        let locale =
+            Locale::resolve(&req.locale, accept_language.as_deref()).map_err(Status::from)?;
+        let language = Locale::preferred_language(&req.preferred_language)
+            .map_err(Status::from)?
+            .map(Locale::code);
+        let boosts = ranking_boosts(req.boosts.as_ref()).map_err(Status::from)?;
+        check_ensemble(req.ensemble, &req.cursor).map_err(Status::from)?;
+
+        // v2 only takes time expressions; v1's integer fields map to unset
+        let validator = TemporalValidator {
+            now: self.now(),
+            tolerance_secs: self.clock_skew_tolerance.as_secs() as i64,
+        };
+        let bounds = validator
+            .normalize(&TemporalInput {
+                start_expr: &req.start,
+                end_expr: &req.end,
+                as_of_expr: &req.as_of,
+                ..Default::default()
+            })
+            .map_err(Status::from)?;
+
+        let wants_llm = req.use_llm && runtime.llm_synthesis;
+        let llm_capped = wants_llm && !self.usage.synthesis_allowed();
+        let use_llm = wants_llm && !llm_capped;
+        if llm_capped {
+            metrics::increment_llm_capped();
+        }
+
+        let acronyms = searcher.acronyms();
+        let mut ask_request = SearcherAskRequest {
+            question: acronyms.expand_query(&req.question),
+            use_llm,
+            // Retrieve extra candidates to re-rank
+            top_k: ranking_window(top_k, language, boosts.as_ref()),
+            filters: req.filters,
+            start: bounds.start,
+            end: bounds.end,
+            snippet_chars,
+            mode,
+            uri: Some(req.uri).filter(|uri| !uri.is_empty()),
+            cursor: None,
+            as_of_frame: req.as_of_frame.map(|frame| frame as i64),
+            as_of_ts: bounds.as_of_ts,
+            adaptive: adaptive_options(req.adaptive, None)?,
+            acl,
+        };
+        let generation = searcher.generation();
+        let scope = ask_scope(
+            &ask_request,
+            language,
+            req.strict_language,
+            boosts.as_ref(),
+            &req.index,
+        );
+        let offset = self
+            .cursors
+            .decode(&req.cursor, generation, scope)
+            .map_err(Status::from)?;
+        ask_request.cursor = (offset > 0).then(|| offset.to_string());
+        // Retrieve extra candidates to make up for frames the caller may not see
+        if self
+            .visibility
+            .restricts(audience, &searcher.section_counts())
+        {
+            ask_request.top_k = ask_request.top_k.saturating_mul(VISIBILITY_OVERFETCH);
+        }
+
+        let (mut result, ensemble) = if req.ensemble && self.deterministic_now.is_none() {
+            let (result, stats) = ensemble_ask(&*searcher, ask_request)
+                .await
+                .map_err(Status::from)?;
+            (result, Some(ensemble_stats(stats)))
+        } else {
+            let result = searcher.ask(ask_request).await.map_err(Status::from)?;
+            (result, None)
+        };
+        let synthesized = use_llm.then(|| result.evidence.clone());
+        let hidden = self
+            .visibility
+            .retain_visible(&mut result.evidence, audience)
+            > 0;
+        if let Some(language) = language {
+            apply_language_preference(&mut result.evidence, language, req.strict_language);
+        }
+        if let Some(boosts) = &boosts {
+            boosts.apply(&mut result.evidence, self.now());
+        }
+        result.evidence.truncate(top_k.max(0) as usize);
+        result.stats.results_returned = result.evidence.len() as i32;
+        if hidden {
+            // The answer may draw on restricted evidence
+            result.answer = context_answer(&result.evidence);
+            result.stats.used_fallback = true;
+        }
+        let citations = match synthesized.filter(|_| !hidden) {
+            Some(synthesized) => enforce_citations(
+                self.citations,
+                &mut result.answer,
+                &mut result.stats,
+                &synthesized,
+                &result.evidence,
+            ),
+            None => CitationCheck::default(),
+        };
+        let llm_usage = if use_llm {
+            self.usage
+                .record_ask(&api_key, &req.question, &result.evidence, &result.answer)
+        } else {
+            LlmUsage::default()
+        };
+        self.coverage
+            .record(result.evidence.iter().filter_map(|e| e.frame_id));
+        if req.cursor.is_empty() {
+            self.query_stats
+                .record(&req.question, result.evidence.len(), started.elapsed());
+        }
+
+        let encoding = OutputEncoding::try_from(req.output_encoding).unwrap_or_default();
+        let profile = if req.link_entities {
+            Some(self.profiles.get(&*searcher).await.map_err(Status::from)?)
+        } else {
+            None
+        };
+        let answer = match locale {
+            Some(locale) => localize_answer(&result.answer, locale),
+            None => result.answer,
+        };
+
+        let mut response = AskResponse {
+            answer: encode(&answer, encoding),
+            evidence: result
+                .evidence
+                .into_iter()
+                .map(|e| to_hit(e, encoding, profile.as_deref().map(Profile::linker)))
+                .collect(),
+            stats: Some(AskStats {
+                candidates_retrieved: result.stats.candidates_retrieved,
+                results_returned: result.stats.results_returned,
+                retrieval_ms: result.stats.retrieval_ms,
+                reranking_ms: result.stats.reranking_ms,
+                used_fallback: result.stats.used_fallback,
+                prompt_tokens: llm_usage.prompt_tokens as i32,
+                completion_tokens: llm_usage.completion_tokens as i32,
+                estimated_cost_usd: llm_usage.cost_usd,
+                llm_capped,
+                invalid_citations: citations.invalid_markers as i32,
+                uncited_claims_removed: citations.claims_removed as i32,
+                aspects: Vec::new(),
+            }),
+            next_cursor: result
+                .next_cursor
+                .and_then(|cursor| cursor.parse().ok())
+                .map(|offset| self.cursors.encode(generation, offset, scope))
+                .unwrap_or_default(),
+            trimmed: None,
+            highlight_terms: acronyms
+                .highlight_terms(&req.question)
+                .iter()
+                .map(|term| encode(term, encoding))
+                .collect(),
+            ensemble,
+        };
+
+        if fit_response(&mut response, self.max_response_bytes, |snippet| {
+            encode(snippet, encoding)
+        }) {
+            metrics::increment_response_trimmed("v2_ask");
+        }
+        metrics::record_message_sizes("v2_ask", request_bytes, response.encoded_len());
+
+        Ok(Response::new(response))
+    }
+
+    #[instrument(skip(self, request), fields(entity, caller = caller_subject(&request)))]
+    async fn get_state(
+        &self,
+        request: Request<GetStateRequest>,
+    ) -> Result<Response<GetStateResponse>, Status> {
+        let req = request.into_inner();
+        tracing::Span::current().record("entity", &req.entity);
+
+        let slot = Some(req.slot.as_str()).filter(|slot| !slot.is_empty());
+        let result = self
+            .searcher
+            .get_state(&req.entity, slot)
+            .await
+            .map_err(Status::from)?;
+
+        let response = GetStateResponse {
+            found: result.found,
+            entity: result.entity,
+            slots: result.slots,
+        };
+        metrics::record_message_sizes("v2_get_state", req.encoded_len(), response.encoded_len());
+
+        Ok(Response::new(response))
+    }
+
+    #[instrument(skip(self, request), fields(caller = caller_subject(&request)))]
+    async fn list_entities(
+        &self,
+        request: Request<ListEntitiesRequest>,
+    ) -> Result<Response<ListEntitiesResponse>, Status> {
+        let request_bytes = request.get_ref().encoded_len();
+        let entities = self.searcher.list_entities().await.map_err(Status::from)?;
+
+        let response = ListEntitiesResponse {
+            entities: entity_summaries(entities),
+        };
+        metrics::record_message_sizes("v2_list_entities", request_bytes, response.encoded_len());
+
+        Ok(Response::new(response))
+    }
+
+    #[instrument(skip(self, request), fields(caller = caller_subject(&request)))]
+    async fn list_tags(
+        &self,
+        request: Request<ListTagsRequest>,
+    ) -> Result<Response<ListTagsResponse>, Status> {
+        let request_bytes = request.get_ref().encoded_len();
+        let response = ListTagsResponse {
+            tags: tag_counts(&self.searcher.section_counts()),
+        };
+        metrics::record_message_sizes("v2_list_tags", request_bytes, response.encoded_len());
+
+        Ok(Response::new(response))
+    }
+
+    async fn export_frames(
+        &self,
+        request: Request<Streaming<ExportFramesRequest>>,
+    ) -> Result<Response<Self::ExportFramesStream>, Status> {
+        refuse_when_enforced(&request, "ExportFrames")?;
+        let audience = caller_audience(&self.visibility, request.metadata());
+        let mut inbound = request.into_inner();
+        let start = expect_start(inbound.message().await?.and_then(|m| match m.request {
+            Some(export_frames_request::Request::Start(start)) => Some(start),
+            _ => None,
+        }))
+        .map_err(Status::from)?;
+
+        info!(
+            after_frame_id = ?start.after_frame_id,
+            cursor = %start.cursor,
+            batch_size = start.batch_size,
+            window = start.window,
+            "Starting frame export"
+        );
+
+        let generation = self.searcher.generation();
+        let after = if start.cursor.is_empty() {
+            start.after_frame_id
+        } else if start.after_frame_id.is_some() {
+            return Err(Status::invalid_argument(
+                "set either after_frame_id or cursor, not both",
+            ));
+        } else {
+            self.cursors
+                .decode(&start.cursor, generation, export_frames_scope())
+                .map_err(Status::from)?
+                .checked_sub(1)
+        };
+        let source = FrameExport {
+            searcher: Arc::clone(&self.searcher),
+            after,
+            batch_size: export::batch_size(start.batch_size),
+            cursors: Arc::clone(&self.cursors),
+            generation,
+            visibility: Arc::clone(&self.visibility),
+            audience,
+        };
+        let acks = AckWindow::new(
+            inbound,
+            frames_ack,
+            export::window(start.window),
+            self.export_ack_timeout,
+        );
+        let (tx, rx) = mpsc::channel(1);
+        tokio::spawn(run_export(
+            "export_frames",
+            source,
+            acks,
+            Arc::clone(&self.export_limiter),
+            tx,
+        ));
+
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+
+    async fn export_state(
+        &self,
+        request: Request<Streaming<ExportStateRequest>>,
+    ) -> Result<Response<Self::ExportStateStream>, Status> {
+        refuse_when_enforced(&request, "ExportState")?;
+        let mut inbound = request.into_inner();
+        let start = expect_start(inbound.message().await?.and_then(|m| match m.request {
+            Some(export_state_request::Request::Start(start)) => Some(start),
+            _ => None,
+        }))
+        .map_err(Status::from)?;
+
+        info!(
+            entities = start.entities.len(),
+            batch_size = start.batch_size,
+            window = start.window,
+            "Starting state export"
+        );
+
+        let source = StateExport {
+            searcher: Arc::clone(&self.searcher),
+            entities: start.entities.into_iter(),
+            batch_size: export::batch_size(start.batch_size),
+        };
+        let acks = AckWindow::new(
+            inbound,
+            state_ack,
+            export::window(start.window),
+            self.export_ack_timeout,
+        );
+        let (tx, rx) = mpsc::channel(1);
+        tokio::spawn(run_export(
+            "export_state",
+            source,
+            acks,
+            Arc::clone(&self.export_limiter),
+            tx,
+        ));
+
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memvid::{MockSearcher, Visibility};
+    use tokio_stream::StreamExt;
+
+    fn service() -> MemvidV2Service {
+        MemvidV2Service::new(Arc::new(MockSearcher::new()))
+    }
+
+    /// Cursor scope of an unfiltered search without language preference.
+    fn search_scope(query: &str) -> u64 {
+        scope_hash("search", &[query, "", ""], &HashMap::new())
+    }
+
+    #[tokio::test]
+    async fn test_search_returns_frame_ids_and_pages() {
+        let service = service();
+
+        let first = service
+            .search(Request::new(SearchRequest {
+                query: "experience".to_string(),
+                top_k: 2,
+                ..Default::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(first.hits.len(), 2);
+        assert!(first.hits.iter().all(|h| h.frame_id.is_some()));
+        assert_eq!(
+            service
+                .cursors
+                .decode(&first.next_cursor, 1, search_scope("experience"))
+                .unwrap(),
+            2
+        );
+
+        let second = service
+            .search(Request::new(SearchRequest {
+                query: "experience".to_string(),
+                top_k: 2,
+                cursor: first.next_cursor,
+                ..Default::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(!second.hits.is_empty());
+        assert_ne!(first.hits[0].frame_id, second.hits[0].frame_id);
+    }
+
+    #[tokio::test]
+    async fn test_search_filters_by_tag() {
+        let response = service()
+            .search(Request::new(SearchRequest {
+                query: "experience".to_string(),
+                top_k: 10,
+                filters: HashMap::from([("section".to_string(), "leadership".to_string())]),
+                ..Default::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+
+        assert!(!response.hits.is_empty());
+        assert!(response
+            .hits
+            .iter()
+            .all(|h| h.tags.iter().any(|t| t == "leadership")));
+        assert!(response.next_cursor.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_hidden_frames_are_excluded_from_search_and_export() {
+        let store = Arc::new(VisibilityStore::new());
+        store.set(&[2], Visibility::Hidden).unwrap();
+        store.set(&[3], Visibility::Authenticated).unwrap();
+        let service = service().with_visibility_store(Arc::clone(&store));
+
+        let response = service
+            .search(Request::new(SearchRequest {
+                query: "experience".to_string(),
+                top_k: 10,
+                ..Default::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(!response.hits.is_empty());
+        assert!(response
+            .hits
+            .iter()
+            .all(|h| !matches!(h.frame_id, Some(2) | Some(3))));
+
+        let mut export = FrameExport {
+            searcher: Arc::clone(&service.searcher),
+            after: None,
+            batch_size: 4,
+            cursors: Arc::clone(&service.cursors),
+            generation: 1,
+            visibility: store,
+            audience: Audience::Public,
+        };
+        let (batch, _) = export.next_batch(0).await.unwrap();
+        let ids: Vec<u64> = batch.frames.iter().map(|f| f.frame_id).collect();
+        assert_eq!(ids, vec![1, 4]);
+
+        export.after = None;
+        export.audience = Audience::Authenticated;
+        let (batch, _) = export.next_batch(0).await.unwrap();
+        let ids: Vec<u64> = batch.frames.iter().map(|f| f.frame_id).collect();
+        assert_eq!(ids, vec![1, 3, 4]);
+    }
+
+    #[tokio::test]
+    async fn test_search_invalid_cursor() {
+        let status = service()
+            .search(Request::new(SearchRequest {
+                query: "experience".to_string(),
+                cursor: "not-a-cursor".to_string(),
+                ..Default::default()
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+    }
+
+    #[tokio::test]
+    async fn test_search_stream_matches_search_page() {
+        let service = service();
+        let request = SearchRequest {
+            query: "leadership".to_string(),
+            top_k: 3,
+            ..Default::default()
+        };
+
+        let page = service
+            .search(Request::new(request.clone()))
+            .await
+            .unwrap()
+            .into_inner();
+        let streamed: Vec<SearchHit> = service
+            .search_stream(Request::new(request))
+            .await
+            .unwrap()
+            .into_inner()
+            .map(Result::unwrap)
+            .collect()
+            .await;
+
+        assert_eq!(streamed, page.hits);
+    }
+
+    #[tokio::test]
+    async fn test_ask_returns_next_cursor() {
+        let response = service()
+            .ask(Request::new(AskRequest {
+                question: "leadership".to_string(),
+                top_k: 2,
+                start: "-30d".to_string(),
+                ..Default::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+
+        assert_eq!(response.evidence.len(), 2);
+        assert!(!response.next_cursor.is_empty());
+        assert!(response.evidence.iter().all(|e| e.frame_id.is_some()));
+    }
+
+    #[tokio::test]
+    async fn test_ask_pages_with_cursor() {
+        let service = service();
+        let request = |cursor: String| AskRequest {
+            question: "leadership".to_string(),
+            top_k: 2,
+            cursor,
+            ..Default::default()
+        };
+        let first = service
+            .ask(Request::new(request(String::new())))
+            .await
+            .unwrap()
+            .into_inner();
+        let second = service
+            .ask(Request::new(request(first.next_cursor)))
+            .await
+            .unwrap()
+            .into_inner();
+
+        assert!(!second.evidence.is_empty());
+        assert_ne!(first.evidence[0].frame_id, second.evidence[0].frame_id);
+    }
+
+    #[tokio::test]
+    async fn test_cursor_from_other_generation_or_query_is_rejected() {
+        let service = service();
+        let request = |query: &str, cursor: String| {
+            Request::new(SearchRequest {
+                query: query.to_string(),
+                cursor,
+                ..Default::default()
+            })
+        };
+
+        let stale = service.cursors.encode(0, 2, search_scope("experience"));
+        let status = service
+            .search(request("experience", stale))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::FailedPrecondition);
+
+        let current = service.cursors.encode(1, 2, search_scope("experience"));
+        let status = service
+            .search(request("leadership", current))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+    }
+
+    #[tokio::test]
+    async fn test_ask_rejects_future_time_expression() {
+        let status = service()
+            .ask(Request::new(AskRequest {
+                question: "leadership".to_string(),
+                end: "2999-01-01T00:00:00Z".to_string(),
+                ..Default::default()
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+    }
+}
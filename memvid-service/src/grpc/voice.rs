@@ -0,0 +1,261 @@
+//! Cleanup of transcribed (voice) queries.
+//!
+//! Speech recognizers emit text without punctuation, with filler words,
+//! stutters, dictated punctuation ("question mark") and several questions
+//! run together. Requests with `source = QUERY_SOURCE_VOICE` are normalized
+//! into plain sentences before retrieval; typed queries are left untouched.
+
+use tracing::debug;
+
+use crate::generated::memvid::v1::QuerySource;
+
+/// Words dropped wherever they occur.
+const FILLERS: [&str; 12] = [
+    "um", "umm", "uh", "uhh", "uhm", "er", "erm", "ah", "hmm", "hm", "mm", "mhm",
+];
+
+/// Two-word filler phrases dropped wherever they occur.
+const FILLER_PHRASES: [(&str, &str); 2] = [("you", "know"), ("i", "mean")];
+
+/// Discourse markers dropped at the start of a sentence.
+const LEADING_MARKERS: [&str; 6] = ["so", "okay", "ok", "well", "alright", "right"];
+
+/// Dictated punctuation, as (first word, optional second word, mark).
+/// "period" is left alone: it is as likely to mean a span of time.
+const SPOKEN_PUNCTUATION: [(&str, Option<&str>, char); 3] = [
+    ("question", Some("mark"), '?'),
+    ("full", Some("stop"), '.'),
+    ("comma", None, ','),
+];
+
+/// Question words; a conjunction before one starts a new question.
+const QUESTION_WORDS: [&str; 9] = [
+    "what", "where", "when", "which", "who", "whom", "whose", "why", "how",
+];
+
+/// Auxiliaries that start a yes/no question.
+const AUXILIARIES: [&str; 15] = [
+    "does", "did", "do", "is", "are", "was", "were", "has", "have", "had", "can", "could", "will",
+    "would", "should",
+];
+
+/// Conjunctions joining run-on questions ("... and where did he work").
+const RUN_ON_CONJUNCTIONS: [&str; 2] = ["and", "also"];
+
+/// Normalize `text` in place when the request comes from a voice frontend.
+pub fn normalize_query(text: &mut String, source: i32) {
+    if QuerySource::try_from(source) != Ok(QuerySource::Voice) {
+        return;
+    }
+    let normalized = normalize_transcript(text);
+    // Word counts only: query text is logged subject to the retention policy
+    debug!(
+        original_words = text.split_whitespace().count(),
+        normalized_words = normalized.split_whitespace().count(),
+        "Normalized voice query"
+    );
+    *text = normalized;
+}
+
+/// Clean up a speech transcript: drop fillers and stutters, apply dictated
+/// punctuation, split run-on questions and punctuate each sentence.
+///
+/// A transcript consisting only of fillers is returned trimmed but otherwise
+/// unchanged.
+pub fn normalize_transcript(text: &str) -> String {
+    let tokens = clean_tokens(text);
+    let sentences: Vec<String> = split_sentences(tokens)
+        .into_iter()
+        .filter_map(punctuate)
+        .collect();
+    if sentences.is_empty() {
+        return text.trim().to_string();
+    }
+    sentences.join(" ")
+}
+
+/// `token` lowercased without surrounding punctuation.
+fn bare(token: &str) -> String {
+    token
+        .trim_matches(|c: char| !c.is_alphanumeric() && c != '\'')
+        .to_lowercase()
+}
+
+fn ends_sentence(token: &str) -> bool {
+    token.ends_with(['.', '?', '!'])
+}
+
+/// Drop fillers and repeated words and attach dictated punctuation to the
+/// preceding word.
+fn clean_tokens(text: &str) -> Vec<String> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let mut tokens: Vec<String> = Vec::with_capacity(words.len());
+    let mut i = 0;
+    while i < words.len() {
+        let word = bare(words[i]);
+        let next = words.get(i + 1).map(|w| bare(w));
+
+        if let Some((len, mark)) = spoken_punctuation(&word, next.as_deref()) {
+            if let Some(last) = tokens.last_mut() {
+                let trimmed = last.trim_end_matches([',', '.', '?', '!', ';', ':']).len();
+                last.truncate(trimmed);
+                last.push(mark);
+            }
+            i += len;
+            continue;
+        }
+
+        let filler_len = if FILLERS.contains(&word.as_str()) {
+            1
+        } else if FILLER_PHRASES
+            .iter()
+            .any(|(first, second)| word == *first && next.as_deref() == Some(*second))
+        {
+            2
+        } else {
+            0
+        };
+        if filler_len > 0 {
+            // Keep a sentence end carried by the filler ("... uh.")
+            let dropped = words[i + filler_len - 1];
+            if let Some(last) = tokens.last_mut() {
+                if ends_sentence(dropped) && !ends_sentence(last) {
+                    last.push(dropped.chars().last().unwrap_or('.'));
+                }
+            }
+            i += filler_len;
+            continue;
+        }
+
+        // Stutter: "the the" -> "the"
+        let repeated = tokens
+            .last()
+            .is_some_and(|last| bare(last) == word && last.to_lowercase() == word);
+        if repeated && !word.is_empty() {
+            tokens.pop();
+        }
+        tokens.push(words[i].to_string());
+        i += 1;
+    }
+    tokens
+}
+
+/// Length in words and mark of dictated punctuation starting at `word`.
+fn spoken_punctuation(word: &str, next: Option<&str>) -> Option<(usize, char)> {
+    SPOKEN_PUNCTUATION
+        .iter()
+        .find_map(|(first, second, mark)| match second {
+            Some(second) if word == *first && next == Some(*second) => Some((2, *mark)),
+            None if word == *first => Some((1, *mark)),
+            _ => None,
+        })
+}
+
+/// Split at sentence-ending punctuation and before questions joined by a
+/// conjunction ("... and where did he work").
+fn split_sentences(tokens: Vec<String>) -> Vec<Vec<String>> {
+    let mut sentences = vec![Vec::new()];
+    let mut i = 0;
+    while i < tokens.len() {
+        let run_on = (i..tokens.len())
+            .take_while(|j| RUN_ON_CONJUNCTIONS.contains(&bare(&tokens[*j]).as_str()))
+            .count();
+        let current = sentences.last_mut().expect("at least one sentence");
+        if run_on > 0
+            && !current.is_empty()
+            && tokens
+                .get(i + run_on)
+                .is_some_and(|next| QUESTION_WORDS.contains(&bare(next).as_str()))
+        {
+            sentences.push(Vec::new());
+            i += run_on;
+            continue;
+        }
+
+        let token = tokens[i].clone();
+        let ends = ends_sentence(&token);
+        current.push(token);
+        if ends {
+            sentences.push(Vec::new());
+        }
+        i += 1;
+    }
+    sentences
+}
+
+/// Drop leading discourse markers, capitalize and end with `?` or `.`.
+fn punctuate(mut words: Vec<String>) -> Option<String> {
+    while words.len() > 1 && LEADING_MARKERS.contains(&bare(&words[0]).as_str()) {
+        words.remove(0);
+    }
+    if words.is_empty() {
+        return None;
+    }
+
+    for word in &mut words {
+        if bare(word) == "i" {
+            *word = word.replacen('i', "I", 1);
+        }
+    }
+    let first = bare(&words[0]);
+    let question =
+        QUESTION_WORDS.contains(&first.as_str()) || AUXILIARIES.contains(&first.as_str());
+    let mut sentence = words.join(" ");
+    let trimmed = sentence.trim_end_matches([',', ';', ':']).len();
+    sentence.truncate(trimmed);
+    if !ends_sentence(&sentence) {
+        sentence.push(if question { '?' } else { '.' });
+    }
+
+    let mut chars = sentence.chars();
+    let first = chars.next()?;
+    Some(first.to_uppercase().chain(chars).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_splits_run_on_questions_and_drops_fillers() {
+        assert_eq!(
+            normalize_transcript(
+                "um so what uh programming languages does he know and where did he work"
+            ),
+            "What programming languages does he know? Where did he work?"
+        );
+        assert_eq!(
+            normalize_transcript("tell me about the the kubernetes work you know at acme"),
+            "Tell me about the kubernetes work at acme."
+        );
+    }
+
+    #[test]
+    fn test_applies_dictated_punctuation() {
+        assert_eq!(
+            normalize_transcript("has he led teams question mark okay what size"),
+            "Has he led teams? What size?"
+        );
+        assert_eq!(
+            normalize_transcript("what period was i mean he at acme"),
+            "What period was he at acme?"
+        );
+        assert_eq!(
+            normalize_transcript("rust comma go and python full stop"),
+            "Rust, go and python."
+        );
+    }
+
+    #[test]
+    fn test_only_voice_queries_are_normalized() {
+        let mut typed = "um what about rust".to_string();
+        normalize_query(&mut typed, QuerySource::Text as i32);
+        assert_eq!(typed, "um what about rust");
+
+        let mut spoken = "um what about rust".to_string();
+        normalize_query(&mut spoken, QuerySource::Voice as i32);
+        assert_eq!(spoken, "What about rust?");
+
+        assert_eq!(normalize_transcript(" uh um "), "uh um");
+    }
+}
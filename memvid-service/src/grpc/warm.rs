@@ -0,0 +1,200 @@
+//! Warm answer cache snapshots.
+//!
+//! WarmCache runs a corpus of common questions through the Ask pipeline,
+//! filling the answer cache, and writes the answers to a snapshot file.
+//! Replicas restore that snapshot at startup, so a newly scheduled one
+//! answers those questions from a hot cache instead of paying cold-start
+//! latency. Questions are warmed as a plain public Ask on the default index
+//! would send them; requests with other options miss the warmed entries.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use super::service::MemvidGrpcService;
+use crate::error::ServiceError;
+use crate::memvid::{CacheSnapshot, CachingSearcher};
+
+/// Outcome of one cache warm-up.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WarmReport {
+    /// Questions run
+    pub questions: usize,
+    /// Answers cached and snapshotted
+    pub cached: usize,
+    /// Questions that failed
+    pub failed: usize,
+    /// Where the snapshot was written (None = not configured)
+    pub snapshot_path: Option<PathBuf>,
+}
+
+/// Fills the answer cache from a question corpus and persists it.
+pub struct CacheWarmer {
+    service: Arc<MemvidGrpcService>,
+    cache: Arc<CachingSearcher>,
+    corpus: Vec<String>,
+    snapshot_path: Option<PathBuf>,
+}
+
+impl CacheWarmer {
+    /// Warm `cache`, the answer cache in front of `service`'s default index.
+    pub fn new(service: Arc<MemvidGrpcService>, cache: Arc<CachingSearcher>) -> Self {
+        Self {
+            service,
+            cache,
+            corpus: Vec::new(),
+            snapshot_path: None,
+        }
+    }
+
+    /// Questions warmed when a request names none.
+    pub fn with_corpus(mut self, corpus: Vec<String>) -> Self {
+        self.corpus = corpus;
+        self
+    }
+
+    /// Write snapshots to (and restore them from) `path`.
+    pub fn with_snapshot_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.snapshot_path = Some(path.into());
+        self
+    }
+
+    /// Run `questions` (empty = the configured corpus) through the cache
+    /// and write the snapshot.
+    ///
+    /// # Errors
+    /// `InvalidRequest` when there are no questions to run; writing the
+    /// snapshot failed.
+    pub async fn warm(
+        &self,
+        questions: &[String],
+        use_llm: bool,
+    ) -> Result<WarmReport, ServiceError> {
+        let questions = if questions.is_empty() {
+            &self.corpus
+        } else {
+            questions
+        };
+        if questions.is_empty() {
+            return Err(ServiceError::InvalidRequest(
+                "no questions given and no WARM_CACHE_CORPUS configured".to_string(),
+            ));
+        }
+
+        let mut entries = Vec::new();
+        let mut failed = 0;
+        for question in questions {
+            let warmed = match self.service.warm_request(question, use_llm) {
+                Ok(request) => self.cache.warm(request).await,
+                Err(e) => Err(e),
+            };
+            match warmed {
+                Ok(entry) => entries.push(entry),
+                Err(e) => {
+                    warn!(error = %e, "Failed to warm a cached answer");
+                    failed += 1;
+                }
+            }
+        }
+
+        let cached = entries.len();
+        if let Some(path) = &self.snapshot_path {
+            self.cache.snapshot(entries).write(path)?;
+        }
+        info!(
+            questions = questions.len(),
+            cached, failed, "Answer cache warmed"
+        );
+        Ok(WarmReport {
+            questions: questions.len(),
+            cached,
+            failed,
+            snapshot_path: self.snapshot_path.clone(),
+        })
+    }
+
+    /// Restore the snapshot into the cache, returning the answers restored
+    /// (0 without a snapshot file).
+    pub async fn restore(&self) -> Result<usize, ServiceError> {
+        let Some(path) = &self.snapshot_path else {
+            return Ok(0);
+        };
+        let Some(snapshot) = CacheSnapshot::read(path)? else {
+            return Ok(0);
+        };
+        let restored = self.cache.restore(snapshot).await?;
+        info!(path = %path.display(), restored, "Restored answer cache snapshot");
+        Ok(restored)
+    }
+}
+
+/// Read a question corpus: one question per line, skipping blank lines and
+/// `#` comments.
+pub fn read_corpus(path: &Path) -> Result<Vec<String>, ServiceError> {
+    let text = std::fs::read_to_string(path).map_err(|e| {
+        ServiceError::Internal(format!(
+            "Failed to read question corpus {}: {}",
+            path.display(),
+            e
+        ))
+    })?;
+    Ok(text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(String::from)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memvid::{MemoryAnswerStore, MockSearcher, Searcher, DEFAULT_ANSWER_TTL};
+
+    fn warmer(snapshot: &Path) -> CacheWarmer {
+        let cache = Arc::new(CachingSearcher::new(
+            Arc::new(MockSearcher::new()),
+            Arc::new(MemoryAnswerStore::default()),
+            DEFAULT_ANSWER_TTL,
+        ));
+        let service = Arc::new(MemvidGrpcService::new(
+            Arc::clone(&cache) as Arc<dyn Searcher>
+        ));
+        CacheWarmer::new(service, cache).with_snapshot_path(snapshot)
+    }
+
+    #[tokio::test]
+    async fn test_warms_corpus_and_restores_snapshot() {
+        let dir = std::env::temp_dir().join(format!("memvid-warm-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let corpus = dir.join("corpus.txt");
+        std::fs::write(
+            &corpus,
+            "# common questions\nWhat are your skills?\n\nRust experience\n",
+        )
+        .unwrap();
+        let snapshot = dir.join("snapshot.json");
+
+        let corpus = read_corpus(&corpus).unwrap();
+        assert_eq!(corpus, ["What are your skills?", "Rust experience"]);
+        let report = warmer(&snapshot)
+            .with_corpus(corpus)
+            .warm(&[], false)
+            .await
+            .unwrap();
+        assert_eq!(report.questions, 2);
+        assert_eq!(report.cached, 2);
+        assert_eq!(report.snapshot_path.as_deref(), Some(snapshot.as_path()));
+
+        // A fresh replica restores every warmed answer
+        assert_eq!(warmer(&snapshot).restore().await.unwrap(), 2);
+        assert_eq!(
+            warmer(&dir.join("missing.json")).restore().await.unwrap(),
+            0
+        );
+        let err = warmer(&snapshot).warm(&[], false).await.unwrap_err();
+        assert!(matches!(err, ServiceError::InvalidRequest(_)));
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
@@ -0,0 +1,1041 @@
+//! Building a .mv2 index from resume sources.
+//!
+//! The service expects a particular index layout: frames per resume section
+//! (one per role, FAQ entry and failure story) tagged for filtering, and the
+//! candidate profile as JSON in the `__profile__` memory card. [`IngestPlan`]
+//! produces that layout from one of three sources:
+//!
+//! - Markdown with YAML frontmatter, the format of `data/example_resume.md`:
+//!   the frontmatter holds the profile fields, `## ` headings start sections
+//!   and `### ` headings split the experience, FAQ and failure sections into
+//!   one frame each
+//! - JSON or YAML documents holding the profile fields, whose experience
+//!   entries, skills and summary become frames, plus optional free-form
+//!   `sections` (`title`, `text`, `tags`)
+//!
+//! Either source may carry a `memory_cards` map (`entity => slot => value`)
+//! written next to the profile, and a `version` recorded on every frame as
+//! its `source_version`. [`write_index`] writes the plan with memvid-core's
+//! write API, with a lexical index; semantic vectors are not computed.
+//! [`append_frames`] and [`write_memory_cards`] add frames and memory cards
+//! to an existing index the same way, and [`delete_frames`] tombstones
+//! frames (see Admin/AppendFrames, SetState and DeleteFrames).
+//!
+//! A serving index holds shared locks on its file, so writes never open it
+//! for writing: they go to a copy next to it, which then replaces the file.
+//! The searchers keep the file they opened until the index is reloaded.
+
+use memvid_core::{MemoryCardBuilder, MemoryKind, Memvid, PutOptions};
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::error::ServiceError;
+use crate::grpc::{PROFILE_ENTITY, PROFILE_SLOT};
+use crate::memvid::{INGESTED_AT_KEY, SOURCE_VERSION_KEY};
+
+/// Frame metadata key holding the frame's section.
+pub const SECTION_KEY: &str = "section";
+
+/// Frame metadata key holding the frame's comma-separated keywords.
+pub const KEYWORDS_KEY: &str = "keywords";
+
+/// Sections of a markdown resume that are not indexed.
+const SKIPPED_SECTIONS: [&str; 2] = ["Contact & Links", "Metadata for Memvid Chunking"];
+
+/// Terms prepended to the summary frame. "summary" and "overview" are stop
+/// words of the lexical analyzer, so searches for them match nothing.
+const SUMMARY_KEYWORDS: &str = "**Keywords:** candidate, qualifications, strengths, weaknesses, \
+career-summary, background, profile-overview";
+
+/// How a known markdown section is indexed.
+struct SectionRule {
+    /// `## ` heading of the section
+    heading: &'static str,
+    /// Section recorded on its frames
+    section: &'static str,
+    /// Title of its single frame (None = the heading)
+    title: Option<&'static str>,
+    /// Prefix of the `### ` headings splitting it into frames, if split
+    split_on: Option<&'static str>,
+    /// Prefix of the split frames' titles
+    title_prefix: &'static str,
+    /// Tags added to its frames
+    tags: &'static [&'static str],
+}
+
+const SECTION_RULES: [SectionRule; 7] = [
+    SectionRule {
+        heading: "Professional Experience",
+        section: "experience",
+        title: None,
+        split_on: Some("### "),
+        title_prefix: "Experience: ",
+        tags: &["experience"],
+    },
+    SectionRule {
+        heading: "Frequently Asked Questions",
+        section: "faq",
+        title: None,
+        split_on: Some("### "),
+        title_prefix: "FAQ: ",
+        tags: &["faq", "question-answer"],
+    },
+    SectionRule {
+        heading: "Documented Failures & Lessons Learned",
+        section: "failures",
+        title: None,
+        split_on: Some("### Failure"),
+        title_prefix: "",
+        tags: &["failure", "lessons-learned"],
+    },
+    SectionRule {
+        heading: "Skills Assessment",
+        section: "skills",
+        title: None,
+        split_on: None,
+        title_prefix: "",
+        tags: &["skills", "assessment"],
+    },
+    SectionRule {
+        heading: "Fit Assessment Guidance",
+        section: "fit-assessment",
+        title: None,
+        split_on: None,
+        title_prefix: "",
+        tags: &["fit-assessment", "job-matching"],
+    },
+    SectionRule {
+        heading: "Leadership & Management",
+        section: "leadership",
+        title: None,
+        split_on: None,
+        title_prefix: "",
+        tags: &["leadership", "management", "soft-skills"],
+    },
+    SectionRule {
+        heading: "Summary",
+        section: "summary",
+        title: Some("Professional Summary"),
+        split_on: None,
+        title_prefix: "",
+        tags: &["summary", "overview"],
+    },
+];
+
+/// Format of a resume source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceFormat {
+    /// Markdown with YAML frontmatter
+    Markdown,
+    /// A JSON document
+    Json,
+    /// A YAML document
+    Yaml,
+}
+
+impl SourceFormat {
+    /// Parse a format name ("markdown"/"md", "json", "yaml"/"yml").
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "markdown" | "md" => Some(Self::Markdown),
+            "json" => Some(Self::Json),
+            "yaml" | "yml" => Some(Self::Yaml),
+            _ => None,
+        }
+    }
+
+    /// The format named by a file's extension.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        path.extension()
+            .and_then(|extension| extension.to_str())
+            .and_then(Self::parse)
+    }
+}
+
+/// A frame to write.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IngestFrame {
+    /// Frame title, also its label
+    pub title: String,
+    /// Section (e.g., "experience")
+    pub section: String,
+    /// Indexed text
+    pub text: String,
+    /// Tags, without repeats
+    pub tags: Vec<String>,
+    /// Keywords from a `**Keywords:**` line
+    pub keywords: Vec<String>,
+    /// Extra frame metadata, below the keys set when writing
+    pub metadata: BTreeMap<String, String>,
+}
+
+impl IngestFrame {
+    /// A frame with its keywords read and its tags deduplicated.
+    pub fn new(title: String, section: &str, text: String, tags: Vec<String>) -> Self {
+        let mut unique = Vec::with_capacity(tags.len());
+        for tag in tags {
+            if !tag.is_empty() && !unique.contains(&tag) {
+                unique.push(tag);
+            }
+        }
+        Self {
+            keywords: field_list(&text, "**Keywords:**"),
+            title,
+            section: section.to_string(),
+            text: text.trim().to_string(),
+            tags: unique,
+            metadata: BTreeMap::new(),
+        }
+    }
+
+    /// URI of the frame, unique within a resume.
+    pub fn uri(&self) -> String {
+        format!("mv2://resume/{}/{}", self.section, slug(&self.title))
+    }
+}
+
+/// A memory card to write.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IngestCard {
+    /// Entity (e.g., "__profile__")
+    pub entity: String,
+    /// Slot within the entity
+    pub slot: String,
+    /// Value
+    pub value: String,
+}
+
+/// Frames and memory cards built from a resume source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IngestPlan {
+    /// Frames, in source order
+    pub frames: Vec<IngestFrame>,
+    /// Memory cards, the profile first
+    pub cards: Vec<IngestCard>,
+    /// Source version recorded on every frame
+    pub version: Option<String>,
+}
+
+impl IngestPlan {
+    /// Build the plan for a source in `format`.
+    ///
+    /// # Errors
+    /// `InvalidRequest` for malformed sources or sources without content.
+    pub fn parse(text: &str, format: SourceFormat) -> Result<Self, ServiceError> {
+        let plan = match format {
+            SourceFormat::Markdown => Self::from_markdown(text)?,
+            SourceFormat::Json => Self::from_document(
+                serde_json::from_str(text).map_err(|e| invalid(format!("invalid JSON: {}", e)))?,
+            )?,
+            SourceFormat::Yaml => Self::from_document(
+                serde_yaml::from_str(text).map_err(|e| invalid(format!("invalid YAML: {}", e)))?,
+            )?,
+        };
+        if plan.frames.is_empty() {
+            return Err(invalid("the source has no content to index".to_string()));
+        }
+        Ok(plan)
+    }
+
+    /// Build the plan for a markdown resume with YAML frontmatter.
+    ///
+    /// # Errors
+    /// `InvalidRequest` for malformed frontmatter.
+    pub fn from_markdown(text: &str) -> Result<Self, ServiceError> {
+        let (frontmatter, body) = split_frontmatter(text);
+        let frontmatter = match frontmatter {
+            Some(yaml) => object(
+                serde_yaml::from_str(yaml)
+                    .map_err(|e| invalid(format!("invalid frontmatter: {}", e)))?,
+                "frontmatter",
+            )?,
+            None => Map::new(),
+        };
+        let global_tags = strings(entry(&frontmatter, "tags"));
+
+        let mut frames = Vec::new();
+        push_system_prompt(&mut frames, &frontmatter);
+        let mut experience = Vec::new();
+        let mut skills = json!({"strong": [], "moderate": [], "gaps": []});
+        for (heading, content) in sections(body, "## ") {
+            if SKIPPED_SECTIONS.contains(&heading) {
+                continue;
+            }
+            let rule = SECTION_RULES.iter().find(|rule| rule.heading == heading);
+            match rule {
+                Some(rule) if rule.split_on.is_some() => {
+                    let split_on = rule.split_on.unwrap_or("### ");
+                    for (title, text) in sections(content, split_on) {
+                        // "### Failure 1: ..." keeps its whole heading
+                        let title = if split_on == "### " {
+                            title.to_string()
+                        } else {
+                            format!("{}{}", &split_on[4..], title)
+                        };
+                        if rule.section == "experience" {
+                            experience.push(experience_entry(&title, text));
+                        }
+                        let mut tags = global_tags.clone();
+                        tags.extend(field_list(text, "**Tags:**"));
+                        tags.extend(field_list(text, "**Keywords:**").into_iter().filter(|_| {
+                            // FAQ keywords double as tags
+                            rule.section == "faq"
+                        }));
+                        tags.extend(rule.tags.iter().map(|tag| tag.to_string()));
+                        frames.push(IngestFrame::new(
+                            format!("{}{}", rule.title_prefix, title),
+                            rule.section,
+                            text.to_string(),
+                            tags,
+                        ));
+                    }
+                }
+                _ => {
+                    if heading == "Skills Assessment" {
+                        skills = skills_from_markdown(content);
+                    }
+                    let (section, title, extra_tags) = match rule {
+                        Some(rule) => (
+                            rule.section.to_string(),
+                            rule.title.unwrap_or(rule.heading).to_string(),
+                            rule.tags,
+                        ),
+                        None => (slug(heading), heading.to_string(), &[][..]),
+                    };
+                    let text = if section == "summary" {
+                        format!("{}\n\n{}", SUMMARY_KEYWORDS, content)
+                    } else {
+                        content.to_string()
+                    };
+                    let mut tags = global_tags.clone();
+                    tags.extend(extra_tags.iter().map(|tag| tag.to_string()));
+                    frames.push(IngestFrame::new(title, &section, text, tags));
+                }
+            }
+        }
+
+        let mut profile = Map::new();
+        for key in ["name", "title", "email", "linkedin", "location", "status"] {
+            profile.insert(
+                key.to_string(),
+                frontmatter.get(key).cloned().unwrap_or(json!("")),
+            );
+        }
+        for key in ["suggested_questions", "tags", "education"] {
+            profile.insert(
+                key.to_string(),
+                frontmatter.get(key).cloned().unwrap_or(json!([])),
+            );
+        }
+        profile.insert(
+            "system_prompt".to_string(),
+            frontmatter
+                .get("system_prompt")
+                .cloned()
+                .unwrap_or(json!("")),
+        );
+        profile.insert("experience".to_string(), Value::Array(experience));
+        profile.insert("skills".to_string(), skills);
+
+        Ok(Self {
+            frames,
+            cards: cards(Value::Object(profile), frontmatter.get("memory_cards"))?,
+            version: version(&frontmatter),
+        })
+    }
+
+    /// Build the plan for a structured (JSON or YAML) resume document.
+    ///
+    /// # Errors
+    /// `InvalidRequest` when the document or its sections are malformed.
+    pub fn from_document(document: Value) -> Result<Self, ServiceError> {
+        let mut document = object(document, "the resume document")?;
+        let sections = document.remove("sections");
+        let memory_cards = document.remove("memory_cards");
+        let global_tags = strings(entry(&document, "tags"));
+
+        let mut frames = Vec::new();
+        push_system_prompt(&mut frames, &document);
+        if let Some(summary) = document.get("summary").and_then(Value::as_str) {
+            let mut tags = global_tags.clone();
+            tags.extend(["summary".to_string(), "overview".to_string()]);
+            frames.push(IngestFrame::new(
+                "Professional Summary".to_string(),
+                "summary",
+                format!("{}\n\n{}", SUMMARY_KEYWORDS, summary.trim()),
+                tags,
+            ));
+        }
+        for entry in entry(&document, "experience")
+            .as_array()
+            .into_iter()
+            .flatten()
+        {
+            let Some(company) = entry["company"].as_str() else {
+                continue;
+            };
+            let mut tags = global_tags.clone();
+            tags.extend(strings(&entry["tags"]));
+            tags.push("experience".to_string());
+            frames.push(IngestFrame::new(
+                format!("Experience: {}", company),
+                "experience",
+                experience_text(entry),
+                tags,
+            ));
+        }
+        if let Some(skills) = entry(&document, "skills")
+            .as_object()
+            .filter(|s| !s.is_empty())
+        {
+            let text = skills
+                .iter()
+                .map(|(level, names)| format!("{}: {}", level, strings(names).join(", ")))
+                .collect::<Vec<_>>()
+                .join("\n");
+            let mut tags = global_tags.clone();
+            tags.extend(["skills".to_string(), "assessment".to_string()]);
+            frames.push(IngestFrame::new(
+                "Skills Assessment".to_string(),
+                "skills",
+                text,
+                tags,
+            ));
+        }
+        for (index, section) in sections
+            .as_ref()
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .enumerate()
+        {
+            let (Some(title), Some(text)) = (section["title"].as_str(), section["text"].as_str())
+            else {
+                return Err(invalid(format!(
+                    "sections[{}] needs a title and a text",
+                    index
+                )));
+            };
+            let mut tags = global_tags.clone();
+            tags.extend(strings(&section["tags"]));
+            let name = section["section"]
+                .as_str()
+                .map(str::to_string)
+                .unwrap_or_else(|| slug(title));
+            frames.push(IngestFrame::new(
+                title.to_string(),
+                &name,
+                text.to_string(),
+                tags,
+            ));
+        }
+
+        let version = version(&document);
+        Ok(Self {
+            frames,
+            cards: cards(Value::Object(document), memory_cards.as_ref())?,
+            version,
+        })
+    }
+}
+
+/// Write `plan` to a new index at `path` (blocking).
+///
+/// # Errors
+/// Failures creating or writing the index.
+pub fn write_index(plan: &IngestPlan, path: &Path) -> Result<(), ServiceError> {
+    let write_error = |e: memvid_core::MemvidError| {
+        ServiceError::Internal(format!("Failed to write index {}: {}", path.display(), e))
+    };
+    let mut memvid = Memvid::create(path).map_err(write_error)?;
+    memvid.enable_lex().map_err(write_error)?;
+
+    let ingested_at = chrono::Utc::now().timestamp();
+    for frame in &plan.frames {
+        put_frame(&mut memvid, frame, ingested_at, plan.version.as_deref()).map_err(write_error)?;
+    }
+
+    put_cards(&mut memvid, &plan.cards, write_error)?;
+    memvid.commit().map_err(write_error)
+}
+
+/// Write `cards` to the existing index at `path` (blocking). A card
+/// supersedes the one already held for its entity and slot.
+///
+/// # Errors
+/// Invalid cards; failures copying, opening or writing the index.
+pub fn write_memory_cards(path: &Path, cards: &[IngestCard]) -> Result<(), ServiceError> {
+    let write_error = |e: memvid_core::MemvidError| {
+        ServiceError::Internal(format!(
+            "Failed to write memory cards to {}: {}",
+            path.display(),
+            e
+        ))
+    };
+    rewrite_index(path, write_error, |memvid| {
+        put_cards(memvid, cards, write_error)
+    })
+}
+
+/// Put `cards`, the profile as a profile card and the others as facts.
+/// Write failures are reported through `write_error`.
+fn put_cards(
+    memvid: &mut Memvid,
+    cards: &[IngestCard],
+    write_error: impl Fn(memvid_core::MemvidError) -> ServiceError,
+) -> Result<(), ServiceError> {
+    let cards = cards
+        .iter()
+        .map(|card| {
+            let kind = if card.entity == PROFILE_ENTITY {
+                MemoryKind::Profile
+            } else {
+                MemoryKind::Fact
+            };
+            MemoryCardBuilder::new()
+                .kind(kind)
+                .entity(&card.entity)
+                .slot(&card.slot)
+                .value(&card.value)
+                // Cards are written as given rather than extracted from a
+                // frame; memvid-core still requires a source and engine
+                .source(0, None)
+                .engine(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"))
+                .build(0)
+                .map_err(|e| {
+                    invalid(format!(
+                        "Invalid memory card {}.{}: {}",
+                        card.entity, card.slot, e
+                    ))
+                })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    memvid
+        .put_memory_cards(cards)
+        .map(|_| ())
+        .map_err(write_error)
+}
+
+/// Append `frames` to the existing index at `path` (blocking), returning
+/// the IDs they were written with.
+///
+/// # Errors
+/// Failures copying, opening or writing the index.
+pub fn append_frames(
+    path: &Path,
+    frames: &[IngestFrame],
+    version: Option<&str>,
+) -> Result<Vec<u64>, ServiceError> {
+    let write_error = |e: memvid_core::MemvidError| {
+        ServiceError::Internal(format!("Failed to append to {}: {}", path.display(), e))
+    };
+    let ingested_at = chrono::Utc::now().timestamp();
+    rewrite_index(path, write_error, |memvid| {
+        frames
+            .iter()
+            .map(|frame| put_frame(memvid, frame, ingested_at, version))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(write_error)
+    })
+}
+
+/// Apply `write` to a copy of the index at `path` and move the committed
+/// copy over it (blocking). On failure the copy is removed and the index
+/// is left as it was.
+fn rewrite_index<T>(
+    path: &Path,
+    write_error: impl Fn(memvid_core::MemvidError) -> ServiceError,
+    write: impl FnOnce(&mut Memvid) -> Result<T, ServiceError>,
+) -> Result<T, ServiceError> {
+    let copy = PathBuf::from(format!("{}.tmp", path.display()));
+    let io_error = |e: std::io::Error| {
+        ServiceError::Internal(format!("Failed to rewrite {}: {}", path.display(), e))
+    };
+    std::fs::copy(path, &copy).map_err(io_error)?;
+    let written = Memvid::open(&copy)
+        .map_err(&write_error)
+        .and_then(|mut memvid| {
+            let output = write(&mut memvid)?;
+            memvid.commit().map_err(&write_error)?;
+            Ok(output)
+        })
+        .and_then(|output| {
+            std::fs::rename(&copy, path)
+                .map(|()| output)
+                .map_err(io_error)
+        });
+    if written.is_err() {
+        let _ = std::fs::remove_file(&copy);
+    }
+    written
+}
+
+/// Tombstone `frame_ids` in the existing index at `path` (blocking). The
+/// frames stay in the file but are no longer retrieved.
+///
+/// # Errors
+/// Failures copying, opening or writing the index.
+pub fn delete_frames(path: &Path, frame_ids: &[u64]) -> Result<(), ServiceError> {
+    let write_error = |e: memvid_core::MemvidError| {
+        ServiceError::Internal(format!(
+            "Failed to delete frames from {}: {}",
+            path.display(),
+            e
+        ))
+    };
+    rewrite_index(path, write_error, |memvid| {
+        for &frame_id in frame_ids {
+            memvid.delete_frame(frame_id).map_err(write_error)?;
+        }
+        Ok(())
+    })
+}
+
+/// Put `frame` with its section, keywords, ingestion time and `version`
+/// in its metadata.
+fn put_frame(
+    memvid: &mut Memvid,
+    frame: &IngestFrame,
+    ingested_at: i64,
+    version: Option<&str>,
+) -> Result<u64, memvid_core::MemvidError> {
+    let mut metadata = frame.metadata.clone();
+    metadata.insert(SECTION_KEY.to_string(), frame.section.clone());
+    metadata.insert(INGESTED_AT_KEY.to_string(), ingested_at.to_string());
+    if !frame.keywords.is_empty() {
+        metadata.insert(KEYWORDS_KEY.to_string(), frame.keywords.join(","));
+    }
+    if let Some(version) = version {
+        metadata.insert(SOURCE_VERSION_KEY.to_string(), version.to_string());
+    }
+    let options = PutOptions {
+        timestamp: Some(ingested_at),
+        uri: Some(frame.uri()),
+        title: Some(frame.title.clone()),
+        tags: frame.tags.clone(),
+        labels: vec![frame.title.clone()],
+        extra_metadata: metadata,
+        ..Default::default()
+    };
+    memvid.put_bytes_with_options(frame.text.as_bytes(), options)
+}
+
+fn invalid(message: String) -> ServiceError {
+    ServiceError::InvalidRequest(message)
+}
+
+/// `value` as an object, or an error naming `what`.
+fn object(value: Value, what: &str) -> Result<Map<String, Value>, ServiceError> {
+    match value {
+        Value::Object(map) => Ok(map),
+        Value::Null => Ok(Map::new()),
+        _ => Err(invalid(format!("{} must be a mapping", what))),
+    }
+}
+
+/// The value of `key` (Null when absent).
+fn entry<'a>(fields: &'a Map<String, Value>, key: &str) -> &'a Value {
+    fields.get(key).unwrap_or(&Value::Null)
+}
+
+/// String items of an array value (or a single string), trimmed.
+fn strings(value: &Value) -> Vec<String> {
+    match value {
+        Value::String(s) => vec![s.trim().to_string()],
+        Value::Array(items) => items
+            .iter()
+            .filter_map(Value::as_str)
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// The `version` field as a string, when set.
+fn version(fields: &Map<String, Value>) -> Option<String> {
+    match fields.get("version")? {
+        Value::String(version) => Some(version.trim().to_string()),
+        Value::Number(version) => Some(version.to_string()),
+        _ => None,
+    }
+    .filter(|version| !version.is_empty())
+}
+
+/// Add the system prompt frame when the fields carry one.
+fn push_system_prompt(frames: &mut Vec<IngestFrame>, fields: &Map<String, Value>) {
+    if let Some(prompt) = fields
+        .get("system_prompt")
+        .and_then(Value::as_str)
+        .filter(|prompt| !prompt.trim().is_empty())
+    {
+        frames.push(IngestFrame::new(
+            "AI System Prompt".to_string(),
+            "system",
+            prompt.to_string(),
+            vec!["system-prompt".to_string(), "ai-instructions".to_string()],
+        ));
+    }
+}
+
+/// The profile card followed by the `memory_cards` map's cards.
+fn cards(profile: Value, memory_cards: Option<&Value>) -> Result<Vec<IngestCard>, ServiceError> {
+    let profile = serde_json::to_string_pretty(&profile)
+        .map_err(|e| ServiceError::Internal(format!("Failed to serialize the profile: {}", e)))?;
+    let mut cards = vec![IngestCard {
+        entity: PROFILE_ENTITY.to_string(),
+        slot: PROFILE_SLOT.to_string(),
+        value: profile,
+    }];
+    let Some(memory_cards) = memory_cards else {
+        return Ok(cards);
+    };
+    let memory_cards = object(memory_cards.clone(), "memory_cards")?;
+    for (entity, slots) in memory_cards {
+        if entity == PROFILE_ENTITY {
+            return Err(invalid(format!(
+                "memory_cards may not set {}, which holds the profile",
+                PROFILE_ENTITY
+            )));
+        }
+        for (slot, value) in object(slots, "memory_cards entries")? {
+            let value = match value {
+                Value::String(value) => value,
+                other => other.to_string(),
+            };
+            cards.push(IngestCard {
+                entity: entity.clone(),
+                slot,
+                value,
+            });
+        }
+    }
+    Ok(cards)
+}
+
+/// The YAML frontmatter between leading `---` lines, and the rest.
+fn split_frontmatter(text: &str) -> (Option<&str>, &str) {
+    let Some(rest) = text.strip_prefix("---") else {
+        return (None, text);
+    };
+    match rest.find("\n---") {
+        Some(end) => {
+            let body = &rest[end + 4..];
+            (Some(&rest[..end]), body.strip_prefix('\n').unwrap_or(body))
+        }
+        None => (None, text),
+    }
+}
+
+/// Sections of `text` started by lines beginning with `prefix`: the rest of
+/// the heading line and the trimmed content up to the next one. Text before
+/// the first heading is dropped.
+fn sections<'a>(text: &'a str, prefix: &str) -> Vec<(&'a str, &'a str)> {
+    let mut sections = Vec::new();
+    let mut current: Option<(&str, usize)> = None;
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        if let Some(heading) = line.strip_prefix(prefix) {
+            if let Some((title, start)) = current {
+                sections.push((title, text[start..offset].trim()));
+            }
+            current = Some((heading.trim(), offset + line.len()));
+        }
+        offset += line.len();
+    }
+    if let Some((title, start)) = current {
+        sections.push((title, text[start..].trim()));
+    }
+    sections
+}
+
+/// Comma-separated items of the first line starting with `field`.
+fn field_list(text: &str, field: &str) -> Vec<String> {
+    text.lines()
+        .find_map(|line| line.strip_prefix(field))
+        .map(|items| {
+            items
+                .split(',')
+                .map(|item| item.trim().to_string())
+                .filter(|item| !item.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// The value of the first line starting with `field`.
+fn field(text: &str, field: &str) -> String {
+    text.lines()
+        .find_map(|line| line.strip_prefix(field))
+        .map(|value| value.trim().to_string())
+        .unwrap_or_default()
+}
+
+/// A profile `experience[]` entry from a markdown role.
+fn experience_entry(company: &str, text: &str) -> Value {
+    let highlights: Vec<&str> = text
+        .lines()
+        .filter_map(|line| line.strip_prefix("- "))
+        .filter(|line| line.starts_with("**") && line.contains(':'))
+        .map(str::trim)
+        .collect();
+    json!({
+        "company": company,
+        "role": field(text, "**Role:**"),
+        "period": field(text, "**Period:**"),
+        "location": field(text, "**Location:**"),
+        "tags": field_list(text, "**Tags:**"),
+        "highlights": highlights,
+    })
+}
+
+/// Frame text of a structured `experience[]` entry.
+fn experience_text(entry: &Value) -> String {
+    let mut lines = Vec::new();
+    for (label, key) in [
+        ("Role", "role"),
+        ("Period", "period"),
+        ("Location", "location"),
+    ] {
+        if let Some(value) = entry[key].as_str().filter(|value| !value.is_empty()) {
+            lines.push(format!("**{}:** {}", label, value));
+        }
+    }
+    if let Some(description) = entry["description"].as_str() {
+        lines.push(String::new());
+        lines.push(description.trim().to_string());
+    }
+    let highlights = strings(&entry["highlights"]);
+    if !highlights.is_empty() {
+        lines.push(String::new());
+        lines.extend(
+            highlights
+                .iter()
+                .map(|highlight| format!("- {}", highlight)),
+        );
+    }
+    lines.join("\n")
+}
+
+/// Skill names by level from the `### Strong`/`### Moderate`/`### Gaps`
+/// subsections of the skills section (`- **Name:** description` bullets).
+fn skills_from_markdown(content: &str) -> Value {
+    let mut skills: BTreeMap<&str, Vec<String>> =
+        BTreeMap::from([("strong", vec![]), ("moderate", vec![]), ("gaps", vec![])]);
+    let mut level = None;
+    for line in content.lines() {
+        if let Some(heading) = line.strip_prefix("### ") {
+            level = ["Strong", "Moderate", "Gaps"]
+                .into_iter()
+                .find(|prefix| heading.starts_with(prefix))
+                .map(|prefix| match prefix {
+                    "Strong" => "strong",
+                    "Moderate" => "moderate",
+                    _ => "gaps",
+                });
+        } else if let (Some(level), Some(bullet)) = (level, line.strip_prefix("- **")) {
+            if let Some((name, _)) = bullet.split_once(":**") {
+                if let Some(names) = skills.get_mut(level) {
+                    names.push(name.trim().to_string());
+                }
+            }
+        }
+    }
+    json!(skills)
+}
+
+/// Lowercase words of `text` joined by dashes ("Leadership & Management" =>
+/// "leadership-and-management").
+pub fn slug(text: &str) -> String {
+    text.replace('&', " and ")
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RESUME: &str = "---
+# Profile Metadata
+name: Jane Chen
+title: VP of Platform Engineering
+status: Open to VP roles
+version: v12
+system_prompt: |
+  You are helping hiring managers evaluate Jane Chen.
+suggested_questions:
+  - \"What programming languages does she know?\"
+tags:
+  - platform-engineering
+memory_cards:
+  availability:
+    notice: 4 weeks
+---
+
+## Summary
+
+Jane builds developer platforms.
+
+## Professional Experience
+
+### Acme Corp
+
+**Role:** VP of Platform Engineering
+**Period:** 2020-present
+**Tags:** kubernetes, leadership
+
+- **Deployments:** 50x faster deploys
+
+### Globex
+
+**Role:** Staff Engineer
+
+## Frequently Asked Questions
+
+### What languages does she know?
+
+**Keywords:** python, go
+
+Python and Go.
+
+## Skills Assessment
+
+### Strong
+
+- **Python:** 10 years
+- **Kubernetes:** daily
+
+### Gaps
+
+- **Mobile:** none
+
+## Contact & Links
+
+jane@example.com
+
+## Side Projects
+
+An open-source operator.
+";
+
+    fn titles(plan: &IngestPlan) -> Vec<&str> {
+        plan.frames.iter().map(|f| f.title.as_str()).collect()
+    }
+
+    #[test]
+    fn test_markdown_sections_become_frames() {
+        let plan = IngestPlan::parse(RESUME, SourceFormat::Markdown).unwrap();
+        assert_eq!(
+            titles(&plan),
+            [
+                "AI System Prompt",
+                "Professional Summary",
+                "Experience: Acme Corp",
+                "Experience: Globex",
+                "FAQ: What languages does she know?",
+                "Skills Assessment",
+                "Side Projects",
+            ]
+        );
+        let acme = &plan.frames[2];
+        assert_eq!(
+            acme.tags,
+            [
+                "platform-engineering",
+                "kubernetes",
+                "leadership",
+                "experience"
+            ]
+        );
+        assert_eq!(acme.uri(), "mv2://resume/experience/experience-acme-corp");
+        assert!(plan.frames[1].text.starts_with("**Keywords:** candidate"));
+        assert_eq!(plan.frames[4].keywords, ["python", "go"]);
+        assert!(plan.frames[4].tags.contains(&"faq".to_string()));
+        assert_eq!(plan.frames[6].section, "side-projects");
+        assert_eq!(plan.version.as_deref(), Some("v12"));
+    }
+
+    #[test]
+    fn test_markdown_profile_card() {
+        let plan = IngestPlan::parse(RESUME, SourceFormat::Markdown).unwrap();
+        assert_eq!(plan.cards[0].entity, PROFILE_ENTITY);
+        assert_eq!(plan.cards[0].slot, PROFILE_SLOT);
+        let profile: Value = serde_json::from_str(&plan.cards[0].value).unwrap();
+        assert_eq!(profile["name"], "Jane Chen");
+        assert_eq!(profile["experience"][0]["company"], "Acme Corp");
+        assert_eq!(profile["experience"][0]["period"], "2020-present");
+        assert_eq!(
+            profile["experience"][0]["highlights"][0],
+            "**Deployments:** 50x faster deploys"
+        );
+        assert_eq!(profile["skills"]["strong"], json!(["Python", "Kubernetes"]));
+        assert_eq!(profile["skills"]["gaps"], json!(["Mobile"]));
+
+        assert_eq!(
+            plan.cards[1],
+            IngestCard {
+                entity: "availability".to_string(),
+                slot: "notice".to_string(),
+                value: "4 weeks".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_structured_documents() {
+        let yaml = "
+name: Jane Chen
+tags: [platform]
+summary: Builds developer platforms.
+experience:
+  - company: Acme Corp
+    role: VP
+    highlights: [Grew the team to 15]
+skills:
+  strong: [Rust]
+sections:
+  - title: Publications
+    text: A paper on schedulers.
+    tags: [writing]
+";
+        let plan = IngestPlan::parse(yaml, SourceFormat::Yaml).unwrap();
+        assert_eq!(
+            titles(&plan),
+            [
+                "Professional Summary",
+                "Experience: Acme Corp",
+                "Skills Assessment",
+                "Publications",
+            ]
+        );
+        assert_eq!(plan.frames[1].text, "**Role:** VP\n\n- Grew the team to 15");
+        assert_eq!(plan.frames[3].tags, ["platform", "writing"]);
+        let profile: Value = serde_json::from_str(&plan.cards[0].value).unwrap();
+        assert_eq!(profile["experience"][0]["role"], "VP");
+        assert!(profile.get("sections").is_none());
+
+        let json = r#"{"name": "Jane", "sections": [{"title": "About"}]}"#;
+        assert!(IngestPlan::parse(json, SourceFormat::Json).is_err());
+        assert!(IngestPlan::parse(r#"{"name": "Jane"}"#, SourceFormat::Json).is_err());
+        assert!(IngestPlan::parse("[1, 2]", SourceFormat::Json).is_err());
+    }
+
+    #[test]
+    fn test_source_format_and_slug() {
+        assert_eq!(
+            SourceFormat::from_path(Path::new("resume.MD")),
+            Some(SourceFormat::Markdown)
+        );
+        assert_eq!(
+            SourceFormat::from_path(Path::new("resume.yml")),
+            Some(SourceFormat::Yaml)
+        );
+        assert_eq!(SourceFormat::from_path(Path::new("resume.txt")), None);
+        assert_eq!(slug("Leadership & Management"), "leadership-and-management");
+    }
+}
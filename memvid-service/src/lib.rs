@@ -3,11 +3,30 @@
 //! This library exposes the core modules for integration testing while
 //! keeping the actual binary entry point in main.rs.
 
+pub mod alert;
+pub mod capabilities;
+pub mod cli;
 pub mod config;
+pub mod crash;
+pub mod debug;
+pub mod egress;
 pub mod error;
+pub mod error_details;
 pub mod grpc;
+pub mod ingest;
+pub mod lifecycle;
+pub mod llm;
+pub mod log_level;
 pub mod memvid;
 pub mod metrics;
+pub mod random;
+pub mod readiness;
+pub mod report;
+pub mod runtime_config;
+pub mod schedule;
+pub mod session;
+pub mod supervisor;
+pub mod tls;
 
 // Include generated proto code from build script
 pub mod generated {
@@ -15,5 +34,8 @@ pub mod generated {
         pub mod v1 {
             include!(concat!(env!("OUT_DIR"), "/memvid.v1.rs"));
         }
+        pub mod v2 {
+            include!(concat!(env!("OUT_DIR"), "/memvid.v2.rs"));
+        }
     }
 }
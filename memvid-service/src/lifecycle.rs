@@ -0,0 +1,354 @@
+//! Kubernetes-aware lifecycle: pod labels and graceful drain.
+//!
+//! [`PodInfo`] reads the pod metadata exposed through the downward API as
+//! environment variables (`POD_NAME`, `POD_NAMESPACE`, `NODE_NAME`,
+//! `POD_IP`). When present, every JSON log line carries it under
+//! `kubernetes` and every metric carries `pod`, `namespace` and `node`
+//! labels.
+//!
+//! [`Drain`] coordinates shutdown. SIGTERM, Ctrl-C or a request to
+//! `/quitquitquit` (a preStop-compatible endpoint on the metrics listener)
+//! start a drain: health checks report `NOT_SERVING` at once, so load
+//! balancers stop routing here; after the drain delay the gRPC server stops
+//! accepting requests and finishes those in flight. Whatever is still
+//! running shortly before `terminationGracePeriodSeconds` runs out is
+//! abandoned, so the process exits on its own before the kubelet kills it.
+//! The metrics server stops last, so scrapes cover the whole drain.
+
+use axum::extract::State;
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::Serialize;
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Notify;
+use tracing::{info, warn};
+use tracing_subscriber::fmt::MakeWriter;
+
+/// Time left for the process to exit before the grace period runs out.
+pub const SHUTDOWN_MARGIN: Duration = Duration::from_secs(2);
+
+/// Pod metadata from the Kubernetes downward API.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PodInfo {
+    /// Pod name (`metadata.name`)
+    pub pod: String,
+    /// Namespace (`metadata.namespace`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+    /// Node the pod runs on (`spec.nodeName`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub node: Option<String>,
+    /// Pod IP (`status.podIP`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ip: Option<String>,
+}
+
+impl PodInfo {
+    /// Read pod metadata from the environment; None outside Kubernetes.
+    pub fn from_env() -> Option<Self> {
+        Self::from_lookup(|name| std::env::var(name).ok())
+    }
+
+    /// Read pod metadata with `lookup`; None without a pod name.
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Option<Self> {
+        let var = |name: &str| lookup(name).filter(|v| !v.trim().is_empty());
+        Some(Self {
+            pod: var("POD_NAME")?,
+            namespace: var("POD_NAMESPACE"),
+            node: var("NODE_NAME"),
+            ip: var("POD_IP"),
+        })
+    }
+
+    /// Labels added to every metric.
+    pub fn metric_labels(&self) -> Vec<(String, String)> {
+        let mut labels = vec![("pod".to_string(), self.pod.clone())];
+        if let Some(namespace) = &self.namespace {
+            labels.push(("namespace".to_string(), namespace.clone()));
+        }
+        if let Some(node) = &self.node {
+            labels.push(("node".to_string(), node.clone()));
+        }
+        labels
+    }
+}
+
+/// Log writer adding a `kubernetes` object with pod metadata to each JSON
+/// log line written to stdout.
+#[derive(Debug, Clone)]
+pub struct PodLogWriter {
+    field: Arc<[u8]>,
+}
+
+impl PodLogWriter {
+    /// Label log lines with `pod`.
+    pub fn new(pod: &PodInfo) -> Self {
+        let json = serde_json::to_string(pod).unwrap_or_else(|_| "{}".to_string());
+        Self {
+            field: format!(",\"kubernetes\":{}", json).into_bytes().into(),
+        }
+    }
+
+    /// Write `line`, splicing the pod field in before its closing brace.
+    fn write_line(&self, out: &mut impl Write, line: &[u8]) -> std::io::Result<()> {
+        let end = line.iter().rposition(|b| *b == b'}');
+        match end {
+            Some(end) if line.first() == Some(&b'{') => {
+                out.write_all(&line[..end])?;
+                out.write_all(&self.field)?;
+                out.write_all(&line[end..])
+            }
+            _ => out.write_all(line),
+        }
+    }
+}
+
+impl<'a> MakeWriter<'a> for PodLogWriter {
+    type Writer = PodLogLine;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        PodLogLine {
+            writer: self.clone(),
+        }
+    }
+}
+
+/// Writer for one log line (see [`PodLogWriter`]).
+pub struct PodLogLine {
+    writer: PodLogWriter,
+}
+
+impl Write for PodLogLine {
+    /// The formatter hands over each event as one complete buffer.
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.writer.write_line(&mut std::io::stdout().lock(), buf)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        std::io::stdout().flush()
+    }
+}
+
+/// Shutdown coordination shared by the signal handler, the `/quitquitquit`
+/// endpoint, health checks and the gRPC server.
+#[derive(Debug)]
+pub struct Drain {
+    draining: AtomicBool,
+    started: Notify,
+    delay: Duration,
+    grace_period: Duration,
+}
+
+impl Drain {
+    /// Stop serving `delay` after a drain starts and give up on in-flight
+    /// requests shortly before `grace_period` ends.
+    pub fn new(delay: Duration, grace_period: Duration) -> Self {
+        Self {
+            draining: AtomicBool::new(false),
+            started: Notify::new(),
+            delay,
+            grace_period,
+        }
+    }
+
+    /// Start draining; later calls have no effect.
+    pub fn start(&self, reason: &str) {
+        if self.draining.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        info!(
+            reason,
+            delay_secs = self.delay.as_secs(),
+            grace_period_secs = self.grace_period.as_secs(),
+            "Draining: reporting NOT_SERVING before shutdown"
+        );
+        self.started.notify_waiters();
+    }
+
+    /// Whether a drain has started.
+    pub fn is_draining(&self) -> bool {
+        self.draining.load(Ordering::Acquire)
+    }
+
+    /// Complete once a drain has started.
+    pub async fn started(&self) {
+        let notified = self.started.notified();
+        if self.is_draining() {
+            return;
+        }
+        notified.await;
+    }
+
+    /// Complete when the server should stop accepting requests.
+    pub async fn stop_accepting(&self) {
+        self.started().await;
+        tokio::time::sleep(self.delay).await;
+        info!("Drain delay over, finishing in-flight requests");
+    }
+
+    /// Complete when in-flight requests must be abandoned.
+    pub async fn deadline(&self) {
+        self.started().await;
+        tokio::time::sleep(self.grace_period.saturating_sub(SHUTDOWN_MARGIN)).await;
+        warn!("Grace period ending, exiting with requests in flight");
+    }
+}
+
+/// Start a drain on SIGTERM or Ctrl-C.
+pub async fn drain_on_signal(drain: Arc<Drain>) {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::terminate()) {
+            Ok(mut terminate) => {
+                tokio::select! {
+                    _ = terminate.recv() => drain.start("SIGTERM"),
+                    _ = tokio::signal::ctrl_c() => drain.start("interrupt"),
+                }
+                return;
+            }
+            Err(e) => warn!(error = %e, "Failed to listen for SIGTERM"),
+        }
+    }
+    if tokio::signal::ctrl_c().await.is_ok() {
+        drain.start("interrupt");
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct QuitResponse {
+    /// Always true: the drain has started
+    draining: bool,
+    /// Seconds until the server stops accepting requests
+    drain_delay_secs: u64,
+}
+
+/// The `/quitquitquit` route (GET for preStop `httpGet` hooks, or POST)
+/// when `enabled`; an empty router otherwise.
+pub fn lifecycle_router(enabled: bool, drain: Arc<Drain>) -> Router {
+    if !enabled {
+        return Router::new();
+    }
+    Router::new()
+        .route("/quitquitquit", get(quit).post(quit))
+        .with_state(drain)
+}
+
+async fn quit(State(drain): State<Arc<Drain>>) -> Json<QuitResponse> {
+    drain.start("quitquitquit");
+    Json(QuitResponse {
+        draining: true,
+        drain_delay_secs: drain.delay.as_secs(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use tower::ServiceExt;
+
+    #[test]
+    fn test_pod_info_from_downward_api() {
+        let env = |name: &str| match name {
+            "POD_NAME" => Some("memvid-7d9f-abcde".to_string()),
+            "POD_NAMESPACE" => Some("resume".to_string()),
+            "NODE_NAME" => Some(" ".to_string()),
+            _ => None,
+        };
+        let pod = PodInfo::from_lookup(env).unwrap();
+        assert_eq!(pod.namespace.as_deref(), Some("resume"));
+        assert_eq!(pod.node, None);
+        assert_eq!(
+            pod.metric_labels(),
+            vec![
+                ("pod".to_string(), "memvid-7d9f-abcde".to_string()),
+                ("namespace".to_string(), "resume".to_string()),
+            ]
+        );
+
+        assert_eq!(PodInfo::from_lookup(|_| None), None);
+    }
+
+    #[test]
+    fn test_log_lines_carry_pod_metadata() {
+        let writer = PodLogWriter::new(&PodInfo {
+            pod: "memvid-0".to_string(),
+            namespace: None,
+            node: Some("node-a".to_string()),
+            ip: None,
+        });
+        let mut out = Vec::new();
+        writer
+            .write_line(&mut out, b"{\"level\":\"INFO\",\"fields\":{}}\n")
+            .unwrap();
+
+        let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(json["level"], "INFO");
+        assert_eq!(json["kubernetes"]["pod"], "memvid-0");
+        assert_eq!(json["kubernetes"]["node"], "node-a");
+        assert!(out.ends_with(b"}\n"));
+
+        let mut plain = Vec::new();
+        writer.write_line(&mut plain, b"not json\n").unwrap();
+        assert_eq!(plain, b"not json\n");
+    }
+
+    #[tokio::test]
+    async fn test_drain_stops_after_delay_within_grace_period() {
+        let drain = Arc::new(Drain::new(
+            Duration::from_millis(20),
+            SHUTDOWN_MARGIN + Duration::from_millis(50),
+        ));
+        let stop = tokio::spawn({
+            let drain = Arc::clone(&drain);
+            async move { drain.stop_accepting().await }
+        });
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        assert!(!stop.is_finished());
+        assert!(!drain.is_draining());
+
+        let started = tokio::time::Instant::now();
+        drain.start("test");
+        drain.start("again");
+        assert!(drain.is_draining());
+        stop.await.unwrap();
+        assert!(started.elapsed() >= Duration::from_millis(20));
+
+        drain.deadline().await;
+        assert!(started.elapsed() >= Duration::from_millis(50));
+    }
+
+    #[tokio::test]
+    async fn test_quitquitquit_starts_drain() {
+        let drain = Arc::new(Drain::new(Duration::from_secs(5), Duration::from_secs(30)));
+        let response = lifecycle_router(true, Arc::clone(&drain))
+            .oneshot(
+                Request::builder()
+                    .uri("/quitquitquit")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(drain.is_draining());
+
+        let response = lifecycle_router(false, drain)
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/quitquitquit")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,747 @@
+//! LLM answer synthesis through a chat API.
+//!
+//! [`LlmClient`] sends the question and the retrieved evidence to a chat
+//! model and returns its answer. `LLM_PROVIDER` picks the wire format:
+//!
+//! - `openai` (default): `<LLM_BASE_URL>/chat/completions` on any
+//!   OpenAI-compatible server (OpenAI, vLLM, LiteLLM, ...), with
+//!   `LLM_API_KEY` sent as a bearer token when set.
+//! - `ollama`: `<LLM_BASE_URL>/api/chat` on a local Ollama instance
+//!   (default `http://localhost:11434`), so self-hosted deployments run
+//!   fully offline.
+//!
+//! Both backends share the per-attempt timeout, retries with exponential
+//! backoff on connection errors, timeouts, 429 and 5xx responses, and the
+//! `memvid_llm_*` metrics. [`LlmClient::synthesize_stream`] returns the
+//! answer piece by piece as the model writes it (server-sent events for
+//! OpenAI, newline-delimited JSON for Ollama), with the timeout applied to
+//! each wait for the next piece. Evidence is numbered from 1 in retrieval order
+//! and the model is told to cite it with `[n]` markers, which the citation
+//! checks then verify. Requests go through the installed egress settings.
+
+use http_body_util::{BodyExt, Full};
+use hyper::body::{Bytes, Incoming};
+use hyper::StatusCode;
+use hyper_rustls::HttpsConnector;
+use hyper_util::client::legacy::Client;
+use hyper_util::rt::TokioExecutor;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+use crate::egress::{self, EgressConnector};
+use crate::memvid::SearchResult;
+use crate::metrics;
+
+/// Default time one chat request may take.
+pub const DEFAULT_LLM_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Default retries of a failed chat request.
+pub const DEFAULT_LLM_MAX_RETRIES: u32 = 2;
+
+/// Base URL of a local Ollama instance.
+pub const DEFAULT_OLLAMA_URL: &str = "http://localhost:11434";
+
+/// Delay before the first retry, doubled for each further one.
+const RETRY_BACKOFF: Duration = Duration::from_millis(250);
+
+/// Instructions sent ahead of every synthesis request.
+const SYSTEM_PROMPT: &str = "You answer questions about a candidate's resume. \
+Use only the numbered context passages. Cite the passages each sentence relies on \
+with markers such as [1] or [2, 3]. If the context does not answer the question, \
+say so instead of guessing. Be concise.";
+
+/// Wire format of the chat API.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LlmProvider {
+    /// OpenAI-compatible `/chat/completions`.
+    #[default]
+    OpenAi,
+    /// Ollama's native `/api/chat`.
+    Ollama,
+}
+
+impl LlmProvider {
+    /// Parse "openai" or "ollama".
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "openai" => Ok(Self::OpenAi),
+            "ollama" => Ok(Self::Ollama),
+            other => Err(format!("expected openai or ollama, got '{}'", other)),
+        }
+    }
+
+    /// Name used in logs and metric labels.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::OpenAi => "openai",
+            Self::Ollama => "ollama",
+        }
+    }
+
+    /// Chat endpoint below `base_url`, unless `base_url` already names it.
+    fn endpoint(self, base_url: &str) -> String {
+        let path = match self {
+            Self::OpenAi => "/chat/completions",
+            Self::Ollama => "/api/chat",
+        };
+        let base = base_url.trim_end_matches('/');
+        if base.ends_with(path) {
+            base.to_string()
+        } else {
+            format!("{}{}", base, path)
+        }
+    }
+
+    /// Request body asking `model` to answer `messages`, streamed or whole.
+    fn request_body(
+        self,
+        model: &str,
+        messages: &[ChatMessage],
+        stream: bool,
+    ) -> serde_json::Value {
+        match self {
+            Self::OpenAi => serde_json::json!({
+                "model": model,
+                "messages": messages,
+                "temperature": 0.0,
+                "stream": stream,
+            }),
+            Self::Ollama => serde_json::json!({
+                "model": model,
+                "messages": messages,
+                "stream": stream,
+                "options": {"temperature": 0.0},
+            }),
+        }
+    }
+
+    /// Meaning of one line of a streamed response.
+    fn parse_stream_line(self, line: &str) -> Result<StreamLine, String> {
+        let invalid = |e: serde_json::Error| format!("invalid LLM stream: {}", e);
+        let delta = |content: Option<String>| {
+            content
+                .filter(|content| !content.is_empty())
+                .map_or(StreamLine::Skip, StreamLine::Delta)
+        };
+        match self {
+            // Server-sent events: "data: {...}", ending with "data: [DONE]"
+            Self::OpenAi => match line.strip_prefix("data:").map(str::trim) {
+                Some("[DONE]") => Ok(StreamLine::Done),
+                Some(data) => {
+                    let chunk: ChatCompletionChunk = serde_json::from_str(data).map_err(invalid)?;
+                    Ok(delta(
+                        chunk
+                            .choices
+                            .into_iter()
+                            .next()
+                            .and_then(|choice| choice.delta.content),
+                    ))
+                }
+                None => Ok(StreamLine::Skip),
+            },
+            // One JSON object per line; the last has "done": true
+            Self::Ollama if line.is_empty() => Ok(StreamLine::Skip),
+            Self::Ollama => {
+                let chunk: OllamaChunk = serde_json::from_str(line).map_err(invalid)?;
+                match delta(chunk.message.and_then(|message| message.content)) {
+                    StreamLine::Skip if chunk.done => Ok(StreamLine::Done),
+                    line => Ok(line),
+                }
+            }
+        }
+    }
+
+    /// Answer text of a response body.
+    fn parse_response(self, body: &[u8]) -> Result<String, String> {
+        let content = match self {
+            Self::OpenAi => serde_json::from_slice::<ChatCompletion>(body)
+                .map(|response| response.choices.into_iter().next().map(|c| c.message)),
+            Self::Ollama => {
+                serde_json::from_slice::<OllamaChat>(body).map(|response| Some(response.message))
+            }
+        }
+        .map_err(|e| format!("invalid LLM response: {}", e))?
+        .and_then(|message| message.content);
+        content
+            .map(|content| content.trim().to_string())
+            .filter(|content| !content.is_empty())
+            .ok_or_else(|| "LLM response has no answer".to_string())
+    }
+}
+
+/// One message of a chat conversation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ChatMessage {
+    /// "system", "user" or "assistant"
+    pub role: &'static str,
+    /// Message text
+    pub content: String,
+}
+
+#[derive(Deserialize)]
+struct ChatCompletion {
+    #[serde(default)]
+    choices: Vec<ChatChoice>,
+}
+
+#[derive(Deserialize)]
+struct ChatChoice {
+    message: ChatReply,
+}
+
+#[derive(Deserialize)]
+struct OllamaChat {
+    message: ChatReply,
+}
+
+#[derive(Deserialize)]
+struct ChatCompletionChunk {
+    #[serde(default)]
+    choices: Vec<ChunkChoice>,
+}
+
+#[derive(Deserialize)]
+struct ChunkChoice {
+    delta: ChatReply,
+}
+
+#[derive(Deserialize)]
+struct OllamaChunk {
+    #[serde(default)]
+    message: Option<ChatReply>,
+    #[serde(default)]
+    done: bool,
+}
+
+/// One line of a streamed response.
+#[derive(Debug, PartialEq, Eq)]
+enum StreamLine {
+    /// Next piece of the answer
+    Delta(String),
+    /// The answer is complete
+    Done,
+    /// Nothing to emit (keep-alives, role announcements, empty pieces)
+    Skip,
+}
+
+#[derive(Deserialize)]
+struct ChatReply {
+    #[serde(default)]
+    content: Option<String>,
+}
+
+/// A failed chat request.
+struct Failure {
+    message: String,
+    /// Whether sending the request again may succeed
+    retryable: bool,
+}
+
+impl Failure {
+    fn retryable(message: String) -> Self {
+        Self {
+            message,
+            retryable: true,
+        }
+    }
+
+    fn fatal(message: String) -> Self {
+        Self {
+            message,
+            retryable: false,
+        }
+    }
+}
+
+/// Client of a chat API.
+#[derive(Clone)]
+pub struct LlmClient {
+    provider: LlmProvider,
+    endpoint: String,
+    model: String,
+    api_key: Option<String>,
+    timeout: Duration,
+    max_retries: u32,
+    http: Client<HttpsConnector<EgressConnector>, Full<Bytes>>,
+}
+
+impl std::fmt::Debug for LlmClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LlmClient")
+            .field("provider", &self.provider)
+            .field("endpoint", &self.endpoint)
+            .field("model", &self.model)
+            .field("timeout", &self.timeout)
+            .field("max_retries", &self.max_retries)
+            .finish_non_exhaustive()
+    }
+}
+
+impl LlmClient {
+    /// OpenAI-compatible client for `model` served below `base_url` (e.g.
+    /// `https://api.openai.com/v1`), through the installed egress settings.
+    pub fn new(base_url: &str, model: impl Into<String>, api_key: Option<String>) -> Self {
+        Self::with_provider(LlmProvider::OpenAi, base_url, model, api_key)
+    }
+
+    /// Client for `model` served below `base_url` in `provider`'s format.
+    pub fn with_provider(
+        provider: LlmProvider,
+        base_url: &str,
+        model: impl Into<String>,
+        api_key: Option<String>,
+    ) -> Self {
+        let connector = hyper_rustls::HttpsConnectorBuilder::new()
+            .with_tls_config(egress::current().tls_config())
+            .https_or_http()
+            .enable_http1()
+            .wrap_connector(EgressConnector::new());
+        Self {
+            provider,
+            endpoint: provider.endpoint(base_url),
+            model: model.into(),
+            api_key,
+            timeout: DEFAULT_LLM_TIMEOUT,
+            max_retries: DEFAULT_LLM_MAX_RETRIES,
+            http: Client::builder(TokioExecutor::new()).build(connector),
+        }
+    }
+
+    /// Give up on requests taking longer than `timeout` (per attempt).
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Retry a failed request up to `max_retries` times.
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Backend the answers are requested from.
+    pub fn provider(&self) -> LlmProvider {
+        self.provider
+    }
+
+    /// Model the answers are requested from.
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    /// Answer `question` from `evidence`, citing it with `[n]` markers.
+    pub async fn synthesize(
+        &self,
+        question: &str,
+        evidence: &[SearchResult],
+    ) -> Result<String, String> {
+        self.generate(&synthesis_messages(question, evidence)).await
+    }
+
+    /// Run one chat request like [`complete`](Self::complete), recording it
+    /// in the `memvid_llm_*` metrics.
+    pub async fn generate(&self, messages: &[ChatMessage]) -> Result<String, String> {
+        let started = Instant::now();
+        let result = self.complete(messages).await;
+        metrics::record_llm_request(
+            self.provider.name(),
+            result.is_ok(),
+            started.elapsed().as_secs_f64() * 1000.0,
+        );
+        result
+    }
+
+    /// Answer `question` from `evidence` like [`synthesize`](Self::synthesize),
+    /// returning the answer as a stream of deltas as the model writes it.
+    ///
+    /// Failures before the first delta (connection errors, 429 and 5xx
+    /// responses) are retried; the stream fails only on errors after that.
+    pub async fn synthesize_stream(
+        &self,
+        question: &str,
+        evidence: &[SearchResult],
+    ) -> Result<LlmStream, String> {
+        let started = Instant::now();
+        let messages = synthesis_messages(question, evidence);
+        match self.with_retries(|| self.open(&messages, true)).await {
+            Ok(response) => Ok(LlmStream {
+                provider: self.provider,
+                body: response.into_body(),
+                buffer: Vec::new(),
+                idle_timeout: self.timeout,
+                started,
+                eof: false,
+                finished: false,
+            }),
+            Err(e) => {
+                metrics::record_llm_request(
+                    self.provider.name(),
+                    false,
+                    started.elapsed().as_secs_f64() * 1000.0,
+                );
+                Err(e)
+            }
+        }
+    }
+
+    /// Run one chat request, retrying transient failures, and return the
+    /// answer text.
+    pub async fn complete(&self, messages: &[ChatMessage]) -> Result<String, String> {
+        self.with_retries(|| self.send(messages)).await
+    }
+
+    /// Run `attempt` until it succeeds, fails for good or runs out of
+    /// retries.
+    async fn with_retries<T, F, Fut>(&self, attempt: F) -> Result<T, String>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<T, Failure>>,
+    {
+        let mut retries = 0;
+        loop {
+            match attempt().await {
+                Ok(value) => return Ok(value),
+                Err(failure) if failure.retryable && retries < self.max_retries => {
+                    let delay = RETRY_BACKOFF * 2u32.pow(retries);
+                    retries += 1;
+                    warn!(
+                        provider = self.provider.name(),
+                        error = %failure.message,
+                        retry = retries,
+                        delay_ms = delay.as_millis() as u64,
+                        "LLM request failed, retrying"
+                    );
+                    metrics::increment_llm_retries(self.provider.name());
+                    tokio::time::sleep(delay).await;
+                }
+                Err(failure) => return Err(failure.message),
+            }
+        }
+    }
+
+    /// Send one chat request and read the whole answer.
+    async fn send(&self, messages: &[ChatMessage]) -> Result<String, Failure> {
+        let response = self.open(messages, false).await?;
+        let body = tokio::time::timeout(self.timeout, response.into_body().collect())
+            .await
+            .map_err(|_| Failure::retryable("LLM response timed out".to_string()))?
+            .map_err(|e| Failure::retryable(format!("LLM response read failed: {}", e)))?
+            .to_bytes();
+        self.provider.parse_response(&body).map_err(Failure::fatal)
+    }
+
+    /// Send one chat request and wait for a successful response's headers.
+    async fn open(
+        &self,
+        messages: &[ChatMessage],
+        stream: bool,
+    ) -> Result<hyper::Response<Incoming>, Failure> {
+        let body = self
+            .provider
+            .request_body(&self.model, messages, stream)
+            .to_string();
+        let mut builder = hyper::Request::post(&self.endpoint)
+            .header("Content-Type", "application/json")
+            .header("Accept", "application/json");
+        if let Some(key) = &self.api_key {
+            builder = builder.header("Authorization", format!("Bearer {}", key));
+        }
+        let request = builder
+            .body(Full::from(body))
+            .map_err(|e| Failure::fatal(format!("invalid LLM request: {}", e)))?;
+
+        let response = tokio::time::timeout(self.timeout, self.http.request(request))
+            .await
+            .map_err(|_| Failure::retryable("LLM request timed out".to_string()))?
+            .map_err(|e| Failure::retryable(format!("LLM request failed: {}", e)))?;
+        let status = response.status();
+        if !status.is_success() {
+            let message = format!("LLM request returned {}", status);
+            return Err(
+                if status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error() {
+                    Failure::retryable(message)
+                } else {
+                    Failure::fatal(message)
+                },
+            );
+        }
+        Ok(response)
+    }
+}
+
+/// Answer deltas of a streamed chat response.
+pub struct LlmStream {
+    provider: LlmProvider,
+    body: Incoming,
+    /// Bytes received but not yet split into lines
+    buffer: Vec<u8>,
+    /// Longest wait for the next piece of the response
+    idle_timeout: Duration,
+    started: Instant,
+    /// Whether the response body ended
+    eof: bool,
+    /// Whether the stream ended and was recorded in the metrics
+    finished: bool,
+}
+
+impl std::fmt::Debug for LlmStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LlmStream")
+            .field("provider", &self.provider)
+            .field("finished", &self.finished)
+            .finish_non_exhaustive()
+    }
+}
+
+impl LlmStream {
+    /// Next piece of the answer, None once it is complete.
+    pub async fn next(&mut self) -> Option<Result<String, String>> {
+        while !self.finished {
+            if let Some(newline) = self.buffer.iter().position(|&b| b == b'\n') {
+                let line: Vec<u8> = self.buffer.drain(..=newline).collect();
+                match self
+                    .provider
+                    .parse_stream_line(String::from_utf8_lossy(&line).trim())
+                {
+                    Ok(StreamLine::Delta(delta)) => return Some(Ok(delta)),
+                    Ok(StreamLine::Done) => self.finish(true),
+                    Ok(StreamLine::Skip) => {}
+                    Err(e) => return Some(Err(self.fail(e))),
+                }
+                continue;
+            }
+            if self.eof {
+                // A last line without a newline still counts
+                if self.buffer.is_empty() {
+                    self.finish(true);
+                } else {
+                    self.buffer.push(b'\n');
+                }
+                continue;
+            }
+            match tokio::time::timeout(self.idle_timeout, self.body.frame()).await {
+                Err(_) => return Some(Err(self.fail("LLM stream stalled".to_string()))),
+                Ok(None) => self.eof = true,
+                Ok(Some(Err(e))) => {
+                    return Some(Err(self.fail(format!("LLM stream read failed: {}", e))))
+                }
+                Ok(Some(Ok(frame))) => {
+                    if let Ok(data) = frame.into_data() {
+                        self.buffer.extend_from_slice(&data);
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    fn fail(&mut self, message: String) -> String {
+        self.finish(false);
+        message
+    }
+
+    fn finish(&mut self, success: bool) {
+        self.finished = true;
+        metrics::record_llm_request(
+            self.provider.name(),
+            success,
+            self.started.elapsed().as_secs_f64() * 1000.0,
+        );
+    }
+}
+
+/// System and user messages asking for an answer to `question` from the
+/// numbered `evidence`.
+pub fn synthesis_messages(question: &str, evidence: &[SearchResult]) -> Vec<ChatMessage> {
+    let context = evidence
+        .iter()
+        .enumerate()
+        .map(|(i, hit)| format!("[{}] {}\n{}", i + 1, hit.title, hit.snippet))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    vec![
+        ChatMessage {
+            role: "system",
+            content: SYSTEM_PROMPT.to_string(),
+        },
+        ChatMessage {
+            role: "user",
+            content: format!("Context:\n{}\n\nQuestion: {}", context, question),
+        },
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    fn hit(title: &str, snippet: &str) -> SearchResult {
+        SearchResult {
+            title: title.to_string(),
+            score: 1.0,
+            snippet: snippet.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Answer one connection per `(status, body)`, in order, and return the
+    /// base URL.
+    async fn serve(responses: Vec<(u16, &'static str)>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            for (status, body) in responses {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut buf = vec![0; 64 * 1024];
+                let _ = stream.read(&mut buf).await;
+                let response = format!(
+                    "HTTP/1.1 {} X\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                stream.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+        format!("http://{}", address)
+    }
+
+    #[test]
+    fn test_provider_endpoints() {
+        assert_eq!(
+            LlmProvider::OpenAi.endpoint("https://api.openai.com/v1/"),
+            "https://api.openai.com/v1/chat/completions"
+        );
+        assert_eq!(
+            LlmProvider::OpenAi.endpoint("http://localhost:8000/v1/chat/completions"),
+            "http://localhost:8000/v1/chat/completions"
+        );
+        assert_eq!(
+            LlmProvider::Ollama.endpoint(DEFAULT_OLLAMA_URL),
+            "http://localhost:11434/api/chat"
+        );
+        assert_eq!(LlmProvider::parse(" Ollama "), Ok(LlmProvider::Ollama));
+        assert!(LlmProvider::parse("anthropic").is_err());
+    }
+
+    #[test]
+    fn test_synthesis_messages_number_evidence() {
+        let messages = synthesis_messages(
+            "Rust experience?",
+            &[
+                hit("Acme", "Built services in Rust"),
+                hit("Skills", "Rust, Go"),
+            ],
+        );
+        assert_eq!(messages[0].role, "system");
+        assert_eq!(
+            messages[1].content,
+            "Context:\n[1] Acme\nBuilt services in Rust\n\n[2] Skills\nRust, Go\n\n\
+             Question: Rust experience?"
+        );
+    }
+
+    #[test]
+    fn test_parse_responses() {
+        let openai =
+            br#"{"choices":[{"message":{"role":"assistant","content":" Five years [1]. "}}]}"#;
+        assert_eq!(
+            LlmProvider::OpenAi.parse_response(openai).unwrap(),
+            "Five years [1]."
+        );
+        assert!(LlmProvider::OpenAi
+            .parse_response(br#"{"choices":[]}"#)
+            .is_err());
+        assert!(LlmProvider::OpenAi
+            .parse_response(br#"{"choices":[{"message":{"content":null}}]}"#)
+            .is_err());
+        assert!(LlmProvider::OpenAi.parse_response(b"not json").is_err());
+
+        let ollama = br#"{"model":"llama3","message":{"role":"assistant","content":"Yes [2]."},"done":true}"#;
+        assert_eq!(
+            LlmProvider::Ollama.parse_response(ollama).unwrap(),
+            "Yes [2]."
+        );
+        let body = LlmProvider::Ollama.request_body("llama3", &[], false);
+        assert_eq!(body["stream"], false);
+    }
+
+    #[tokio::test]
+    async fn test_retries_transient_failures() {
+        let answer = r#"{"message":{"role":"assistant","content":"Rust [1]."},"done":true}"#;
+        let base_url = serve(vec![(503, "{}"), (200, answer)]).await;
+        let client = LlmClient::with_provider(LlmProvider::Ollama, &base_url, "llama3", None);
+        assert_eq!(client.complete(&[]).await.unwrap(), "Rust [1].");
+
+        // Client errors are not retried
+        let base_url = serve(vec![(400, "{}"), (200, answer)]).await;
+        let client = LlmClient::with_provider(LlmProvider::Ollama, &base_url, "llama3", None);
+        assert_eq!(
+            client.complete(&[]).await.unwrap_err(),
+            "LLM request returned 400 Bad Request"
+        );
+    }
+
+    #[test]
+    fn test_parse_stream_lines() {
+        let openai = LlmProvider::OpenAi;
+        assert_eq!(
+            openai
+                .parse_stream_line(r#"data: {"choices":[{"delta":{"content":"Rust"}}]}"#)
+                .unwrap(),
+            StreamLine::Delta("Rust".to_string())
+        );
+        assert_eq!(
+            openai
+                .parse_stream_line(r#"data: {"choices":[{"delta":{"role":"assistant"}}]}"#)
+                .unwrap(),
+            StreamLine::Skip
+        );
+        assert_eq!(
+            openai.parse_stream_line(": keep-alive").unwrap(),
+            StreamLine::Skip
+        );
+        assert_eq!(
+            openai.parse_stream_line("data: [DONE]").unwrap(),
+            StreamLine::Done
+        );
+        assert!(openai.parse_stream_line("data: {").is_err());
+
+        let ollama = LlmProvider::Ollama;
+        assert_eq!(
+            ollama
+                .parse_stream_line(
+                    r#"{"message":{"role":"assistant","content":" [1]"},"done":false}"#
+                )
+                .unwrap(),
+            StreamLine::Delta(" [1]".to_string())
+        );
+        assert_eq!(
+            ollama
+                .parse_stream_line(r#"{"message":{"role":"assistant","content":""},"done":true}"#)
+                .unwrap(),
+            StreamLine::Done
+        );
+        assert_eq!(ollama.parse_stream_line("").unwrap(), StreamLine::Skip);
+    }
+
+    #[tokio::test]
+    async fn test_synthesize_stream_yields_deltas() {
+        let body = "data: {\"choices\":[{\"delta\":{\"content\":\"Five years\"}}]}\n\n\
+                    data: {\"choices\":[{\"delta\":{\"content\":\" of Rust [1].\"}}]}\n\n\
+                    data: [DONE]\n\n";
+        let base_url = serve(vec![(200, body)]).await;
+        let client = LlmClient::new(&base_url, "test-model", None);
+        let mut stream = client.synthesize_stream("Rust?", &[]).await.unwrap();
+
+        let mut deltas = Vec::new();
+        while let Some(delta) = stream.next().await {
+            deltas.push(delta.unwrap());
+        }
+        assert_eq!(deltas, ["Five years", " of Rust [1]."]);
+    }
+}
@@ -0,0 +1,190 @@
+//! Temporary log level overrides.
+//!
+//! The tracing filter is installed behind a reload handle. [`LogLevelControl`]
+//! swaps it for another `RUST_LOG`-style filter (e.g. `debug` or
+//! `ai_resume_memvid=debug,info`) and restores the startup filter once the
+//! override's duration has passed, so debug logging can be switched on in
+//! production without a restart and cannot be left on by mistake. A newer
+//! override replaces the pending one, including its revert time.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{info, warn};
+use tracing_subscriber::{reload, EnvFilter, Registry};
+
+use crate::error::ServiceError;
+
+/// Reload handle of the filter layered directly on the registry.
+pub type FilterHandle = reload::Handle<EnvFilter, Registry>;
+
+/// Override duration used when a request leaves it unset.
+pub const DEFAULT_OVERRIDE_DURATION: Duration = Duration::from_secs(300);
+
+/// Longest override; debug logging is costly to leave on.
+pub const MAX_OVERRIDE_DURATION: Duration = Duration::from_secs(3600);
+
+/// An override in effect.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogLevelOverride {
+    /// Active filter directives
+    pub filter: String,
+    /// Unix time (seconds) the startup filter is restored
+    pub reverts_at: i64,
+}
+
+/// Sets and reverts temporary log filters.
+pub struct LogLevelControl {
+    handle: FilterHandle,
+    default_filter: String,
+    /// Bumped per change so a superseded revert does nothing
+    generation: AtomicU64,
+    current: Mutex<Option<LogLevelOverride>>,
+}
+
+impl LogLevelControl {
+    /// Control the filter behind `handle`, reverting to `default_filter`.
+    pub fn new(handle: FilterHandle, default_filter: impl Into<String>) -> Self {
+        Self {
+            handle,
+            default_filter: default_filter.into(),
+            generation: AtomicU64::new(0),
+            current: Mutex::new(None),
+        }
+    }
+
+    /// Filter restored when an override ends.
+    pub fn default_filter(&self) -> &str {
+        &self.default_filter
+    }
+
+    /// The override in effect, if any.
+    pub fn current(&self) -> Option<LogLevelOverride> {
+        self.current.lock().unwrap().clone()
+    }
+
+    /// Apply `filter` for `duration` (zero = [`DEFAULT_OVERRIDE_DURATION`]);
+    /// an empty filter restores the startup filter now.
+    ///
+    /// # Errors
+    /// `InvalidRequest` for invalid directives or a duration above
+    /// [`MAX_OVERRIDE_DURATION`].
+    pub fn set(
+        self: &Arc<Self>,
+        filter: &str,
+        duration: Duration,
+    ) -> Result<Option<LogLevelOverride>, ServiceError> {
+        let filter = filter.trim();
+        if filter.is_empty() {
+            self.revert(self.generation.fetch_add(1, Ordering::AcqRel) + 1);
+            return Ok(None);
+        }
+        if duration > MAX_OVERRIDE_DURATION {
+            return Err(ServiceError::InvalidRequest(format!(
+                "duration must be at most {}s, got {}s",
+                MAX_OVERRIDE_DURATION.as_secs(),
+                duration.as_secs()
+            )));
+        }
+        let duration = if duration.is_zero() {
+            DEFAULT_OVERRIDE_DURATION
+        } else {
+            duration
+        };
+        let parsed = EnvFilter::try_new(filter).map_err(|e| {
+            ServiceError::InvalidRequest(format!("Invalid log filter '{}': {}", filter, e))
+        })?;
+
+        let mut current = self.current.lock().unwrap();
+        self.handle
+            .reload(parsed)
+            .map_err(|e| ServiceError::Internal(format!("Failed to set log filter: {}", e)))?;
+        let generation = self.generation.fetch_add(1, Ordering::AcqRel) + 1;
+        let applied = LogLevelOverride {
+            filter: filter.to_string(),
+            reverts_at: chrono::Utc::now().timestamp() + duration.as_secs() as i64,
+        };
+        *current = Some(applied.clone());
+        drop(current);
+        info!(
+            filter = filter,
+            duration_secs = duration.as_secs(),
+            "Log filter overridden"
+        );
+
+        let control = Arc::clone(self);
+        tokio::spawn(async move {
+            tokio::time::sleep(duration).await;
+            control.revert(generation);
+        });
+        Ok(Some(applied))
+    }
+
+    /// Restore the startup filter, unless a change after `generation`
+    /// superseded it.
+    fn revert(&self, generation: u64) {
+        let mut current = self.current.lock().unwrap();
+        if self.generation.load(Ordering::Acquire) != generation || current.take().is_none() {
+            return;
+        }
+        match self.handle.reload(EnvFilter::new(&self.default_filter)) {
+            Ok(()) => info!(filter = %self.default_filter, "Log filter restored"),
+            Err(e) => warn!(error = %e, "Failed to restore log filter"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing::level_filters::LevelFilter;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[tokio::test]
+    async fn test_override_reverts_after_duration() {
+        let (layer, handle) = reload::Layer::new(EnvFilter::new("info"));
+        let _subscriber = tracing_subscriber::registry().with(layer);
+        let max_level = || handle.with_current(|f| f.max_level_hint()).unwrap();
+        let control = Arc::new(LogLevelControl::new(handle.clone(), "info"));
+
+        let applied = control
+            .set("debug", Duration::from_millis(50))
+            .unwrap()
+            .unwrap();
+        assert_eq!(applied.filter, "debug");
+        assert_eq!(max_level(), Some(LevelFilter::DEBUG));
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(max_level(), Some(LevelFilter::INFO));
+        assert_eq!(control.current(), None);
+    }
+
+    #[tokio::test]
+    async fn test_newer_override_replaces_pending_revert() {
+        let (layer, handle) = reload::Layer::new(EnvFilter::new("info"));
+        let _subscriber = tracing_subscriber::registry().with(layer);
+        let max_level = || handle.with_current(|f| f.max_level_hint()).unwrap();
+        let control = Arc::new(LogLevelControl::new(handle.clone(), "info"));
+
+        control.set("debug", Duration::from_millis(50)).unwrap();
+        control.set("trace", Duration::from_secs(60)).unwrap();
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(max_level(), Some(LevelFilter::TRACE));
+
+        // An empty filter restores the startup filter right away
+        assert_eq!(control.set("", Duration::ZERO).unwrap(), None);
+        assert_eq!(max_level(), Some(LevelFilter::INFO));
+    }
+
+    #[test]
+    fn test_rejects_invalid_overrides() {
+        let (_layer, handle) = reload::Layer::new(EnvFilter::new("info"));
+        let control = Arc::new(LogLevelControl::new(handle, "info"));
+
+        let err = control.set("memvid=loud", Duration::ZERO).unwrap_err();
+        assert!(matches!(err, ServiceError::InvalidRequest(_)));
+        let err = control.set("debug", Duration::from_secs(7200)).unwrap_err();
+        assert!(matches!(err, ServiceError::InvalidRequest(_)));
+        assert_eq!(control.current(), None);
+    }
+}
@@ -5,95 +5,230 @@
 //!
 //! # Environment Variables
 //! - `MEMVID_FILE_PATH` - Path to .mv2 file (required unless MOCK_MEMVID=true)
+//! - `MEMVID_FILE_PATHS` - Named indexes as `name=path,...` or a TOML map, the first being the default (optional)
 //! - `GRPC_PORT` - gRPC listen port (default: 50051)
 //! - `METRICS_PORT` - Prometheus metrics port (default: 9090)
 //! - `MOCK_MEMVID` - Use mock searcher for testing (default: false)
-//! - `RUST_LOG` - Log level (default: info)
+//! - `RUST_LOG` - Log level, adjustable for a while with Admin/SetLogLevel (default: info)
+//! - `RELOAD_SCHEDULE` - Cron expression (UTC) for scheduled index reloads (default: off)
+//! - `RELOAD_JITTER_SECS` - Max random delay per scheduled reload (default: 300)
+//! - `RELOAD_RETRY_INITIAL_SECS` - Delay before retrying a failed scheduled reload, doubling per failure (default: 30)
+//! - `RELOAD_RETRY_MAX_SECS` - Longest delay between reload retries (default: 900)
+//! - `SHADOW_MEMVID_FILE_PATH` - Candidate .mv2 file receiving mirrored traffic (default: off)
+//! - `SHADOW_SAMPLE_PERCENT` - Percentage of requests mirrored to the candidate (default: 100)
+//! - `SYNTHETIC_FRAMES` - Serve a seeded synthetic corpus of this size in mock mode (default: off)
+//! - `SYNTHETIC_SEED` - Seed for the synthetic corpus (default: 42)
+//! - `EXPORT_MAX_BYTES_PER_SEC` - Bandwidth cap shared by export streams (default: 1048576)
+//! - `EXPORT_ACK_TIMEOUT_SECS` - Max wait for an export acknowledgment (default: 30)
+//! - `LLM_PROMPT_COST_PER_MTOK` - LLM prompt price, USD per million tokens (default: 0)
+//! - `LLM_COMPLETION_COST_PER_MTOK` - LLM completion price, USD per million tokens (default: 0)
+//! - `LLM_DAILY_COST_CAP_USD` - Daily LLM cost cap in USD, 0 = unlimited (default: 0)
+//! - `RETRIEVAL_PIPELINE` - JSON list of retrieval stages (default: retrieve only)
+//! - `COVERAGE_WINDOW_HOURS` - Rolling window for the frame coverage report (default: 168)
+//! - `PUBLIC_DEMO` - Public demo profile: anonymized, rate limited, no admin RPCs,
+//!   metrics on localhost, no LLM synthesis (default: false)
+//! - `DETERMINISTIC` - Byte-identical responses run after run: ties broken by frame id, pinned
+//!   clock, no time budgets, timings or paraphrases, no LLM synthesis (default: false)
+//! - `DETERMINISTIC_NOW` - Unix time deterministic mode uses as "now" (default: 1735689600, 2025-01-01)
+//! - `ANONYMIZE` - Redact contact details from responses (default: false)
+//! - `RATE_LIMIT_PER_MINUTE` - Requests per minute per client, 0 = unlimited (default: 0)
+//! - `RATE_LIMIT_RPS` - Requests per second per client, instead of `RATE_LIMIT_PER_MINUTE` (optional)
+//! - `RATE_LIMIT_BURST` - Requests a client may burst (default: `RATE_LIMIT_RPS` rounded up, else one minute's quota)
+//! - `DEFAULT_TOP_K` - `top_k` of requests that leave it unset (default: 5)
+//! - `MAX_TOP_K` - Largest `top_k` a request is served, larger ones are capped (default: 20)
+//! - `DEFAULT_SNIPPET_CHARS` - `snippet_chars` of requests that leave it unset (default: 200)
+//! - `MAX_SNIPPET_CHARS` - Largest `snippet_chars` a request is served, at least 50 (default: 1000)
+//! - `ADMIN_RPCS` - Serve the Admin gRPC service (default: true)
+//! - `ADMIN_TOKEN` - Bearer token every Admin RPC requires (default: none, Admin RPCs refused)
+//! - `INDEX_WRITES` - Let Admin/AppendFrames, SetState and DeleteFrames write to the serving index file (default: false)
+//! - `BACKUP_DIR` - Directory Admin/SnapshotIndex copies the serving index to, with a checksum and manifest (optional)
+//! - `METRICS_BIND_ADDRESS` - Metrics listener bind address (default: auto)
+//! - `LLM_SYNTHESIS` - Allow LLM answer synthesis (default: true)
+//! - `LLM_PROVIDER` - LLM API format: openai (any OpenAI-compatible server) or ollama (default: openai)
+//! - `LLM_BASE_URL` - LLM API base URL, e.g. https://api.openai.com/v1 (default: off, answers are the retrieved context; ollama: http://localhost:11434)
+//! - `LLM_MODEL` - Chat model answers are synthesized with, required with an LLM backend
+//! - `LLM_API_KEY` - Bearer token for the LLM API (optional)
+//! - `LLM_TIMEOUT_SECS` - Seconds an LLM request may take before it is retried or the context answer is used (default: 30)
+//! - `LLM_MAX_RETRIES` - Retries of LLM requests failing with connection errors, timeouts, 429 or 5xx (default: 2)
+//! - `CITATION_POLICY` - Citation checks of synthesized answers: off, lenient or strict (default: lenient)
+//! - `PRELOAD_INDEX` - Read the whole .mv2 into memory at load (default: false)
+//! - `MLOCK_INDEX` - Preload and mlock the .mv2, needs CAP_IPC_LOCK (default: false)
+//! - `PRELOAD_MAX_MEMORY_PERCENT` - Skip preloading above this share of available memory (default: 50)
+//! - `MAX_CONCURRENT_SEARCHES` - Blocking searches and asks running at once, 0 = unlimited (default: 16)
+//! - `MAX_QUEUED_SEARCHES` - Searches waiting for a slot before RESOURCE_EXHAUSTED (default: 64)
+//! - `MEMVID_READ_POOL_SIZE` - Read-only instances opened per index for concurrent queries, 1-32 (default: 1)
+//! - `EMBED_MODEL_PATH` - Local ONNX embedding model (directory or .onnx file) for semantic retrieval; needs the `onnx` feature (default: off)
+//! - `CRASH_REPORT_PATH` - Directory receiving JSON crash reports (default: off)
+//! - `CRASH_REPORT_WEBHOOK` - http:// URL receiving JSON crash reports (default: off)
+//! - `ALERT_WEBHOOK` - http:// URL receiving JSON alerts, e.g. on failing index reloads (default: off)
+//! - `REQUEST_LOG_CAPACITY` - Recent requests kept for crash reports and /debug/last-requests (default: 50)
+//! - `DEBUG_TOKEN` - Bearer token enabling /debug/last-requests on the metrics port (default: off)
+//! - `CURSOR_SECRET` - Key signing page cursors; set the same value on every replica (default: random per process)
+//! - `VISIBILITY_FILE` - JSON file persisting frame visibility overrides (default: in memory)
+//! - `AUTHENTICATED_API_KEYS` - Comma-separated API keys that may see authenticated-only frames (default: none)
+//! - `CONFIG_SOURCE` - consul://host:port/prefix or etcd://host:port/prefix holding runtime settings (default: off)
+//! - `CONFIG_SOURCE_TOKEN` - Consul ACL token or etcd Authorization header value (default: none)
+//! - `CONFIG_POLL_SECS` - Interval between runtime config reads (default: 10)
+//! - `ANSWER_CACHE` - Answer cache: off, memory, or a redis:// URL shared by all replicas (default: off)
+//! - `ANSWER_CACHE_TTL_SECS` - Lifetime of a cached answer (default: 300)
+//! - `SYNONYMS_FILE` - File of `term → expansion` rules appended to search queries (default: none)
+//! - `ASK_ASPECTS` - Comma-separated aspects a decomposed Ask retrieves for (default: experience,skills,leadership,education)
+//! - `ASK_ASPECT_LLM` - Have the LLM write the sub-questions of a decomposed Ask (default: false)
+//! - `SPELL_CORRECTION` - Retry searches without hits with their misspellings corrected (default: true)
+//! - `SESSION_STORE` - Conversation sessions: memory, a directory, or a redis:// URL shared by all replicas (default: memory)
+//! - `SESSION_TTL_SECS` - Lifetime of a session after its last turn (default: 3600)
+//! - `SESSION_MAX_TURNS` - Most recent turns kept per session (default: 20)
+//! - `WARM_CACHE_CORPUS` - File of common questions the WarmCache RPC runs, one per line (optional)
+//! - `WARM_CACHE_SNAPSHOT` - Warm answer cache snapshot, written by WarmCache and restored at startup (optional)
+//! - `SEARCH_CACHE_TTL_SECS` - Lifetime of a cached search result (default: 60)
+//! - `SEARCH_CACHE_CAPACITY` - Search results cached in process at most, 0 = off (default: 1024)
+//! - `TERMINATION_GRACE_PERIOD_SECS` - Pod termination grace period; a drain ends before it (default: 30)
+//! - `DRAIN_DELAY_SECS` - Time reporting NOT_SERVING before the server stops accepting requests (default: 5)
+//! - `LIFECYCLE_ENDPOINT` - Serve the preStop-compatible /quitquitquit on the metrics port (default: false)
+//! - `READINESS_POLICY` - Readiness gate policies as gate=required|degrade|ignore pairs (default: index_loaded and warmup_done required)
+//! - `WARMUP_QUERIES` - Comma-separated queries searched and asked before reporting SERVING (default: one generic query)
+//! - `WARMUP_QUERIES_FILE` - File of warm-up queries, one per line, run after `WARMUP_QUERIES` (optional)
+//! - `HTTP_PROXY` / `HTTPS_PROXY` - Proxies for outbound HTTP and HTTPS, either case (optional)
+//! - `NO_PROXY` - Comma-separated hosts reached without the proxy (optional)
+//! - `EGRESS_ALLOWLIST` - Comma-separated hosts outbound connections may reach (default: any)
+//! - `CA_BUNDLE_PATH` - PEM CA certificates trusted for outbound HTTPS besides the web PKI roots (optional)
+//! - `FRAME_STATS_PATH` - Directory persisting per-frame serve counts across restarts (optional)
+//! - `TLS_CERT_PATH` - PEM certificate chain; serves gRPC over TLS when set (optional)
+//! - `TLS_KEY_PATH` - PEM private key, required with `TLS_CERT_PATH` (optional)
+//! - `ANALYTICS_REPORT_SCHEDULE` - Deliver an analytics digest: daily, weekly or a cron expression (optional)
+//! - `ANALYTICS_REPORT_FORMAT` - Report file format: markdown or json (default: markdown)
+//! - `ANALYTICS_REPORT_PATH` - Directory analytics reports are written to (optional)
+//! - `ANALYTICS_REPORT_WEBHOOK` - http:// URL analytics reports are posted to (optional)
+//! - `JWT_ISSUER` - Required bearer-token issuer; enables JWT validation (optional)
+//! - `JWT_JWKS_URL` - Issuer JWKS URL, required with `JWT_ISSUER` (optional)
+//! - `JWT_AUDIENCE` - Required bearer-token audience (optional)
+//! - `JWT_REQUIRED` - Reject query requests without a bearer token (default: false)
+//! - `JWT_JWKS_REFRESH_SECS` - Seconds between JWKS refreshes (default: 600)
+//! - `ACL_ENFORCEMENT` - Frame ACLs: audit (report only) or enforce (drop restricted frames) (default: audit)
+//! - `RETENTION_MAX_AGE_HOURS` - Maximum age of request records and query statistics (default: unlimited)
+//! - `RETENTION_MAX_ENTRIES` - Records kept per retained store (default: store limits)
+//! - `RETENTION_HASH_QUERIES` - Log and count query text only as a keyed hash (default: false)
+//! - `POD_NAME`, `POD_NAMESPACE`, `NODE_NAME`, `POD_IP` - Downward API pod metadata for log and metric labels
 
+use std::path::PathBuf;
 use std::sync::Arc;
+use tonic::service::interceptor::InterceptedService;
+use tonic::service::Interceptor;
 use tonic::transport::Server;
+use tonic::Status;
 use tracing::{error, info, warn};
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
+use tracing_subscriber::{layer::SubscriberExt, reload, util::SubscriberInitExt, EnvFilter};
 
-mod config;
-mod error;
-mod grpc;
-mod memvid;
-mod metrics;
+use ai_resume_memvid::alert::AlertSender;
+use ai_resume_memvid::capabilities::{CapabilityReport, ListenerInfo};
+use ai_resume_memvid::cli::{
+    check_health, run_export, run_ingest, run_inspect, run_search, run_validate, ExportArgs,
+    HealthcheckArgs, IngestArgs, InspectArgs, SearchArgs, ValidateArgs, EXPORT_USAGE,
+    HEALTHCHECK_USAGE, INGEST_USAGE, INSPECT_USAGE, SEARCH_USAGE, VALIDATE_USAGE,
+};
+use ai_resume_memvid::config::Config;
+use ai_resume_memvid::crash::{self, CrashReporter};
+use ai_resume_memvid::debug;
+use ai_resume_memvid::egress::{self, Egress};
+use ai_resume_memvid::error::ServiceError;
+use ai_resume_memvid::generated;
+use ai_resume_memvid::generated::memvid::v1::{
+    admin_server::AdminServer, health_server::HealthServer,
+    memvid_service_server::MemvidServiceServer,
+};
+use ai_resume_memvid::generated::memvid::v2::memvid_service_server::MemvidServiceServer as MemvidServiceV2Server;
+use ai_resume_memvid::grpc::{
+    read_corpus, refresh_jwks, AclInterceptor, AdminAuth, AdminService, CacheWarmer,
+    CoverageTracker, CursorCodec, DeadlineLayer, HealthService, JwksClient, JwtValidator,
+    LlmPricing, MemvidGrpcService, MemvidV2Service, ProfileCache, QueryStats, RateLimiter,
+    RequestLog, RequestLogLayer, RetentionPolicy, TopicClassifier, UsageLedger,
+};
+use ai_resume_memvid::lifecycle::{
+    drain_on_signal, lifecycle_router, Drain, PodInfo, PodLogWriter,
+};
+use ai_resume_memvid::llm::{LlmClient, LlmProvider};
+use ai_resume_memvid::log_level::LogLevelControl;
+use ai_resume_memvid::memvid::{
+    AclMode, AnonymizingSearcher, AnswerCacheBackend, AnswerStore, AspectPlanner, CachingSearcher,
+    CitationPolicy, DeterministicSearcher, EmbedderChain, InstrumentedSearcher, MemoryAnswerStore,
+    MockSearcher, PipelineSearcher, PreloadOptions, QueryEmbeddingCache, RealSearcher,
+    RedisAnswerStore, ReloadableSearcher, RetrievalPipeline, SearchCache, SearchCachingSearcher,
+    SearchLimiter, Searcher, SearcherRegistry, ShadowSearcher, SynonymMap, SynthesizingSearcher,
+    VisibilityStore, DEFAULT_INDEX,
+};
+use ai_resume_memvid::metrics;
+use ai_resume_memvid::readiness::{warm_up, warmup_queries, Gate, Readiness, ReadinessPolicy};
+use ai_resume_memvid::report::{
+    parse_report_schedule, run_analytics_reports, AnalyticsReporter, ReportFormat,
+};
+use ai_resume_memvid::runtime_config::{watch_config_source, ConfigSource, RuntimeConfig};
+use ai_resume_memvid::schedule::{run_reload_schedule, CronSchedule, ReloadRetry};
+use ai_resume_memvid::session::{
+    FileSessionStore, MemorySessionStore, RedisSessionStore, SessionBackend, SessionManager,
+    SessionStore,
+};
+use ai_resume_memvid::supervisor::{Supervisor, DEFAULT_RESTART};
+use ai_resume_memvid::tls::server_tls_config;
 
-// Include generated proto code from build script
-mod generated {
-    pub mod memvid {
-        pub mod v1 {
-            include!(concat!(env!("OUT_DIR"), "/memvid.v1.rs"));
-        }
-    }
+/// How long background tasks (e.g. the metrics server) may take to stop at
+/// shutdown.
+const TASK_SHUTDOWN_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// Interval between health probes of the embedding model.
+const EMBEDDER_PROBE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Interceptor for the query APIs: bearer-token validation (when
+/// configured), the caller's ACL context, then the rate limit.
+#[derive(Clone)]
+struct QueryInterceptor {
+    jwt: Option<JwtValidator>,
+    acl: AclInterceptor,
+    rate_limiter: RateLimiter,
 }
 
-use config::Config;
-use generated::memvid::v1::{
-    health_server::HealthServer, memvid_service_server::MemvidServiceServer,
-};
-use grpc::{HealthService, MemvidGrpcService};
-use memvid::{MockSearcher, RealSearcher, Searcher};
+impl Interceptor for QueryInterceptor {
+    fn call(&mut self, request: tonic::Request<()>) -> Result<tonic::Request<()>, Status> {
+        let request = match &mut self.jwt {
+            Some(jwt) => jwt.call(request)?,
+            None => request,
+        };
+        let request = self.acl.call(request)?;
+        self.rate_limiter.call(request)
+    }
+}
 
 /// Run healthcheck mode: connect to gRPC service and check health
-/// Tries both IPv4 and IPv6 addresses for dual-stack support
-async fn run_healthcheck() -> Result<(), Box<dyn std::error::Error>> {
-    // If GRPC_URL is explicitly set, use it; otherwise try both IPv4 and IPv6
-    if let Ok(grpc_url) = std::env::var("GRPC_URL") {
-        // Explicit URL provided, use it directly
-        match tokio::time::timeout(
-            std::time::Duration::from_secs(5),
-            check_grpc_health(&grpc_url),
-        )
-        .await
-        {
-            Ok(Ok(_)) => {
-                eprintln!("healthcheck: gRPC service is healthy");
-                std::process::exit(0);
-            }
-            Ok(Err(e)) => {
-                eprintln!("healthcheck: gRPC health check failed: {}", e);
-                std::process::exit(1);
-            }
-            Err(_) => {
-                eprintln!("healthcheck: timeout connecting to gRPC service");
-                std::process::exit(1);
-            }
+/// Tries both IPv4 and IPv6 addresses for dual-stack support, on
+/// `GRPC_PORT`, unless `GRPC_URL` names the service
+async fn run_healthcheck(
+    args: impl Iterator<Item = String>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let args = match HealthcheckArgs::parse(args) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("healthcheck: {}\n\n{}", e, HEALTHCHECK_USAGE);
+            std::process::exit(2);
         }
-    } else {
-        // Try both IPv6 and IPv4 for dual-stack support
-        let urls = vec![
-            "http://[::1]:50051",     // IPv6 localhost
-            "http://127.0.0.1:50051", // IPv4 localhost
-        ];
-
-        for grpc_url in urls {
-            match tokio::time::timeout(
-                std::time::Duration::from_secs(2),
-                check_grpc_health(grpc_url),
-            )
-            .await
-            {
-                Ok(Ok(_)) => {
-                    eprintln!("healthcheck: gRPC service is healthy (via {})", grpc_url);
-                    std::process::exit(0);
-                }
-                Ok(Err(_)) | Err(_) => {
-                    // Continue to next URL
-                    continue;
-                }
+    };
+
+    let mut failure = String::from("no address to try");
+    for (grpc_url, timeout) in args.targets() {
+        match tokio::time::timeout(timeout, check_grpc_health(&grpc_url, args.deep)).await {
+            Ok(Ok(())) => {
+                eprintln!("healthcheck: gRPC service is healthy (via {})", grpc_url);
+                std::process::exit(0);
             }
+            Ok(Err(e)) => failure = format!("{}: {}", grpc_url, e),
+            Err(_) => failure = format!("{}: timeout after {:?}", grpc_url, timeout),
         }
-
-        eprintln!("healthcheck: failed to connect via IPv4 or IPv6");
-        std::process::exit(1);
     }
+
+    eprintln!("healthcheck: gRPC health check failed ({})", failure);
+    std::process::exit(1);
 }
 
 /// Check gRPC health endpoint
-async fn check_grpc_health(grpc_url: &str) -> Result<(), Box<dyn std::error::Error>> {
+async fn check_grpc_health(grpc_url: &str, deep: bool) -> Result<(), Box<dyn std::error::Error>> {
     use generated::memvid::v1::health_client::HealthClient;
     use generated::memvid::v1::HealthCheckRequest;
 
@@ -107,22 +242,270 @@ async fn check_grpc_health(grpc_url: &str) -> Result<(), Box<dyn std::error::Err
     });
 
     let response = client.check(request).await?;
+    check_health(response.get_ref(), deep).map_err(Into::into)
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let result = run().await;
+    if let Err(e) = &result {
+        crash::report_fatal(&e.to_string());
+    }
+    result
+}
+
+/// `memvid-service search ...`: query an index file and print the hits.
+async fn run_search_command(
+    args: impl Iterator<Item = String>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let args = match SearchArgs::parse(args) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("search: {}\n\n{}", e, SEARCH_USAGE);
+            std::process::exit(2);
+        }
+    };
+    print_command_output("search", run_search(&args).await)
+}
+
+/// `memvid-service inspect ...`: summarize what an index file holds.
+async fn run_inspect_command(
+    args: impl Iterator<Item = String>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let args = match InspectArgs::parse(args) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("inspect: {}\n\n{}", e, INSPECT_USAGE);
+            std::process::exit(2);
+        }
+    };
+    print_command_output("inspect", run_inspect(&args).await)
+}
+
+/// `memvid-service ingest ...`: build an index file from a resume source.
+async fn run_ingest_command(
+    args: impl Iterator<Item = String>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let args = match IngestArgs::parse(args) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("ingest: {}\n\n{}", e, INGEST_USAGE);
+            std::process::exit(2);
+        }
+    };
+    print_command_output("ingest", run_ingest(&args).await)
+}
+
+/// `memvid-service export ...`: dump an index file as JSON Lines.
+async fn run_export_command(
+    args: impl Iterator<Item = String>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let args = match ExportArgs::parse(args) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("export: {}\n\n{}", e, EXPORT_USAGE);
+            std::process::exit(2);
+        }
+    };
+    print_command_output("export", run_export(&args).await)
+}
 
-    // Status 1 = SERVING
-    if response.get_ref().status == 1 {
-        Ok(())
+/// `memvid-service validate ...`: run integrity checks on an index file,
+/// exiting 1 when one fails.
+async fn run_validate_command(
+    args: impl Iterator<Item = String>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let args = match ValidateArgs::parse(args) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("validate: {}\n\n{}", e, VALIDATE_USAGE);
+            std::process::exit(2);
+        }
+    };
+    let (output, passed) = match run_validate(&args).await {
+        Ok(validated) => validated,
+        Err(e) => {
+            eprintln!("validate: {}", e);
+            std::process::exit(1);
+        }
+    };
+    print!("{}", output);
+    if !passed {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// Print a subcommand's output, or its error and exit 1.
+fn print_command_output(
+    command: &str,
+    output: Result<String, ServiceError>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // A bad index is the operator's answer, not a service crash to report
+    match output {
+        Ok(output) => {
+            print!("{}", output);
+            Ok(())
+        }
+        Err(e) => {
+            eprintln!("{}: {}", command, e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Open a further named index behind the decorators every index shares:
+/// call tracing, the retrieval pipeline and redaction. Scheduled reloads,
+/// shadow traffic and the answer cache stay with the default index.
+/// Embedder chain of the local ONNX model named by `EMBED_MODEL_PATH`.
+#[cfg(feature = "onnx")]
+fn load_embedder(
+    config: &Config,
+) -> Result<Option<Arc<EmbedderChain>>, Box<dyn std::error::Error>> {
+    let Some(path) = &config.embed_model_path else {
+        return Ok(None);
+    };
+    let embedder = ai_resume_memvid::memvid::OnnxEmbedder::load(path).map_err(|e| {
+        error!(error = %e, model = %path, "FATAL: Failed to load embedding model");
+        e
+    })?;
+    let chain = EmbedderChain::new(vec![("onnx".to_string(), Arc::new(embedder) as _)])?;
+    Ok(Some(Arc::new(chain)))
+}
+
+/// Without the `onnx` feature there is no local model (config rejects
+/// `EMBED_MODEL_PATH`).
+#[cfg(not(feature = "onnx"))]
+fn load_embedder(
+    _config: &Config,
+) -> Result<Option<Arc<EmbedderChain>>, Box<dyn std::error::Error>> {
+    Ok(None)
+}
+
+/// Synonym rules from `SYNONYMS_FILE`, shared by every index.
+fn load_synonyms(config: &Config) -> Result<Option<Arc<SynonymMap>>, Box<dyn std::error::Error>> {
+    let Some(path) = &config.synonyms_file else {
+        return Ok(None);
+    };
+    let synonyms = SynonymMap::load(std::path::Path::new(path)).map_err(|e| {
+        error!(error = %e, synonyms_file = %path, "FATAL: Failed to load synonyms");
+        e
+    })?;
+    info!(synonyms_file = %path, terms = synonyms.len(), "Synonym expansion enabled");
+    Ok(Some(Arc::new(synonyms)))
+}
+
+/// Preloading options for every index, when `PRELOAD_INDEX` is set.
+fn preload_options(config: &Config) -> Option<PreloadOptions> {
+    config.preload_index.then_some(PreloadOptions {
+        lock: config.mlock_index,
+        max_memory_percent: config.preload_max_memory_percent,
+    })
+}
+
+/// What every index shares, across named indexes and reloads.
+#[derive(Clone)]
+struct SharedIndexParts {
+    limiter: Option<Arc<SearchLimiter>>,
+    embedder: Option<Arc<EmbedderChain>>,
+    embedding_cache: Arc<QueryEmbeddingCache>,
+    synonyms: Option<Arc<SynonymMap>>,
+}
+
+async fn open_named_index(
+    config: &Config,
+    name: &str,
+    path: &str,
+    shared: &SharedIndexParts,
+    llm: Option<Arc<LlmClient>>,
+) -> Result<Arc<dyn Searcher>, Box<dyn std::error::Error>> {
+    let index: Arc<dyn Searcher> = if config.mock_memvid {
+        if config.synthetic_frames > 0 {
+            Arc::new(MockSearcher::synthetic(
+                config.synthetic_seed,
+                config.synthetic_frames,
+            ))
+        } else {
+            Arc::new(MockSearcher::new())
+        }
     } else {
-        Err("service not serving".into())
+        let index = ReloadableSearcher::open_preloaded(
+            path,
+            preload_options(config),
+            shared.limiter.clone(),
+            config.memvid_read_pool_size,
+            shared.embedder.clone(),
+            Arc::clone(&shared.embedding_cache),
+            shared.synonyms.clone(),
+        )
+            .await
+            .map_err(|e| {
+                error!(error = %e, index = name, memvid_file = path, "FATAL: Failed to load named index");
+                e
+            })?;
+        Arc::new(index)
+    };
+    info!(
+        index = name,
+        memvid_file = path,
+        frame_count = index.frame_count(),
+        "Named index loaded"
+    );
+
+    let mut index: Arc<dyn Searcher> = Arc::new(InstrumentedSearcher::new(index));
+    if let Some(json) = &config.retrieval_pipeline {
+        index = Arc::new(PipelineSearcher::new(
+            index,
+            RetrievalPipeline::parse(json)?,
+        ));
+    }
+    if let Some(client) = llm {
+        index = Arc::new(SynthesizingSearcher::new(index, client));
     }
+    if config.anonymize {
+        index = Arc::new(AnonymizingSearcher::new(index));
+    }
+    if config.deterministic {
+        index = Arc::new(DeterministicSearcher::new(index));
+    }
+    Ok(index)
 }
 
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // Initialize tracing (use RUST_LOG env var to control log level)
-    tracing_subscriber::registry()
-        .with(EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")))
-        .with(tracing_subscriber::fmt::layer().json())
-        .init();
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    // Subcommands run without the server, and without JSON logs mixed into
+    // their output
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        Some("search") => return run_search_command(args.into_iter().skip(1)).await,
+        Some("inspect") => return run_inspect_command(args.into_iter().skip(1)).await,
+        Some("validate") => return run_validate_command(args.into_iter().skip(1)).await,
+        Some("ingest") => return run_ingest_command(args.into_iter().skip(1)).await,
+        Some("export") => return run_export_command(args.into_iter().skip(1)).await,
+        _ => {}
+    }
+
+    // Initialize tracing (use RUST_LOG env var to control log level); in
+    // Kubernetes, log lines carry the pod metadata
+    let pod = PodInfo::from_env();
+    let default_filter = std::env::var(EnvFilter::DEFAULT_ENV)
+        .ok()
+        .filter(|filter| EnvFilter::try_new(filter).is_ok())
+        .unwrap_or_else(|| "info".to_string());
+    let (filter, filter_handle) = reload::Layer::new(EnvFilter::new(&default_filter));
+    let log_level = Arc::new(LogLevelControl::new(filter_handle, default_filter));
+    let registry = tracing_subscriber::registry().with(filter);
+    match &pod {
+        Some(pod) => registry
+            .with(
+                tracing_subscriber::fmt::layer()
+                    .json()
+                    .with_writer(PodLogWriter::new(pod)),
+            )
+            .init(),
+        None => registry
+            .with(tracing_subscriber::fmt::layer().json())
+            .init(),
+    }
 
     // Check if running in healthcheck mode
     let program_name = std::env::args()
@@ -135,7 +518,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .unwrap_or_default();
 
     if program_name == "healthcheck" {
-        return run_healthcheck().await;
+        return run_healthcheck(std::env::args().skip(1)).await;
     }
 
     info!("Starting memvid gRPC service");
@@ -153,20 +536,139 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         "Configuration loaded"
     );
 
+    // Outbound clients (JWKS, config store, webhooks, Redis) go through the
+    // configured proxy, CA bundle and allowlist
+    let egress = Egress::from_config(&config).map_err(|e| {
+        error!("Egress configuration error: {}", e);
+        e
+    })?;
+    egress::install(egress);
+
+    // Request records, query statistics and logged query text are kept
+    // within the retention limits
+    let retention = RetentionPolicy::new(
+        config
+            .retention_max_age_hours
+            .map(|hours| std::time::Duration::from_secs(hours * 3600)),
+        config.retention_max_entries,
+        config.retention_hash_queries,
+    );
+    // Crash reports and the debug endpoint show the last requests served by
+    // any gRPC service
+    let request_log =
+        Arc::new(RequestLog::new(config.request_log_capacity).with_retention(retention.clone()));
+    let crash_reporter = CrashReporter::new(&config, Arc::clone(&request_log));
+    if crash_reporter.is_enabled() {
+        crash_reporter.install();
+        info!("Crash reporting enabled");
+    }
+
     // Initialize metrics
-    let metrics_handle = metrics::init_metrics();
+    let metrics_handle = metrics::init_metrics_with_labels(
+        pod.as_ref().map(PodInfo::metric_labels).unwrap_or_default(),
+    );
+    if let Some(pod) = &pod {
+        info!(pod = %pod.pod, namespace = ?pod.namespace, node = ?pod.node, "Running in Kubernetes");
+    }
+
+    // Background tasks are supervised: panics are restarted, then fatal
+    let mut supervisor = Supervisor::new();
+
+    // SIGTERM, Ctrl-C and /quitquitquit drain the server before it stops
+    let drain = Arc::new(Drain::new(
+        std::time::Duration::from_secs(config.drain_delay_secs),
+        std::time::Duration::from_secs(config.termination_grace_period_secs),
+    ));
+    supervisor.spawn("signals", DEFAULT_RESTART, {
+        let drain = Arc::clone(&drain);
+        move || drain_on_signal(Arc::clone(&drain))
+    });
+
+    // Preloading, the search limit, synonyms and the query embedding cache
+    // apply to every index
+    let preload = preload_options(&config);
+    let limiter = (config.max_concurrent_searches > 0).then(|| {
+        Arc::new(SearchLimiter::new(
+            config.max_concurrent_searches,
+            config.max_queued_searches,
+        ))
+    });
+    let synonyms = load_synonyms(&config)?;
+
+    // A local embedding model gives every index semantic retrieval; health
+    // probes let Ask recover from lexical-only fallback
+    let embedder = load_embedder(&config)?;
+    if let Some(chain) = &embedder {
+        chain.spawn_probes(EMBEDDER_PROBE_INTERVAL);
+    }
+    let shared = SharedIndexParts {
+        limiter,
+        embedder,
+        embedding_cache: Arc::new(QueryEmbeddingCache::default()),
+        synonyms,
+    };
+
+    // Ask answers are synthesized by the configured chat model, if any
+    let llm = match (&config.llm_base_url, &config.llm_model) {
+        (Some(base_url), Some(model)) => {
+            let provider = LlmProvider::parse(&config.llm_provider)?;
+            info!(
+                provider = provider.name(),
+                base_url = %base_url,
+                model = %model,
+                "LLM answer synthesis enabled"
+            );
+            let client = LlmClient::with_provider(
+                provider,
+                base_url,
+                model.clone(),
+                config.llm_api_key.clone(),
+            )
+            .with_timeout(std::time::Duration::from_secs(config.llm_timeout_secs))
+            .with_max_retries(config.llm_max_retries);
+            Some(Arc::new(client))
+        }
+        _ => None,
+    };
 
     // Create searcher (mock or real based on config)
     // STRICT POLICY: No silent fallbacks - fail loudly if real implementation unavailable
-    let searcher: Arc<dyn memvid::Searcher> = if config.mock_memvid {
-        info!("MOCK_MEMVID=true: Using mock searcher for testing");
-        Arc::new(MockSearcher::new())
+    let (searcher, reloadable): (Arc<dyn Searcher>, Option<Arc<ReloadableSearcher>>) = if config
+        .mock_memvid
+    {
+        if config.synthetic_frames > 0 {
+            info!(
+                frames = config.synthetic_frames,
+                seed = config.synthetic_seed,
+                "MOCK_MEMVID=true: Using mock searcher with synthetic data"
+            );
+            (
+                Arc::new(MockSearcher::synthetic(
+                    config.synthetic_seed,
+                    config.synthetic_frames,
+                )),
+                None,
+            )
+        } else {
+            info!("MOCK_MEMVID=true: Using mock searcher for testing");
+            (Arc::new(MockSearcher::new()), None)
+        }
     } else {
         info!(
             memvid_file = %config.memvid_file_path,
             "MOCK_MEMVID=false: Loading real memvid searcher (will exit on failure)"
         );
-        match RealSearcher::new(&config.memvid_file_path).await {
+        match ReloadableSearcher::open_preloaded(
+            &config.memvid_file_path,
+            preload,
+            shared.limiter.clone(),
+            config.memvid_read_pool_size,
+            shared.embedder.clone(),
+            Arc::clone(&shared.embedding_cache),
+            shared.synonyms.clone(),
+        )
+        .await
+        {
             Ok(searcher) => {
                 let fc = searcher.frame_count();
                 if fc == 0 {
@@ -176,7 +678,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                     );
                 }
                 info!(frame_count = fc, "Real memvid searcher loaded successfully");
-                Arc::new(searcher)
+                let searcher = Arc::new(searcher);
+                (Arc::clone(&searcher) as Arc<dyn Searcher>, Some(searcher))
             }
             Err(e) => {
                 error!(
@@ -189,14 +692,393 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     };
 
+    // Trace where each call's time goes, next to the index itself
+    let searcher: Arc<dyn Searcher> = Arc::new(InstrumentedSearcher::new(searcher));
+
+    // Mirror traffic to a candidate index for comparison (canary mode)
+    let searcher: Arc<dyn Searcher> = match &config.shadow_memvid_file_path {
+        Some(path) => {
+            info!(
+                shadow_file = %path,
+                sample_percent = config.shadow_sample_percent,
+                "Shadow mode enabled: mirroring traffic to candidate index"
+            );
+            let mut candidate = RealSearcher::new(path).await.map_err(|e| {
+                error!(error = %e, shadow_file = %path, "FATAL: Failed to load shadow candidate");
+                e
+            })?;
+            if let Some(synonyms) = &shared.synonyms {
+                candidate = candidate.with_synonyms(Arc::clone(synonyms));
+            }
+            Arc::new(ShadowSearcher::new(
+                searcher,
+                Arc::new(candidate),
+                config.shadow_sample_percent,
+            ))
+        }
+        None => searcher,
+    };
+
+    // Run requests through the configured retrieval stages
+    let searcher: Arc<dyn Searcher> = match &config.retrieval_pipeline {
+        Some(json) => {
+            let pipeline = RetrievalPipeline::parse(json)?;
+            info!(stages = ?pipeline.stage_names(), "Retrieval pipeline enabled");
+            Arc::new(PipelineSearcher::new(searcher, pipeline))
+        }
+        None => searcher,
+    };
+
+    // Write answers from the final evidence, before redaction and caching
+    let searcher: Arc<dyn Searcher> = match &llm {
+        Some(client) => Arc::new(SynthesizingSearcher::new(searcher, Arc::clone(client))),
+        None => searcher,
+    };
+
+    // Redact contact details last, so no decorator output escapes it
+    let searcher: Arc<dyn Searcher> = if config.anonymize {
+        info!("Anonymized mode enabled: redacting contact details from responses");
+        Arc::new(AnonymizingSearcher::new(searcher))
+    } else {
+        searcher
+    };
+
+    // Fix ranking ties, budgets and timings for reproducible responses
+    let searcher: Arc<dyn Searcher> = if config.deterministic {
+        info!(
+            now = config.deterministic_now,
+            "Deterministic mode enabled: responses are reproducible"
+        );
+        Arc::new(DeterministicSearcher::new(searcher))
+    } else {
+        searcher
+    };
+
+    // Cache searches and answers outermost, so cached content is already redacted
+    let searcher: Arc<dyn Searcher> = if config.search_cache_capacity > 0 {
+        info!(
+            ttl_secs = config.search_cache_ttl_secs,
+            capacity = config.search_cache_capacity,
+            "Search cache enabled"
+        );
+        Arc::new(SearchCachingSearcher::new(
+            searcher,
+            SearchCache::new(
+                std::time::Duration::from_secs(config.search_cache_ttl_secs),
+                config.search_cache_capacity,
+            ),
+        ))
+    } else {
+        searcher
+    };
+    let answer_cache = match &config.answer_cache {
+        Some(value) => AnswerCacheBackend::parse(value)?,
+        None => None,
+    };
+    let answer_cache = match answer_cache {
+        Some(backend) => {
+            let store: Arc<dyn AnswerStore> = match backend {
+                AnswerCacheBackend::Memory => Arc::new(MemoryAnswerStore::default()),
+                AnswerCacheBackend::Redis(url) => {
+                    Arc::new(RedisAnswerStore::connect(&url).await.map_err(|e| {
+                        error!(error = %e, "FATAL: Failed to connect to the answer cache");
+                        e
+                    })?)
+                }
+            };
+            info!(
+                ttl_secs = config.answer_cache_ttl_secs,
+                "Answer cache enabled"
+            );
+            Some(Arc::new(CachingSearcher::new(
+                Arc::clone(&searcher),
+                store,
+                std::time::Duration::from_secs(config.answer_cache_ttl_secs),
+            )))
+        }
+        None => None,
+    };
+    let searcher: Arc<dyn Searcher> = match &answer_cache {
+        Some(cache) => Arc::clone(cache) as Arc<dyn Searcher>,
+        None => searcher,
+    };
+
+    metrics::set_section_frame_counts(&Default::default(), &searcher.section_counts());
+
+    // Further named indexes answer the Search and Ask requests naming them
+    let registry = match config.memvid_file_paths.split_first() {
+        Some(((default, _), others)) => {
+            let mut registry = SearcherRegistry::new(default.clone(), Arc::clone(&searcher));
+            for (name, path) in others {
+                let index = open_named_index(&config, name, path, &shared, llm.clone()).await?;
+                registry = registry.with_index(name.clone(), index);
+            }
+            info!(indexes = ?registry.names(), default = %default, "Serving named indexes");
+            registry
+        }
+        None => SearcherRegistry::new(DEFAULT_INDEX, Arc::clone(&searcher)),
+    };
+    let registry = Arc::new(registry);
+
+    // Start scheduled index refresh (real searcher only)
+    if let (Some(expr), Some(reloadable)) = (&config.reload_schedule, &reloadable) {
+        let schedule = CronSchedule::parse(expr)?;
+        let jitter = std::time::Duration::from_secs(config.reload_jitter_secs);
+        info!(schedule = %expr, jitter_secs = config.reload_jitter_secs, "Scheduled index reload enabled");
+        let retry = ReloadRetry {
+            initial: std::time::Duration::from_secs(config.reload_retry_initial_secs),
+            max: std::time::Duration::from_secs(config.reload_retry_max_secs),
+        };
+        let reloadable = Arc::clone(reloadable);
+        let alerts = AlertSender::new(config.alert_webhook.clone());
+        supervisor.spawn("reload-schedule", DEFAULT_RESTART, move || {
+            run_reload_schedule(
+                schedule.clone(),
+                jitter,
+                retry,
+                Arc::clone(&reloadable),
+                alerts.clone(),
+            )
+        });
+    }
+
+    // Runtime settings start from the environment and follow the config
+    // store, when one is configured
+    let (runtime_tx, runtime_rx) = tokio::sync::watch::channel(RuntimeConfig::from_config(&config));
+    if let Some(url) = &config.config_source {
+        let source = ConfigSource::parse(url)?;
+        let token = config.config_source_token.clone();
+        let poll_interval = std::time::Duration::from_secs(config.config_poll_secs);
+        let base = RuntimeConfig::from_config(&config);
+        let public_demo = config.public_demo;
+        supervisor.spawn("config-source", DEFAULT_RESTART, move || {
+            watch_config_source(
+                source.clone(),
+                token.clone(),
+                poll_interval,
+                base.clone(),
+                public_demo,
+                runtime_tx.clone(),
+            )
+        });
+    }
+
     // Create gRPC services
-    let memvid_service = MemvidGrpcService::new(Arc::clone(&searcher));
-    let health_service = HealthService::new(Arc::clone(&searcher));
+    // LLM spend and its daily cap are shared across API versions
+    let usage_ledger = Arc::new(UsageLedger::new(
+        LlmPricing {
+            prompt_usd_per_mtok: config.llm_prompt_cost_per_mtok,
+            completion_usd_per_mtok: config.llm_completion_cost_per_mtok,
+        },
+        config.llm_daily_cost_cap_usd,
+    ));
+    // Frames surfaced by either API version count towards coverage
+    let coverage = CoverageTracker::new(std::time::Duration::from_secs(
+        config.coverage_window_hours * 3600,
+    ));
+    let coverage = Arc::new(match &config.frame_stats_path {
+        Some(path) => coverage.with_store(path).map_err(|e| {
+            error!(error = %e, "FATAL: Failed to open the frame stats store");
+            e
+        })?,
+        None => coverage,
+    });
+    // Cursors issued by v2 are accepted by v1 Ask
+    let cursors = Arc::new(match &config.cursor_secret {
+        Some(secret) => CursorCodec::new(secret.as_bytes()),
+        None => CursorCodec::ephemeral(),
+    });
+    // Questions to either API version count towards the topic metric
+    let topics = Arc::new(TopicClassifier::default());
+    // ... and towards the analytics report
+    let query_stats = Arc::new(QueryStats::default().with_retention(retention.clone()));
+    if let Some(expr) = &config.analytics_report_schedule {
+        let reporter = AnalyticsReporter::new(
+            ReportFormat::parse(&config.analytics_report_format)?,
+            config.analytics_report_path.as_ref().map(PathBuf::from),
+            config.analytics_report_webhook.clone(),
+        );
+        info!(schedule = %expr, "Scheduled analytics report enabled");
+        let schedule = parse_report_schedule(expr)?;
+        let query_stats = Arc::clone(&query_stats);
+        supervisor.spawn("analytics-reports", DEFAULT_RESTART, move || {
+            run_analytics_reports(schedule.clone(), Arc::clone(&query_stats), reporter.clone())
+        });
+    }
+    // ... and read the profile memory card through one cache
+    let profiles = Arc::new(ProfileCache::default());
+    // Both API versions and Admin share the visibility overrides
+    let visibility = Arc::new(
+        match &config.visibility_file {
+            Some(path) => VisibilityStore::open(path)?,
+            None => VisibilityStore::new(),
+        }
+        .with_authenticated_keys(config.authenticated_api_keys.clone()),
+    );
+    let session_store: Arc<dyn SessionStore> = match SessionBackend::parse(&config.session_store) {
+        SessionBackend::Memory => Arc::new(MemorySessionStore::default()),
+        SessionBackend::File(dir) => Arc::new(FileSessionStore::open(dir)?),
+        SessionBackend::Redis(url) => {
+            Arc::new(RedisSessionStore::connect(&url).await.map_err(|e| {
+                error!(error = %e, "FATAL: Failed to connect to the session store");
+                e
+            })?)
+        }
+    };
+    let sessions = Arc::new(
+        SessionManager::new(session_store)
+            .with_ttl(std::time::Duration::from_secs(config.session_ttl_secs))
+            .with_max_turns(config.session_max_turns),
+    );
+    let citation_policy = CitationPolicy::parse(&config.citation_policy)?;
+    let mut aspect_planner = AspectPlanner::new(&config.ask_aspects);
+    if config.ask_aspect_llm {
+        if let Some(client) = &llm {
+            aspect_planner = aspect_planner.with_llm(Arc::clone(client));
+        }
+    }
+    let mut memvid_service = MemvidGrpcService::new(Arc::clone(&searcher))
+        .with_registry(Arc::clone(&registry))
+        .with_clock_skew_tolerance(std::time::Duration::from_secs(
+            config.clock_skew_tolerance_secs,
+        ))
+        .with_max_response_bytes(config.max_response_bytes)
+        .with_usage_ledger(Arc::clone(&usage_ledger))
+        .with_coverage_tracker(Arc::clone(&coverage))
+        .with_query_stats(Arc::clone(&query_stats))
+        .with_profile_cache(Arc::clone(&profiles))
+        .with_retention(retention.clone())
+        .with_cursor_codec(Arc::clone(&cursors))
+        .with_visibility_store(Arc::clone(&visibility))
+        .with_topic_classifier(Arc::clone(&topics))
+        .with_runtime_config(runtime_rx.clone())
+        .with_citation_policy(citation_policy)
+        .with_spell_correction(config.spell_correction)
+        .with_aspect_planner(Arc::new(aspect_planner))
+        .with_session_manager(sessions);
+    if let Some(client) = &llm {
+        memvid_service = memvid_service.with_llm_client(Arc::clone(client));
+    }
+    if config.deterministic {
+        memvid_service = memvid_service.with_deterministic(config.deterministic_now);
+    }
+    let memvid_service = Arc::new(memvid_service);
+    let readiness = Arc::new(Readiness::new(ReadinessPolicy::parse(
+        &config.readiness_policy,
+    )?));
 
-    // Start metrics server in background
+    // Warm the answer cache from the last snapshot before serving
+    let warmer = match &answer_cache {
+        Some(cache) => {
+            let mut warmer = CacheWarmer::new(Arc::clone(&memvid_service), Arc::clone(cache));
+            if let Some(path) = &config.warm_cache_corpus {
+                warmer =
+                    warmer.with_corpus(read_corpus(std::path::Path::new(path)).map_err(|e| {
+                        error!(error = %e, "FATAL: Failed to read the warm cache corpus");
+                        e
+                    })?);
+            }
+            if let Some(path) = &config.warm_cache_snapshot {
+                warmer = warmer.with_snapshot_path(path);
+                readiness.close(Gate::CachesRestored, "restoring answer cache snapshot");
+            }
+            match warmer.restore().await {
+                Ok(restored) if config.warm_cache_snapshot.is_some() => readiness.open(
+                    Gate::CachesRestored,
+                    format!("{} answers restored", restored),
+                ),
+                Ok(_) => {}
+                Err(e) => {
+                    warn!(error = %e, "Failed to restore the answer cache snapshot, starting cold");
+                    readiness.close(Gate::CachesRestored, e.to_string());
+                }
+            }
+            Some(Arc::new(warmer))
+        }
+        None => {
+            if config.warm_cache_corpus.is_some() || config.warm_cache_snapshot.is_some() {
+                warn!("WARM_CACHE_CORPUS and WARM_CACHE_SNAPSHOT need ANSWER_CACHE, ignoring them");
+            }
+            None
+        }
+    };
+    // memvid.v2 is served alongside v1 from the same searcher
+    let mut memvid_v2_service = MemvidV2Service::new(Arc::clone(&searcher))
+        .with_registry(registry)
+        .with_clock_skew_tolerance(std::time::Duration::from_secs(
+            config.clock_skew_tolerance_secs,
+        ))
+        .with_max_response_bytes(config.max_response_bytes)
+        .with_export_limits(
+            config.export_max_bytes_per_sec,
+            std::time::Duration::from_secs(config.export_ack_timeout_secs),
+        )
+        .with_usage_ledger(Arc::clone(&usage_ledger))
+        .with_coverage_tracker(Arc::clone(&coverage))
+        .with_query_stats(Arc::clone(&query_stats))
+        .with_profile_cache(profiles)
+        .with_retention(retention)
+        .with_cursor_codec(cursors)
+        .with_visibility_store(Arc::clone(&visibility))
+        .with_topic_classifier(topics)
+        .with_runtime_config(runtime_rx.clone())
+        .with_citation_policy(citation_policy);
+    if config.deterministic {
+        memvid_v2_service = memvid_v2_service.with_deterministic(config.deterministic_now);
+    }
+    let mut health_service = HealthService::new(Arc::clone(&searcher));
+    if let Some(reloadable) = &reloadable {
+        health_service = health_service.with_reloadable(Arc::clone(reloadable));
+    }
+    if let Some(chain) = &shared.embedder {
+        health_service = health_service.with_embedder_chain(Arc::clone(chain));
+    }
+    health_service = health_service
+        .with_drain(Arc::clone(&drain))
+        .with_usage_ledger(Arc::clone(&usage_ledger))
+        .with_readiness(Arc::clone(&readiness));
+
+    // Readiness waits for the warm-up queries to load the index's lazy
+    // segments and the embedder before real traffic
+    let queries = warmup_queries(
+        config.warmup_queries.as_deref(),
+        config.warmup_queries_file.as_deref(),
+    )
+    .map_err(|e| {
+        error!(error = %e, "FATAL: Failed to read the warm-up queries");
+        e
+    })?;
+    readiness.close(Gate::WarmupDone, "warm-up queries pending");
+    supervisor.spawn("warmup", DEFAULT_RESTART, {
+        let searcher = Arc::clone(&searcher);
+        move || {
+            warm_up(
+                Arc::clone(&searcher),
+                Arc::clone(&readiness),
+                queries.clone(),
+            )
+        }
+    });
+
+    // Start metrics server in background, with the debug routes when enabled
+    let metrics_app = metrics::metrics_router(metrics_handle)
+        .merge(debug::debug_router(
+            config.debug_token.as_deref(),
+            Arc::clone(&request_log),
+        ))
+        .merge(lifecycle_router(
+            config.lifecycle_endpoint,
+            Arc::clone(&drain),
+        ));
+    // It keeps serving while the gRPC server drains and stops after it
     let metrics_port = config.metrics_port;
-    tokio::spawn(async move {
-        metrics::start_metrics_server(metrics_port, metrics_handle).await;
+    let metrics_bind = config.metrics_bind_address.clone();
+    supervisor.spawn_graceful("metrics-server", DEFAULT_RESTART, move |shutdown| {
+        let bind = metrics_bind.clone();
+        let app = metrics_app.clone();
+        async move {
+            metrics::start_metrics_server_on(&bind, metrics_port, app, shutdown.requested()).await;
+        }
     });
 
     // Start gRPC server with configurable bind address
@@ -234,13 +1116,147 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         bind_str.parse()?
     };
 
-    info!(addr = %grpc_addr, "Starting gRPC server");
+    // Log the effective capability report so support can see how this instance is configured
+    let listeners = vec![
+        ListenerInfo {
+            name: "grpc".to_string(),
+            address: grpc_addr.to_string(),
+            port: config.grpc_port,
+            tls: config.tls_cert_path.is_some(),
+        },
+        ListenerInfo {
+            name: "metrics".to_string(),
+            address: config.metrics_bind_address.clone(),
+            port: config.metrics_port,
+            tls: false,
+        },
+    ];
+    let report = Arc::new(CapabilityReport::new(&config, searcher.as_ref(), listeners));
+    info!(capabilities = %report.to_json(), "Effective capability report");
+    let mut admin_service = AdminService::new(Arc::clone(&report), Arc::clone(&searcher))
+        .with_coverage_tracker(coverage)
+        .with_visibility_store(Arc::clone(&visibility))
+        .with_request_log(Arc::clone(&request_log))
+        .with_query_stats(query_stats)
+        .with_log_level(log_level)
+        .with_index_writes(config.index_writes)
+        .with_backup_dir(config.backup_dir.as_ref().map(PathBuf::from))
+        .with_index_dir(config.index_dir.as_ref().map(PathBuf::from));
+    if let Some(reloadable) = &reloadable {
+        admin_service = admin_service.with_reloadable(Arc::clone(reloadable));
+    }
+    if let Some(warmer) = warmer {
+        admin_service = admin_service.with_cache_warmer(warmer);
+    }
+
+    if !config.admin_rpcs {
+        info!("Admin RPCs disabled");
+    } else if config.admin_token.is_none() {
+        warn!("ADMIN_RPCS is set without ADMIN_TOKEN: every Admin RPC is refused");
+    } else if config.index_writes {
+        info!("Index writes enabled for Admin callers with the admin token");
+    }
+    // Every Admin RPC needs the admin token, checked before any handler runs
+    let admin_auth = AdminAuth::new(config.admin_token.clone());
+    let admin_server = config
+        .admin_rpcs
+        .then(|| AdminServer::with_interceptor(admin_service, admin_auth));
 
-    Server::builder()
-        .add_service(MemvidServiceServer::new(memvid_service))
+    // Query APIs share one per-client budget; health checks are never limited
+    let rate_limiter = RateLimiter::new(config.rate_limit_per_minute)
+        .with_burst(config.rate_limit_burst)
+        .with_authenticated_keys(config.authenticated_api_keys.clone())
+        .with_runtime_config(runtime_rx);
+    // Bearer tokens are validated first, so the limit applies per subject
+    let jwt = match (&config.jwt_issuer, &config.jwt_jwks_url) {
+        (Some(issuer), Some(url)) => {
+            let validator = JwtValidator::new(
+                issuer.clone(),
+                config.jwt_audience.clone(),
+                config.jwt_required,
+            );
+            info!(
+                issuer = %issuer,
+                required = config.jwt_required,
+                "JWT bearer-token validation enabled"
+            );
+            let client = JwksClient::new(url.clone());
+            let refresh = std::time::Duration::from_secs(config.jwt_jwks_refresh_secs);
+            supervisor.spawn("jwks-refresh", DEFAULT_RESTART, {
+                let validator = validator.clone();
+                move || refresh_jwks(client.clone(), validator.clone(), refresh)
+            });
+            Some(validator)
+        }
+        _ => None,
+    };
+    let acl_mode = AclMode::parse(&config.acl_enforcement)?;
+    if acl_mode == AclMode::Enforce {
+        info!("ACL enforcement enabled: restricted frames are dropped for unauthorized callers");
+    }
+    let query_interceptor = QueryInterceptor {
+        jwt,
+        acl: AclInterceptor::new(acl_mode),
+        rate_limiter,
+    };
+
+    let mut server = Server::builder();
+    if let (Some(cert), Some(key)) = (&config.tls_cert_path, &config.tls_key_path) {
+        let tls = server_tls_config(cert, key).map_err(|e| {
+            error!(error = %e, "FATAL: Failed to load the TLS certificate");
+            e
+        })?;
+        server = server.tls_config(tls).map_err(|e| {
+            error!(error = %e, "FATAL: Invalid TLS certificate or key");
+            e
+        })?;
+    }
+
+    info!(
+        addr = %grpc_addr,
+        tls = config.tls_cert_path.is_some(),
+        "Starting gRPC server"
+    );
+
+    let server = server
+        .layer(RequestLogLayer::new(request_log))
+        .layer(DeadlineLayer)
+        .add_service(InterceptedService::new(
+            MemvidServiceServer::from_arc(memvid_service),
+            query_interceptor.clone(),
+        ))
+        .add_service(MemvidServiceV2Server::with_interceptor(
+            memvid_v2_service,
+            query_interceptor,
+        ))
         .add_service(HealthServer::new(health_service))
-        .serve(grpc_addr)
-        .await?;
+        .add_optional_service(admin_server)
+        .serve_with_shutdown(grpc_addr, {
+            let drain = Arc::clone(&drain);
+            async move { drain.stop_accepting().await }
+        });
 
-    Ok(())
+    // A background task failing for good drains the server too
+    tokio::pin!(server);
+    let mut task_failure = None;
+    tokio::select! {
+        result = &mut server => result?,
+        () = drain.deadline() => {}
+        summary = supervisor.failed() => {
+            error!(tasks = %summary, "Background task failed, shutting down");
+            drain.start("background task failure");
+            task_failure = Some(summary);
+            tokio::select! {
+                result = &mut server => result?,
+                () = drain.deadline() => {}
+            }
+        }
+    }
+    info!("Server stopped");
+    supervisor.shutdown(TASK_SHUTDOWN_TIMEOUT).await;
+
+    match task_failure {
+        Some(summary) => Err(format!("Background task failed: {}", summary).into()),
+        None => Ok(()),
+    }
 }
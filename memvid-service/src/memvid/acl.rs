@@ -0,0 +1,55 @@
+//! Frame access control passed through to memvid-core.
+//!
+//! Frames may carry ACLs, which memvid-core matches against the caller's
+//! [`AclContext`]. In [`AclMode::Audit`] (the default) every frame is still
+//! returned and violations are only reported; in [`AclMode::Enforce`] frames
+//! the caller may not read are dropped from search and ask results.
+
+/// How memvid-core applies frame ACLs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum AclMode {
+    /// Report violations, but return every frame.
+    #[default]
+    Audit,
+    /// Drop frames the caller may not read.
+    Enforce,
+}
+
+impl AclMode {
+    /// Parse "audit" or "enforce".
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "audit" => Ok(Self::Audit),
+            "enforce" => Ok(Self::Enforce),
+            other => Err(format!("expected audit or enforce, got '{}'", other)),
+        }
+    }
+}
+
+/// Identity of a caller, matched against frame ACLs.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct AclContext {
+    /// Tenant the caller belongs to
+    pub tenant_id: Option<String>,
+    /// Caller subject (e.g., the bearer token's `sub`)
+    pub subject_id: Option<String>,
+    /// Caller roles, sorted
+    pub roles: Vec<String>,
+    /// Caller groups, sorted
+    pub group_ids: Vec<String>,
+    /// How violations are handled
+    pub mode: AclMode,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_acl_mode() {
+        assert_eq!(AclMode::parse("audit"), Ok(AclMode::Audit));
+        assert_eq!(AclMode::parse(" Enforce "), Ok(AclMode::Enforce));
+        assert!(AclMode::parse("strict").is_err());
+        assert_eq!(AclMode::default(), AclMode::Audit);
+    }
+}
@@ -0,0 +1,269 @@
+//! Acronym dictionary learned from the loaded corpus.
+//!
+//! Definitions written as `Long Form (ACR)` or `ACR (Long Form)` are
+//! collected when an index is loaded. Queries are then expanded in both
+//! directions, so recruiter shorthand ("IIoT") finds the spelled-out form
+//! ("Industrial IoT") and vice versa, and both forms are highlighted.
+
+use std::collections::BTreeMap;
+
+/// Longest acronym accepted, in characters.
+const MAX_ACRONYM_LEN: usize = 10;
+
+/// Words that do not contribute a letter to an acronym.
+const STOP_WORDS: &[&str] = &["of", "and", "the", "for", "to", "in", "&"];
+
+/// Bidirectional map between acronyms and their long forms.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AcronymMap {
+    /// Lowercased acronym => (acronym, long form)
+    by_acronym: BTreeMap<String, (String, String)>,
+    /// Lowercased long form => lowercased acronym
+    by_long_form: BTreeMap<String, String>,
+}
+
+impl AcronymMap {
+    /// Collect acronym definitions from corpus texts.
+    ///
+    /// The first definition of an acronym wins.
+    pub fn from_texts<'a>(texts: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut map = Self::default();
+        for text in texts {
+            for (acronym, long_form) in definitions(text) {
+                map.insert(&acronym, &long_form);
+            }
+        }
+        map
+    }
+
+    /// Add a definition unless the acronym or long form is already known.
+    pub fn insert(&mut self, acronym: &str, long_form: &str) {
+        let key = acronym.to_lowercase();
+        let long_key = long_form.to_lowercase();
+        if self.by_acronym.contains_key(&key) || self.by_long_form.contains_key(&long_key) {
+            return;
+        }
+        self.by_acronym
+            .insert(key.clone(), (acronym.to_string(), long_form.to_string()));
+        self.by_long_form.insert(long_key, key);
+    }
+
+    /// Number of known acronyms.
+    pub fn len(&self) -> usize {
+        self.by_acronym.len()
+    }
+
+    /// Whether no acronyms are known.
+    pub fn is_empty(&self) -> bool {
+        self.by_acronym.is_empty()
+    }
+
+    /// Known definitions as (acronym, long form), ordered by acronym.
+    pub fn definitions(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.by_acronym
+            .values()
+            .map(|(acronym, long_form)| (acronym.as_str(), long_form.as_str()))
+    }
+
+    /// Terms to highlight for `query`: each acronym or long form it
+    /// mentions, together with its counterpart.
+    pub fn highlight_terms(&self, query: &str) -> Vec<String> {
+        let lower = query.to_lowercase();
+        let mut terms = Vec::new();
+        for (key, (acronym, long_form)) in &self.by_acronym {
+            let mentioned =
+                contains_phrase(&lower, key) || contains_phrase(&lower, &long_form.to_lowercase());
+            if mentioned {
+                terms.push(acronym.clone());
+                terms.push(long_form.clone());
+            }
+        }
+        terms
+    }
+
+    /// Append the counterpart of every acronym or long form in `query`.
+    pub fn expand_query(&self, query: &str) -> String {
+        let lower = query.to_lowercase();
+        let mut expanded = query.to_string();
+        for term in self.highlight_terms(query) {
+            if !contains_phrase(&lower, &term.to_lowercase()) {
+                expanded.push(' ');
+                expanded.push_str(&term);
+            }
+        }
+        expanded
+    }
+}
+
+/// Whether `phrase` occurs in `text` as whole words (both lowercased).
+pub(super) fn contains_phrase(text: &str, phrase: &str) -> bool {
+    text.match_indices(phrase).any(|(start, _)| {
+        let end = start + phrase.len();
+        !text[..start]
+            .chars()
+            .next_back()
+            .is_some_and(char::is_alphanumeric)
+            && !text[end..]
+                .chars()
+                .next()
+                .is_some_and(char::is_alphanumeric)
+    })
+}
+
+/// Whether `token` looks like an acronym ("IIoT", "SOC2", "CI/CD" is not).
+fn is_acronym(token: &str) -> bool {
+    let len = token.chars().count();
+    (2..=MAX_ACRONYM_LEN).contains(&len)
+        && token.chars().all(char::is_alphanumeric)
+        && token.chars().next().is_some_and(|c| c.is_uppercase())
+        && token.chars().filter(|c| c.is_uppercase()).count() >= 2
+}
+
+/// Letters an acronym abbreviates: its capitals and digits, lowercased.
+fn acronym_letters(acronym: &str) -> String {
+    acronym
+        .chars()
+        .filter(|c| c.is_uppercase() || c.is_ascii_digit())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Letters a word contributes: the capitals of a mixed-case word ("IoT"
+/// => "it"), otherwise its first letter.
+fn word_letters(word: &str) -> String {
+    let capitals: String = word
+        .chars()
+        .filter(|c| c.is_uppercase())
+        .flat_map(char::to_lowercase)
+        .collect();
+    if capitals.chars().count() >= 2 {
+        capitals
+    } else {
+        word.chars()
+            .next()
+            .into_iter()
+            .flat_map(char::to_lowercase)
+            .collect()
+    }
+}
+
+/// The shortest run of trailing `words` whose letters spell `letters`.
+fn long_form_suffix<'a>(words: &[&'a str], letters: &str) -> Option<Vec<&'a str>> {
+    let mut spelled = String::new();
+    for start in (0..words.len()).rev() {
+        let word = words[start];
+        if !STOP_WORDS.contains(&word.to_lowercase().as_str()) {
+            spelled.insert_str(0, &word_letters(word));
+        }
+        if spelled == letters {
+            return Some(words[start..].to_vec());
+        }
+        if spelled.len() >= letters.len() {
+            return None;
+        }
+    }
+    None
+}
+
+/// Find `Long Form (ACR)` and `ACR (Long Form)` definitions in `text`.
+fn definitions(text: &str) -> Vec<(String, String)> {
+    let mut found = Vec::new();
+    let mut rest = text;
+
+    while let Some(open) = rest.find('(') {
+        let before = &rest[..open];
+        let after = &rest[open + 1..];
+        let Some(close) = after.find(')') else {
+            break;
+        };
+        let inner = after[..close].trim();
+        rest = &after[close + 1..];
+
+        let words: Vec<&str> = before
+            .split(|c: char| c.is_whitespace() || matches!(c, ',' | ';' | ':' | '.'))
+            .filter(|w| !w.is_empty())
+            .collect();
+
+        if is_acronym(inner) {
+            // Long Form (ACR)
+            if let Some(long) = long_form_suffix(&words, &acronym_letters(inner)) {
+                found.push((inner.to_string(), long.join(" ")));
+            }
+        } else if let Some(acronym) = words.last().filter(|w| is_acronym(w)) {
+            // ACR (Long Form)
+            let inner_words: Vec<&str> = inner.split_whitespace().collect();
+            if long_form_suffix(&inner_words, &acronym_letters(acronym))
+                .is_some_and(|long| long.len() == inner_words.len())
+            {
+                found.push((acronym.to_string(), inner_words.join(" ")));
+            }
+        }
+    }
+
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_learns_both_definition_styles() {
+        let map = AcronymMap::from_texts([
+            "Built an Industrial IoT (IIoT) platform for plants.",
+            "Ran the SRE (Site Reliability Engineering) practice.",
+            "Mobile (iOS, Android) and CI/CD (GitHub Actions) work.",
+        ]);
+
+        let definitions: Vec<(&str, &str)> = map.definitions().collect();
+        assert_eq!(
+            definitions,
+            vec![
+                ("IIoT", "Industrial IoT"),
+                ("SRE", "Site Reliability Engineering")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_skips_stop_words() {
+        let map = AcronymMap::from_texts(["Head of the Department of Defense (DoD) programs"]);
+        assert_eq!(
+            map.definitions().collect::<Vec<_>>(),
+            vec![("DoD", "Department of Defense")]
+        );
+    }
+
+    #[test]
+    fn test_expands_in_both_directions() {
+        let map = AcronymMap::from_texts(["Industrial IoT (IIoT)"]);
+
+        assert_eq!(map.expand_query("iiot edge"), "iiot edge Industrial IoT");
+        assert_eq!(
+            map.expand_query("industrial iot experience"),
+            "industrial iot experience IIoT"
+        );
+        // Unrelated and partial-word mentions are left alone
+        assert_eq!(map.expand_query("iiotx"), "iiotx");
+    }
+
+    #[test]
+    fn test_highlight_terms() {
+        let map = AcronymMap::from_texts(["Industrial IoT (IIoT)"]);
+        assert_eq!(map.highlight_terms("IIoT"), vec!["IIoT", "Industrial IoT"]);
+        assert!(map.highlight_terms("rust").is_empty());
+    }
+
+    #[test]
+    fn test_first_definition_wins() {
+        let map = AcronymMap::from_texts([
+            "Site Reliability Engineering (SRE)",
+            "Software Release Enablement (SRE)",
+        ]);
+        assert_eq!(map.len(), 1);
+        assert_eq!(
+            map.definitions().next(),
+            Some(("SRE", "Site Reliability Engineering"))
+        );
+    }
+}
@@ -0,0 +1,374 @@
+//! Contact-detail redaction for publicly exposed deployments.
+//!
+//! `AnonymizingSearcher` removes email addresses, phone numbers and
+//! LinkedIn/GitHub profile URLs from everything the searcher returns:
+//! snippets, answers and memory-card values. The resume content itself is
+//! left intact, so the service can answer questions about the candidate
+//! without handing out ways to contact them directly.
+
+use async_trait::async_trait;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use super::acronyms::AcronymMap;
+use super::instrumented::LockDiagnostics;
+use super::searcher::{
+    AskRequest, AskResponse, EntitySummary, FrameMetadata, FrameText, IndexFeatures, SearchRequest,
+    SearchResponse, SearchResult, Searcher, StateResponse,
+};
+use super::spelling::SpellDictionary;
+use super::suggest::SuggestionIndex;
+use crate::error::ServiceError;
+
+/// Replacement for a redacted email address.
+pub const EMAIL_PLACEHOLDER: &str = "[email]";
+
+/// Replacement for a redacted phone number.
+pub const PHONE_PLACEHOLDER: &str = "[phone]";
+
+/// Replacement for a redacted profile URL.
+pub const URL_PLACEHOLDER: &str = "[url]";
+
+/// Hosts whose URLs identify a person.
+const PROFILE_HOSTS: &[&str] = &["linkedin.com/", "github.com/"];
+
+/// Digits in a phone number, including country code.
+const PHONE_DIGITS: std::ops::RangeInclusive<usize> = 10..=15;
+
+/// Redact contact details from `text`.
+pub fn redact(text: &str) -> String {
+    let mut spans: Vec<(usize, usize, &'static str)> = Vec::new();
+    spans.extend(email_spans(text).map(|(s, e)| (s, e, EMAIL_PLACEHOLDER)));
+    spans.extend(url_spans(text).map(|(s, e)| (s, e, URL_PLACEHOLDER)));
+    spans.extend(phone_spans(text).map(|(s, e)| (s, e, PHONE_PLACEHOLDER)));
+    if spans.is_empty() {
+        return text.to_string();
+    }
+    spans.sort_by_key(|&(start, _, _)| start);
+
+    let mut out = String::with_capacity(text.len());
+    let mut pos = 0;
+    for (start, end, placeholder) in spans {
+        // Overlapping spans (e.g. digits inside an email) were already replaced
+        if start < pos {
+            continue;
+        }
+        out.push_str(&text[pos..start]);
+        out.push_str(placeholder);
+        pos = end;
+    }
+    out.push_str(&text[pos..]);
+    out
+}
+
+fn is_email_local(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || matches!(b, b'.' | b'_' | b'%' | b'+' | b'-')
+}
+
+fn is_domain(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || matches!(b, b'.' | b'-')
+}
+
+fn is_url(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b"-._~:/?#%=&+".contains(&b)
+}
+
+/// Byte ranges of email addresses (all matched bytes are ASCII).
+fn email_spans(text: &str) -> impl Iterator<Item = (usize, usize)> + '_ {
+    let bytes = text.as_bytes();
+    text.match_indices('@').filter_map(move |(at, _)| {
+        let start = (0..at)
+            .rev()
+            .take_while(|&i| is_email_local(bytes[i]))
+            .last()?;
+        let mut end = (at + 1..bytes.len())
+            .take_while(|&i| is_domain(bytes[i]))
+            .last()?
+            + 1;
+        // A sentence-ending period is not part of the domain
+        while bytes[end - 1] == b'.' {
+            end -= 1;
+        }
+        let domain = &text[at + 1..end];
+        let valid = domain
+            .split('.')
+            .all(|label| !label.is_empty() && !label.starts_with('-'))
+            && domain.contains('.');
+        valid.then_some((start, end))
+    })
+}
+
+/// Byte ranges of URLs pointing at a profile host.
+fn url_spans(text: &str) -> impl Iterator<Item = (usize, usize)> + '_ {
+    let bytes = text.as_bytes();
+    let lower = text.to_ascii_lowercase();
+    let mut spans: Vec<(usize, usize)> = PROFILE_HOSTS
+        .iter()
+        .flat_map(|host| {
+            lower
+                .match_indices(host)
+                .map(|(i, _)| i)
+                .collect::<Vec<_>>()
+        })
+        .map(|host_start| {
+            let start = (0..host_start)
+                .rev()
+                .take_while(|&i| is_url(bytes[i]))
+                .last()
+                .unwrap_or(host_start);
+            let mut end = (host_start..bytes.len())
+                .take_while(|&i| is_url(bytes[i]))
+                .last()
+                .map_or(host_start, |i| i + 1);
+            while matches!(bytes[end - 1], b'.' | b',' | b':' | b'?') {
+                end -= 1;
+            }
+            (start, end)
+        })
+        .collect();
+    spans.sort_unstable();
+    spans.into_iter()
+}
+
+/// Byte ranges of phone numbers: 10-15 digits joined by single separators,
+/// optionally led by `+`. Runs made only of 4-digit groups (year lists like
+/// "2019 2020 2021") are not phone numbers.
+fn phone_spans(text: &str) -> impl Iterator<Item = (usize, usize)> + '_ {
+    let bytes = text.as_bytes();
+    let mut spans = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let starts_number = bytes[i].is_ascii_digit() || matches!(bytes[i], b'+' | b'(');
+        let after_word = i > 0 && bytes[i - 1].is_ascii_alphanumeric();
+        if !starts_number || after_word {
+            i += 1;
+            continue;
+        }
+
+        let start = i;
+        let mut groups: Vec<usize> = Vec::new();
+        let mut digits_in_group = 0;
+        let mut last_digit = None;
+        let mut j = i;
+        while j < bytes.len() {
+            let b = bytes[j];
+            if b.is_ascii_digit() {
+                digits_in_group += 1;
+                last_digit = Some(j);
+            } else if matches!(b, b'-' | b'.' | b' ' | b'(' | b')') || (b == b'+' && j == start) {
+                if digits_in_group > 0 {
+                    groups.push(digits_in_group);
+                    digits_in_group = 0;
+                }
+                // Two separators in a row end the number, except ") " and " ("
+                let next = bytes.get(j + 1).copied().unwrap_or(b'x');
+                let paired = matches!((b, next), (b')', b' ') | (b' ', b'(') | (b'+', b'('));
+                if !next.is_ascii_digit() && !paired {
+                    break;
+                }
+            } else {
+                break;
+            }
+            j += 1;
+        }
+        if digits_in_group > 0 {
+            groups.push(digits_in_group);
+        }
+
+        let total: usize = groups.iter().sum();
+        let year_list = groups.len() > 1 && groups.iter().all(|&g| g == 4);
+        let glued = bytes
+            .get(last_digit.map_or(j, |d| d + 1))
+            .is_some_and(|b| b.is_ascii_alphanumeric());
+        match last_digit {
+            Some(end) if PHONE_DIGITS.contains(&total) && !year_list && !glued => {
+                spans.push((start, end + 1));
+                i = end + 1;
+            }
+            _ => i = j.max(i + 1),
+        }
+    }
+
+    spans.into_iter()
+}
+
+fn redact_hits(hits: &mut [SearchResult]) {
+    for hit in hits {
+        hit.title = redact(&hit.title);
+        hit.snippet = redact(&hit.snippet);
+    }
+}
+
+/// Searcher that redacts contact details from all returned content.
+pub struct AnonymizingSearcher {
+    inner: Arc<dyn Searcher>,
+}
+
+impl AnonymizingSearcher {
+    /// Wrap `inner`.
+    pub fn new(inner: Arc<dyn Searcher>) -> Self {
+        Self { inner }
+    }
+}
+
+#[async_trait]
+impl Searcher for AnonymizingSearcher {
+    async fn search(&self, request: SearchRequest) -> Result<SearchResponse, ServiceError> {
+        let mut response = self.inner.search(request).await?;
+        redact_hits(&mut response.hits);
+        Ok(response)
+    }
+
+    async fn get_state(
+        &self,
+        entity: &str,
+        slot: Option<&str>,
+    ) -> Result<StateResponse, ServiceError> {
+        let mut response = self.inner.get_state(entity, slot).await?;
+        for value in response.slots.values_mut() {
+            *value = redact(value);
+        }
+        Ok(response)
+    }
+
+    async fn list_entities(&self) -> Result<Vec<EntitySummary>, ServiceError> {
+        self.inner.list_entities().await
+    }
+
+    async fn ask(&self, request: AskRequest) -> Result<AskResponse, ServiceError> {
+        let mut response = self.inner.ask(request).await?;
+        response.answer = redact(&response.answer);
+        redact_hits(&mut response.evidence);
+        Ok(response)
+    }
+
+    async fn export_frames(
+        &self,
+        after: Option<u64>,
+        limit: usize,
+    ) -> Result<Vec<FrameMetadata>, ServiceError> {
+        let mut frames = self.inner.export_frames(after, limit).await?;
+        for frame in &mut frames {
+            frame.title = redact(&frame.title);
+        }
+        Ok(frames)
+    }
+
+    async fn frame_texts(
+        &self,
+        after: Option<u64>,
+        limit: usize,
+    ) -> Result<Vec<FrameText>, ServiceError> {
+        let mut frames = self.inner.frame_texts(after, limit).await?;
+        for frame in &mut frames {
+            frame.title = redact(&frame.title);
+            frame.text = redact(&frame.text);
+        }
+        Ok(frames)
+    }
+
+    fn frame_count(&self) -> i32 {
+        self.inner.frame_count()
+    }
+
+    fn memvid_file(&self) -> String {
+        self.inner.memvid_file()
+    }
+
+    fn generation(&self) -> u64 {
+        self.inner.generation()
+    }
+
+    fn index_features(&self) -> IndexFeatures {
+        self.inner.index_features()
+    }
+
+    fn section_counts(&self) -> BTreeMap<String, i32> {
+        self.inner.section_counts()
+    }
+
+    fn lock_diagnostics(&self) -> LockDiagnostics {
+        self.inner.lock_diagnostics()
+    }
+
+    fn acronyms(&self) -> Arc<AcronymMap> {
+        self.inner.acronyms()
+    }
+
+    fn spelling(&self) -> Arc<SpellDictionary> {
+        self.inner.spelling()
+    }
+
+    fn suggestions(&self) -> Arc<SuggestionIndex> {
+        self.inner.suggestions()
+    }
+
+    fn is_ready(&self) -> bool {
+        self.inner.is_ready()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memvid::MockSearcher;
+
+    #[test]
+    fn test_redacts_emails() {
+        assert_eq!(
+            redact("Reach me at jane.doe+cv@mail.example.com."),
+            "Reach me at [email]."
+        );
+        assert_eq!(redact(r#"{"email":"a@b.io"}"#), r#"{"email":"[email]"}"#);
+        // Handles and bare @ signs are not addresses
+        assert_eq!(
+            redact("Ping @jane or me@localhost"),
+            "Ping @jane or me@localhost"
+        );
+    }
+
+    #[test]
+    fn test_redacts_profile_urls() {
+        assert_eq!(
+            redact("See https://www.linkedin.com/in/jane-doe, or github.com/jdoe."),
+            "See [url], or [url]."
+        );
+        assert_eq!(
+            redact("Shipped on example.com/blog"),
+            "Shipped on example.com/blog"
+        );
+    }
+
+    #[test]
+    fn test_redacts_phone_numbers() {
+        assert_eq!(redact("Call +1 (555) 123-4567 today"), "Call [phone] today");
+        assert_eq!(redact("Tel: +49 89 1234 5678"), "Tel: [phone]");
+        assert_eq!(redact("555.123.4567"), "[phone]");
+    }
+
+    #[test]
+    fn test_keeps_numbers_that_are_not_phones() {
+        for text in [
+            "Led a team of 12 engineers from 2019-2024",
+            "Roles in 2015 2016 2017 2018",
+            "Reduced latency by 60% across 1,200 devices",
+            "Order ID A1234567890B",
+        ] {
+            assert_eq!(redact(text), text);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_searcher_redacts_profile() {
+        let searcher = AnonymizingSearcher::new(Arc::new(MockSearcher::new()));
+        let state = searcher.get_state("__profile__", None).await.unwrap();
+        let profile = &state.slots["data"];
+
+        assert!(profile.contains(r#""email": "[email]""#));
+        assert!(profile.contains(r#""linkedin": "[url]""#));
+        // Non-contact content is untouched and the JSON stays valid
+        assert!(profile.contains("Siemens"));
+        assert!(serde_json::from_str::<serde_json::Value>(profile).is_ok());
+    }
+}
@@ -0,0 +1,936 @@
+//! Cache of Ask responses, in process or shared through Redis.
+//!
+//! [`CachingSearcher`] serves a repeated question from the cache instead of
+//! retrieving (and synthesizing) again. Entries are keyed by every request
+//! field that affects the answer and expire after a TTL.
+//!
+//! When the wrapped index's generation changes (a reload swapped in a new
+//! index), the cache is invalidated. With [`RedisAnswerStore`] all replicas
+//! share one cache: invalidation bumps a shared epoch that namespaces the
+//! keys and publishes it on a pub/sub channel, so every replica stops
+//! reading entries from the old index at once. A replica that missed the
+//! message picks the epoch up again when its subscription reconnects.
+//!
+//! The cache never fails a request: store errors are logged and count as
+//! misses.
+//!
+//! AskStream is cached too: a hit is replayed as events, and a miss streams
+//! from the wrapped searcher and stores the answer once the stream completes.
+//!
+//! A [`CacheSnapshot`] carries warmed answers to disk, so a replica started
+//! later restores them into its store instead of starting cold.
+
+use async_trait::async_trait;
+use redis::aio::ConnectionManager;
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
+use tokio_stream::StreamExt;
+use tracing::{debug, info, warn};
+
+use super::acronyms::AcronymMap;
+use super::instrumented::LockDiagnostics;
+use super::searcher::{
+    replay_ask, AskEvent, AskEventStream, AskRequest, AskResponse, EntitySummary, FrameMetadata,
+    FrameText, IndexFeatures, SearchRequest, SearchResponse, Searcher, StateResponse,
+};
+use super::spelling::SpellDictionary;
+use super::suggest::SuggestionIndex;
+use crate::error::ServiceError;
+use crate::metrics;
+
+/// Default lifetime of a cached answer.
+pub const DEFAULT_ANSWER_TTL: Duration = Duration::from_secs(300);
+
+/// Maximum number of answers kept by [`MemoryAnswerStore`].
+pub const DEFAULT_ANSWER_CAPACITY: usize = 1024;
+
+/// Prefix of the Redis keys and channel used by [`RedisAnswerStore`].
+const REDIS_NAMESPACE: &str = "memvid:answers";
+
+/// `cache` label of the answer cache's metrics.
+const CACHE_NAME: &str = "answer";
+
+/// Delay before a lost Redis subscription is re-established.
+const RESUBSCRIBE_DELAY: Duration = Duration::from_secs(5);
+
+/// Where cached answers are kept.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AnswerCacheBackend {
+    /// In this process only
+    Memory,
+    /// In Redis at the given URL, shared by all replicas
+    Redis(String),
+}
+
+impl AnswerCacheBackend {
+    /// Parse `off`, `memory` or a `redis://` / `rediss://` URL.
+    pub fn parse(value: &str) -> Result<Option<Self>, String> {
+        let value = value.trim();
+        match value.to_ascii_lowercase().as_str() {
+            "" | "off" => Ok(None),
+            "memory" => Ok(Some(Self::Memory)),
+            lower if lower.starts_with("redis://") || lower.starts_with("rediss://") => {
+                Ok(Some(Self::Redis(value.to_string())))
+            }
+            _ => Err(format!(
+                "expected off, memory or a redis:// URL, got '{}'",
+                value
+            )),
+        }
+    }
+}
+
+/// Storage for cached answers.
+#[async_trait]
+pub trait AnswerStore: Send + Sync {
+    /// Cached JSON for `key`, if any.
+    async fn get(&self, key: &str) -> Result<Option<String>, ServiceError>;
+
+    /// Cache `value` under `key` for `ttl`.
+    async fn put(&self, key: &str, value: String, ttl: Duration) -> Result<(), ServiceError>;
+
+    /// Drop every cached answer, for all users of the store.
+    async fn invalidate(&self) -> Result<(), ServiceError>;
+}
+
+/// In-process answer store.
+pub struct MemoryAnswerStore {
+    capacity: usize,
+    entries: Mutex<HashMap<String, (Instant, String)>>,
+}
+
+impl MemoryAnswerStore {
+    /// Create a store holding at most `capacity` answers.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, (Instant, String)>> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Default for MemoryAnswerStore {
+    fn default() -> Self {
+        Self::new(DEFAULT_ANSWER_CAPACITY)
+    }
+}
+
+#[async_trait]
+impl AnswerStore for MemoryAnswerStore {
+    async fn get(&self, key: &str) -> Result<Option<String>, ServiceError> {
+        Ok(self
+            .lock()
+            .get(key)
+            .filter(|(expires, _)| *expires > Instant::now())
+            .map(|(_, value)| value.clone()))
+    }
+
+    async fn put(&self, key: &str, value: String, ttl: Duration) -> Result<(), ServiceError> {
+        let now = Instant::now();
+        let mut entries = self.lock();
+        let before = entries.len();
+        entries.retain(|_, (expires, _)| *expires > now);
+        metrics::record_cache_evictions(CACHE_NAME, "expired", before - entries.len());
+
+        if entries.len() >= self.capacity && !entries.contains_key(key) {
+            let soonest = entries
+                .iter()
+                .min_by_key(|(_, (expires, _))| *expires)
+                .map(|(k, _)| k.clone());
+            if let Some(soonest) = soonest {
+                entries.remove(&soonest);
+                metrics::record_cache_evictions(CACHE_NAME, "capacity", 1);
+            }
+        }
+        if self.capacity > 0 {
+            entries.insert(key.to_string(), (now + ttl, value));
+        }
+        metrics::set_cache_entries(CACHE_NAME, entries.len());
+        Ok(())
+    }
+
+    async fn invalidate(&self) -> Result<(), ServiceError> {
+        let mut entries = self.lock();
+        metrics::record_cache_evictions(CACHE_NAME, "invalidated", entries.len());
+        entries.clear();
+        metrics::set_cache_entries(CACHE_NAME, 0);
+        Ok(())
+    }
+}
+
+/// Answer store in Redis, shared by every replica using the same server.
+pub struct RedisAnswerStore {
+    connection: ConnectionManager,
+    epoch: Arc<AtomicU64>,
+}
+
+impl RedisAnswerStore {
+    /// Connect to Redis at `url`, which must pass the egress allowlist, and
+    /// follow invalidations from other replicas.
+    pub async fn connect(url: &str) -> Result<Self, ServiceError> {
+        let client = redis::Client::open(url).map_err(redis_error)?;
+        if let redis::ConnectionAddr::Tcp(host, _) | redis::ConnectionAddr::TcpTls { host, .. } =
+            &client.get_connection_info().addr
+        {
+            crate::egress::current()
+                .check(host)
+                .map_err(ServiceError::Internal)?;
+        }
+        let mut connection = client.get_connection_manager().await.map_err(redis_error)?;
+        let epoch: Option<u64> = connection.get(epoch_key()).await.map_err(redis_error)?;
+        let epoch = Arc::new(AtomicU64::new(epoch.unwrap_or(0)));
+        info!(
+            epoch = epoch.load(Ordering::Acquire),
+            "Connected to Redis answer cache"
+        );
+
+        tokio::spawn(follow_invalidations(
+            client,
+            connection.clone(),
+            Arc::downgrade(&epoch),
+        ));
+        Ok(Self { connection, epoch })
+    }
+
+    fn entry_key(&self, key: &str) -> String {
+        format!(
+            "{}:{}:{}",
+            REDIS_NAMESPACE,
+            self.epoch.load(Ordering::Acquire),
+            key
+        )
+    }
+}
+
+#[async_trait]
+impl AnswerStore for RedisAnswerStore {
+    async fn get(&self, key: &str) -> Result<Option<String>, ServiceError> {
+        let mut connection = self.connection.clone();
+        connection
+            .get(self.entry_key(key))
+            .await
+            .map_err(redis_error)
+    }
+
+    async fn put(&self, key: &str, value: String, ttl: Duration) -> Result<(), ServiceError> {
+        let mut connection = self.connection.clone();
+        connection
+            .set_ex(self.entry_key(key), value, ttl.as_secs().max(1))
+            .await
+            .map_err(redis_error)
+    }
+
+    async fn invalidate(&self) -> Result<(), ServiceError> {
+        let mut connection = self.connection.clone();
+        let epoch: u64 = connection.incr(epoch_key(), 1).await.map_err(redis_error)?;
+        self.epoch.fetch_max(epoch, Ordering::AcqRel);
+        // Entries of the old epoch are left to expire
+        let _: i64 = connection
+            .publish(channel(), epoch)
+            .await
+            .map_err(redis_error)?;
+        info!(epoch, "Published answer cache invalidation");
+        Ok(())
+    }
+}
+
+fn epoch_key() -> String {
+    format!("{}:epoch", REDIS_NAMESPACE)
+}
+
+fn channel() -> String {
+    format!("{}:invalidate", REDIS_NAMESPACE)
+}
+
+fn redis_error(e: redis::RedisError) -> ServiceError {
+    ServiceError::Internal(format!("Redis answer cache: {}", e))
+}
+
+/// Adopt epochs published by other replicas until the store is dropped.
+async fn follow_invalidations(
+    client: redis::Client,
+    mut connection: ConnectionManager,
+    epoch: Weak<AtomicU64>,
+) {
+    loop {
+        let subscribed = async {
+            let mut pubsub = client.get_async_pubsub().await?;
+            pubsub.subscribe(channel()).await?;
+            // Read the epoch after subscribing, so no bump falls in between
+            let current: Option<u64> = connection.get(epoch_key()).await?;
+            Ok::<_, redis::RedisError>((pubsub, current.unwrap_or(0)))
+        }
+        .await;
+
+        match subscribed {
+            Ok((mut pubsub, current)) => {
+                let Some(local) = epoch.upgrade() else {
+                    return;
+                };
+                local.fetch_max(current, Ordering::AcqRel);
+                drop(local);
+
+                let mut messages = pubsub.on_message();
+                while let Some(message) = messages.next().await {
+                    let Some(local) = epoch.upgrade() else {
+                        return;
+                    };
+                    match message.get_payload::<u64>() {
+                        Ok(published) => {
+                            local.fetch_max(published, Ordering::AcqRel);
+                            debug!(epoch = published, "Answer cache invalidated");
+                        }
+                        Err(e) => warn!(error = %e, "Ignoring invalid answer cache epoch"),
+                    }
+                }
+                warn!("Answer cache invalidation subscription lost, resubscribing");
+            }
+            Err(e) => warn!(error = %e, "Failed to subscribe to answer cache invalidations"),
+        }
+        if epoch.strong_count() == 0 {
+            return;
+        }
+        tokio::time::sleep(RESUBSCRIBE_DELAY).await;
+    }
+}
+
+/// Cache key of an Ask request: a hash of every field affecting the answer.
+/// Cached answer under `key`, recording the lookup in the metrics.
+async fn lookup(store: &dyn AnswerStore, key: &str) -> Option<AskResponse> {
+    match store.get(key).await {
+        Ok(Some(json)) => match serde_json::from_str(&json) {
+            Ok(response) => {
+                metrics::record_answer_cache("hit");
+                metrics::record_cache_lookup(CACHE_NAME, true);
+                return Some(response);
+            }
+            Err(e) => warn!(error = %e, "Ignoring undecodable cached answer"),
+        },
+        Ok(None) => {}
+        Err(e) => {
+            metrics::record_answer_cache("error");
+            warn!(error = %e, "Failed to read answer cache");
+        }
+    }
+
+    metrics::record_answer_cache("miss");
+    metrics::record_cache_lookup(CACHE_NAME, false);
+    None
+}
+
+/// Cache `response` under `key` for `ttl`, logging failures.
+async fn store_answer(store: &dyn AnswerStore, key: &str, response: &AskResponse, ttl: Duration) {
+    match serde_json::to_string(response) {
+        Ok(json) => {
+            if let Err(e) = store.put(key, json, ttl).await {
+                metrics::record_answer_cache("error");
+                warn!(error = %e, "Failed to write answer cache");
+            }
+        }
+        Err(e) => warn!(error = %e, "Failed to encode answer for the cache"),
+    }
+}
+
+fn cache_key(request: &AskRequest) -> String {
+    let filters: BTreeMap<_, _> = request.filters.iter().collect();
+    let fields = format!(
+        "{:?}|{}|{}|{:?}|{}|{}|{}|{:?}|{:?}|{:?}|{:?}|{:?}|{:?}",
+        request.question.trim(),
+        request.use_llm,
+        request.top_k,
+        filters,
+        request.start,
+        request.end,
+        request.snippet_chars,
+        request.mode,
+        request.uri,
+        request.cursor,
+        request.as_of_frame,
+        request.as_of_ts,
+        request.adaptive,
+    );
+    // Callers with different ACL contexts may see different frames; keys of
+    // requests without one are unchanged
+    let fields = match &request.acl {
+        Some(acl) => format!("{}|{:?}", fields, acl),
+        None => fields,
+    };
+    Sha256::digest(fields.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Answers warmed for one index, written to disk and restored at startup.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheSnapshot {
+    /// Index file the answers were computed from
+    pub memvid_file: String,
+    /// Frame count of that index, to tell rebuilt files apart
+    pub frame_count: i32,
+    /// When the snapshot was taken (Unix seconds)
+    pub created_at: i64,
+    /// Cached answers by cache key
+    pub entries: Vec<SnapshotEntry>,
+}
+
+/// One cached answer of a [`CacheSnapshot`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotEntry {
+    /// Cache key of the request
+    pub key: String,
+    /// The cached response
+    pub answer: AskResponse,
+}
+
+impl CacheSnapshot {
+    /// Read a snapshot written by [`CacheSnapshot::write`]; None when there
+    /// is no file at `path`.
+    pub fn read(path: &Path) -> Result<Option<Self>, ServiceError> {
+        let json = match std::fs::read_to_string(path) {
+            Ok(json) => json,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => {
+                return Err(ServiceError::Internal(format!(
+                    "Failed to read cache snapshot {}: {}",
+                    path.display(),
+                    e
+                )))
+            }
+        };
+        serde_json::from_str(&json).map(Some).map_err(|e| {
+            ServiceError::Internal(format!("Invalid cache snapshot {}: {}", path.display(), e))
+        })
+    }
+
+    /// Write the snapshot to `path` via a temporary file, so a crash never
+    /// leaves a truncated snapshot behind.
+    pub fn write(&self, path: &Path) -> Result<(), ServiceError> {
+        let json = serde_json::to_string(self)
+            .map_err(|e| ServiceError::Internal(format!("Failed to encode snapshot: {}", e)))?;
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, json)
+            .and_then(|()| std::fs::rename(&tmp, path))
+            .map_err(|e| {
+                ServiceError::Internal(format!(
+                    "Failed to write cache snapshot {}: {}",
+                    path.display(),
+                    e
+                ))
+            })
+    }
+}
+
+/// Searcher that answers repeated questions from an [`AnswerStore`].
+pub struct CachingSearcher {
+    inner: Arc<dyn Searcher>,
+    store: Arc<dyn AnswerStore>,
+    ttl: Duration,
+    generation: AtomicU64,
+}
+
+impl CachingSearcher {
+    /// Wrap `inner`, caching its answers in `store` for `ttl`.
+    pub fn new(inner: Arc<dyn Searcher>, store: Arc<dyn AnswerStore>, ttl: Duration) -> Self {
+        let generation = AtomicU64::new(inner.generation());
+        Self {
+            inner,
+            store,
+            ttl,
+            generation,
+        }
+    }
+
+    /// Invalidate the store when the wrapped index changed since last seen.
+    async fn check_generation(&self) {
+        let current = self.inner.generation();
+        if self.generation.swap(current, Ordering::AcqRel) == current {
+            return;
+        }
+        info!(
+            generation = current,
+            "Index changed, invalidating answer cache"
+        );
+        if let Err(e) = self.store.invalidate().await {
+            warn!(error = %e, "Failed to invalidate answer cache");
+        }
+    }
+
+    /// Answer `request` through the cache, returning its snapshot entry.
+    pub async fn warm(&self, request: AskRequest) -> Result<SnapshotEntry, ServiceError> {
+        let key = cache_key(&request);
+        let answer = self.ask(request).await?;
+        Ok(SnapshotEntry { key, answer })
+    }
+
+    /// Snapshot `entries` as answers of the wrapped index.
+    pub fn snapshot(&self, entries: Vec<SnapshotEntry>) -> CacheSnapshot {
+        CacheSnapshot {
+            memvid_file: self.inner.memvid_file(),
+            frame_count: self.inner.frame_count(),
+            created_at: chrono::Utc::now().timestamp(),
+            entries,
+        }
+    }
+
+    /// Put the entries of `snapshot` into the store for the cache TTL,
+    /// returning how many; none when it was taken of another index.
+    pub async fn restore(&self, snapshot: CacheSnapshot) -> Result<usize, ServiceError> {
+        if snapshot.memvid_file != self.inner.memvid_file()
+            || snapshot.frame_count != self.inner.frame_count()
+        {
+            warn!(
+                snapshot_file = %snapshot.memvid_file,
+                snapshot_frames = snapshot.frame_count,
+                "Ignoring cache snapshot of another index"
+            );
+            return Ok(0);
+        }
+        self.check_generation().await;
+        let restored = snapshot.entries.len();
+        for entry in snapshot.entries {
+            let json = serde_json::to_string(&entry.answer).map_err(|e| {
+                ServiceError::Internal(format!("Failed to encode cached answer: {}", e))
+            })?;
+            self.store.put(&entry.key, json, self.ttl).await?;
+        }
+        Ok(restored)
+    }
+}
+
+#[async_trait]
+impl Searcher for CachingSearcher {
+    async fn search(&self, request: SearchRequest) -> Result<SearchResponse, ServiceError> {
+        self.inner.search(request).await
+    }
+
+    async fn get_state(
+        &self,
+        entity: &str,
+        slot: Option<&str>,
+    ) -> Result<StateResponse, ServiceError> {
+        self.inner.get_state(entity, slot).await
+    }
+
+    async fn list_entities(&self) -> Result<Vec<EntitySummary>, ServiceError> {
+        self.inner.list_entities().await
+    }
+
+    async fn ask(&self, request: AskRequest) -> Result<AskResponse, ServiceError> {
+        self.check_generation().await;
+        let key = cache_key(&request);
+        if let Some(response) = lookup(self.store.as_ref(), &key).await {
+            return Ok(response);
+        }
+
+        let response = self.inner.ask(request).await?;
+        store_answer(self.store.as_ref(), &key, &response, self.ttl).await;
+        Ok(response)
+    }
+
+    async fn ask_stream(&self, request: AskRequest) -> Result<AskEventStream, ServiceError> {
+        self.check_generation().await;
+        let key = cache_key(&request);
+        if let Some(response) = lookup(self.store.as_ref(), &key).await {
+            return Ok(replay_ask(response));
+        }
+
+        // Pass events through as they arrive and cache the answer once the
+        // stream completes; a failed or abandoned stream is not cached
+        let mut events = self.inner.ask_stream(request).await?;
+        let store = Arc::clone(&self.store);
+        let ttl = self.ttl;
+        let (tx, rx) = tokio::sync::mpsc::channel(16);
+        tokio::spawn(async move {
+            let mut evidence = Vec::new();
+            let mut answer = String::new();
+            while let Some(event) = events.next().await {
+                let finished = match &event {
+                    Ok(AskEvent::Evidence(hits)) => {
+                        evidence = hits.clone();
+                        None
+                    }
+                    Ok(AskEvent::AnswerDelta(delta)) => {
+                        answer.push_str(delta);
+                        None
+                    }
+                    Ok(AskEvent::Done { stats, next_cursor }) => Some(AskResponse {
+                        answer: std::mem::take(&mut answer),
+                        evidence: std::mem::take(&mut evidence),
+                        stats: stats.clone(),
+                        next_cursor: next_cursor.clone(),
+                    }),
+                    Err(_) => None,
+                };
+                let failed = event.is_err();
+                if tx.send(event).await.is_err() || failed {
+                    return;
+                }
+                if let Some(response) = finished {
+                    store_answer(store.as_ref(), &key, &response, ttl).await;
+                }
+            }
+        });
+        Ok(Box::pin(tokio_stream::wrappers::ReceiverStream::new(rx)))
+    }
+
+    async fn export_frames(
+        &self,
+        after: Option<u64>,
+        limit: usize,
+    ) -> Result<Vec<FrameMetadata>, ServiceError> {
+        self.inner.export_frames(after, limit).await
+    }
+
+    async fn frame_texts(
+        &self,
+        after: Option<u64>,
+        limit: usize,
+    ) -> Result<Vec<FrameText>, ServiceError> {
+        self.inner.frame_texts(after, limit).await
+    }
+
+    fn frame_count(&self) -> i32 {
+        self.inner.frame_count()
+    }
+
+    fn memvid_file(&self) -> String {
+        self.inner.memvid_file()
+    }
+
+    fn generation(&self) -> u64 {
+        self.inner.generation()
+    }
+
+    fn index_features(&self) -> IndexFeatures {
+        self.inner.index_features()
+    }
+
+    fn section_counts(&self) -> BTreeMap<String, i32> {
+        self.inner.section_counts()
+    }
+
+    fn lock_diagnostics(&self) -> LockDiagnostics {
+        self.inner.lock_diagnostics()
+    }
+
+    fn acronyms(&self) -> Arc<AcronymMap> {
+        self.inner.acronyms()
+    }
+
+    fn spelling(&self) -> Arc<SpellDictionary> {
+        self.inner.spelling()
+    }
+
+    fn suggestions(&self) -> Arc<SuggestionIndex> {
+        self.inner.suggestions()
+    }
+
+    fn is_ready(&self) -> bool {
+        self.inner.is_ready()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memvid::reloadable::{LoadFuture, ReloadableSearcher, SearcherLoader};
+    use crate::memvid::searcher::AskMode;
+    use crate::memvid::{AclContext, MockSearcher};
+    use std::sync::atomic::AtomicUsize;
+
+    fn ask(question: &str) -> AskRequest {
+        AskRequest {
+            question: question.to_string(),
+            use_llm: false,
+            top_k: 5,
+            filters: HashMap::new(),
+            start: 0,
+            end: 0,
+            snippet_chars: 200,
+            mode: AskMode::Hybrid,
+            uri: None,
+            cursor: None,
+            as_of_frame: None,
+            as_of_ts: None,
+            adaptive: None,
+            acl: None,
+        }
+    }
+
+    /// Loader counting the asks served by the loaded searchers.
+    fn counting_loader(asks: Arc<AtomicUsize>) -> SearcherLoader {
+        struct Counting(MockSearcher, Arc<AtomicUsize>);
+
+        #[async_trait]
+        impl Searcher for Counting {
+            async fn search(&self, r: SearchRequest) -> Result<SearchResponse, ServiceError> {
+                self.0.search(r).await
+            }
+            async fn get_state(
+                &self,
+                entity: &str,
+                slot: Option<&str>,
+            ) -> Result<StateResponse, ServiceError> {
+                self.0.get_state(entity, slot).await
+            }
+            async fn list_entities(&self) -> Result<Vec<EntitySummary>, ServiceError> {
+                self.0.list_entities().await
+            }
+            async fn ask(&self, r: AskRequest) -> Result<AskResponse, ServiceError> {
+                self.1.fetch_add(1, Ordering::SeqCst);
+                self.0.ask(r).await
+            }
+            async fn export_frames(
+                &self,
+                after: Option<u64>,
+                limit: usize,
+            ) -> Result<Vec<FrameMetadata>, ServiceError> {
+                self.0.export_frames(after, limit).await
+            }
+            async fn frame_texts(
+                &self,
+                after: Option<u64>,
+                limit: usize,
+            ) -> Result<Vec<FrameText>, ServiceError> {
+                self.0.frame_texts(after, limit).await
+            }
+            fn frame_count(&self) -> i32 {
+                self.0.frame_count()
+            }
+            fn memvid_file(&self) -> String {
+                self.0.memvid_file()
+            }
+            fn generation(&self) -> u64 {
+                self.0.generation()
+            }
+            fn index_features(&self) -> IndexFeatures {
+                self.0.index_features()
+            }
+            fn section_counts(&self) -> BTreeMap<String, i32> {
+                self.0.section_counts()
+            }
+            fn lock_diagnostics(&self) -> LockDiagnostics {
+                self.0.lock_diagnostics()
+            }
+            fn acronyms(&self) -> Arc<AcronymMap> {
+                self.0.acronyms()
+            }
+            fn spelling(&self) -> Arc<SpellDictionary> {
+                self.0.spelling()
+            }
+            fn suggestions(&self) -> Arc<SuggestionIndex> {
+                self.0.suggestions()
+            }
+            fn is_ready(&self) -> bool {
+                true
+            }
+        }
+
+        Arc::new(move |_path: String| {
+            let searcher = Counting(MockSearcher::new(), Arc::clone(&asks));
+            Box::pin(async move { Ok(Arc::new(searcher) as Arc<dyn Searcher>) }) as LoadFuture
+        })
+    }
+
+    #[test]
+    fn test_parse_backend() {
+        assert_eq!(AnswerCacheBackend::parse("off").unwrap(), None);
+        assert_eq!(
+            AnswerCacheBackend::parse("Memory").unwrap(),
+            Some(AnswerCacheBackend::Memory)
+        );
+        assert_eq!(
+            AnswerCacheBackend::parse("redis://cache:6379/0").unwrap(),
+            Some(AnswerCacheBackend::Redis(
+                "redis://cache:6379/0".to_string()
+            ))
+        );
+        assert!(AnswerCacheBackend::parse("memcached://cache").is_err());
+    }
+
+    #[test]
+    fn test_cache_key_covers_answer_fields() {
+        let base = ask("Rust experience");
+        assert_eq!(cache_key(&base), cache_key(&ask("  Rust experience ")));
+
+        let mut llm = base.clone();
+        llm.use_llm = true;
+        let mut filtered = base.clone();
+        filtered
+            .filters
+            .insert("tag".to_string(), "skills".to_string());
+        let mut paged = base.clone();
+        paged.cursor = Some("5".to_string());
+        let mut restricted = base.clone();
+        restricted.acl = Some(AclContext {
+            roles: vec!["recruiter".to_string()],
+            ..Default::default()
+        });
+        for other in [llm, filtered, paged, restricted] {
+            assert_ne!(cache_key(&base), cache_key(&other));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_memory_store_expires_and_evicts() {
+        let store = MemoryAnswerStore::new(1);
+        store
+            .put("a", "1".to_string(), Duration::from_secs(60))
+            .await
+            .unwrap();
+        store
+            .put("b", "2".to_string(), Duration::from_secs(60))
+            .await
+            .unwrap();
+        assert_eq!(store.get("a").await.unwrap(), None);
+        assert_eq!(store.get("b").await.unwrap().as_deref(), Some("2"));
+
+        store
+            .put("c", "3".to_string(), Duration::ZERO)
+            .await
+            .unwrap();
+        assert_eq!(store.get("c").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_shared_store_serves_replicas_until_reload() {
+        let path =
+            std::env::temp_dir().join(format!("memvid-answer-cache-{}.mv2", std::process::id()));
+        std::fs::write(&path, "v1").unwrap();
+        let asks = Arc::new(AtomicUsize::new(0));
+        let index = Arc::new(
+            ReloadableSearcher::open_with(
+                path.to_string_lossy(),
+                counting_loader(Arc::clone(&asks)),
+            )
+            .await
+            .unwrap(),
+        );
+        // Two replicas sharing one store
+        let store: Arc<dyn AnswerStore> = Arc::new(MemoryAnswerStore::default());
+        let first = CachingSearcher::new(index.clone(), Arc::clone(&store), DEFAULT_ANSWER_TTL);
+        let second = CachingSearcher::new(index.clone(), store, DEFAULT_ANSWER_TTL);
+
+        let answer = first.ask(ask("Rust experience")).await.unwrap();
+        let cached = second.ask(ask("Rust experience")).await.unwrap();
+        assert_eq!(cached.answer, answer.answer);
+        assert_eq!(cached.evidence.len(), answer.evidence.len());
+        assert_eq!(asks.load(Ordering::SeqCst), 1);
+
+        first.ask(ask("Python experience")).await.unwrap();
+        assert_eq!(asks.load(Ordering::SeqCst), 2);
+
+        // A new index generation invalidates the shared entries
+        index.reload().await.unwrap();
+        // The reload's embedding drift probes ask the indexes directly
+        asks.store(0, Ordering::SeqCst);
+        first.ask(ask("Rust experience")).await.unwrap();
+        assert_eq!(asks.load(Ordering::SeqCst), 1);
+        first.ask(ask("Rust experience")).await.unwrap();
+        assert_eq!(asks.load(Ordering::SeqCst), 1);
+
+        std::fs::remove_file(path).ok();
+    }
+
+    #[tokio::test]
+    async fn test_streamed_answers_are_cached() {
+        let path =
+            std::env::temp_dir().join(format!("memvid-answer-stream-{}.mv2", std::process::id()));
+        std::fs::write(&path, "v1").unwrap();
+        let asks = Arc::new(AtomicUsize::new(0));
+        let index = ReloadableSearcher::open_with(
+            path.to_string_lossy(),
+            counting_loader(Arc::clone(&asks)),
+        )
+        .await
+        .unwrap();
+        let store: Arc<dyn AnswerStore> = Arc::new(MemoryAnswerStore::default());
+        let searcher = CachingSearcher::new(Arc::new(index), store, DEFAULT_ANSWER_TTL);
+
+        let streamed: Vec<_> = searcher
+            .ask_stream(ask("Rust experience"))
+            .await
+            .unwrap()
+            .collect()
+            .await;
+        let answer: String = streamed
+            .iter()
+            .filter_map(|event| match event {
+                Ok(AskEvent::AnswerDelta(delta)) => Some(delta.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(asks.load(Ordering::SeqCst), 1);
+
+        // The completed stream serves later asks and streams
+        assert_eq!(
+            searcher.ask(ask("Rust experience")).await.unwrap().answer,
+            answer
+        );
+        let replayed = searcher
+            .ask_stream(ask("Rust experience"))
+            .await
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(replayed.len(), streamed.len());
+        assert_eq!(asks.load(Ordering::SeqCst), 1);
+
+        std::fs::remove_file(path).ok();
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_restores_into_a_cold_store() {
+        let path =
+            std::env::temp_dir().join(format!("memvid-cache-snapshot-{}.json", std::process::id()));
+        let asks = Arc::new(AtomicUsize::new(0));
+        let index: Arc<dyn Searcher> = Arc::new(
+            ReloadableSearcher::open_with("resume.mv2", counting_loader(Arc::clone(&asks)))
+                .await
+                .unwrap(),
+        );
+
+        let warm = CachingSearcher::new(
+            Arc::clone(&index),
+            Arc::new(MemoryAnswerStore::default()),
+            DEFAULT_ANSWER_TTL,
+        );
+        let entry = warm.warm(ask("Rust experience")).await.unwrap();
+        warm.snapshot(vec![entry]).write(&path).unwrap();
+        assert_eq!(asks.load(Ordering::SeqCst), 1);
+
+        // A new replica starts from the snapshot
+        let cold = CachingSearcher::new(
+            index,
+            Arc::new(MemoryAnswerStore::default()),
+            DEFAULT_ANSWER_TTL,
+        );
+        let snapshot = CacheSnapshot::read(&path).unwrap().unwrap();
+        assert_eq!(cold.restore(snapshot.clone()).await.unwrap(), 1);
+        cold.ask(ask("Rust experience")).await.unwrap();
+        assert_eq!(asks.load(Ordering::SeqCst), 1);
+
+        // Snapshots of another index are ignored
+        let other = CacheSnapshot {
+            frame_count: snapshot.frame_count + 1,
+            ..snapshot
+        };
+        assert_eq!(cold.restore(other).await.unwrap(), 0);
+        assert!(CacheSnapshot::read(&path.with_extension("missing"))
+            .unwrap()
+            .is_none());
+
+        std::fs::remove_file(path).ok();
+    }
+}
@@ -0,0 +1,354 @@
+//! Multi-aspect retrieval for broad Ask questions.
+//!
+//! A broad question ("Tell me about her background") spans several parts of
+//! a resume, and a single retrieval tends to return only the part most
+//! similar to its wording. A decomposed ask plans sub-queries for the
+//! question's aspects: its keywords with each configured aspect ("background
+//! skills"), or focused sub-questions written by the LLM. It then retrieves
+//! for the question and every sub-query in parallel and fuses the candidate
+//! lists with reciprocal rank fusion, like an ensemble ask. With an LLM the
+//! answer is written from the fused evidence; otherwise it is the fused
+//! context.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::task::JoinSet;
+use tracing::{warn, Instrument, Span};
+
+use super::citations::{check_citations, CitationPolicy};
+use super::deadline;
+use super::ensemble::{fuse, keywords, HitKey};
+use super::searcher::{AskRequest, AskResponse, Searcher};
+use super::visibility::context_answer;
+use crate::error::ServiceError;
+use crate::llm::{ChatMessage, LlmClient};
+
+/// Most sub-queries retrieved in addition to the question.
+pub const MAX_ASPECTS: usize = 6;
+
+/// Aspects a question is decomposed into unless configured otherwise.
+pub const DEFAULT_ASK_ASPECTS: [&str; 4] = ["experience", "skills", "leadership", "education"];
+
+const PLANNER_PROMPT: &str = "You plan searches over a candidate's resume. Split the \
+question into at most {max} short, self-contained search queries, each about a different \
+aspect of it (e.g. roles, skills, leadership, education, projects). Reply with one query per \
+line and nothing else.";
+
+/// A sub-query of a decomposed question.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Aspect {
+    /// Configured aspect name (empty for sub-questions written by the LLM)
+    pub name: String,
+    /// Query retrieved for it
+    pub query: String,
+}
+
+/// Retrieval statistics of one aspect.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AspectStats {
+    /// Configured aspect name (empty for sub-questions written by the LLM)
+    pub aspect: String,
+    /// Query retrieved for it
+    pub query: String,
+    /// Candidates it retrieved
+    pub candidates: usize,
+    /// Candidates the question itself did not retrieve
+    pub new_candidates: usize,
+    /// Retrieval time in milliseconds
+    pub retrieval_ms: i32,
+}
+
+/// Plans the sub-queries of a decomposed ask.
+#[derive(Debug, Clone)]
+pub struct AspectPlanner {
+    aspects: Vec<String>,
+    llm: Option<Arc<LlmClient>>,
+}
+
+impl Default for AspectPlanner {
+    fn default() -> Self {
+        Self::new(DEFAULT_ASK_ASPECTS)
+    }
+}
+
+impl AspectPlanner {
+    /// Decompose questions into `aspects` (at most [`MAX_ASPECTS`]).
+    pub fn new(aspects: impl IntoIterator<Item = impl AsRef<str>>) -> Self {
+        let mut seen = HashSet::new();
+        let aspects = aspects
+            .into_iter()
+            .map(|aspect| aspect.as_ref().trim().to_lowercase())
+            .filter(|aspect| !aspect.is_empty() && seen.insert(aspect.clone()))
+            .take(MAX_ASPECTS)
+            .collect();
+        Self { aspects, llm: None }
+    }
+
+    /// Have `client` write the sub-questions, falling back to the aspects
+    /// when it fails.
+    pub fn with_llm(mut self, client: Arc<LlmClient>) -> Self {
+        self.llm = Some(client);
+        self
+    }
+
+    /// Configured aspects.
+    pub fn aspects(&self) -> &[String] {
+        &self.aspects
+    }
+
+    /// Sub-queries of `question`: written by the LLM when one is configured
+    /// and `allow_llm` is set, otherwise its keywords with each aspect.
+    pub async fn plan(&self, question: &str, allow_llm: bool) -> Vec<Aspect> {
+        if let Some(client) = self.llm.as_ref().filter(|_| allow_llm) {
+            match client.generate(&planner_messages(question)).await {
+                Ok(text) => {
+                    let queries = parse_sub_queries(&text, question);
+                    if !queries.is_empty() {
+                        return queries
+                            .into_iter()
+                            .map(|query| Aspect {
+                                name: String::new(),
+                                query,
+                            })
+                            .collect();
+                    }
+                    warn!(
+                        model = client.model(),
+                        "LLM planned no sub-queries, using the configured aspects"
+                    );
+                }
+                Err(e) => warn!(
+                    error = %e,
+                    model = client.model(),
+                    "LLM aspect planning failed, using the configured aspects"
+                ),
+            }
+        }
+        self.aspect_queries(question)
+    }
+
+    /// `question`'s keywords with each configured aspect it does not
+    /// already mention.
+    pub fn aspect_queries(&self, question: &str) -> Vec<Aspect> {
+        let keywords = keywords(question);
+        self.aspects
+            .iter()
+            .filter(|aspect| !keywords.contains(aspect))
+            .map(|aspect| Aspect {
+                name: aspect.clone(),
+                query: keywords
+                    .iter()
+                    .chain(std::iter::once(aspect))
+                    .cloned()
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            })
+            .collect()
+    }
+}
+
+/// Messages asking the LLM to split `question` into sub-queries.
+fn planner_messages(question: &str) -> Vec<ChatMessage> {
+    vec![
+        ChatMessage {
+            role: "system",
+            content: PLANNER_PROMPT.replace("{max}", &MAX_ASPECTS.to_string()),
+        },
+        ChatMessage {
+            role: "user",
+            content: question.trim().to_string(),
+        },
+    ]
+}
+
+/// Sub-queries listed one per line in `text`, without list markers, the
+/// question itself or repeats.
+fn parse_sub_queries(text: &str, question: &str) -> Vec<String> {
+    let question = question.trim().to_lowercase();
+    let mut seen = HashSet::new();
+    text.lines()
+        .map(|line| {
+            line.trim()
+                .trim_start_matches(|c: char| c.is_ascii_digit())
+                .trim_start_matches(['-', '*', '•', '.', ')'])
+                .trim()
+                .trim_matches('"')
+                .to_string()
+        })
+        .filter(|query| {
+            let lower = query.to_lowercase();
+            !query.is_empty() && lower != question && seen.insert(lower)
+        })
+        .take(MAX_ASPECTS)
+        .collect()
+}
+
+/// Ask `request` and its `aspects` in parallel, returning the question's
+/// response with the fused evidence and the statistics of every aspect.
+///
+/// With `llm` and `use_llm` set, the answer is written from the fused
+/// evidence; without `llm`, the searcher answers the question itself and
+/// its citations are checked against the fused evidence.
+///
+/// # Errors
+/// The first failed retrieval.
+pub async fn aspect_ask(
+    searcher: Arc<dyn Searcher>,
+    request: AskRequest,
+    aspects: Vec<Aspect>,
+    llm: Option<&LlmClient>,
+) -> Result<(AskResponse, Vec<AspectStats>), ServiceError> {
+    let top_k = request.top_k.max(0) as usize;
+    let llm = llm.filter(|_| request.use_llm);
+    let started = Instant::now();
+
+    // Each retrieval keeps the request's deadline and passes the search limit
+    let deadline = deadline::current();
+    let mut tasks = JoinSet::new();
+    let queries = std::iter::once(request.question.clone())
+        .chain(aspects.iter().map(|aspect| aspect.query.clone()));
+    for (index, query) in queries.enumerate() {
+        let searcher = Arc::clone(&searcher);
+        let request = AskRequest {
+            question: query,
+            use_llm: index == 0 && request.use_llm && llm.is_none(),
+            ..request.clone()
+        };
+        let retrieval = async move {
+            let started = Instant::now();
+            let response = searcher.ask(request).await;
+            (index, response, started.elapsed().as_millis() as i32)
+        };
+        tasks.spawn(deadline::scope(deadline, retrieval).instrument(Span::current()));
+    }
+    let mut results: Vec<Option<(AskResponse, i32)>> = vec![None; aspects.len() + 1];
+    while let Some(joined) = tasks.join_next().await {
+        let (index, response, retrieval_ms) = joined
+            .map_err(|e| ServiceError::Internal(format!("Aspect retrieval task error: {}", e)))?;
+        results[index] = Some((response?, retrieval_ms));
+    }
+    let mut results = results.into_iter().flatten();
+    let Some((mut response, _)) = results.next() else {
+        return Err(ServiceError::Internal(
+            "Aspect retrieval returned no response".to_string(),
+        ));
+    };
+
+    let asked: HashSet<HitKey> = response.evidence.iter().map(HitKey::of).collect();
+    let mut lists = vec![std::mem::take(&mut response.evidence)];
+    let mut stats = Vec::with_capacity(aspects.len());
+    for (aspect, (aspect_response, retrieval_ms)) in aspects.into_iter().zip(results) {
+        stats.push(AspectStats {
+            aspect: aspect.name,
+            query: aspect.query,
+            candidates: aspect_response.evidence.len(),
+            new_candidates: aspect_response
+                .evidence
+                .iter()
+                .filter(|hit| !asked.contains(&HitKey::of(hit)))
+                .count(),
+            retrieval_ms,
+        });
+        lists.push(aspect_response.evidence);
+    }
+
+    let answered = lists[0].clone();
+    let mut fused = fuse(lists);
+    response.stats.candidates_retrieved = fused.len() as i32;
+    response.stats.retrieval_ms = started.elapsed().as_millis() as i32;
+    fused.truncate(top_k);
+    match llm {
+        Some(client) if !fused.is_empty() => {
+            match client.synthesize(&request.question, &fused).await {
+                Ok(answer) => response.answer = answer,
+                Err(e) => {
+                    warn!(
+                        error = %e,
+                        model = client.model(),
+                        "LLM synthesis failed, answering with the fused context"
+                    );
+                    response.answer = context_answer(&fused);
+                    response.stats.used_fallback = true;
+                }
+            }
+        }
+        None if request.use_llm => {
+            // Cite the fused evidence the answer is returned with
+            response.answer =
+                check_citations(&response.answer, &answered, &fused, CitationPolicy::Lenient)
+                    .answer;
+        }
+        _ => response.answer = context_answer(&fused),
+    }
+    response.stats.results_returned = fused.len() as i32;
+    response.evidence = fused;
+    // Fused evidence has no page order to continue
+    response.next_cursor = None;
+    Ok((response, stats))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memvid::{AskMode, MockSearcher};
+
+    #[test]
+    fn test_aspect_queries_pair_keywords_with_aspects() {
+        let planner = AspectPlanner::new(["Skills", "leadership", "skills", " "]);
+        assert_eq!(planner.aspects(), ["skills", "leadership"]);
+
+        let aspects = planner.aspect_queries("Tell me about her skills and background");
+        assert_eq!(
+            aspects,
+            vec![Aspect {
+                name: "leadership".to_string(),
+                query: "skills background leadership".to_string(),
+            }]
+        );
+        assert_eq!(planner.aspect_queries("Who is she?")[0].query, "skills");
+    }
+
+    #[test]
+    fn test_parse_sub_queries() {
+        let text = "1. Rust experience\n- \"Kubernetes projects\"\n\n* rust EXPERIENCE\n\
+                    2) Tell me about her\n";
+        assert_eq!(
+            parse_sub_queries(text, "Tell me about her"),
+            ["Rust experience", "Kubernetes projects"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_aspect_ask_fuses_parallel_retrievals() {
+        let searcher: Arc<dyn Searcher> = Arc::new(MockSearcher::new());
+        let request = AskRequest {
+            question: "Overview of the candidate".to_string(),
+            use_llm: false,
+            top_k: 3,
+            filters: Default::default(),
+            start: 0,
+            end: 0,
+            snippet_chars: 200,
+            mode: AskMode::Hybrid,
+            uri: None,
+            cursor: None,
+            as_of_frame: None,
+            as_of_ts: None,
+            adaptive: None,
+            acl: None,
+        };
+        let aspects = AspectPlanner::default().plan(&request.question, true).await;
+        assert_eq!(aspects.len(), DEFAULT_ASK_ASPECTS.len());
+
+        let (response, stats) = aspect_ask(searcher, request, aspects, None).await.unwrap();
+        assert_eq!(stats.len(), DEFAULT_ASK_ASPECTS.len());
+        assert_eq!(stats[1].aspect, "skills");
+        assert_eq!(stats[1].query, "overview candidate skills");
+        assert!(stats.iter().all(|s| s.candidates > 0));
+        assert_eq!(response.evidence.len(), 3);
+        assert_eq!(response.evidence[0].score, 1.0);
+        assert!(response.stats.candidates_retrieved >= 3);
+        assert!(response.next_cursor.is_none());
+    }
+}
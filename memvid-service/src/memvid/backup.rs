@@ -0,0 +1,175 @@
+//! Snapshots of the serving index, so index replacements can be rolled back.
+//!
+//! [`snapshot_index`] copies the index file into a backup directory as
+//! `<stem>-<UTC time>.mv2`, next to:
+//!
+//! - `<backup>.mv2.sha256`: the copy's checksum in `sha256sum` format, the
+//!   sidecar index validation checks against
+//! - `<backup>.json`: a [`BackupManifest`] recording the source file,
+//!   checksum, frame count and generation
+//!
+//! Rolling back is a cutover to the copy: StageIndex with its path, then
+//! PromoteIndex. The directory can be a mounted bucket (s3fs, gcsfuse) to
+//! keep backups in object storage.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use super::searcher::Searcher;
+use super::validate::{file_sha256, sidecar_path};
+use crate::error::ServiceError;
+
+/// What a backup holds, written next to it as `<backup>.json`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupManifest {
+    /// The index file's stem and the snapshot time
+    pub backup_id: String,
+    /// Index file the backup was copied from
+    pub source: String,
+    /// The copy
+    pub path: String,
+    /// SHA-256 of the copy, hex
+    pub sha256: String,
+    /// Size of the copy in bytes
+    pub size_bytes: u64,
+    /// Frames the serving index held
+    pub frame_count: i32,
+    /// Index generation at the snapshot
+    pub generation: u64,
+    /// Snapshot time, RFC 3339
+    pub created_at: String,
+}
+
+impl BackupManifest {
+    /// The manifest file next to the copy.
+    pub fn manifest_path(&self) -> PathBuf {
+        Path::new(&self.path).with_extension("json")
+    }
+}
+
+/// Copy `source`, the file `searcher` serves, into `dir` with its checksum
+/// sidecar and manifest.
+///
+/// The caller keeps writers off the file while it is copied; a file that
+/// changes anyway fails the snapshot rather than leaving a torn copy.
+///
+/// # Errors
+/// `FailedPrecondition` when `source` is not a file on disk; `Internal`
+/// when copying or writing the manifest fails.
+pub async fn snapshot_index(
+    searcher: &dyn Searcher,
+    source: &Path,
+    dir: &Path,
+) -> Result<BackupManifest, ServiceError> {
+    if !source.is_file() {
+        return Err(ServiceError::FailedPrecondition(format!(
+            "{} is not a file on disk",
+            source.display()
+        )));
+    }
+    let now = chrono::Utc::now();
+    let stem = source
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("index");
+    let backup_id = format!("{}-{}", stem, now.format("%Y%m%dT%H%M%S%3fZ"));
+    let manifest = BackupManifest {
+        path: dir.join(format!("{}.mv2", backup_id)).display().to_string(),
+        backup_id,
+        source: source.display().to_string(),
+        sha256: String::new(),
+        size_bytes: 0,
+        frame_count: searcher.frame_count(),
+        generation: searcher.generation(),
+        created_at: now.to_rfc3339(),
+    };
+
+    let (source, dir) = (source.to_path_buf(), dir.to_path_buf());
+    tokio::task::spawn_blocking(move || {
+        write_backup(&source, &dir, manifest).map_err(|e| {
+            ServiceError::Internal(format!(
+                "Failed to back up {} to {}: {}",
+                source.display(),
+                dir.display(),
+                e
+            ))
+        })
+    })
+    .await
+    .map_err(|e| ServiceError::Internal(format!("Backup task error: {}", e)))?
+}
+
+fn write_backup(
+    source: &Path,
+    dir: &Path,
+    mut manifest: BackupManifest,
+) -> std::io::Result<BackupManifest> {
+    std::fs::create_dir_all(dir)?;
+    let path = PathBuf::from(&manifest.path);
+    let partial = PathBuf::from(format!("{}.tmp", manifest.path));
+    let copied = std::fs::copy(source, &partial).and_then(|size| {
+        let (expected, sha256) = (file_sha256(source)?, file_sha256(&partial)?);
+        if expected != sha256 {
+            return Err(std::io::Error::other(
+                "the index changed while it was copied",
+            ));
+        }
+        Ok((size, sha256))
+    });
+    let (size, sha256) = match copied {
+        Ok(copied) => copied,
+        Err(e) => {
+            let _ = std::fs::remove_file(&partial);
+            return Err(e);
+        }
+    };
+    std::fs::rename(&partial, &path)?;
+
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    std::fs::write(sidecar_path(&path), format!("{}  {}\n", sha256, file_name))?;
+    manifest.sha256 = sha256;
+    manifest.size_bytes = size;
+    // Plain data never fails to serialize
+    let json = serde_json::to_string_pretty(&manifest).unwrap_or_default();
+    std::fs::write(manifest.manifest_path(), json + "\n")?;
+    Ok(manifest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memvid::MockSearcher;
+
+    #[tokio::test]
+    async fn test_snapshot_index() {
+        let root = std::env::temp_dir().join(format!("memvid-backup-{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        let source = root.join("resume.mv2");
+        std::fs::write(&source, b"mv2 index bytes").unwrap();
+        let searcher = MockSearcher::new();
+
+        let dir = root.join("backups");
+        let manifest = snapshot_index(&searcher, &source, &dir).await.unwrap();
+        assert!(manifest.backup_id.starts_with("resume-"));
+        assert_eq!(manifest.size_bytes, 15);
+        assert_eq!(manifest.frame_count, searcher.frame_count());
+        assert_eq!(manifest.sha256, file_sha256(&source).unwrap());
+        let copy = PathBuf::from(&manifest.path);
+        assert_eq!(std::fs::read(&copy).unwrap(), b"mv2 index bytes");
+        assert!(std::fs::read_to_string(sidecar_path(&copy))
+            .unwrap()
+            .starts_with(&manifest.sha256));
+        let written: BackupManifest =
+            serde_json::from_str(&std::fs::read_to_string(manifest.manifest_path()).unwrap())
+                .unwrap();
+        assert_eq!(written, manifest);
+        assert!(!dir.join(format!("{}.mv2.tmp", manifest.backup_id)).exists());
+
+        let missing = snapshot_index(&searcher, &root.join("missing.mv2"), &dir).await;
+        assert!(matches!(missing, Err(ServiceError::FailedPrecondition(_))));
+        std::fs::remove_dir_all(&root).ok();
+    }
+}
@@ -0,0 +1,225 @@
+//! Query-time ranking boosts.
+//!
+//! A request can tilt its ranking for a UI context (e.g., the Skills tab
+//! boosting `skills`-tagged frames) without a separate index. Tag and URI
+//! boosts multiply the score of matching hits; the recency weight multiplies
+//! every hit's score by `1 + weight * freshness`, where freshness halves
+//! with each year of the frame's age (undated hits have none). Like a
+//! language preference, boosts re-rank an overfetched candidate window.
+
+use std::collections::{BTreeMap, HashMap};
+
+use super::searcher::SearchResult;
+use crate::error::ServiceError;
+
+/// Candidates retrieved per requested hit when boosts are set.
+pub const BOOST_OVERFETCH: i32 = 4;
+
+/// Largest tag or URI boost, and the largest recency weight.
+pub const MAX_BOOST: f32 = 10.0;
+
+/// Age at which a frame's freshness has halved.
+const FRESHNESS_HALF_LIFE_SECS: f64 = 365.0 * 24.0 * 3600.0;
+
+/// Validated boosts of one request.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Boosts {
+    /// Score multiplier per tag (lowercase)
+    tags: BTreeMap<String, f32>,
+    /// Score multiplier per URI prefix
+    uris: BTreeMap<String, f32>,
+    /// Weight of freshness in the score (0 = off)
+    recency_weight: f32,
+}
+
+impl Boosts {
+    /// Validate request boosts; None when none are set.
+    ///
+    /// # Errors
+    /// `InvalidRequest` for a boost outside (0, [`MAX_BOOST`]] or a recency
+    /// weight outside [0, [`MAX_BOOST`]].
+    pub fn new(
+        tag_boosts: &HashMap<String, f32>,
+        uri_boosts: &HashMap<String, f32>,
+        recency_weight: f32,
+    ) -> Result<Option<Self>, ServiceError> {
+        let valid = |name: &str, key: &str, boost: f32| {
+            if boost > 0.0 && boost <= MAX_BOOST {
+                Ok(boost)
+            } else {
+                Err(ServiceError::InvalidRequest(format!(
+                    "{} for '{}' must be in (0, {}], got {}",
+                    name, key, MAX_BOOST, boost
+                )))
+            }
+        };
+        let tags = tag_boosts
+            .iter()
+            .map(|(tag, &boost)| Ok((tag.to_lowercase(), valid("tag_boosts", tag, boost)?)))
+            .collect::<Result<BTreeMap<_, _>, ServiceError>>()?;
+        let uris = uri_boosts
+            .iter()
+            .map(|(uri, &boost)| Ok((uri.clone(), valid("uri_boosts", uri, boost)?)))
+            .collect::<Result<BTreeMap<_, _>, ServiceError>>()?;
+        if !(0.0..=MAX_BOOST).contains(&recency_weight) {
+            return Err(ServiceError::InvalidRequest(format!(
+                "recency_weight must be in [0, {}], got {}",
+                MAX_BOOST, recency_weight
+            )));
+        }
+
+        let boosts = Self {
+            tags,
+            uris,
+            recency_weight,
+        };
+        Ok((boosts != Self::default()).then_some(boosts))
+    }
+
+    /// Boost the hits' scores as of `now` (Unix seconds) and re-sort them.
+    pub fn apply(&self, hits: &mut [SearchResult], now: i64) {
+        for hit in hits.iter_mut() {
+            hit.score *= self.multiplier(hit, now);
+        }
+        hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+    }
+
+    /// Canonical form, for cursor scopes: equal boosts give equal keys.
+    pub fn scope_key(&self) -> String {
+        let tags: Vec<String> = self
+            .tags
+            .iter()
+            .map(|(tag, boost)| format!("{}={}", tag, boost))
+            .collect();
+        let uris: Vec<String> = self
+            .uris
+            .iter()
+            .map(|(uri, boost)| format!("{}={}", uri, boost))
+            .collect();
+        format!(
+            "tags:{};uris:{};recency:{}",
+            tags.join(","),
+            uris.join(","),
+            self.recency_weight
+        )
+    }
+
+    fn multiplier(&self, hit: &SearchResult, now: i64) -> f32 {
+        let mut multiplier = 1.0;
+        for tag in &hit.tags {
+            if let Some(boost) = self.tags.get(&tag.to_lowercase()) {
+                multiplier *= boost;
+            }
+        }
+        // The most specific matching prefix wins
+        if let Some(uri) = &hit.uri {
+            let boost = self
+                .uris
+                .iter()
+                .filter(|(prefix, _)| uri.starts_with(prefix.as_str()))
+                .max_by_key(|(prefix, _)| prefix.len())
+                .map(|(_, boost)| *boost);
+            multiplier *= boost.unwrap_or(1.0);
+        }
+        if self.recency_weight > 0.0 {
+            multiplier *= 1.0 + self.recency_weight * freshness(hit.timestamp, now);
+        }
+        multiplier
+    }
+}
+
+/// 1 for a frame dated now (or later), halving per year of age; 0 undated.
+fn freshness(timestamp: Option<i64>, now: i64) -> f32 {
+    let Some(timestamp) = timestamp else {
+        return 0.0;
+    };
+    let age = (now - timestamp).max(0) as f64;
+    0.5f64.powf(age / FRESHNESS_HALF_LIFE_SECS) as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const YEAR: i64 = 365 * 24 * 3600;
+
+    fn hit(title: &str, score: f32, tag: &str, uri: &str, timestamp: Option<i64>) -> SearchResult {
+        SearchResult {
+            title: title.to_string(),
+            score,
+            tags: vec![tag.to_string()],
+            uri: Some(uri.to_string()),
+            timestamp,
+            ..Default::default()
+        }
+    }
+
+    fn titles(hits: &[SearchResult]) -> Vec<&str> {
+        hits.iter().map(|h| h.title.as_str()).collect()
+    }
+
+    #[test]
+    fn test_validates_and_skips_empty_boosts() {
+        let none = HashMap::new();
+        assert_eq!(Boosts::new(&none, &none, 0.0).unwrap(), None);
+
+        let negative = HashMap::from([("skills".to_string(), -1.0)]);
+        assert!(Boosts::new(&negative, &none, 0.0).is_err());
+        let huge = HashMap::from([("mv2://".to_string(), 100.0)]);
+        assert!(Boosts::new(&none, &huge, 0.0).is_err());
+        assert!(Boosts::new(&none, &none, f32::NAN).is_err());
+    }
+
+    #[test]
+    fn test_tag_and_uri_boosts_reorder_hits() {
+        let tags = HashMap::from([("Skills".to_string(), 1.5)]);
+        let uris = HashMap::from([
+            ("mv2://resume/".to_string(), 0.5),
+            ("mv2://resume/projects/".to_string(), 2.0),
+        ]);
+        let boosts = Boosts::new(&tags, &uris, 0.0).unwrap().unwrap();
+
+        let mut hits = vec![
+            hit("experience", 0.9, "experience", "mv2://resume/work", None),
+            hit("skills", 0.7, "skills", "mv2://other/skills", None),
+            hit(
+                "project",
+                0.5,
+                "projects",
+                "mv2://resume/projects/iot",
+                None,
+            ),
+        ];
+        boosts.apply(&mut hits, 0);
+        assert_eq!(titles(&hits), ["skills", "project", "experience"]);
+        assert_eq!(hits[0].score, 0.7 * 1.5);
+        assert_eq!(hits[2].score, 0.9 * 0.5);
+    }
+
+    #[test]
+    fn test_recency_weight_prefers_recent_frames() {
+        let none = HashMap::new();
+        let boosts = Boosts::new(&none, &none, 1.0).unwrap().unwrap();
+        let now = 10 * YEAR;
+
+        let mut hits = vec![
+            hit("old", 0.8, "experience", "", Some(now - 8 * YEAR)),
+            hit("undated", 0.75, "experience", "", None),
+            hit("recent", 0.6, "experience", "", Some(now)),
+        ];
+        boosts.apply(&mut hits, now);
+        assert_eq!(titles(&hits), ["recent", "old", "undated"]);
+        assert_eq!(hits[0].score, 0.6 * 2.0);
+        assert_eq!(hits[2].score, 0.75);
+        assert!((freshness(Some(now - YEAR), now) - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_scope_key_is_canonical() {
+        let none = HashMap::new();
+        let a = HashMap::from([("a".to_string(), 2.0), ("B".to_string(), 3.0)]);
+        let b = HashMap::from([("b".to_string(), 3.0), ("a".to_string(), 2.0)]);
+        let key = |tags| Boosts::new(tags, &none, 0.0).unwrap().unwrap().scope_key();
+        assert_eq!(key(&a), key(&b));
+    }
+}
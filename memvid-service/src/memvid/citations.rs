@@ -0,0 +1,344 @@
+//! Evidence citation markers in synthesized answers.
+//!
+//! A synthesized answer cites its evidence inline with markers such as `[2]`
+//! or `[1, 3]`, numbered from 1 in the order the evidence was retrieved for
+//! synthesis. Re-ranking, visibility filtering and truncation reorder that
+//! evidence before it reaches the client, so markers are renumbered to the
+//! returned evidence, and markers citing evidence that was not returned are
+//! dropped. Under the strict policy every sentence must keep a valid marker;
+//! uncited sentences are stripped as unsupported claims.
+//!
+//! The lenient policy only rewrites markers, so [`CitationStream`] applies it
+//! to an answer arriving in pieces without waiting for the whole answer.
+
+use super::searcher::SearchResult;
+
+/// How synthesized answers are held to their citations.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CitationPolicy {
+    /// Leave answers as synthesized.
+    Off,
+    /// Renumber markers and drop those citing evidence not returned.
+    #[default]
+    Lenient,
+    /// Also strip sentences without a valid marker.
+    Strict,
+}
+
+impl CitationPolicy {
+    /// Parse "off", "lenient" or "strict".
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "off" => Ok(Self::Off),
+            "lenient" => Ok(Self::Lenient),
+            "strict" => Ok(Self::Strict),
+            other => Err(format!("expected off, lenient or strict, got '{}'", other)),
+        }
+    }
+}
+
+/// An answer after citation verification.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CitationCheck {
+    /// The answer with markers renumbered to the returned evidence
+    pub answer: String,
+    /// Markers dropped for citing evidence that was not returned
+    pub invalid_markers: usize,
+    /// Sentences stripped for citing no returned evidence
+    pub claims_removed: usize,
+}
+
+/// Verify the markers of `answer`, synthesized from `synthesized` evidence,
+/// against the `returned` evidence under `policy`. The answer is empty when
+/// the strict policy strips every sentence.
+pub fn check_citations(
+    answer: &str,
+    synthesized: &[SearchResult],
+    returned: &[SearchResult],
+    policy: CitationPolicy,
+) -> CitationCheck {
+    if policy == CitationPolicy::Off {
+        return CitationCheck {
+            answer: answer.to_string(),
+            ..Default::default()
+        };
+    }
+
+    let renumber = |marker: usize| {
+        let cited = synthesized.get(marker.checked_sub(1)?)?;
+        returned
+            .iter()
+            .position(|hit| hit.frame_id == cited.frame_id && hit.title == cited.title)
+            .map(|index| index + 1)
+    };
+
+    let groups = marker_groups(answer);
+    let mut check = CitationCheck::default();
+    for (start, end) in sentences(answer, &groups) {
+        let mut sentence = String::new();
+        let mut cursor = start;
+        let mut cited = 0;
+        for group in groups.iter().filter(|g| g.start >= start && g.end <= end) {
+            sentence.push_str(&answer[cursor..group.start]);
+            let mut markers: Vec<usize> =
+                group.markers.iter().filter_map(|&m| renumber(m)).collect();
+            check.invalid_markers += group.markers.len() - markers.len();
+            markers.sort_unstable();
+            markers.dedup();
+            if markers.is_empty() {
+                sentence.truncate(sentence.trim_end_matches([' ', '\t']).len());
+            } else {
+                let markers: Vec<String> = markers.iter().map(usize::to_string).collect();
+                sentence.push_str(&format!("[{}]", markers.join(", ")));
+                cited += markers.len();
+            }
+            cursor = group.end;
+        }
+        sentence.push_str(&answer[cursor..end]);
+
+        if policy == CitationPolicy::Strict && cited == 0 && !sentence.trim().is_empty() {
+            check.claims_removed += 1;
+            continue;
+        }
+        check.answer.push_str(&sentence);
+    }
+    if check.claims_removed > 0 {
+        check.answer = check.answer.trim().to_string();
+    }
+    check
+}
+
+/// Longest text after a `[` that is still held back as a possible marker.
+const MAX_MARKER_CHARS: usize = 32;
+
+/// Lenient citation check of an answer that arrives in pieces.
+///
+/// Text is released as soon as no marker can still be open in it. An
+/// unclosed `[`, and the spaces before it that a dropped marker takes along,
+/// are held back until the marker closes or grows too long to be one.
+#[derive(Debug)]
+pub struct CitationStream {
+    synthesized: Vec<SearchResult>,
+    returned: Vec<SearchResult>,
+    pending: String,
+    invalid_markers: usize,
+}
+
+impl CitationStream {
+    /// Check an answer synthesized from `synthesized` evidence against the
+    /// `returned` evidence.
+    pub fn new(synthesized: Vec<SearchResult>, returned: Vec<SearchResult>) -> Self {
+        Self {
+            synthesized,
+            returned,
+            pending: String::new(),
+            invalid_markers: 0,
+        }
+    }
+
+    /// Append the next piece of the answer, returning the checked text that
+    /// is ready to send (possibly empty).
+    pub fn push(&mut self, delta: &str) -> String {
+        self.pending.push_str(delta);
+        let ready = self.pending.len() - held_back(&self.pending);
+        let text: String = self.pending.drain(..ready).collect();
+        self.check(&text)
+    }
+
+    /// Checked text still held back once the answer is complete.
+    pub fn finish(&mut self) -> String {
+        let text = std::mem::take(&mut self.pending);
+        self.check(&text)
+    }
+
+    /// Markers dropped so far for citing evidence that was not returned.
+    pub fn invalid_markers(&self) -> usize {
+        self.invalid_markers
+    }
+
+    fn check(&mut self, text: &str) -> String {
+        if text.is_empty() {
+            return String::new();
+        }
+        let check = check_citations(
+            text,
+            &self.synthesized,
+            &self.returned,
+            CitationPolicy::Lenient,
+        );
+        self.invalid_markers += check.invalid_markers;
+        check.answer
+    }
+}
+
+/// Bytes at the end of `text` that a later piece may still rewrite.
+fn held_back(text: &str) -> usize {
+    let open = match text.rfind('[') {
+        Some(open) if !text[open..].contains(']') && text.len() - open <= MAX_MARKER_CHARS => open,
+        _ => text.len(),
+    };
+    text.len() - text[..open].trim_end_matches([' ', '\t']).len()
+}
+
+/// A marker group such as `[1, 3]` spanning `start..end` of the answer.
+struct MarkerGroup {
+    start: usize,
+    end: usize,
+    markers: Vec<usize>,
+}
+
+fn marker_groups(text: &str) -> Vec<MarkerGroup> {
+    let mut groups = Vec::new();
+    let mut rest = 0;
+    while let Some(open) = text[rest..].find('[') {
+        let start = rest + open;
+        let Some(close) = text[start..].find(']') else {
+            break;
+        };
+        let end = start + close + 1;
+        let markers: Option<Vec<usize>> = text[start + 1..end - 1]
+            .split(',')
+            .map(|marker| marker.trim().parse().ok())
+            .collect();
+        match markers {
+            Some(markers) => {
+                groups.push(MarkerGroup {
+                    start,
+                    end,
+                    markers,
+                });
+                rest = end;
+            }
+            None => rest = start + 1,
+        }
+    }
+    groups
+}
+
+/// Byte spans of the sentences of `text`, each including the markers right
+/// after its terminator and the whitespace before it.
+fn sentences(text: &str, groups: &[MarkerGroup]) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut start = 0;
+    let mut pos = 0;
+    while let Some(c) = text[pos..].chars().next() {
+        pos += c.len_utf8();
+        let next = text[pos..].chars().next();
+        let terminal =
+            c == '\n' || (matches!(c, '.' | '!' | '?') && next.is_none_or(char::is_whitespace));
+        if !terminal {
+            continue;
+        }
+        // Markers after the terminator ("... teams. [2]") cite this sentence
+        loop {
+            let gap = text[pos..].len() - text[pos..].trim_start_matches([' ', '\t']).len();
+            match groups.iter().find(|group| group.start == pos + gap) {
+                Some(group) => pos = group.end,
+                None => break,
+            }
+        }
+        spans.push((start, pos));
+        start = pos;
+    }
+    if start < text.len() {
+        spans.push((start, text.len()));
+    }
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hit(frame_id: u64) -> SearchResult {
+        SearchResult {
+            frame_id: Some(frame_id),
+            title: format!("Frame {}", frame_id),
+            score: 0.5,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parses_policy() {
+        assert_eq!(
+            CitationPolicy::parse(" Strict").unwrap(),
+            CitationPolicy::Strict
+        );
+        assert_eq!(CitationPolicy::parse("off").unwrap(), CitationPolicy::Off);
+        assert!(CitationPolicy::parse("loose").is_err());
+    }
+
+    #[test]
+    fn test_renumbers_markers_to_returned_evidence() {
+        let synthesized = [hit(1), hit(2), hit(3)];
+        let returned = [hit(3), hit(1)];
+        let check = check_citations(
+            "Led a team of 12 [1]. Shipped Kafka pipelines [2, 3]. Knows Rust [7].",
+            &synthesized,
+            &returned,
+            CitationPolicy::Lenient,
+        );
+        assert_eq!(
+            check.answer,
+            "Led a team of 12 [2]. Shipped Kafka pipelines [1]. Knows Rust."
+        );
+        assert_eq!(check.invalid_markers, 2);
+        assert_eq!(check.claims_removed, 0);
+    }
+
+    #[test]
+    fn test_strict_strips_uncited_claims() {
+        let evidence = [hit(1), hit(2)];
+        let check = check_citations(
+            "Led a team of 12. [1] Won every award! Knows Rust [9].\nArray [a] syntax [2]",
+            &evidence,
+            &evidence,
+            CitationPolicy::Strict,
+        );
+        assert_eq!(check.answer, "Led a team of 12. [1]\nArray [a] syntax [2]");
+        assert_eq!(check.claims_removed, 2);
+        assert_eq!(check.invalid_markers, 1);
+
+        let check = check_citations(
+            "Nothing cited.",
+            &evidence,
+            &evidence,
+            CitationPolicy::Strict,
+        );
+        assert!(check.answer.is_empty());
+
+        let answer = "Knows Rust [9].";
+        let check = check_citations(answer, &evidence, &evidence, CitationPolicy::Off);
+        assert_eq!(check.answer, answer);
+    }
+
+    #[test]
+    fn test_stream_matches_whole_answer_check() {
+        let synthesized = [hit(1), hit(2), hit(3)];
+        let returned = [hit(3), hit(1)];
+        let answer = "Led a team of 12 [1]. Shipped Kafka pipelines [2, 3]. Knows Rust [7].";
+        let whole = check_citations(answer, &synthesized, &returned, CitationPolicy::Lenient);
+
+        // Split everywhere, including inside markers
+        for size in 1..=answer.len() {
+            let mut stream = CitationStream::new(synthesized.to_vec(), returned.to_vec());
+            let mut streamed = String::new();
+            for piece in answer.as_bytes().chunks(size) {
+                streamed.push_str(&stream.push(std::str::from_utf8(piece).unwrap()));
+            }
+            streamed.push_str(&stream.finish());
+            assert_eq!(streamed, whole.answer, "pieces of {} bytes", size);
+            assert_eq!(stream.invalid_markers(), whole.invalid_markers);
+        }
+
+        // Text is released without waiting for the rest of the answer
+        let mut stream = CitationStream::new(synthesized.to_vec(), returned.to_vec());
+        assert_eq!(stream.push("Led a team "), "Led a team");
+        assert_eq!(stream.push("of 12 [1"), " of 12");
+        assert_eq!(stream.push("]. Shipped"), " [2]. Shipped");
+        // An unclosed bracket is released once it is too long to be a marker
+        let prose = format!(" [{}", "x".repeat(MAX_MARKER_CHARS));
+        assert_eq!(stream.push(&prose), prose);
+    }
+}
@@ -0,0 +1,133 @@
+//! Bound on concurrent blocking searches.
+//!
+//! Every search and ask occupies a thread of tokio's blocking pool while it
+//! waits for and holds the index lock. A [`SearchLimiter`] admits at most
+//! `max_concurrent` of them at once; up to `max_queued` more wait for a slot
+//! (until their deadline, see [`deadline`](super::deadline)) and any beyond
+//! that fail fast with `RESOURCE_EXHAUSTED`, so a burst of requests cannot
+//! exhaust the pool.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use super::deadline;
+use crate::error::ServiceError;
+use crate::metrics;
+
+/// Default blocking searches running at once.
+pub const DEFAULT_MAX_CONCURRENT_SEARCHES: usize = 16;
+
+/// Default searches waiting for a slot before new ones are rejected.
+pub const DEFAULT_MAX_QUEUED_SEARCHES: usize = 64;
+
+/// Admission control for blocking searches, shared across index reloads.
+#[derive(Debug)]
+pub struct SearchLimiter {
+    permits: Arc<Semaphore>,
+    max_queued: usize,
+    queued: AtomicUsize,
+}
+
+impl SearchLimiter {
+    /// Run at most `max_concurrent` searches, with up to `max_queued`
+    /// waiting.
+    pub fn new(max_concurrent: usize, max_queued: usize) -> Self {
+        Self {
+            permits: Arc::new(Semaphore::new(max_concurrent)),
+            max_queued,
+            queued: AtomicUsize::new(0),
+        }
+    }
+
+    /// Wait for a slot for `operation`; the slot is held until the permit
+    /// is dropped.
+    ///
+    /// # Errors
+    /// `ResourceExhausted` when the queue is full, `DeadlineExceeded` when
+    /// the request's deadline passes while queued.
+    pub async fn acquire(
+        &self,
+        operation: &'static str,
+    ) -> Result<OwnedSemaphorePermit, ServiceError> {
+        if let Ok(permit) = Arc::clone(&self.permits).try_acquire_owned() {
+            return Ok(permit);
+        }
+        let _queued = self.enqueue().ok_or_else(|| {
+            metrics::increment_search_rejected(operation);
+            ServiceError::ResourceExhausted(format!(
+                "Too many concurrent requests, {} rejected; retry later",
+                operation
+            ))
+        })?;
+
+        let permit = Arc::clone(&self.permits).acquire_owned();
+        let permit = deadline::within(deadline::current(), operation, permit).await?;
+        Ok(permit.expect("search semaphore is never closed"))
+    }
+
+    /// Searches waiting for a slot.
+    pub fn queued(&self) -> usize {
+        self.queued.load(Ordering::Relaxed)
+    }
+
+    /// Take a queue place, if one is free.
+    fn enqueue(&self) -> Option<QueuePlace<'_>> {
+        self.queued
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |queued| {
+                (queued < self.max_queued).then_some(queued + 1)
+            })
+            .ok()
+            .map(|_| QueuePlace(&self.queued))
+    }
+}
+
+/// Place in the queue, given up when dropped (also when the waiting
+/// request is cancelled).
+struct QueuePlace<'a>(&'a AtomicUsize);
+
+impl Drop for QueuePlace<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_queues_then_rejects() {
+        let limiter = Arc::new(SearchLimiter::new(1, 1));
+        let running = limiter.acquire("search").await.unwrap();
+
+        let waiting = tokio::spawn({
+            let limiter = Arc::clone(&limiter);
+            async move { limiter.acquire("search").await.map(drop) }
+        });
+        while limiter.queued() == 0 {
+            tokio::task::yield_now().await;
+        }
+
+        let err = limiter.acquire("ask").await.unwrap_err();
+        assert!(matches!(err, ServiceError::ResourceExhausted(_)));
+
+        drop(running);
+        waiting.await.unwrap().unwrap();
+        assert_eq!(limiter.queued(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_queued_request_gives_up_at_its_deadline() {
+        let limiter = SearchLimiter::new(1, 4);
+        let _running = limiter.acquire("search").await.unwrap();
+
+        let deadline = tokio::time::Instant::now() + Duration::from_millis(10);
+        let err = deadline::scope(Some(deadline), limiter.acquire("search"))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ServiceError::DeadlineExceeded(_)));
+        assert_eq!(limiter.queued(), 0);
+    }
+}
@@ -0,0 +1,81 @@
+//! Per-request deadlines for blocking index work.
+//!
+//! The gRPC layer runs each call within [`scope`] with the deadline its
+//! client sent. Searchers read it with [`current`] and pass it to
+//! [`within`], so a search or ask still queued for the blocking pool or
+//! waiting for the index lock when the deadline passes is abandoned before
+//! it starts, instead of computing an answer nobody will read.
+
+use std::future::Future;
+use tokio::time::Instant;
+
+use crate::error::ServiceError;
+use crate::metrics;
+
+tokio::task_local! {
+    static DEADLINE: Option<Instant>;
+}
+
+/// Run `future` with `deadline` as the current request's deadline.
+pub async fn scope<F: Future>(deadline: Option<Instant>, future: F) -> F::Output {
+    DEADLINE.scope(deadline, future).await
+}
+
+/// Deadline of the request being served (None when the client set none or
+/// outside a request).
+pub fn current() -> Option<Instant> {
+    DEADLINE.try_with(|deadline| *deadline).ok().flatten()
+}
+
+/// Await `future` unless `deadline` passes first; `operation` names the
+/// abandoned work in the error. An expired deadline fails without polling.
+pub async fn within<F: Future>(
+    deadline: Option<Instant>,
+    operation: &'static str,
+    future: F,
+) -> Result<F::Output, ServiceError> {
+    let Some(deadline) = deadline else {
+        return Ok(future.await);
+    };
+    if Instant::now() < deadline {
+        if let Ok(output) = tokio::time::timeout_at(deadline, future).await {
+            return Ok(output);
+        }
+    }
+    metrics::increment_deadline_exceeded(operation);
+    Err(ServiceError::DeadlineExceeded(format!(
+        "{} abandoned: the client's deadline passed",
+        operation
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_within_deadline() {
+        assert_eq!(current(), None);
+        let deadline = Instant::now() + Duration::from_secs(60);
+        let seen = scope(Some(deadline), async { current() }).await;
+        assert_eq!(seen, Some(deadline));
+
+        assert_eq!(within(None, "search", async { 1 }).await.unwrap(), 1);
+        assert_eq!(
+            within(Some(deadline), "search", async { 2 }).await.unwrap(),
+            2
+        );
+
+        // A passed deadline fails even when the work is ready
+        let passed = Instant::now() - Duration::from_millis(1);
+        let err = within(Some(passed), "search", async { 3 })
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ServiceError::DeadlineExceeded(_)));
+
+        let soon = Instant::now() + Duration::from_millis(10);
+        let pending = within(Some(soon), "ask", std::future::pending::<()>()).await;
+        assert!(matches!(pending, Err(ServiceError::DeadlineExceeded(_))));
+    }
+}
@@ -0,0 +1,247 @@
+//! Background deep-search registry for two-tier retrieval.
+//!
+//! Two-tier search returns a fast lexical first page immediately and runs a
+//! deeper hybrid pass in the background. The deep result is retrieved later
+//! with the opaque cursor handed out alongside the first page.
+//!
+//! Cursors are random, so they cannot be guessed, and only the caller that
+//! started a deep search (the same ACL context) can claim it. At most
+//! [`MAX_PENDING_DEEP_SEARCHES`] run or wait at once, and each stops when
+//! its TTL is up, claimed or not.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use super::acl::AclContext;
+use super::searcher::{AskMode, AskRequest, SearchRequest, SearchResponse, Searcher};
+use crate::error::ServiceError;
+use crate::random::random_bytes;
+
+/// Most deep searches running or awaiting their claim at once.
+pub const MAX_PENDING_DEEP_SEARCHES: usize = 256;
+
+/// A deep search running (or finished) in the background.
+struct PendingDeepSearch {
+    started: Instant,
+    /// ACL context of the caller that started it
+    acl: Option<AclContext>,
+    handle: JoinHandle<Result<SearchResponse, ServiceError>>,
+}
+
+/// Registry of background deep searches keyed by cursor.
+pub struct DeepSearchStore {
+    pending: Mutex<HashMap<String, PendingDeepSearch>>,
+    ttl: Duration,
+}
+
+impl DeepSearchStore {
+    /// Create a store whose unclaimed results expire after `ttl`.
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            pending: Mutex::new(HashMap::new()),
+            ttl,
+        }
+    }
+
+    /// Start a hybrid deep search in the background and return its cursor.
+    ///
+    /// Returns `None`, starting nothing, when
+    /// [`MAX_PENDING_DEEP_SEARCHES`] are already pending.
+    pub fn start(&self, searcher: Arc<dyn Searcher>, request: SearchRequest) -> Option<String> {
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        self.evict_expired(&mut pending);
+        if pending.len() >= MAX_PENDING_DEEP_SEARCHES {
+            warn!(
+                pending = pending.len(),
+                "Too many pending deep searches; skipping the deep pass"
+            );
+            return None;
+        }
+
+        let cursor: String = random_bytes::<16>()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        let cursor = format!("deep-{}", cursor);
+        let acl = request.acl.clone();
+        let ttl = self.ttl;
+        let handle = tokio::spawn(async move {
+            let ask_request = AskRequest {
+                question: request.query,
+                use_llm: false,
+                top_k: request.top_k,
+                filters: request.filters,
+                start: 0,
+                end: 0,
+                snippet_chars: request.snippet_chars,
+                mode: AskMode::Hybrid,
+                uri: request.uri,
+                cursor: None,
+                as_of_frame: None,
+                as_of_ts: None,
+                adaptive: None,
+                acl: request.acl,
+            };
+
+            // The result is of no use once it can no longer be claimed
+            let response = tokio::time::timeout(ttl, searcher.ask(ask_request))
+                .await
+                .map_err(|_| {
+                    ServiceError::DeadlineExceeded("Deep search outlived its cursor".to_string())
+                })??;
+            let total_hits = response.evidence.len() as i32;
+
+            Ok(SearchResponse {
+                hits: response.evidence,
+                total_hits,
+                took_ms: response.stats.retrieval_ms,
+                partial: false,
+                expanded_query: None,
+            })
+        });
+
+        pending.insert(
+            cursor.clone(),
+            PendingDeepSearch {
+                started: Instant::now(),
+                acl,
+                handle,
+            },
+        );
+
+        info!("Started background deep search");
+        Some(cursor)
+    }
+
+    /// Claim the deep result for `cursor` on behalf of a caller with `acl`,
+    /// waiting for it to finish if needed.
+    ///
+    /// Returns `None` if the cursor is unknown, already claimed, expired, or
+    /// was started by a caller with another ACL context.
+    pub async fn take(
+        &self,
+        cursor: &str,
+        acl: Option<&AclContext>,
+    ) -> Option<Result<SearchResponse, ServiceError>> {
+        let entry = {
+            let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+            self.evict_expired(&mut pending);
+            if pending.get(cursor)?.acl.as_ref() != acl {
+                return None;
+            }
+            pending.remove(cursor)?
+        };
+
+        Some(entry.handle.await.unwrap_or_else(|e| {
+            warn!(error = %e, "Deep search task failed");
+            Err(ServiceError::Internal(format!(
+                "Deep search task error: {}",
+                e
+            )))
+        }))
+    }
+
+    /// Drop entries older than the TTL, aborting any still running.
+    fn evict_expired(&self, pending: &mut HashMap<String, PendingDeepSearch>) {
+        pending.retain(|_, entry| {
+            let keep = entry.started.elapsed() < self.ttl;
+            if !keep {
+                info!("Evicting unclaimed deep search");
+                entry.handle.abort();
+            }
+            keep
+        });
+    }
+}
+
+impl Drop for DeepSearchStore {
+    fn drop(&mut self) {
+        let pending = self.pending.get_mut().unwrap_or_else(|e| e.into_inner());
+        for entry in pending.values() {
+            entry.handle.abort();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memvid::MockSearcher;
+
+    #[tokio::test]
+    async fn test_deep_search_round_trip() {
+        let store = DeepSearchStore::new(Duration::from_secs(60));
+        let searcher: Arc<dyn Searcher> = Arc::new(MockSearcher::new());
+
+        let cursor = store
+            .start(searcher, SearchRequest::new("leadership", 3, 200))
+            .unwrap();
+        assert_eq!(cursor.len(), "deep-".len() + 32);
+        let response = store.take(&cursor, None).await.unwrap().unwrap();
+
+        assert!(!response.hits.is_empty());
+        assert!(response.hits.len() <= 3);
+
+        // A cursor can only be claimed once
+        assert!(store.take(&cursor, None).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_unknown_cursor() {
+        let store = DeepSearchStore::new(Duration::from_secs(60));
+        assert!(store.take("deep-999", None).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_only_the_starting_caller_claims() {
+        let store = DeepSearchStore::new(Duration::from_secs(60));
+        let searcher: Arc<dyn Searcher> = Arc::new(MockSearcher::new());
+        let owner = AclContext {
+            subject_id: Some("alice".to_string()),
+            ..AclContext::default()
+        };
+        let other = AclContext {
+            subject_id: Some("mallory".to_string()),
+            ..AclContext::default()
+        };
+
+        let request = SearchRequest {
+            acl: Some(owner.clone()),
+            ..SearchRequest::new("leadership", 3, 200)
+        };
+        let cursor = store.start(searcher, request).unwrap();
+        assert!(store.take(&cursor, Some(&other)).await.is_none());
+        assert!(store.take(&cursor, None).await.is_none());
+        assert!(store.take(&cursor, Some(&owner)).await.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_pending_searches_are_capped() {
+        let store = DeepSearchStore::new(Duration::from_secs(60));
+        let searcher: Arc<dyn Searcher> = Arc::new(MockSearcher::new());
+        let start = || store.start(Arc::clone(&searcher), SearchRequest::new("rust", 3, 200));
+
+        let cursors: Vec<String> = (0..MAX_PENDING_DEEP_SEARCHES)
+            .map(|_| start().unwrap())
+            .collect();
+        assert!(start().is_none());
+
+        // Claiming frees a slot
+        assert!(store.take(&cursors[0], None).await.is_some());
+        assert!(start().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_expired_cursor_is_evicted() {
+        let store = DeepSearchStore::new(Duration::ZERO);
+        let searcher: Arc<dyn Searcher> = Arc::new(MockSearcher::new());
+
+        let cursor = store
+            .start(searcher, SearchRequest::new("leadership", 3, 200))
+            .unwrap();
+        assert!(store.take(&cursor, None).await.is_none());
+    }
+}
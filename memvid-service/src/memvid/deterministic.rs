@@ -0,0 +1,170 @@
+//! Deterministic searcher for reproducible responses.
+//!
+//! Golden tests and demos compare responses byte for byte, so with
+//! `DETERMINISTIC=true` the searcher is wrapped in a
+//! [`DeterministicSearcher`]: hits with equal scores are ordered by frame
+//! id, then URI and title, instead of whatever order the index returned
+//! them in; search time budgets are dropped, so a slow run never returns
+//! partial results; and measured timings (`took_ms`, retrieval and
+//! re-ranking times) are reported as zero.
+
+use async_trait::async_trait;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use super::acronyms::AcronymMap;
+use super::instrumented::LockDiagnostics;
+use super::searcher::{
+    AskRequest, AskResponse, EntitySummary, FrameMetadata, FrameText, IndexFeatures, SearchRequest,
+    SearchResponse, SearchResult, Searcher, StateResponse,
+};
+use super::spelling::SpellDictionary;
+use super::suggest::SuggestionIndex;
+use crate::error::ServiceError;
+
+/// Default time `DETERMINISTIC_NOW` pins "now" to (2025-01-01T00:00:00Z).
+pub const DEFAULT_DETERMINISTIC_NOW: i64 = 1_735_689_600;
+
+/// Order hits by descending score, breaking ties by frame id, URI and title.
+pub fn deterministic_order(a: &SearchResult, b: &SearchResult) -> Ordering {
+    b.score
+        .total_cmp(&a.score)
+        .then_with(|| a.frame_id.cmp(&b.frame_id))
+        .then_with(|| a.uri.cmp(&b.uri))
+        .then_with(|| a.title.cmp(&b.title))
+}
+
+/// Searcher whose responses depend only on the request and the index.
+pub struct DeterministicSearcher {
+    inner: Arc<dyn Searcher>,
+}
+
+impl DeterministicSearcher {
+    /// Wrap `inner`.
+    pub fn new(inner: Arc<dyn Searcher>) -> Self {
+        Self { inner }
+    }
+}
+
+#[async_trait]
+impl Searcher for DeterministicSearcher {
+    async fn search(&self, mut request: SearchRequest) -> Result<SearchResponse, ServiceError> {
+        request.budget_ms = None;
+        let mut response = self.inner.search(request).await?;
+        response.hits.sort_by(deterministic_order);
+        response.took_ms = 0;
+        Ok(response)
+    }
+
+    async fn get_state(
+        &self,
+        entity: &str,
+        slot: Option<&str>,
+    ) -> Result<StateResponse, ServiceError> {
+        self.inner.get_state(entity, slot).await
+    }
+
+    async fn list_entities(&self) -> Result<Vec<EntitySummary>, ServiceError> {
+        self.inner.list_entities().await
+    }
+
+    async fn ask(&self, request: AskRequest) -> Result<AskResponse, ServiceError> {
+        let mut response = self.inner.ask(request).await?;
+        response.evidence.sort_by(deterministic_order);
+        response.stats.retrieval_ms = 0;
+        response.stats.reranking_ms = 0;
+        Ok(response)
+    }
+
+    async fn export_frames(
+        &self,
+        after: Option<u64>,
+        limit: usize,
+    ) -> Result<Vec<FrameMetadata>, ServiceError> {
+        self.inner.export_frames(after, limit).await
+    }
+
+    async fn frame_texts(
+        &self,
+        after: Option<u64>,
+        limit: usize,
+    ) -> Result<Vec<FrameText>, ServiceError> {
+        self.inner.frame_texts(after, limit).await
+    }
+
+    fn frame_count(&self) -> i32 {
+        self.inner.frame_count()
+    }
+
+    fn memvid_file(&self) -> String {
+        self.inner.memvid_file()
+    }
+
+    fn generation(&self) -> u64 {
+        self.inner.generation()
+    }
+
+    fn index_features(&self) -> IndexFeatures {
+        self.inner.index_features()
+    }
+
+    fn section_counts(&self) -> BTreeMap<String, i32> {
+        self.inner.section_counts()
+    }
+
+    fn lock_diagnostics(&self) -> LockDiagnostics {
+        self.inner.lock_diagnostics()
+    }
+
+    fn acronyms(&self) -> Arc<AcronymMap> {
+        self.inner.acronyms()
+    }
+
+    fn spelling(&self) -> Arc<SpellDictionary> {
+        self.inner.spelling()
+    }
+
+    fn suggestions(&self) -> Arc<SuggestionIndex> {
+        self.inner.suggestions()
+    }
+
+    fn is_ready(&self) -> bool {
+        self.inner.is_ready()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memvid::MockSearcher;
+
+    fn hit(frame_id: u64, score: f32) -> SearchResult {
+        SearchResult {
+            frame_id: Some(frame_id),
+            title: format!("frame {}", frame_id),
+            score,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_breaks_score_ties_by_frame_id() {
+        let mut hits = [hit(7, 0.5), hit(3, 0.9), hit(2, 0.5)];
+        hits.sort_by(deterministic_order);
+        let order: Vec<u64> = hits.iter().filter_map(|h| h.frame_id).collect();
+        assert_eq!(order, [3, 2, 7]);
+    }
+
+    #[tokio::test]
+    async fn test_repeated_requests_are_identical() {
+        let searcher = DeterministicSearcher::new(Arc::new(MockSearcher::new()));
+        let mut request = SearchRequest::new("Python experience", 5, 200);
+        request.budget_ms = Some(1);
+        let first = searcher.search(request.clone()).await.unwrap();
+        let second = searcher.search(request).await.unwrap();
+        assert_eq!(first.took_ms, 0);
+        assert!(!first.partial);
+        assert_eq!(format!("{:?}", first.hits), format!("{:?}", second.hits));
+    }
+}
@@ -0,0 +1,183 @@
+//! Embedding drift detection between index versions.
+//!
+//! An index rebuilt with a different embedding model (or dimension) loads
+//! fine but ranks semantic queries like noise. memvid-core does not expose
+//! the stored vectors, so each index is profiled through semantic-only
+//! probes instead: the opening text of its first frames is asked in `Sem`
+//! mode. With the embedder the index was built for, a frame retrieves
+//! itself near the top with a high score; with another one the self-hit
+//! rate and the scores collapse, or the probes fail outright on a dimension
+//! mismatch. On reload the new index's profile is compared with the serving
+//! one's and a mismatch is reported loudly.
+
+use std::collections::HashMap;
+
+use super::searcher::{AskMode, AskRequest, Searcher};
+
+/// Frames probed per index.
+pub const DRIFT_PROBES: usize = 8;
+
+/// Hits retrieved per probe; a frame within them counts as a self-hit.
+const PROBE_TOP_K: i32 = 3;
+
+/// Characters of frame text used as the probe question.
+const PROBE_CHARS: usize = 200;
+
+/// Drop in self-hit rate that indicates a different embedding space.
+const MAX_SELF_HIT_DROP: f32 = 0.5;
+
+/// Largest factor by which the mean top score may change.
+const MAX_SCORE_RATIO: f32 = 2.0;
+
+/// Semantic retrieval statistics of one index.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EmbeddingProfile {
+    /// Probes asked
+    pub probes: usize,
+    /// Probes that failed (e.g. on a dimension mismatch)
+    pub errors: usize,
+    /// Probes that retrieved their own frame
+    pub self_hits: usize,
+    /// Mean score of the top hit over answered probes
+    pub mean_top_score: f32,
+}
+
+impl EmbeddingProfile {
+    /// Probe `searcher` with the text of its first [`DRIFT_PROBES`] frames.
+    pub async fn probe(searcher: &dyn Searcher) -> Self {
+        let frames = match searcher.frame_texts(None, DRIFT_PROBES).await {
+            Ok(frames) => frames,
+            Err(_) => return Self::default(),
+        };
+
+        let mut profile = Self::default();
+        let mut top_scores = 0.0;
+        for frame in frames {
+            let question: String = frame.text.chars().take(PROBE_CHARS).collect();
+            if question.trim().is_empty() {
+                continue;
+            }
+            profile.probes += 1;
+            let request = AskRequest {
+                question,
+                use_llm: false,
+                top_k: PROBE_TOP_K,
+                filters: HashMap::new(),
+                start: 0,
+                end: 0,
+                snippet_chars: 0,
+                mode: AskMode::Sem,
+                uri: None,
+                cursor: None,
+                as_of_frame: None,
+                as_of_ts: None,
+                adaptive: None,
+                acl: None,
+            };
+            match searcher.ask(request).await {
+                Ok(response) => {
+                    if response
+                        .evidence
+                        .iter()
+                        .any(|hit| hit.frame_id == Some(frame.frame_id))
+                    {
+                        profile.self_hits += 1;
+                    }
+                    top_scores += response.evidence.first().map_or(0.0, |hit| hit.score);
+                }
+                Err(_) => profile.errors += 1,
+            }
+        }
+        let answered = profile.probes - profile.errors;
+        if answered > 0 {
+            profile.mean_top_score = top_scores / answered as f32;
+        }
+        profile
+    }
+
+    fn self_hit_rate(&self) -> f32 {
+        let answered = self.probes - self.errors;
+        if answered == 0 {
+            return 0.0;
+        }
+        self.self_hits as f32 / answered as f32
+    }
+
+    /// Why `self` looks embedded with a different model than `previous`;
+    /// None when it does not, or either profile has nothing to compare.
+    pub fn drift_from(&self, previous: &Self) -> Option<String> {
+        if self.probes == 0 || previous.probes == previous.errors {
+            return None;
+        }
+        if self.errors == self.probes {
+            return Some(format!(
+                "all {} semantic probes failed (embedding dimension mismatch?)",
+                self.probes
+            ));
+        }
+        let (before, after) = (previous.self_hit_rate(), self.self_hit_rate());
+        if before - after >= MAX_SELF_HIT_DROP {
+            return Some(format!(
+                "frames retrieve themselves in {:.0}% of probes, down from {:.0}%",
+                after * 100.0,
+                before * 100.0
+            ));
+        }
+        let (before, after) = (previous.mean_top_score, self.mean_top_score);
+        if before > 0.0 && !(1.0 / MAX_SCORE_RATIO..=MAX_SCORE_RATIO).contains(&(after / before)) {
+            return Some(format!(
+                "mean top semantic score moved from {:.3} to {:.3}",
+                before, after
+            ));
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memvid::MockSearcher;
+
+    fn profile(probes: usize, errors: usize, self_hits: usize, score: f32) -> EmbeddingProfile {
+        EmbeddingProfile {
+            probes,
+            errors,
+            self_hits,
+            mean_top_score: score,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_probes_first_frames() {
+        let searcher = MockSearcher::new();
+        let profile = EmbeddingProfile::probe(&searcher).await;
+        assert!(profile.probes > 0 && profile.probes <= DRIFT_PROBES);
+        assert_eq!(profile.errors, 0);
+        assert!(profile.mean_top_score > 0.0);
+
+        // The same index never drifts from itself
+        assert_eq!(EmbeddingProfile::probe(&searcher).await, profile);
+        assert_eq!(profile.drift_from(&profile), None);
+    }
+
+    #[test]
+    fn test_detects_drift() {
+        let good = profile(8, 0, 7, 0.8);
+        assert_eq!(profile(8, 0, 6, 0.7).drift_from(&good), None);
+
+        let failing = profile(8, 8, 0, 0.0).drift_from(&good).unwrap();
+        assert!(failing.contains("dimension mismatch"));
+        let lost = profile(8, 0, 1, 0.75).drift_from(&good).unwrap();
+        assert!(lost.contains("12%"), "{}", lost);
+        let collapsed = profile(8, 0, 6, 0.2).drift_from(&good).unwrap();
+        assert!(collapsed.contains("0.800 to 0.200"));
+
+        // Nothing to compare against
+        assert_eq!(
+            profile(8, 8, 0, 0.0).drift_from(&profile(8, 8, 0, 0.0)),
+            None
+        );
+        assert_eq!(EmbeddingProfile::default().drift_from(&good), None);
+    }
+}
@@ -0,0 +1,234 @@
+//! JSON Lines dump of everything an index serves.
+//!
+//! An [`IndexDump`] reads the index through the [`Searcher`] in batches and
+//! renders one JSON object per line, tagged by `type`:
+//!
+//! - `index`: the file, frame count, generation, index structures and the
+//!   export time (the first line)
+//! - `frame`: ID, URI, title, tags, labels, the metadata recorded at ingest
+//!   and, unless left out, the text
+//! - `memory_card`: entity, slot and value
+//!
+//! The dump serves audits (what content is actually served) and migrations
+//! to new index formats; Admin/ExportIndex streams it and the `export`
+//! subcommand writes it to a file. Admin/ExportIndex leaves out frames its
+//! caller may not see, as the query APIs do.
+
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use super::searcher::Searcher;
+use super::visibility::{Audience, VisibilityStore};
+use crate::error::ServiceError;
+
+/// Frames read per batch.
+pub const DUMP_BATCH: usize = 100;
+
+/// Where a dump has got to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Stage {
+    Header,
+    Frames { after: Option<u64> },
+    Cards,
+    Done,
+}
+
+/// A JSON Lines dump of an index, read in batches.
+pub struct IndexDump {
+    searcher: Arc<dyn Searcher>,
+    include_text: bool,
+    visibility: Option<(Arc<VisibilityStore>, Audience)>,
+    stage: Stage,
+}
+
+impl IndexDump {
+    /// Dump `searcher`'s index, with frame texts when `include_text` is set.
+    pub fn new(searcher: Arc<dyn Searcher>, include_text: bool) -> Self {
+        Self {
+            searcher,
+            include_text,
+            visibility: None,
+            stage: Stage::Header,
+        }
+    }
+
+    /// Leave out frames `audience` may not see under `store`.
+    pub fn with_visibility(mut self, store: Arc<VisibilityStore>, audience: Audience) -> Self {
+        self.visibility = Some((store, audience));
+        self
+    }
+
+    /// The next lines of the dump, or None when it is complete.
+    ///
+    /// # Errors
+    /// Failures reading frames or memory cards.
+    pub async fn next_batch(&mut self) -> Result<Option<Vec<String>>, ServiceError> {
+        let searcher = Arc::clone(&self.searcher);
+        loop {
+            match self.stage {
+                Stage::Header => {
+                    self.stage = Stage::Frames { after: None };
+                    let header = json!({
+                        "type": "index",
+                        "memvid_file": searcher.memvid_file(),
+                        "frame_count": searcher.frame_count(),
+                        "generation": searcher.generation(),
+                        "features": searcher.index_features(),
+                        "exported_at": chrono::Utc::now().to_rfc3339(),
+                    });
+                    return Ok(Some(vec![header.to_string()]));
+                }
+                Stage::Frames { after } => {
+                    let frames = searcher.export_frames(after, DUMP_BATCH).await?;
+                    let Some(last) = frames.last().map(|frame| frame.frame_id) else {
+                        self.stage = Stage::Cards;
+                        continue;
+                    };
+                    let mut texts: HashMap<u64, String> = if self.include_text {
+                        searcher
+                            .frame_texts(after, DUMP_BATCH)
+                            .await?
+                            .into_iter()
+                            .map(|text| (text.frame_id, text.text))
+                            .collect()
+                    } else {
+                        HashMap::new()
+                    };
+                    self.stage = Stage::Frames { after: Some(last) };
+                    let lines: Vec<String> = frames
+                        .into_iter()
+                        .filter(|frame| match &self.visibility {
+                            Some((store, audience)) => store
+                                .visibility(Some(frame.frame_id), &frame.tags)
+                                .visible_to(*audience),
+                            None => true,
+                        })
+                        .map(|frame| {
+                            let mut line = json!({
+                                "type": "frame",
+                                "frame_id": frame.frame_id,
+                                "uri": frame.uri,
+                                "title": frame.title,
+                                "tags": frame.tags,
+                                "labels": frame.labels,
+                                "metadata": frame.metadata,
+                            });
+                            if self.include_text {
+                                line["text"] = json!(texts.remove(&frame.frame_id));
+                            }
+                            line.to_string()
+                        })
+                        .collect();
+                    if lines.is_empty() {
+                        continue;
+                    }
+                    return Ok(Some(lines));
+                }
+                Stage::Cards => {
+                    self.stage = Stage::Done;
+                    let mut lines = Vec::new();
+                    for entity in searcher.list_entities().await? {
+                        let state = searcher.get_state(&entity.name, None).await?;
+                        let mut slots: Vec<_> = state.slots.into_iter().collect();
+                        slots.sort();
+                        lines.extend(slots.into_iter().map(|(slot, value)| {
+                            json!({
+                                "type": "memory_card",
+                                "entity": entity.name,
+                                "slot": slot,
+                                "value": value,
+                            })
+                            .to_string()
+                        }));
+                    }
+                    return Ok(Some(lines));
+                }
+                Stage::Done => return Ok(None),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memvid::MockSearcher;
+    use serde_json::Value;
+
+    async fn dump(include_text: bool) -> Vec<Value> {
+        read(IndexDump::new(
+            Arc::new(MockSearcher::synthetic(3, 150)),
+            include_text,
+        ))
+        .await
+    }
+
+    async fn read(mut dump: IndexDump) -> Vec<Value> {
+        let mut lines = Vec::new();
+        while let Some(batch) = dump.next_batch().await.unwrap() {
+            lines.extend(batch);
+        }
+        lines
+            .iter()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_dump_lists_frames_and_cards() {
+        let lines = dump(true).await;
+        assert_eq!(lines[0]["type"], "index");
+        assert_eq!(lines[0]["frame_count"], 150);
+
+        let frames: Vec<&Value> = lines.iter().filter(|l| l["type"] == "frame").collect();
+        assert_eq!(frames.len(), 150);
+        assert_eq!(frames[0]["frame_id"], 1);
+        assert!(frames.iter().all(|f| f["text"].is_string()));
+        assert!(frames
+            .windows(2)
+            .all(|w| w[0]["frame_id"].as_u64() < w[1]["frame_id"].as_u64()));
+
+        let cards: Vec<&Value> = lines
+            .iter()
+            .filter(|l| l["type"] == "memory_card")
+            .collect();
+        assert!(cards.iter().any(|c| c["entity"] == "__profile__"));
+        assert!(cards.iter().all(|c| c["value"].is_string()));
+
+        let lines = dump(false).await;
+        assert!(lines
+            .iter()
+            .filter(|l| l["type"] == "frame")
+            .all(|f| f.get("text").is_none()));
+    }
+
+    #[tokio::test]
+    async fn test_dump_leaves_out_frames_hidden_from_the_caller() {
+        use crate::memvid::Visibility;
+
+        let store = Arc::new(VisibilityStore::new());
+        store.set(&[1, 2], Visibility::Hidden).unwrap();
+        store.set(&[3], Visibility::Authenticated).unwrap();
+        let frame_ids = |lines: Vec<Value>| -> Vec<u64> {
+            lines
+                .iter()
+                .filter(|l| l["type"] == "frame")
+                .map(|f| f["frame_id"].as_u64().unwrap())
+                .collect()
+        };
+        let searcher: Arc<dyn Searcher> = Arc::new(MockSearcher::synthetic(3, 150));
+
+        let public = IndexDump::new(Arc::clone(&searcher), true)
+            .with_visibility(Arc::clone(&store), Audience::Public);
+        let ids = frame_ids(read(public).await);
+        assert_eq!(ids.len(), 147);
+        assert_eq!(ids[0], 4);
+
+        let authenticated =
+            IndexDump::new(searcher, true).with_visibility(store, Audience::Authenticated);
+        let ids = frame_ids(read(authenticated).await);
+        assert_eq!(ids.len(), 148);
+        assert_eq!(ids[0], 3);
+    }
+}
@@ -0,0 +1,216 @@
+//! Near-duplicate frame detection.
+//!
+//! Ingesting overlapping resume versions leaves frames with almost the same
+//! text, which makes search results feel repetitive. Frames are compared by
+//! the Jaccard similarity of their word trigrams, and frames linked by a
+//! similarity at or above the threshold are grouped into clusters.
+
+use std::collections::{BTreeMap, HashSet};
+
+use super::searcher::{FrameText, Searcher};
+use crate::error::ServiceError;
+
+/// Frames read per `frame_texts` call.
+const SCAN_BATCH: usize = 256;
+
+/// Most frames compared in one report; pairwise comparison is quadratic.
+pub const MAX_SCAN_FRAMES: usize = 5_000;
+
+/// Words per shingle.
+const SHINGLE_WORDS: usize = 3;
+
+/// A group of frames with near-identical text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DuplicateCluster {
+    /// Frame IDs, ascending
+    pub frame_ids: Vec<u64>,
+    /// Frame titles, in `frame_ids` order
+    pub titles: Vec<String>,
+    /// Highest pairwise similarity within the cluster
+    pub similarity: f32,
+}
+
+/// Result of a duplicate scan.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DuplicateReport {
+    /// Number of frames compared
+    pub frames_scanned: usize,
+    /// Whether the index has more than [`MAX_SCAN_FRAMES`] frames
+    pub truncated: bool,
+    /// Clusters, largest first
+    pub clusters: Vec<DuplicateCluster>,
+}
+
+/// Scan the searcher's index for near-duplicate frames.
+pub async fn scan_duplicates(
+    searcher: &dyn Searcher,
+    min_similarity: f32,
+) -> Result<DuplicateReport, ServiceError> {
+    let mut frames: Vec<FrameText> = Vec::new();
+    let mut after = None;
+    let mut truncated = false;
+
+    loop {
+        let batch = searcher.frame_texts(after, SCAN_BATCH).await?;
+        let Some(last) = batch.last() else {
+            break;
+        };
+        after = Some(last.frame_id);
+        frames.extend(batch);
+        if frames.len() > MAX_SCAN_FRAMES {
+            frames.truncate(MAX_SCAN_FRAMES);
+            truncated = true;
+            break;
+        }
+    }
+
+    // Pairwise comparison is CPU-bound; keep it off the async workers
+    let frames_scanned = frames.len();
+    let clusters = tokio::task::spawn_blocking(move || find_duplicates(&frames, min_similarity))
+        .await
+        .map_err(|e| ServiceError::Internal(format!("Duplicate scan task error: {}", e)))?;
+
+    Ok(DuplicateReport {
+        frames_scanned,
+        truncated,
+        clusters,
+    })
+}
+
+/// Group frames whose pairwise similarity is at least `min_similarity`.
+///
+/// Clusters are ordered by size, then by similarity, both descending.
+pub fn find_duplicates(frames: &[FrameText], min_similarity: f32) -> Vec<DuplicateCluster> {
+    let shingles: Vec<HashSet<String>> = frames.iter().map(|f| shingles(&f.text)).collect();
+    let mut parent: Vec<usize> = (0..frames.len()).collect();
+    let mut best = vec![0.0f32; frames.len()];
+
+    for i in 0..frames.len() {
+        for j in i + 1..frames.len() {
+            let similarity = jaccard(&shingles[i], &shingles[j]);
+            if similarity >= min_similarity {
+                let (a, b) = (root(&mut parent, i), root(&mut parent, j));
+                parent[b] = a;
+                best[a] = best[a].max(best[b]).max(similarity);
+            }
+        }
+    }
+
+    let mut groups: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+    for i in 0..frames.len() {
+        let r = root(&mut parent, i);
+        groups.entry(r).or_default().push(i);
+    }
+
+    let mut clusters: Vec<DuplicateCluster> = groups
+        .into_iter()
+        .filter(|(_, members)| members.len() > 1)
+        .map(|(r, mut members)| {
+            members.sort_by_key(|&i| frames[i].frame_id);
+            DuplicateCluster {
+                frame_ids: members.iter().map(|&i| frames[i].frame_id).collect(),
+                titles: members.iter().map(|&i| frames[i].title.clone()).collect(),
+                similarity: best[r],
+            }
+        })
+        .collect();
+    clusters.sort_by(|a, b| {
+        b.frame_ids
+            .len()
+            .cmp(&a.frame_ids.len())
+            .then(b.similarity.total_cmp(&a.similarity))
+    });
+    clusters
+}
+
+/// Union-find root with path halving.
+fn root(parent: &mut [usize], mut i: usize) -> usize {
+    while parent[i] != i {
+        parent[i] = parent[parent[i]];
+        i = parent[i];
+    }
+    i
+}
+
+/// Lowercased word trigrams of `text` (the whole text when shorter).
+fn shingles(text: &str) -> HashSet<String> {
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    if words.len() < SHINGLE_WORDS {
+        return std::iter::once(words.join(" "))
+            .filter(|s| !s.is_empty())
+            .collect();
+    }
+    words.windows(SHINGLE_WORDS).map(|w| w.join(" ")).collect()
+}
+
+fn jaccard(a: &HashSet<String>, b: &HashSet<String>) -> f32 {
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    let shared = a.intersection(b).count();
+    shared as f32 / (a.len() + b.len() - shared) as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memvid::MockSearcher;
+
+    fn frame(frame_id: u64, text: &str) -> FrameText {
+        FrameText {
+            frame_id,
+            title: format!("Frame {}", frame_id),
+            text: text.to_string(),
+        }
+    }
+
+    const SIEMENS: &str = "Led a team of 12 engineers building an industrial IoT platform \
+                           and drove adoption of Rust for edge services.";
+
+    #[test]
+    fn test_clusters_near_duplicates() {
+        let frames = vec![
+            frame(1, SIEMENS),
+            frame(2, "Proficient in Rust, Python, TypeScript and Go."),
+            // Same text from an older resume version, with different casing
+            frame(3, &SIEMENS.to_uppercase()),
+            frame(4, &SIEMENS.replace("12", "twelve")),
+        ];
+
+        let clusters = find_duplicates(&frames, 0.6);
+        assert_eq!(clusters.len(), 1);
+        assert_eq!(clusters[0].frame_ids, vec![1, 3, 4]);
+        assert_eq!(clusters[0].titles[0], "Frame 1");
+        assert_eq!(clusters[0].similarity, 1.0);
+    }
+
+    #[test]
+    fn test_threshold_separates_overlapping_frames() {
+        let frames = vec![
+            frame(1, SIEMENS),
+            frame(2, &format!("{} Also mentored three new managers.", SIEMENS)),
+        ];
+
+        assert_eq!(find_duplicates(&frames, 0.5).len(), 1);
+        assert!(find_duplicates(&frames, 0.95).is_empty());
+    }
+
+    #[test]
+    fn test_empty_frames_are_not_duplicates() {
+        let frames = vec![frame(1, ""), frame(2, "  ")];
+        assert!(find_duplicates(&frames, 0.1).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_scan_reads_every_frame() {
+        let searcher = MockSearcher::synthetic(7, 600);
+        let report = scan_duplicates(&searcher, 0.99).await.unwrap();
+
+        assert_eq!(report.frames_scanned, 600);
+        assert!(!report.truncated);
+    }
+}
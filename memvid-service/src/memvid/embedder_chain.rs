@@ -0,0 +1,288 @@
+//! Ordered fallback chain of query embedders.
+//!
+//! Tiers are tried in order (e.g. remote → local ONNX). Background probes
+//! track the health of each tier. When no tier can embed a query, the
+//! searcher degrades to lexical-only retrieval, reported as the
+//! [`LEXICAL_TIER`].
+
+use memvid_core::VecEmbedder;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::error::ServiceError;
+use crate::metrics;
+
+/// Tier name reported when a request falls back to lexical-only retrieval.
+pub const LEXICAL_TIER: &str = "lexical";
+
+/// Text embedded by health probes.
+const PROBE_TEXT: &str = "health check";
+
+/// Health of one embedder tier.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TierHealth {
+    /// Tier name (e.g., "remote", "onnx")
+    pub name: String,
+    /// Whether the last probe or request on this tier succeeded
+    pub healthy: bool,
+    /// Last error, empty when healthy
+    pub detail: String,
+}
+
+struct Tier {
+    name: String,
+    embedder: Arc<dyn VecEmbedder + Send + Sync>,
+    healthy: AtomicBool,
+    detail: Mutex<String>,
+}
+
+impl Tier {
+    fn mark(&self, healthy: bool, detail: String) {
+        let was_healthy = self.healthy.swap(healthy, Ordering::Relaxed);
+        if was_healthy != healthy {
+            if healthy {
+                info!(tier = %self.name, "Embedder tier recovered");
+            } else {
+                warn!(tier = %self.name, error = %detail, "Embedder tier unhealthy");
+            }
+        }
+        *self.detail.lock().unwrap_or_else(|e| e.into_inner()) = detail;
+        metrics::set_embedder_healthy(&self.name, healthy);
+    }
+}
+
+/// Embedder that tries each configured tier in order.
+pub struct EmbedderChain {
+    tiers: Vec<Tier>,
+    dimension: usize,
+}
+
+impl EmbedderChain {
+    /// Build a chain from `(name, embedder)` tiers, highest priority first.
+    ///
+    /// All tiers must produce embeddings of the same dimension.
+    pub fn new(
+        tiers: Vec<(String, Arc<dyn VecEmbedder + Send + Sync>)>,
+    ) -> Result<Self, ServiceError> {
+        let Some(dimension) = tiers.first().map(|(_, e)| e.embedding_dimension()) else {
+            return Err(ServiceError::InvalidRequest(
+                "Embedder chain needs at least one tier".to_string(),
+            ));
+        };
+        if let Some((name, embedder)) = tiers
+            .iter()
+            .find(|(_, e)| e.embedding_dimension() != dimension)
+        {
+            return Err(ServiceError::InvalidRequest(format!(
+                "Embedder tier '{}' has dimension {}, expected {}",
+                name,
+                embedder.embedding_dimension(),
+                dimension
+            )));
+        }
+
+        let tiers = tiers
+            .into_iter()
+            .map(|(name, embedder)| {
+                metrics::set_embedder_healthy(&name, true);
+                Tier {
+                    name,
+                    embedder,
+                    healthy: AtomicBool::new(true),
+                    detail: Mutex::new(String::new()),
+                }
+            })
+            .collect();
+
+        Ok(Self { tiers, dimension })
+    }
+
+    /// Whether any tier is currently believed healthy.
+    pub fn has_healthy_tier(&self) -> bool {
+        self.tiers.iter().any(|t| t.healthy.load(Ordering::Relaxed))
+    }
+
+    /// Health of every tier, in chain order.
+    pub fn health(&self) -> Vec<TierHealth> {
+        self.tiers
+            .iter()
+            .map(|t| TierHealth {
+                name: t.name.clone(),
+                healthy: t.healthy.load(Ordering::Relaxed),
+                detail: t.detail.lock().unwrap_or_else(|e| e.into_inner()).clone(),
+            })
+            .collect()
+    }
+
+    /// Probe every tier once by embedding a fixed text (blocking).
+    pub fn probe(&self) {
+        for tier in &self.tiers {
+            match tier.embedder.embed_query(PROBE_TEXT) {
+                Ok(_) => tier.mark(true, String::new()),
+                Err(e) => tier.mark(false, e.to_string()),
+            }
+        }
+    }
+
+    /// Probe all tiers every `interval` in the background.
+    pub fn spawn_probes(self: &Arc<Self>, interval: Duration) -> tokio::task::JoinHandle<()> {
+        let chain = Arc::clone(self);
+        tokio::spawn(async move {
+            loop {
+                let probe = Arc::clone(&chain);
+                if let Err(e) = tokio::task::spawn_blocking(move || probe.probe()).await {
+                    warn!(error = %e, "Embedder probe task failed");
+                }
+                tokio::time::sleep(interval).await;
+            }
+        })
+    }
+}
+
+impl VecEmbedder for EmbedderChain {
+    /// Embed with the first healthy tier that succeeds.
+    ///
+    /// When every tier is marked unhealthy, all are tried anyway so a
+    /// recovered backend is picked up before the next probe.
+    fn embed_query(&self, text: &str) -> memvid_core::Result<Vec<f32>> {
+        let any_healthy = self.has_healthy_tier();
+        let mut last_error = None;
+
+        for tier in &self.tiers {
+            if any_healthy && !tier.healthy.load(Ordering::Relaxed) {
+                continue;
+            }
+            match tier.embedder.embed_query(text) {
+                Ok(embedding) => {
+                    if !tier.healthy.load(Ordering::Relaxed) {
+                        tier.mark(true, String::new());
+                    }
+                    metrics::record_embedder_tier(&tier.name);
+                    return Ok(embedding);
+                }
+                Err(e) => {
+                    tier.mark(false, e.to_string());
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        // The chain is never empty, so at least one tier was tried
+        Err(last_error.expect("embedder chain has at least one tier"))
+    }
+
+    fn embedding_dimension(&self) -> usize {
+        self.dimension
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use memvid_core::Memvid;
+    use std::sync::atomic::AtomicUsize;
+
+    /// Embedder that fails while `down` is set and counts calls.
+    struct FakeEmbedder {
+        dimension: usize,
+        down: AtomicBool,
+        calls: AtomicUsize,
+    }
+
+    impl FakeEmbedder {
+        fn new(dimension: usize, down: bool) -> Arc<Self> {
+            Arc::new(Self {
+                dimension,
+                down: AtomicBool::new(down),
+                calls: AtomicUsize::new(0),
+            })
+        }
+    }
+
+    impl VecEmbedder for FakeEmbedder {
+        fn embed_query(&self, _text: &str) -> memvid_core::Result<Vec<f32>> {
+            self.calls.fetch_add(1, Ordering::Relaxed);
+            if self.down.load(Ordering::Relaxed) {
+                // Any memvid-core error stands in for a backend failure
+                Err(Memvid::open_read_only("/nonexistent/backend.mv2")
+                    .err()
+                    .expect("opening a missing file fails"))
+            } else {
+                Ok(vec![1.0; self.dimension])
+            }
+        }
+
+        fn embedding_dimension(&self) -> usize {
+            self.dimension
+        }
+    }
+
+    fn chain(remote: &Arc<FakeEmbedder>, local: &Arc<FakeEmbedder>) -> EmbedderChain {
+        EmbedderChain::new(vec![
+            ("remote".to_string(), remote.clone() as _),
+            ("onnx".to_string(), local.clone() as _),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn test_falls_back_to_next_tier() {
+        let remote = FakeEmbedder::new(4, true);
+        let local = FakeEmbedder::new(4, false);
+        let chain = chain(&remote, &local);
+
+        assert_eq!(chain.embed_query("rust").unwrap().len(), 4);
+        let health = chain.health();
+        assert!(!health[0].healthy);
+        assert!(!health[0].detail.is_empty());
+        assert!(health[1].healthy);
+
+        // Unhealthy tiers are skipped until they recover
+        chain.embed_query("rust").unwrap();
+        assert_eq!(remote.calls.load(Ordering::Relaxed), 1);
+        assert_eq!(local.calls.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn test_probe_restores_recovered_tier() {
+        let remote = FakeEmbedder::new(4, true);
+        let local = FakeEmbedder::new(4, false);
+        let chain = chain(&remote, &local);
+
+        chain.probe();
+        assert!(!chain.health()[0].healthy);
+
+        remote.down.store(false, Ordering::Relaxed);
+        chain.probe();
+        assert!(chain.health()[0].healthy);
+        assert!(chain.health()[0].detail.is_empty());
+    }
+
+    #[test]
+    fn test_all_tiers_down() {
+        let remote = FakeEmbedder::new(4, true);
+        let local = FakeEmbedder::new(4, true);
+        let chain = chain(&remote, &local);
+
+        assert!(chain.embed_query("rust").is_err());
+        assert!(!chain.has_healthy_tier());
+
+        // With every tier down, all are retried so a recovery is noticed
+        local.down.store(false, Ordering::Relaxed);
+        assert!(chain.embed_query("rust").is_ok());
+        assert!(chain.has_healthy_tier());
+    }
+
+    #[test]
+    fn test_rejects_invalid_chains() {
+        assert!(EmbedderChain::new(Vec::new()).is_err());
+        assert!(EmbedderChain::new(vec![
+            ("remote".to_string(), FakeEmbedder::new(4, false) as _),
+            ("onnx".to_string(), FakeEmbedder::new(8, false) as _),
+        ])
+        .is_err());
+    }
+}
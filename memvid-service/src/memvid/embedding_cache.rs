@@ -0,0 +1,170 @@
+//! Short-lived cache of query embeddings shared across RPCs.
+//!
+//! Only Ask embeds queries in the service (memvid-core's lexical search takes
+//! no embedder), so the cache serves repeated Asks of the same question, such
+//! as retries or a suggested question many visitors click, within the TTL.
+//! One cache is shared by every index and survives reloads.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::metrics;
+
+/// Default lifetime of a cached embedding.
+pub const DEFAULT_EMBEDDING_TTL: Duration = Duration::from_secs(30);
+
+/// Default maximum number of cached embeddings.
+pub const DEFAULT_EMBEDDING_CAPACITY: usize = 256;
+
+/// `cache` label of this cache's metrics.
+const CACHE_NAME: &str = "embedding";
+
+/// TTL-bounded map from query text to embedding.
+pub struct QueryEmbeddingCache {
+    ttl: Duration,
+    capacity: usize,
+    entries: Mutex<HashMap<String, (Instant, Vec<f32>)>>,
+}
+
+impl QueryEmbeddingCache {
+    /// Create a cache holding at most `capacity` embeddings for `ttl` each.
+    pub fn new(ttl: Duration, capacity: usize) -> Self {
+        Self {
+            ttl,
+            capacity,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Return the cached embedding for `text`, computing and caching it on a miss.
+    ///
+    /// Errors from `compute` are returned as-is and nothing is cached.
+    pub fn get_or_compute<E>(
+        &self,
+        text: &str,
+        compute: impl FnOnce() -> Result<Vec<f32>, E>,
+    ) -> Result<Vec<f32>, E> {
+        let key = text.trim();
+
+        if let Some((inserted, embedding)) = self.lock().get(key) {
+            if inserted.elapsed() < self.ttl {
+                metrics::record_embedding_cache(true);
+                metrics::record_cache_lookup(CACHE_NAME, true);
+                return Ok(embedding.clone());
+            }
+        }
+
+        metrics::record_embedding_cache(false);
+        metrics::record_cache_lookup(CACHE_NAME, false);
+        let embedding = compute()?;
+        self.insert(key, embedding.clone());
+        Ok(embedding)
+    }
+
+    /// Number of live entries.
+    pub fn len(&self) -> usize {
+        let ttl = self.ttl;
+        self.lock()
+            .values()
+            .filter(|(inserted, _)| inserted.elapsed() < ttl)
+            .count()
+    }
+
+    /// Whether the cache has no live entries.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn insert(&self, key: &str, embedding: Vec<f32>) {
+        let mut entries = self.lock();
+        let ttl = self.ttl;
+        let before = entries.len();
+        entries.retain(|_, (inserted, _)| inserted.elapsed() < ttl);
+        metrics::record_cache_evictions(CACHE_NAME, "expired", before - entries.len());
+
+        if entries.len() >= self.capacity && !entries.contains_key(key) {
+            let oldest = entries
+                .iter()
+                .min_by_key(|(_, (inserted, _))| *inserted)
+                .map(|(k, _)| k.clone());
+            if let Some(oldest) = oldest {
+                entries.remove(&oldest);
+                metrics::record_cache_evictions(CACHE_NAME, "capacity", 1);
+            }
+        }
+        if self.capacity > 0 {
+            entries.insert(key.to_string(), (Instant::now(), embedding));
+        }
+        metrics::set_cache_entries(CACHE_NAME, entries.len());
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, (Instant, Vec<f32>)>> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Default for QueryEmbeddingCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_EMBEDDING_TTL, DEFAULT_EMBEDDING_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn test_second_lookup_hits_cache() {
+        let cache = QueryEmbeddingCache::default();
+        let computed = Cell::new(0);
+        let compute = || {
+            computed.set(computed.get() + 1);
+            Ok::<_, ()>(vec![0.1, 0.2])
+        };
+
+        assert_eq!(
+            cache.get_or_compute("rust experience", compute),
+            Ok(vec![0.1, 0.2])
+        );
+        assert_eq!(
+            cache.get_or_compute(" rust experience ", compute),
+            Ok(vec![0.1, 0.2])
+        );
+        assert_eq!(computed.get(), 1);
+    }
+
+    #[test]
+    fn test_expired_entry_is_recomputed() {
+        let cache = QueryEmbeddingCache::new(Duration::ZERO, 8);
+        let computed = Cell::new(0);
+        let compute = || {
+            computed.set(computed.get() + 1);
+            Ok::<_, ()>(vec![1.0])
+        };
+
+        cache.get_or_compute("q", compute).unwrap();
+        cache.get_or_compute("q", compute).unwrap();
+        assert_eq!(computed.get(), 2);
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_errors_are_not_cached() {
+        let cache = QueryEmbeddingCache::default();
+        assert_eq!(cache.get_or_compute("q", || Err("down")), Err("down"));
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_capacity_evicts_oldest() {
+        let cache = QueryEmbeddingCache::new(Duration::from_secs(60), 2);
+        for text in ["a", "b", "c"] {
+            cache
+                .get_or_compute(text, || Ok::<_, ()>(vec![0.0]))
+                .unwrap();
+        }
+        assert_eq!(cache.len(), 2);
+    }
+}
@@ -0,0 +1,274 @@
+//! Multi-query ensemble retrieval for Ask.
+//!
+//! Vaguely worded questions ("What has she done with the cloud?") often
+//! miss evidence that a plainer phrasing finds. An ensemble ask retrieves
+//! for the question and for a few rule-based paraphrases of it (its
+//! keywords alone, then with common resume synonyms swapped in) and fuses
+//! the candidate lists with reciprocal rank fusion. Fused evidence is scored
+//! relative to the best candidate (1.0). Paraphrases retrieve context only;
+//! a synthesized answer still comes from the question itself, since
+//! memvid-core synthesizes from its own retrieval.
+
+use std::collections::{HashMap, HashSet};
+use std::time::Instant;
+
+use super::citations::{check_citations, CitationPolicy};
+use super::searcher::{AskRequest, AskResponse, SearchResult, Searcher};
+use super::visibility::context_answer;
+use crate::error::ServiceError;
+
+/// Paraphrases retrieved in addition to the question.
+pub const MAX_PARAPHRASES: usize = 3;
+
+/// Rank offset of reciprocal rank fusion; higher flattens rank differences.
+const RRF_K: f32 = 60.0;
+
+/// Words dropped from the keyword paraphrase.
+const FILLER_WORDS: &[&str] = &[
+    "a", "about", "an", "and", "any", "are", "at", "can", "could", "describe", "did", "do", "does",
+    "explain", "for", "has", "have", "he", "her", "his", "how", "in", "is", "me", "of", "on",
+    "please", "she", "tell", "the", "their", "they", "to", "was", "were", "what", "when", "where",
+    "which", "who", "why", "with", "would", "you", "your",
+];
+
+/// Resume vocabulary swapped into further paraphrases.
+const SYNONYMS: &[(&str, &str)] = &[
+    ("background", "experience"),
+    ("built", "developed"),
+    ("cloud", "aws"),
+    ("company", "employer"),
+    ("degree", "education"),
+    ("done", "projects"),
+    ("job", "role"),
+    ("led", "managed"),
+    ("skills", "technologies"),
+    ("strengths", "skills"),
+    ("studied", "education"),
+    ("team", "leadership"),
+    ("worked", "experience"),
+];
+
+/// Retrieval statistics of one ensemble query.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParaphraseStats {
+    /// Query text (the question first)
+    pub query: String,
+    /// Candidates it retrieved
+    pub candidates: usize,
+    /// Candidates no earlier query retrieved
+    pub new_candidates: usize,
+    /// Retrieval time in milliseconds
+    pub retrieval_ms: i32,
+}
+
+/// Up to [`MAX_PARAPHRASES`] rule-based paraphrases of `question`, none
+/// equal to it.
+pub fn paraphrase(question: &str) -> Vec<String> {
+    let keywords = keywords(question);
+    if keywords.is_empty() {
+        return Vec::new();
+    }
+
+    let mut paraphrases = vec![keywords.join(" ")];
+    for (index, keyword) in keywords.iter().enumerate() {
+        if let Some((_, synonym)) = SYNONYMS.iter().find(|(word, _)| word == keyword) {
+            let mut swapped = keywords.clone();
+            swapped[index] = synonym.to_string();
+            paraphrases.push(swapped.join(" "));
+        }
+    }
+
+    let question = question.trim().to_lowercase();
+    let mut seen = HashSet::new();
+    paraphrases.retain(|p| *p != question && seen.insert(p.clone()));
+    paraphrases.truncate(MAX_PARAPHRASES);
+    paraphrases
+}
+
+/// Lowercased words of `question` without filler words.
+pub(super) fn keywords(question: &str) -> Vec<String> {
+    question
+        .split(|c: char| !c.is_alphanumeric() && c != '+' && c != '#')
+        .map(str::to_lowercase)
+        .filter(|word| !word.is_empty() && !FILLER_WORDS.contains(&word.as_str()))
+        .collect()
+}
+
+/// Ask `request` and its paraphrases, returning the question's response
+/// with the fused evidence and the statistics of every query.
+///
+/// # Errors
+/// The first failed retrieval.
+pub async fn ensemble_ask(
+    searcher: &dyn Searcher,
+    request: AskRequest,
+) -> Result<(AskResponse, Vec<ParaphraseStats>), ServiceError> {
+    let top_k = request.top_k.max(0) as usize;
+    let paraphrases = paraphrase(&request.question);
+
+    let started = Instant::now();
+    let mut response = searcher.ask(request.clone()).await?;
+    let mut stats = vec![ParaphraseStats {
+        query: request.question.clone(),
+        candidates: response.evidence.len(),
+        new_candidates: response.evidence.len(),
+        retrieval_ms: started.elapsed().as_millis() as i32,
+    }];
+    let mut lists = vec![std::mem::take(&mut response.evidence)];
+
+    // Sequential, so each retrieval keeps the request's deadline and
+    // search limit
+    for query in paraphrases {
+        let started = Instant::now();
+        let paraphrased = searcher
+            .ask(AskRequest {
+                question: query.clone(),
+                use_llm: false,
+                ..request.clone()
+            })
+            .await?;
+        let seen: HashSet<HitKey> = lists.iter().flatten().map(HitKey::of).collect();
+        stats.push(ParaphraseStats {
+            query,
+            candidates: paraphrased.evidence.len(),
+            new_candidates: paraphrased
+                .evidence
+                .iter()
+                .filter(|hit| !seen.contains(&HitKey::of(hit)))
+                .count(),
+            retrieval_ms: started.elapsed().as_millis() as i32,
+        });
+        lists.push(paraphrased.evidence);
+    }
+
+    let synthesized = lists[0].clone();
+    let mut fused = fuse(lists);
+    response.stats.candidates_retrieved = fused.len() as i32;
+    fused.truncate(top_k);
+    if request.use_llm {
+        // Cite the fused evidence the answer is returned with
+        response.answer = check_citations(
+            &response.answer,
+            &synthesized,
+            &fused,
+            CitationPolicy::Lenient,
+        )
+        .answer;
+    } else {
+        response.answer = context_answer(&fused);
+    }
+    response.stats.results_returned = fused.len() as i32;
+    response.evidence = fused;
+    // Fused evidence has no page order to continue
+    response.next_cursor = None;
+    Ok((response, stats))
+}
+
+/// Identity of a hit across retrievals.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(super) enum HitKey {
+    Frame(u64),
+    Text(String, String),
+}
+
+impl HitKey {
+    pub(super) fn of(hit: &SearchResult) -> Self {
+        match hit.frame_id {
+            Some(frame_id) => HitKey::Frame(frame_id),
+            None => HitKey::Text(hit.title.clone(), hit.snippet.clone()),
+        }
+    }
+}
+
+/// Reciprocal rank fusion of ranked lists, best first, scored relative to
+/// the best hit.
+pub(super) fn fuse(lists: Vec<Vec<SearchResult>>) -> Vec<SearchResult> {
+    let mut fused: HashMap<HitKey, (f32, SearchResult)> = HashMap::new();
+    for list in lists {
+        for (rank, hit) in list.into_iter().enumerate() {
+            let score = 1.0 / (RRF_K + rank as f32 + 1.0);
+            fused
+                .entry(HitKey::of(&hit))
+                .and_modify(|(total, _)| *total += score)
+                .or_insert((score, hit));
+        }
+    }
+
+    let mut hits: Vec<(f32, SearchResult)> = fused.into_values().collect();
+    hits.sort_by(|a, b| b.0.total_cmp(&a.0));
+    let best = hits.first().map_or(1.0, |(score, _)| *score);
+    hits.into_iter()
+        .map(|(score, mut hit)| {
+            hit.score = score / best;
+            hit
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memvid::{AskMode, MockSearcher};
+
+    fn hit(frame_id: u64) -> SearchResult {
+        SearchResult {
+            frame_id: Some(frame_id),
+            title: format!("Frame {}", frame_id),
+            score: 0.5,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_paraphrases_keywords_and_synonyms() {
+        let paraphrases = paraphrase("What skills has he worked with?");
+        assert_eq!(
+            paraphrases,
+            ["skills worked", "technologies worked", "skills experience"]
+        );
+        assert!(paraphrase("What is it?").len() <= 1);
+        assert!(paraphrase("rust").is_empty());
+    }
+
+    #[test]
+    fn test_fusion_prefers_hits_found_by_several_queries() {
+        let fused = fuse(vec![
+            vec![hit(1), hit(2), hit(3)],
+            vec![hit(3), hit(4)],
+            vec![hit(3), hit(2)],
+        ]);
+        let order: Vec<u64> = fused.iter().filter_map(|h| h.frame_id).collect();
+        assert_eq!(order, [3, 2, 1, 4]);
+        assert_eq!(fused[0].score, 1.0);
+        assert!(fused[3].score < fused[2].score);
+    }
+
+    #[tokio::test]
+    async fn test_ensemble_ask_reports_every_query() {
+        let searcher = MockSearcher::new();
+        let request = AskRequest {
+            question: "What leadership experience do you have?".to_string(),
+            use_llm: false,
+            top_k: 3,
+            filters: HashMap::new(),
+            start: 0,
+            end: 0,
+            snippet_chars: 200,
+            mode: AskMode::Hybrid,
+            uri: None,
+            cursor: None,
+            as_of_frame: None,
+            as_of_ts: None,
+            adaptive: None,
+            acl: None,
+        };
+
+        let (response, stats) = ensemble_ask(&searcher, request.clone()).await.unwrap();
+        assert_eq!(stats[0].query, request.question);
+        assert_eq!(stats.len(), 1 + paraphrase(&request.question).len());
+        assert!(stats.len() > 1);
+        assert_eq!(response.evidence.len(), 3);
+        assert_eq!(response.evidence[0].score, 1.0);
+        assert!(response.answer.contains(&response.evidence[0].title));
+    }
+}
@@ -0,0 +1,97 @@
+//! Per-frame freshness hints.
+//!
+//! Hits carry when their frame was ingested and which version of the source
+//! document it came from, so a client can label results like "from resume
+//! v12, updated 2024-06". Both come from frame metadata written at ingest:
+//! `source_version` verbatim and `ingested_at` as Unix seconds or RFC 3339.
+//! Frames without an `ingested_at` entry fall back to their frame
+//! timestamp, which the ingest pipeline sets at ingestion time.
+
+use std::collections::BTreeMap;
+
+/// Frame metadata key holding the ingestion time.
+pub const INGESTED_AT_KEY: &str = "ingested_at";
+
+/// Frame metadata key holding the source document version.
+pub const SOURCE_VERSION_KEY: &str = "source_version";
+
+/// Freshness of one frame.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Freshness {
+    /// When the frame was ingested (Unix seconds), when known
+    pub ingested_at: Option<i64>,
+    /// Version of the source document, when recorded
+    pub source_version: Option<String>,
+}
+
+impl Freshness {
+    /// Read a frame's freshness from its metadata and timestamp (0 = unset).
+    pub fn from_metadata(metadata: &BTreeMap<String, String>, timestamp: i64) -> Self {
+        let ingested_at = metadata
+            .get(INGESTED_AT_KEY)
+            .and_then(|value| parse_time(value))
+            .or((timestamp > 0).then_some(timestamp));
+        let source_version = metadata
+            .get(SOURCE_VERSION_KEY)
+            .map(|version| version.trim())
+            .filter(|version| !version.is_empty())
+            .map(String::from);
+        Self {
+            ingested_at,
+            source_version,
+        }
+    }
+
+    /// True when neither hint is known.
+    pub fn is_empty(&self) -> bool {
+        self.ingested_at.is_none() && self.source_version.is_none()
+    }
+}
+
+/// Unix seconds or an RFC 3339 date-time.
+fn parse_time(value: &str) -> Option<i64> {
+    let value = value.trim();
+    value.parse().ok().or_else(|| {
+        chrono::DateTime::parse_from_rfc3339(value)
+            .ok()
+            .map(|at| at.timestamp())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata(entries: &[(&str, &str)]) -> BTreeMap<String, String> {
+        entries
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_reads_metadata_entries() {
+        let freshness = Freshness::from_metadata(
+            &metadata(&[
+                ("ingested_at", "2024-06-01T12:00:00Z"),
+                ("source_version", " v12 "),
+            ]),
+            1,
+        );
+        assert_eq!(freshness.ingested_at, Some(1_717_243_200));
+        assert_eq!(freshness.source_version.as_deref(), Some("v12"));
+
+        let freshness = Freshness::from_metadata(&metadata(&[("ingested_at", "1717243200")]), 0);
+        assert_eq!(freshness.ingested_at, Some(1_717_243_200));
+    }
+
+    #[test]
+    fn test_falls_back_to_frame_timestamp() {
+        let freshness =
+            Freshness::from_metadata(&metadata(&[("ingested_at", "last week")]), 1_700_000_000);
+        assert_eq!(freshness.ingested_at, Some(1_700_000_000));
+        assert_eq!(freshness.source_version, None);
+
+        assert!(Freshness::from_metadata(&metadata(&[("source_version", "")]), 0).is_empty());
+    }
+}
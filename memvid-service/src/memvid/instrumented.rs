@@ -0,0 +1,254 @@
+//! Instrumented lock wrapper for contention diagnostics.
+//!
+//! memvid-core needs `&mut Memvid` for search and ask, so every query takes the
+//! write lock from inside a blocking task (state lookups and frame exports
+//! share the read lock). These wrappers measure how long
+//! callers wait for the lock, how long writers hold it, and how long blocking
+//! tasks sit in tokio's queue before they start.
+
+use serde::Serialize;
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use tokio::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard, TryLockError};
+
+/// Point-in-time snapshot of lock and blocking-pool timings (microseconds).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct LockDiagnostics {
+    /// Read lock acquisitions
+    pub read_acquisitions: u64,
+    /// Total time spent waiting for read locks
+    pub read_wait_us_total: u64,
+    /// Longest wait for a read lock
+    pub read_wait_us_max: u64,
+    /// Write lock acquisitions
+    pub write_acquisitions: u64,
+    /// Total time spent waiting for write locks
+    pub write_wait_us_total: u64,
+    /// Longest wait for a write lock
+    pub write_wait_us_max: u64,
+    /// Longest time a write lock was held
+    pub write_hold_us_max: u64,
+    /// Blocking tasks started
+    pub blocking_tasks: u64,
+    /// Total time blocking tasks waited to start
+    pub blocking_queue_us_total: u64,
+    /// Longest time a blocking task waited to start
+    pub blocking_queue_us_max: u64,
+}
+
+impl LockDiagnostics {
+    /// Combine the timings of two locks: totals add up, maxima take the
+    /// larger.
+    pub fn merge(self, other: Self) -> Self {
+        Self {
+            read_acquisitions: self.read_acquisitions + other.read_acquisitions,
+            read_wait_us_total: self.read_wait_us_total + other.read_wait_us_total,
+            read_wait_us_max: self.read_wait_us_max.max(other.read_wait_us_max),
+            write_acquisitions: self.write_acquisitions + other.write_acquisitions,
+            write_wait_us_total: self.write_wait_us_total + other.write_wait_us_total,
+            write_wait_us_max: self.write_wait_us_max.max(other.write_wait_us_max),
+            write_hold_us_max: self.write_hold_us_max.max(other.write_hold_us_max),
+            blocking_tasks: self.blocking_tasks + other.blocking_tasks,
+            blocking_queue_us_total: self.blocking_queue_us_total + other.blocking_queue_us_total,
+            blocking_queue_us_max: self.blocking_queue_us_max.max(other.blocking_queue_us_max),
+        }
+    }
+}
+
+/// Running totals behind [`LockDiagnostics`].
+#[derive(Debug, Default)]
+pub struct LockStats {
+    read_acquisitions: AtomicU64,
+    read_wait_us_total: AtomicU64,
+    read_wait_us_max: AtomicU64,
+    write_acquisitions: AtomicU64,
+    write_wait_us_total: AtomicU64,
+    write_wait_us_max: AtomicU64,
+    write_hold_us_max: AtomicU64,
+    blocking_tasks: AtomicU64,
+    blocking_queue_us_total: AtomicU64,
+    blocking_queue_us_max: AtomicU64,
+}
+
+impl LockStats {
+    /// Record how long a blocking task waited between spawn and start.
+    pub fn record_blocking_queue(&self, queued: Duration) {
+        let us = micros(queued);
+        self.blocking_tasks.fetch_add(1, Ordering::Relaxed);
+        self.blocking_queue_us_total
+            .fetch_add(us, Ordering::Relaxed);
+        self.blocking_queue_us_max.fetch_max(us, Ordering::Relaxed);
+    }
+
+    /// Snapshot the current totals.
+    pub fn snapshot(&self) -> LockDiagnostics {
+        let load = |v: &AtomicU64| v.load(Ordering::Relaxed);
+        LockDiagnostics {
+            read_acquisitions: load(&self.read_acquisitions),
+            read_wait_us_total: load(&self.read_wait_us_total),
+            read_wait_us_max: load(&self.read_wait_us_max),
+            write_acquisitions: load(&self.write_acquisitions),
+            write_wait_us_total: load(&self.write_wait_us_total),
+            write_wait_us_max: load(&self.write_wait_us_max),
+            write_hold_us_max: load(&self.write_hold_us_max),
+            blocking_tasks: load(&self.blocking_tasks),
+            blocking_queue_us_total: load(&self.blocking_queue_us_total),
+            blocking_queue_us_max: load(&self.blocking_queue_us_max),
+        }
+    }
+
+    fn record_read_wait(&self, waited: Duration) {
+        let us = micros(waited);
+        self.read_acquisitions.fetch_add(1, Ordering::Relaxed);
+        self.read_wait_us_total.fetch_add(us, Ordering::Relaxed);
+        self.read_wait_us_max.fetch_max(us, Ordering::Relaxed);
+    }
+
+    fn record_write_wait(&self, waited: Duration) {
+        let us = micros(waited);
+        self.write_acquisitions.fetch_add(1, Ordering::Relaxed);
+        self.write_wait_us_total.fetch_add(us, Ordering::Relaxed);
+        self.write_wait_us_max.fetch_max(us, Ordering::Relaxed);
+    }
+
+    fn record_write_hold(&self, held: Duration) {
+        self.write_hold_us_max
+            .fetch_max(micros(held), Ordering::Relaxed);
+    }
+}
+
+fn micros(d: Duration) -> u64 {
+    d.as_micros().min(u64::MAX as u128) as u64
+}
+
+/// Async RwLock that records wait and hold times into [`LockStats`].
+#[derive(Debug, Default)]
+pub struct InstrumentedRwLock<T> {
+    inner: RwLock<T>,
+    stats: LockStats,
+}
+
+impl<T> InstrumentedRwLock<T> {
+    /// Wrap `value` in an instrumented lock.
+    pub fn new(value: T) -> Self {
+        Self {
+            inner: RwLock::new(value),
+            stats: LockStats::default(),
+        }
+    }
+
+    /// Lock statistics, including blocking-queue timings recorded by callers.
+    pub fn stats(&self) -> &LockStats {
+        &self.stats
+    }
+
+    /// Try to acquire a read lock without waiting (not recorded).
+    pub fn try_read(&self) -> Result<RwLockReadGuard<'_, T>, TryLockError> {
+        self.inner.try_read()
+    }
+
+    /// Try to acquire the write lock without waiting (not recorded).
+    pub fn try_write(&self) -> Result<RwLockWriteGuard<'_, T>, TryLockError> {
+        self.inner.try_write()
+    }
+
+    /// Acquire a shared read lock, recording the wait.
+    pub async fn read(&self) -> RwLockReadGuard<'_, T> {
+        let start = Instant::now();
+        let guard = self.inner.read().await;
+        self.stats.record_read_wait(start.elapsed());
+        guard
+    }
+
+    /// Acquire the exclusive write lock, recording the wait and hold times.
+    pub async fn write(&self) -> TimedWriteGuard<'_, T> {
+        let start = Instant::now();
+        let guard = self.inner.write().await;
+        self.stats.record_write_wait(start.elapsed());
+        TimedWriteGuard {
+            guard,
+            acquired: Instant::now(),
+            stats: &self.stats,
+        }
+    }
+}
+
+/// Write guard that records how long it was held when dropped.
+pub struct TimedWriteGuard<'a, T> {
+    guard: RwLockWriteGuard<'a, T>,
+    acquired: Instant,
+    stats: &'a LockStats,
+}
+
+impl<T> Deref for TimedWriteGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.guard
+    }
+}
+
+impl<T> DerefMut for TimedWriteGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.guard
+    }
+}
+
+impl<T> Drop for TimedWriteGuard<'_, T> {
+    fn drop(&mut self) {
+        self.stats.record_write_hold(self.acquired.elapsed());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_records_acquisitions_and_hold_time() {
+        let lock = InstrumentedRwLock::new(0u32);
+
+        {
+            let mut guard = lock.write().await;
+            *guard += 1;
+            std::thread::sleep(Duration::from_millis(2));
+        }
+        assert_eq!(*lock.read().await, 1);
+
+        let diag = lock.stats().snapshot();
+        assert_eq!(diag.write_acquisitions, 1);
+        assert_eq!(diag.read_acquisitions, 1);
+        assert!(diag.write_hold_us_max >= 2_000);
+    }
+
+    #[tokio::test]
+    async fn test_contended_write_wait_is_recorded() {
+        let lock = std::sync::Arc::new(InstrumentedRwLock::new(()));
+        let guard = lock.write().await;
+
+        let waiter = tokio::spawn({
+            let lock = std::sync::Arc::clone(&lock);
+            async move {
+                let _guard = lock.write().await;
+            }
+        });
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        drop(guard);
+        waiter.await.unwrap();
+
+        assert!(lock.stats().snapshot().write_wait_us_max >= 5_000);
+    }
+
+    #[test]
+    fn test_blocking_queue_max() {
+        let stats = LockStats::default();
+        stats.record_blocking_queue(Duration::from_micros(10));
+        stats.record_blocking_queue(Duration::from_micros(30));
+
+        let diag = stats.snapshot();
+        assert_eq!(diag.blocking_tasks, 2);
+        assert_eq!(diag.blocking_queue_us_total, 40);
+        assert_eq!(diag.blocking_queue_us_max, 30);
+    }
+}
@@ -0,0 +1,182 @@
+//! Frame language detection and language-aware ranking.
+//!
+//! A multilingual index (e.g., an English and a German resume in one .mv2)
+//! is tagged at load: each frame's language is detected from its text by
+//! counting common function words, and hits carry a `lang:<code>` tag.
+//! Requests can then prefer one language, either boosting its hits or
+//! dropping the others.
+
+use super::searcher::SearchResult;
+
+/// Prefix of the tag carrying a hit's detected language.
+pub const LANGUAGE_TAG_PREFIX: &str = "lang:";
+
+/// Score multiplier for hits in the preferred language.
+pub const LANGUAGE_BOOST: f32 = 1.25;
+
+/// Candidates retrieved per requested hit when a language is preferred.
+pub const LANGUAGE_OVERFETCH: i32 = 4;
+
+/// Function words that must match before a language is assigned.
+const MIN_STOPWORD_HITS: usize = 2;
+
+/// Common function words per language code (the answer locales).
+const STOPWORDS: &[(&str, &[&str])] = &[
+    (
+        "en",
+        &[
+            "the", "and", "of", "to", "in", "is", "with", "for", "on", "as", "was", "at", "by",
+            "an", "are", "from", "this", "that", "it",
+        ],
+    ),
+    (
+        "de",
+        &[
+            "der", "die", "das", "und", "ist", "mit", "für", "von", "den", "dem", "ein", "eine",
+            "nicht", "auf", "im", "zu", "sich", "auch", "bei", "wurde",
+        ],
+    ),
+    (
+        "fr",
+        &[
+            "le", "la", "les", "et", "des", "est", "une", "un", "du", "dans", "pour", "avec",
+            "sur", "au", "aux", "que", "qui", "par", "été",
+        ],
+    ),
+    (
+        "es",
+        &[
+            "el", "los", "las", "y", "del", "una", "un", "es", "en", "con", "para", "por", "que",
+            "como", "su", "al", "se", "fue",
+        ],
+    ),
+];
+
+/// Detect the language of `text`, returning its code.
+///
+/// Returns `None` when too few function words match or two languages tie.
+pub fn detect_language(text: &str) -> Option<&'static str> {
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+
+    let mut counts: Vec<(&'static str, usize)> = STOPWORDS
+        .iter()
+        .map(|(code, stopwords)| {
+            let hits = words
+                .iter()
+                .filter(|w| stopwords.contains(&w.as_str()))
+                .count();
+            (*code, hits)
+        })
+        .collect();
+    counts.sort_by_key(|&(_, hits)| std::cmp::Reverse(hits));
+
+    let (code, best) = counts[0];
+    let runner_up = counts[1].1;
+    (best >= MIN_STOPWORD_HITS && best > runner_up).then_some(code)
+}
+
+/// Tag for a detected language (e.g., "lang:de").
+pub fn language_tag(code: &str) -> String {
+    format!("{}{}", LANGUAGE_TAG_PREFIX, code)
+}
+
+/// Language code from a hit's tags, if it was detected.
+pub fn hit_language(hit: &SearchResult) -> Option<&str> {
+    hit.tags
+        .iter()
+        .find_map(|tag| tag.strip_prefix(LANGUAGE_TAG_PREFIX))
+}
+
+/// Rank hits for a preferred language.
+///
+/// With `strict`, hits in other or undetected languages are dropped;
+/// otherwise hits in the preferred language have their score boosted by
+/// [`LANGUAGE_BOOST`] and hits are re-sorted by score.
+pub fn apply_language_preference(hits: &mut Vec<SearchResult>, language: &str, strict: bool) {
+    if strict {
+        hits.retain(|hit| hit_language(hit) == Some(language));
+        return;
+    }
+    for hit in hits.iter_mut() {
+        if hit_language(hit) == Some(language) {
+            hit.score *= LANGUAGE_BOOST;
+        }
+    }
+    hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hit(title: &str, score: f32, language: Option<&str>) -> SearchResult {
+        SearchResult {
+            title: title.to_string(),
+            score,
+            tags: language.map(language_tag).into_iter().collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_detects_languages() {
+        assert_eq!(
+            detect_language("Led a team of 12 engineers building the platform for edge devices."),
+            Some("en")
+        );
+        assert_eq!(
+            detect_language("Leitung eines Teams von 12 Ingenieuren und Aufbau der Plattform."),
+            Some("de")
+        );
+        assert_eq!(
+            detect_language(
+                "Direction d'une équipe de 12 ingénieurs pour la plateforme et les services."
+            ),
+            Some("fr")
+        );
+        assert_eq!(
+            detect_language(
+                "Dirigió un equipo de 12 ingenieros para la plataforma del grupo y los servicios."
+            ),
+            Some("es")
+        );
+    }
+
+    #[test]
+    fn test_short_or_ambiguous_text_is_undetected() {
+        assert_eq!(detect_language("Rust, Python, Kubernetes"), None);
+        assert_eq!(detect_language(""), None);
+    }
+
+    #[test]
+    fn test_boost_reorders_preferred_language() {
+        let mut hits = vec![
+            hit("en", 0.9, Some("en")),
+            hit("de", 0.8, Some("de")),
+            hit("none", 0.7, None),
+        ];
+        apply_language_preference(&mut hits, "de", false);
+
+        let titles: Vec<&str> = hits.iter().map(|h| h.title.as_str()).collect();
+        assert_eq!(titles, vec!["de", "en", "none"]);
+        assert_eq!(hits[0].score, 0.8 * LANGUAGE_BOOST);
+    }
+
+    #[test]
+    fn test_strict_drops_other_languages() {
+        let mut hits = vec![
+            hit("en", 0.9, Some("en")),
+            hit("de", 0.8, Some("de")),
+            hit("none", 0.7, None),
+        ];
+        apply_language_preference(&mut hits, "de", true);
+
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].title, "de");
+        assert_eq!(hits[0].score, 0.8);
+    }
+}
@@ -1,13 +1,33 @@
 //! Mock searcher implementation for testing without memvid-core.
 
 use async_trait::async_trait;
-use std::time::Instant;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tracing::info;
 
+use super::acronyms::AcronymMap;
+use super::instrumented::LockDiagnostics;
+use super::language::{detect_language, language_tag};
 use super::searcher::{
-    AskRequest, AskResponse, AskStats, SearchResponse, SearchResult, Searcher, StateResponse,
+    AskMode, AskRequest, AskResponse, AskStats, EntitySummary, FrameMetadata, FrameText,
+    IndexFeatures, SearchRequest, SearchResponse, SearchResult, Searcher, StateResponse,
 };
+use super::snippet::truncate_snippet;
+use super::spelling::SpellDictionary;
+use super::suggest::SuggestionIndex;
+use super::synthetic::{self, SyntheticFrame};
 use crate::error::ServiceError;
+use crate::runtime_config::MIN_SNIPPET_CHARS;
+
+/// Ingestion time reported for every mock frame (2024-06-01).
+const MOCK_INGESTED_AT: i64 = 1_717_243_200;
+
+/// Source document version reported for every mock frame.
+const MOCK_SOURCE_VERSION: &str = "v1";
+
+/// Simulated search latency (real memvid would be ~1-5ms).
+const MOCK_SEARCH_LATENCY: Duration = Duration::from_millis(2);
 
 /// Mock searcher that returns hardcoded results for testing.
 ///
@@ -16,6 +36,14 @@ use crate::error::ServiceError;
 pub struct MockSearcher {
     frame_count: i32,
     memvid_file: String,
+    /// Generated corpus replacing the fixed sample entries
+    synthetic: Option<Vec<SyntheticFrame>>,
+    /// Acronyms defined in the corpus
+    acronyms: Arc<AcronymMap>,
+    /// Words of the corpus, for spelling correction
+    spelling: Arc<SpellDictionary>,
+    /// Titles, tags and words of the corpus, for typeahead
+    suggestions: Arc<SuggestionIndex>,
 }
 
 impl MockSearcher {
@@ -25,20 +53,67 @@ impl MockSearcher {
         Self {
             frame_count: 42, // Simulated frame count
             memvid_file: "mock://sample-resume.mv2".to_string(),
+            synthetic: None,
+            acronyms: Arc::default(),
+            spelling: Arc::default(),
+            suggestions: Arc::default(),
         }
+        .with_learned_terms()
     }
 
-    /// Generate mock search results based on query keywords.
-    fn generate_results(&self, query: &str, top_k: i32, snippet_chars: i32) -> Vec<SearchResult> {
-        let query_lower = query.to_lowercase();
-        let mut results = Vec::new();
+    /// Create a mock searcher over a seeded synthetic corpus of `size` frames.
+    pub fn synthetic(seed: u64, size: usize) -> Self {
+        info!(
+            seed,
+            size, "Initializing MockSearcher with synthetic resume data"
+        );
+        let frames = synthetic::generate(seed, size);
+        Self {
+            frame_count: frames.len() as i32,
+            memvid_file: format!("mock://synthetic-{}-{}.mv2", seed, size),
+            synthetic: Some(frames),
+            acronyms: Arc::default(),
+            spelling: Arc::default(),
+            suggestions: Arc::default(),
+        }
+        .with_learned_terms()
+    }
 
+    /// Learn acronyms, spelling and suggestions from corpus titles,
+    /// snippets and tags, as at index load.
+    fn with_learned_terms(mut self) -> Self {
+        let (acronyms, spelling, suggestions) = {
+            let corpus = self.corpus();
+            let texts = || {
+                corpus
+                    .iter()
+                    .flat_map(|(title, _, snippet, _)| [*title, *snippet])
+            };
+            let mut suggestions = SuggestionIndex::default();
+            for (index, (title, _, snippet, tags)) in corpus.iter().enumerate() {
+                // Frame IDs as reported in search results
+                suggestions.add_frame(index as u64 + 1, title, snippet, tags);
+            }
+            (
+                AcronymMap::from_texts(texts()),
+                SpellDictionary::from_texts(texts()),
+                suggestions,
+            )
+        };
+        self.acronyms = Arc::new(acronyms);
+        self.spelling = Arc::new(spelling);
+        self.suggestions = Arc::new(suggestions);
+        self
+    }
+
+    /// Corpus entries as (title, base score, snippet, tags), in frame ID order.
+    fn corpus(&self) -> Vec<(&str, f32, &str, Vec<&str>)> {
         // Sample resume data - would come from .mv2 in real implementation
-        let sample_data = vec![
+        let fixed_data = vec![
             (
                 "Senior Engineering Manager at Siemens",
                 0.95,
-                "Led cross-functional team of 12 engineers building industrial IoT platform. \
+                "Led cross-functional team of 12 engineers building industrial IoT (IIoT) platform. \
                  Implemented CI/CD pipelines reducing deployment time by 60%. \
                  Drove adoption of Rust for performance-critical edge services.",
                 vec!["experience", "leadership", "siemens"],
@@ -85,8 +160,54 @@ impl MockSearcher {
             ),
         ];
 
+        match &self.synthetic {
+            Some(frames) => frames
+                .iter()
+                .map(|f| {
+                    let tags = f.tags.iter().map(String::as_str).collect();
+                    (f.title.as_str(), f.base_score, f.snippet.as_str(), tags)
+                })
+                .collect(),
+            None => fixed_data,
+        }
+    }
+
+    /// Generate mock search results based on query keywords.
+    ///
+    /// Stops scoring candidates once `deadline` passes and reports the
+    /// result set as partial.
+    fn generate_results(
+        &self,
+        query: &str,
+        top_k: i32,
+        snippet_chars: i32,
+        deadline: Option<Instant>,
+    ) -> (Vec<SearchResult>, bool) {
+        let query_lower = query.to_lowercase();
+        let mut results = Vec::new();
+        let mut partial = false;
+
+        let sample_data = self.corpus();
+
         // Score and filter results based on query relevance
-        for (title, base_score, snippet, tags) in sample_data {
+        for (index, (title, base_score, snippet, tags)) in sample_data.into_iter().enumerate() {
+            if deadline.is_some_and(|d| Instant::now() >= d) {
+                partial = true;
+                break;
+            }
+
+            // Like a lexical index, the synthetic corpus only returns frames
+            // sharing at least one query term
+            if self.synthetic.is_some() {
+                let text = format!("{} {} {}", title, snippet, tags.join(" ")).to_lowercase();
+                if !query_lower
+                    .split_whitespace()
+                    .any(|term| text.contains(term))
+                {
+                    continue;
+                }
+            }
+
             let mut score: f32 = base_score;
 
             // Boost score if query matches tags or content
@@ -105,18 +226,25 @@ impl MockSearcher {
             // Clamp score to 1.0
             score = score.min(1.0);
 
-            // Truncate snippet to requested length
-            let truncated_snippet = if snippet.len() > snippet_chars as usize {
-                format!("{}...", &snippet[..snippet_chars as usize - 3])
-            } else {
-                snippet.to_string()
-            };
+            let truncated_snippet = truncate_snippet(snippet, snippet_chars as usize);
 
+            let uri = tags
+                .first()
+                .map(|section| format!("mv2://resume/{}", section));
             results.push(SearchResult {
+                frame_id: Some(index as u64 + 1),
                 title: title.to_string(),
                 score,
                 snippet: truncated_snippet,
-                tags: tags.into_iter().map(String::from).collect(),
+                tags: tags
+                    .into_iter()
+                    .map(String::from)
+                    .chain(detect_language(snippet).map(language_tag))
+                    .collect(),
+                uri,
+                timestamp: None,
+                ingested_at: Some(MOCK_INGESTED_AT),
+                source_version: Some(MOCK_SOURCE_VERSION.to_string()),
             });
         }
 
@@ -126,7 +254,7 @@ impl MockSearcher {
         // Limit to top_k
         results.truncate(top_k as usize);
 
-        results
+        (results, partial)
     }
 }
 
@@ -136,35 +264,69 @@ impl Default for MockSearcher {
     }
 }
 
+/// Metadata filters: every filter value must be one of the hit's tags.
+fn matches_filters(
+    hit: &SearchResult,
+    filters: &std::collections::HashMap<String, String>,
+) -> bool {
+    filters
+        .values()
+        .all(|v| hit.tags.iter().any(|t| t.eq_ignore_ascii_case(v)))
+}
+
 #[async_trait]
 impl Searcher for MockSearcher {
-    async fn search(
-        &self,
-        query: &str,
-        top_k: i32,
-        snippet_chars: i32,
-    ) -> Result<SearchResponse, ServiceError> {
+    async fn search(&self, request: SearchRequest) -> Result<SearchResponse, ServiceError> {
         let start = Instant::now();
+        let budget = request
+            .budget_ms
+            .map(|budget| Duration::from_millis(budget as u64));
+        let deadline = budget.map(|budget| start + budget);
 
         // Validate inputs
-        if query.trim().is_empty() {
+        if request.query.trim().is_empty() {
             return Err(ServiceError::InvalidRequest("Query cannot be empty".into()));
         }
 
-        let top_k = top_k.clamp(1, 20);
-        let snippet_chars = snippet_chars.clamp(50, 1000);
-
-        // Simulate some processing time (real memvid would be ~1-5ms)
-        tokio::time::sleep(tokio::time::Duration::from_millis(2)).await;
+        // Request limits are applied by the gRPC layer (see RuntimeConfig)
+        let top_k = request.top_k.max(1);
+        let snippet_chars = request.snippet_chars.max(MIN_SNIPPET_CHARS);
+
+        // Like the real index, a budget that expires before retrieval
+        // finishes returns no hits
+        if let Some(budget) = budget.filter(|&budget| budget < MOCK_SEARCH_LATENCY) {
+            tokio::time::sleep(budget).await;
+            return Ok(SearchResponse {
+                hits: Vec::new(),
+                total_hits: 0,
+                took_ms: start.elapsed().as_millis() as i32,
+                partial: true,
+                expanded_query: None,
+            });
+        }
+        tokio::time::sleep(MOCK_SEARCH_LATENCY).await;
 
-        let hits = self.generate_results(query, top_k, snippet_chars);
+        // With filters or a URI, rank every candidate and keep the matching ones
+        let scoped = !request.filters.is_empty() || request.uri.is_some();
+        let (mut hits, partial) = if scoped {
+            self.generate_results(&request.query, i32::MAX, snippet_chars, deadline)
+        } else {
+            self.generate_results(&request.query, top_k, snippet_chars, deadline)
+        };
+        if scoped {
+            hits.retain(|hit| {
+                matches_filters(hit, &request.filters)
+                    && (request.uri.is_none() || hit.uri == request.uri)
+            });
+            hits.truncate(top_k as usize);
+        }
         let total_hits = hits.len() as i32;
         let took_ms = start.elapsed().as_millis() as i32;
 
         info!(
-            query = %query,
             hits = total_hits,
             took_ms = took_ms,
+            partial = partial,
             "Mock search completed"
         );
 
@@ -172,6 +334,8 @@ impl Searcher for MockSearcher {
             hits,
             total_hits,
             took_ms,
+            partial,
+            expanded_query: None,
         })
     }
 
@@ -179,7 +343,6 @@ impl Searcher for MockSearcher {
         let start = Instant::now();
 
         info!(
-            question = %request.question,
             mode = ?request.mode,
             "Mock ask called"
         );
@@ -191,15 +354,98 @@ impl Searcher for MockSearcher {
             ));
         }
 
-        let top_k = request.top_k.clamp(1, 20);
-        let snippet_chars = request.snippet_chars.clamp(50, 1000);
+        let top_k = request.top_k.max(1) as usize;
+        let snippet_chars = request.snippet_chars.max(MIN_SNIPPET_CHARS);
+
+        // Pagination cursor: offset into the ranked candidates
+        let offset = match request.cursor.as_deref() {
+            None | Some("") => 0,
+            Some(cursor) => cursor
+                .parse::<usize>()
+                .map_err(|_| ServiceError::InvalidRequest(format!("Invalid cursor: {}", cursor)))?,
+        };
 
         // Simulate processing time
         tokio::time::sleep(tokio::time::Duration::from_millis(5)).await;
 
-        // Reuse search logic to get evidence
-        let evidence = self.generate_results(&request.question, top_k, snippet_chars);
-        let candidates_retrieved = evidence.len() as i32;
+        // Reuse search logic to rank every candidate
+        let (mut candidates, _) =
+            self.generate_results(&request.question, i32::MAX, snippet_chars, None);
+
+        if !request.filters.is_empty() {
+            candidates.retain(|hit| matches_filters(hit, &request.filters));
+        }
+        if request.uri.is_some() {
+            candidates.retain(|hit| hit.uri == request.uri);
+        }
+
+        // Simulate mode differences
+        let terms: Vec<String> = request
+            .question
+            .to_lowercase()
+            .split_whitespace()
+            .filter(|t| t.len() > 2)
+            .map(String::from)
+            .collect();
+        let is_lexical_match = |hit: &SearchResult| {
+            let text =
+                format!("{} {} {}", hit.title, hit.snippet, hit.tags.join(" ")).to_lowercase();
+            terms.iter().any(|t| text.contains(t.as_str()))
+        };
+
+        let mut used_fallback = false;
+        let mut reranking_ms = 0;
+        match request.mode {
+            AskMode::Lex => {
+                // BM25 only returns term matches; fall back to semantic ranking
+                // when nothing matches, like memvid does
+                let matched: Vec<SearchResult> = candidates
+                    .iter()
+                    .filter(|h| is_lexical_match(h))
+                    .cloned()
+                    .collect();
+                if matched.is_empty() {
+                    used_fallback = true;
+                } else {
+                    candidates = matched;
+                }
+            }
+            AskMode::Sem => {}
+            AskMode::Hybrid => {
+                // Fuse lexical matches into the semantic ranking
+                let rerank_start = Instant::now();
+                for hit in &mut candidates {
+                    if is_lexical_match(hit) {
+                        hit.score = (hit.score + 0.05).min(1.0);
+                    }
+                }
+                candidates.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap());
+                reranking_ms = rerank_start.elapsed().as_millis() as i32;
+            }
+        }
+
+        // Adaptive retrieval keeps only results close to the best score
+        if let Some(adaptive) = &request.adaptive {
+            if let Some(best) = candidates.first().map(|h| h.score) {
+                let ratio = adaptive.relative_threshold.unwrap_or(0.9);
+                let min_score = adaptive.min_score.unwrap_or(0.0);
+                let min_results = adaptive.min_results.unwrap_or(1);
+                let mut kept = 0;
+                candidates.retain(|h| {
+                    kept += 1;
+                    kept <= min_results || (h.score >= best * ratio && h.score >= min_score)
+                });
+            }
+            if let Some(max_results) = adaptive.max_results {
+                candidates.truncate(max_results);
+            }
+        }
+
+        let candidates_retrieved = candidates.len() as i32;
+        let evidence: Vec<SearchResult> = candidates.into_iter().skip(offset).take(top_k).collect();
+        let next_offset = offset + evidence.len();
+        let next_cursor =
+            (next_offset < candidates_retrieved as usize).then(|| next_offset.to_string());
 
         // Generate mock answer (concatenate snippets in real Ask mode without LLM)
         let answer = if request.use_llm {
@@ -208,7 +454,7 @@ impl Searcher for MockSearcher {
                 request.question,
                 evidence
                     .first()
-                    .map(|e| e.snippet.clone())
+                    .map(|e| format!("{} [1]", e.snippet))
                     .unwrap_or_default()
             )
         } else {
@@ -224,14 +470,15 @@ impl Searcher for MockSearcher {
 
         Ok(AskResponse {
             answer,
-            evidence,
             stats: AskStats {
                 candidates_retrieved,
-                results_returned: candidates_retrieved,
-                retrieval_ms: took_ms,
-                reranking_ms: 0, // Mock doesn't do real re-ranking
-                used_fallback: false,
+                results_returned: evidence.len() as i32,
+                retrieval_ms: took_ms - reranking_ms,
+                reranking_ms,
+                used_fallback,
             },
+            evidence,
+            next_cursor,
         })
     }
 
@@ -295,12 +542,118 @@ impl Searcher for MockSearcher {
         })
     }
 
+    async fn list_entities(&self) -> Result<Vec<EntitySummary>, ServiceError> {
+        // Only the profile is mocked, with its single "data" slot
+        Ok(vec![EntitySummary {
+            name: "__profile__".to_string(),
+            slot_count: 1,
+        }])
+    }
+
+    async fn export_frames(
+        &self,
+        after: Option<u64>,
+        limit: usize,
+    ) -> Result<Vec<FrameMetadata>, ServiceError> {
+        // Frame IDs match the ones reported by search (1-based corpus position)
+        let first = after.map_or(1, |id| id + 1);
+        Ok(self
+            .corpus()
+            .into_iter()
+            .enumerate()
+            .map(|(index, entry)| (index as u64 + 1, entry))
+            .skip_while(|(frame_id, _)| *frame_id < first)
+            .take(limit)
+            .map(|(frame_id, (title, _, _, tags))| FrameMetadata {
+                frame_id,
+                uri: format!("{}/{}", self.memvid_file, frame_id),
+                title: title.to_string(),
+                tags: tags.into_iter().map(String::from).collect(),
+                labels: Vec::new(),
+                metadata: Default::default(),
+            })
+            .collect())
+    }
+
+    async fn frame_texts(
+        &self,
+        after: Option<u64>,
+        limit: usize,
+    ) -> Result<Vec<FrameText>, ServiceError> {
+        let first = after.map_or(1, |id| id + 1);
+        Ok(self
+            .corpus()
+            .into_iter()
+            .enumerate()
+            .map(|(index, entry)| (index as u64 + 1, entry))
+            .skip_while(|(frame_id, _)| *frame_id < first)
+            .take(limit)
+            .map(|(frame_id, (title, _, snippet, _))| FrameText {
+                frame_id,
+                title: title.to_string(),
+                text: snippet.to_string(),
+            })
+            .collect())
+    }
+
     fn frame_count(&self) -> i32 {
         self.frame_count
     }
 
-    fn memvid_file(&self) -> &str {
-        &self.memvid_file
+    fn memvid_file(&self) -> String {
+        self.memvid_file.clone()
+    }
+
+    fn generation(&self) -> u64 {
+        1
+    }
+
+    fn index_features(&self) -> IndexFeatures {
+        // The mock simulates every retrieval mode
+        IndexFeatures {
+            lexical: true,
+            vector: true,
+            temporal: true,
+        }
+    }
+
+    fn section_counts(&self) -> BTreeMap<String, i32> {
+        if let Some(frames) = &self.synthetic {
+            let mut counts = BTreeMap::new();
+            for tag in frames.iter().flat_map(|f| &f.tags) {
+                *counts.entry(tag.clone()).or_insert(0) += 1;
+            }
+            return counts;
+        }
+
+        // Simulated breakdown summing to the simulated frame count
+        [
+            ("experience", 18),
+            ("skills", 12),
+            ("leadership", 6),
+            ("education", 3),
+            ("profile", 3),
+        ]
+        .into_iter()
+        .map(|(section, count)| (section.to_string(), count))
+        .collect()
+    }
+
+    fn lock_diagnostics(&self) -> LockDiagnostics {
+        // The mock holds no locks
+        LockDiagnostics::default()
+    }
+
+    fn acronyms(&self) -> Arc<AcronymMap> {
+        Arc::clone(&self.acronyms)
+    }
+
+    fn spelling(&self) -> Arc<SpellDictionary> {
+        Arc::clone(&self.spelling)
+    }
+
+    fn suggestions(&self) -> Arc<SuggestionIndex> {
+        Arc::clone(&self.suggestions)
     }
 
     fn is_ready(&self) -> bool {
@@ -315,17 +668,66 @@ mod tests {
     #[tokio::test]
     async fn test_mock_search() {
         let searcher = MockSearcher::new();
-        let response = searcher.search("Python experience", 5, 200).await.unwrap();
+        let response = searcher
+            .search(SearchRequest::new("Python experience", 5, 200))
+            .await
+            .unwrap();
 
         assert!(!response.hits.is_empty());
         assert!(response.took_ms >= 0);
         assert!(response.hits[0].score > 0.0);
+        assert!(!response.partial);
+    }
+
+    #[tokio::test]
+    async fn test_search_filters_by_tag() {
+        let searcher = MockSearcher::new();
+        let mut request = SearchRequest::new("experience", 5, 200);
+        request
+            .filters
+            .insert("section".to_string(), "skills".to_string());
+        let response = searcher.search(request).await.unwrap();
+
+        assert!(!response.hits.is_empty());
+        assert!(response
+            .hits
+            .iter()
+            .all(|hit| hit.tags.iter().any(|t| t == "skills")));
+    }
+
+    #[tokio::test]
+    async fn test_search_scoped_to_uri() {
+        let searcher = MockSearcher::new();
+        let request = SearchRequest {
+            uri: Some("mv2://resume/skills".to_string()),
+            ..SearchRequest::new("experience", 5, 200)
+        };
+        let response = searcher.search(request).await.unwrap();
+
+        assert!(!response.hits.is_empty());
+        assert!(response
+            .hits
+            .iter()
+            .all(|hit| hit.uri.as_deref() == Some("mv2://resume/skills")));
+    }
+
+    #[tokio::test]
+    async fn test_search_budget_exceeded_returns_partial() {
+        let searcher = MockSearcher::new();
+        let request = SearchRequest {
+            budget_ms: Some(0),
+            ..SearchRequest::new("Python experience", 5, 200)
+        };
+        let response = searcher.search(request).await.unwrap();
+
+        assert!(response.partial);
+        assert!(response.hits.is_empty());
     }
 
     #[tokio::test]
     async fn test_empty_query_error() {
         let searcher = MockSearcher::new();
-        let result = searcher.search("", 5, 200).await;
+        let result = searcher.search(SearchRequest::new("", 5, 200)).await;
 
         assert!(result.is_err());
     }
@@ -336,6 +738,132 @@ mod tests {
         assert_eq!(searcher.frame_count(), 42);
     }
 
+    #[test]
+    fn test_section_counts_sum_to_frame_count() {
+        let searcher = MockSearcher::new();
+        let counts = searcher.section_counts();
+        assert_eq!(counts.get("experience"), Some(&18));
+        assert_eq!(counts.values().sum::<i32>(), searcher.frame_count());
+    }
+
+    #[tokio::test]
+    async fn test_synthetic_corpus_search() {
+        let searcher = MockSearcher::synthetic(42, 300);
+        assert_eq!(searcher.frame_count(), 300);
+
+        let request = SearchRequest::new("kubernetes", 10, 200);
+        let response = searcher.search(request).await.unwrap();
+
+        assert_eq!(response.hits.len(), 10);
+        assert!(response.hits[0].tags.contains(&"kubernetes".to_string()));
+        assert!(searcher.section_counts()["experience"] > 0);
+    }
+
+    fn ask_request(question: &str, mode: AskMode) -> AskRequest {
+        AskRequest {
+            question: question.to_string(),
+            use_llm: false,
+            top_k: 2,
+            filters: std::collections::HashMap::new(),
+            start: 0,
+            end: 0,
+            snippet_chars: 200,
+            mode,
+            uri: None,
+            cursor: None,
+            as_of_frame: None,
+            as_of_ts: None,
+            adaptive: None,
+            acl: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ask_stats_and_cursor_pagination() {
+        let searcher = MockSearcher::new();
+
+        let first = searcher
+            .ask(ask_request("leadership", AskMode::Hybrid))
+            .await
+            .unwrap();
+        assert_eq!(first.stats.candidates_retrieved, 6);
+        assert_eq!(first.stats.results_returned, 2);
+        assert!(!first.stats.used_fallback);
+
+        let mut request = ask_request("leadership", AskMode::Hybrid);
+        request.cursor = Some("2".to_string());
+        let second = searcher.ask(request).await.unwrap();
+        assert_eq!(second.evidence.len(), 2);
+        assert_ne!(first.evidence[0].title, second.evidence[0].title);
+        assert_eq!(first.next_cursor.as_deref(), Some("2"));
+        assert_eq!(second.next_cursor.as_deref(), Some("4"));
+    }
+
+    #[tokio::test]
+    async fn test_ask_lex_mode_matches_terms_or_falls_back() {
+        let searcher = MockSearcher::new();
+
+        let response = searcher
+            .ask(ask_request("Siemens", AskMode::Lex))
+            .await
+            .unwrap();
+        assert_eq!(response.stats.candidates_retrieved, 1);
+        assert!(!response.stats.used_fallback);
+
+        let response = searcher
+            .ask(ask_request("kubernetes", AskMode::Lex))
+            .await
+            .unwrap();
+        assert!(response.stats.used_fallback);
+        assert!(!response.evidence.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_ask_filters_by_tag() {
+        let searcher = MockSearcher::new();
+        let mut request = ask_request("experience", AskMode::Sem);
+        request.top_k = 10;
+        request
+            .filters
+            .insert("section".to_string(), "skills".to_string());
+
+        let response = searcher.ask(request).await.unwrap();
+        assert_eq!(response.evidence.len(), 2);
+        assert!(response
+            .evidence
+            .iter()
+            .all(|e| e.tags.contains(&"skills".to_string())));
+    }
+
+    #[tokio::test]
+    async fn test_ask_invalid_cursor() {
+        let searcher = MockSearcher::new();
+        let mut request = ask_request("experience", AskMode::Hybrid);
+        request.cursor = Some("page-two".to_string());
+
+        let result = searcher.ask(request).await;
+        assert!(matches!(result, Err(ServiceError::InvalidRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn test_export_frames_pages_in_id_order() {
+        let searcher = MockSearcher::synthetic(7, 25);
+
+        let first = searcher.export_frames(None, 10).await.unwrap();
+        assert_eq!(first.len(), 10);
+        assert_eq!(first[0].frame_id, 1);
+
+        let rest = searcher.export_frames(Some(20), 10).await.unwrap();
+        let ids: Vec<u64> = rest.iter().map(|f| f.frame_id).collect();
+        assert_eq!(ids, vec![21, 22, 23, 24, 25]);
+
+        assert!(searcher
+            .export_frames(Some(25), 10)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
     #[tokio::test]
     async fn test_get_state_profile_found() {
         let searcher = MockSearcher::new();
@@ -385,4 +913,15 @@ mod tests {
         assert!(response.found);
         assert!(response.slots.is_empty()); // Requested slot doesn't exist
     }
+
+    #[tokio::test]
+    async fn test_list_entities_matches_get_state() {
+        let searcher = MockSearcher::new();
+        let entities = searcher.list_entities().await.unwrap();
+        assert_eq!(entities.len(), 1);
+
+        let state = searcher.get_state(&entities[0].name, None).await.unwrap();
+        assert!(state.found);
+        assert_eq!(state.slots.len() as i32, entities[0].slot_count);
+    }
 }
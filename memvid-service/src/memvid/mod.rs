@@ -3,11 +3,127 @@
 //! This module provides a `Searcher` trait and implementations:
 //! - `MockSearcher` - Returns hardcoded results for testing
 //! - `RealSearcher` - Real memvid-core integration
+//! - `ReloadableSearcher` - Hot-swappable wrapper for scheduled index refresh
+//! - `ShadowSearcher` - Mirrors traffic to a candidate index and reports differences
+//! - `PipelineSearcher` - Runs requests through a configured retrieval pipeline
+//! - `SynthesizingSearcher` - Writes Ask answers with an OpenAI-compatible LLM
+//! - `AnonymizingSearcher` - Redacts contact details from returned content
+//! - `InstrumentedSearcher` - Traces each call with queue, lock-wait and memvid-core timings
+//! - `CachingSearcher` - Serves repeated questions from an in-process or Redis answer cache
+//! - `DeterministicSearcher` - Breaks ranking ties and drops timings for reproducible responses
+//!
+//! With the `onnx` feature, `OnnxEmbedder` embeds queries with a local model.
+//!
+//! A `SearcherRegistry` serves several named indexes side by side.
 
+mod acl;
+mod acronyms;
+mod anonymize;
+mod answer_cache;
+mod aspects;
+mod backup;
+mod boosts;
+mod citations;
+mod concurrency;
+pub mod deadline;
+mod deep;
+mod deterministic;
+mod drift;
+mod dump;
+mod duplicates;
+mod embedder_chain;
+mod embedding_cache;
+mod ensemble;
+mod freshness;
+mod instrumented;
+mod language;
 mod mock;
+#[cfg(feature = "onnx")]
+mod onnx;
+mod pipeline;
+mod preload;
+mod read_pool;
 mod real;
+mod registry;
+mod reloadable;
+mod search_cache;
 mod searcher;
+mod shadow;
+mod snippet;
+mod spans;
+mod spelling;
+mod suggest;
+mod synonyms;
+mod synthesis;
+mod synthetic;
+mod validate;
+mod visibility;
 
+pub use acl::{AclContext, AclMode};
+pub use acronyms::AcronymMap;
+pub use anonymize::{redact, AnonymizingSearcher};
+pub use answer_cache::{
+    AnswerCacheBackend, AnswerStore, CacheSnapshot, CachingSearcher, MemoryAnswerStore,
+    RedisAnswerStore, SnapshotEntry, DEFAULT_ANSWER_CAPACITY, DEFAULT_ANSWER_TTL,
+};
+pub use aspects::{
+    aspect_ask, Aspect, AspectPlanner, AspectStats, DEFAULT_ASK_ASPECTS, MAX_ASPECTS,
+};
+pub use backup::{snapshot_index, BackupManifest};
+pub use boosts::{Boosts, BOOST_OVERFETCH, MAX_BOOST};
+pub use citations::{check_citations, CitationCheck, CitationPolicy, CitationStream};
+pub use concurrency::{
+    SearchLimiter, DEFAULT_MAX_CONCURRENT_SEARCHES, DEFAULT_MAX_QUEUED_SEARCHES,
+};
+pub use deep::DeepSearchStore;
+pub use deterministic::{deterministic_order, DeterministicSearcher, DEFAULT_DETERMINISTIC_NOW};
+pub use drift::{EmbeddingProfile, DRIFT_PROBES};
+pub use dump::{IndexDump, DUMP_BATCH};
+pub use duplicates::{
+    find_duplicates, scan_duplicates, DuplicateCluster, DuplicateReport, MAX_SCAN_FRAMES,
+};
+pub use embedder_chain::{EmbedderChain, TierHealth, LEXICAL_TIER};
+pub use embedding_cache::QueryEmbeddingCache;
+pub use ensemble::{ensemble_ask, paraphrase, ParaphraseStats, MAX_PARAPHRASES};
+pub use freshness::{Freshness, INGESTED_AT_KEY, SOURCE_VERSION_KEY};
+pub use instrumented::{InstrumentedRwLock, LockDiagnostics, LockStats};
+pub use language::{
+    apply_language_preference, detect_language, hit_language, language_tag, LANGUAGE_BOOST,
+    LANGUAGE_OVERFETCH, LANGUAGE_TAG_PREFIX,
+};
 pub use mock::MockSearcher;
+#[cfg(feature = "onnx")]
+pub use onnx::OnnxEmbedder;
+pub use pipeline::{PipelineSearcher, RetrievalPipeline, Stage};
+pub use preload::{PreloadOptions, PreloadedIndex};
+pub use read_pool::{ReadPool, MAX_READ_POOL_SIZE};
 pub use real::RealSearcher;
-pub use searcher::{AskMode, AskRequest, Searcher};
+pub use registry::{parse_index_paths, SearcherRegistry, DEFAULT_INDEX};
+pub use reloadable::{
+    CutoverStatus, LoadFuture, ReloadFailure, ReloadOutcome, ReloadableSearcher, SearcherLoader,
+};
+pub use search_cache::{
+    SearchCache, SearchCachingSearcher, DEFAULT_SEARCH_CACHE_CAPACITY, DEFAULT_SEARCH_CACHE_TTL,
+};
+pub use searcher::{
+    AdaptiveOptions, AskEvent, AskEventStream, AskMode, AskRequest, AskStats, EntitySummary,
+    FrameMetadata, FrameText, IndexFeatures, SearchRequest, SearchResponse, SearchResult, Searcher,
+};
+pub use shadow::{compare_hits, ShadowDiff, ShadowSearcher};
+pub use snippet::truncate_snippet;
+pub use spans::{BlockingTiming, InstrumentedSearcher, CORE_FIELD, LOCK_WAIT_FIELD, QUEUE_FIELD};
+pub use spelling::{SpellDictionary, MAX_EDIT_DISTANCE};
+pub use suggest::{
+    Suggestion, SuggestionIndex, SuggestionKind, DEFAULT_SUGGESTIONS, MAX_SUGGESTIONS,
+};
+pub use synonyms::SynonymMap;
+pub use synthesis::SynthesizingSearcher;
+pub use synthetic::SyntheticFrame;
+pub use validate::{
+    validate_index, CheckStatus, ValidationCheck, ValidationReport, DEFAULT_VALIDATION_SAMPLES,
+    MAX_VALIDATION_SAMPLES,
+};
+pub use visibility::{
+    context_answer, Audience, Visibility, VisibilityStore, VISIBILITY_OVERFETCH,
+    VISIBILITY_TAG_PREFIX,
+};
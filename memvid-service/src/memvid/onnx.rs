@@ -0,0 +1,194 @@
+//! Local ONNX query embedder (`onnx` feature).
+//!
+//! [`OnnxEmbedder`] runs a sentence-transformer model exported to ONNX with
+//! ONNX Runtime, so semantic retrieval works fully offline. `EMBED_MODEL_PATH`
+//! names the model: either a directory holding `model.onnx` and
+//! `tokenizer.json`, or the `.onnx` file itself with `tokenizer.json` next to
+//! it. Models returning token embeddings are mean-pooled over the attention
+//! mask; models returning a sentence embedding are used as is. Embeddings
+//! are L2-normalized.
+//!
+//! ONNX Runtime is loaded dynamically: point `ORT_DYLIB_PATH` at
+//! `libonnxruntime` when it is not on the library search path.
+
+use memvid_core::VecEmbedder;
+use ort::session::Session;
+use ort::value::Tensor;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tokenizers::Tokenizer;
+use tracing::info;
+
+use crate::error::ServiceError;
+
+/// Model file looked up in a model directory.
+const MODEL_FILE: &str = "model.onnx";
+
+/// Tokenizer file looked up next to the model.
+const TOKENIZER_FILE: &str = "tokenizer.json";
+
+/// Text embedded at load to learn the embedding dimension.
+const DIMENSION_PROBE: &str = "dimension probe";
+
+/// Sentence-transformer embedder running on ONNX Runtime.
+pub struct OnnxEmbedder {
+    session: Mutex<Session>,
+    tokenizer: Tokenizer,
+    /// Whether the model takes a `token_type_ids` input (BERT-style)
+    token_type_ids: bool,
+    dimension: usize,
+}
+
+impl std::fmt::Debug for OnnxEmbedder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OnnxEmbedder")
+            .field("dimension", &self.dimension)
+            .finish_non_exhaustive()
+    }
+}
+
+impl OnnxEmbedder {
+    /// Load the model and tokenizer at `path` (a directory or `.onnx` file).
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ServiceError> {
+        let (model, tokenizer) = model_files(path.as_ref());
+        let invalid = |e: String| {
+            ServiceError::Internal(format!(
+                "Failed to load embedding model {}: {}",
+                model.display(),
+                e
+            ))
+        };
+
+        let tokenizer = Tokenizer::from_file(&tokenizer)
+            .map_err(|e| invalid(format!("{}: {}", tokenizer.display(), e)))?;
+        let session = Session::builder()
+            .and_then(|builder| builder.commit_from_file(&model))
+            .map_err(|e| invalid(e.to_string()))?;
+        let token_type_ids = session
+            .inputs
+            .iter()
+            .any(|input| input.name == "token_type_ids");
+
+        let mut embedder = Self {
+            session: Mutex::new(session),
+            tokenizer,
+            token_type_ids,
+            dimension: 0,
+        };
+        embedder.dimension = embedder.embed(DIMENSION_PROBE).map_err(invalid)?.len();
+        info!(
+            model = %model.display(),
+            dimension = embedder.dimension,
+            "ONNX embedding model loaded"
+        );
+        Ok(embedder)
+    }
+
+    /// Embed `text` into a normalized sentence vector.
+    fn embed(&self, text: &str) -> Result<Vec<f32>, String> {
+        let encoding = self
+            .tokenizer
+            .encode(text, true)
+            .map_err(|e| format!("tokenization failed: {}", e))?;
+        let len = encoding.get_ids().len();
+        let shape = [1, len as i64];
+        let tensor = |values: &[u32]| {
+            Tensor::from_array((shape, values.iter().map(|&v| v as i64).collect::<Vec<_>>()))
+                .map_err(|e| e.to_string())
+        };
+        let mask = encoding.get_attention_mask();
+
+        let mut inputs = ort::inputs![
+            "input_ids" => tensor(encoding.get_ids())?,
+            "attention_mask" => tensor(mask)?,
+        ];
+        if self.token_type_ids {
+            inputs.push((
+                "token_type_ids".into(),
+                tensor(encoding.get_type_ids())?.into(),
+            ));
+        }
+
+        let mut session = self.session.lock().unwrap_or_else(|e| e.into_inner());
+        let outputs = session.run(inputs).map_err(|e| e.to_string())?;
+        let (shape, values) = outputs[0]
+            .try_extract_tensor::<f32>()
+            .map_err(|e| e.to_string())?;
+
+        let embedding = match **shape {
+            // Sentence embedding: [1, dim]
+            [1, dim] => values[..dim as usize].to_vec(),
+            // Token embeddings: [1, tokens, dim], mean-pooled over the mask
+            [1, tokens, dim] => mean_pool(values, mask, tokens as usize, dim as usize),
+            _ => return Err(format!("unexpected output shape {:?}", &**shape)),
+        };
+        Ok(normalize(embedding))
+    }
+}
+
+impl VecEmbedder for OnnxEmbedder {
+    fn embed_query(&self, text: &str) -> memvid_core::Result<Vec<f32>> {
+        self.embed(text)
+            .map_err(|e| std::io::Error::other(format!("ONNX embedding failed: {}", e)).into())
+    }
+
+    fn embedding_dimension(&self) -> usize {
+        self.dimension
+    }
+}
+
+/// Model and tokenizer files for `path`.
+fn model_files(path: &Path) -> (PathBuf, PathBuf) {
+    if path.is_dir() {
+        (path.join(MODEL_FILE), path.join(TOKENIZER_FILE))
+    } else {
+        let dir = path.parent().unwrap_or(Path::new("."));
+        (path.to_path_buf(), dir.join(TOKENIZER_FILE))
+    }
+}
+
+/// Average of the token embeddings whose attention mask is set.
+fn mean_pool(values: &[f32], mask: &[u32], tokens: usize, dim: usize) -> Vec<f32> {
+    let mut sum = vec![0.0; dim];
+    let mut count = 0.0;
+    for (token, _) in mask.iter().enumerate().take(tokens).filter(|(_, &m)| m > 0) {
+        for (total, value) in sum.iter_mut().zip(&values[token * dim..(token + 1) * dim]) {
+            *total += value;
+        }
+        count += 1.0;
+    }
+    if count > 0.0 {
+        sum.iter_mut().for_each(|total| *total /= count);
+    }
+    sum
+}
+
+/// `vector` scaled to unit length (unchanged when zero).
+fn normalize(mut vector: Vec<f32>) -> Vec<f32> {
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|v| *v /= norm);
+    }
+    vector
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mean_pool_skips_masked_tokens() {
+        let values = [1.0, 2.0, 3.0, 4.0, 100.0, 100.0];
+        assert_eq!(mean_pool(&values, &[1, 1, 0], 3, 2), vec![2.0, 3.0]);
+        let unit = normalize(vec![3.0, 4.0]);
+        assert!((unit[0] - 0.6).abs() < 1e-6 && (unit[1] - 0.8).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_model_files_of_directory_and_file() {
+        let dir = std::env::temp_dir();
+        assert_eq!(model_files(&dir).0, dir.join(MODEL_FILE));
+        let file = dir.join("minilm.onnx");
+        assert_eq!(model_files(&file), (file.clone(), dir.join(TOKENIZER_FILE)));
+    }
+}
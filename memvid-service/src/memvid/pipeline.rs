@@ -0,0 +1,473 @@
+//! Declarative retrieval pipeline.
+//!
+//! The pipeline is an ordered list of named stages with parameters, declared
+//! as JSON in configuration:
+//!
+//! ```json
+//! [
+//!   {"stage": "normalize"},
+//!   {"stage": "expand", "synonyms": {"ml": ["machine learning"]}},
+//!   {"stage": "retrieve", "overfetch": 3},
+//!   {"stage": "rerank", "tag_boosts": {"experience": 1.2}},
+//!   {"stage": "diversify", "max_per_tag": 2},
+//!   {"stage": "truncate", "min_score": 0.3}
+//! ]
+//! ```
+//!
+//! Query stages (`normalize`, `expand`) run before the single `retrieve`
+//! stage; result stages (`rerank`, `diversify`, `truncate`) run after it.
+//! Results are always capped at the requested `top_k`.
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use super::acronyms::AcronymMap;
+use super::instrumented::LockDiagnostics;
+use super::searcher::{
+    AskRequest, AskResponse, EntitySummary, FrameMetadata, FrameText, IndexFeatures, SearchRequest,
+    SearchResponse, SearchResult, Searcher, StateResponse,
+};
+use super::spelling::SpellDictionary;
+use super::suggest::SuggestionIndex;
+use crate::error::ServiceError;
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_overfetch() -> u32 {
+    1
+}
+
+/// One named pipeline stage and its parameters.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "stage", rename_all = "snake_case", deny_unknown_fields)]
+pub enum Stage {
+    /// Trim and collapse whitespace, optionally lowercasing the query
+    Normalize {
+        #[serde(default = "default_true")]
+        lowercase: bool,
+    },
+    /// Append synonyms for query terms (keys match case-insensitively)
+    Expand {
+        synonyms: BTreeMap<String, Vec<String>>,
+    },
+    /// Retrieve `top_k * overfetch` candidates from the index
+    Retrieve {
+        #[serde(default = "default_overfetch")]
+        overfetch: u32,
+    },
+    /// Multiply scores by per-tag boosts and re-sort
+    Rerank {
+        #[serde(default)]
+        tag_boosts: BTreeMap<String, f32>,
+    },
+    /// Keep at most `max_per_tag` hits sharing the same primary tag
+    Diversify { max_per_tag: usize },
+    /// Drop hits scoring below `min_score`
+    Truncate {
+        #[serde(default)]
+        min_score: f32,
+    },
+}
+
+impl Stage {
+    /// Stage name as written in configuration.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Stage::Normalize { .. } => "normalize",
+            Stage::Expand { .. } => "expand",
+            Stage::Retrieve { .. } => "retrieve",
+            Stage::Rerank { .. } => "rerank",
+            Stage::Diversify { .. } => "diversify",
+            Stage::Truncate { .. } => "truncate",
+        }
+    }
+
+    fn is_query_stage(&self) -> bool {
+        matches!(self, Stage::Normalize { .. } | Stage::Expand { .. })
+    }
+}
+
+/// A validated, ordered list of retrieval stages.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetrievalPipeline {
+    stages: Vec<Stage>,
+}
+
+impl RetrievalPipeline {
+    /// Parse and validate a JSON pipeline definition.
+    pub fn parse(json: &str) -> Result<Self, String> {
+        let stages: Vec<Stage> = serde_json::from_str(json).map_err(|e| e.to_string())?;
+
+        let retrieve_at = match stages
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| matches!(s, Stage::Retrieve { .. }))
+            .map(|(i, _)| i)
+            .collect::<Vec<_>>()[..]
+        {
+            [i] => i,
+            _ => return Err("pipeline needs exactly one 'retrieve' stage".to_string()),
+        };
+
+        for (i, stage) in stages.iter().enumerate() {
+            if i < retrieve_at && !stage.is_query_stage() {
+                return Err(format!("'{}' must come after 'retrieve'", stage.name()));
+            }
+            if i > retrieve_at && stage.is_query_stage() {
+                return Err(format!("'{}' must come before 'retrieve'", stage.name()));
+            }
+            match stage {
+                Stage::Retrieve { overfetch: 0 } => {
+                    return Err("'retrieve' overfetch must be at least 1".to_string())
+                }
+                Stage::Diversify { max_per_tag: 0 } => {
+                    return Err("'diversify' max_per_tag must be at least 1".to_string())
+                }
+                _ => {}
+            }
+        }
+
+        Ok(Self { stages })
+    }
+
+    /// Stage names in execution order.
+    pub fn stage_names(&self) -> Vec<&'static str> {
+        self.stages.iter().map(Stage::name).collect()
+    }
+
+    /// Apply the query stages.
+    fn prepare_query(&self, query: &str) -> String {
+        let mut query = query.to_string();
+        for stage in &self.stages {
+            match stage {
+                Stage::Normalize { lowercase } => {
+                    query = query.split_whitespace().collect::<Vec<_>>().join(" ");
+                    if *lowercase {
+                        query = query.to_lowercase();
+                    }
+                }
+                Stage::Expand { synonyms } => {
+                    let lower = query.to_lowercase();
+                    let mut additions = Vec::new();
+                    for term in lower.split_whitespace() {
+                        let Some((_, expansions)) =
+                            synonyms.iter().find(|(key, _)| key.to_lowercase() == term)
+                        else {
+                            continue;
+                        };
+                        for expansion in expansions {
+                            if !lower.contains(&expansion.to_lowercase())
+                                && !additions.contains(expansion)
+                            {
+                                additions.push(expansion.clone());
+                            }
+                        }
+                    }
+                    for addition in additions {
+                        query.push(' ');
+                        query.push_str(&addition);
+                    }
+                }
+                _ => {}
+            }
+        }
+        query
+    }
+
+    /// Number of candidates to retrieve for a requested `top_k`.
+    fn fetch_k(&self, top_k: i32) -> i32 {
+        let overfetch = self
+            .stages
+            .iter()
+            .find_map(|stage| match stage {
+                Stage::Retrieve { overfetch } => Some(*overfetch),
+                _ => None,
+            })
+            .unwrap_or(1);
+        top_k.saturating_mul(overfetch.min(i32::MAX as u32) as i32)
+    }
+
+    /// Apply the result stages and cap at `top_k`.
+    fn process(&self, mut hits: Vec<SearchResult>, top_k: i32) -> Vec<SearchResult> {
+        for stage in &self.stages {
+            match stage {
+                Stage::Rerank { tag_boosts } => {
+                    for hit in &mut hits {
+                        for tag in &hit.tags {
+                            if let Some(boost) = tag_boosts.get(tag) {
+                                hit.score *= boost;
+                            }
+                        }
+                    }
+                    hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+                }
+                Stage::Diversify { max_per_tag } => {
+                    let mut seen: BTreeMap<String, usize> = BTreeMap::new();
+                    hits.retain(|hit| match hit.tags.first() {
+                        Some(tag) => {
+                            let count = seen.entry(tag.clone()).or_default();
+                            *count += 1;
+                            *count <= *max_per_tag
+                        }
+                        None => true,
+                    });
+                }
+                Stage::Truncate { min_score } => hits.retain(|hit| hit.score >= *min_score),
+                _ => {}
+            }
+        }
+        hits.truncate(top_k.max(0) as usize);
+        hits
+    }
+}
+
+/// Searcher that runs requests through a configured retrieval pipeline.
+pub struct PipelineSearcher {
+    inner: Arc<dyn Searcher>,
+    pipeline: RetrievalPipeline,
+}
+
+impl PipelineSearcher {
+    /// Wrap `inner` with `pipeline`.
+    pub fn new(inner: Arc<dyn Searcher>, pipeline: RetrievalPipeline) -> Self {
+        Self { inner, pipeline }
+    }
+}
+
+#[async_trait]
+impl Searcher for PipelineSearcher {
+    async fn search(&self, request: SearchRequest) -> Result<SearchResponse, ServiceError> {
+        let top_k = request.top_k;
+        let mut response = self
+            .inner
+            .search(SearchRequest {
+                query: self.pipeline.prepare_query(&request.query),
+                top_k: self.pipeline.fetch_k(top_k),
+                ..request
+            })
+            .await?;
+
+        response.hits = self.pipeline.process(response.hits, top_k);
+        response.total_hits = response.total_hits.min(response.hits.len() as i32);
+        Ok(response)
+    }
+
+    async fn get_state(
+        &self,
+        entity: &str,
+        slot: Option<&str>,
+    ) -> Result<StateResponse, ServiceError> {
+        self.inner.get_state(entity, slot).await
+    }
+
+    async fn list_entities(&self) -> Result<Vec<EntitySummary>, ServiceError> {
+        self.inner.list_entities().await
+    }
+
+    async fn ask(&self, request: AskRequest) -> Result<AskResponse, ServiceError> {
+        let top_k = request.top_k;
+        let mut response = self
+            .inner
+            .ask(AskRequest {
+                question: self.pipeline.prepare_query(&request.question),
+                top_k: self.pipeline.fetch_k(top_k),
+                ..request
+            })
+            .await?;
+
+        response.evidence = self.pipeline.process(response.evidence, top_k);
+        response.stats.results_returned = response.evidence.len() as i32;
+        Ok(response)
+    }
+
+    async fn export_frames(
+        &self,
+        after: Option<u64>,
+        limit: usize,
+    ) -> Result<Vec<FrameMetadata>, ServiceError> {
+        self.inner.export_frames(after, limit).await
+    }
+
+    async fn frame_texts(
+        &self,
+        after: Option<u64>,
+        limit: usize,
+    ) -> Result<Vec<FrameText>, ServiceError> {
+        self.inner.frame_texts(after, limit).await
+    }
+
+    fn frame_count(&self) -> i32 {
+        self.inner.frame_count()
+    }
+
+    fn memvid_file(&self) -> String {
+        self.inner.memvid_file()
+    }
+
+    fn generation(&self) -> u64 {
+        self.inner.generation()
+    }
+
+    fn index_features(&self) -> IndexFeatures {
+        self.inner.index_features()
+    }
+
+    fn section_counts(&self) -> BTreeMap<String, i32> {
+        self.inner.section_counts()
+    }
+
+    fn lock_diagnostics(&self) -> LockDiagnostics {
+        self.inner.lock_diagnostics()
+    }
+
+    fn acronyms(&self) -> Arc<AcronymMap> {
+        self.inner.acronyms()
+    }
+
+    fn spelling(&self) -> Arc<SpellDictionary> {
+        self.inner.spelling()
+    }
+
+    fn suggestions(&self) -> Arc<SuggestionIndex> {
+        self.inner.suggestions()
+    }
+
+    fn is_ready(&self) -> bool {
+        self.inner.is_ready()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memvid::MockSearcher;
+
+    fn hit(title: &str, score: f32, tag: &str) -> SearchResult {
+        SearchResult {
+            title: title.to_string(),
+            score,
+            tags: vec![tag.to_string()],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_full_pipeline() {
+        let pipeline = RetrievalPipeline::parse(
+            r#"[
+                {"stage": "normalize"},
+                {"stage": "expand", "synonyms": {"ml": ["machine learning"]}},
+                {"stage": "retrieve", "overfetch": 3},
+                {"stage": "rerank", "tag_boosts": {"skills": 1.5}},
+                {"stage": "diversify", "max_per_tag": 2},
+                {"stage": "truncate", "min_score": 0.3}
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            pipeline.stage_names(),
+            vec![
+                "normalize",
+                "expand",
+                "retrieve",
+                "rerank",
+                "diversify",
+                "truncate"
+            ]
+        );
+        assert_eq!(pipeline.fetch_k(5), 15);
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_pipelines() {
+        // Missing or duplicate retrieve
+        assert!(RetrievalPipeline::parse(r#"[{"stage": "normalize"}]"#).is_err());
+        assert!(
+            RetrievalPipeline::parse(r#"[{"stage": "retrieve"}, {"stage": "retrieve"}]"#).is_err()
+        );
+        // Stages on the wrong side of retrieve
+        assert!(
+            RetrievalPipeline::parse(r#"[{"stage": "retrieve"}, {"stage": "normalize"}]"#).is_err()
+        );
+        assert!(
+            RetrievalPipeline::parse(r#"[{"stage": "truncate"}, {"stage": "retrieve"}]"#).is_err()
+        );
+        // Unknown stages, unknown parameters, and invalid values
+        assert!(RetrievalPipeline::parse(r#"[{"stage": "magic"}]"#).is_err());
+        assert!(RetrievalPipeline::parse(r#"[{"stage": "retrieve", "k": 3}]"#).is_err());
+        assert!(RetrievalPipeline::parse(r#"[{"stage": "retrieve", "overfetch": 0}]"#).is_err());
+    }
+
+    #[test]
+    fn test_query_stages() {
+        let pipeline = RetrievalPipeline::parse(
+            r#"[
+                {"stage": "normalize"},
+                {"stage": "expand", "synonyms": {"ML": ["machine learning", "ai"]}},
+                {"stage": "retrieve"}
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            pipeline.prepare_query("  ML   Projects "),
+            "ml projects machine learning ai"
+        );
+        // Expansions already in the query are not repeated
+        assert_eq!(
+            pipeline.prepare_query("ml and AI"),
+            "ml and ai machine learning"
+        );
+    }
+
+    #[test]
+    fn test_result_stages() {
+        let pipeline = RetrievalPipeline::parse(
+            r#"[
+                {"stage": "retrieve", "overfetch": 2},
+                {"stage": "rerank", "tag_boosts": {"skills": 2.0}},
+                {"stage": "diversify", "max_per_tag": 1},
+                {"stage": "truncate", "min_score": 0.3}
+            ]"#,
+        )
+        .unwrap();
+
+        let hits = vec![
+            hit("a", 0.9, "experience"),
+            hit("b", 0.8, "experience"),
+            hit("c", 0.5, "skills"),
+            hit("d", 0.2, "education"),
+        ];
+        let titles: Vec<String> = pipeline
+            .process(hits, 3)
+            .into_iter()
+            .map(|h| h.title)
+            .collect();
+
+        // "c" is boosted to the top, "b" is a second experience hit, "d" scores too low
+        assert_eq!(titles, vec!["c", "a"]);
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_searcher_caps_at_top_k() {
+        let pipeline = RetrievalPipeline::parse(
+            r#"[{"stage": "normalize"}, {"stage": "retrieve", "overfetch": 4}]"#,
+        )
+        .unwrap();
+        let searcher = PipelineSearcher::new(Arc::new(MockSearcher::new()), pipeline);
+
+        let response = searcher
+            .search(SearchRequest::new("  RUST  Experience", 2, 200))
+            .await
+            .unwrap();
+
+        assert!(!response.hits.is_empty());
+        assert!(response.hits.len() <= 2);
+        assert_eq!(searcher.frame_count(), MockSearcher::new().frame_count());
+    }
+}
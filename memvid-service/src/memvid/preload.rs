@@ -0,0 +1,248 @@
+//! Index preloading for consistent latency on cold storage.
+//!
+//! The .mv2 file is memory-mapped and every page read once at load, so the
+//! first queries after startup do not wait on disk; memvid-core reads the
+//! same file and is served from the page cache. With `lock`, the pages are
+//! also mlocked so the kernel cannot evict them under memory pressure, which
+//! needs `CAP_IPC_LOCK` or a sufficient `RLIMIT_MEMLOCK`.
+//!
+//! Preloading is skipped, with a warning, when the file would take more than
+//! the configured share of available memory.
+
+use memmap2::Mmap;
+use std::fs::File;
+use std::path::Path;
+use tracing::{info, warn};
+
+use crate::error::ServiceError;
+use crate::metrics;
+
+/// Page stride used to fault the mapping in.
+const PAGE_SIZE: usize = 4096;
+
+/// How to preload an index file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PreloadOptions {
+    /// mlock the file's pages after reading them
+    pub lock: bool,
+    /// Largest share of available memory the file may take, in percent
+    pub max_memory_percent: u8,
+}
+
+impl Default for PreloadOptions {
+    fn default() -> Self {
+        Self {
+            lock: false,
+            max_memory_percent: 50,
+        }
+    }
+}
+
+/// An index file held resident in memory for as long as this value lives.
+pub struct PreloadedIndex {
+    map: Mmap,
+    locked: bool,
+}
+
+impl std::fmt::Debug for PreloadedIndex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PreloadedIndex")
+            .field("bytes", &self.map.len())
+            .field("locked", &self.locked)
+            .finish()
+    }
+}
+
+impl PreloadedIndex {
+    /// Map `path`, read every page and optionally lock the pages in memory.
+    ///
+    /// Returns `Ok(None)` when the file exceeds the allowed share of
+    /// available memory. A failed mlock is logged and the index stays
+    /// preloaded but unlocked.
+    pub fn load(path: &Path, options: PreloadOptions) -> Result<Option<Self>, ServiceError> {
+        let file = File::open(path)
+            .map_err(|e| ServiceError::MemvidLoadError(format!("Preload open failed: {}", e)))?;
+        let size = file
+            .metadata()
+            .map_err(|e| ServiceError::MemvidLoadError(format!("Preload stat failed: {}", e)))?
+            .len();
+
+        if let Some(available) = available_memory() {
+            if !fits_in_memory(size, available, options.max_memory_percent) {
+                warn!(
+                    path = %path.display(),
+                    size_bytes = size,
+                    available_bytes = available,
+                    max_memory_percent = options.max_memory_percent,
+                    "Index too large to preload, serving from disk"
+                );
+                return Ok(None);
+            }
+        } else {
+            warn!("Available memory unknown, preloading without a size guardrail");
+        }
+
+        // SAFETY: the mapping is read-only and index files are replaced by
+        // writing a new file and reloading, never modified in place
+        let map = unsafe { Mmap::map(&file) }
+            .map_err(|e| ServiceError::MemvidLoadError(format!("Preload mmap failed: {}", e)))?;
+
+        #[cfg(unix)]
+        let _ = map.advise(memmap2::Advice::WillNeed);
+        let checksum = map
+            .iter()
+            .step_by(PAGE_SIZE)
+            .fold(0u8, |acc, b| acc.wrapping_add(*b));
+        std::hint::black_box(checksum);
+
+        let locked = options.lock && lock(&map, path);
+        metrics::adjust_index_resident_bytes(map.len() as f64, locked);
+        info!(
+            path = %path.display(),
+            size_bytes = map.len(),
+            locked,
+            "Index preloaded into memory"
+        );
+
+        Ok(Some(Self { map, locked }))
+    }
+
+    /// Bytes held in memory.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Whether the file is empty.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Whether the pages are mlocked.
+    pub fn is_locked(&self) -> bool {
+        self.locked
+    }
+}
+
+impl Drop for PreloadedIndex {
+    fn drop(&mut self) {
+        // Unmapping also releases the lock
+        metrics::adjust_index_resident_bytes(-(self.map.len() as f64), self.locked);
+    }
+}
+
+#[cfg(unix)]
+fn lock(map: &Mmap, path: &Path) -> bool {
+    match map.lock() {
+        Ok(()) => true,
+        Err(e) => {
+            warn!(
+                path = %path.display(),
+                error = %e,
+                "mlock failed (needs CAP_IPC_LOCK or a higher RLIMIT_MEMLOCK), index left unlocked"
+            );
+            false
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn lock(_map: &Mmap, path: &Path) -> bool {
+    warn!(path = %path.display(), "mlock is not supported on this platform");
+    false
+}
+
+/// Whether `size` bytes fit within `max_percent` of `available` bytes.
+fn fits_in_memory(size: u64, available: u64, max_percent: u8) -> bool {
+    size as u128 * 100 <= available as u128 * max_percent as u128
+}
+
+/// Memory available to this process: the smaller of the system's
+/// `MemAvailable` and the headroom under a cgroup v2 limit.
+fn available_memory() -> Option<u64> {
+    let system = std::fs::read_to_string("/proc/meminfo")
+        .ok()
+        .and_then(|meminfo| parse_mem_available(&meminfo));
+    let cgroup = cgroup_headroom(
+        std::fs::read_to_string("/sys/fs/cgroup/memory.max").ok(),
+        std::fs::read_to_string("/sys/fs/cgroup/memory.current").ok(),
+    );
+
+    match (system, cgroup) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+/// `MemAvailable` from /proc/meminfo, in bytes.
+fn parse_mem_available(meminfo: &str) -> Option<u64> {
+    meminfo.lines().find_map(|line| {
+        let kb = line
+            .strip_prefix("MemAvailable:")?
+            .trim()
+            .strip_suffix("kB")?;
+        kb.trim().parse::<u64>().ok().map(|kb| kb * 1024)
+    })
+}
+
+/// Bytes left under a cgroup v2 memory limit ("max" = unlimited).
+fn cgroup_headroom(max: Option<String>, current: Option<String>) -> Option<u64> {
+    let max: u64 = max?.trim().parse().ok()?;
+    let current: u64 = current?.trim().parse().ok()?;
+    Some(max.saturating_sub(current))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_guardrail() {
+        assert!(fits_in_memory(500, 1000, 50));
+        assert!(!fits_in_memory(501, 1000, 50));
+        assert!(!fits_in_memory(1, 1000, 0));
+        assert!(fits_in_memory(u64::MAX, u64::MAX, 100));
+    }
+
+    #[test]
+    fn test_parse_available_memory() {
+        let meminfo = "MemTotal:       16318504 kB\nMemFree:         1202312 kB\n\
+                       MemAvailable:    8159252 kB\n";
+        assert_eq!(parse_mem_available(meminfo), Some(8_159_252 * 1024));
+        assert_eq!(parse_mem_available("MemTotal: 1 kB\n"), None);
+
+        assert_eq!(
+            cgroup_headroom(Some("1073741824\n".into()), Some("73741824\n".into())),
+            Some(1_000_000_000)
+        );
+        assert_eq!(
+            cgroup_headroom(Some("max\n".into()), Some("1".into())),
+            None
+        );
+    }
+
+    #[test]
+    fn test_preloads_file() {
+        let path = std::env::temp_dir().join(format!("memvid-preload-{}.mv2", std::process::id()));
+        std::fs::write(&path, vec![7u8; 3 * PAGE_SIZE + 1]).unwrap();
+
+        let index = PreloadedIndex::load(
+            &path,
+            PreloadOptions {
+                lock: false,
+                max_memory_percent: 100,
+            },
+        )
+        .unwrap()
+        .expect("small file fits in memory");
+        assert_eq!(index.len(), 3 * PAGE_SIZE + 1);
+        assert!(!index.is_locked());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_missing_file_is_an_error() {
+        let result = PreloadedIndex::load(Path::new("/nonexistent/index.mv2"), Default::default());
+        assert!(matches!(result, Err(ServiceError::MemvidLoadError(_))));
+    }
+}
@@ -0,0 +1,159 @@
+//! Pool of independently locked index instances.
+//!
+//! memvid-core needs `&mut Memvid` for search and ask, so a single instance
+//! serializes every query behind its write lock. A read pool opens the same
+//! file several times read-only and hands the instances out round-robin, so
+//! up to one query per instance runs at a time. Queries needing exclusive
+//! access prefer an instance nobody holds; shared readers (state lookups,
+//! frame exports) take any instance not locked for writing, alongside its
+//! other readers.
+//! Each instance keeps its own lock statistics; diagnostics sum them.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use super::instrumented::{InstrumentedRwLock, LockDiagnostics};
+
+/// Largest number of instances `MEMVID_READ_POOL_SIZE` may open.
+pub const MAX_READ_POOL_SIZE: usize = 32;
+
+/// Instances of one index, each behind its own instrumented lock.
+#[derive(Debug)]
+pub struct ReadPool<T> {
+    instances: Vec<Arc<InstrumentedRwLock<T>>>,
+    next: AtomicUsize,
+}
+
+impl<T> ReadPool<T> {
+    /// Pool of the single instance `value`.
+    pub fn new(value: T) -> Self {
+        Self {
+            instances: vec![Arc::new(InstrumentedRwLock::new(value))],
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    /// This pool's instances followed by `values`.
+    pub fn extended(&self, values: impl IntoIterator<Item = T>) -> Self {
+        let mut instances = self.instances.clone();
+        instances.extend(
+            values
+                .into_iter()
+                .map(InstrumentedRwLock::new)
+                .map(Arc::new),
+        );
+        Self {
+            instances,
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    /// Number of instances.
+    pub fn size(&self) -> usize {
+        self.instances.len()
+    }
+
+    /// The instance for the next shared reader: the first one not locked
+    /// for writing, starting from the round-robin position, else the
+    /// instance at that position.
+    pub fn next(&self) -> Arc<InstrumentedRwLock<T>> {
+        let start = self.next.fetch_add(1, Ordering::Relaxed);
+        let idle = self
+            .from(start)
+            .find(|instance| instance.try_read().is_ok());
+        Arc::clone(idle.unwrap_or(&self.instances[start % self.size()]))
+    }
+
+    /// The instance for the next query taking the write lock: the first one
+    /// nobody holds, else the first one not locked for writing (its readers
+    /// finish quickly), else the instance at the round-robin position.
+    pub fn next_exclusive(&self) -> Arc<InstrumentedRwLock<T>> {
+        let start = self.next.fetch_add(1, Ordering::Relaxed);
+        let instance = self
+            .from(start)
+            .find(|instance| instance.try_write().is_ok())
+            .or_else(|| {
+                self.from(start)
+                    .find(|instance| instance.try_read().is_ok())
+            })
+            .unwrap_or(&self.instances[start % self.size()]);
+        Arc::clone(instance)
+    }
+
+    /// All instances, starting from position `start`.
+    fn from(&self, start: usize) -> impl Iterator<Item = &Arc<InstrumentedRwLock<T>>> {
+        let size = self.size();
+        (0..size).map(move |offset| &self.instances[(start + offset) % size])
+    }
+
+    /// True when any instance can take a query right now.
+    pub fn is_ready(&self) -> bool {
+        self.instances
+            .iter()
+            .any(|instance| instance.try_read().is_ok())
+    }
+
+    /// Lock and blocking-pool timings of all instances together.
+    pub fn diagnostics(&self) -> LockDiagnostics {
+        self.instances
+            .iter()
+            .map(|instance| instance.stats().snapshot())
+            .fold(LockDiagnostics::default(), LockDiagnostics::merge)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_hands_out_idle_instances_round_robin() {
+        let pool = ReadPool::new(0u32).extended([1, 2]);
+        assert_eq!(pool.size(), 3);
+
+        let picks: Vec<u32> = (0..4).map(|_| *pool.next().try_read().unwrap()).collect();
+        assert_eq!(picks, [0, 1, 2, 0]);
+
+        // A busy instance is skipped
+        let busy = pool.next();
+        let _guard = busy.write().await;
+        assert!(!Arc::ptr_eq(&pool.next(), &busy));
+        assert!(pool.is_ready());
+    }
+
+    #[tokio::test]
+    async fn test_exclusive_queries_avoid_readers() {
+        let pool = ReadPool::new(0u32).extended([1]);
+        let read = pool.next();
+        let _reader = read.read().await;
+
+        // A writer takes the instance nobody holds
+        for _ in 0..3 {
+            assert!(!Arc::ptr_eq(&pool.next_exclusive(), &read));
+        }
+
+        // With every instance busy, a writer queues behind readers
+        let written = pool.next_exclusive();
+        let _writer = written.write().await;
+        assert!(Arc::ptr_eq(&pool.next_exclusive(), &read));
+    }
+
+    #[tokio::test]
+    async fn test_sums_instance_diagnostics() {
+        let pool = ReadPool::new(0u32).extended([1]);
+        for _ in 0..4 {
+            let instance = pool.next();
+            let _guard = instance.write().await;
+        }
+        let diag = pool.diagnostics();
+        assert_eq!(diag.write_acquisitions, 4);
+        assert_eq!(
+            diag.write_wait_us_max,
+            pool.instances
+                .iter()
+                .map(|i| i.stats().snapshot().write_wait_us_max)
+                .max()
+                .unwrap()
+        );
+    }
+}
@@ -4,28 +4,74 @@
 
 use async_trait::async_trait;
 use memvid_core::{
-    AclEnforcementMode, AdaptiveConfig, AskMode as MemvidAskMode, AskRequest as MemvidAskRequest,
-    Memvid, SearchRequest,
+    AclContext as MemvidAclContext, AclEnforcementMode, AdaptiveConfig, AskMode as MemvidAskMode,
+    AskRequest as MemvidAskRequest, AskResponse as MemvidAskResponse, CutoffStrategy, Memvid,
+    SearchRequest as MemvidSearchRequest, VecEmbedder,
 };
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use tokio::sync::RwLock;
-use tracing::{error, info};
-
+use std::time::Duration;
+use tokio::sync::OwnedSemaphorePermit;
+use tracing::{debug, error, info, warn};
+
+use super::acl::{AclContext, AclMode};
+use super::acronyms::AcronymMap;
+use super::concurrency::SearchLimiter;
+use super::deadline;
+use super::embedder_chain::{EmbedderChain, LEXICAL_TIER};
+use super::embedding_cache::QueryEmbeddingCache;
+use super::freshness::Freshness;
+use super::instrumented::{InstrumentedRwLock, LockDiagnostics, TimedWriteGuard};
+use super::language::{detect_language, language_tag};
+use super::preload::{PreloadOptions, PreloadedIndex};
+use super::read_pool::ReadPool;
+use super::snippet::truncate_snippet;
+use super::spans::{BlockingTiming, CORE_FIELD, LOCK_WAIT_FIELD};
+use super::spelling::SpellDictionary;
+use super::suggest::SuggestionIndex;
+use super::synonyms::SynonymMap;
 use crate::error::ServiceError;
 use crate::memvid::searcher::{
-    AskMode, AskRequest, AskResponse, AskStats, SearchResponse, SearchResult, Searcher,
-    StateResponse,
+    AdaptiveOptions, AskMode, AskRequest, AskResponse, AskStats, EntitySummary, FrameMetadata,
+    FrameText, IndexFeatures, SearchRequest, SearchResponse, SearchResult, Searcher, StateResponse,
 };
+use crate::metrics;
 
 /// Real searcher that uses memvid-core to load and search .mv2 files.
 pub struct RealSearcher {
     /// Path to the .mv2 file
     file_path: PathBuf,
-    /// Memvid instance (wrapped in Arc<RwLock> for async access)
-    memvid: Arc<RwLock<Memvid>>,
+    /// Memvid instances, each behind an instrumented RwLock for async access
+    memvid: Arc<ReadPool<Memvid>>,
     /// Cached frame count (to avoid locking for frame_count() calls)
     frame_count: i32,
+    /// Index structures detected at load time
+    index_features: IndexFeatures,
+    /// Frames per tag, counted at load time
+    section_counts: BTreeMap<String, i32>,
+    /// Acronyms defined in frame titles and the profile, learned at load time
+    acronyms: Arc<AcronymMap>,
+    /// Words of frame titles and texts, learned at load time
+    spelling: Arc<SpellDictionary>,
+    /// Titles, tags and words of frames, for typeahead, built at load time
+    suggestions: Arc<SuggestionIndex>,
+    /// Detected language per frame, at load time (undetected frames absent)
+    frame_languages: Arc<HashMap<u64, &'static str>>,
+    /// Ingestion time and source version per frame (frames with neither absent)
+    frame_freshness: Arc<HashMap<u64, Freshness>>,
+    /// Query embedder for semantic retrieval (None = memvid built-in)
+    embedder: Option<Arc<dyn VecEmbedder + Send + Sync>>,
+    /// Embedder fallback chain, when `embedder` is one
+    embedder_chain: Option<Arc<EmbedderChain>>,
+    /// Query embeddings shared across Search and Ask
+    embedding_cache: Arc<QueryEmbeddingCache>,
+    /// Index file held resident in memory (None = read from disk on demand)
+    preloaded: Option<PreloadedIndex>,
+    /// Admission control for blocking searches (None = unlimited)
+    limiter: Option<Arc<SearchLimiter>>,
+    /// Operator-configured synonyms appended to search queries
+    synonyms: Arc<SynonymMap>,
 }
 
 impl std::fmt::Debug for RealSearcher {
@@ -33,6 +79,8 @@ impl std::fmt::Debug for RealSearcher {
         f.debug_struct("RealSearcher")
             .field("file_path", &self.file_path)
             .field("frame_count", &self.frame_count)
+            .field("index_features", &self.index_features)
+            .field("preloaded", &self.preloaded)
             .finish_non_exhaustive()
     }
 }
@@ -64,10 +112,38 @@ impl RealSearcher {
             ));
         }
 
-        // Load the memvid file (open read-only)
-        let memvid = tokio::task::spawn_blocking({
+        // Load the memvid file (open read-only), count frames per tag, learn
+        // acronyms, spelling and suggestions, detect frame languages and read
+        // frame freshness
+        let (
+            memvid,
+            section_counts,
+            acronyms,
+            spelling,
+            suggestions,
+            frame_languages,
+            frame_freshness,
+        ) = tokio::task::spawn_blocking({
             let file_path = file_path.clone();
-            move || Memvid::open_read_only(&file_path)
+            move || {
+                Memvid::open_read_only(&file_path).map(|mut memvid| {
+                    let counts = count_sections(&memvid);
+                    let acronyms = learn_acronyms(&memvid);
+                    let spelling = learn_spelling(&mut memvid);
+                    let suggestions = learn_suggestions(&mut memvid);
+                    let languages = detect_frame_languages(&mut memvid);
+                    let freshness = read_frame_freshness(&memvid);
+                    (
+                        memvid,
+                        counts,
+                        acronyms,
+                        spelling,
+                        suggestions,
+                        languages,
+                        freshness,
+                    )
+                })
+            }
         })
         .await
         .map_err(|e| {
@@ -82,69 +158,500 @@ impl RealSearcher {
         // Get file metadata
         let frame_count = memvid.frame_count() as i32;
 
+        // Detect optional index structures; a stats failure is not fatal
+        let index_features = match memvid.stats() {
+            Ok(stats) => IndexFeatures {
+                lexical: stats.has_lex_index,
+                vector: stats.has_vec_index,
+                temporal: stats.has_time_index,
+            },
+            Err(e) => {
+                warn!(error = %e, "Failed to read index stats, reporting no optional indexes");
+                IndexFeatures::default()
+            }
+        };
+
         info!(
             path = %file_path.display(),
             frame_count,
+            lexical_index = index_features.lexical,
+            vector_index = index_features.vector,
+            sections = section_counts.len(),
+            acronyms = acronyms.len(),
+            spelling_words = spelling.len(),
+            suggestions = suggestions.len(),
+            language_tagged = frame_languages.len(),
+            freshness_known = frame_freshness.len(),
             "Memvid file loaded successfully"
         );
 
         Ok(Self {
             file_path,
-            memvid: Arc::new(RwLock::new(memvid)),
+            memvid: Arc::new(ReadPool::new(memvid)),
             frame_count,
+            index_features,
+            section_counts,
+            acronyms: Arc::new(acronyms),
+            spelling: Arc::new(spelling),
+            suggestions: Arc::new(suggestions),
+            frame_languages: Arc::new(frame_languages),
+            frame_freshness: Arc::new(frame_freshness),
+            embedder: None,
+            embedder_chain: None,
+            embedding_cache: Arc::new(QueryEmbeddingCache::default()),
+            preloaded: None,
+            limiter: None,
+            synonyms: Arc::new(SynonymMap::default()),
+        })
+    }
+
+    /// Use `embedder` for Ask query embeddings, caching results across RPCs.
+    pub fn with_embedder(mut self, embedder: Arc<dyn VecEmbedder + Send + Sync>) -> Self {
+        self.embedder = Some(embedder);
+        self
+    }
+
+    /// Embed queries through an ordered fallback chain of embedders.
+    ///
+    /// When no tier of the chain is healthy, Ask degrades to lexical-only
+    /// retrieval instead of failing.
+    pub fn with_embedder_chain(mut self, chain: Arc<EmbedderChain>) -> Self {
+        self.embedder = Some(Arc::clone(&chain) as Arc<dyn VecEmbedder + Send + Sync>);
+        self.embedder_chain = Some(chain);
+        self
+    }
+
+    /// Share an embedding cache with other searchers, e.g. across reloads
+    /// and named indexes.
+    pub fn with_embedding_cache(mut self, cache: Arc<QueryEmbeddingCache>) -> Self {
+        self.embedding_cache = cache;
+        self
+    }
+
+    /// Admit searches and asks through `limiter` (shared across reloads).
+    pub fn with_limiter(mut self, limiter: Arc<SearchLimiter>) -> Self {
+        self.limiter = Some(limiter);
+        self
+    }
+
+    /// Expand search queries with `synonyms` (shared across reloads).
+    pub fn with_synonyms(mut self, synonyms: Arc<SynonymMap>) -> Self {
+        self.synonyms = synonyms;
+        self
+    }
+
+    /// Open the index `size` times in all and spread queries across the
+    /// instances, so concurrent searches do not queue on one lock.
+    pub async fn with_read_pool(mut self, size: usize) -> Result<Self, ServiceError> {
+        if size <= self.memvid.size() {
+            return Ok(self);
+        }
+        let path = self.file_path.clone();
+        let extra = size - self.memvid.size();
+        let instances = tokio::task::spawn_blocking(move || {
+            (0..extra)
+                .map(|_| Memvid::open_read_only(&path))
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .await
+        .map_err(|e| ServiceError::Internal(format!("Read pool task error: {}", e)))?
+        .map_err(|e| {
+            error!(error = %e, "Failed to open memvid read pool instance");
+            ServiceError::MemvidLoadError(e.to_string())
+        })?;
+        self.memvid = Arc::new(self.memvid.extended(instances));
+        info!(
+            path = %self.file_path.display(),
+            instances = size,
+            "Memvid read pool opened"
+        );
+        Ok(self)
+    }
+
+    /// Read the whole index file into memory (and optionally mlock it),
+    /// keeping it resident for the lifetime of this searcher.
+    pub async fn with_preload(mut self, options: PreloadOptions) -> Result<Self, ServiceError> {
+        let path = self.file_path.clone();
+        self.preloaded = tokio::task::spawn_blocking(move || PreloadedIndex::load(&path, options))
+            .await
+            .map_err(|e| ServiceError::Internal(format!("Preload task error: {}", e)))??;
+        Ok(self)
+    }
+}
+
+impl RealSearcher {
+    /// Wait for a search slot; the permit is held by the blocking task until
+    /// it finishes, even when the caller stops waiting for it.
+    async fn admit(
+        &self,
+        operation: &'static str,
+    ) -> Result<Option<OwnedSemaphorePermit>, ServiceError> {
+        match &self.limiter {
+            Some(limiter) => limiter.acquire(operation).await.map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Run a memvid-core ask on the blocking pool.
+    async fn run_ask(
+        &self,
+        memvid_request: MemvidAskRequest,
+    ) -> Result<MemvidAskResponse, ServiceError> {
+        let embedder = self.embedder.as_ref().map(|inner| CachingEmbedder {
+            inner: Arc::clone(inner),
+            cache: Arc::clone(&self.embedding_cache),
+        });
+        let permit = self.admit("ask").await?;
+        let deadline = deadline::current();
+        let timing = BlockingTiming::start();
+        let task = tokio::task::spawn_blocking({
+            let memvid = self.memvid.next_exclusive();
+            move || {
+                let _permit = permit;
+                timing.started(memvid.stats());
+                let mut memvid =
+                    timing.time(LOCK_WAIT_FIELD, || lock_index(&memvid, deadline, "ask"))?;
+
+                // Without a configured embedder, memvid uses built-in embeddings
+                Ok(timing.time(CORE_FIELD, || memvid.ask(memvid_request, embedder.as_ref())))
+            }
+        });
+
+        deadline::within(deadline, "ask", task)
+            .await?
+            .map_err(|e| {
+                error!(error = %e, "Ask task failed");
+                ServiceError::Internal(format!("Ask task error: {}", e))
+            })??
+            .map_err(|e| {
+                error!(error = %e, "Memvid ask failed");
+                ServiceError::Internal(format!("Ask error: {}", e))
+            })
+    }
+}
+
+/// Take the index write lock from a blocking task, unless the request's
+/// deadline passes while queued or waiting for it.
+///
+/// Search and ask need it: memvid-core takes `&mut Memvid` for both, since
+/// it loads index segments and frame payloads lazily. Lookups that only
+/// read loaded metadata (state, frame exports) take the read lock and run
+/// alongside each other; extra read pool instances let queries run
+/// concurrently.
+fn lock_index<'a>(
+    memvid: &'a InstrumentedRwLock<Memvid>,
+    deadline: Option<tokio::time::Instant>,
+    operation: &'static str,
+) -> Result<TimedWriteGuard<'a, Memvid>, ServiceError> {
+    tokio::runtime::Handle::current().block_on(deadline::within(
+        deadline,
+        operation,
+        memvid.write(),
+    ))
+}
+
+/// Embedder that consults the shared query embedding cache first.
+struct CachingEmbedder {
+    inner: Arc<dyn VecEmbedder + Send + Sync>,
+    cache: Arc<QueryEmbeddingCache>,
+}
+
+impl VecEmbedder for CachingEmbedder {
+    fn embed_query(&self, text: &str) -> memvid_core::Result<Vec<f32>> {
+        self.cache
+            .get_or_compute(text, || self.inner.embed_query(text))
+    }
+
+    fn embedding_dimension(&self) -> usize {
+        self.inner.embedding_dimension()
+    }
+}
+
+/// memvid-core ACL context of a request.
+fn core_acl_context(acl: Option<&AclContext>) -> Option<MemvidAclContext> {
+    acl.map(|acl| MemvidAclContext {
+        tenant_id: acl.tenant_id.clone(),
+        subject_id: acl.subject_id.clone(),
+        roles: acl.roles.clone(),
+        group_ids: acl.group_ids.clone(),
+    })
+}
+
+/// memvid-core ACL enforcement of a request; without a context, audit only.
+fn core_acl_mode(acl: Option<&AclContext>) -> AclEnforcementMode {
+    match acl.map(|acl| acl.mode) {
+        Some(AclMode::Enforce) => AclEnforcementMode::Enforce,
+        Some(AclMode::Audit) | None => AclEnforcementMode::Audit,
+    }
+}
+
+/// Convert adaptive retrieval options to memvid-core's config.
+///
+/// Thresholds left unset keep the defaults of memvid-core's combined
+/// cutoff, so setting one threshold does not switch the others off.
+fn core_adaptive(options: &AdaptiveOptions) -> AdaptiveConfig {
+    let mut config = AdaptiveConfig::default();
+    if let Some(min_results) = options.min_results {
+        config.min_results = min_results;
+    }
+    if let Some(max_results) = options.max_results {
+        config.max_results = max_results;
+    }
+    if options.min_score.is_some()
+        || options.relative_threshold.is_some()
+        || options.max_score_drop.is_some()
+    {
+        let (relative, drop, absolute) = match config.strategy {
+            CutoffStrategy::Combined {
+                relative_threshold,
+                max_drop_ratio,
+                absolute_min,
+            } => (relative_threshold, max_drop_ratio, absolute_min),
+            _ => (0.0, 1.0, 0.0),
+        };
+        config.strategy = CutoffStrategy::Combined {
+            relative_threshold: options.relative_threshold.unwrap_or(relative),
+            max_drop_ratio: options.max_score_drop.unwrap_or(drop),
+            absolute_min: options.min_score.unwrap_or(absolute),
+        };
+    }
+    config
+}
+
+/// Convert metadata filters to a memvid-core scope query, if any.
+///
+/// Scope format: "key1:value1 key2:value2", sorted so the same filters
+/// always make the same query.
+fn filter_scope(filters: &HashMap<String, String>) -> Option<String> {
+    if filters.is_empty() {
+        return None;
+    }
+    let mut terms = filters
+        .iter()
+        .map(|(k, v)| format!("{}:{}", k, v))
+        .collect::<Vec<_>>();
+    terms.sort();
+    Some(terms.join(" "))
+}
+
+/// Count frames per tag by walking every frame in the index.
+///
+/// Frames that cannot be read are skipped; a warning reports how many.
+fn count_sections(memvid: &Memvid) -> BTreeMap<String, i32> {
+    let mut counts = BTreeMap::new();
+    let mut unreadable = 0usize;
+
+    for frame_id in 0..memvid.frame_count() as u64 {
+        match memvid.frame_by_id(frame_id) {
+            Ok(frame) => {
+                for tag in frame.tags {
+                    *counts.entry(tag).or_insert(0) += 1;
+                }
+            }
+            Err(_) => unreadable += 1,
+        }
+    }
+
+    if unreadable > 0 {
+        warn!(
+            unreadable,
+            "Skipped unreadable frames while counting sections"
+        );
+    }
+    counts
+}
+
+/// Learn acronym definitions from frame titles and profile memory cards.
+fn learn_acronyms(memvid: &Memvid) -> AcronymMap {
+    let titles: Vec<String> = (0..memvid.frame_count() as u64)
+        .filter_map(|frame_id| memvid.frame_by_id(frame_id).ok()?.title)
+        .collect();
+    // The profile card holds the resume summary and experience text
+    let profile: Vec<String> = memvid
+        .get_entity_memories("__profile__")
+        .into_iter()
+        .map(|card| card.value.clone())
+        .collect();
+
+    AcronymMap::from_texts(titles.iter().chain(&profile).map(String::as_str))
+}
+
+/// Learn the words of frame titles and texts for spelling correction.
+fn learn_spelling(memvid: &mut Memvid) -> SpellDictionary {
+    let texts: Vec<String> = (0..memvid.frame_count() as u64)
+        .flat_map(|frame_id| {
+            let title = memvid.frame_by_id(frame_id).ok().and_then(|f| f.title);
+            let text = memvid.frame_text_by_id(frame_id).ok();
+            title.into_iter().chain(text)
         })
+        .collect();
+    SpellDictionary::from_texts(texts.iter().map(String::as_str))
+}
+
+/// Build the typeahead trie from frame titles, texts and tags.
+fn learn_suggestions(memvid: &mut Memvid) -> SuggestionIndex {
+    let mut suggestions = SuggestionIndex::default();
+    for frame_id in 0..memvid.frame_count() as u64 {
+        let Ok(frame) = memvid.frame_by_id(frame_id) else {
+            continue;
+        };
+        let text = memvid.frame_text_by_id(frame_id).unwrap_or_default();
+        let tags: Vec<&str> = frame.tags.iter().map(String::as_str).collect();
+        suggestions.add_frame(frame_id, frame.title.as_deref().unwrap_or(""), &text, &tags);
+    }
+    suggestions
+}
+
+/// Detect the language of every readable frame.
+fn detect_frame_languages(memvid: &mut Memvid) -> HashMap<u64, &'static str> {
+    (0..memvid.frame_count() as u64)
+        .filter_map(|frame_id| {
+            let text = memvid.frame_text_by_id(frame_id).ok()?;
+            detect_language(&text).map(|code| (frame_id, code))
+        })
+        .collect()
+}
+
+/// Read the ingestion time and source version of every readable frame.
+fn read_frame_freshness(memvid: &Memvid) -> HashMap<u64, Freshness> {
+    (0..memvid.frame_count() as u64)
+        .filter_map(|frame_id| {
+            let frame = memvid.frame_by_id(frame_id).ok()?;
+            let freshness = Freshness::from_metadata(&frame.extra_metadata, frame.timestamp);
+            (!freshness.is_empty()).then_some((frame_id, freshness))
+        })
+        .collect()
+}
+
+impl RealSearcher {
+    /// Append the frame's detected language tag, if any.
+    fn tag_language(&self, frame_id: u64, tags: &mut Vec<String>) {
+        if let Some(code) = self.frame_languages.get(&frame_id) {
+            tags.push(language_tag(code));
+        }
+    }
+
+    /// The frame's freshness hints (empty when unknown).
+    fn freshness(&self, frame_id: u64) -> Freshness {
+        self.frame_freshness
+            .get(&frame_id)
+            .cloned()
+            .unwrap_or_default()
     }
 }
 
 #[async_trait]
 impl Searcher for RealSearcher {
-    async fn search(
-        &self,
-        query: &str,
-        top_k: i32,
-        snippet_chars: i32,
-    ) -> Result<SearchResponse, ServiceError> {
+    async fn search(&self, request: SearchRequest) -> Result<SearchResponse, ServiceError> {
         let start = std::time::Instant::now();
+        let snippet_chars = request.snippet_chars;
+        // The budget runs from here, so time queued for a permit counts
+        let expires = request.budget_ms.map(|budget_ms| {
+            tokio::time::Instant::from_std(start) + Duration::from_millis(budget_ms as u64)
+        });
+        // memvid-core returns candidates all at once, so an expired budget
+        // always yields an empty partial response
+        let expired = |expanded_query: Option<String>| {
+            warn!(
+                budget_ms = ?request.budget_ms,
+                "Search exceeded time budget, returning no hits"
+            );
+            SearchResponse {
+                hits: Vec::new(),
+                total_hits: 0,
+                took_ms: start.elapsed().as_millis() as i32,
+                partial: true,
+                expanded_query,
+            }
+        };
 
+        // The query text is logged by the gRPC layer, under the retention
+        // policy
         info!(
-            query = query,
-            top_k = top_k,
+            top_k = request.top_k,
+            budget_ms = ?request.budget_ms,
             "Performing real memvid search"
         );
 
+        // Append configured synonyms, reporting the query actually searched
+        let expanded_query = self.synonyms.expand_query(&request.query);
+        if let Some(expanded) = &expanded_query {
+            let added_terms = expanded
+                .split_whitespace()
+                .count()
+                .saturating_sub(request.query.split_whitespace().count());
+            debug!(added_terms, "Expanded query with synonyms");
+        }
+
         // Build search request (convert i32 to usize for memvid-core)
-        let search_request = SearchRequest {
-            query: query.to_string(),
-            top_k: top_k as usize,
+        let search_request = MemvidSearchRequest {
+            query: expanded_query
+                .clone()
+                .unwrap_or_else(|| request.query.clone()),
+            top_k: request.top_k as usize,
             snippet_chars: snippet_chars as usize,
-            uri: None,
-            scope: None,
+            uri: request.uri.clone(),
+            scope: filter_scope(&request.filters),
             cursor: None,
             as_of_frame: None,
             as_of_ts: None,
             no_sketch: false,
-            acl_context: None,
-            acl_enforcement_mode: AclEnforcementMode::Audit,
+            acl_context: core_acl_context(request.acl.as_ref()),
+            acl_enforcement_mode: core_acl_mode(request.acl.as_ref()),
         };
 
         // Perform the search (blocking operation)
-        let search_response = tokio::task::spawn_blocking({
-            let memvid = Arc::clone(&self.memvid);
+        let admitted = match expires {
+            Some(expires) => tokio::time::timeout_at(expires, self.admit("search"))
+                .await
+                .ok(),
+            None => Some(self.admit("search").await),
+        };
+        let Some(permit) = admitted else {
+            return Ok(expired(expanded_query));
+        };
+        let permit = permit?;
+        let deadline = deadline::current();
+        let timing = BlockingTiming::start();
+        let task = tokio::task::spawn_blocking({
+            let memvid = self.memvid.next_exclusive();
             move || {
-                let mut memvid = tokio::runtime::Handle::current().block_on(memvid.write());
+                let _permit = permit;
+                // Nobody waits for a search past its budget: give the permit
+                // back rather than lock an index instance
+                if expires.is_some_and(|expires| tokio::time::Instant::now() >= expires) {
+                    return Err(ServiceError::DeadlineExceeded(
+                        "search abandoned: its time budget expired".to_string(),
+                    ));
+                }
+                timing.started(memvid.stats());
+                let mut memvid =
+                    timing.time(LOCK_WAIT_FIELD, || lock_index(&memvid, deadline, "search"))?;
 
-                memvid.search(search_request)
+                Ok(timing.time(CORE_FIELD, || memvid.search(search_request)))
             }
-        })
-        .await
-        .map_err(|e| {
-            error!(error = %e, "Search task failed");
-            ServiceError::Internal(format!("Search task error: {}", e))
-        })?
-        .map_err(|e| {
-            error!(error = %e, "Memvid search failed");
-            ServiceError::Internal(format!("Search error: {}", e))
-        })?;
+        });
+        let task = deadline::within(deadline, "search", task);
+
+        // A search already running when the budget expires finishes in the
+        // background; one still queued gives up before locking
+        let joined = match expires {
+            Some(expires) => match tokio::time::timeout_at(expires, task).await {
+                Ok(joined) => joined,
+                Err(_) => return Ok(expired(expanded_query)),
+            },
+            None => task.await,
+        };
+
+        let search_response = joined?
+            .map_err(|e| {
+                error!(error = %e, "Search task failed");
+                ServiceError::Internal(format!("Search task error: {}", e))
+            })??
+            .map_err(|e| {
+                error!(error = %e, "Memvid search failed");
+                ServiceError::Internal(format!("Search error: {}", e))
+            })?;
 
         // Convert memvid results to our SearchResult format
         let hits: Vec<SearchResult> = search_response
@@ -165,25 +672,31 @@ impl Searcher for RealSearcher {
                     .unwrap_or_default();
 
                 // Get tags from metadata
-                let tags = result
+                let mut tags = result
                     .metadata
                     .as_ref()
                     .map(|m| m.tags.clone())
                     .unwrap_or_default();
+                self.tag_language(result.frame_id, &mut tags);
+                let freshness = self.freshness(result.frame_id);
 
-                // Truncate snippet to requested length
-                let snippet_len = snippet_chars as usize;
-                let snippet = if result.text.len() > snippet_len {
-                    format!("{}...", &result.text[..snippet_len])
-                } else {
-                    result.text.clone()
-                };
+                let snippet = truncate_snippet(&result.text, snippet_chars as usize);
 
                 SearchResult {
+                    frame_id: Some(result.frame_id),
                     title,
                     score: result.score.unwrap_or(0.0),
                     snippet,
                     tags,
+                    uri: Some(result.uri).filter(|uri| !uri.is_empty()),
+                    timestamp: result
+                        .metadata
+                        .as_ref()
+                        .and_then(|m| m.created_at.as_deref())
+                        .and_then(|at| chrono::DateTime::parse_from_rfc3339(at).ok())
+                        .map(|at| at.timestamp()),
+                    ingested_at: freshness.ingested_at,
+                    source_version: freshness.source_version,
                 }
             })
             .collect();
@@ -201,6 +714,8 @@ impl Searcher for RealSearcher {
             hits,
             total_hits,
             took_ms,
+            partial: false,
+            expanded_query,
         })
     }
 
@@ -208,39 +723,24 @@ impl Searcher for RealSearcher {
         let start = std::time::Instant::now();
 
         info!(
-            question = request.question,
             mode = ?request.mode,
             top_k = request.top_k,
             "Performing real memvid ask"
         );
 
-        // Map our AskMode to memvid-core AskMode
-        let mode = match request.mode {
-            AskMode::Hybrid => MemvidAskMode::Hybrid,
-            AskMode::Sem => MemvidAskMode::Sem,
-            AskMode::Lex => MemvidAskMode::Lex,
-        };
-
-        // Convert filters to scope query if provided
-        // Scope format: "key1:value1 key2:value2" for metadata filtering
-        let scope = if !request.filters.is_empty() {
-            let scope_query = request
-                .filters
-                .iter()
-                .map(|(k, v)| format!("{}:{}", k, v))
-                .collect::<Vec<_>>()
-                .join(" ");
-            Some(scope_query)
-        } else {
-            None
-        };
+        let scope = filter_scope(&request.filters);
 
         // Build memvid-core AskRequest
-        let memvid_request = MemvidAskRequest {
+        let build_request = |mode: AskMode| MemvidAskRequest {
             question: request.question.clone(),
             top_k: request.top_k as usize,
             snippet_chars: request.snippet_chars as usize,
-            mode,
+            // Map our AskMode to memvid-core AskMode
+            mode: match mode {
+                AskMode::Hybrid => MemvidAskMode::Hybrid,
+                AskMode::Sem => MemvidAskMode::Sem,
+                AskMode::Lex => MemvidAskMode::Lex,
+            },
             start: if request.start > 0 {
                 Some(request.start)
             } else {
@@ -253,40 +753,41 @@ impl Searcher for RealSearcher {
             },
             context_only: !request.use_llm, // context_only = true means no LLM synthesis
             uri: request.uri.clone(),
-            scope,
+            scope: scope.clone(),
             cursor: request.cursor.clone(),
             as_of_frame: request.as_of_frame.map(|f| f as u64),
             as_of_ts: request.as_of_ts,
-            adaptive: request.adaptive.and_then(|enabled| {
-                if enabled {
-                    Some(AdaptiveConfig::default())
-                } else {
-                    None
-                }
-            }),
-            acl_context: None,
-            acl_enforcement_mode: AclEnforcementMode::Audit,
+            adaptive: request.adaptive.as_ref().map(core_adaptive),
+            acl_context: core_acl_context(request.acl.as_ref()),
+            acl_enforcement_mode: core_acl_mode(request.acl.as_ref()),
         };
 
-        // Perform the ask operation (blocking)
-        let ask_response = tokio::task::spawn_blocking({
-            let memvid = Arc::clone(&self.memvid);
-            move || {
-                let mut memvid = tokio::runtime::Handle::current().block_on(memvid.write());
+        // Lexical-only retrieval is the last tier of the embedder chain: use
+        // it up front when no tier is healthy, or retry with it when every
+        // tier failed during this request
+        let chain_down = || {
+            self.embedder_chain
+                .as_ref()
+                .is_some_and(|chain| !chain.has_healthy_tier())
+        };
+        let needs_embeddings = !matches!(request.mode, AskMode::Lex);
+        let mut used_fallback = needs_embeddings && chain_down();
+        let mode = if used_fallback {
+            AskMode::Lex
+        } else {
+            request.mode
+        };
 
-                // Pass None for embedder - memvid will use built-in embeddings
-                memvid.ask(memvid_request, None::<&dyn memvid_core::VecEmbedder>)
-            }
-        })
-        .await
-        .map_err(|e| {
-            error!(error = %e, "Ask task failed");
-            ServiceError::Internal(format!("Ask task error: {}", e))
-        })?
-        .map_err(|e| {
-            error!(error = %e, "Memvid ask failed");
-            ServiceError::Internal(format!("Ask error: {}", e))
-        })?;
+        let mut result = self.run_ask(build_request(mode)).await;
+        if result.is_err() && needs_embeddings && !used_fallback && chain_down() {
+            warn!("All embedder tiers failed, retrying ask lexical-only");
+            used_fallback = true;
+            result = self.run_ask(build_request(AskMode::Lex)).await;
+        }
+        if used_fallback {
+            metrics::record_embedder_tier(LEXICAL_TIER);
+        }
+        let ask_response = result?;
 
         // Convert memvid results to our format
         let evidence: Vec<SearchResult> = ask_response
@@ -306,13 +807,22 @@ impl Searcher for RealSearcher {
                 };
 
                 // Get tags from metadata if available
-                let tags = vec![]; // memvid AskContextFragment doesn't expose tags directly
+                // memvid AskContextFragment doesn't expose tags directly
+                let mut tags = vec![];
+                self.tag_language(fragment.frame_id, &mut tags);
+                let freshness = self.freshness(fragment.frame_id);
 
                 SearchResult {
+                    frame_id: Some(fragment.frame_id),
                     title,
                     score: fragment.score.unwrap_or(0.0),
                     snippet: fragment.text,
                     tags,
+                    uri: Some(fragment.uri).filter(|uri| !uri.is_empty()),
+                    // memvid AskContextFragment doesn't expose frame dates
+                    timestamp: None,
+                    ingested_at: freshness.ingested_at,
+                    source_version: freshness.source_version,
                 }
             })
             .collect();
@@ -342,9 +852,10 @@ impl Searcher for RealSearcher {
                 candidates_retrieved: evidence_count,
                 results_returned: evidence_count,
                 retrieval_ms: took_ms,
-                reranking_ms: 0,      // memvid-core doesn't expose this separately
-                used_fallback: false, // memvid-core doesn't expose this
+                reranking_ms: 0, // memvid-core doesn't expose this separately
+                used_fallback,
             },
+            next_cursor: None, // memvid-core ask doesn't return a continuation cursor
         })
     }
 
@@ -356,19 +867,25 @@ impl Searcher for RealSearcher {
         info!(entity = entity, slot = ?slot, "Performing memvid state lookup");
 
         // Get entity memory cards (blocking operation)
+        let timing = BlockingTiming::start();
         let memory_cards = tokio::task::spawn_blocking({
-            let memvid = Arc::clone(&self.memvid);
+            let memvid = self.memvid.next();
             let entity = entity.to_string();
 
             move || -> Vec<(String, String)> {
-                let memvid = tokio::runtime::Handle::current().block_on(memvid.read());
+                timing.started(memvid.stats());
+                let memvid = timing.time(LOCK_WAIT_FIELD, || {
+                    tokio::runtime::Handle::current().block_on(memvid.read())
+                });
 
                 // Get all memory cards for this entity
-                memvid
-                    .get_entity_memories(&entity)
-                    .into_iter()
-                    .map(|card| (card.slot.clone(), card.value.clone()))
-                    .collect()
+                timing.time(CORE_FIELD, || {
+                    memvid
+                        .get_entity_memories(&entity)
+                        .into_iter()
+                        .map(|card| (card.slot.clone(), card.value.clone()))
+                        .collect()
+                })
             }
         })
         .await
@@ -416,17 +933,182 @@ impl Searcher for RealSearcher {
         })
     }
 
+    async fn list_entities(&self) -> Result<Vec<EntitySummary>, ServiceError> {
+        let timing = BlockingTiming::start();
+        let mut entities = tokio::task::spawn_blocking({
+            let memvid = self.memvid.next();
+
+            move || -> Vec<EntitySummary> {
+                timing.started(memvid.stats());
+                let memvid = timing.time(LOCK_WAIT_FIELD, || {
+                    tokio::runtime::Handle::current().block_on(memvid.read())
+                });
+
+                timing.time(CORE_FIELD, || {
+                    memvid
+                        .memory_entities()
+                        .into_iter()
+                        .map(|name| {
+                            // Slots can hold several cards; count each once
+                            let slots: HashSet<&str> = memvid
+                                .get_entity_memories(&name)
+                                .into_iter()
+                                .map(|card| card.slot.as_str())
+                                .collect();
+                            EntitySummary {
+                                name,
+                                slot_count: slots.len() as i32,
+                            }
+                        })
+                        .collect()
+                })
+            }
+        })
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Entity listing task failed");
+            ServiceError::Internal(format!("Entity listing task error: {}", e))
+        })?;
+
+        entities.sort_by(|a, b| a.name.cmp(&b.name));
+        info!(entities = entities.len(), "Entity listing completed");
+        Ok(entities)
+    }
+
+    async fn export_frames(
+        &self,
+        after: Option<u64>,
+        limit: usize,
+    ) -> Result<Vec<FrameMetadata>, ServiceError> {
+        let first = after.map_or(0, |id| id + 1);
+        let end = self.frame_count.max(0) as u64;
+
+        // One bounded batch per blocking task, so a long export shares the
+        // blocking pool and the index lock with queries between batches
+        let timing = BlockingTiming::start();
+        tokio::task::spawn_blocking({
+            let memvid = self.memvid.next();
+            move || {
+                timing.started(memvid.stats());
+                let memvid = timing.time(LOCK_WAIT_FIELD, || {
+                    tokio::runtime::Handle::current().block_on(memvid.read())
+                });
+
+                timing.time(CORE_FIELD, || {
+                    (first..end)
+                        .filter_map(|frame_id| match memvid.frame_by_id(frame_id) {
+                            Ok(frame) => Some(FrameMetadata {
+                                frame_id,
+                                uri: frame.uri.unwrap_or_default(),
+                                title: frame.title.unwrap_or_default(),
+                                tags: frame.tags,
+                                labels: frame.labels,
+                                metadata: frame.extra_metadata.into_iter().collect(),
+                            }),
+                            Err(e) => {
+                                warn!(frame_id, error = %e, "Skipping unreadable frame in export");
+                                None
+                            }
+                        })
+                        .take(limit)
+                        .collect()
+                })
+            }
+        })
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Export task failed");
+            ServiceError::Internal(format!("Export task error: {}", e))
+        })
+    }
+
+    async fn frame_texts(
+        &self,
+        after: Option<u64>,
+        limit: usize,
+    ) -> Result<Vec<FrameText>, ServiceError> {
+        let first = after.map_or(0, |id| id + 1);
+        let end = self.frame_count.max(0) as u64;
+
+        // Bounded batches, as in export_frames; reading text needs the
+        // write lock because memvid-core decompresses frame payloads lazily
+        let timing = BlockingTiming::start();
+        tokio::task::spawn_blocking({
+            let memvid = self.memvid.next_exclusive();
+            move || {
+                timing.started(memvid.stats());
+                let mut memvid = timing.time(LOCK_WAIT_FIELD, || {
+                    tokio::runtime::Handle::current().block_on(memvid.write())
+                });
+
+                timing.time(CORE_FIELD, || {
+                    (first..end)
+                        .filter_map(|frame_id| {
+                            let title = memvid.frame_by_id(frame_id).ok()?.title;
+                            match memvid.frame_text_by_id(frame_id) {
+                                Ok(text) => Some(FrameText {
+                                    frame_id,
+                                    title: title.unwrap_or_default(),
+                                    text,
+                                }),
+                                Err(e) => {
+                                    warn!(frame_id, error = %e, "Skipping unreadable frame text");
+                                    None
+                                }
+                            }
+                        })
+                        .take(limit)
+                        .collect()
+                })
+            }
+        })
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Frame text task failed");
+            ServiceError::Internal(format!("Frame text task error: {}", e))
+        })
+    }
+
     fn frame_count(&self) -> i32 {
         self.frame_count
     }
 
-    fn memvid_file(&self) -> &str {
-        self.file_path.to_str().unwrap_or("unknown")
+    fn memvid_file(&self) -> String {
+        self.file_path.to_str().unwrap_or("unknown").to_string()
+    }
+
+    fn generation(&self) -> u64 {
+        // A loaded file never changes; reloads swap in a new searcher
+        1
+    }
+
+    fn index_features(&self) -> IndexFeatures {
+        self.index_features
+    }
+
+    fn section_counts(&self) -> BTreeMap<String, i32> {
+        self.section_counts.clone()
+    }
+
+    fn lock_diagnostics(&self) -> LockDiagnostics {
+        self.memvid.diagnostics()
+    }
+
+    fn acronyms(&self) -> Arc<AcronymMap> {
+        Arc::clone(&self.acronyms)
+    }
+
+    fn spelling(&self) -> Arc<SpellDictionary> {
+        Arc::clone(&self.spelling)
+    }
+
+    fn suggestions(&self) -> Arc<SuggestionIndex> {
+        Arc::clone(&self.suggestions)
     }
 
     fn is_ready(&self) -> bool {
-        // Check if we can acquire a read lock
-        self.memvid.try_read().is_ok()
+        // Check if any instance can take a query
+        self.memvid.is_ready()
     }
 }
 
@@ -444,6 +1126,35 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_search_budget_counts_time_queued_for_a_permit() {
+        use crate::ingest::{write_index, IngestPlan, SourceFormat};
+
+        let path = std::env::temp_dir().join(format!("memvid-budget-{}.mv2", std::process::id()));
+        let plan =
+            IngestPlan::parse(r#"{"summary": "Platform engineer."}"#, SourceFormat::Json).unwrap();
+        write_index(&plan, &path).unwrap();
+        let limiter = Arc::new(SearchLimiter::new(1, 4));
+        let searcher = RealSearcher::new(&path)
+            .await
+            .unwrap()
+            .with_limiter(Arc::clone(&limiter));
+
+        // Every permit is taken, so the search waits out its budget queued
+        let _held = limiter.acquire("search").await.unwrap();
+        let request = SearchRequest {
+            budget_ms: Some(20),
+            ..SearchRequest::new("platform", 5, 200)
+        };
+        let response = tokio::time::timeout(Duration::from_secs(5), searcher.search(request))
+            .await
+            .expect("the budget bounds the queue wait")
+            .unwrap();
+        assert!(response.partial);
+        assert!(response.hits.is_empty());
+        std::fs::remove_file(path).ok();
+    }
+
     #[tokio::test]
     async fn test_real_searcher_loads_valid_file() {
         // Use the actual resume.mv2 file from the project
@@ -477,7 +1188,7 @@ mod tests {
             .expect("Should load .mv2 file");
 
         let response = searcher
-            .search("Python experience", 5, 200)
+            .search(SearchRequest::new("Python experience", 5, 200))
             .await
             .expect("Search should succeed");
 
@@ -518,6 +1229,7 @@ mod tests {
             as_of_frame: None,
             as_of_ts: None,
             adaptive: None,
+            acl: None,
         };
 
         let response = searcher.ask(request).await.expect("Ask should succeed");
@@ -553,6 +1265,7 @@ mod tests {
             as_of_frame: None,
             as_of_ts: None,
             adaptive: None,
+            acl: None,
         };
 
         let response = searcher.ask(request).await.expect("Ask should succeed");
@@ -587,6 +1300,7 @@ mod tests {
             as_of_frame: None,
             as_of_ts: None,
             adaptive: None,
+            acl: None,
         };
 
         let response = searcher.ask(request).await.expect("Ask should succeed");
@@ -728,6 +1442,7 @@ mod tests {
             as_of_frame: None,
             as_of_ts: None,
             adaptive: None,
+            acl: None,
         };
 
         let response = searcher
@@ -779,6 +1494,7 @@ mod tests {
             as_of_frame: None,
             as_of_ts: None,
             adaptive: None,
+            acl: None,
         };
 
         let response = searcher
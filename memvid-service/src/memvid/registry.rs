@@ -0,0 +1,190 @@
+//! Several named indexes served from one process.
+//!
+//! `MEMVID_FILE_PATHS` names the .mv2 files to serve, e.g. several candidate
+//! resumes or versions of one resume, either comma-separated
+//! (`alice=/data/alice.mv2,bob=/data/bob.mv2`, or bare paths named after
+//! their file stem) or as a TOML map of name to path. The first index is
+//! the default one, which requests without an `index` field and everything
+//! beyond Search and Ask (health, state, Admin) use. A [`SearcherRegistry`]
+//! resolves a request's `index` to its searcher.
+
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+use std::sync::Arc;
+
+use super::searcher::Searcher;
+use crate::error::ServiceError;
+
+/// Name of the only index when `MEMVID_FILE_PATHS` is not set.
+pub const DEFAULT_INDEX: &str = "default";
+
+/// Searchers keyed by index name.
+pub struct SearcherRegistry {
+    default: String,
+    indexes: BTreeMap<String, Arc<dyn Searcher>>,
+}
+
+impl SearcherRegistry {
+    /// Registry serving `searcher` as the default index `name`.
+    pub fn new(name: impl Into<String>, searcher: Arc<dyn Searcher>) -> Self {
+        let default = name.into();
+        Self {
+            indexes: BTreeMap::from([(default.clone(), searcher)]),
+            default,
+        }
+    }
+
+    /// Also serve `searcher` as index `name`.
+    pub fn with_index(mut self, name: impl Into<String>, searcher: Arc<dyn Searcher>) -> Self {
+        self.indexes.insert(name.into(), searcher);
+        self
+    }
+
+    /// Name of the default index.
+    pub fn default_name(&self) -> &str {
+        &self.default
+    }
+
+    /// The default index.
+    pub fn default_searcher(&self) -> &Arc<dyn Searcher> {
+        &self.indexes[&self.default]
+    }
+
+    /// Names of all indexes, sorted.
+    pub fn names(&self) -> Vec<&str> {
+        self.indexes.keys().map(String::as_str).collect()
+    }
+
+    /// The searcher of index `name` (empty = the default index).
+    ///
+    /// # Errors
+    /// `InvalidRequest` when no index has that name.
+    pub fn get(&self, name: &str) -> Result<&Arc<dyn Searcher>, ServiceError> {
+        if name.is_empty() {
+            return Ok(self.default_searcher());
+        }
+        self.indexes.get(name).ok_or_else(|| {
+            ServiceError::InvalidRequest(format!(
+                "Unknown index '{}', expected one of: {}",
+                name,
+                self.names().join(", ")
+            ))
+        })
+    }
+}
+
+/// Parse `MEMVID_FILE_PATHS` into (name, path) pairs in order; the first
+/// is the default index.
+///
+/// # Errors
+/// A description of the first malformed entry, invalid or duplicate name.
+pub fn parse_index_paths(value: &str) -> Result<Vec<(String, String)>, String> {
+    let entries = if value.contains('"') {
+        let table: toml::Table = value
+            .parse()
+            .map_err(|e| format!("invalid TOML map: {}", e))?;
+        table
+            .into_iter()
+            .map(|(name, path)| match path {
+                toml::Value::String(path) => Ok((name, path)),
+                other => Err(format!(
+                    "path of '{}' must be a string, got {}",
+                    name, other
+                )),
+            })
+            .collect::<Result<Vec<_>, String>>()?
+    } else {
+        value
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| match entry.split_once('=') {
+                Some((name, path)) => Ok((name.trim().to_string(), path.trim().to_string())),
+                None => Path::new(entry)
+                    .file_stem()
+                    .map(|stem| (stem.to_string_lossy().into_owned(), entry.to_string()))
+                    .ok_or_else(|| format!("cannot name index '{}'", entry)),
+            })
+            .collect::<Result<Vec<_>, String>>()?
+    };
+
+    let mut names = HashSet::new();
+    for (name, path) in &entries {
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        {
+            return Err(format!(
+                "index name '{}' must be letters, digits, '-', '_' or '.'",
+                name
+            ));
+        }
+        if path.trim().is_empty() {
+            return Err(format!("index '{}' has an empty path", name));
+        }
+        if !names.insert(name.as_str()) {
+            return Err(format!("index '{}' is listed twice", name));
+        }
+    }
+    if entries.is_empty() {
+        return Err("no indexes listed".to_string());
+    }
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memvid::MockSearcher;
+
+    fn pairs(entries: &[(&str, &str)]) -> Vec<(String, String)> {
+        entries
+            .iter()
+            .map(|(name, path)| (name.to_string(), path.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parses_comma_separated_paths() {
+        assert_eq!(
+            parse_index_paths("alice=/data/alice.mv2, /data/resume-v12.mv2").unwrap(),
+            pairs(&[
+                ("alice", "/data/alice.mv2"),
+                ("resume-v12", "/data/resume-v12.mv2")
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parses_toml_map_in_order() {
+        let value = "bob = \"/data/bob.mv2\"\nalice = \"/data/alice.mv2\"\n";
+        assert_eq!(
+            parse_index_paths(value).unwrap(),
+            pairs(&[("bob", "/data/bob.mv2"), ("alice", "/data/alice.mv2")])
+        );
+        assert!(parse_index_paths("bob = 3\nalice = \"a.mv2\"").is_err());
+    }
+
+    #[test]
+    fn test_rejects_invalid_entries() {
+        assert!(parse_index_paths("a=x.mv2,a=y.mv2")
+            .unwrap_err()
+            .contains("twice"));
+        assert!(parse_index_paths("a b=x.mv2").is_err());
+        assert!(parse_index_paths("a=").is_err());
+        assert!(parse_index_paths(" , ").is_err());
+    }
+
+    #[test]
+    fn test_resolves_indexes_by_name() {
+        let registry = SearcherRegistry::new("alice", Arc::new(MockSearcher::new()))
+            .with_index("bob", Arc::new(MockSearcher::synthetic(7, 10)));
+        assert_eq!(registry.default_name(), "alice");
+        assert_eq!(registry.get("").unwrap().frame_count(), 42);
+        assert_eq!(registry.get("bob").unwrap().frame_count(), 10);
+
+        let err = registry.get("carol").err().unwrap();
+        assert!(matches!(err, ServiceError::InvalidRequest(ref msg) if msg.contains("alice, bob")));
+    }
+}
@@ -0,0 +1,683 @@
+//! Hot-reloadable searcher wrapper.
+//!
+//! Holds the current searcher behind a swap-able handle so the .mv2 file can be
+//! re-checked and reloaded without restarting the service. In-flight requests
+//! keep using the searcher they started with.
+//!
+//! A failed reload never takes the service down: the last good index keeps
+//! serving and the failure is kept (see [`ReloadableSearcher::reload_failure`])
+//! until a later reload succeeds, so health checks can report DEGRADED.
+//!
+//! Also supports blue/green cutover: a second index can be staged alongside the
+//! active one, promoted atomically, and rolled back instantly until the
+//! operator confirms the swap.
+//!
+//! Every reloaded or staged index is probed for embedding drift against the
+//! serving one (see [`drift`](super::drift)); a mismatch is logged as an
+//! error but does not block the swap.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::SystemTime;
+use tracing::{error, info, warn};
+
+use super::acronyms::AcronymMap;
+use super::concurrency::SearchLimiter;
+use super::drift::EmbeddingProfile;
+use super::embedder_chain::EmbedderChain;
+use super::embedding_cache::QueryEmbeddingCache;
+use super::instrumented::LockDiagnostics;
+use super::preload::PreloadOptions;
+use super::real::RealSearcher;
+use super::searcher::{
+    AskRequest, AskResponse, EntitySummary, FrameMetadata, FrameText, IndexFeatures, SearchRequest,
+    SearchResponse, Searcher, StateResponse,
+};
+use super::spelling::SpellDictionary;
+use super::suggest::SuggestionIndex;
+use super::synonyms::SynonymMap;
+use crate::error::ServiceError;
+use crate::metrics;
+
+/// Boxed future returned by a [`SearcherLoader`].
+pub type LoadFuture = Pin<Box<dyn Future<Output = Result<Arc<dyn Searcher>, ServiceError>> + Send>>;
+
+/// Builds a searcher for the given file path.
+pub type SearcherLoader = Arc<dyn Fn(String) -> LoadFuture + Send + Sync>;
+
+/// Result of a reload attempt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReloadOutcome {
+    /// The source changed and the new index is now serving
+    Reloaded,
+    /// The source is unchanged since the last load; nothing was done
+    Unchanged,
+}
+
+/// Reloads failing since the last good load.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReloadFailure {
+    /// Error of the most recent attempt
+    pub reason: String,
+    /// When the first failed attempt happened
+    pub since: DateTime<Utc>,
+    /// Consecutive failed attempts
+    pub attempts: u32,
+}
+
+/// Which index files are resident, for blue/green cutover.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CutoverStatus {
+    /// File currently serving traffic
+    pub active: String,
+    /// File loaded and waiting to be promoted
+    pub staged: Option<String>,
+    /// File kept resident for rollback until the cutover is confirmed
+    pub previous: Option<String>,
+    /// Current index generation
+    pub generation: u64,
+}
+
+/// Modification time and size used to detect source changes.
+type SourceFingerprint = (Option<SystemTime>, u64);
+
+/// A loaded index and the file it came from.
+#[derive(Clone)]
+struct IndexSlot {
+    path: String,
+    searcher: Arc<dyn Searcher>,
+    fingerprint: Option<SourceFingerprint>,
+}
+
+impl IndexSlot {
+    async fn load(loader: &SearcherLoader, path: String) -> Result<Self, ServiceError> {
+        let fingerprint = source_fingerprint(&path);
+        let searcher = loader(path.clone()).await?;
+        Ok(Self {
+            path,
+            searcher,
+            fingerprint,
+        })
+    }
+}
+
+/// Searcher that can swap its underlying index at runtime.
+pub struct ReloadableSearcher {
+    loader: SearcherLoader,
+    active: RwLock<IndexSlot>,
+    staged: Mutex<Option<IndexSlot>>,
+    previous: Mutex<Option<IndexSlot>>,
+    generation: AtomicU64,
+    reload_lock: tokio::sync::Mutex<()>,
+    failure: Mutex<Option<ReloadFailure>>,
+}
+
+impl ReloadableSearcher {
+    /// Load a .mv2 file with memvid-core and make it reloadable.
+    pub async fn open(file_path: impl Into<String>) -> Result<Self, ServiceError> {
+        Self::open_preloaded(
+            file_path,
+            None,
+            None,
+            1,
+            None,
+            Arc::new(QueryEmbeddingCache::default()),
+            None,
+        )
+        .await
+    }
+
+    /// Load a .mv2 file with memvid-core, preloading each loaded index into
+    /// memory when `preload` is set, opening it `read_pool_size` times for
+    /// concurrent queries, admitting searches through `limiter`, embedding
+    /// queries with `embedder` into `embedding_cache` and expanding them with
+    /// `synonyms`, and make it reloadable. Every loaded index shares the one
+    /// `embedding_cache`.
+    pub async fn open_preloaded(
+        file_path: impl Into<String>,
+        preload: Option<PreloadOptions>,
+        limiter: Option<Arc<SearchLimiter>>,
+        read_pool_size: usize,
+        embedder: Option<Arc<EmbedderChain>>,
+        embedding_cache: Arc<QueryEmbeddingCache>,
+        synonyms: Option<Arc<SynonymMap>>,
+    ) -> Result<Self, ServiceError> {
+        let loader: SearcherLoader = Arc::new(move |path: String| {
+            let limiter = limiter.clone();
+            let embedder = embedder.clone();
+            let synonyms = synonyms.clone();
+            let embedding_cache = Arc::clone(&embedding_cache);
+            Box::pin(async move {
+                let mut searcher = RealSearcher::new(&path)
+                    .await?
+                    .with_read_pool(read_pool_size)
+                    .await?
+                    .with_embedding_cache(embedding_cache);
+                if let Some(options) = preload {
+                    searcher = searcher.with_preload(options).await?;
+                }
+                if let Some(limiter) = limiter {
+                    searcher = searcher.with_limiter(limiter);
+                }
+                if let Some(embedder) = embedder {
+                    searcher = searcher.with_embedder_chain(embedder);
+                }
+                if let Some(synonyms) = synonyms {
+                    searcher = searcher.with_synonyms(synonyms);
+                }
+                Ok(Arc::new(searcher) as Arc<dyn Searcher>)
+            }) as LoadFuture
+        });
+        Self::open_with(file_path, loader).await
+    }
+
+    /// Load a file with a custom loader and make it reloadable.
+    pub async fn open_with(
+        file_path: impl Into<String>,
+        loader: SearcherLoader,
+    ) -> Result<Self, ServiceError> {
+        let slot = IndexSlot::load(&loader, file_path.into()).await?;
+
+        Ok(Self {
+            loader,
+            active: RwLock::new(slot),
+            staged: Mutex::new(None),
+            previous: Mutex::new(None),
+            generation: AtomicU64::new(1),
+            reload_lock: tokio::sync::Mutex::new(()),
+            failure: Mutex::new(None),
+        })
+    }
+
+    /// The searcher currently serving requests.
+    pub fn current(&self) -> Arc<dyn Searcher> {
+        Arc::clone(&self.active_slot().searcher)
+    }
+
+    /// Monotonic index generation, incremented on every successful reload.
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Acquire)
+    }
+
+    /// Reload the index if the source file changed since the last load.
+    ///
+    /// On failure the current index keeps serving.
+    pub async fn reload_if_changed(&self) -> Result<ReloadOutcome, ServiceError> {
+        let _guard = self.reload_lock.lock().await;
+
+        let active = self.active_slot();
+        let fingerprint = source_fingerprint(&active.path);
+        if fingerprint.is_some() && fingerprint == active.fingerprint {
+            // The source is back to the file that is serving
+            self.track_reload(&Ok(()));
+            return Ok(ReloadOutcome::Unchanged);
+        }
+
+        let result = self.swap_in(active.path).await;
+        self.track_reload(&result);
+        result.map(|()| ReloadOutcome::Reloaded)
+    }
+
+    /// Reload the index unconditionally.
+    ///
+    /// On failure the current index keeps serving.
+    pub async fn reload(&self) -> Result<(), ServiceError> {
+        let _guard = self.reload_lock.lock().await;
+        let result = self.swap_in(self.active_slot().path).await;
+        self.track_reload(&result);
+        result
+    }
+
+    /// Failure of the reloads since the last good load, if they are failing.
+    pub fn reload_failure(&self) -> Option<ReloadFailure> {
+        lock(&self.failure).clone()
+    }
+
+    /// Load `path` alongside the active index without serving it.
+    ///
+    /// Replaces any previously staged index.
+    pub async fn stage(&self, path: impl Into<String>) -> Result<CutoverStatus, ServiceError> {
+        let _guard = self.reload_lock.lock().await;
+
+        let path = path.into();
+        info!(path = %path, "Staging memvid index for cutover");
+        let slot = IndexSlot::load(&self.loader, path).await?;
+        self.check_embedding_drift(&slot).await;
+        info!(
+            path = %slot.path,
+            frame_count = slot.searcher.frame_count(),
+            "Memvid index staged"
+        );
+        *lock(&self.staged) = Some(slot);
+        Ok(self.cutover_status())
+    }
+
+    /// Atomically switch traffic to the staged index.
+    ///
+    /// The outgoing index stays resident for [`rollback`](Self::rollback)
+    /// until [`confirm`](Self::confirm) is called.
+    pub async fn promote(&self) -> Result<CutoverStatus, ServiceError> {
+        let _guard = self.reload_lock.lock().await;
+
+        if lock(&self.previous).is_some() {
+            return Err(ServiceError::FailedPrecondition(
+                "a previous cutover is awaiting confirm or rollback".to_string(),
+            ));
+        }
+        let staged = lock(&self.staged)
+            .take()
+            .ok_or_else(|| ServiceError::FailedPrecondition("no index is staged".to_string()))?;
+
+        let outgoing = self.replace_active(staged);
+        info!(from = %outgoing.path, to = %self.active_slot().path, "Promoted staged index");
+        *lock(&self.previous) = Some(outgoing);
+        Ok(self.cutover_status())
+    }
+
+    /// Switch traffic back to the index that was active before the last promote.
+    ///
+    /// The rolled-back index is kept staged so it can be promoted again.
+    pub async fn rollback(&self) -> Result<CutoverStatus, ServiceError> {
+        let _guard = self.reload_lock.lock().await;
+
+        let previous = lock(&self.previous).take().ok_or_else(|| {
+            ServiceError::FailedPrecondition("no cutover to roll back".to_string())
+        })?;
+
+        let outgoing = self.replace_active(previous);
+        info!(from = %outgoing.path, to = %self.active_slot().path, "Rolled back index cutover");
+        *lock(&self.staged) = Some(outgoing);
+        Ok(self.cutover_status())
+    }
+
+    /// Confirm the last cutover and release the previous index.
+    pub async fn confirm(&self) -> Result<CutoverStatus, ServiceError> {
+        let _guard = self.reload_lock.lock().await;
+
+        let previous = lock(&self.previous)
+            .take()
+            .ok_or_else(|| ServiceError::FailedPrecondition("no cutover to confirm".to_string()))?;
+        info!(released = %previous.path, "Confirmed index cutover");
+        Ok(self.cutover_status())
+    }
+
+    /// Which index files are currently resident.
+    pub fn cutover_status(&self) -> CutoverStatus {
+        CutoverStatus {
+            active: self.active_slot().path,
+            staged: lock(&self.staged).as_ref().map(|s| s.path.clone()),
+            previous: lock(&self.previous).as_ref().map(|s| s.path.clone()),
+            generation: self.generation(),
+        }
+    }
+
+    /// Record the outcome of a reload attempt.
+    fn track_reload(&self, result: &Result<(), ServiceError>) {
+        let mut failure = lock(&self.failure);
+        match result {
+            Ok(()) => {
+                if let Some(previous) = failure.take() {
+                    info!(
+                        attempts = previous.attempts,
+                        "Index reload recovered after failures"
+                    );
+                }
+            }
+            Err(e) => {
+                let (since, attempts) = failure
+                    .as_ref()
+                    .map_or((Utc::now(), 0), |f| (f.since, f.attempts));
+                warn!(
+                    error = %e,
+                    attempts = attempts + 1,
+                    "Index reload failed, serving the last good index"
+                );
+                *failure = Some(ReloadFailure {
+                    reason: e.to_string(),
+                    since,
+                    attempts: attempts + 1,
+                });
+            }
+        }
+        metrics::set_index_degraded(failure.is_some());
+    }
+
+    fn active_slot(&self) -> IndexSlot {
+        self.active
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Make `slot` the serving index and return the one it replaced.
+    fn replace_active(&self, slot: IndexSlot) -> IndexSlot {
+        metrics::set_section_frame_counts(
+            &self.current().section_counts(),
+            &slot.searcher.section_counts(),
+        );
+        let outgoing = std::mem::replace(
+            &mut *self.active.write().unwrap_or_else(|e| e.into_inner()),
+            slot,
+        );
+        self.generation.fetch_add(1, Ordering::AcqRel);
+        outgoing
+    }
+
+    /// Log an error when `incoming` looks embedded with a different model
+    /// than the serving index.
+    async fn check_embedding_drift(&self, incoming: &IndexSlot) {
+        let active = self.active_slot();
+        if !active.searcher.index_features().vector || !incoming.searcher.index_features().vector {
+            return;
+        }
+        let previous = EmbeddingProfile::probe(&*active.searcher).await;
+        let profile = EmbeddingProfile::probe(&*incoming.searcher).await;
+        match profile.drift_from(&previous) {
+            Some(reason) => {
+                metrics::increment_embedding_drift();
+                error!(
+                    serving = %active.path,
+                    incoming = %incoming.path,
+                    reason = %reason,
+                    "Embedding drift: the new index looks embedded with a different model \
+                     than the serving one; semantic relevance will suffer"
+                );
+            }
+            None => info!(
+                path = %incoming.path,
+                probes = profile.probes,
+                self_hits = profile.self_hits,
+                mean_top_score = profile.mean_top_score,
+                "Embedding profile consistent with the serving index"
+            ),
+        }
+    }
+
+    /// Load the source and atomically replace the serving searcher.
+    async fn swap_in(&self, path: String) -> Result<(), ServiceError> {
+        info!(path = %path, "Reloading memvid index");
+        let slot = IndexSlot::load(&self.loader, path.clone()).await?;
+        let frame_count = slot.searcher.frame_count();
+        self.check_embedding_drift(&slot).await;
+
+        self.replace_active(slot);
+
+        info!(
+            path = %path,
+            frame_count,
+            generation = self.generation(),
+            "Memvid index reloaded"
+        );
+        Ok(())
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Read the modification time and size of the source file.
+fn source_fingerprint(path: impl AsRef<Path>) -> Option<SourceFingerprint> {
+    std::fs::metadata(path)
+        .ok()
+        .map(|m| (m.modified().ok(), m.len()))
+}
+
+#[async_trait]
+impl Searcher for ReloadableSearcher {
+    async fn search(&self, request: SearchRequest) -> Result<SearchResponse, ServiceError> {
+        self.current().search(request).await
+    }
+
+    async fn get_state(
+        &self,
+        entity: &str,
+        slot: Option<&str>,
+    ) -> Result<StateResponse, ServiceError> {
+        self.current().get_state(entity, slot).await
+    }
+
+    async fn list_entities(&self) -> Result<Vec<EntitySummary>, ServiceError> {
+        self.current().list_entities().await
+    }
+
+    async fn ask(&self, request: AskRequest) -> Result<AskResponse, ServiceError> {
+        self.current().ask(request).await
+    }
+
+    async fn export_frames(
+        &self,
+        after: Option<u64>,
+        limit: usize,
+    ) -> Result<Vec<FrameMetadata>, ServiceError> {
+        self.current().export_frames(after, limit).await
+    }
+
+    async fn frame_texts(
+        &self,
+        after: Option<u64>,
+        limit: usize,
+    ) -> Result<Vec<FrameText>, ServiceError> {
+        self.current().frame_texts(after, limit).await
+    }
+
+    fn frame_count(&self) -> i32 {
+        self.current().frame_count()
+    }
+
+    fn memvid_file(&self) -> String {
+        self.active_slot().path
+    }
+
+    fn generation(&self) -> u64 {
+        ReloadableSearcher::generation(self)
+    }
+
+    fn index_features(&self) -> IndexFeatures {
+        self.current().index_features()
+    }
+
+    fn section_counts(&self) -> BTreeMap<String, i32> {
+        self.current().section_counts()
+    }
+
+    fn lock_diagnostics(&self) -> LockDiagnostics {
+        self.current().lock_diagnostics()
+    }
+
+    fn acronyms(&self) -> Arc<AcronymMap> {
+        self.current().acronyms()
+    }
+
+    fn spelling(&self) -> Arc<SpellDictionary> {
+        self.current().spelling()
+    }
+
+    fn suggestions(&self) -> Arc<SuggestionIndex> {
+        self.current().suggestions()
+    }
+
+    fn is_ready(&self) -> bool {
+        self.current().is_ready()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memvid::MockSearcher;
+    use std::sync::atomic::AtomicUsize;
+
+    fn counting_loader(loads: Arc<AtomicUsize>) -> SearcherLoader {
+        Arc::new(move |_path: String| {
+            loads.fetch_add(1, Ordering::SeqCst);
+            Box::pin(async { Ok(Arc::new(MockSearcher::new()) as Arc<dyn Searcher>) }) as LoadFuture
+        })
+    }
+
+    fn temp_file(name: &str, contents: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "memvid-reloadable-{}-{}.mv2",
+            name,
+            std::process::id()
+        ));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[tokio::test]
+    async fn test_unchanged_source_is_not_reloaded() {
+        let path = temp_file("unchanged", "v1");
+        let loads = Arc::new(AtomicUsize::new(0));
+        let searcher = ReloadableSearcher::open_with(
+            path.to_string_lossy(),
+            counting_loader(Arc::clone(&loads)),
+        )
+        .await
+        .unwrap();
+
+        let outcome = searcher.reload_if_changed().await.unwrap();
+
+        assert_eq!(outcome, ReloadOutcome::Unchanged);
+        assert_eq!(loads.load(Ordering::SeqCst), 1);
+        assert_eq!(searcher.generation(), 1);
+        std::fs::remove_file(path).ok();
+    }
+
+    #[tokio::test]
+    async fn test_changed_source_is_reloaded() {
+        let path = temp_file("changed", "v1");
+        let loads = Arc::new(AtomicUsize::new(0));
+        let searcher = ReloadableSearcher::open_with(
+            path.to_string_lossy(),
+            counting_loader(Arc::clone(&loads)),
+        )
+        .await
+        .unwrap();
+
+        std::fs::write(&path, "version two").unwrap();
+        let outcome = searcher.reload_if_changed().await.unwrap();
+
+        assert_eq!(outcome, ReloadOutcome::Reloaded);
+        assert_eq!(loads.load(Ordering::SeqCst), 2);
+        assert_eq!(searcher.generation(), 2);
+        assert!(searcher.memvid_file().ends_with(".mv2"));
+        std::fs::remove_file(path).ok();
+    }
+
+    #[tokio::test]
+    async fn test_failed_reload_keeps_serving_until_recovery() {
+        let path = temp_file("failing", "v1");
+        let fail = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let loader: SearcherLoader = {
+            let fail = Arc::clone(&fail);
+            Arc::new(move |path: String| {
+                let fail = fail.load(Ordering::SeqCst);
+                Box::pin(async move {
+                    if fail {
+                        Err(ServiceError::MemvidLoadError(format!("corrupt {}", path)))
+                    } else {
+                        Ok(Arc::new(MockSearcher::new()) as Arc<dyn Searcher>)
+                    }
+                }) as LoadFuture
+            })
+        };
+        let searcher = ReloadableSearcher::open_with(path.to_string_lossy(), loader)
+            .await
+            .unwrap();
+
+        fail.store(true, Ordering::SeqCst);
+        std::fs::write(&path, "corrupt").unwrap();
+        assert!(searcher.reload_if_changed().await.is_err());
+        assert!(searcher.reload().await.is_err());
+
+        let failure = searcher.reload_failure().unwrap();
+        assert_eq!(failure.attempts, 2);
+        assert!(failure.reason.contains("corrupt"));
+        assert!(searcher.is_ready());
+        assert_eq!(searcher.generation(), 1);
+
+        fail.store(false, Ordering::SeqCst);
+        assert_eq!(
+            searcher.reload_if_changed().await.unwrap(),
+            ReloadOutcome::Reloaded
+        );
+        assert_eq!(searcher.reload_failure(), None);
+        assert_eq!(searcher.generation(), 2);
+        std::fs::remove_file(path).ok();
+    }
+
+    #[tokio::test]
+    async fn test_open_missing_file_fails() {
+        let result = ReloadableSearcher::open("/nonexistent/file.mv2").await;
+        assert!(matches!(result, Err(ServiceError::MemvidFileNotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_blue_green_promote_rollback_confirm() {
+        let blue = temp_file("blue", "v1");
+        let green = temp_file("green", "v2");
+        let loads = Arc::new(AtomicUsize::new(0));
+        let searcher =
+            ReloadableSearcher::open_with(blue.to_string_lossy(), counting_loader(loads))
+                .await
+                .unwrap();
+        let blue = blue.to_string_lossy().to_string();
+        let green = green.to_string_lossy().to_string();
+
+        let status = searcher.stage(green.clone()).await.unwrap();
+        assert_eq!(status.active, blue);
+        assert_eq!(status.staged.as_deref(), Some(green.as_str()));
+
+        let status = searcher.promote().await.unwrap();
+        assert_eq!(status.active, green);
+        assert_eq!(status.previous.as_deref(), Some(blue.as_str()));
+        assert_eq!(searcher.memvid_file(), green);
+
+        // A second promote must wait for confirm or rollback
+        assert!(matches!(
+            searcher.promote().await,
+            Err(ServiceError::FailedPrecondition(_))
+        ));
+
+        let status = searcher.rollback().await.unwrap();
+        assert_eq!(status.active, blue);
+        assert_eq!(status.staged.as_deref(), Some(green.as_str()));
+        assert_eq!(status.previous, None);
+
+        searcher.promote().await.unwrap();
+        let status = searcher.confirm().await.unwrap();
+        assert_eq!(status.active, green);
+        assert_eq!(status.previous, None);
+        assert_eq!(status.generation, 4);
+
+        std::fs::remove_file(blue).ok();
+        std::fs::remove_file(green).ok();
+    }
+
+    #[tokio::test]
+    async fn test_promote_without_staged_fails() {
+        let path = temp_file("nostage", "v1");
+        let loads = Arc::new(AtomicUsize::new(0));
+        let searcher =
+            ReloadableSearcher::open_with(path.to_string_lossy(), counting_loader(loads))
+                .await
+                .unwrap();
+
+        assert!(matches!(
+            searcher.promote().await,
+            Err(ServiceError::FailedPrecondition(_))
+        ));
+        assert!(matches!(
+            searcher.rollback().await,
+            Err(ServiceError::FailedPrecondition(_))
+        ));
+        std::fs::remove_file(path).ok();
+    }
+}
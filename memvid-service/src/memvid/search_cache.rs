@@ -0,0 +1,367 @@
+//! In-process LRU cache of Search results.
+//!
+//! Resume traffic is highly repetitive: visitors click the same suggested
+//! questions, so most searches retrieve what an earlier one already did.
+//! [`SearchCachingSearcher`] serves a repeated search, keyed by query,
+//! `top_k`, `snippet_chars`, filters, URI scope and ACL context, from memory for a TTL. When the cache is
+//! full the least recently used entry is evicted. Partial results (a search
+//! that ran out of time budget) are never cached, and a reload of the
+//! wrapped index clears the cache.
+
+use async_trait::async_trait;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::info;
+
+use super::acl::AclContext;
+use super::acronyms::AcronymMap;
+use super::instrumented::LockDiagnostics;
+use super::searcher::{
+    AskEventStream, AskRequest, AskResponse, EntitySummary, FrameMetadata, FrameText,
+    IndexFeatures, SearchRequest, SearchResponse, Searcher, StateResponse,
+};
+use super::spelling::SpellDictionary;
+use super::suggest::SuggestionIndex;
+use crate::error::ServiceError;
+use crate::metrics;
+
+/// Default lifetime of a cached search result.
+pub const DEFAULT_SEARCH_CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// Default maximum number of cached search results.
+pub const DEFAULT_SEARCH_CACHE_CAPACITY: usize = 1024;
+
+/// `cache` label of this cache's metrics.
+const CACHE_NAME: &str = "search";
+
+/// Request fields a search result depends on.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct SearchKey {
+    query: String,
+    top_k: i32,
+    snippet_chars: i32,
+    filters: BTreeMap<String, String>,
+    uri: Option<String>,
+    acl: Option<AclContext>,
+}
+
+impl SearchKey {
+    fn of(request: &SearchRequest) -> Self {
+        Self {
+            query: request.query.trim().to_string(),
+            top_k: request.top_k,
+            snippet_chars: request.snippet_chars,
+            filters: request
+                .filters
+                .iter()
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect(),
+            uri: request.uri.clone(),
+            acl: request.acl.clone(),
+        }
+    }
+}
+
+struct CachedSearch {
+    inserted: Instant,
+    last_used: Instant,
+    response: SearchResponse,
+}
+
+/// TTL- and capacity-bounded LRU map from search request to response.
+pub struct SearchCache {
+    ttl: Duration,
+    capacity: usize,
+    entries: Mutex<HashMap<SearchKey, CachedSearch>>,
+}
+
+impl SearchCache {
+    /// Create a cache holding at most `capacity` results for `ttl` each.
+    pub fn new(ttl: Duration, capacity: usize) -> Self {
+        Self {
+            ttl,
+            capacity,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The live cached response to `request`, marking it recently used.
+    pub fn get(&self, request: &SearchRequest) -> Option<SearchResponse> {
+        let mut entries = self.lock();
+        let entry = entries.get_mut(&SearchKey::of(request))?;
+        if entry.inserted.elapsed() >= self.ttl {
+            return None;
+        }
+        entry.last_used = Instant::now();
+        Some(entry.response.clone())
+    }
+
+    /// Cache `response` to `request`, evicting the least recently used
+    /// entry when full.
+    pub fn insert(&self, request: &SearchRequest, response: SearchResponse) {
+        if self.capacity == 0 {
+            return;
+        }
+        let key = SearchKey::of(request);
+        let mut entries = self.lock();
+        let ttl = self.ttl;
+        let before = entries.len();
+        entries.retain(|_, entry| entry.inserted.elapsed() < ttl);
+        metrics::record_cache_evictions(CACHE_NAME, "expired", before - entries.len());
+
+        if entries.len() >= self.capacity && !entries.contains_key(&key) {
+            let least_recent = entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone());
+            if let Some(least_recent) = least_recent {
+                entries.remove(&least_recent);
+                metrics::record_cache_evictions(CACHE_NAME, "capacity", 1);
+            }
+        }
+        let now = Instant::now();
+        entries.insert(
+            key,
+            CachedSearch {
+                inserted: now,
+                last_used: now,
+                response,
+            },
+        );
+        metrics::set_cache_entries(CACHE_NAME, entries.len());
+    }
+
+    /// Drop every entry.
+    pub fn clear(&self) {
+        let mut entries = self.lock();
+        metrics::record_cache_evictions(CACHE_NAME, "invalidated", entries.len());
+        entries.clear();
+        metrics::set_cache_entries(CACHE_NAME, 0);
+    }
+
+    /// Number of live entries.
+    pub fn len(&self) -> usize {
+        let ttl = self.ttl;
+        self.lock()
+            .values()
+            .filter(|entry| entry.inserted.elapsed() < ttl)
+            .count()
+    }
+
+    /// Whether the cache has no live entries.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<SearchKey, CachedSearch>> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Default for SearchCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_SEARCH_CACHE_TTL, DEFAULT_SEARCH_CACHE_CAPACITY)
+    }
+}
+
+/// Searcher that answers repeated searches from a [`SearchCache`].
+pub struct SearchCachingSearcher {
+    inner: Arc<dyn Searcher>,
+    cache: SearchCache,
+    generation: AtomicU64,
+}
+
+impl SearchCachingSearcher {
+    /// Wrap `inner`, caching its search results in `cache`.
+    pub fn new(inner: Arc<dyn Searcher>, cache: SearchCache) -> Self {
+        let generation = AtomicU64::new(inner.generation());
+        Self {
+            inner,
+            cache,
+            generation,
+        }
+    }
+
+    /// Clear the cache when the wrapped index changed since last seen.
+    fn check_generation(&self) {
+        let current = self.inner.generation();
+        if self.generation.swap(current, Ordering::AcqRel) != current {
+            info!(generation = current, "Index changed, clearing search cache");
+            self.cache.clear();
+        }
+    }
+}
+
+#[async_trait]
+impl Searcher for SearchCachingSearcher {
+    async fn search(&self, request: SearchRequest) -> Result<SearchResponse, ServiceError> {
+        self.check_generation();
+        if let Some(response) = self.cache.get(&request) {
+            metrics::record_search_cache(true);
+            metrics::record_cache_lookup(CACHE_NAME, true);
+            return Ok(response);
+        }
+
+        metrics::record_search_cache(false);
+        metrics::record_cache_lookup(CACHE_NAME, false);
+        let response = self.inner.search(request.clone()).await?;
+        if !response.partial {
+            self.cache.insert(&request, response.clone());
+        }
+        Ok(response)
+    }
+
+    async fn get_state(
+        &self,
+        entity: &str,
+        slot: Option<&str>,
+    ) -> Result<StateResponse, ServiceError> {
+        self.inner.get_state(entity, slot).await
+    }
+
+    async fn list_entities(&self) -> Result<Vec<EntitySummary>, ServiceError> {
+        self.inner.list_entities().await
+    }
+
+    async fn ask(&self, request: AskRequest) -> Result<AskResponse, ServiceError> {
+        self.inner.ask(request).await
+    }
+
+    async fn ask_stream(&self, request: AskRequest) -> Result<AskEventStream, ServiceError> {
+        self.inner.ask_stream(request).await
+    }
+
+    async fn export_frames(
+        &self,
+        after: Option<u64>,
+        limit: usize,
+    ) -> Result<Vec<FrameMetadata>, ServiceError> {
+        self.inner.export_frames(after, limit).await
+    }
+
+    async fn frame_texts(
+        &self,
+        after: Option<u64>,
+        limit: usize,
+    ) -> Result<Vec<FrameText>, ServiceError> {
+        self.inner.frame_texts(after, limit).await
+    }
+
+    fn frame_count(&self) -> i32 {
+        self.inner.frame_count()
+    }
+
+    fn memvid_file(&self) -> String {
+        self.inner.memvid_file()
+    }
+
+    fn generation(&self) -> u64 {
+        self.inner.generation()
+    }
+
+    fn index_features(&self) -> IndexFeatures {
+        self.inner.index_features()
+    }
+
+    fn section_counts(&self) -> BTreeMap<String, i32> {
+        self.inner.section_counts()
+    }
+
+    fn lock_diagnostics(&self) -> LockDiagnostics {
+        self.inner.lock_diagnostics()
+    }
+
+    fn acronyms(&self) -> Arc<AcronymMap> {
+        self.inner.acronyms()
+    }
+
+    fn spelling(&self) -> Arc<SpellDictionary> {
+        self.inner.spelling()
+    }
+
+    fn suggestions(&self) -> Arc<SuggestionIndex> {
+        self.inner.suggestions()
+    }
+
+    fn is_ready(&self) -> bool {
+        self.inner.is_ready()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memvid::MockSearcher;
+
+    fn response(total_hits: i32) -> SearchResponse {
+        SearchResponse {
+            hits: Vec::new(),
+            total_hits,
+            took_ms: 1,
+            partial: false,
+            expanded_query: None,
+        }
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let cache = SearchCache::new(Duration::from_secs(60), 2);
+        let (a, b, c) = (
+            SearchRequest::new("rust", 5, 200),
+            SearchRequest::new("python", 5, 200),
+            SearchRequest::new("kafka", 5, 200),
+        );
+        cache.insert(&a, response(1));
+        cache.insert(&b, response(2));
+        // Using `a` makes `b` the least recently used
+        assert_eq!(cache.get(&a).unwrap().total_hits, 1);
+        cache.insert(&c, response(3));
+
+        assert!(cache.get(&b).is_none());
+        assert_eq!(cache.get(&a).unwrap().total_hits, 1);
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get(&SearchRequest::new("rust", 3, 200)).is_none());
+        assert!(cache.get(&SearchRequest::new(" rust ", 5, 200)).is_some());
+        let mut filtered = SearchRequest::new("rust", 5, 200);
+        filtered
+            .filters
+            .insert("section".to_string(), "skills".to_string());
+        assert!(cache.get(&filtered).is_none());
+        let scoped = SearchRequest {
+            uri: Some("mv2://resume/skills".to_string()),
+            ..SearchRequest::new("rust", 5, 200)
+        };
+        assert!(cache.get(&scoped).is_none());
+    }
+
+    #[test]
+    fn test_expired_and_disabled_entries_miss() {
+        let cache = SearchCache::new(Duration::ZERO, 8);
+        let request = SearchRequest::new("rust", 5, 200);
+        cache.insert(&request, response(1));
+        assert!(cache.get(&request).is_none());
+        assert!(cache.is_empty());
+
+        let disabled = SearchCache::new(Duration::from_secs(60), 0);
+        disabled.insert(&request, response(1));
+        assert!(disabled.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_serves_repeated_searches_from_cache() {
+        let searcher =
+            SearchCachingSearcher::new(Arc::new(MockSearcher::new()), SearchCache::default());
+        let first = searcher
+            .search(SearchRequest::new("Python experience", 5, 200))
+            .await
+            .unwrap();
+        let second = searcher
+            .search(SearchRequest::new("Python experience", 5, 200))
+            .await
+            .unwrap();
+        assert_eq!(first.total_hits, second.total_hits);
+        assert_eq!(searcher.cache.len(), 1);
+    }
+}
@@ -1,12 +1,24 @@
 //! Searcher trait defining the interface for memvid search operations.
 
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::pin::Pin;
+use std::sync::Arc;
+use tokio_stream::Stream;
 
+use super::acl::AclContext;
+use super::acronyms::AcronymMap;
+use super::instrumented::LockDiagnostics;
+use super::spelling::SpellDictionary;
+use super::suggest::SuggestionIndex;
 use crate::error::ServiceError;
 
 /// A single search result from memvid.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SearchResult {
+    /// Identifier of the matched frame in the index, when known
+    pub frame_id: Option<u64>,
     /// Title or heading of the matched section
     pub title: String,
     /// Relevance score (0.0 to 1.0)
@@ -15,6 +27,49 @@ pub struct SearchResult {
     pub snippet: String,
     /// Tags/metadata (e.g., "skills", "experience", "education")
     pub tags: Vec<String>,
+    /// URI of the matched frame, when known
+    pub uri: Option<String>,
+    /// When the matched content was created (Unix seconds), when known
+    pub timestamp: Option<i64>,
+    /// When the matched frame was ingested (Unix seconds), when known
+    pub ingested_at: Option<i64>,
+    /// Version of the source document the frame came from, when recorded
+    pub source_version: Option<String>,
+}
+
+/// Request for search operation.
+#[derive(Debug, Clone)]
+pub struct SearchRequest {
+    /// Natural language search query
+    pub query: String,
+    /// Maximum number of results
+    pub top_k: i32,
+    /// Maximum characters per snippet
+    pub snippet_chars: i32,
+    /// Retrieval time budget in milliseconds (None = unbounded)
+    pub budget_ms: Option<u32>,
+    /// Metadata filters
+    pub filters: std::collections::HashMap<String, String>,
+    /// Restrict the search to the frames of one document (None = all)
+    pub uri: Option<String>,
+    /// Caller identity matched against frame ACLs (None = no context)
+    pub acl: Option<AclContext>,
+}
+
+impl SearchRequest {
+    /// Create a search request with no time budget, filters, URI scope or
+    /// ACL context.
+    pub fn new(query: impl Into<String>, top_k: i32, snippet_chars: i32) -> Self {
+        Self {
+            query: query.into(),
+            top_k,
+            snippet_chars,
+            budget_ms: None,
+            filters: std::collections::HashMap::new(),
+            uri: None,
+            acl: None,
+        }
+    }
 }
 
 /// Search response containing results and metadata.
@@ -26,6 +81,10 @@ pub struct SearchResponse {
     pub total_hits: i32,
     /// Time taken for the search in milliseconds
     pub took_ms: i32,
+    /// Whether the time budget expired before retrieval completed
+    pub partial: bool,
+    /// The query as searched after synonym expansion, when any rule applied
+    pub expanded_query: Option<String>,
 }
 
 /// State response for memory card entity lookup.
@@ -39,6 +98,54 @@ pub struct StateResponse {
     pub slots: std::collections::HashMap<String, String>,
 }
 
+/// A memory-card entity and how many slots it holds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntitySummary {
+    /// The entity name (e.g., "__profile__")
+    pub name: String,
+    /// Number of slots recorded for the entity
+    pub slot_count: i32,
+}
+
+/// Metadata of a single frame, as returned by frame export.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrameMetadata {
+    /// Frame identifier in the index
+    pub frame_id: u64,
+    /// Source URI of the frame
+    pub uri: String,
+    /// Title or heading
+    pub title: String,
+    /// Tags (e.g., "skills", "experience")
+    pub tags: Vec<String>,
+    /// Labels attached at ingest
+    pub labels: Vec<String>,
+    /// Extra metadata recorded at ingest (e.g., section, ingested_at)
+    pub metadata: BTreeMap<String, String>,
+}
+
+/// Text of a single frame, as scanned for duplicate detection.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrameText {
+    /// Frame identifier in the index
+    pub frame_id: u64,
+    /// Title or heading
+    pub title: String,
+    /// Indexed frame text
+    pub text: String,
+}
+
+/// Optional index structures detected in the loaded .mv2 file.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct IndexFeatures {
+    /// Lexical (BM25) index present
+    pub lexical: bool,
+    /// Vector (embedding) index present
+    pub vector: bool,
+    /// Temporal index present
+    pub temporal: bool,
+}
+
 /// Ask mode specifying which search algorithm to use (mirrors memvid_core::AskMode).
 #[derive(Debug, Clone, Copy)]
 pub enum AskMode {
@@ -50,6 +157,59 @@ pub enum AskMode {
     Lex,
 }
 
+/// Tuning of adaptive retrieval (mirrors memvid_core::AdaptiveConfig).
+///
+/// Unset fields keep memvid-core's defaults. Setting any threshold cuts the
+/// ranked candidates where the first of them is crossed.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AdaptiveOptions {
+    /// Drop candidates scoring below this normalized score
+    pub min_score: Option<f32>,
+    /// Drop candidates scoring below this fraction of the best one
+    pub relative_threshold: Option<f32>,
+    /// Stop at a score drop larger than this fraction between neighbours
+    pub max_score_drop: Option<f32>,
+    /// Keep at least this many candidates
+    pub min_results: Option<usize>,
+    /// Consider at most this many candidates
+    pub max_results: Option<usize>,
+}
+
+impl AdaptiveOptions {
+    /// Check that thresholds are fractions and the result bounds ordered.
+    ///
+    /// # Errors
+    /// `InvalidField` (a field of the request's `adaptive_options`) for a
+    /// threshold outside [0, 1] or `min_results` above `max_results`.
+    pub fn validate(&self) -> Result<(), ServiceError> {
+        let thresholds = [
+            ("min_score", self.min_score),
+            ("relative_threshold", self.relative_threshold),
+            ("max_score_drop", self.max_score_drop),
+        ];
+        for (name, threshold) in thresholds {
+            if let Some(threshold) = threshold.filter(|t| !(0.0..=1.0).contains(t)) {
+                return Err(ServiceError::invalid_field(
+                    format!("adaptive_options.{}", name),
+                    format!("adaptive {} must be in [0, 1], got {}", name, threshold),
+                ));
+            }
+        }
+        if let (Some(min), Some(max)) = (self.min_results, self.max_results) {
+            if min > max {
+                return Err(ServiceError::invalid_field(
+                    "adaptive_options.min_results",
+                    format!(
+                        "adaptive min_results ({}) exceeds max_results ({})",
+                        min, max
+                    ),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
 /// Request for ask operation with question-answering.
 #[derive(Debug, Clone)]
 pub struct AskRequest {
@@ -71,18 +231,21 @@ pub struct AskRequest {
     pub mode: AskMode,
     /// Optional URI to scope search to specific document
     pub uri: Option<String>,
-    /// Pagination cursor for retrieving next page
+    /// Pagination cursor for retrieving next page (an offset into the
+    /// ranked candidates; the gRPC layer signs it before handing it out)
     pub cursor: Option<String>,
     /// View data as of specific frame ID (time-travel query)
     pub as_of_frame: Option<i64>,
     /// View data as of specific timestamp (time-travel query)
     pub as_of_ts: Option<i64>,
-    /// Enable adaptive retrieval for better results
-    pub adaptive: Option<bool>,
+    /// Adaptive retrieval and its tuning (None = off)
+    pub adaptive: Option<AdaptiveOptions>,
+    /// Caller identity matched against frame ACLs (None = no context)
+    pub acl: Option<AclContext>,
 }
 
 /// Statistics about the ask operation.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AskStats {
     /// Number of candidates retrieved
     pub candidates_retrieved: i32,
@@ -97,7 +260,7 @@ pub struct AskStats {
 }
 
 /// Response from ask operation.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AskResponse {
     /// Synthesized answer or concatenated context
     pub answer: String,
@@ -105,6 +268,45 @@ pub struct AskResponse {
     pub evidence: Vec<SearchResult>,
     /// Statistics
     pub stats: AskStats,
+    /// Cursor for the next page of evidence, if there is one
+    pub next_cursor: Option<String>,
+}
+
+/// One step of a streamed ask, in the order they are emitted.
+#[derive(Debug, Clone)]
+pub enum AskEvent {
+    /// Evidence retrieved for the question (emitted once, first)
+    Evidence(Vec<SearchResult>),
+    /// Next piece of the answer, to be appended to the previous pieces
+    AnswerDelta(String),
+    /// Statistics for the finished ask (emitted once, last)
+    Done {
+        /// Statistics
+        stats: AskStats,
+        /// Cursor for the next page of evidence, if there is one
+        next_cursor: Option<String>,
+    },
+}
+
+/// Stream of [`AskEvent`]s produced by [`Searcher::ask_stream`].
+pub type AskEventStream = Pin<Box<dyn Stream<Item = Result<AskEvent, ServiceError>> + Send>>;
+
+/// Split an answer into word-sized deltas, each keeping its trailing whitespace.
+pub(super) fn answer_deltas(answer: &str) -> impl Iterator<Item = &str> {
+    answer.split_inclusive(char::is_whitespace)
+}
+
+/// Replay a finished ask as events: evidence, answer deltas, done.
+pub(super) fn replay_ask(response: AskResponse) -> AskEventStream {
+    let mut events = vec![Ok(AskEvent::Evidence(response.evidence))];
+    events.extend(
+        answer_deltas(&response.answer).map(|delta| Ok(AskEvent::AnswerDelta(delta.to_string()))),
+    );
+    events.push(Ok(AskEvent::Done {
+        stats: response.stats,
+        next_cursor: response.next_cursor,
+    }));
+    Box::pin(tokio_stream::iter(events))
 }
 
 /// Trait defining the interface for memvid search operations.
@@ -117,18 +319,14 @@ pub trait Searcher: Send + Sync {
     /// Perform a semantic search over the loaded index.
     ///
     /// # Arguments
-    /// * `query` - Natural language search query
-    /// * `top_k` - Maximum number of results to return
-    /// * `snippet_chars` - Maximum characters per snippet
+    /// * `request` - Search request with query, limits, and optional time budget
     ///
     /// # Returns
-    /// Search results ordered by relevance score (descending)
-    async fn search(
-        &self,
-        query: &str,
-        top_k: i32,
-        snippet_chars: i32,
-    ) -> Result<SearchResponse, ServiceError>;
+    /// Search results ordered by relevance score (descending). If the time
+    /// budget expires, whatever candidates were gathered are returned with
+    /// `partial = true`; memvid-core gathers them all at once, so for the
+    /// real index that response is empty.
+    async fn search(&self, request: SearchRequest) -> Result<SearchResponse, ServiceError>;
 
     /// Get memory card state for an entity (O(1) lookup).
     ///
@@ -147,6 +345,10 @@ pub trait Searcher: Send + Sync {
         slot: Option<&str>,
     ) -> Result<StateResponse, ServiceError>;
 
+    /// List the memory-card entities of the index, sorted by name, with
+    /// their slot counts, so callers can discover what `get_state` serves.
+    async fn list_entities(&self) -> Result<Vec<EntitySummary>, ServiceError>;
+
     /// Perform question-answering with intelligent retrieval.
     ///
     /// Uses memvid's Ask mode with hybrid search, temporal filtering,
@@ -159,11 +361,66 @@ pub trait Searcher: Send + Sync {
     /// Ask response with answer, evidence chunks, and statistics
     async fn ask(&self, request: AskRequest) -> Result<AskResponse, ServiceError>;
 
+    /// Perform question-answering, emitting evidence first and then the
+    /// answer in word-sized deltas.
+    ///
+    /// The default runs [`ask`](Searcher::ask) and replays its response as
+    /// events, so decorators that post-process `ask` (redaction, pipeline
+    /// stages) apply to the stream too. memvid-core returns the synthesized
+    /// answer whole, so the deltas are only as incremental as the backend;
+    /// [`SynthesizingSearcher`](super::SynthesizingSearcher) overrides this
+    /// to stream the model's tokens as they arrive.
+    async fn ask_stream(&self, request: AskRequest) -> Result<AskEventStream, ServiceError> {
+        Ok(replay_ask(self.ask(request).await?))
+    }
+
+    /// Read metadata for up to `limit` frames in ID order, starting after
+    /// frame `after` (or from the first frame when `None`).
+    ///
+    /// An empty result means the export is complete.
+    async fn export_frames(
+        &self,
+        after: Option<u64>,
+        limit: usize,
+    ) -> Result<Vec<FrameMetadata>, ServiceError>;
+
+    /// Read the text of up to `limit` frames in ID order, starting after
+    /// frame `after` (or from the first frame when `None`).
+    ///
+    /// An empty result means the scan is complete.
+    async fn frame_texts(
+        &self,
+        after: Option<u64>,
+        limit: usize,
+    ) -> Result<Vec<FrameText>, ServiceError>;
+
     /// Get the number of frames/chunks in the loaded index.
     fn frame_count(&self) -> i32;
 
     /// Get the path to the loaded memvid file.
-    fn memvid_file(&self) -> &str;
+    fn memvid_file(&self) -> String;
+
+    /// Get the index generation, which changes whenever a different index
+    /// is swapped in. Page cursors are only valid within one generation.
+    fn generation(&self) -> u64;
+
+    /// Get the optional index structures available in the loaded file.
+    fn index_features(&self) -> IndexFeatures;
+
+    /// Get the number of frames carrying each tag (e.g., "experience" => 18).
+    fn section_counts(&self) -> BTreeMap<String, i32>;
+
+    /// Get lock wait/hold and blocking-queue timings for contention diagnostics.
+    fn lock_diagnostics(&self) -> LockDiagnostics;
+
+    /// Get the acronym dictionary learned from the loaded corpus.
+    fn acronyms(&self) -> Arc<AcronymMap>;
+
+    /// Get the spelling dictionary learned from the loaded corpus.
+    fn spelling(&self) -> Arc<SpellDictionary>;
+
+    /// Get the typeahead trie built from the loaded corpus.
+    fn suggestions(&self) -> Arc<SuggestionIndex>;
 
     /// Check if the searcher is ready to handle requests.
     fn is_ready(&self) -> bool;
@@ -0,0 +1,312 @@
+//! Shadow (canary-compare) searcher for validating index rebuilds.
+//!
+//! Wraps the serving searcher and mirrors a sample of Search/Ask traffic to a
+//! candidate searcher built from a different .mv2 file. Clients always receive
+//! the primary result; the candidate result is compared in the background and
+//! the differences are reported via metrics.
+
+use async_trait::async_trait;
+use std::collections::hash_map::RandomState;
+use std::collections::{BTreeMap, HashSet};
+use std::hash::{BuildHasher, Hasher};
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::Semaphore;
+use tracing::debug;
+
+use super::acronyms::AcronymMap;
+use super::instrumented::LockDiagnostics;
+use super::searcher::{
+    AskRequest, AskResponse, EntitySummary, FrameMetadata, FrameText, IndexFeatures, SearchRequest,
+    SearchResponse, SearchResult, Searcher, StateResponse,
+};
+use super::spelling::SpellDictionary;
+use super::suggest::SuggestionIndex;
+use crate::error::ServiceError;
+use crate::metrics;
+
+/// Maximum number of mirrored requests in flight; extra traffic is skipped.
+const MAX_IN_FLIGHT: usize = 16;
+
+/// How a candidate result set differs from the primary one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ShadowDiff {
+    /// Shared hits divided by the larger result count (1.0 when both are empty)
+    pub overlap: f64,
+    /// Whether both result sets agree on the top hit
+    pub top_match: bool,
+    /// Whether both result sets list the same hits in the same order
+    pub identical: bool,
+}
+
+/// Compare two ranked hit lists by title.
+pub fn compare_hits(primary: &[SearchResult], candidate: &[SearchResult]) -> ShadowDiff {
+    let larger = primary.len().max(candidate.len());
+    if larger == 0 {
+        return ShadowDiff {
+            overlap: 1.0,
+            top_match: true,
+            identical: true,
+        };
+    }
+
+    let primary_titles: HashSet<&str> = primary.iter().map(|h| h.title.as_str()).collect();
+    let shared = candidate
+        .iter()
+        .map(|h| h.title.as_str())
+        .collect::<HashSet<_>>()
+        .intersection(&primary_titles)
+        .count();
+
+    ShadowDiff {
+        overlap: shared as f64 / larger as f64,
+        top_match: primary.first().map(|h| &h.title) == candidate.first().map(|h| &h.title),
+        identical: primary.len() == candidate.len()
+            && primary
+                .iter()
+                .zip(candidate)
+                .all(|(a, b)| a.title == b.title),
+    }
+}
+
+/// Searcher that serves from `primary` and mirrors traffic to `candidate`.
+pub struct ShadowSearcher {
+    primary: Arc<dyn Searcher>,
+    candidate: Arc<dyn Searcher>,
+    sample_percent: u8,
+    in_flight: Arc<Semaphore>,
+}
+
+impl ShadowSearcher {
+    /// Mirror `sample_percent` (0-100) of requests to `candidate`.
+    pub fn new(
+        primary: Arc<dyn Searcher>,
+        candidate: Arc<dyn Searcher>,
+        sample_percent: u8,
+    ) -> Self {
+        Self {
+            primary,
+            candidate,
+            sample_percent: sample_percent.min(100),
+            in_flight: Arc::new(Semaphore::new(MAX_IN_FLIGHT)),
+        }
+    }
+
+    /// The candidate searcher receiving mirrored traffic.
+    pub fn candidate(&self) -> &Arc<dyn Searcher> {
+        &self.candidate
+    }
+
+    fn sampled(&self) -> bool {
+        match self.sample_percent {
+            0 => false,
+            100 => true,
+            p => RandomState::new().build_hasher().finish() % 100 < p as u64,
+        }
+    }
+
+    /// Run `f` against the candidate in the background if sampled and capacity allows.
+    fn mirror<F, Fut>(
+        &self,
+        rpc: &'static str,
+        primary_hits: Vec<SearchResult>,
+        primary_ms: f64,
+        f: F,
+    ) where
+        F: FnOnce(Arc<dyn Searcher>) -> Fut + Send + 'static,
+        Fut: std::future::Future<Output = Result<Vec<SearchResult>, ServiceError>> + Send,
+    {
+        if !self.sampled() {
+            return;
+        }
+        let Ok(permit) = Arc::clone(&self.in_flight).try_acquire_owned() else {
+            metrics::record_shadow_outcome(rpc, "skipped");
+            return;
+        };
+
+        let candidate = Arc::clone(&self.candidate);
+        tokio::spawn(async move {
+            let _permit = permit;
+            let start = Instant::now();
+            match f(candidate).await {
+                Ok(candidate_hits) => {
+                    let candidate_ms = start.elapsed().as_secs_f64() * 1000.0;
+                    let diff = compare_hits(&primary_hits, &candidate_hits);
+                    debug!(
+                        rpc,
+                        overlap = diff.overlap,
+                        top_match = diff.top_match,
+                        identical = diff.identical,
+                        "Shadow comparison"
+                    );
+                    metrics::record_shadow_diff(rpc, diff.overlap, candidate_ms - primary_ms);
+                    metrics::record_shadow_outcome(
+                        rpc,
+                        if diff.identical { "match" } else { "diff" },
+                    );
+                }
+                Err(e) => {
+                    debug!(rpc, error = %e, "Shadow request failed");
+                    metrics::record_shadow_outcome(rpc, "error");
+                }
+            }
+        });
+    }
+}
+
+#[async_trait]
+impl Searcher for ShadowSearcher {
+    async fn search(&self, request: SearchRequest) -> Result<SearchResponse, ServiceError> {
+        let start = Instant::now();
+        let response = self.primary.search(request.clone()).await?;
+        let primary_ms = start.elapsed().as_secs_f64() * 1000.0;
+
+        self.mirror(
+            "search",
+            response.hits.clone(),
+            primary_ms,
+            |candidate| async move { candidate.search(request).await.map(|r| r.hits) },
+        );
+        Ok(response)
+    }
+
+    async fn get_state(
+        &self,
+        entity: &str,
+        slot: Option<&str>,
+    ) -> Result<StateResponse, ServiceError> {
+        self.primary.get_state(entity, slot).await
+    }
+
+    async fn list_entities(&self) -> Result<Vec<EntitySummary>, ServiceError> {
+        self.primary.list_entities().await
+    }
+
+    async fn ask(&self, request: AskRequest) -> Result<AskResponse, ServiceError> {
+        let start = Instant::now();
+        let response = self.primary.ask(request.clone()).await?;
+        let primary_ms = start.elapsed().as_secs_f64() * 1000.0;
+
+        self.mirror(
+            "ask",
+            response.evidence.clone(),
+            primary_ms,
+            |candidate| async move { candidate.ask(request).await.map(|r| r.evidence) },
+        );
+        Ok(response)
+    }
+
+    async fn export_frames(
+        &self,
+        after: Option<u64>,
+        limit: usize,
+    ) -> Result<Vec<FrameMetadata>, ServiceError> {
+        self.primary.export_frames(after, limit).await
+    }
+
+    async fn frame_texts(
+        &self,
+        after: Option<u64>,
+        limit: usize,
+    ) -> Result<Vec<FrameText>, ServiceError> {
+        self.primary.frame_texts(after, limit).await
+    }
+
+    fn frame_count(&self) -> i32 {
+        self.primary.frame_count()
+    }
+
+    fn memvid_file(&self) -> String {
+        self.primary.memvid_file()
+    }
+
+    fn generation(&self) -> u64 {
+        self.primary.generation()
+    }
+
+    fn index_features(&self) -> IndexFeatures {
+        self.primary.index_features()
+    }
+
+    fn section_counts(&self) -> BTreeMap<String, i32> {
+        self.primary.section_counts()
+    }
+
+    fn lock_diagnostics(&self) -> LockDiagnostics {
+        self.primary.lock_diagnostics()
+    }
+
+    fn acronyms(&self) -> Arc<AcronymMap> {
+        self.primary.acronyms()
+    }
+
+    fn spelling(&self) -> Arc<SpellDictionary> {
+        self.primary.spelling()
+    }
+
+    fn suggestions(&self) -> Arc<SuggestionIndex> {
+        self.primary.suggestions()
+    }
+
+    fn is_ready(&self) -> bool {
+        self.primary.is_ready()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memvid::MockSearcher;
+
+    fn hit(title: &str) -> SearchResult {
+        SearchResult {
+            title: title.to_string(),
+            score: 0.5,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_compare_identical() {
+        let hits = vec![hit("a"), hit("b")];
+        let diff = compare_hits(&hits, &hits);
+        assert!(diff.identical);
+        assert!(diff.top_match);
+        assert_eq!(diff.overlap, 1.0);
+    }
+
+    #[test]
+    fn test_compare_reordered_and_missing() {
+        let primary = vec![hit("a"), hit("b"), hit("c"), hit("d")];
+        let candidate = vec![hit("b"), hit("a")];
+        let diff = compare_hits(&primary, &candidate);
+        assert!(!diff.identical);
+        assert!(!diff.top_match);
+        assert_eq!(diff.overlap, 0.5);
+    }
+
+    #[test]
+    fn test_compare_empty() {
+        assert!(compare_hits(&[], &[]).identical);
+        assert_eq!(compare_hits(&[hit("a")], &[]).overlap, 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_shadow_returns_primary_result() {
+        let primary: Arc<dyn Searcher> = Arc::new(MockSearcher::new());
+        let candidate: Arc<dyn Searcher> = Arc::new(MockSearcher::new());
+        let shadow = ShadowSearcher::new(Arc::clone(&primary), candidate, 100);
+
+        let expected = primary
+            .search(SearchRequest::new("rust", 3, 200))
+            .await
+            .unwrap();
+        let response = shadow
+            .search(SearchRequest::new("rust", 3, 200))
+            .await
+            .unwrap();
+
+        assert!(compare_hits(&expected.hits, &response.hits).identical);
+        assert_eq!(shadow.memvid_file(), primary.memvid_file());
+    }
+}
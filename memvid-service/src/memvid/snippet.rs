@@ -0,0 +1,89 @@
+//! Snippet truncation shared by the searchers.
+//!
+//! Snippets are cut to at most `snippet_chars` user-perceived characters
+//! (grapheme clusters), so multi-byte text never splits a code point and an
+//! accented letter or emoji never loses its combining marks. The cut moves
+//! back to the last word boundary, and the ellipsis is only appended when
+//! text was actually dropped.
+
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Appended to truncated snippets; counts towards the limit.
+pub const ELLIPSIS: &str = "...";
+
+/// `text` cut to at most `max_chars` graphemes, ellipsis included.
+///
+/// The cut falls on the last whitespace within the limit unless that would
+/// drop more than half of the kept text (e.g. one very long token), in
+/// which case the word is cut at a grapheme boundary. Limits too short for
+/// the ellipsis keep the first `max_chars` graphemes without one.
+pub fn truncate_snippet(text: &str, max_chars: usize) -> String {
+    let graphemes: Vec<(usize, &str)> = text.grapheme_indices(true).collect();
+    if graphemes.len() <= max_chars {
+        return text.to_string();
+    }
+    if max_chars < ELLIPSIS.len() {
+        return text[..graphemes[max_chars].0].to_string();
+    }
+
+    let keep = max_chars.saturating_sub(ELLIPSIS.len());
+    let (cut, next) = graphemes[keep];
+    let mut head = &text[..cut];
+    if !next.chars().all(char::is_whitespace) {
+        if let Some(space) = head.rfind(char::is_whitespace) {
+            if space >= cut / 2 {
+                head = &head[..space];
+            }
+        }
+    }
+    format!("{}{}", head.trim_end(), ELLIPSIS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_short_text_is_unchanged() {
+        assert_eq!(truncate_snippet("Rust and Go", 11), "Rust and Go");
+        assert_eq!(truncate_snippet("", 0), "");
+    }
+
+    #[test]
+    fn test_breaks_at_word_boundary() {
+        let text = "Led platform engineering for industrial systems";
+        let snippet = truncate_snippet(text, 20);
+        assert_eq!(snippet, "Led platform...");
+        assert!(snippet.chars().count() <= 20);
+        // A cut right before whitespace keeps the whole last word
+        assert_eq!(truncate_snippet(text, 15), "Led platform...");
+    }
+
+    #[test]
+    fn test_multibyte_text_never_splits_graphemes() {
+        let text = "Zürich — Straße, café: naïve résumé 🦀🦀🦀";
+        for max_chars in 0..=text.chars().count() {
+            let snippet = truncate_snippet(text, max_chars);
+            assert!(snippet.graphemes(true).count() <= max_chars);
+        }
+
+        // "e" + combining acute accent is one grapheme
+        let combining = "cafe\u{301}cafe\u{301}cafe\u{301}";
+        assert_eq!(truncate_snippet(combining, 8), "cafe\u{301}c...");
+    }
+
+    #[test]
+    fn test_long_token_is_cut_mid_word() {
+        assert_eq!(
+            truncate_snippet("a supercalifragilisticexpialidocious", 20),
+            "a supercalifragil..."
+        );
+    }
+
+    #[test]
+    fn test_limit_below_ellipsis_cuts_without_one() {
+        assert_eq!(truncate_snippet("Zürich", 0), "");
+        assert_eq!(truncate_snippet("Zürich", 2), "Zü");
+        assert_eq!(truncate_snippet("Zürich", 3), "...");
+    }
+}
@@ -0,0 +1,259 @@
+//! Per-operation tracing spans for searcher calls.
+//!
+//! `InstrumentedSearcher` runs every searcher call in its own span with
+//! empty timing fields. The real searcher fills them in from its blocking
+//! tasks through [`BlockingTiming`]: how long the task sat in tokio's
+//! blocking queue (`queue_us`), how long it waited for the index lock
+//! (`lock_wait_us`) and how long the memvid-core call took (`core_us`). A
+//! slow request's trace then shows whether it was waiting or working. With
+//! an ask retried lexical-only, the fields describe the retry.
+
+use async_trait::async_trait;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{field::Empty, instrument, Span};
+
+use super::acronyms::AcronymMap;
+use super::instrumented::{LockDiagnostics, LockStats};
+use super::searcher::{
+    AskRequest, AskResponse, EntitySummary, FrameMetadata, FrameText, IndexFeatures, SearchRequest,
+    SearchResponse, Searcher, StateResponse,
+};
+use super::spelling::SpellDictionary;
+use super::suggest::SuggestionIndex;
+use crate::error::ServiceError;
+
+/// Span field: time between spawning the blocking task and its start.
+pub const QUEUE_FIELD: &str = "queue_us";
+
+/// Span field: time spent waiting for the index lock.
+pub const LOCK_WAIT_FIELD: &str = "lock_wait_us";
+
+/// Span field: duration of the memvid-core call.
+pub const CORE_FIELD: &str = "core_us";
+
+/// Caller's span and spawn time, carried into a blocking task.
+#[derive(Debug)]
+pub struct BlockingTiming {
+    span: Span,
+    spawned: Instant,
+}
+
+impl BlockingTiming {
+    /// Capture the current span; call right before spawning the task.
+    pub fn start() -> Self {
+        Self {
+            span: Span::current(),
+            spawned: Instant::now(),
+        }
+    }
+
+    /// Record the time the task queued, in the span and in `stats`; call
+    /// first thing in the task.
+    pub fn started(&self, stats: &LockStats) {
+        let queued = self.spawned.elapsed();
+        stats.record_blocking_queue(queued);
+        self.span.record(QUEUE_FIELD, micros(queued));
+    }
+
+    /// Run `f`, recording its duration in the span field `field`.
+    pub fn time<T>(&self, field: &'static str, f: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let output = f();
+        self.span.record(field, micros(start.elapsed()));
+        output
+    }
+}
+
+fn micros(d: Duration) -> u64 {
+    d.as_micros().min(u64::MAX as u128) as u64
+}
+
+/// Searcher that traces each call in a span with timing fields.
+pub struct InstrumentedSearcher {
+    inner: Arc<dyn Searcher>,
+}
+
+impl InstrumentedSearcher {
+    /// Wrap `inner`.
+    pub fn new(inner: Arc<dyn Searcher>) -> Self {
+        Self { inner }
+    }
+}
+
+#[async_trait]
+impl Searcher for InstrumentedSearcher {
+    #[instrument(
+        name = "searcher.search",
+        skip_all,
+        fields(queue_us = Empty, lock_wait_us = Empty, core_us = Empty)
+    )]
+    async fn search(&self, request: SearchRequest) -> Result<SearchResponse, ServiceError> {
+        self.inner.search(request).await
+    }
+
+    #[instrument(
+        name = "searcher.get_state",
+        skip_all,
+        fields(entity = entity, queue_us = Empty, lock_wait_us = Empty, core_us = Empty)
+    )]
+    async fn get_state(
+        &self,
+        entity: &str,
+        slot: Option<&str>,
+    ) -> Result<StateResponse, ServiceError> {
+        self.inner.get_state(entity, slot).await
+    }
+
+    async fn list_entities(&self) -> Result<Vec<EntitySummary>, ServiceError> {
+        self.inner.list_entities().await
+    }
+
+    #[instrument(
+        name = "searcher.ask",
+        skip_all,
+        fields(queue_us = Empty, lock_wait_us = Empty, core_us = Empty)
+    )]
+    async fn ask(&self, request: AskRequest) -> Result<AskResponse, ServiceError> {
+        self.inner.ask(request).await
+    }
+
+    #[instrument(
+        name = "searcher.export_frames",
+        skip_all,
+        fields(queue_us = Empty, lock_wait_us = Empty, core_us = Empty)
+    )]
+    async fn export_frames(
+        &self,
+        after: Option<u64>,
+        limit: usize,
+    ) -> Result<Vec<FrameMetadata>, ServiceError> {
+        self.inner.export_frames(after, limit).await
+    }
+
+    #[instrument(
+        name = "searcher.frame_texts",
+        skip_all,
+        fields(queue_us = Empty, lock_wait_us = Empty, core_us = Empty)
+    )]
+    async fn frame_texts(
+        &self,
+        after: Option<u64>,
+        limit: usize,
+    ) -> Result<Vec<FrameText>, ServiceError> {
+        self.inner.frame_texts(after, limit).await
+    }
+
+    fn frame_count(&self) -> i32 {
+        self.inner.frame_count()
+    }
+
+    fn memvid_file(&self) -> String {
+        self.inner.memvid_file()
+    }
+
+    fn generation(&self) -> u64 {
+        self.inner.generation()
+    }
+
+    fn index_features(&self) -> IndexFeatures {
+        self.inner.index_features()
+    }
+
+    fn section_counts(&self) -> BTreeMap<String, i32> {
+        self.inner.section_counts()
+    }
+
+    fn lock_diagnostics(&self) -> LockDiagnostics {
+        self.inner.lock_diagnostics()
+    }
+
+    fn acronyms(&self) -> Arc<AcronymMap> {
+        self.inner.acronyms()
+    }
+
+    fn spelling(&self) -> Arc<SpellDictionary> {
+        self.inner.spelling()
+    }
+
+    fn suggestions(&self) -> Arc<SuggestionIndex> {
+        self.inner.suggestions()
+    }
+
+    fn is_ready(&self) -> bool {
+        self.inner.is_ready()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memvid::MockSearcher;
+    use std::sync::Mutex;
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing_subscriber::layer::{Context, SubscriberExt};
+    use tracing_subscriber::Layer;
+
+    /// Collects the span fields recorded after creation.
+    #[derive(Clone, Default)]
+    struct Recorded(Arc<Mutex<Vec<String>>>);
+
+    impl Visit for Recorded {
+        fn record_debug(&mut self, field: &Field, _value: &dyn std::fmt::Debug) {
+            self.0.lock().unwrap().push(field.name().to_string());
+        }
+    }
+
+    impl<S: tracing::Subscriber> Layer<S> for Recorded {
+        fn on_new_span(&self, attrs: &Attributes<'_>, _id: &Id, _ctx: Context<'_, S>) {
+            let name = attrs.metadata().name().to_string();
+            self.0.lock().unwrap().push(name);
+        }
+
+        fn on_record(&self, _id: &Id, values: &Record<'_>, _ctx: Context<'_, S>) {
+            values.record(&mut self.clone());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_blocking_timings_are_recorded_in_the_call_span() {
+        let recorded = Recorded::default();
+        let _guard =
+            tracing::subscriber::set_default(tracing_subscriber::registry().with(recorded.clone()));
+
+        let searcher = InstrumentedSearcher::new(Arc::new(MockSearcher::new()));
+        searcher
+            .search(SearchRequest::new("rust", 5, 200))
+            .await
+            .unwrap();
+        assert_eq!(*recorded.0.lock().unwrap(), ["searcher.search"]);
+
+        // What the real searcher does from inside such a span
+        let span = tracing::info_span!(
+            "searcher.ask",
+            queue_us = Empty,
+            lock_wait_us = Empty,
+            core_us = Empty
+        );
+        let timing = span.in_scope(BlockingTiming::start);
+        tokio::task::spawn_blocking(move || {
+            timing.started(&LockStats::default());
+            timing.time(LOCK_WAIT_FIELD, || ());
+            timing.time(CORE_FIELD, || ());
+        })
+        .await
+        .unwrap();
+        assert_eq!(
+            *recorded.0.lock().unwrap(),
+            [
+                "searcher.search",
+                "searcher.ask",
+                QUEUE_FIELD,
+                LOCK_WAIT_FIELD,
+                CORE_FIELD
+            ]
+        );
+    }
+}
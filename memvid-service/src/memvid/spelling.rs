@@ -0,0 +1,254 @@
+//! Spelling correction against the loaded corpus.
+//!
+//! [`SpellDictionary`] holds the words of the index with their frequencies,
+//! collected when it is loaded, and a SymSpell-style deletion index: every
+//! variant of a word with up to two characters deleted, mapped back to the
+//! word. A misspelled word shares a deletion variant with its correction, so
+//! candidates are found by lookup rather than by comparing against every
+//! word, then ranked by edit distance (transpositions count once) and
+//! frequency.
+//!
+//! Words shorter than four characters and words containing digits are left
+//! alone; words shorter than six characters are corrected by one edit at
+//! most.
+
+use std::collections::{HashMap, HashSet};
+
+/// Largest edit distance of a correction.
+pub const MAX_EDIT_DISTANCE: usize = 2;
+
+/// Shortest word corrected, in characters.
+const MIN_CORRECTED_CHARS: usize = 4;
+
+/// Shortest word corrected by two edits, in characters.
+const MIN_TWO_EDIT_CHARS: usize = 6;
+
+/// Longest word learned, in characters.
+const MAX_WORD_CHARS: usize = 32;
+
+/// Corpus words and their deletion variants.
+#[derive(Debug, Clone, Default)]
+pub struct SpellDictionary {
+    /// Lowercased word => occurrences in the corpus
+    frequencies: HashMap<String, u32>,
+    /// Deletion variant => words it was derived from
+    deletes: HashMap<String, Vec<String>>,
+}
+
+impl SpellDictionary {
+    /// Learn the words of corpus texts.
+    pub fn from_texts<'a>(texts: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut frequencies: HashMap<String, u32> = HashMap::new();
+        for text in texts {
+            for word in words(text) {
+                let count = word.chars().count();
+                if (2..=MAX_WORD_CHARS).contains(&count) && word.chars().all(char::is_alphabetic) {
+                    *frequencies.entry(word.to_lowercase()).or_default() += 1;
+                }
+            }
+        }
+
+        let mut deletes: HashMap<String, Vec<String>> = HashMap::new();
+        for word in frequencies.keys() {
+            for variant in deletions(word, MAX_EDIT_DISTANCE) {
+                deletes.entry(variant).or_default().push(word.clone());
+            }
+        }
+        Self {
+            frequencies,
+            deletes,
+        }
+    }
+
+    /// Number of distinct words learned.
+    pub fn len(&self) -> usize {
+        self.frequencies.len()
+    }
+
+    /// Whether no words were learned.
+    pub fn is_empty(&self) -> bool {
+        self.frequencies.is_empty()
+    }
+
+    /// The closest known word to an unknown `word`, most frequent on ties
+    /// (None when it is known, too short, or nothing is close enough).
+    pub fn correct_word(&self, word: &str) -> Option<&str> {
+        let word = word.to_lowercase();
+        let count = word.chars().count();
+        if count < MIN_CORRECTED_CHARS
+            || !word.chars().all(char::is_alphabetic)
+            || self.frequencies.contains_key(&word)
+        {
+            return None;
+        }
+        let max_distance = if count < MIN_TWO_EDIT_CHARS {
+            1
+        } else {
+            MAX_EDIT_DISTANCE
+        };
+
+        deletions(&word, max_distance)
+            .iter()
+            .filter_map(|variant| self.deletes.get(variant))
+            .flatten()
+            .filter_map(|candidate| {
+                let distance = edit_distance(&word, candidate);
+                (distance <= max_distance).then_some((distance, candidate))
+            })
+            .min_by(|(a_distance, a), (b_distance, b)| {
+                a_distance
+                    .cmp(b_distance)
+                    .then_with(|| self.frequencies[*b].cmp(&self.frequencies[*a]))
+                    .then_with(|| a.cmp(b))
+            })
+            .map(|(_, candidate)| candidate.as_str())
+    }
+
+    /// `query` with its unknown words corrected, keeping everything else as
+    /// written, or None when no word needed correcting.
+    pub fn correct_query(&self, query: &str) -> Option<String> {
+        let mut corrected = String::with_capacity(query.len());
+        let mut changed = false;
+        let mut rest = query;
+        while let Some(start) = rest.find(char::is_alphanumeric) {
+            corrected.push_str(&rest[..start]);
+            rest = &rest[start..];
+            let end = rest
+                .find(|c: char| !c.is_alphanumeric())
+                .unwrap_or(rest.len());
+            let word = &rest[..end];
+            match self.correct_word(word) {
+                Some(correction) => {
+                    corrected.push_str(&match_case(word, correction));
+                    changed = true;
+                }
+                None => corrected.push_str(word),
+            }
+            rest = &rest[end..];
+        }
+        corrected.push_str(rest);
+        changed.then_some(corrected)
+    }
+}
+
+/// Alphanumeric runs of `text`.
+fn words(text: &str) -> impl Iterator<Item = &str> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+}
+
+/// `word` and its variants with up to `distance` characters deleted.
+fn deletions(word: &str, distance: usize) -> HashSet<String> {
+    let mut variants = HashSet::from([word.to_string()]);
+    let mut frontier = vec![word.to_string()];
+    for _ in 0..distance {
+        let mut next = Vec::new();
+        for variant in &frontier {
+            let chars: Vec<char> = variant.chars().collect();
+            for skip in 0..chars.len() {
+                let deleted: String = chars
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, _)| *i != skip)
+                    .map(|(_, c)| c)
+                    .collect();
+                if variants.insert(deleted.clone()) {
+                    next.push(deleted);
+                }
+            }
+        }
+        frontier = next;
+    }
+    variants
+}
+
+/// Edit distance counting insertions, deletions, substitutions and
+/// transpositions of adjacent characters (optimal string alignment).
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut rows = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    rows[0] = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut distance = (rows[i - 1][j] + 1)
+                .min(rows[i][j - 1] + 1)
+                .min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = distance;
+        }
+    }
+    rows[a.len()][b.len()]
+}
+
+/// `correction` capitalized like `word`: all caps, initial capital, or as is.
+fn match_case(word: &str, correction: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) if first.is_uppercase() => {
+            if word.chars().count() > 1 && chars.all(char::is_uppercase) {
+                correction.to_uppercase()
+            } else {
+                let mut corrected = correction.chars();
+                corrected
+                    .next()
+                    .map(|c| c.to_uppercase().chain(corrected).collect())
+                    .unwrap_or_default()
+            }
+        }
+        _ => correction.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dictionary() -> SpellDictionary {
+        SpellDictionary::from_texts([
+            "Kubernetes platform engineering at Siemens",
+            "Built Kubernetes operators and Python services",
+            "Python data pipelines; machine learning",
+        ])
+    }
+
+    #[test]
+    fn test_correct_word() {
+        let dictionary = dictionary();
+        assert_eq!(dictionary.correct_word("kubernets"), Some("kubernetes"));
+        assert_eq!(dictionary.correct_word("Pyhton"), Some("python"));
+        assert_eq!(dictionary.correct_word("siemems"), Some("siemens"));
+        // Known, short, numeric and distant words are left alone
+        assert_eq!(dictionary.correct_word("python"), None);
+        assert_eq!(dictionary.correct_word("pyt"), None);
+        assert_eq!(dictionary.correct_word("k8s"), None);
+        assert_eq!(dictionary.correct_word("javascript"), None);
+        // Words under six characters take one edit at most
+        assert_eq!(dictionary.correct_word("bilt"), Some("built"));
+        assert_eq!(dictionary.correct_word("blt"), None);
+    }
+
+    #[test]
+    fn test_correct_query_keeps_case_and_punctuation() {
+        let dictionary = dictionary();
+        assert_eq!(
+            dictionary.correct_query("Kubernets and PYTHNO, at Siemens?"),
+            Some("Kubernetes and PYTHON, at Siemens?".to_string())
+        );
+        assert_eq!(dictionary.correct_query("python at siemens"), None);
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("python", "pyhton"), 1);
+        assert_eq!(edit_distance("kubernetes", "kubernets"), 1);
+        assert_eq!(edit_distance("abc", ""), 3);
+        assert_eq!(edit_distance("", ""), 0);
+    }
+}
@@ -0,0 +1,345 @@
+//! Query suggestions for typeahead.
+//!
+//! [`SuggestionIndex`] is a character trie over the frame titles, tags and
+//! words of the loaded index, built when it is loaded. Each entry records the
+//! frames it came from, so suggestions are ranked by how many frames the
+//! caller may see carry them, and entries only found in restricted frames
+//! are never suggested.
+//!
+//! A prefix is completed as a whole against titles, tags and words
+//! ("senior pl" => "Senior Platform Engineer"). After those, its last word
+//! is completed against words, keeping the words before it ("rust ku" =>
+//! "rust kubernetes").
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use super::anonymize::redact;
+use super::visibility::VISIBILITY_TAG_PREFIX;
+
+/// Suggestions returned when the request leaves the limit unset.
+pub const DEFAULT_SUGGESTIONS: usize = 10;
+
+/// Largest number of suggestions a request may ask for.
+pub const MAX_SUGGESTIONS: usize = 50;
+
+/// Shortest word suggested, in characters.
+const MIN_WORD_CHARS: usize = 3;
+
+/// Longest word suggested, in characters.
+const MAX_WORD_CHARS: usize = 32;
+
+/// Words too common to be worth suggesting.
+const STOP_WORDS: &[&str] = &[
+    "and", "are", "for", "from", "had", "has", "have", "into", "its", "our", "that", "the",
+    "their", "this", "was", "were", "with",
+];
+
+/// Where a suggestion comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SuggestionKind {
+    /// A frame title
+    Title,
+    /// A frame tag
+    Tag,
+    /// A word of a frame's title or text
+    Term,
+}
+
+/// A completion of a query prefix.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Suggestion {
+    /// The completed query
+    pub text: String,
+    /// Where the completion comes from
+    pub kind: SuggestionKind,
+    /// Number of visible frames carrying it
+    pub frames: usize,
+}
+
+/// A title, tag or word and the frames carrying it.
+#[derive(Debug, Clone)]
+struct Entry {
+    text: String,
+    kind: SuggestionKind,
+    frames: Vec<u64>,
+}
+
+/// Trie node: children by character and the entries ending here.
+#[derive(Debug, Clone, Default)]
+struct Node {
+    children: BTreeMap<char, usize>,
+    entries: Vec<usize>,
+}
+
+/// Character trie over the titles, tags and words of an index.
+#[derive(Debug, Clone)]
+pub struct SuggestionIndex {
+    /// Trie nodes; the root is the first
+    nodes: Vec<Node>,
+    entries: Vec<Entry>,
+    /// (kind, lowercased text) => entry
+    by_key: HashMap<(SuggestionKind, String), usize>,
+    /// Visibility tags of the frames carrying any
+    frame_visibility: HashMap<u64, Vec<String>>,
+}
+
+impl Default for SuggestionIndex {
+    fn default() -> Self {
+        Self {
+            nodes: vec![Node::default()],
+            entries: Vec::new(),
+            by_key: HashMap::new(),
+            frame_visibility: HashMap::new(),
+        }
+    }
+}
+
+impl SuggestionIndex {
+    /// Add a frame's title, tags and the words of its title and `text`.
+    pub fn add_frame(&mut self, frame_id: u64, title: &str, text: &str, tags: &[&str]) {
+        let title = title.trim();
+        if !title.is_empty() {
+            self.insert(SuggestionKind::Title, title, frame_id);
+        }
+        for tag in tags {
+            if tag.starts_with(VISIBILITY_TAG_PREFIX) {
+                self.frame_visibility
+                    .entry(frame_id)
+                    .or_default()
+                    .push(tag.to_string());
+            } else if !tag.trim().is_empty() {
+                self.insert(SuggestionKind::Tag, tag.trim(), frame_id);
+            }
+        }
+        // Contact details are never suggested, anonymized or not
+        let text: Vec<&str> = text
+            .split_whitespace()
+            .filter(|token| redact(token) == *token)
+            .collect();
+        let text = text.join(" ");
+        for word in [title, text.as_str()].into_iter().flat_map(words) {
+            let count = word.chars().count();
+            let lower = word.to_lowercase();
+            if (MIN_WORD_CHARS..=MAX_WORD_CHARS).contains(&count)
+                && word.chars().any(char::is_alphabetic)
+                && !STOP_WORDS.contains(&lower.as_str())
+            {
+                let entry = self.insert(SuggestionKind::Term, word, frame_id);
+                // Words are shown lowercased once they appear that way
+                if word == lower {
+                    self.entries[entry].text = lower;
+                }
+            }
+        }
+    }
+
+    /// Number of distinct titles, tags and words.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether nothing was added.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Up to `limit` completions of `prefix`, most visible frames first,
+    /// counting only the frames `visible` accepts given their visibility
+    /// tags.
+    pub fn complete(
+        &self,
+        prefix: &str,
+        limit: usize,
+        visible: impl Fn(u64, &[String]) -> bool,
+    ) -> Vec<Suggestion> {
+        let prefix = prefix.trim_start().to_lowercase();
+        if prefix.trim().is_empty() {
+            return Vec::new();
+        }
+        let visible_frames = |entry: &Entry| {
+            entry
+                .frames
+                .iter()
+                .filter(|&&frame_id| {
+                    let tags = self.frame_visibility.get(&frame_id);
+                    visible(frame_id, tags.map_or(&[], Vec::as_slice))
+                })
+                .count()
+        };
+
+        let mut suggestions = ranked(
+            self.entries_under(&prefix)
+                .map(|entry| Suggestion {
+                    text: entry.text.clone(),
+                    kind: entry.kind,
+                    frames: visible_frames(entry),
+                })
+                .collect(),
+        );
+
+        // Complete the last word, keeping the words before it
+        if let Some((head, last)) = prefix.rsplit_once(char::is_whitespace) {
+            if !last.is_empty() {
+                let head = head.trim_end();
+                suggestions.extend(ranked(
+                    self.entries_under(last)
+                        .filter(|entry| entry.kind == SuggestionKind::Term)
+                        .map(|entry| Suggestion {
+                            text: format!("{} {}", head, entry.text),
+                            kind: SuggestionKind::Term,
+                            frames: visible_frames(entry),
+                        })
+                        .collect(),
+                ));
+            }
+        }
+
+        let mut seen = HashSet::new();
+        suggestions.retain(|suggestion| seen.insert(suggestion.text.to_lowercase()));
+        suggestions.truncate(limit);
+        suggestions
+    }
+
+    /// Add `frame_id` to the entry for `text`, creating it as needed.
+    fn insert(&mut self, kind: SuggestionKind, text: &str, frame_id: u64) -> usize {
+        let key = text.to_lowercase();
+        let entry = match self.by_key.get(&(kind, key.clone())) {
+            Some(&entry) => entry,
+            None => {
+                let entry = self.entries.len();
+                self.entries.push(Entry {
+                    text: text.to_string(),
+                    kind,
+                    frames: Vec::new(),
+                });
+                let mut node = 0;
+                for c in key.chars() {
+                    node = match self.nodes[node].children.get(&c) {
+                        Some(&child) => child,
+                        None => {
+                            self.nodes.push(Node::default());
+                            let child = self.nodes.len() - 1;
+                            self.nodes[node].children.insert(c, child);
+                            child
+                        }
+                    };
+                }
+                self.nodes[node].entries.push(entry);
+                self.by_key.insert((kind, key), entry);
+                entry
+            }
+        };
+        let frames = &mut self.entries[entry].frames;
+        if !frames.contains(&frame_id) {
+            frames.push(frame_id);
+        }
+        entry
+    }
+
+    /// Entries whose lowercased text starts with `prefix` (lowercased).
+    fn entries_under<'a>(&'a self, prefix: &str) -> impl Iterator<Item = &'a Entry> + 'a {
+        let mut node = Some(0);
+        for c in prefix.chars() {
+            node = node.and_then(|node| self.nodes[node].children.get(&c).copied());
+        }
+        let mut pending: Vec<usize> = node.into_iter().collect();
+        std::iter::from_fn(move || {
+            let node = &self.nodes[pending.pop()?];
+            pending.extend(node.children.values());
+            Some(node.entries.iter().map(|&entry| &self.entries[entry]))
+        })
+        .flatten()
+    }
+}
+
+/// `suggestions` with visible frames, most frames first; ties go to titles,
+/// then tags, then shorter text.
+fn ranked(mut suggestions: Vec<Suggestion>) -> Vec<Suggestion> {
+    suggestions.retain(|suggestion| suggestion.frames > 0);
+    suggestions.sort_by(|a, b| {
+        b.frames
+            .cmp(&a.frames)
+            .then_with(|| a.kind.cmp(&b.kind))
+            .then_with(|| a.text.len().cmp(&b.text.len()))
+            .then_with(|| a.text.cmp(&b.text))
+    });
+    suggestions
+}
+
+/// Alphanumeric runs of `text`.
+fn words(text: &str) -> impl Iterator<Item = &str> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index() -> SuggestionIndex {
+        let mut index = SuggestionIndex::default();
+        index.add_frame(
+            1,
+            "Senior Platform Engineer",
+            "Built Kubernetes operators in Rust and Python. jane@kubemail.dev",
+            &["experience", "platform"],
+        );
+        index.add_frame(
+            2,
+            "Staff Engineer",
+            "Ran kubernetes clusters and the Kafka platform.",
+            &["experience"],
+        );
+        index.add_frame(
+            3,
+            "Confidential Project",
+            "Kubeflow pipelines for a stealth startup.",
+            &["projects", "visibility:hidden"],
+        );
+        index
+    }
+
+    fn texts(suggestions: &[Suggestion]) -> Vec<&str> {
+        suggestions.iter().map(|s| s.text.as_str()).collect()
+    }
+
+    #[test]
+    fn test_completes_titles_tags_and_words() {
+        let index = index();
+        let all = |_: u64, _: &[String]| true;
+
+        let suggestions = index.complete("Kub", 10, all);
+        // Seen lowercased once, so shown lowercased; most frames first
+        assert_eq!(texts(&suggestions), vec!["kubernetes", "Kubeflow"]);
+        assert_eq!(suggestions[0].frames, 2);
+
+        let suggestions = index.complete("senior pl", 10, all);
+        assert_eq!(suggestions[0].text, "Senior Platform Engineer");
+        assert_eq!(suggestions[0].kind, SuggestionKind::Title);
+        assert_eq!(texts(&suggestions)[1..], ["senior platform"]);
+
+        let suggestions = index.complete("exp", 10, all);
+        assert_eq!(suggestions[0].text, "experience");
+        assert_eq!(suggestions[0].kind, SuggestionKind::Tag);
+
+        // Stop words, short words and blank prefixes are not completed
+        assert!(index.complete("th", 10, all).is_empty());
+        assert!(index.complete("jane", 10, all).is_empty());
+        assert!(index.complete("  ", 10, all).is_empty());
+        assert_eq!(index.complete("e", 1, all).len(), 1);
+    }
+
+    #[test]
+    fn test_restricted_frames_are_not_suggested() {
+        let index = index();
+        let public = |_: u64, tags: &[String]| tags.is_empty();
+
+        assert_eq!(
+            texts(&index.complete("kub", 10, public)),
+            vec!["kubernetes"]
+        );
+        assert!(index.complete("confidential", 10, public).is_empty());
+        assert!(index.complete("visibility", 10, public).is_empty());
+        assert!(index.complete("proj", 10, |id, _| id != 3).is_empty());
+    }
+}
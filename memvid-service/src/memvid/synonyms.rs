@@ -0,0 +1,163 @@
+//! Synonym expansion configured by the operator.
+//!
+//! A synonyms file maps shorthand a recruiter may type to the wording the
+//! resume uses, one rule per line:
+//!
+//! ```text
+//! # comments and blank lines are skipped
+//! k8s → kubernetes
+//! ml -> machine learning
+//! ci/cd => continuous integration, continuous delivery
+//! ```
+//!
+//! A query mentioning a term (as whole words, ignoring case) gets the
+//! term's expansions appended, unless it already mentions them. Rules are
+//! one-directional; write both directions to make two terms equivalent.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use super::acronyms::contains_phrase;
+use crate::error::ServiceError;
+
+/// Separators between a term and its expansions.
+const ARROWS: [&str; 3] = ["→", "->", "=>"];
+
+/// Operator-configured term => expansions rules.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SynonymMap {
+    /// Lowercased term => expansions, in file order
+    rules: BTreeMap<String, Vec<String>>,
+}
+
+impl SynonymMap {
+    /// Parse synonym rules, one `term → expansion[, expansion...]` per line.
+    ///
+    /// Rules for the same term are merged.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut map = Self::default();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (term, expansions) = ARROWS
+                .iter()
+                .find_map(|arrow| line.split_once(arrow))
+                .ok_or_else(|| format!("line {}: expected `term → expansion`", number + 1))?;
+            let term = term.trim().to_lowercase();
+            let expansions: Vec<String> = expansions
+                .split(',')
+                .map(str::trim)
+                .filter(|expansion| !expansion.is_empty())
+                .map(String::from)
+                .collect();
+            if term.is_empty() || expansions.is_empty() {
+                return Err(format!(
+                    "line {}: both a term and an expansion are required",
+                    number + 1
+                ));
+            }
+            let known = map.rules.entry(term).or_default();
+            for expansion in expansions {
+                if !known.contains(&expansion) {
+                    known.push(expansion);
+                }
+            }
+        }
+        Ok(map)
+    }
+
+    /// Read and parse a synonyms file.
+    pub fn load(path: &Path) -> Result<Self, ServiceError> {
+        let text = std::fs::read_to_string(path).map_err(|e| {
+            ServiceError::Internal(format!(
+                "Failed to read synonyms file {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        Self::parse(&text).map_err(|e| {
+            ServiceError::InvalidRequest(format!("Invalid synonyms file {}: {}", path.display(), e))
+        })
+    }
+
+    /// Number of terms with rules.
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    /// Whether no rules are configured.
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// `query` with the expansions of the terms it mentions appended, or
+    /// None when no rule applies.
+    pub fn expand_query(&self, query: &str) -> Option<String> {
+        let lower = query.to_lowercase();
+        let mut expanded = query.to_string();
+        for (term, expansions) in &self.rules {
+            if !contains_phrase(&lower, term) {
+                continue;
+            }
+            for expansion in expansions {
+                if !contains_phrase(&expanded.to_lowercase(), &expansion.to_lowercase()) {
+                    expanded.push(' ');
+                    expanded.push_str(expansion);
+                }
+            }
+        }
+        (expanded != query).then_some(expanded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RULES: &str = "\
+# shorthand
+k8s → kubernetes
+ml -> machine learning
+CI/CD => continuous integration, continuous delivery
+
+k8s → container orchestration
+";
+
+    #[test]
+    fn test_parse_rules() {
+        let map = SynonymMap::parse(RULES).unwrap();
+        assert_eq!(map.len(), 3);
+        assert_eq!(
+            map.rules["k8s"],
+            vec!["kubernetes", "container orchestration"]
+        );
+        assert_eq!(
+            map.rules["ci/cd"],
+            vec!["continuous integration", "continuous delivery"]
+        );
+
+        assert!(SynonymMap::parse("k8s kubernetes").is_err());
+        assert!(SynonymMap::parse("→ kubernetes").is_err());
+        assert!(SynonymMap::parse("k8s → , ").is_err());
+        assert!(SynonymMap::parse("# only a comment\n").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_expand_query() {
+        let map = SynonymMap::parse(RULES).unwrap();
+        assert_eq!(
+            map.expand_query("K8s and ML experience").as_deref(),
+            Some("K8s and ML experience kubernetes container orchestration machine learning")
+        );
+        // Expansions already present are not repeated
+        assert_eq!(map.expand_query("ML and machine learning").as_deref(), None);
+        // Terms match whole words only
+        assert_eq!(map.expand_query("html and xml"), None);
+        assert_eq!(
+            map.expand_query("ci/cd pipelines").as_deref(),
+            Some("ci/cd pipelines continuous integration continuous delivery")
+        );
+    }
+}
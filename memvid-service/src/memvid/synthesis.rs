@@ -0,0 +1,371 @@
+//! LLM answer synthesis over retrieved evidence.
+//!
+//! [`SynthesizingSearcher`] answers Ask requests with `use_llm` set through
+//! an [`LlmClient`]: the inner searcher retrieves context only, and the
+//! model writes the answer from the returned evidence. When the model fails
+//! or times out, the concatenated context is returned instead with
+//! `used_fallback` set, so an LLM outage never fails an Ask.
+//!
+//! AskStream forwards the model's tokens as they arrive. The fallback still
+//! applies while nothing has been streamed; once part of the answer has
+//! been sent, a failing model fails the stream.
+
+use async_trait::async_trait;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tracing::warn;
+
+use super::acronyms::AcronymMap;
+use super::instrumented::LockDiagnostics;
+use super::searcher::{
+    answer_deltas, replay_ask, AskEvent, AskEventStream, AskRequest, AskResponse, EntitySummary,
+    FrameMetadata, FrameText, IndexFeatures, SearchRequest, SearchResponse, Searcher,
+    StateResponse,
+};
+use super::spelling::SpellDictionary;
+use super::suggest::SuggestionIndex;
+use crate::error::ServiceError;
+use crate::llm::LlmClient;
+
+/// Searcher whose Ask answers are written by an LLM.
+pub struct SynthesizingSearcher {
+    inner: Arc<dyn Searcher>,
+    client: Arc<LlmClient>,
+}
+
+impl SynthesizingSearcher {
+    /// Wrap `inner`, synthesizing answers with `client`.
+    pub fn new(inner: Arc<dyn Searcher>, client: Arc<LlmClient>) -> Self {
+        Self { inner, client }
+    }
+}
+
+fn warn_fallback(model: &str, error: &str) {
+    warn!(
+        error = %error,
+        model = model,
+        "LLM synthesis failed, answering with the retrieved context"
+    );
+}
+
+#[async_trait]
+impl Searcher for SynthesizingSearcher {
+    async fn search(&self, request: SearchRequest) -> Result<SearchResponse, ServiceError> {
+        self.inner.search(request).await
+    }
+
+    async fn get_state(
+        &self,
+        entity: &str,
+        slot: Option<&str>,
+    ) -> Result<StateResponse, ServiceError> {
+        self.inner.get_state(entity, slot).await
+    }
+
+    async fn list_entities(&self) -> Result<Vec<EntitySummary>, ServiceError> {
+        self.inner.list_entities().await
+    }
+
+    async fn ask(&self, mut request: AskRequest) -> Result<AskResponse, ServiceError> {
+        if !request.use_llm {
+            return self.inner.ask(request).await;
+        }
+
+        // Retrieve context only; the answer is written here
+        let question = request.question.clone();
+        request.use_llm = false;
+        let mut response = self.inner.ask(request).await?;
+        if response.evidence.is_empty() {
+            return Ok(response);
+        }
+
+        match self.client.synthesize(&question, &response.evidence).await {
+            Ok(answer) => response.answer = answer,
+            Err(e) => {
+                warn_fallback(self.client.model(), &e);
+                response.stats.used_fallback = true;
+            }
+        }
+        Ok(response)
+    }
+
+    async fn ask_stream(&self, mut request: AskRequest) -> Result<AskEventStream, ServiceError> {
+        if !request.use_llm {
+            return self.inner.ask_stream(request).await;
+        }
+
+        let question = request.question.clone();
+        request.use_llm = false;
+        let mut response = self.inner.ask(request).await?;
+        if response.evidence.is_empty() {
+            return Ok(replay_ask(response));
+        }
+
+        let mut llm = match self
+            .client
+            .synthesize_stream(&question, &response.evidence)
+            .await
+        {
+            Ok(llm) => llm,
+            Err(e) => {
+                warn_fallback(self.client.model(), &e);
+                response.stats.used_fallback = true;
+                return Ok(replay_ask(response));
+            }
+        };
+
+        let (tx, rx) = mpsc::channel(16);
+        let model = self.client.model().to_string();
+        tokio::spawn(async move {
+            let AskResponse {
+                answer: context,
+                evidence,
+                mut stats,
+                next_cursor,
+            } = response;
+            if tx.send(Ok(AskEvent::Evidence(evidence))).await.is_err() {
+                return;
+            }
+
+            let mut streamed = false;
+            while let Some(delta) = llm.next().await {
+                let event = match delta {
+                    Ok(delta) => {
+                        streamed = true;
+                        Ok(AskEvent::AnswerDelta(delta))
+                    }
+                    Err(e) if !streamed => {
+                        warn_fallback(&model, &e);
+                        break;
+                    }
+                    Err(e) => Err(ServiceError::Internal(format!("LLM stream failed: {}", e))),
+                };
+                let failed = event.is_err();
+                if tx.send(event).await.is_err() || failed {
+                    return;
+                }
+            }
+
+            if !streamed {
+                stats.used_fallback = true;
+                for delta in answer_deltas(&context) {
+                    if tx
+                        .send(Ok(AskEvent::AnswerDelta(delta.to_string())))
+                        .await
+                        .is_err()
+                    {
+                        return;
+                    }
+                }
+            }
+            let _ = tx.send(Ok(AskEvent::Done { stats, next_cursor })).await;
+        });
+        Ok(Box::pin(ReceiverStream::new(rx)))
+    }
+
+    async fn export_frames(
+        &self,
+        after: Option<u64>,
+        limit: usize,
+    ) -> Result<Vec<FrameMetadata>, ServiceError> {
+        self.inner.export_frames(after, limit).await
+    }
+
+    async fn frame_texts(
+        &self,
+        after: Option<u64>,
+        limit: usize,
+    ) -> Result<Vec<FrameText>, ServiceError> {
+        self.inner.frame_texts(after, limit).await
+    }
+
+    fn frame_count(&self) -> i32 {
+        self.inner.frame_count()
+    }
+
+    fn memvid_file(&self) -> String {
+        self.inner.memvid_file()
+    }
+
+    fn generation(&self) -> u64 {
+        self.inner.generation()
+    }
+
+    fn index_features(&self) -> IndexFeatures {
+        self.inner.index_features()
+    }
+
+    fn section_counts(&self) -> BTreeMap<String, i32> {
+        self.inner.section_counts()
+    }
+
+    fn lock_diagnostics(&self) -> LockDiagnostics {
+        self.inner.lock_diagnostics()
+    }
+
+    fn acronyms(&self) -> Arc<AcronymMap> {
+        self.inner.acronyms()
+    }
+
+    fn spelling(&self) -> Arc<SpellDictionary> {
+        self.inner.spelling()
+    }
+
+    fn suggestions(&self) -> Arc<SuggestionIndex> {
+        self.inner.suggestions()
+    }
+
+    fn is_ready(&self) -> bool {
+        self.inner.is_ready()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memvid::{AskMode, MockSearcher};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+    use tokio_stream::StreamExt;
+
+    fn ask_request(use_llm: bool) -> AskRequest {
+        AskRequest {
+            question: "What Python experience?".to_string(),
+            use_llm,
+            top_k: 3,
+            filters: Default::default(),
+            start: 0,
+            end: 0,
+            snippet_chars: 200,
+            mode: AskMode::Hybrid,
+            uri: None,
+            cursor: None,
+            as_of_frame: None,
+            as_of_ts: None,
+            adaptive: None,
+            acl: None,
+        }
+    }
+
+    /// Serve one chat completion answering `content`, returning the base URL.
+    async fn serve_completion(content: &'static str) -> String {
+        serve(
+            serde_json::json!({
+                "choices": [{"message": {"role": "assistant", "content": content}}]
+            })
+            .to_string(),
+        )
+        .await
+    }
+
+    /// Serve one streamed chat completion sending `deltas`, returning the base URL.
+    async fn serve_stream(deltas: &[&str]) -> String {
+        let mut body = String::new();
+        for delta in deltas {
+            let chunk = serde_json::json!({"choices": [{"delta": {"content": delta}}]});
+            body.push_str(&format!("data: {}\n\n", chunk));
+        }
+        body.push_str("data: [DONE]\n\n");
+        serve(body).await
+    }
+
+    /// Serve one 200 response carrying `body`, returning the base URL.
+    async fn serve(body: String) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buf = vec![0; 64 * 1024];
+            let _ = stream.read(&mut buf).await;
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            stream.write_all(response.as_bytes()).await.unwrap();
+        });
+        format!("http://{}/v1", address)
+    }
+
+    #[tokio::test]
+    async fn test_answer_is_synthesized_by_the_llm() {
+        let base_url = serve_completion("Five years of Python [1].").await;
+        let client = Arc::new(LlmClient::new(&base_url, "test-model", None));
+        let searcher = SynthesizingSearcher::new(Arc::new(MockSearcher::new()), client);
+
+        let response = searcher.ask(ask_request(true)).await.unwrap();
+        assert_eq!(response.answer, "Five years of Python [1].");
+        assert!(!response.stats.used_fallback);
+        assert!(!response.evidence.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_llm_failure_falls_back_to_context() {
+        // Nothing listens on the discard port
+        let client = Arc::new(
+            LlmClient::new("http://127.0.0.1:9/v1", "test-model", None).with_max_retries(0),
+        );
+        let inner = Arc::new(MockSearcher::new());
+        let searcher = SynthesizingSearcher::new(Arc::clone(&inner) as _, client);
+
+        let response = searcher.ask(ask_request(true)).await.unwrap();
+        let context = inner.ask(ask_request(false)).await.unwrap();
+        assert_eq!(response.answer, context.answer);
+        assert!(response.stats.used_fallback);
+
+        // Requests without use_llm never reach the model
+        let plain = searcher.ask(ask_request(false)).await.unwrap();
+        assert!(!plain.stats.used_fallback);
+    }
+
+    #[tokio::test]
+    async fn test_ask_stream_forwards_llm_tokens() {
+        let base_url = serve_stream(&["Five years", " of Python", " [1]."]).await;
+        let client = Arc::new(LlmClient::new(&base_url, "test-model", None));
+        let searcher = SynthesizingSearcher::new(Arc::new(MockSearcher::new()), client);
+
+        let events: Vec<_> = searcher
+            .ask_stream(ask_request(true))
+            .await
+            .unwrap()
+            .collect()
+            .await;
+        assert!(matches!(events[0], Ok(AskEvent::Evidence(ref hits)) if !hits.is_empty()));
+        let deltas: Vec<_> = events
+            .iter()
+            .filter_map(|event| match event {
+                Ok(AskEvent::AnswerDelta(delta)) => Some(delta.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(deltas, ["Five years", " of Python", " [1]."]);
+        assert!(matches!(
+            events.last(),
+            Some(Ok(AskEvent::Done { stats, .. })) if !stats.used_fallback
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_ask_stream_falls_back_before_first_token() {
+        let client = Arc::new(
+            LlmClient::new("http://127.0.0.1:9/v1", "test-model", None).with_max_retries(0),
+        );
+        let inner = Arc::new(MockSearcher::new());
+        let searcher = SynthesizingSearcher::new(Arc::clone(&inner) as _, client);
+
+        let mut answer = String::new();
+        let mut used_fallback = false;
+        let mut events = searcher.ask_stream(ask_request(true)).await.unwrap();
+        while let Some(event) = events.next().await {
+            match event.unwrap() {
+                AskEvent::AnswerDelta(delta) => answer.push_str(&delta),
+                AskEvent::Done { stats, .. } => used_fallback = stats.used_fallback,
+                AskEvent::Evidence(_) => {}
+            }
+        }
+        assert_eq!(answer, inner.ask(ask_request(false)).await.unwrap().answer);
+        assert!(used_fallback);
+    }
+}
@@ -0,0 +1,462 @@
+//! Integrity checks of a loaded index.
+//!
+//! A truncated or corrupted upload often still opens: it then serves empty
+//! or partial results instead of failing loudly. Validation runs the checks
+//! an operator would otherwise do by hand:
+//!
+//! - `checksum`: the SHA-256 of the .mv2 file against the expected one
+//!   (given by the caller, or read from a `<file>.sha256` sidecar in
+//!   `sha256sum` format)
+//! - `frames`: the index has frames, and its metadata lists all of them
+//! - `frame_decode`: frames sampled across the index decode to text
+//! - `embeddings`: semantic probes (see [`EmbeddingProfile`]) succeed and
+//!   retrieve their own frames, which fails on a dimension mismatch or a
+//!   different embedding model
+//!
+//! Checks only read through the [`Searcher`], so the served index is
+//! validated as loaded, with the configured embedder.
+
+use sha2::{Digest, Sha256};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use super::drift::EmbeddingProfile;
+use super::searcher::Searcher;
+use crate::error::ServiceError;
+
+/// Frames decoded when the caller leaves the sample size unset.
+pub const DEFAULT_VALIDATION_SAMPLES: usize = 16;
+
+/// Largest number of frames decoded in one validation.
+pub const MAX_VALIDATION_SAMPLES: usize = 1_000;
+
+/// Frame metadata read per `export_frames` call.
+const EXPORT_BATCH: usize = 1_000;
+
+/// Outcome of one check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStatus {
+    /// Nothing wrong found
+    Pass,
+    /// Suspicious, but the index serves
+    Warn,
+    /// The index is damaged or unusable
+    Fail,
+    /// Not applicable to this index
+    Skipped,
+}
+
+/// Result of one integrity check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationCheck {
+    /// Check name (e.g., "checksum")
+    pub name: &'static str,
+    /// Outcome
+    pub status: CheckStatus,
+    /// What was found
+    pub detail: String,
+}
+
+/// Results of validating an index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationReport {
+    /// Index file
+    pub memvid_file: String,
+    /// SHA-256 of the file, hex (None when it is not on disk)
+    pub sha256: Option<String>,
+    /// Frames whose text was decoded
+    pub frames_sampled: usize,
+    /// Checks in the order they ran
+    pub checks: Vec<ValidationCheck>,
+}
+
+impl ValidationReport {
+    /// Whether no check failed.
+    pub fn passed(&self) -> bool {
+        self.checks
+            .iter()
+            .all(|check| check.status != CheckStatus::Fail)
+    }
+}
+
+/// Run the integrity checks on `searcher`'s index, decoding `samples`
+/// frames (capped at [`MAX_VALIDATION_SAMPLES`]) and comparing the file
+/// with `expected_sha256` or its sidecar.
+///
+/// # Errors
+/// Failures computing the checksum of a file that exists; failed reads of
+/// the index are reported as failed checks instead.
+pub async fn validate_index(
+    searcher: &dyn Searcher,
+    samples: usize,
+    expected_sha256: Option<&str>,
+) -> Result<ValidationReport, ServiceError> {
+    let memvid_file = searcher.memvid_file();
+    let mut checks = Vec::new();
+
+    let (sha256, checksum) = check_checksum(Path::new(&memvid_file), expected_sha256).await?;
+    checks.push(checksum);
+
+    let frame_count = searcher.frame_count();
+    let frame_ids = match list_frame_ids(searcher).await {
+        Ok(ids) => {
+            checks.push(match ids.len() {
+                _ if frame_count <= 0 => fail("frames", "the index has no frames".to_string()),
+                listed if listed != frame_count as usize => fail(
+                    "frames",
+                    format!(
+                        "the index reports {} frames but its metadata lists {}",
+                        frame_count, listed
+                    ),
+                ),
+                _ => pass("frames", format!("{} frames", frame_count)),
+            });
+            ids
+        }
+        Err(e) => {
+            checks.push(fail("frames", format!("frame metadata unreadable: {}", e)));
+            Vec::new()
+        }
+    };
+
+    let sampled = sample(&frame_ids, samples.min(MAX_VALIDATION_SAMPLES));
+    checks.push(check_frame_decode(searcher, &frame_ids, &sampled).await);
+
+    checks.push(if !searcher.index_features().vector {
+        skipped("embeddings", "no vector index".to_string())
+    } else {
+        embedding_check(&EmbeddingProfile::probe(searcher).await)
+    });
+
+    Ok(ValidationReport {
+        memvid_file,
+        sha256,
+        frames_sampled: sampled.len(),
+        checks,
+    })
+}
+
+/// The file's SHA-256 and how it compares with the expected one.
+async fn check_checksum(
+    path: &Path,
+    expected: Option<&str>,
+) -> Result<(Option<String>, ValidationCheck), ServiceError> {
+    if !path.is_file() {
+        return Ok((
+            None,
+            skipped("checksum", "the index is not a file on disk".to_string()),
+        ));
+    }
+    let sha256 = tokio::task::spawn_blocking({
+        let path = path.to_path_buf();
+        move || file_sha256(&path)
+    })
+    .await
+    .map_err(|e| ServiceError::Internal(format!("Checksum task error: {}", e)))?
+    .map_err(|e| {
+        ServiceError::Internal(format!(
+            "Failed to read {} for its checksum: {}",
+            path.display(),
+            e
+        ))
+    })?;
+
+    let sidecar = sidecar_path(path);
+    let expected = match expected.filter(|expected| !expected.trim().is_empty()) {
+        Some(expected) => Some((expected.trim().to_lowercase(), "the request".to_string())),
+        None => std::fs::read_to_string(&sidecar).ok().and_then(|text| {
+            let expected = text.split_whitespace().next()?.to_lowercase();
+            Some((expected, sidecar.display().to_string()))
+        }),
+    };
+    let check = match expected {
+        None => skipped(
+            "checksum",
+            format!(
+                "sha256 {}; no expected checksum ({} not found)",
+                sha256,
+                sidecar.display()
+            ),
+        ),
+        Some((expected, source)) if expected == sha256 => {
+            pass("checksum", format!("sha256 {} matches {}", sha256, source))
+        }
+        Some((expected, source)) => fail(
+            "checksum",
+            format!(
+                "sha256 {} does not match {} from {}",
+                sha256, expected, source
+            ),
+        ),
+    };
+    Ok((Some(sha256), check))
+}
+
+/// `<file>.sha256` next to the index.
+pub(crate) fn sidecar_path(path: &Path) -> PathBuf {
+    let mut sidecar = path.as_os_str().to_owned();
+    sidecar.push(".sha256");
+    PathBuf::from(sidecar)
+}
+
+/// SHA-256 of a file, hex, read in chunks.
+pub(crate) fn file_sha256(path: &Path) -> std::io::Result<String> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0; 64 * 1024];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect())
+}
+
+/// IDs of every frame, in order.
+async fn list_frame_ids(searcher: &dyn Searcher) -> Result<Vec<u64>, ServiceError> {
+    let mut ids = Vec::new();
+    loop {
+        let batch = searcher
+            .export_frames(ids.last().copied(), EXPORT_BATCH)
+            .await?;
+        if batch.is_empty() {
+            return Ok(ids);
+        }
+        ids.extend(batch.into_iter().map(|frame| frame.frame_id));
+    }
+}
+
+/// Up to `count` of `ids`, spread evenly from the first to the last.
+fn sample(ids: &[u64], count: usize) -> Vec<u64> {
+    if count == 0 || ids.is_empty() {
+        return Vec::new();
+    }
+    if count >= ids.len() {
+        return ids.to_vec();
+    }
+    let mut sampled: Vec<u64> = (0..count)
+        .map(|i| ids[i * (ids.len() - 1) / (count - 1).max(1)])
+        .collect();
+    sampled.dedup();
+    sampled
+}
+
+/// Decode the text of the `sampled` frames.
+async fn check_frame_decode(
+    searcher: &dyn Searcher,
+    ids: &[u64],
+    sampled: &[u64],
+) -> ValidationCheck {
+    if sampled.is_empty() {
+        return skipped("frame_decode", "no frames to sample".to_string());
+    }
+    let mut failed = Vec::new();
+    let mut empty = Vec::new();
+    for &frame_id in sampled {
+        // Frame texts are read after the preceding frame ID
+        let position = ids.iter().position(|&id| id == frame_id).unwrap_or(0);
+        let after = position.checked_sub(1).map(|previous| ids[previous]);
+        match searcher.frame_texts(after, 1).await {
+            Ok(frames) => match frames.first() {
+                Some(frame) if frame.frame_id == frame_id => {
+                    if frame.text.trim().is_empty() {
+                        empty.push(frame_id);
+                    }
+                }
+                _ => failed.push(format!("frame {}: not found", frame_id)),
+            },
+            Err(e) => failed.push(format!("frame {}: {}", frame_id, e)),
+        }
+    }
+
+    if !failed.is_empty() {
+        fail(
+            "frame_decode",
+            format!(
+                "{} of {} sampled frames failed to decode ({})",
+                failed.len(),
+                sampled.len(),
+                failed.join("; ")
+            ),
+        )
+    } else if !empty.is_empty() {
+        warn(
+            "frame_decode",
+            format!(
+                "{} of {} sampled frames have no text (frames {:?})",
+                empty.len(),
+                sampled.len(),
+                empty
+            ),
+        )
+    } else {
+        pass(
+            "frame_decode",
+            format!("{} sampled frames decoded", sampled.len()),
+        )
+    }
+}
+
+/// How semantic probes of the index went.
+fn embedding_check(profile: &EmbeddingProfile) -> ValidationCheck {
+    let answered = profile.probes - profile.errors;
+    if profile.probes == 0 {
+        skipped("embeddings", "no frame text to probe with".to_string())
+    } else if answered == 0 {
+        fail(
+            "embeddings",
+            format!(
+                "all {} semantic probes failed (embedding dimension mismatch?)",
+                profile.probes
+            ),
+        )
+    } else if profile.errors > 0 {
+        warn(
+            "embeddings",
+            format!(
+                "{} of {} semantic probes failed",
+                profile.errors, profile.probes
+            ),
+        )
+    } else if profile.self_hits == 0 {
+        warn(
+            "embeddings",
+            "no semantic probe retrieved its own frame (index embedded with another model?)"
+                .to_string(),
+        )
+    } else {
+        pass(
+            "embeddings",
+            format!(
+                "{} of {} semantic probes retrieved their own frame",
+                profile.self_hits, profile.probes
+            ),
+        )
+    }
+}
+
+fn pass(name: &'static str, detail: String) -> ValidationCheck {
+    ValidationCheck {
+        name,
+        status: CheckStatus::Pass,
+        detail,
+    }
+}
+
+fn warn(name: &'static str, detail: String) -> ValidationCheck {
+    ValidationCheck {
+        name,
+        status: CheckStatus::Warn,
+        detail,
+    }
+}
+
+fn fail(name: &'static str, detail: String) -> ValidationCheck {
+    ValidationCheck {
+        name,
+        status: CheckStatus::Fail,
+        detail,
+    }
+}
+
+fn skipped(name: &'static str, detail: String) -> ValidationCheck {
+    ValidationCheck {
+        name,
+        status: CheckStatus::Skipped,
+        detail,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memvid::MockSearcher;
+
+    #[test]
+    fn test_sample_spreads_across_the_index() {
+        let ids: Vec<u64> = (1..=10).collect();
+        assert_eq!(sample(&ids, 3), vec![1, 5, 10]);
+        assert_eq!(sample(&ids, 1), vec![1]);
+        assert_eq!(sample(&ids, 20), ids);
+        assert!(sample(&ids, 0).is_empty());
+        assert!(sample(&[], 3).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_checksum_against_sidecar_and_request() {
+        let dir = std::env::temp_dir().join(format!("validate-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let index = dir.join("resume.mv2");
+        std::fs::write(&index, b"abc").unwrap();
+        let abc = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+
+        let (sha256, check) = check_checksum(&index, None).await.unwrap();
+        assert_eq!(sha256.as_deref(), Some(abc));
+        assert_eq!(check.status, CheckStatus::Skipped);
+
+        std::fs::write(sidecar_path(&index), format!("{}  resume.mv2\n", abc)).unwrap();
+        let (_, check) = check_checksum(&index, None).await.unwrap();
+        assert_eq!(check.status, CheckStatus::Pass);
+
+        let (_, check) = check_checksum(&index, Some("00ff")).await.unwrap();
+        assert_eq!(check.status, CheckStatus::Fail);
+        assert!(check.detail.contains("from the request"));
+
+        let (sha256, check) = check_checksum(&dir.join("missing.mv2"), None)
+            .await
+            .unwrap();
+        assert_eq!((sha256, check.status), (None, CheckStatus::Skipped));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_validate_mock_index() {
+        let statuses = |report: &ValidationReport| -> Vec<_> {
+            report
+                .checks
+                .iter()
+                .map(|check| (check.name, check.status))
+                .collect()
+        };
+
+        let report = validate_index(&MockSearcher::synthetic(7, 30), 4, None)
+            .await
+            .unwrap();
+        let checks = statuses(&report);
+        assert_eq!(checks[0], ("checksum", CheckStatus::Skipped));
+        assert_eq!(checks[1], ("frames", CheckStatus::Pass));
+        assert_eq!(checks[2], ("frame_decode", CheckStatus::Pass));
+        assert_eq!(checks[3].0, "embeddings");
+        assert_eq!(report.frames_sampled, 4);
+        assert!(report.passed());
+
+        // The sample corpus lists fewer frames than the count it reports
+        let report = validate_index(&MockSearcher::new(), 4, None).await.unwrap();
+        assert_eq!(statuses(&report)[1], ("frames", CheckStatus::Fail));
+        assert!(!report.passed());
+    }
+
+    #[test]
+    fn test_embedding_check() {
+        let profile = |probes, errors, self_hits| EmbeddingProfile {
+            probes,
+            errors,
+            self_hits,
+            mean_top_score: 0.5,
+        };
+        assert_eq!(embedding_check(&profile(8, 8, 0)).status, CheckStatus::Fail);
+        assert_eq!(embedding_check(&profile(8, 2, 4)).status, CheckStatus::Warn);
+        assert_eq!(embedding_check(&profile(8, 0, 0)).status, CheckStatus::Warn);
+        assert_eq!(embedding_check(&profile(8, 0, 6)).status, CheckStatus::Pass);
+        assert_eq!(
+            embedding_check(&profile(0, 0, 0)).status,
+            CheckStatus::Skipped
+        );
+    }
+}
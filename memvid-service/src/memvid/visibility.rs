@@ -0,0 +1,335 @@
+//! Frame visibility: public, authenticated callers only, or hidden.
+//!
+//! A frame's visibility comes from a `visibility:<level>` tag set at ingest,
+//! overridden per frame ID through the Admin `SetFrameVisibility` RPC, so a
+//! section (e.g., current employer details) can be hidden without rebuilding
+//! the index. Overrides refer to the frame IDs of the loaded index and are
+//! persisted to a JSON file when one is configured.
+//!
+//! Callers count as authenticated when their `x-api-key` is one of the
+//! configured keys; everyone else only sees public frames.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::path::PathBuf;
+use std::sync::RwLock;
+use tracing::info;
+
+use super::searcher::SearchResult;
+use crate::error::ServiceError;
+
+/// Prefix of the tag carrying a frame's ingest-time visibility.
+pub const VISIBILITY_TAG_PREFIX: &str = "visibility:";
+
+/// Candidates retrieved per requested hit while some frames are restricted.
+pub const VISIBILITY_OVERFETCH: i32 = 2;
+
+/// Who may see a frame.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Visibility {
+    /// Everyone
+    #[default]
+    Public,
+    /// Only callers with a configured API key
+    Authenticated,
+    /// Nobody
+    Hidden,
+}
+
+impl Visibility {
+    /// Parse a visibility level ("public", "authenticated", "hidden").
+    pub fn parse(level: &str) -> Option<Self> {
+        match level.trim().to_ascii_lowercase().as_str() {
+            "public" => Some(Self::Public),
+            "authenticated" => Some(Self::Authenticated),
+            "hidden" => Some(Self::Hidden),
+            _ => None,
+        }
+    }
+
+    /// Whether `audience` may see frames at this level.
+    pub fn visible_to(self, audience: Audience) -> bool {
+        match self {
+            Self::Public => true,
+            Self::Authenticated => audience == Audience::Authenticated,
+            Self::Hidden => false,
+        }
+    }
+}
+
+/// Which frames a caller may see.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Audience {
+    /// Public frames only
+    Public,
+    /// Public and authenticated frames
+    Authenticated,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct OverrideFile {
+    frames: BTreeMap<u64, Visibility>,
+}
+
+/// Per-frame visibility overrides and the keys of authenticated callers.
+#[derive(Debug, Default)]
+pub struct VisibilityStore {
+    overrides: RwLock<BTreeMap<u64, Visibility>>,
+    path: Option<PathBuf>,
+    authenticated_keys: HashSet<String>,
+}
+
+impl VisibilityStore {
+    /// Create an empty, in-memory store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Persist overrides to `path`, loading any saved there before.
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, ServiceError> {
+        let path = path.into();
+        let overrides = match std::fs::read_to_string(&path) {
+            Ok(json) => {
+                serde_json::from_str::<OverrideFile>(&json)
+                    .map_err(|e| {
+                        ServiceError::Internal(format!(
+                            "Invalid visibility file {}: {}",
+                            path.display(),
+                            e
+                        ))
+                    })?
+                    .frames
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => {
+                return Err(ServiceError::Internal(format!(
+                    "Failed to read visibility file {}: {}",
+                    path.display(),
+                    e
+                )))
+            }
+        };
+        info!(
+            path = %path.display(),
+            overrides = overrides.len(),
+            "Loaded frame visibility overrides"
+        );
+        Ok(Self {
+            overrides: RwLock::new(overrides),
+            path: Some(path),
+            authenticated_keys: HashSet::new(),
+        })
+    }
+
+    /// Treat callers presenting one of `keys` as authenticated.
+    pub fn with_authenticated_keys(mut self, keys: impl IntoIterator<Item = String>) -> Self {
+        self.authenticated_keys = keys.into_iter().collect();
+        self
+    }
+
+    /// Audience of a caller presenting `api_key`.
+    pub fn audience(&self, api_key: Option<&str>) -> Audience {
+        match api_key {
+            Some(key) if self.authenticated_keys.contains(key) => Audience::Authenticated,
+            _ => Audience::Public,
+        }
+    }
+
+    /// Set the visibility of `frame_ids`; `Public` clears their overrides.
+    ///
+    /// The change is persisted before it takes effect.
+    pub fn set(&self, frame_ids: &[u64], visibility: Visibility) -> Result<(), ServiceError> {
+        let mut overrides = self.overrides.write().unwrap_or_else(|e| e.into_inner());
+        let mut updated = overrides.clone();
+        for &frame_id in frame_ids {
+            match visibility {
+                Visibility::Public => updated.remove(&frame_id),
+                level => updated.insert(frame_id, level),
+            };
+        }
+        if let Some(path) = &self.path {
+            persist(path, &updated)?;
+        }
+        *overrides = updated;
+        Ok(())
+    }
+
+    /// Current overrides by frame ID.
+    pub fn overrides(&self) -> BTreeMap<u64, Visibility> {
+        self.overrides
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Visibility of a frame: its override, else its tag, else public.
+    pub fn visibility(&self, frame_id: Option<u64>, tags: &[String]) -> Visibility {
+        let overridden = frame_id.and_then(|id| {
+            self.overrides
+                .read()
+                .unwrap_or_else(|e| e.into_inner())
+                .get(&id)
+                .copied()
+        });
+        overridden
+            .or_else(|| {
+                tags.iter()
+                    .find_map(|tag| tag.strip_prefix(VISIBILITY_TAG_PREFIX))
+                    .and_then(Visibility::parse)
+            })
+            .unwrap_or_default()
+    }
+
+    /// Drop hits `audience` may not see, returning how many were dropped.
+    pub fn retain_visible(&self, hits: &mut Vec<SearchResult>, audience: Audience) -> usize {
+        let before = hits.len();
+        hits.retain(|hit| {
+            self.visibility(hit.frame_id, &hit.tags)
+                .visible_to(audience)
+        });
+        before - hits.len()
+    }
+
+    /// Whether any frame may be hidden from `audience`, given the index's
+    /// tag counts.
+    pub fn restricts(&self, audience: Audience, tag_counts: &BTreeMap<String, i32>) -> bool {
+        let overridden = self
+            .overrides
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .values()
+            .any(|level| !level.visible_to(audience));
+        overridden
+            || tag_counts.iter().any(|(tag, &count)| {
+                count > 0
+                    && tag
+                        .strip_prefix(VISIBILITY_TAG_PREFIX)
+                        .and_then(Visibility::parse)
+                        .is_some_and(|level| !level.visible_to(audience))
+            })
+    }
+}
+
+/// Context-only answer built from the evidence (the format memvid returns
+/// without synthesis).
+pub fn context_answer(evidence: &[SearchResult]) -> String {
+    evidence
+        .iter()
+        .map(|e| format!("**{}**\n{}", e.title, e.snippet))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Write overrides to `path` via a temporary file, so a crash never leaves
+/// a truncated file behind.
+fn persist(path: &PathBuf, overrides: &BTreeMap<u64, Visibility>) -> Result<(), ServiceError> {
+    let json = serde_json::to_string_pretty(&OverrideFile {
+        frames: overrides.clone(),
+    })
+    .map_err(|e| ServiceError::Internal(format!("Failed to encode visibility: {}", e)))?;
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, json)
+        .and_then(|()| std::fs::rename(&tmp, path))
+        .map_err(|e| {
+            ServiceError::Internal(format!(
+                "Failed to write visibility file {}: {}",
+                path.display(),
+                e
+            ))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hit(frame_id: u64, tags: &[&str]) -> SearchResult {
+        SearchResult {
+            frame_id: Some(frame_id),
+            title: format!("frame {}", frame_id),
+            score: 1.0,
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_tags_and_overrides() {
+        let store = VisibilityStore::new();
+        assert_eq!(store.visibility(Some(1), &[]), Visibility::Public);
+        assert_eq!(
+            store.visibility(Some(1), &["visibility:authenticated".to_string()]),
+            Visibility::Authenticated
+        );
+
+        store.set(&[1], Visibility::Hidden).unwrap();
+        assert_eq!(
+            store.visibility(Some(1), &["visibility:authenticated".to_string()]),
+            Visibility::Hidden
+        );
+        store.set(&[1], Visibility::Public).unwrap();
+        assert!(store.overrides().is_empty());
+    }
+
+    #[test]
+    fn test_retain_visible_by_audience() {
+        let store = VisibilityStore::new().with_authenticated_keys(["k1".to_string()]);
+        store.set(&[3], Visibility::Hidden).unwrap();
+        let hits = vec![
+            hit(1, &[]),
+            hit(2, &["visibility:authenticated"]),
+            hit(3, &[]),
+        ];
+
+        let mut public = hits.clone();
+        assert_eq!(store.retain_visible(&mut public, store.audience(None)), 2);
+        assert_eq!(public.len(), 1);
+
+        let mut authenticated = hits;
+        let audience = store.audience(Some("k1"));
+        assert_eq!(audience, Audience::Authenticated);
+        assert_eq!(store.retain_visible(&mut authenticated, audience), 1);
+        let ids: Vec<Option<u64>> = authenticated.iter().map(|h| h.frame_id).collect();
+        assert_eq!(ids, vec![Some(1), Some(2)]);
+
+        assert_eq!(store.audience(Some("k2")), Audience::Public);
+    }
+
+    #[test]
+    fn test_restricts() {
+        let store = VisibilityStore::new();
+        let mut counts = BTreeMap::from([("experience".to_string(), 4)]);
+        assert!(!store.restricts(Audience::Public, &counts));
+
+        counts.insert("visibility:authenticated".to_string(), 1);
+        assert!(store.restricts(Audience::Public, &counts));
+        assert!(!store.restricts(Audience::Authenticated, &counts));
+
+        store.set(&[9], Visibility::Hidden).unwrap();
+        assert!(store.restricts(Audience::Authenticated, &counts));
+    }
+
+    #[test]
+    fn test_overrides_persist() {
+        let path =
+            std::env::temp_dir().join(format!("memvid-visibility-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let store = VisibilityStore::open(&path).unwrap();
+        store.set(&[4, 5], Visibility::Hidden).unwrap();
+        store.set(&[6], Visibility::Authenticated).unwrap();
+
+        let reopened = VisibilityStore::open(&path).unwrap();
+        assert_eq!(
+            reopened.overrides(),
+            BTreeMap::from([
+                (4, Visibility::Hidden),
+                (5, Visibility::Hidden),
+                (6, Visibility::Authenticated),
+            ])
+        );
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -3,12 +3,26 @@
 //! Exposes an HTTP endpoint for Prometheus scraping.
 
 use axum::{routing::get, Router};
-use metrics::{counter, describe_counter, describe_histogram, histogram};
-use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
+use metrics::{counter, describe_counter, describe_gauge, describe_histogram, gauge, histogram};
+use metrics_exporter_prometheus::{Matcher, PrometheusBuilder, PrometheusHandle};
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::time::Duration;
 use tracing::info;
 
+/// Bucket bounds for message size histograms (256 B to 4 MiB).
+const SIZE_BUCKETS: &[f64] = &[
+    256.0, 1024.0, 4096.0, 16384.0, 65536.0, 262144.0, 1048576.0, 4194304.0,
+];
+
 /// Initialize the metrics system and return the Prometheus handle.
 pub fn init_metrics() -> PrometheusHandle {
+    init_metrics_with_labels(Vec::new())
+}
+
+/// Initialize the metrics system with `labels` added to every metric (e.g.,
+/// the pod name) and return the Prometheus handle.
+pub fn init_metrics_with_labels(labels: Vec<(String, String)>) -> PrometheusHandle {
     // Register metric descriptions
     describe_histogram!(
         "memvid_search_latency_ms",
@@ -22,9 +36,187 @@ pub fn init_metrics() -> PrometheusHandle {
         "memvid_search_errors_total",
         "Total number of search errors"
     );
+    describe_counter!(
+        "memvid_search_partial_total",
+        "Total number of searches returning partial results after exceeding their time budget"
+    );
+    describe_counter!(
+        "memvid_spell_corrections_total",
+        "Searches without hits retried with a spelling-corrected query, by result (corrected, no_hits)"
+    );
+    describe_histogram!(
+        "memvid_request_bytes",
+        "Serialized gRPC request size in bytes, by RPC"
+    );
+    describe_histogram!(
+        "memvid_response_bytes",
+        "Serialized gRPC response size in bytes, by RPC"
+    );
+    describe_counter!(
+        "memvid_embedding_cache_total",
+        "Query embedding cache lookups by result (hit, miss)"
+    );
+    describe_counter!(
+        "memvid_response_trimmed_total",
+        "Total number of responses trimmed to fit the maximum response size, by RPC"
+    );
+    describe_counter!(
+        "memvid_legacy_field_total",
+        "Total number of v1 requests using a deprecated field or sentinel, by RPC and field"
+    );
+    describe_counter!(
+        "memvid_export_bytes_total",
+        "Total bytes sent on export streams, by RPC"
+    );
+    describe_histogram!(
+        "memvid_export_wait_ms",
+        "Time an export batch waited before sending, by RPC and reason (ack, bandwidth)"
+    );
+    describe_counter!(
+        "memvid_embedder_tier_total",
+        "Total number of Ask requests by the embedder fallback tier that served them"
+    );
+    describe_counter!(
+        "memvid_answer_cache_total",
+        "Total number of answer cache lookups by result (hit, miss, error)"
+    );
+    describe_counter!(
+        "memvid_search_cache_total",
+        "Total number of search result cache lookups by result (hit, miss)"
+    );
+    describe_counter!(
+        "memvid_cache_hits_total",
+        "Total number of cache hits, by cache (search, embedding, answer)"
+    );
+    describe_counter!(
+        "memvid_cache_misses_total",
+        "Total number of cache misses, by cache (search, embedding, answer)"
+    );
+    describe_counter!(
+        "memvid_cache_evictions_total",
+        "Total number of entries dropped from in-process caches, by cache and reason (capacity, expired, invalidated)"
+    );
+    describe_gauge!(
+        "memvid_cache_entries",
+        "Number of entries held by each in-process cache"
+    );
+    describe_counter!(
+        "memvid_question_topic_total",
+        "Total number of Ask questions by classified topic"
+    );
+    describe_gauge!(
+        "memvid_embedder_healthy",
+        "Health of each embedder fallback tier (1 = healthy, 0 = unhealthy)"
+    );
+    describe_counter!(
+        "memvid_llm_tokens_total",
+        "Estimated LLM tokens used for answer synthesis by API key and kind (prompt, completion)"
+    );
+    describe_gauge!(
+        "memvid_llm_daily_cost_usd",
+        "Estimated LLM spend in USD today (UTC) by API key"
+    );
+    describe_counter!(
+        "memvid_llm_capped_total",
+        "Total number of Ask requests whose synthesis was skipped by the daily cost cap"
+    );
+    describe_counter!(
+        "memvid_llm_requests_total",
+        "Total number of LLM requests for answer synthesis by provider and outcome (ok, error)"
+    );
+    describe_counter!(
+        "memvid_llm_retries_total",
+        "Total number of retried LLM requests by provider"
+    );
+    describe_histogram!(
+        "memvid_llm_latency_ms",
+        "Time taken by LLM requests for answer synthesis, retries included, in milliseconds"
+    );
+    describe_counter!(
+        "memvid_rate_limited_total",
+        "Total number of requests rejected by the per-client rate limit"
+    );
+    describe_counter!(
+        "memvid_jwt_rejected_total",
+        "Total number of requests rejected by bearer-token validation by reason (missing, invalid)"
+    );
+    describe_counter!(
+        "memvid_deadline_exceeded_total",
+        "Total number of operations abandoned because the client's deadline passed, by operation"
+    );
+    describe_counter!(
+        "memvid_search_rejected_total",
+        "Total number of searches and asks rejected because the concurrency queue was full, by operation"
+    );
+    describe_counter!(
+        "memvid_invalid_citations_total",
+        "Total number of citation markers dropped from synthesized answers for citing evidence not returned"
+    );
+    describe_counter!(
+        "memvid_uncited_claims_removed_total",
+        "Total number of uncited sentences stripped from synthesized answers by the strict citation policy"
+    );
+    describe_counter!(
+        "memvid_embedding_drift_total",
+        "Total number of reloaded or staged indexes whose embeddings look different from the serving index's"
+    );
+    describe_counter!(
+        "memvid_task_failures_total",
+        "Total number of panics of supervised background tasks, by task"
+    );
+    describe_counter!(
+        "memvid_scheduled_reload_total",
+        "Total number of scheduled index reloads by outcome (reloaded, unchanged, failed)"
+    );
+    describe_counter!(
+        "memvid_runtime_config_updates_total",
+        "Total number of runtime config reads by outcome (applied, failed)"
+    );
+    describe_counter!(
+        "memvid_shadow_compare_total",
+        "Total number of mirrored shadow requests by RPC and outcome (match, diff, error, skipped)"
+    );
+    describe_histogram!(
+        "memvid_shadow_overlap_ratio",
+        "Fraction of primary hits also returned by the shadow candidate"
+    );
+    describe_histogram!(
+        "memvid_shadow_latency_delta_ms",
+        "Shadow candidate latency minus primary latency in milliseconds"
+    );
+    describe_gauge!(
+        "memvid_section_frame_count",
+        "Number of frames in the loaded index per section tag"
+    );
+    describe_gauge!(
+        "memvid_index_resident_bytes",
+        "Bytes of preloaded index files held in memory, by whether they are mlocked"
+    );
+    describe_gauge!(
+        "memvid_scheduled_reload_last_success",
+        "Outcome of the last scheduled index reload (1 = success, 0 = failed)"
+    );
+    describe_gauge!(
+        "memvid_scheduled_reload_last_timestamp_seconds",
+        "Unix timestamp of the last scheduled index reload attempt"
+    );
+    describe_gauge!(
+        "memvid_index_degraded",
+        "Whether index reloads are failing and a stale index is serving (1 = degraded)"
+    );
+    describe_gauge!(
+        "memvid_readiness_gate",
+        "Whether each readiness gate is open (1 = open)"
+    );
 
     // Build Prometheus exporter
-    PrometheusBuilder::new()
+    labels
+        .into_iter()
+        .fold(PrometheusBuilder::new(), |builder, (key, value)| {
+            builder.add_global_label(key, value)
+        })
+        .set_buckets_for_metric(Matcher::Suffix("_bytes".to_string()), SIZE_BUCKETS)
+        .expect("Invalid size histogram buckets")
         .install_recorder()
         .expect("Failed to install Prometheus recorder")
 }
@@ -45,15 +237,262 @@ pub fn increment_search_errors() {
     counter!("memvid_search_errors_total").increment(1);
 }
 
+/// Increment the partial (budget exceeded) search count.
+pub fn increment_search_partial() {
+    counter!("memvid_search_partial_total").increment(1);
+}
+
+/// Count a spelling-corrected retry of a search without hits.
+pub fn record_spell_correction(found_hits: bool) {
+    let result = if found_hits { "corrected" } else { "no_hits" };
+    counter!("memvid_spell_corrections_total", "result" => result).increment(1);
+}
+
+/// Record serialized request and response sizes for an RPC.
+pub fn record_message_sizes(rpc: &'static str, request_bytes: usize, response_bytes: usize) {
+    histogram!("memvid_request_bytes", "rpc" => rpc).record(request_bytes as f64);
+    histogram!("memvid_response_bytes", "rpc" => rpc).record(response_bytes as f64);
+}
+
+/// Record a query embedding cache lookup.
+pub fn record_embedding_cache(hit: bool) {
+    let result = if hit { "hit" } else { "miss" };
+    counter!("memvid_embedding_cache_total", "result" => result).increment(1);
+}
+
+/// Increment the trimmed response count for an RPC.
+pub fn increment_response_trimmed(rpc: &'static str) {
+    counter!("memvid_response_trimmed_total", "rpc" => rpc).increment(1);
+}
+
+/// Count a request that used a deprecated v1 field or sentinel value.
+pub fn record_legacy_field(rpc: &'static str, field: &'static str) {
+    counter!("memvid_legacy_field_total", "rpc" => rpc, "field" => field).increment(1);
+}
+
+/// Record a batch sent on an export stream.
+pub fn record_export_batch(rpc: &'static str, bytes: usize) {
+    counter!("memvid_export_bytes_total", "rpc" => rpc).increment(bytes as u64);
+}
+
+/// Record how long an export batch was held back ("ack" or "bandwidth").
+pub fn record_export_wait(rpc: &'static str, reason: &'static str, wait: Duration) {
+    histogram!("memvid_export_wait_ms", "rpc" => rpc, "reason" => reason)
+        .record(wait.as_secs_f64() * 1000.0);
+}
+
+/// Count a request served by an embedder fallback tier.
+pub fn record_embedder_tier(tier: &str) {
+    counter!("memvid_embedder_tier_total", "tier" => tier.to_string()).increment(1);
+}
+
+/// Record a search result cache lookup.
+pub fn record_search_cache(hit: bool) {
+    let result = if hit { "hit" } else { "miss" };
+    counter!("memvid_search_cache_total", "result" => result).increment(1);
+}
+
+/// Count a cache lookup as a hit or miss, by cache (search, embedding, answer).
+pub fn record_cache_lookup(cache: &'static str, hit: bool) {
+    if hit {
+        counter!("memvid_cache_hits_total", "cache" => cache).increment(1);
+    } else {
+        counter!("memvid_cache_misses_total", "cache" => cache).increment(1);
+    }
+}
+
+/// Count entries dropped from a cache, by reason (capacity, expired, invalidated).
+pub fn record_cache_evictions(cache: &'static str, reason: &'static str, count: usize) {
+    if count > 0 {
+        counter!("memvid_cache_evictions_total", "cache" => cache, "reason" => reason)
+            .increment(count as u64);
+    }
+}
+
+/// Publish the number of entries an in-process cache holds.
+pub fn set_cache_entries(cache: &'static str, entries: usize) {
+    gauge!("memvid_cache_entries", "cache" => cache).set(entries as f64);
+}
+
+/// Count an answer cache lookup by result (hit, miss, error).
+pub fn record_answer_cache(result: &'static str) {
+    counter!("memvid_answer_cache_total", "result" => result).increment(1);
+}
+
+/// Count an Ask question by its classified topic.
+pub fn record_question_topic(topic: &str) {
+    counter!("memvid_question_topic_total", "topic" => topic.to_string()).increment(1);
+}
+
+/// Publish the health of an embedder fallback tier.
+pub fn set_embedder_healthy(tier: &str, healthy: bool) {
+    gauge!("memvid_embedder_healthy", "tier" => tier.to_string()).set(if healthy {
+        1.0
+    } else {
+        0.0
+    });
+}
+
+/// Record estimated LLM token usage for an API key id.
+pub fn record_llm_usage(key: &str, prompt_tokens: u32, completion_tokens: u32) {
+    counter!("memvid_llm_tokens_total", "key" => key.to_string(), "kind" => "prompt")
+        .increment(prompt_tokens as u64);
+    counter!("memvid_llm_tokens_total", "key" => key.to_string(), "kind" => "completion")
+        .increment(completion_tokens as u64);
+}
+
+/// Publish today's estimated LLM spend for an API key id.
+pub fn set_llm_daily_cost(key: &str, cost_usd: f64) {
+    gauge!("memvid_llm_daily_cost_usd", "key" => key.to_string()).set(cost_usd);
+}
+
+/// Increment the count of Ask requests denied synthesis by the cost cap.
+pub fn increment_llm_capped() {
+    counter!("memvid_llm_capped_total").increment(1);
+}
+
+/// Record an LLM request for answer synthesis, retries included.
+pub fn record_llm_request(provider: &'static str, success: bool, latency_ms: f64) {
+    let outcome = if success { "ok" } else { "error" };
+    counter!("memvid_llm_requests_total", "provider" => provider, "outcome" => outcome)
+        .increment(1);
+    histogram!("memvid_llm_latency_ms", "provider" => provider).record(latency_ms);
+}
+
+/// Increment the count of retried LLM requests.
+pub fn increment_llm_retries(provider: &'static str) {
+    counter!("memvid_llm_retries_total", "provider" => provider).increment(1);
+}
+
+/// Increment the count of requests rejected by the rate limiter.
+pub fn increment_rate_limited() {
+    counter!("memvid_rate_limited_total").increment(1);
+}
+
+/// Increment the count of requests rejected by bearer-token validation.
+pub fn increment_jwt_rejected(reason: &'static str) {
+    counter!("memvid_jwt_rejected_total", "reason" => reason).increment(1);
+}
+
+/// Increment the counter of work abandoned at the client's deadline.
+pub fn increment_deadline_exceeded(operation: &'static str) {
+    counter!("memvid_deadline_exceeded_total", "operation" => operation).increment(1);
+}
+
+/// Increment the counter of searches rejected by the concurrency limit.
+pub fn increment_search_rejected(operation: &'static str) {
+    counter!("memvid_search_rejected_total", "operation" => operation).increment(1);
+}
+
+/// Record the citation markers dropped and uncited claims stripped from a
+/// synthesized answer.
+pub fn record_citation_check(invalid_markers: usize, claims_removed: usize) {
+    counter!("memvid_invalid_citations_total").increment(invalid_markers as u64);
+    counter!("memvid_uncited_claims_removed_total").increment(claims_removed as u64);
+}
+
+/// Increment the counter of indexes loaded with embedding drift.
+pub fn increment_embedding_drift() {
+    counter!("memvid_embedding_drift_total").increment(1);
+}
+
+/// Increment the counter of supervised task panics.
+pub fn increment_task_failure(task: &'static str) {
+    counter!("memvid_task_failures_total", "task" => task).increment(1);
+}
+
+/// Adjust the preloaded index bytes held in memory (negative when released).
+pub fn adjust_index_resident_bytes(delta: f64, locked: bool) {
+    gauge!("memvid_index_resident_bytes", "locked" => locked.to_string()).increment(delta);
+}
+
+/// Record the outcome of a scheduled reload ("reloaded", "unchanged", or "failed").
+pub fn record_scheduled_reload(outcome: &'static str) {
+    counter!("memvid_scheduled_reload_total", "outcome" => outcome).increment(1);
+    gauge!("memvid_scheduled_reload_last_success").set(if outcome == "failed" { 0.0 } else { 1.0 });
+    gauge!("memvid_scheduled_reload_last_timestamp_seconds")
+        .set(chrono::Utc::now().timestamp() as f64);
+}
+
+/// Publish whether a stale index is serving after failed reloads.
+pub fn set_index_degraded(degraded: bool) {
+    gauge!("memvid_index_degraded").set(if degraded { 1.0 } else { 0.0 });
+}
+
+/// Publish whether a readiness gate is open.
+pub fn set_readiness_gate(gate: &str, open: bool) {
+    gauge!("memvid_readiness_gate", "gate" => gate.to_string()).set(if open { 1.0 } else { 0.0 });
+}
+
+/// Count a runtime config read that changed settings or failed.
+pub fn record_runtime_config_update(outcome: &'static str) {
+    counter!("memvid_runtime_config_updates_total", "outcome" => outcome).increment(1);
+}
+
+/// Record the outcome of a mirrored shadow request.
+pub fn record_shadow_outcome(rpc: &'static str, outcome: &'static str) {
+    counter!("memvid_shadow_compare_total", "rpc" => rpc, "outcome" => outcome).increment(1);
+}
+
+/// Record how a shadow candidate's result differed from the primary result.
+pub fn record_shadow_diff(rpc: &'static str, overlap: f64, latency_delta_ms: f64) {
+    histogram!("memvid_shadow_overlap_ratio", "rpc" => rpc).record(overlap);
+    histogram!("memvid_shadow_latency_delta_ms", "rpc" => rpc).record(latency_delta_ms);
+}
+
+/// Publish per-section frame counts.
+///
+/// Sections present in `previous` but missing from `current` are reported as
+/// zero so a section dropped by a re-ingest shows up on dashboards.
+pub fn set_section_frame_counts(previous: &BTreeMap<String, i32>, current: &BTreeMap<String, i32>) {
+    for section in previous.keys().filter(|s| !current.contains_key(*s)) {
+        gauge!("memvid_section_frame_count", "section" => section.clone()).set(0.0);
+    }
+    for (section, count) in current {
+        gauge!("memvid_section_frame_count", "section" => section.clone()).set(*count as f64);
+    }
+}
+
 /// Create an Axum router for the metrics HTTP endpoint.
 pub fn metrics_router(handle: PrometheusHandle) -> Router {
     Router::new().route("/metrics", get(move || std::future::ready(handle.render())))
 }
 
+/// Serve `app` (the metrics router plus any extra routes) on `bind_address`
+/// ("auto" = auto-detect) until `shutdown` completes, then finish the
+/// requests in flight.
+pub async fn start_metrics_server_on(
+    bind_address: &str,
+    port: u16,
+    app: Router,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) {
+    if bind_address == "auto" {
+        return serve_auto(port, app, shutdown).await;
+    }
+
+    // Add brackets if it's an IPv6 address without them
+    let addr = if bind_address.contains(':') && !bind_address.starts_with('[') {
+        format!("[{}]:{}", bind_address, port)
+    } else {
+        format!("{}:{}", bind_address, port)
+    };
+    info!(port = port, bind = %bind_address, "Starting metrics server");
+
+    let listener = tokio::net::TcpListener::bind(&addr)
+        .await
+        .expect("Failed to bind metrics server");
+
+    serve(listener, app, shutdown).await;
+}
+
 /// Start the metrics HTTP server on the given port with auto-detect binding.
 pub async fn start_metrics_server(port: u16, handle: PrometheusHandle) {
-    let app = metrics_router(handle);
+    serve_auto(port, metrics_router(handle), std::future::pending()).await
+}
 
+/// Serve `app` on the given port, trying dual-stack before IPv4-only.
+async fn serve_auto(port: u16, app: Router, shutdown: impl Future<Output = ()> + Send + 'static) {
     // Auto-detect: Try dual-stack first, fall back to IPv4-only
     let bind_host = match format!("[::]:{}", port).parse::<std::net::SocketAddr>() {
         Ok(addr) => match tokio::net::TcpListener::bind(addr).await {
@@ -63,9 +502,7 @@ pub async fn start_metrics_server(port: u16, handle: PrometheusHandle) {
                     bind = "::",
                     "Starting metrics server (dual-stack)"
                 );
-                axum::serve(listener, app)
-                    .await
-                    .expect("Metrics server failed");
+                serve(listener, app, shutdown).await;
                 return;
             }
             Err(_) => "0.0.0.0",
@@ -80,9 +517,19 @@ pub async fn start_metrics_server(port: u16, handle: PrometheusHandle) {
         .await
         .expect("Failed to bind metrics server");
 
+    serve(listener, app, shutdown).await;
+}
+
+async fn serve(
+    listener: tokio::net::TcpListener,
+    app: Router,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) {
     axum::serve(listener, app)
+        .with_graceful_shutdown(shutdown)
         .await
         .expect("Metrics server failed");
+    info!("Metrics server stopped");
 }
 
 #[cfg(test)]
@@ -114,6 +561,142 @@ mod tests {
         increment_search_errors();
     }
 
+    #[test]
+    fn test_record_message_sizes() {
+        // This should not panic
+        record_message_sizes("search", 42, 4096);
+    }
+
+    #[test]
+    fn test_increment_response_trimmed() {
+        // This should not panic
+        increment_response_trimmed("search");
+    }
+
+    #[test]
+    fn test_record_legacy_field() {
+        // This should not panic
+        record_legacy_field("ask", "start");
+    }
+
+    #[test]
+    fn test_record_export_metrics() {
+        // These should not panic
+        record_export_batch("export_frames", 1024);
+        record_export_wait("export_frames", "bandwidth", Duration::from_millis(5));
+    }
+
+    #[test]
+    fn test_record_embedder_metrics() {
+        // These should not panic
+        record_embedder_tier("remote");
+        set_embedder_healthy("remote", false);
+    }
+
+    #[test]
+    fn test_record_llm_metrics() {
+        // These should not panic
+        record_llm_usage("anonymous", 120, 40);
+        set_llm_daily_cost("anonymous", 0.002);
+        increment_llm_capped();
+        record_llm_request("openai", true, 850.0);
+        record_llm_request("ollama", false, 30000.0);
+        increment_llm_retries("ollama");
+    }
+
+    #[test]
+    fn test_increment_rate_limited() {
+        // This should not panic
+        increment_rate_limited();
+    }
+
+    #[test]
+    fn test_increment_jwt_rejected() {
+        // This should not panic
+        increment_jwt_rejected("invalid");
+    }
+
+    #[test]
+    fn test_increment_search_rejected() {
+        // This should not panic
+        increment_search_rejected("ask");
+    }
+
+    #[test]
+    fn test_increment_deadline_exceeded() {
+        // This should not panic
+        increment_deadline_exceeded("search");
+    }
+
+    #[test]
+    fn test_record_citation_check() {
+        // This should not panic
+        record_citation_check(2, 1);
+    }
+
+    #[test]
+    fn test_increment_embedding_drift() {
+        // This should not panic
+        increment_embedding_drift();
+    }
+
+    #[test]
+    fn test_increment_task_failure() {
+        // This should not panic
+        increment_task_failure("metrics-server");
+    }
+
+    #[test]
+    fn test_record_shadow_metrics() {
+        // These should not panic
+        record_shadow_outcome("search", "diff");
+        record_shadow_diff("search", 0.5, -3.0);
+    }
+
+    #[test]
+    fn test_set_section_frame_counts() {
+        let previous = BTreeMap::from([("education".to_string(), 3)]);
+        let current = BTreeMap::from([("experience".to_string(), 18)]);
+        // This should not panic
+        set_section_frame_counts(&previous, &current);
+    }
+
+    #[test]
+    fn test_record_scheduled_reload() {
+        // This should not panic
+        record_scheduled_reload("reloaded");
+        record_scheduled_reload("failed");
+    }
+
+    #[test]
+    fn test_record_search_cache() {
+        // This should not panic
+        record_search_cache(true);
+        record_search_cache(false);
+    }
+
+    #[test]
+    fn test_record_cache_metrics() {
+        // These should not panic
+        record_cache_lookup("search", true);
+        record_cache_lookup("embedding", false);
+        record_cache_evictions("answer", "capacity", 2);
+        record_cache_evictions("answer", "expired", 0);
+        set_cache_entries("search", 12);
+    }
+
+    #[test]
+    fn test_set_readiness_gate() {
+        // This should not panic
+        set_readiness_gate("warmup_done", false);
+    }
+
+    #[test]
+    fn test_increment_search_partial() {
+        // This should not panic
+        increment_search_partial();
+    }
+
     #[tokio::test]
     async fn test_metrics_router_returns_metrics() {
         // Create a test handle
@@ -186,6 +769,36 @@ mod tests {
         server_handle.abort();
     }
 
+    #[tokio::test]
+    async fn test_metrics_server_binds_explicit_address() {
+        use std::net::TcpListener as StdTcpListener;
+
+        let listener = StdTcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        drop(listener);
+
+        let handle = PrometheusBuilder::new().build_recorder().handle();
+        let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+        let server_handle = tokio::spawn(async move {
+            let shutdown = async {
+                let _ = stopped.await;
+            };
+            start_metrics_server_on("127.0.0.1", port, metrics_router(handle), shutdown).await;
+        });
+
+        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+        assert!(tokio::net::TcpStream::connect(("127.0.0.1", port))
+            .await
+            .is_ok());
+
+        // The server stops on its own once shut down
+        stop.send(()).unwrap();
+        tokio::time::timeout(Duration::from_secs(5), server_handle)
+            .await
+            .unwrap()
+            .unwrap();
+    }
+
     #[tokio::test]
     async fn test_metrics_server_returns_prometheus_format() {
         use http_body_util::{BodyExt, Empty};
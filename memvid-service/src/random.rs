@@ -0,0 +1,27 @@
+//! Random bytes from the operating system's random source.
+//!
+//! Signing keys, session IDs and other values callers must not guess are
+//! drawn here rather than from hashers or clocks.
+
+/// `N` random bytes.
+///
+/// # Panics
+/// When the operating system's random source is unavailable, which leaves
+/// nothing safe to key or name with.
+pub fn random_bytes<const N: usize>() -> [u8; N] {
+    let mut bytes = [0u8; N];
+    getrandom::getrandom(&mut bytes).expect("the OS random source is unavailable");
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_random_bytes_differ() {
+        let (a, b) = (random_bytes::<32>(), random_bytes::<32>());
+        assert_ne!(a, b);
+        assert_ne!(a, [0u8; 32]);
+    }
+}
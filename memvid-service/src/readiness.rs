@@ -0,0 +1,429 @@
+//! Fine-grained readiness gates.
+//!
+//! Readiness is a set of named gates instead of one boolean, so a partially
+//! degraded replica shows which part is missing. `index_loaded` and
+//! `embedder_healthy` are read from the searcher and the embedder chain at
+//! each health check, and `llm_healthy` from the daily LLM cost cap;
+//! `warmup_done` and `caches_restored` are opened by startup tasks: the
+//! first after the warm-up queries have run against the index (loading
+//! memvid-core's lazily read segments and embeddings before real traffic),
+//! the second once the answer cache snapshot is restored. A
+//! [`ReadinessPolicy`] says what a closed gate means: `required` gates make
+//! the service NOT_SERVING, `degrade` gates DEGRADED, and `ignore` gates are
+//! only reported. Every gate is published in health detail and as the
+//! `memvid_readiness_gate` gauge.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tracing::{info, warn};
+
+use crate::memvid::{AskMode, AskRequest, SearchRequest, Searcher};
+use crate::metrics;
+
+/// Warm-up query run when no `WARMUP_QUERIES` are configured.
+pub const WARMUP_QUERY: &str = "experience";
+
+/// Results each warm-up search and ask retrieves.
+const WARMUP_TOP_K: i32 = 5;
+
+/// Snippet length of warm-up results.
+const WARMUP_SNIPPET_CHARS: i32 = 200;
+
+/// A named readiness gate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Gate {
+    /// The index is loaded and can take queries
+    IndexLoaded,
+    /// The startup warm-up query finished
+    WarmupDone,
+    /// Some embedder tier is healthy
+    EmbedderHealthy,
+    /// LLM synthesis is available (within the daily cost cap)
+    LlmHealthy,
+    /// The answer cache snapshot was restored
+    CachesRestored,
+}
+
+impl Gate {
+    /// Every gate, in reporting order.
+    pub const ALL: [Gate; 5] = [
+        Gate::IndexLoaded,
+        Gate::WarmupDone,
+        Gate::EmbedderHealthy,
+        Gate::LlmHealthy,
+        Gate::CachesRestored,
+    ];
+
+    /// Name used in health detail, metrics and `READINESS_POLICY`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Gate::IndexLoaded => "index_loaded",
+            Gate::WarmupDone => "warmup_done",
+            Gate::EmbedderHealthy => "embedder_healthy",
+            Gate::LlmHealthy => "llm_healthy",
+            Gate::CachesRestored => "caches_restored",
+        }
+    }
+
+    fn index(self) -> usize {
+        Self::ALL.iter().position(|gate| *gate == self).unwrap_or(0)
+    }
+}
+
+/// What a closed gate does to the service's health status.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GatePolicy {
+    /// NOT_SERVING while closed
+    Required,
+    /// DEGRADED while closed
+    Degrade,
+    /// Reported only
+    Ignore,
+}
+
+impl GatePolicy {
+    /// Parse "required", "degrade" or "ignore".
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "required" => Ok(Self::Required),
+            "degrade" => Ok(Self::Degrade),
+            "ignore" => Ok(Self::Ignore),
+            other => Err(format!(
+                "expected required, degrade or ignore, got '{}'",
+                other
+            )),
+        }
+    }
+
+    /// Name used in health detail.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Required => "required",
+            Self::Degrade => "degrade",
+            Self::Ignore => "ignore",
+        }
+    }
+}
+
+/// Policy of every gate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReadinessPolicy {
+    policies: [GatePolicy; 5],
+}
+
+impl Default for ReadinessPolicy {
+    /// Only a loaded, warmed-up index is required; the rest is reported.
+    fn default() -> Self {
+        let mut policies = [GatePolicy::Ignore; 5];
+        policies[Gate::IndexLoaded.index()] = GatePolicy::Required;
+        policies[Gate::WarmupDone.index()] = GatePolicy::Required;
+        Self { policies }
+    }
+}
+
+impl ReadinessPolicy {
+    /// Parse `READINESS_POLICY`, comma-separated `gate=policy` pairs
+    /// overriding the defaults, e.g. `llm_healthy=degrade,warmup_done=ignore`.
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let mut policy = Self::default();
+        for entry in value.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let (name, gate_policy) = entry
+                .split_once('=')
+                .ok_or_else(|| format!("expected gate=policy, got '{}'", entry))?;
+            let gate = Gate::ALL
+                .into_iter()
+                .find(|gate| gate.name() == name.trim())
+                .ok_or_else(|| {
+                    let names: Vec<&str> = Gate::ALL.iter().map(|g| g.name()).collect();
+                    format!(
+                        "unknown gate '{}', expected one of: {}",
+                        name.trim(),
+                        names.join(", ")
+                    )
+                })?;
+            policy.policies[gate.index()] = GatePolicy::parse(gate_policy)?;
+        }
+        Ok(policy)
+    }
+
+    /// Policy of `gate`.
+    pub fn get(&self, gate: Gate) -> GatePolicy {
+        self.policies[gate.index()]
+    }
+}
+
+/// State of one gate at a health check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GateStatus {
+    /// The gate
+    pub gate: Gate,
+    /// Whether it is open
+    pub open: bool,
+    /// Why it is closed (or a note on how it opened); may be empty
+    pub detail: String,
+    /// What a closed gate does
+    pub policy: GatePolicy,
+}
+
+/// Health verdict of a set of gates.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    /// Every required and degrade gate is open
+    Serving,
+    /// Degrade gates are closed, described by the reason
+    Degraded(String),
+    /// A required gate is closed
+    NotServing,
+}
+
+/// Combine gate states under their policies.
+pub fn verdict(gates: &[GateStatus]) -> Verdict {
+    let closed = |policy| gates.iter().filter(move |g| !g.open && g.policy == policy);
+    if closed(GatePolicy::Required).next().is_some() {
+        return Verdict::NotServing;
+    }
+    let degraded: Vec<String> = closed(GatePolicy::Degrade)
+        .map(|g| match g.detail.as_str() {
+            "" => format!("{} closed", g.gate.name()),
+            detail => format!("{} closed: {}", g.gate.name(), detail),
+        })
+        .collect();
+    if degraded.is_empty() {
+        Verdict::Serving
+    } else {
+        Verdict::Degraded(degraded.join("; "))
+    }
+}
+
+/// Gates opened by startup tasks, shared with the health service.
+#[derive(Debug)]
+pub struct Readiness {
+    policy: ReadinessPolicy,
+    gates: Mutex<[Option<(bool, String)>; 5]>,
+}
+
+impl Readiness {
+    /// Readiness under `policy`, with every task-owned gate unset.
+    pub fn new(policy: ReadinessPolicy) -> Self {
+        Self {
+            policy,
+            gates: Mutex::new(Default::default()),
+        }
+    }
+
+    /// The policy gates are combined by.
+    pub fn policy(&self) -> ReadinessPolicy {
+        self.policy
+    }
+
+    /// Close `gate` until a task opens it.
+    pub fn close(&self, gate: Gate, detail: impl Into<String>) {
+        self.set(gate, false, detail.into());
+    }
+
+    /// Open `gate`.
+    pub fn open(&self, gate: Gate, detail: impl Into<String>) {
+        self.set(gate, true, detail.into());
+    }
+
+    /// State set by a task for `gate`; None when no task owns it.
+    pub fn get(&self, gate: Gate) -> Option<(bool, String)> {
+        self.gates.lock().unwrap_or_else(|e| e.into_inner())[gate.index()].clone()
+    }
+
+    fn set(&self, gate: Gate, open: bool, detail: String) {
+        self.gates.lock().unwrap_or_else(|e| e.into_inner())[gate.index()] = Some((open, detail));
+        metrics::set_readiness_gate(gate.name(), open);
+    }
+}
+
+impl Default for Readiness {
+    fn default() -> Self {
+        Self::new(ReadinessPolicy::default())
+    }
+}
+
+/// Run each of `queries` (empty = [`WARMUP_QUERY`]) as a search and a
+/// context-only hybrid ask, which embeds the query, then open
+/// `warmup_done`. The gate opens even when queries fail; failures are noted
+/// in its detail.
+pub async fn warm_up(searcher: Arc<dyn Searcher>, readiness: Arc<Readiness>, queries: Vec<String>) {
+    let queries = if queries.is_empty() {
+        vec![WARMUP_QUERY.to_string()]
+    } else {
+        queries
+    };
+    let started = Instant::now();
+    let mut failures = Vec::new();
+    for (i, query) in queries.iter().enumerate() {
+        readiness.close(
+            Gate::WarmupDone,
+            format!("running warm-up query {} of {}", i + 1, queries.len()),
+        );
+        if let Err(e) = warm_query(&*searcher, query).await {
+            warn!(error = %e, "Warm-up query failed");
+            failures.push(e);
+        }
+    }
+
+    info!(
+        queries = queries.len(),
+        failed = failures.len(),
+        took_ms = started.elapsed().as_millis() as u64,
+        "Warm-up queries finished"
+    );
+    match failures.first() {
+        None => readiness.open(Gate::WarmupDone, ""),
+        Some(first) => readiness.open(
+            Gate::WarmupDone,
+            format!(
+                "{} of {} warm-up queries failed: {}",
+                failures.len(),
+                queries.len(),
+                first
+            ),
+        ),
+    }
+}
+
+async fn warm_query(searcher: &dyn Searcher, query: &str) -> Result<(), String> {
+    searcher
+        .search(SearchRequest::new(
+            query,
+            WARMUP_TOP_K,
+            WARMUP_SNIPPET_CHARS,
+        ))
+        .await
+        .map_err(|e| e.to_string())?;
+    searcher
+        .ask(AskRequest {
+            question: query.to_string(),
+            use_llm: false,
+            top_k: WARMUP_TOP_K,
+            filters: HashMap::new(),
+            start: 0,
+            end: 0,
+            snippet_chars: WARMUP_SNIPPET_CHARS,
+            mode: AskMode::Hybrid,
+            uri: None,
+            cursor: None,
+            as_of_frame: None,
+            as_of_ts: None,
+            adaptive: None,
+            acl: None,
+        })
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Warm-up queries from `WARMUP_QUERIES` (comma-separated) followed by the
+/// lines of `WARMUP_QUERIES_FILE`, skipping blank lines and `#` comments.
+pub fn warmup_queries(list: Option<&str>, file: Option<&str>) -> Result<Vec<String>, String> {
+    let mut queries: Vec<String> = list
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|q| !q.is_empty())
+        .map(String::from)
+        .collect();
+    if let Some(path) = file {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("failed to read warm-up queries {}: {}", path, e))?;
+        queries.extend(
+            text.lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .map(String::from),
+        );
+    }
+    Ok(queries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(gate: Gate, open: bool, policy: GatePolicy) -> GateStatus {
+        GateStatus {
+            gate,
+            open,
+            detail: String::new(),
+            policy,
+        }
+    }
+
+    #[test]
+    fn test_parses_policy_overrides() {
+        let policy = ReadinessPolicy::parse("llm_healthy=degrade, warmup_done=IGNORE").unwrap();
+        assert_eq!(policy.get(Gate::IndexLoaded), GatePolicy::Required);
+        assert_eq!(policy.get(Gate::WarmupDone), GatePolicy::Ignore);
+        assert_eq!(policy.get(Gate::LlmHealthy), GatePolicy::Degrade);
+        assert_eq!(
+            ReadinessPolicy::parse("").unwrap(),
+            ReadinessPolicy::default()
+        );
+
+        assert!(ReadinessPolicy::parse("cache=required")
+            .unwrap_err()
+            .contains("index_loaded"));
+        assert!(ReadinessPolicy::parse("llm_healthy").is_err());
+        assert!(ReadinessPolicy::parse("llm_healthy=maybe").is_err());
+    }
+
+    #[test]
+    fn test_combines_gates_by_policy() {
+        let gates = [
+            status(Gate::IndexLoaded, true, GatePolicy::Required),
+            status(Gate::LlmHealthy, false, GatePolicy::Degrade),
+            status(Gate::CachesRestored, false, GatePolicy::Ignore),
+        ];
+        assert_eq!(
+            verdict(&gates),
+            Verdict::Degraded("llm_healthy closed".to_string())
+        );
+        assert_eq!(verdict(&gates[..1]), Verdict::Serving);
+
+        let mut gates = gates.to_vec();
+        gates[0].open = false;
+        assert_eq!(verdict(&gates), Verdict::NotServing);
+    }
+
+    #[test]
+    fn test_tracks_task_gates() {
+        let readiness = Readiness::default();
+        assert_eq!(readiness.get(Gate::WarmupDone), None);
+        readiness.close(Gate::WarmupDone, "running");
+        assert_eq!(
+            readiness.get(Gate::WarmupDone),
+            Some((false, "running".to_string()))
+        );
+        readiness.open(Gate::WarmupDone, "");
+        assert_eq!(readiness.get(Gate::WarmupDone), Some((true, String::new())));
+    }
+
+    #[tokio::test]
+    async fn test_warm_up_opens_gate() {
+        let readiness = Arc::new(Readiness::default());
+        readiness.close(Gate::WarmupDone, "running");
+        warm_up(
+            Arc::new(crate::memvid::MockSearcher::new()),
+            Arc::clone(&readiness),
+            vec!["Rust experience".to_string(), "leadership".to_string()],
+        )
+        .await;
+        assert_eq!(readiness.get(Gate::WarmupDone), Some((true, String::new())));
+    }
+
+    #[test]
+    fn test_reads_warmup_queries() {
+        let path = std::env::temp_dir().join(format!("memvid-warmup-{}.txt", std::process::id()));
+        std::fs::write(&path, "# common\nWhat are your skills?\n\n").unwrap();
+        let queries = warmup_queries(Some("rust, kafka ,"), Some(path.to_str().unwrap())).unwrap();
+        assert_eq!(queries, ["rust", "kafka", "What are your skills?"]);
+        assert!(warmup_queries(None, None).unwrap().is_empty());
+        assert!(warmup_queries(None, Some("/nonexistent/queries.txt")).is_err());
+        std::fs::remove_file(path).ok();
+    }
+}
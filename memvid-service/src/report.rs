@@ -0,0 +1,252 @@
+//! Scheduled analytics digest.
+//!
+//! On `ANALYTICS_REPORT_SCHEDULE` ("daily", "weekly" or a cron expression,
+//! in UTC) the query statistics collected since the previous report (see
+//! [`QueryStats`]) are rendered as JSON or Markdown and written to
+//! `ANALYTICS_REPORT_PATH` and/or posted to `ANALYTICS_REPORT_WEBHOOK`, so
+//! the resume owner gets a digest without opening Grafana. Delivery is
+//! best-effort: failures are logged and the next period starts regardless.
+
+use chrono::Utc;
+use serde::Serialize;
+use std::fmt::Write as _;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tracing::{error, info, warn};
+
+use crate::crash::post_json;
+use crate::grpc::{QueryCount, QueryStats, QuerySummary};
+use crate::schedule::CronSchedule;
+
+/// Queries listed per ranking in a report.
+pub const REPORT_TOP_QUERIES: usize = 10;
+
+/// Parse a report schedule: "daily" (midnight UTC), "weekly" (Monday
+/// midnight UTC) or a five-field cron expression.
+pub fn parse_report_schedule(value: &str) -> Result<CronSchedule, String> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "daily" => CronSchedule::parse("0 0 * * *"),
+        "weekly" => CronSchedule::parse("0 0 * * 1"),
+        _ => CronSchedule::parse(value),
+    }
+}
+
+/// File format of a report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Json,
+    Markdown,
+}
+
+impl ReportFormat {
+    /// Parse "json" or "markdown" (also "md").
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "json" => Ok(Self::Json),
+            "markdown" | "md" => Ok(Self::Markdown),
+            other => Err(format!("expected json or markdown, got '{}'", other)),
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Markdown => "md",
+        }
+    }
+}
+
+/// Webhook payload: the summary plus its Markdown rendering, which chat
+/// relays can post as is.
+#[derive(Debug, Serialize)]
+struct WebhookReport<'a> {
+    #[serde(flatten)]
+    summary: &'a QuerySummary,
+    text: String,
+}
+
+/// Writes and posts analytics reports.
+#[derive(Debug, Clone)]
+pub struct AnalyticsReporter {
+    format: ReportFormat,
+    path: Option<PathBuf>,
+    webhook: Option<String>,
+}
+
+impl AnalyticsReporter {
+    /// Write reports in `format` into the directory `path` and post them to
+    /// `webhook`; either may be None.
+    pub fn new(format: ReportFormat, path: Option<PathBuf>, webhook: Option<String>) -> Self {
+        Self {
+            format,
+            path,
+            webhook,
+        }
+    }
+
+    /// Render `summary` in the configured format.
+    pub fn render(&self, summary: &QuerySummary) -> String {
+        match self.format {
+            ReportFormat::Json => {
+                serde_json::to_string_pretty(summary).unwrap_or_else(|_| "{}".to_string())
+            }
+            ReportFormat::Markdown => render_markdown(summary),
+        }
+    }
+
+    /// Write `summary` to the report directory and post it to the webhook.
+    pub async fn deliver(&self, summary: &QuerySummary) {
+        if let Some(dir) = &self.path {
+            let file = dir.join(format!(
+                "analytics-{}.{}",
+                summary.period_end.format("%Y-%m-%dT%H%M"),
+                self.format.extension()
+            ));
+            let written = std::fs::create_dir_all(dir)
+                .and_then(|()| std::fs::write(&file, self.render(summary)));
+            match written {
+                Ok(()) => info!(path = %file.display(), "Wrote analytics report"),
+                Err(e) => {
+                    warn!(error = %e, path = %file.display(), "Failed to write analytics report")
+                }
+            }
+        }
+
+        let Some(url) = self.webhook.clone() else {
+            return;
+        };
+        let json = serde_json::to_string(&WebhookReport {
+            summary,
+            text: render_markdown(summary),
+        })
+        .unwrap_or_else(|_| "{}".to_string());
+        let delivered = tokio::task::spawn_blocking(move || post_json(&url, &json)).await;
+        match delivered {
+            Ok(Ok(())) => info!("Posted analytics report to webhook"),
+            Ok(Err(e)) => warn!(error = %e, "Failed to post analytics report to webhook"),
+            Err(e) => warn!(error = %e, "Analytics report delivery task failed"),
+        }
+    }
+}
+
+/// Markdown digest of `summary`.
+pub fn render_markdown(summary: &QuerySummary) -> String {
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "# Resume assistant analytics\n\n{} to {} (UTC)\n",
+        summary.period_start.format("%Y-%m-%d %H:%M"),
+        summary.period_end.format("%Y-%m-%d %H:%M")
+    );
+    let _ = writeln!(out, "- Queries: {}", summary.queries);
+    let _ = writeln!(
+        out,
+        "- Zero-result queries: {}",
+        summary.zero_result_queries
+    );
+    let _ = writeln!(
+        out,
+        "- p95 latency: {}",
+        summary
+            .p95_latency_ms
+            .map_or_else(|| "n/a".to_string(), |ms| format!("{} ms", ms))
+    );
+    write_ranking(&mut out, "Top questions", &summary.top_questions);
+    write_ranking(
+        &mut out,
+        "Zero-result queries",
+        &summary.top_zero_result_queries,
+    );
+    out
+}
+
+fn write_ranking(out: &mut String, heading: &str, queries: &[QueryCount]) {
+    let _ = writeln!(out, "\n## {}\n", heading);
+    if queries.is_empty() {
+        let _ = writeln!(out, "None.");
+        return;
+    }
+    let _ = writeln!(out, "| Count | Query |\n| ----: | ----- |");
+    for entry in queries {
+        // Keep user text from breaking the table
+        let query = entry.query.replace('|', "\\|");
+        let _ = writeln!(out, "| {} | {} |", entry.count, query);
+    }
+}
+
+/// Deliver a report of `stats` at every scheduled time, forever.
+pub async fn run_analytics_reports(
+    schedule: CronSchedule,
+    stats: Arc<QueryStats>,
+    reporter: AnalyticsReporter,
+) {
+    loop {
+        let now = Utc::now();
+        let Some(next) = schedule.next_after(now) else {
+            error!("Analytics report schedule never fires, stopping reports");
+            return;
+        };
+        info!(next = %next, "Next analytics report");
+        tokio::time::sleep((next - now).to_std().unwrap_or_default()).await;
+
+        let summary = stats.take_summary(REPORT_TOP_QUERIES);
+        info!(
+            queries = summary.queries,
+            zero_result_queries = summary.zero_result_queries,
+            "Generating analytics report"
+        );
+        reporter.deliver(&summary).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn summary() -> QuerySummary {
+        let stats = QueryStats::default();
+        stats.record("What about Rust?", 2, Duration::from_millis(12));
+        stats.record("Kotlin | Java", 0, Duration::from_millis(30));
+        stats.take_summary(REPORT_TOP_QUERIES)
+    }
+
+    #[test]
+    fn test_parse_schedule_and_format() {
+        let weekly = parse_report_schedule("Weekly").unwrap();
+        assert_eq!(weekly, CronSchedule::parse("0 0 * * 1").unwrap());
+        assert!(parse_report_schedule("0 8 * * *").is_ok());
+        assert!(parse_report_schedule("hourly-ish").is_err());
+
+        assert_eq!(ReportFormat::parse("md").unwrap(), ReportFormat::Markdown);
+        assert!(ReportFormat::parse("pdf").is_err());
+    }
+
+    #[test]
+    fn test_markdown_report() {
+        let markdown = render_markdown(&summary());
+        assert!(markdown.contains("- Queries: 2"));
+        assert!(markdown.contains("- p95 latency: 30 ms"));
+        assert!(markdown.contains("| 1 | what about rust |"));
+        assert!(markdown.contains("| 1 | kotlin \\| java |"));
+    }
+
+    #[tokio::test]
+    async fn test_writes_report_file() {
+        let dir = std::env::temp_dir().join(format!("memvid-reports-{}", std::process::id()));
+        let reporter = AnalyticsReporter::new(ReportFormat::Json, Some(dir.clone()), None);
+        reporter.deliver(&summary()).await;
+
+        let files: Vec<PathBuf> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        assert_eq!(files.len(), 1);
+        let json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&files[0]).unwrap()).unwrap();
+        assert_eq!(json["queries"], 2);
+        assert_eq!(json["top_zero_result_queries"][0]["query"], "kotlin | java");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,645 @@
+//! Settings that can change while the service runs.
+//!
+//! [`RuntimeConfig`] holds the settings a fleet is tuned with centrally: the
+//! per-client rate limit, LLM synthesis, and the default `top_k` and
+//! `snippet_chars`. Consumers hold a [`RuntimeConfigReceiver`] and read the
+//! current value per request, so an update applies from the next request on.
+//!
+//! With `CONFIG_SOURCE` set to `consul://host:8500/<prefix>` or
+//! `etcd://host:2379/<prefix>`, [`watch_config_source`] reads the keys below
+//! the prefix (e.g. `memvid/rate_limit_per_minute`) and publishes every
+//! change. Consul is read with blocking queries, etcd through its JSON
+//! gateway every poll interval. Missing keys fall back to the environment
+//! configuration; unknown keys and invalid values are logged and ignored,
+//! and while the store is unreachable the current settings stay in effect.
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use http_body_util::{BodyExt, Full};
+use hyper::body::Bytes;
+use hyper_util::client::legacy::Client;
+use hyper_util::rt::TokioExecutor;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Duration;
+use tokio::sync::watch;
+use tracing::{info, warn};
+
+use crate::config::{Config, DEMO_RATE_LIMIT_PER_MINUTE};
+use crate::egress::{self, EgressConnector};
+use crate::metrics;
+
+/// Receiving end of the runtime config channel.
+pub type RuntimeConfigReceiver = watch::Receiver<RuntimeConfig>;
+
+/// `top_k` of requests that leave it unset, unless configured otherwise.
+pub const DEFAULT_TOP_K: i32 = 5;
+
+/// Largest `top_k` served, unless configured otherwise.
+pub const DEFAULT_MAX_TOP_K: i32 = 20;
+
+/// `snippet_chars` of requests that leave it unset, unless configured
+/// otherwise.
+pub const DEFAULT_SNIPPET_CHARS: i32 = 200;
+
+/// Largest `snippet_chars` served, unless configured otherwise.
+pub const DEFAULT_MAX_SNIPPET_CHARS: i32 = 1000;
+
+/// Shortest `snippet_chars` served; shorter requests are raised to it.
+pub const MIN_SNIPPET_CHARS: i32 = 50;
+
+/// Shortest delay between two reads of the config store.
+const MIN_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Settings applied live.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RuntimeConfig {
+    /// Requests per minute per client on the query APIs (0 = unlimited)
+    pub rate_limit_per_minute: u32,
+    /// Whether Ask may synthesize answers with the LLM
+    pub llm_synthesis: bool,
+    /// `top_k` used when a request leaves it unset
+    pub default_top_k: i32,
+    /// `snippet_chars` used when a request leaves it unset
+    pub default_snippet_chars: i32,
+    /// Largest `top_k` served
+    pub max_top_k: i32,
+    /// Largest `snippet_chars` served
+    pub max_snippet_chars: i32,
+}
+
+impl Default for RuntimeConfig {
+    fn default() -> Self {
+        Self {
+            rate_limit_per_minute: 0,
+            llm_synthesis: true,
+            default_top_k: DEFAULT_TOP_K,
+            default_snippet_chars: DEFAULT_SNIPPET_CHARS,
+            max_top_k: DEFAULT_MAX_TOP_K,
+            max_snippet_chars: DEFAULT_MAX_SNIPPET_CHARS,
+        }
+    }
+}
+
+impl RuntimeConfig {
+    /// Initial settings from the environment configuration.
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            rate_limit_per_minute: config.rate_limit_per_minute,
+            llm_synthesis: config.llm_synthesis,
+            default_top_k: config.default_top_k,
+            default_snippet_chars: config.default_snippet_chars,
+            max_top_k: config.max_top_k,
+            max_snippet_chars: config.max_snippet_chars,
+        }
+    }
+
+    /// `top_k` a request is served: the default when unset (0 or
+    /// negative), and never more than `max_top_k`.
+    pub fn top_k(&self, requested: i32) -> i32 {
+        let top_k = if requested <= 0 {
+            self.default_top_k
+        } else {
+            requested
+        };
+        top_k.min(self.max_top_k)
+    }
+
+    /// `snippet_chars` a request is served: the default when unset (0 or
+    /// negative), within [`MIN_SNIPPET_CHARS`] and `max_snippet_chars`.
+    pub fn snippet_chars(&self, requested: i32) -> i32 {
+        let snippet_chars = if requested <= 0 {
+            self.default_snippet_chars
+        } else {
+            requested
+        };
+        snippet_chars.clamp(
+            MIN_SNIPPET_CHARS,
+            self.max_snippet_chars.max(MIN_SNIPPET_CHARS),
+        )
+    }
+
+    /// A receiver that always sees these settings.
+    pub fn fixed(self) -> RuntimeConfigReceiver {
+        watch::channel(self).1
+    }
+
+    /// These settings with `values` (setting name to value) applied.
+    ///
+    /// Returns the settings and a warning per key that was not applied.
+    pub fn with_overrides(&self, values: &BTreeMap<String, String>) -> (Self, Vec<String>) {
+        let mut config = self.clone();
+        let mut warnings = Vec::new();
+        for (key, value) in values {
+            let value = value.trim();
+            let applied = match key.as_str() {
+                "rate_limit_per_minute" => parse(value, &mut config.rate_limit_per_minute),
+                "llm_synthesis" => parse_flag(value, &mut config.llm_synthesis),
+                "default_top_k" => parse_positive(value, &mut config.default_top_k),
+                "default_snippet_chars" => parse_positive(value, &mut config.default_snippet_chars),
+                "max_top_k" => parse_positive(value, &mut config.max_top_k),
+                "max_snippet_chars" => parse_positive(value, &mut config.max_snippet_chars),
+                _ => {
+                    warnings.push(format!("unknown runtime setting '{}'", key));
+                    continue;
+                }
+            };
+            if !applied {
+                warnings.push(format!("invalid value '{}' for '{}'", value, key));
+            }
+        }
+        (config, warnings)
+    }
+
+    /// Keep the limits of the public demo profile (see
+    /// [`Config::apply_public_demo`]) whatever the store says.
+    pub fn constrain_public_demo(&mut self) {
+        if self.rate_limit_per_minute == 0
+            || self.rate_limit_per_minute > DEMO_RATE_LIMIT_PER_MINUTE
+        {
+            self.rate_limit_per_minute = DEMO_RATE_LIMIT_PER_MINUTE;
+        }
+        self.llm_synthesis = false;
+    }
+}
+
+fn parse<T: std::str::FromStr>(value: &str, target: &mut T) -> bool {
+    value.parse().map(|v| *target = v).is_ok()
+}
+
+fn parse_flag(value: &str, target: &mut bool) -> bool {
+    match value.to_ascii_lowercase().as_str() {
+        "true" | "1" => *target = true,
+        "false" | "0" => *target = false,
+        _ => return false,
+    }
+    true
+}
+
+fn parse_positive(value: &str, target: &mut i32) -> bool {
+    match value.parse::<i32>() {
+        Ok(v) if v > 0 => {
+            *target = v;
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Key-value store holding runtime settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigBackend {
+    /// Consul KV (HTTP API)
+    Consul,
+    /// etcd v3 (JSON gateway)
+    Etcd,
+}
+
+/// Where runtime settings are read from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigSource {
+    /// Store type
+    pub backend: ConfigBackend,
+    /// `host:port` of the store's HTTP API
+    pub address: String,
+    /// Key prefix, without leading or trailing slashes
+    pub prefix: String,
+}
+
+impl ConfigSource {
+    /// Parse `consul://host:port/prefix` or `etcd://host:port/prefix`.
+    pub fn parse(url: &str) -> Result<Self, String> {
+        let (backend, rest) = if let Some(rest) = url.strip_prefix("consul://") {
+            (ConfigBackend::Consul, rest)
+        } else if let Some(rest) = url.strip_prefix("etcd://") {
+            (ConfigBackend::Etcd, rest)
+        } else {
+            return Err(format!(
+                "expected a consul:// or etcd:// URL, got '{}'",
+                url
+            ));
+        };
+        let (address, prefix) = rest.split_once('/').unwrap_or((rest, ""));
+        let prefix = prefix.trim_matches('/');
+        if address.is_empty() {
+            return Err(format!("missing host in '{}'", url));
+        }
+        if prefix.is_empty() {
+            return Err(format!("missing key prefix in '{}'", url));
+        }
+        Ok(Self {
+            backend,
+            address: address.to_string(),
+            prefix: prefix.to_string(),
+        })
+    }
+}
+
+/// Settings read from the store.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Snapshot {
+    /// Setting name (key below the prefix) to value
+    values: BTreeMap<String, String>,
+    /// Consul index for the next blocking query
+    index: Option<u64>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ConsulEntry {
+    key: String,
+    value: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct EtcdRange {
+    #[serde(default)]
+    kvs: Vec<EtcdEntry>,
+}
+
+#[derive(Deserialize)]
+struct EtcdEntry {
+    key: String,
+    #[serde(default)]
+    value: String,
+}
+
+/// Reads runtime settings from a [`ConfigSource`].
+struct StoreClient {
+    source: ConfigSource,
+    token: Option<String>,
+    http: Client<EgressConnector, Full<Bytes>>,
+}
+
+impl StoreClient {
+    fn new(source: ConfigSource, token: Option<String>) -> Self {
+        Self {
+            source,
+            token,
+            http: Client::builder(TokioExecutor::new()).build(EgressConnector::new()),
+        }
+    }
+
+    /// Read all settings, waiting up to `wait` for a change past `index`
+    /// (Consul only).
+    async fn fetch(&self, index: Option<u64>, wait: Duration) -> Result<Snapshot, String> {
+        let mut request = match self.source.backend {
+            ConfigBackend::Consul => {
+                let mut uri = format!(
+                    "http://{}/v1/kv/{}/?recurse=true",
+                    self.source.address, self.source.prefix
+                );
+                if let Some(index) = index {
+                    uri.push_str(&format!("&index={}&wait={}s", index, wait.as_secs().max(1)));
+                }
+                let mut builder = hyper::Request::get(uri);
+                if let Some(token) = &self.token {
+                    builder = builder.header("X-Consul-Token", token);
+                }
+                builder.body(Full::default())
+            }
+            ConfigBackend::Etcd => {
+                let prefix = format!("{}/", self.source.prefix);
+                let body = serde_json::json!({
+                    "key": STANDARD.encode(&prefix),
+                    "range_end": STANDARD.encode(prefix_end(prefix.as_bytes())),
+                });
+                let mut builder =
+                    hyper::Request::post(format!("http://{}/v3/kv/range", self.source.address))
+                        .header("Content-Type", "application/json");
+                if let Some(token) = &self.token {
+                    builder = builder.header("Authorization", token);
+                }
+                builder.body(Full::from(body.to_string()))
+            }
+        }
+        .map_err(|e| format!("invalid request: {}", e))?;
+        let host = self.source.address.split(':').next().unwrap_or_default();
+        if let Some(authorization) = egress::current().proxy_authorization(host) {
+            if let Ok(value) = authorization.parse() {
+                request.headers_mut().insert("Proxy-Authorization", value);
+            }
+        }
+
+        // Leave room for the blocking query to time out on the server first
+        let response =
+            tokio::time::timeout(wait + Duration::from_secs(10), self.http.request(request))
+                .await
+                .map_err(|_| "request timed out".to_string())?
+                .map_err(|e| e.to_string())?;
+        let status = response.status();
+        let consul_index = response
+            .headers()
+            .get("X-Consul-Index")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse().ok());
+        let body = response
+            .into_body()
+            .collect()
+            .await
+            .map_err(|e| e.to_string())?
+            .to_bytes();
+
+        match self.source.backend {
+            // Consul answers 404 while no key exists below the prefix
+            ConfigBackend::Consul if status == hyper::StatusCode::NOT_FOUND => Ok(Snapshot {
+                values: BTreeMap::new(),
+                index: consul_index,
+            }),
+            _ if !status.is_success() => Err(format!("store responded {}", status)),
+            ConfigBackend::Consul => Ok(Snapshot {
+                values: parse_consul(&body, &self.source.prefix)?,
+                index: consul_index,
+            }),
+            ConfigBackend::Etcd => Ok(Snapshot {
+                values: parse_etcd(&body, &self.source.prefix)?,
+                index: None,
+            }),
+        }
+    }
+}
+
+/// Smallest key after every key starting with `prefix` (etcd `range_end`).
+fn prefix_end(prefix: &[u8]) -> Vec<u8> {
+    let mut end = prefix.to_vec();
+    while let Some(last) = end.pop() {
+        if last < u8::MAX {
+            end.push(last + 1);
+            return end;
+        }
+    }
+    // All 0xff: range to the end of the keyspace
+    vec![0]
+}
+
+/// Setting name of `key`: the part below `prefix`, when it is a direct child.
+fn setting_name(key: &str, prefix: &str) -> Option<String> {
+    let name = key.strip_prefix(prefix)?.strip_prefix('/')?;
+    (!name.is_empty() && !name.contains('/')).then(|| name.to_string())
+}
+
+fn decode_value(value: &str) -> Result<String, String> {
+    let bytes = STANDARD
+        .decode(value)
+        .map_err(|e| format!("invalid base64 value: {}", e))?;
+    String::from_utf8(bytes).map_err(|_| "value is not UTF-8".to_string())
+}
+
+fn parse_consul(body: &[u8], prefix: &str) -> Result<BTreeMap<String, String>, String> {
+    let entries: Vec<ConsulEntry> =
+        serde_json::from_slice(body).map_err(|e| format!("invalid Consul response: {}", e))?;
+    let mut values = BTreeMap::new();
+    for entry in entries {
+        // Folder entries carry no value
+        if let (Some(name), Some(value)) = (setting_name(&entry.key, prefix), entry.value) {
+            values.insert(name, decode_value(&value)?);
+        }
+    }
+    Ok(values)
+}
+
+fn parse_etcd(body: &[u8], prefix: &str) -> Result<BTreeMap<String, String>, String> {
+    let range: EtcdRange =
+        serde_json::from_slice(body).map_err(|e| format!("invalid etcd response: {}", e))?;
+    let mut values = BTreeMap::new();
+    for entry in range.kvs {
+        if let Some(name) = setting_name(&decode_value(&entry.key)?, prefix) {
+            values.insert(name, decode_value(&entry.value)?);
+        }
+    }
+    Ok(values)
+}
+
+/// Publish settings from `source` on `sender` until every receiver is gone.
+///
+/// `base` is the environment configuration keys fall back to. With
+/// `public_demo`, the demo profile's limits are kept whatever the store says.
+pub async fn watch_config_source(
+    source: ConfigSource,
+    token: Option<String>,
+    poll_interval: Duration,
+    base: RuntimeConfig,
+    public_demo: bool,
+    sender: watch::Sender<RuntimeConfig>,
+) {
+    let poll_interval = poll_interval.max(MIN_POLL_INTERVAL);
+    info!(
+        backend = ?source.backend,
+        address = %source.address,
+        prefix = %source.prefix,
+        "Watching runtime config"
+    );
+    let client = StoreClient::new(source, token);
+    let mut index = None;
+
+    while !sender.is_closed() {
+        let started = tokio::time::Instant::now();
+        match client.fetch(index, poll_interval).await {
+            Ok(snapshot) => {
+                // A lower index means the store was restored; start over
+                index = match (index, snapshot.index) {
+                    (Some(previous), Some(next)) if next < previous => None,
+                    (_, next) => next,
+                };
+                let (mut config, warnings) = base.with_overrides(&snapshot.values);
+                for warning in warnings {
+                    warn!(warning = %warning, "Ignoring runtime setting");
+                }
+                if public_demo {
+                    config.constrain_public_demo();
+                }
+                sender.send_if_modified(|current| {
+                    if *current == config {
+                        return false;
+                    }
+                    info!(config = ?config, "Applied runtime config");
+                    metrics::record_runtime_config_update("applied");
+                    *current = config;
+                    true
+                });
+            }
+            Err(e) => {
+                warn!(error = %e, "Failed to read runtime config, keeping current settings");
+                metrics::record_runtime_config_update("failed");
+                index = None;
+                tokio::time::sleep(poll_interval).await;
+                continue;
+            }
+        }
+
+        // Blocking queries wait on the server; plain reads wait here
+        let next = started
+            + if index.is_some() {
+                MIN_POLL_INTERVAL
+            } else {
+                poll_interval
+            };
+        tokio::time::sleep_until(next).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::extract::Query;
+    use axum::http::HeaderMap;
+    use axum::routing::get;
+    use axum::Router;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn test_parse_source() {
+        assert_eq!(
+            ConfigSource::parse("consul://localhost:8500/memvid/prod/").unwrap(),
+            ConfigSource {
+                backend: ConfigBackend::Consul,
+                address: "localhost:8500".to_string(),
+                prefix: "memvid/prod".to_string(),
+            }
+        );
+        assert_eq!(
+            ConfigSource::parse("etcd://etcd:2379/memvid")
+                .unwrap()
+                .backend,
+            ConfigBackend::Etcd
+        );
+        assert!(ConfigSource::parse("http://localhost:8500/memvid").is_err());
+        assert!(ConfigSource::parse("consul://localhost:8500").is_err());
+        assert!(ConfigSource::parse("etcd:///memvid").is_err());
+    }
+
+    #[test]
+    fn test_overrides_skip_invalid_values() {
+        let values = BTreeMap::from([
+            ("rate_limit_per_minute".to_string(), "120".to_string()),
+            ("llm_synthesis".to_string(), "false".to_string()),
+            ("default_top_k".to_string(), "-1".to_string()),
+            ("colour".to_string(), "blue".to_string()),
+        ]);
+        let (config, warnings) = RuntimeConfig::default().with_overrides(&values);
+
+        assert_eq!(config.rate_limit_per_minute, 120);
+        assert!(!config.llm_synthesis);
+        assert_eq!(config.default_top_k, 5);
+        assert_eq!(warnings.len(), 2);
+    }
+
+    #[test]
+    fn test_request_limits() {
+        let config = RuntimeConfig {
+            default_top_k: 3,
+            max_top_k: 10,
+            default_snippet_chars: 120,
+            max_snippet_chars: 400,
+            ..RuntimeConfig::default()
+        };
+        assert_eq!(config.top_k(0), 3);
+        assert_eq!(config.top_k(-4), 3);
+        assert_eq!(config.top_k(7), 7);
+        assert_eq!(config.top_k(50), 10);
+        assert_eq!(config.snippet_chars(0), 120);
+        assert_eq!(config.snippet_chars(10), MIN_SNIPPET_CHARS);
+        assert_eq!(config.snippet_chars(5000), 400);
+    }
+
+    #[test]
+    fn test_public_demo_limits_hold() {
+        let values = BTreeMap::from([
+            ("rate_limit_per_minute".to_string(), "0".to_string()),
+            ("llm_synthesis".to_string(), "true".to_string()),
+        ]);
+        let (mut config, _) = RuntimeConfig::default().with_overrides(&values);
+        config.constrain_public_demo();
+
+        assert_eq!(config.rate_limit_per_minute, DEMO_RATE_LIMIT_PER_MINUTE);
+        assert!(!config.llm_synthesis);
+    }
+
+    #[test]
+    fn test_parse_store_responses() {
+        let consul = format!(
+            r#"[{{"Key":"memvid/","Value":null}},
+                {{"Key":"memvid/default_top_k","Value":"{}"}},
+                {{"Key":"memvid/nested/key","Value":"{}"}}]"#,
+            STANDARD.encode("8"),
+            STANDARD.encode("x"),
+        );
+        assert_eq!(
+            parse_consul(consul.as_bytes(), "memvid").unwrap(),
+            BTreeMap::from([("default_top_k".to_string(), "8".to_string())])
+        );
+
+        let etcd = format!(
+            r#"{{"header":{{"revision":"7"}},"kvs":[{{"key":"{}","value":"{}"}}]}}"#,
+            STANDARD.encode("memvid/llm_synthesis"),
+            STANDARD.encode("false"),
+        );
+        assert_eq!(
+            parse_etcd(etcd.as_bytes(), "memvid").unwrap(),
+            BTreeMap::from([("llm_synthesis".to_string(), "false".to_string())])
+        );
+        // No keys yet
+        assert!(parse_etcd(br#"{"header":{}}"#, "memvid")
+            .unwrap()
+            .is_empty());
+
+        assert_eq!(prefix_end(b"memvid/"), b"memvid0".to_vec());
+    }
+
+    #[tokio::test]
+    async fn test_watch_applies_consul_changes() {
+        let rate = Arc::new(Mutex::new("60".to_string()));
+        let app = {
+            let rate = Arc::clone(&rate);
+            Router::new().route(
+                "/v1/kv/memvid/",
+                get(move |Query(query): Query<BTreeMap<String, String>>| {
+                    let rate = Arc::clone(&rate);
+                    async move {
+                        assert_eq!(query.get("recurse").map(String::as_str), Some("true"));
+                        let value = STANDARD.encode(rate.lock().unwrap().as_str());
+                        let mut headers = HeaderMap::new();
+                        headers.insert("X-Consul-Index", "1".parse().unwrap());
+                        (
+                            headers,
+                            format!(
+                                r#"[{{"Key":"memvid/rate_limit_per_minute","Value":"{}"}}]"#,
+                                value
+                            ),
+                        )
+                    }
+                }),
+            )
+        };
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let (sender, mut receiver) = watch::channel(RuntimeConfig::default());
+        let source = ConfigSource::parse(&format!("consul://{}/memvid", address)).unwrap();
+        let watcher = tokio::spawn(watch_config_source(
+            source,
+            None,
+            Duration::from_secs(1),
+            RuntimeConfig::default(),
+            false,
+            sender,
+        ));
+
+        receiver.changed().await.unwrap();
+        assert_eq!(receiver.borrow_and_update().rate_limit_per_minute, 60);
+
+        *rate.lock().unwrap() = "90".to_string();
+        tokio::time::timeout(Duration::from_secs(10), receiver.changed())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(receiver.borrow().rate_limit_per_minute, 90);
+
+        drop(receiver);
+        tokio::time::timeout(Duration::from_secs(10), watcher)
+            .await
+            .unwrap()
+            .unwrap();
+    }
+}
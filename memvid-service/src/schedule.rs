@@ -0,0 +1,357 @@
+//! Cron-style scheduling for periodic index refresh.
+//!
+//! Supports the standard five cron fields (minute, hour, day-of-month, month,
+//! day-of-week) with `*`, lists (`1,15`), ranges (`1-5`), and steps (`*/10`).
+//! Schedules are evaluated in UTC.
+//!
+//! A failed scheduled reload is retried with exponential backoff while the
+//! last good index keeps serving; the first failure and the recovery raise
+//! alerts.
+
+use chrono::{DateTime, Datelike, Days, NaiveDate, TimeDelta, Timelike, Utc};
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{error, info};
+
+use crate::alert::{Alert, AlertSender};
+use crate::memvid::{ReloadOutcome, ReloadableSearcher, Searcher};
+use crate::metrics;
+
+/// A parsed five-field cron expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    dom_restricted: bool,
+    dow_restricted: bool,
+}
+
+impl CronSchedule {
+    /// Parse a cron expression such as `"0 3 * * *"`.
+    pub fn parse(expr: &str) -> Result<Self, String> {
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(format!(
+                "expected 5 fields (minute hour day-of-month month day-of-week), got {}",
+                fields.len()
+            ));
+        }
+
+        let mut days_of_week = parse_field(fields[4], 0, 7)?;
+        // Both 0 and 7 mean Sunday
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week = (days_of_week | 1) & !(1 << 7);
+        }
+
+        Ok(Self {
+            minutes: parse_field(fields[0], 0, 59)?,
+            hours: parse_field(fields[1], 0, 23)?,
+            days_of_month: parse_field(fields[2], 1, 31)?,
+            months: parse_field(fields[3], 1, 12)?,
+            days_of_week,
+            dom_restricted: fields[2] != "*",
+            dow_restricted: fields[4] != "*",
+        })
+    }
+
+    /// Return the first matching minute strictly after `after`.
+    ///
+    /// Returns `None` if nothing matches within the next few years
+    /// (e.g. `0 0 31 2 *`).
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let mut t = after.with_second(0)?.with_nanosecond(0)? + TimeDelta::minutes(1);
+        let limit = after + TimeDelta::days(366 * 5);
+
+        while t <= limit {
+            if !bit(self.months, t.month()) {
+                let (year, month) = if t.month() == 12 {
+                    (t.year() + 1, 1)
+                } else {
+                    (t.year(), t.month() + 1)
+                };
+                t = NaiveDate::from_ymd_opt(year, month, 1)?
+                    .and_hms_opt(0, 0, 0)?
+                    .and_utc();
+                continue;
+            }
+            if !self.day_matches(t) {
+                t = (t.date_naive() + Days::new(1))
+                    .and_hms_opt(0, 0, 0)?
+                    .and_utc();
+                continue;
+            }
+            if !bit(self.hours, t.hour()) {
+                t = t.with_minute(0)? + TimeDelta::hours(1);
+                continue;
+            }
+            if !bit(self.minutes, t.minute()) {
+                t += TimeDelta::minutes(1);
+                continue;
+            }
+            return Some(t);
+        }
+        None
+    }
+
+    /// Day matching follows Vixie cron: when both day fields are restricted,
+    /// either may match.
+    fn day_matches(&self, t: DateTime<Utc>) -> bool {
+        let dom = bit(self.days_of_month, t.day());
+        let dow = bit(self.days_of_week, t.weekday().num_days_from_sunday());
+        match (self.dom_restricted, self.dow_restricted) {
+            (true, true) => dom || dow,
+            (true, false) => dom,
+            (false, true) => dow,
+            (false, false) => true,
+        }
+    }
+}
+
+fn bit(mask: u64, value: u32) -> bool {
+    mask & (1 << value) != 0
+}
+
+/// Parse one cron field into a bitmask of allowed values.
+fn parse_field(field: &str, min: u32, max: u32) -> Result<u64, String> {
+    let mut mask = 0u64;
+
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step
+                    .parse()
+                    .map_err(|_| format!("invalid step '{}' in '{}'", step, field))?;
+                if step == 0 {
+                    return Err(format!("step must be positive in '{}'", field));
+                }
+                (range, step)
+            }
+            None => (part, 1),
+        };
+
+        let (lo, hi) = if range == "*" {
+            (min, max)
+        } else if let Some((lo, hi)) = range.split_once('-') {
+            (parse_value(lo, field)?, parse_value(hi, field)?)
+        } else {
+            let value = parse_value(range, field)?;
+            // "5/10" means "from 5 to max every 10"
+            if step > 1 {
+                (value, max)
+            } else {
+                (value, value)
+            }
+        };
+
+        if lo < min || hi > max || lo > hi {
+            return Err(format!("value out of range {}-{} in '{}'", min, max, field));
+        }
+
+        for value in (lo..=hi).step_by(step as usize) {
+            mask |= 1 << value;
+        }
+    }
+
+    Ok(mask)
+}
+
+fn parse_value(value: &str, field: &str) -> Result<u32, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid value '{}' in '{}'", value, field))
+}
+
+/// Random delay in `[0, max)` used to spread reloads across replicas.
+fn jitter(max: Duration) -> Duration {
+    if max.is_zero() {
+        return Duration::ZERO;
+    }
+    let random = RandomState::new().build_hasher().finish();
+    Duration::from_millis(random % max.as_millis().max(1) as u64)
+}
+
+/// Backoff between retries of a failed scheduled reload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReloadRetry {
+    /// Delay before the first retry
+    pub initial: Duration,
+    /// Longest delay between retries
+    pub max: Duration,
+}
+
+impl ReloadRetry {
+    /// Delay before retrying after `failures` consecutive failures: doubling
+    /// from `initial`, capped at `max`.
+    pub fn delay(&self, failures: u32) -> Duration {
+        let factor = 2u32.saturating_pow(failures.saturating_sub(1));
+        self.initial.saturating_mul(factor).min(self.max)
+    }
+}
+
+/// Run scheduled reloads forever.
+///
+/// At each scheduled time (plus random jitter) the source file is re-checked
+/// and reloaded if it changed. A failed reload is retried per `retry` until
+/// it succeeds, then the schedule resumes. The outcome is recorded in
+/// metrics, and the first failure and the recovery are sent to `alerts`.
+pub async fn run_reload_schedule(
+    schedule: CronSchedule,
+    max_jitter: Duration,
+    retry: ReloadRetry,
+    searcher: Arc<ReloadableSearcher>,
+    alerts: AlertSender,
+) {
+    let mut failures = 0;
+    loop {
+        let delay = if failures > 0 {
+            let delay = retry.delay(failures);
+            info!(
+                failures,
+                delay_secs = delay.as_secs(),
+                "Retrying failed index reload"
+            );
+            delay
+        } else {
+            let now = Utc::now();
+            let Some(next) = schedule.next_after(now) else {
+                error!("Reload schedule never fires, stopping scheduled reloads");
+                return;
+            };
+            let delay = (next - now).to_std().unwrap_or_default() + jitter(max_jitter);
+            info!(next = %next, delay_secs = delay.as_secs(), "Next scheduled index reload");
+            delay
+        };
+        tokio::time::sleep(delay).await;
+
+        match searcher.reload_if_changed().await {
+            Ok(outcome) => {
+                match outcome {
+                    ReloadOutcome::Reloaded => {
+                        info!("Scheduled reload completed: index reloaded");
+                        metrics::record_scheduled_reload("reloaded");
+                    }
+                    ReloadOutcome::Unchanged => {
+                        info!("Scheduled reload completed: source unchanged");
+                        metrics::record_scheduled_reload("unchanged");
+                    }
+                }
+                if failures > 0 {
+                    let message = format!("Index reload recovered after {} failures", failures);
+                    alerts
+                        .send(Alert::new(
+                            "reload_recovered",
+                            searcher.memvid_file(),
+                            message,
+                        ))
+                        .await;
+                }
+                failures = 0;
+            }
+            Err(e) => {
+                error!(error = %e, "Scheduled reload failed, keeping current index");
+                metrics::record_scheduled_reload("failed");
+                failures += 1;
+                if failures == 1 {
+                    let message =
+                        format!("Index reload failed, serving the last good index: {}", e);
+                    alerts
+                        .send(Alert::new("reload_failed", searcher.memvid_file(), message))
+                        .await;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(y: i32, mo: u32, d: u32, h: u32, mi: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, mo, d, h, mi, 0).unwrap()
+    }
+
+    #[test]
+    fn test_daily_at_three() {
+        let schedule = CronSchedule::parse("0 3 * * *").unwrap();
+        assert_eq!(
+            schedule.next_after(at(2024, 6, 1, 2, 30)),
+            Some(at(2024, 6, 1, 3, 0))
+        );
+        assert_eq!(
+            schedule.next_after(at(2024, 6, 1, 3, 0)),
+            Some(at(2024, 6, 2, 3, 0))
+        );
+    }
+
+    #[test]
+    fn test_step_and_list_fields() {
+        let schedule = CronSchedule::parse("*/15 1,13 * * *").unwrap();
+        assert_eq!(
+            schedule.next_after(at(2024, 6, 1, 1, 16)),
+            Some(at(2024, 6, 1, 1, 30))
+        );
+        assert_eq!(
+            schedule.next_after(at(2024, 6, 1, 1, 45)),
+            Some(at(2024, 6, 1, 13, 0))
+        );
+    }
+
+    #[test]
+    fn test_day_of_week_sunday_as_seven() {
+        // 2024-06-02 is a Sunday
+        let schedule = CronSchedule::parse("30 4 * * 7").unwrap();
+        assert_eq!(
+            schedule.next_after(at(2024, 5, 30, 0, 0)),
+            Some(at(2024, 6, 2, 4, 30))
+        );
+    }
+
+    #[test]
+    fn test_month_rollover() {
+        let schedule = CronSchedule::parse("0 0 1 1 *").unwrap();
+        assert_eq!(
+            schedule.next_after(at(2024, 6, 1, 0, 0)),
+            Some(at(2025, 1, 1, 0, 0))
+        );
+    }
+
+    #[test]
+    fn test_impossible_schedule() {
+        let schedule = CronSchedule::parse("0 0 31 2 *").unwrap();
+        assert_eq!(schedule.next_after(at(2024, 1, 1, 0, 0)), None);
+    }
+
+    #[test]
+    fn test_invalid_expressions() {
+        assert!(CronSchedule::parse("0 3 * *").is_err());
+        assert!(CronSchedule::parse("60 3 * * *").is_err());
+        assert!(CronSchedule::parse("*/0 3 * * *").is_err());
+        assert!(CronSchedule::parse("a 3 * * *").is_err());
+    }
+
+    #[test]
+    fn test_jitter_bounds() {
+        assert_eq!(jitter(Duration::ZERO), Duration::ZERO);
+        for _ in 0..100 {
+            assert!(jitter(Duration::from_secs(5)) < Duration::from_secs(5));
+        }
+    }
+
+    #[test]
+    fn test_retry_backoff_doubles_up_to_max() {
+        let retry = ReloadRetry {
+            initial: Duration::from_secs(30),
+            max: Duration::from_secs(300),
+        };
+        let delays: Vec<u64> = (1..=6).map(|n| retry.delay(n).as_secs()).collect();
+        assert_eq!(delays, vec![30, 60, 120, 240, 300, 300]);
+        assert_eq!(retry.delay(200).as_secs(), 300);
+    }
+}
@@ -0,0 +1,556 @@
+//! Conversation sessions for multi-turn chat.
+//!
+//! A [`Session`] keeps the turns of one conversation, so a follow-up
+//! question can be read in the context of what was asked and answered
+//! before it. [`SessionManager`] creates sessions, appends turns and keeps
+//! only the most recent ones; a session expires once no turn was added to it
+//! for its TTL.
+//!
+//! Sessions live in a [`SessionStore`]: in this process (the default), as
+//! one JSON file per session in a directory so they survive restarts, or in
+//! Redis, shared by all replicas. Session IDs are 128 random bits, so they
+//! cannot be guessed, and are checked before reaching a store.
+
+use async_trait::async_trait;
+use chrono::Utc;
+use redis::aio::ConnectionManager;
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+use crate::error::ServiceError;
+use crate::random::random_bytes;
+
+/// Default time a session is kept after its last turn.
+pub const DEFAULT_SESSION_TTL: Duration = Duration::from_secs(3600);
+
+/// Default number of most recent turns a session keeps.
+pub const DEFAULT_SESSION_MAX_TURNS: usize = 20;
+
+/// Maximum number of sessions kept by [`MemorySessionStore`].
+pub const DEFAULT_SESSION_CAPACITY: usize = 10_000;
+
+/// Longest turn accepted, in characters.
+pub const MAX_TURN_CHARS: usize = 16_000;
+
+/// Prefix of the Redis keys used by [`RedisSessionStore`].
+const REDIS_NAMESPACE: &str = "memvid:sessions";
+
+/// Who wrote a turn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    /// The visitor asking questions
+    User,
+    /// The service answering them
+    Assistant,
+}
+
+/// One message of a conversation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Turn {
+    pub role: Role,
+    pub content: String,
+    /// When the turn was added (Unix seconds)
+    pub created_at: i64,
+}
+
+/// A conversation and its most recent turns, oldest first.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Session {
+    pub id: String,
+    pub turns: Vec<Turn>,
+    /// When the session was created (Unix seconds)
+    pub created_at: i64,
+    /// When the last turn was added, or the session created (Unix seconds)
+    pub updated_at: i64,
+}
+
+impl Session {
+    /// The last `max_turns` turns, oldest first.
+    pub fn history(&self, max_turns: usize) -> &[Turn] {
+        &self.turns[self.turns.len().saturating_sub(max_turns)..]
+    }
+}
+
+/// Where sessions are kept.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SessionBackend {
+    /// In this process only
+    Memory,
+    /// One JSON file per session in the given directory
+    File(PathBuf),
+    /// In Redis at the given URL, shared by all replicas
+    Redis(String),
+}
+
+impl SessionBackend {
+    /// Parse `memory`, a `redis://` / `rediss://` URL or a directory path.
+    pub fn parse(value: &str) -> Self {
+        let value = value.trim();
+        let lower = value.to_ascii_lowercase();
+        if value.is_empty() || lower == "memory" {
+            Self::Memory
+        } else if lower.starts_with("redis://") || lower.starts_with("rediss://") {
+            Self::Redis(value.to_string())
+        } else {
+            Self::File(PathBuf::from(value))
+        }
+    }
+}
+
+/// Storage for sessions.
+#[async_trait]
+pub trait SessionStore: Send + Sync {
+    /// The session with `id`, unless it does not exist or has expired.
+    async fn load(&self, id: &str) -> Result<Option<Session>, ServiceError>;
+
+    /// Store `session`, replacing any previous version, for `ttl`.
+    async fn save(&self, session: &Session, ttl: Duration) -> Result<(), ServiceError>;
+}
+
+/// In-process session store.
+pub struct MemorySessionStore {
+    capacity: usize,
+    sessions: Mutex<HashMap<String, (Instant, Session)>>,
+}
+
+impl MemorySessionStore {
+    /// Create a store holding at most `capacity` sessions.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            sessions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, (Instant, Session)>> {
+        self.sessions.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Default for MemorySessionStore {
+    fn default() -> Self {
+        Self::new(DEFAULT_SESSION_CAPACITY)
+    }
+}
+
+#[async_trait]
+impl SessionStore for MemorySessionStore {
+    async fn load(&self, id: &str) -> Result<Option<Session>, ServiceError> {
+        Ok(self
+            .lock()
+            .get(id)
+            .filter(|(expires, _)| *expires > Instant::now())
+            .map(|(_, session)| session.clone()))
+    }
+
+    async fn save(&self, session: &Session, ttl: Duration) -> Result<(), ServiceError> {
+        let now = Instant::now();
+        let mut sessions = self.lock();
+        sessions.retain(|_, (expires, _)| *expires > now);
+        if sessions.len() >= self.capacity && !sessions.contains_key(&session.id) {
+            let soonest = sessions
+                .iter()
+                .min_by_key(|(_, (expires, _))| *expires)
+                .map(|(id, _)| id.clone());
+            if let Some(soonest) = soonest {
+                sessions.remove(&soonest);
+            }
+        }
+        if self.capacity > 0 {
+            sessions.insert(session.id.clone(), (now + ttl, session.clone()));
+        }
+        Ok(())
+    }
+}
+
+/// A session file: the session and when it expires.
+#[derive(Serialize, Deserialize)]
+struct SessionFile {
+    /// Unix seconds
+    expires_at: i64,
+    session: Session,
+}
+
+/// Session store keeping one JSON file per session in a directory.
+pub struct FileSessionStore {
+    dir: PathBuf,
+}
+
+impl FileSessionStore {
+    /// Keep sessions in `dir`, creating it if needed and removing the files
+    /// of sessions that expired while the service was down.
+    pub fn open(dir: impl Into<PathBuf>) -> Result<Self, ServiceError> {
+        let dir = dir.into();
+        let dir_error = |e: std::io::Error| {
+            ServiceError::Internal(format!(
+                "Failed to open session directory {}: {}",
+                dir.display(),
+                e
+            ))
+        };
+        std::fs::create_dir_all(&dir).map_err(dir_error)?;
+
+        let now = Utc::now().timestamp();
+        let (mut kept, mut expired) = (0, 0);
+        for entry in std::fs::read_dir(&dir).map_err(dir_error)?.flatten() {
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                match read_session_file(&path) {
+                    Ok(file) if file.expires_at > now => kept += 1,
+                    _ => {
+                        let _ = std::fs::remove_file(&path);
+                        expired += 1;
+                    }
+                }
+            }
+        }
+        info!(path = %dir.display(), kept, expired, "Opened session directory");
+        Ok(Self { dir })
+    }
+
+    fn path(&self, id: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", id))
+    }
+}
+
+fn read_session_file(path: &PathBuf) -> Result<SessionFile, String> {
+    let json = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&json).map_err(|e| e.to_string())
+}
+
+#[async_trait]
+impl SessionStore for FileSessionStore {
+    async fn load(&self, id: &str) -> Result<Option<Session>, ServiceError> {
+        let path = self.path(id);
+        match tokio::fs::read_to_string(&path).await {
+            Ok(json) => match serde_json::from_str::<SessionFile>(&json) {
+                Ok(file) if file.expires_at > Utc::now().timestamp() => Ok(Some(file.session)),
+                Ok(_) => {
+                    let _ = tokio::fs::remove_file(&path).await;
+                    Ok(None)
+                }
+                Err(e) => {
+                    warn!(path = %path.display(), error = %e, "Ignoring undecodable session file");
+                    Ok(None)
+                }
+            },
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(ServiceError::Internal(format!(
+                "Failed to read session file {}: {}",
+                path.display(),
+                e
+            ))),
+        }
+    }
+
+    async fn save(&self, session: &Session, ttl: Duration) -> Result<(), ServiceError> {
+        let path = self.path(&session.id);
+        let json = serde_json::to_string(&SessionFile {
+            expires_at: Utc::now().timestamp() + ttl.as_secs() as i64,
+            session: session.clone(),
+        })
+        .map_err(|e| ServiceError::Internal(format!("Failed to encode session: {}", e)))?;
+        // Written via a temporary file, so a crash never leaves a truncated one
+        let tmp = path.with_extension("tmp");
+        let written = match tokio::fs::write(&tmp, json).await {
+            Ok(()) => tokio::fs::rename(&tmp, &path).await,
+            Err(e) => Err(e),
+        };
+        written.map_err(|e| {
+            ServiceError::Internal(format!(
+                "Failed to write session file {}: {}",
+                path.display(),
+                e
+            ))
+        })
+    }
+}
+
+/// Session store in Redis, shared by every replica using the same server.
+pub struct RedisSessionStore {
+    connection: ConnectionManager,
+}
+
+impl RedisSessionStore {
+    /// Connect to Redis at `url`, which must pass the egress allowlist.
+    pub async fn connect(url: &str) -> Result<Self, ServiceError> {
+        let client = redis::Client::open(url).map_err(redis_error)?;
+        if let redis::ConnectionAddr::Tcp(host, _) | redis::ConnectionAddr::TcpTls { host, .. } =
+            &client.get_connection_info().addr
+        {
+            crate::egress::current()
+                .check(host)
+                .map_err(ServiceError::Internal)?;
+        }
+        let connection = client.get_connection_manager().await.map_err(redis_error)?;
+        info!("Connected to Redis session store");
+        Ok(Self { connection })
+    }
+}
+
+fn redis_key(id: &str) -> String {
+    format!("{}:{}", REDIS_NAMESPACE, id)
+}
+
+fn redis_error(e: redis::RedisError) -> ServiceError {
+    ServiceError::Internal(format!("Redis session store: {}", e))
+}
+
+#[async_trait]
+impl SessionStore for RedisSessionStore {
+    async fn load(&self, id: &str) -> Result<Option<Session>, ServiceError> {
+        let mut connection = self.connection.clone();
+        let json: Option<String> = connection.get(redis_key(id)).await.map_err(redis_error)?;
+        Ok(json.and_then(|json| match serde_json::from_str(&json) {
+            Ok(session) => Some(session),
+            Err(e) => {
+                warn!(error = %e, "Ignoring undecodable session");
+                None
+            }
+        }))
+    }
+
+    async fn save(&self, session: &Session, ttl: Duration) -> Result<(), ServiceError> {
+        let json = serde_json::to_string(session)
+            .map_err(|e| ServiceError::Internal(format!("Failed to encode session: {}", e)))?;
+        let mut connection = self.connection.clone();
+        connection
+            .set_ex(redis_key(&session.id), json, ttl.as_secs().max(1))
+            .await
+            .map_err(redis_error)
+    }
+}
+
+/// Creates sessions and records their turns.
+pub struct SessionManager {
+    store: Arc<dyn SessionStore>,
+    ttl: Duration,
+    max_turns: usize,
+    /// Serializes appends, so concurrent turns are not lost (per replica)
+    appending: tokio::sync::Mutex<()>,
+}
+
+impl Default for SessionManager {
+    fn default() -> Self {
+        Self::new(Arc::new(MemorySessionStore::default()))
+    }
+}
+
+impl SessionManager {
+    /// Keep sessions in `store`.
+    pub fn new(store: Arc<dyn SessionStore>) -> Self {
+        Self {
+            store,
+            ttl: DEFAULT_SESSION_TTL,
+            max_turns: DEFAULT_SESSION_MAX_TURNS,
+            appending: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    /// Expire sessions `ttl` after their last turn.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Keep the last `max_turns` turns of each session.
+    pub fn with_max_turns(mut self, max_turns: usize) -> Self {
+        self.max_turns = max_turns;
+        self
+    }
+
+    /// Time a session is kept after its last turn.
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Start an empty session.
+    pub async fn create(&self) -> Result<Session, ServiceError> {
+        let now = Utc::now().timestamp();
+        let session = Session {
+            id: new_session_id(),
+            turns: Vec::new(),
+            created_at: now,
+            updated_at: now,
+        };
+        self.store.save(&session, self.ttl).await?;
+        Ok(session)
+    }
+
+    /// The session with `id`, unless it does not exist or has expired.
+    pub async fn get(&self, id: &str) -> Result<Option<Session>, ServiceError> {
+        check_session_id(id)?;
+        self.store.load(id).await
+    }
+
+    /// Add a turn to the session with `id`, returning the updated session
+    /// (None when it does not exist or has expired).
+    pub async fn append(
+        &self,
+        id: &str,
+        role: Role,
+        content: &str,
+    ) -> Result<Option<Session>, ServiceError> {
+        check_session_id(id)?;
+        let content = content.trim();
+        if content.is_empty() {
+            return Err(ServiceError::InvalidRequest(
+                "turn content is empty".to_string(),
+            ));
+        }
+        if content.chars().count() > MAX_TURN_CHARS {
+            return Err(ServiceError::InvalidRequest(format!(
+                "turn content exceeds {} characters",
+                MAX_TURN_CHARS
+            )));
+        }
+
+        let _appending = self.appending.lock().await;
+        let Some(mut session) = self.store.load(id).await? else {
+            return Ok(None);
+        };
+        let now = Utc::now().timestamp();
+        session.turns.push(Turn {
+            role,
+            content: content.to_string(),
+            created_at: now,
+        });
+        let excess = session.turns.len().saturating_sub(self.max_turns);
+        session.turns.drain(..excess);
+        session.updated_at = now;
+        self.store.save(&session, self.ttl).await?;
+        Ok(Some(session))
+    }
+}
+
+/// A new random session ID: 32 lowercase hex digits.
+fn new_session_id() -> String {
+    random_bytes::<16>()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Reject IDs that [`new_session_id`] cannot have issued, so they never
+/// reach a store (or a file path).
+fn check_session_id(id: &str) -> Result<(), ServiceError> {
+    if id.len() == 32 && id.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f')) {
+        Ok(())
+    } else {
+        Err(ServiceError::InvalidRequest(format!(
+            "invalid session_id '{}'",
+            id.chars().take(64).collect::<String>()
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backend_parse() {
+        assert_eq!(SessionBackend::parse(""), SessionBackend::Memory);
+        assert_eq!(SessionBackend::parse("Memory"), SessionBackend::Memory);
+        assert_eq!(
+            SessionBackend::parse("redis://cache:6379"),
+            SessionBackend::Redis("redis://cache:6379".to_string())
+        );
+        assert_eq!(
+            SessionBackend::parse("/var/lib/memvid/sessions"),
+            SessionBackend::File(PathBuf::from("/var/lib/memvid/sessions"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_turns_are_appended_and_trimmed() {
+        let sessions = SessionManager::default().with_max_turns(3);
+        let session = sessions.create().await.unwrap();
+        assert_eq!(session.id.len(), 32);
+        assert_ne!(session.id, sessions.create().await.unwrap().id);
+
+        for (role, content) in [
+            (Role::User, "Where did you work?"),
+            (Role::Assistant, "At Siemens."),
+            (Role::User, "What about Python?"),
+            (Role::Assistant, "Five years."),
+        ] {
+            sessions.append(&session.id, role, content).await.unwrap();
+        }
+        let stored = sessions.get(&session.id).await.unwrap().unwrap();
+        let contents: Vec<_> = stored.turns.iter().map(|t| t.content.as_str()).collect();
+        assert_eq!(
+            contents,
+            ["At Siemens.", "What about Python?", "Five years."]
+        );
+        assert_eq!(stored.history(1)[0].role, Role::Assistant);
+        assert_eq!(stored.history(10).len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_requests_and_unknown_sessions() {
+        let sessions = SessionManager::default();
+        let session = sessions.create().await.unwrap();
+        assert!(matches!(
+            sessions.append(&session.id, Role::User, "  ").await,
+            Err(ServiceError::InvalidRequest(_))
+        ));
+        let long = "x".repeat(MAX_TURN_CHARS + 1);
+        assert!(matches!(
+            sessions.append(&session.id, Role::User, &long).await,
+            Err(ServiceError::InvalidRequest(_))
+        ));
+        assert!(matches!(
+            sessions.get("../../etc/passwd").await,
+            Err(ServiceError::InvalidRequest(_))
+        ));
+
+        let unknown = "0".repeat(32);
+        assert!(sessions.get(&unknown).await.unwrap().is_none());
+        assert!(sessions
+            .append(&unknown, Role::User, "Hello")
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_sessions_expire() {
+        let sessions = SessionManager::default().with_ttl(Duration::ZERO);
+        let session = sessions.create().await.unwrap();
+        assert!(sessions.get(&session.id).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_file_store_persists_sessions() {
+        let dir = std::env::temp_dir().join(format!("memvid-sessions-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let sessions = SessionManager::new(Arc::new(FileSessionStore::open(&dir).unwrap()));
+        let session = sessions.create().await.unwrap();
+        sessions
+            .append(&session.id, Role::User, "Where did you work?")
+            .await
+            .unwrap();
+
+        let reopened = SessionManager::new(Arc::new(FileSessionStore::open(&dir).unwrap()));
+        let stored = reopened.get(&session.id).await.unwrap().unwrap();
+        assert_eq!(stored.turns[0].content, "Where did you work?");
+
+        // Expired sessions are removed when the directory is opened
+        FileSessionStore::open(&dir)
+            .unwrap()
+            .save(&stored, Duration::ZERO)
+            .await
+            .unwrap();
+        FileSessionStore::open(&dir).unwrap();
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,450 @@
+//! Supervision of the service's background tasks.
+//!
+//! The signal handler, the metrics server, scheduled reloads, the config and
+//! JWKS watchers and analytics reports run as named tasks of a
+//! [`Supervisor`] rather than detached `tokio::spawn`s, whose panics went
+//! unnoticed. A panicking task is logged and counted in
+//! `memvid_task_failures_total`; under [`RestartPolicy::Backoff`] it is
+//! started again after a doubling delay. A task that panics with no restarts
+//! left fails the process: [`Supervisor::failed`] completes with a report of
+//! every task that gave up, so `main` can drain and exit instead of serving
+//! without it.
+//!
+//! At shutdown, tasks spawned with [`Supervisor::spawn_graceful`] are told
+//! to stop and given time to finish; the others are cancelled.
+
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{watch, Notify};
+use tokio::task::JoinHandle;
+use tracing::{error, info, warn};
+
+use crate::metrics;
+
+/// Restart policy of background jobs: five quick restarts, then fatal.
+pub const DEFAULT_RESTART: RestartPolicy = RestartPolicy::Backoff {
+    initial: Duration::from_secs(1),
+    max: Duration::from_secs(60),
+    max_restarts: 5,
+};
+
+/// What to do when a task panics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RestartPolicy {
+    /// Fail the process
+    Never,
+    /// Restart after `initial`, doubling up to `max` per consecutive panic;
+    /// fail the process after `max_restarts` restarts in a row. A run
+    /// longer than `max` resets the count.
+    Backoff {
+        initial: Duration,
+        max: Duration,
+        max_restarts: u32,
+    },
+}
+
+impl RestartPolicy {
+    /// Delay before the restart after `failures` consecutive panics; None
+    /// when the task must not be restarted.
+    pub fn delay(&self, failures: u32) -> Option<Duration> {
+        match *self {
+            Self::Never => None,
+            Self::Backoff {
+                initial,
+                max,
+                max_restarts,
+            } => (failures <= max_restarts)
+                .then(|| initial.saturating_mul(1 << failures.saturating_sub(1).min(16)))
+                .map(|delay| delay.min(max)),
+        }
+    }
+
+    /// Run time after which a task counts as healthy again.
+    fn stable_after(&self) -> Duration {
+        match *self {
+            Self::Never => Duration::MAX,
+            Self::Backoff { max, .. } => max,
+        }
+    }
+}
+
+/// State of a supervised task.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskState {
+    Running,
+    /// Waiting to be restarted after a panic
+    Restarting,
+    /// Ended on its own
+    Finished,
+    /// Panicked with no restarts left
+    Failed,
+    /// Stopped at shutdown
+    Stopped,
+}
+
+/// Status of one supervised task.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskReport {
+    pub name: &'static str,
+    pub state: TaskState,
+    /// Panics since the task was first started
+    pub failures: u32,
+    /// Message of the latest panic
+    pub last_failure: Option<String>,
+}
+
+/// Signal telling a graceful task to stop.
+#[derive(Debug, Clone)]
+pub struct Shutdown(watch::Receiver<bool>);
+
+impl Shutdown {
+    /// Complete once shutdown was requested (or the supervisor is gone).
+    pub async fn requested(mut self) {
+        stopped(&mut self.0).await;
+    }
+}
+
+#[derive(Default)]
+struct Shared {
+    tasks: Mutex<BTreeMap<&'static str, TaskReport>>,
+    gave_up: Notify,
+}
+
+impl Shared {
+    fn update(&self, name: &'static str, f: impl FnOnce(&mut TaskReport)) {
+        let mut tasks = self.tasks.lock().unwrap();
+        let report = tasks.entry(name).or_insert(TaskReport {
+            name,
+            state: TaskState::Running,
+            failures: 0,
+            last_failure: None,
+        });
+        f(report);
+    }
+
+    /// "name: message" of every task that gave up, joined; None if none.
+    fn failure_summary(&self) -> Option<String> {
+        let tasks = self.tasks.lock().unwrap();
+        let failed: Vec<String> = tasks
+            .values()
+            .filter(|task| task.state == TaskState::Failed)
+            .map(|task| {
+                format!(
+                    "{}: {}",
+                    task.name,
+                    task.last_failure.as_deref().unwrap_or("panicked")
+                )
+            })
+            .collect();
+        (!failed.is_empty()).then(|| failed.join("; "))
+    }
+}
+
+/// Owner of the service's background tasks.
+pub struct Supervisor {
+    shared: Arc<Shared>,
+    stop: watch::Sender<bool>,
+    handles: Vec<JoinHandle<()>>,
+}
+
+impl Default for Supervisor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Supervisor {
+    pub fn new() -> Self {
+        Self {
+            shared: Arc::default(),
+            stop: watch::Sender::new(false),
+            handles: Vec::new(),
+        }
+    }
+
+    /// Run the future made by `task` as `name`, cancelled at shutdown.
+    /// `task` is called again for each restart.
+    pub fn spawn<F, Fut>(&mut self, name: &'static str, policy: RestartPolicy, task: F)
+    where
+        F: Fn() -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.start(name, policy, false, move |_| task());
+    }
+
+    /// Like [`spawn`](Self::spawn), but at shutdown the task is handed the
+    /// signal through its [`Shutdown`] and awaited instead of cancelled.
+    pub fn spawn_graceful<F, Fut>(&mut self, name: &'static str, policy: RestartPolicy, task: F)
+    where
+        F: Fn(Shutdown) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.start(name, policy, true, task);
+    }
+
+    fn start<F, Fut>(&mut self, name: &'static str, policy: RestartPolicy, graceful: bool, task: F)
+    where
+        F: Fn(Shutdown) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.shared.update(name, |_| {});
+        let shared = Arc::clone(&self.shared);
+        let stop = self.stop.subscribe();
+        self.handles.push(tokio::spawn(supervise(
+            name, policy, graceful, task, shared, stop,
+        )));
+    }
+
+    /// Status of every task.
+    pub fn report(&self) -> Vec<TaskReport> {
+        self.shared
+            .tasks
+            .lock()
+            .unwrap()
+            .values()
+            .cloned()
+            .collect()
+    }
+
+    /// Complete when a task panicked with no restarts left, with a summary
+    /// of every task that did.
+    pub async fn failed(&self) -> String {
+        loop {
+            let gave_up = self.shared.gave_up.notified();
+            if let Some(summary) = self.shared.failure_summary() {
+                return summary;
+            }
+            gave_up.await;
+        }
+    }
+
+    /// Stop every task: cancel the others, and give graceful ones up to
+    /// `timeout` to finish. Logs the tasks that panicked during the run.
+    pub async fn shutdown(self, timeout: Duration) {
+        let _ = self.stop.send(true);
+        let handles = self.handles;
+        let aborts: Vec<_> = handles.iter().map(JoinHandle::abort_handle).collect();
+        let joined = async {
+            for handle in handles {
+                let _ = handle.await;
+            }
+        };
+        if tokio::time::timeout(timeout, joined).await.is_err() {
+            warn!(
+                timeout_secs = timeout.as_secs(),
+                "Background tasks did not stop in time"
+            );
+            aborts.iter().for_each(|abort| abort.abort());
+        }
+
+        let failed: Vec<String> = self
+            .shared
+            .tasks
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|task| task.failures > 0)
+            .map(|task| format!("{} ({}x)", task.name, task.failures))
+            .collect();
+        if !failed.is_empty() {
+            warn!(tasks = %failed.join(", "), "Background tasks panicked during this run");
+        }
+    }
+}
+
+/// Run `task` as `name` until it ends, fails for good, or shutdown.
+async fn supervise<F, Fut>(
+    name: &'static str,
+    policy: RestartPolicy,
+    graceful: bool,
+    task: F,
+    shared: Arc<Shared>,
+    mut stop: watch::Receiver<bool>,
+) where
+    F: Fn(Shutdown) -> Fut,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    let mut failures = 0;
+    loop {
+        let started = Instant::now();
+        let mut attempt = tokio::spawn(task(Shutdown(stop.clone())));
+        let result = tokio::select! {
+            result = &mut attempt => result,
+            () = stopped(&mut stop) => {
+                if graceful {
+                    let _ = attempt.await;
+                } else {
+                    attempt.abort();
+                }
+                shared.update(name, |report| report.state = TaskState::Stopped);
+                return;
+            }
+        };
+
+        let message = match result {
+            // A graceful task may see the signal before this loop does
+            Ok(()) if *stop.borrow() => {
+                shared.update(name, |report| report.state = TaskState::Stopped);
+                return;
+            }
+            Ok(()) => {
+                info!(task = name, "Background task finished");
+                shared.update(name, |report| report.state = TaskState::Finished);
+                return;
+            }
+            Err(e) if e.is_cancelled() => return,
+            Err(e) => panic_message(e.into_panic()),
+        };
+        metrics::increment_task_failure(name);
+        if started.elapsed() >= policy.stable_after() {
+            failures = 0;
+        }
+        failures += 1;
+        let delay = policy.delay(failures);
+        shared.update(name, |report| {
+            report.failures += 1;
+            report.last_failure = Some(message.clone());
+            report.state = match delay {
+                Some(_) => TaskState::Restarting,
+                None => TaskState::Failed,
+            };
+        });
+
+        let Some(delay) = delay else {
+            error!(task = name, error = %message, "Background task panicked, giving up");
+            shared.gave_up.notify_waiters();
+            return;
+        };
+        error!(
+            task = name,
+            error = %message,
+            restart_in_ms = delay.as_millis() as u64,
+            "Background task panicked, restarting"
+        );
+        tokio::select! {
+            () = tokio::time::sleep(delay) => {}
+            () = stopped(&mut stop) => {
+                shared.update(name, |report| report.state = TaskState::Stopped);
+                return;
+            }
+        }
+        shared.update(name, |report| report.state = TaskState::Running);
+    }
+}
+
+/// Complete once shutdown was requested (or the supervisor is gone).
+async fn stopped(stop: &mut watch::Receiver<bool>) {
+    let _ = stop.wait_for(|stop| *stop).await;
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "Box<dyn Any>".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    const QUICK: RestartPolicy = RestartPolicy::Backoff {
+        initial: Duration::from_millis(1),
+        max: Duration::from_millis(5),
+        max_restarts: 2,
+    };
+
+    #[test]
+    fn test_backoff_delays() {
+        assert_eq!(RestartPolicy::Never.delay(1), None);
+        assert_eq!(DEFAULT_RESTART.delay(1), Some(Duration::from_secs(1)));
+        assert_eq!(DEFAULT_RESTART.delay(3), Some(Duration::from_secs(4)));
+        assert_eq!(DEFAULT_RESTART.delay(5), Some(Duration::from_secs(16)));
+        assert_eq!(DEFAULT_RESTART.delay(6), None);
+        let slow = RestartPolicy::Backoff {
+            initial: Duration::from_secs(10),
+            max: Duration::from_secs(30),
+            max_restarts: 100,
+        };
+        assert_eq!(slow.delay(90), Some(Duration::from_secs(30)));
+    }
+
+    #[tokio::test]
+    async fn test_restarts_panicking_task_then_gives_up() {
+        let mut supervisor = Supervisor::new();
+        let runs = Arc::new(AtomicU32::new(0));
+        supervisor.spawn("flaky", QUICK, {
+            let runs = Arc::clone(&runs);
+            move || {
+                let run = runs.fetch_add(1, Ordering::SeqCst) + 1;
+                async move { panic!("run {} failed", run) }
+            }
+        });
+        supervisor.spawn("steady", QUICK, std::future::pending);
+
+        let summary = tokio::time::timeout(Duration::from_secs(5), supervisor.failed())
+            .await
+            .unwrap();
+        assert_eq!(summary, "flaky: run 3 failed");
+        assert_eq!(runs.load(Ordering::SeqCst), 3);
+
+        let report = supervisor.report();
+        assert_eq!(report[0].name, "flaky");
+        assert_eq!(report[0].state, TaskState::Failed);
+        assert_eq!(report[0].failures, 3);
+        assert_eq!(report[1].state, TaskState::Running);
+        supervisor.shutdown(Duration::from_secs(1)).await;
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_stops_graceful_tasks_and_cancels_others() {
+        let mut supervisor = Supervisor::new();
+        let (done_tx, done_rx) = tokio::sync::oneshot::channel();
+        let done_tx = Mutex::new(Some(done_tx));
+        supervisor.spawn_graceful("server", RestartPolicy::Never, move |shutdown| {
+            let done = done_tx.lock().unwrap().take();
+            async move {
+                shutdown.requested().await;
+                if let Some(done) = done {
+                    let _ = done.send(());
+                }
+            }
+        });
+        supervisor.spawn("watcher", RestartPolicy::Never, std::future::pending);
+        supervisor.spawn("signals", RestartPolicy::Never, || async {});
+
+        while supervisor.report()[1].state != TaskState::Finished {
+            tokio::task::yield_now().await;
+        }
+
+        let shared = Arc::clone(&supervisor.shared);
+        tokio::time::timeout(
+            Duration::from_secs(1),
+            supervisor.shutdown(Duration::from_secs(5)),
+        )
+        .await
+        .unwrap();
+        done_rx.await.unwrap();
+
+        let states: Vec<_> = shared
+            .tasks
+            .lock()
+            .unwrap()
+            .values()
+            .map(|task| (task.name, task.state))
+            .collect();
+        assert_eq!(
+            states,
+            [
+                ("server", TaskState::Stopped),
+                ("signals", TaskState::Finished),
+                ("watcher", TaskState::Stopped),
+            ]
+        );
+    }
+}
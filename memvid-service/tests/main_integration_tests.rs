@@ -39,7 +39,8 @@ impl TestEnv {
 
 impl Drop for TestEnv {
     fn drop(&mut self) {
-        for (key, old_value) in &self.vars_to_restore {
+        // In reverse, so a variable set twice gets its original value back
+        for (key, old_value) in self.vars_to_restore.iter().rev() {
             match old_value {
                 Some(value) => std::env::set_var(key, value),
                 None => std::env::remove_var(key),
@@ -115,6 +116,26 @@ async fn test_config_accepts_memvid_file_path() {
     assert_eq!(config.memvid_file_path, "/path/to/test.mv2");
 }
 
+#[tokio::test]
+#[serial]
+async fn test_config_memvid_file_paths_name_indexes() {
+    let mut env = TestEnv::new();
+    env.remove_var("MOCK_MEMVID");
+    env.set_var("MEMVID_FILE_PATH", "/path/to/test.mv2");
+    env.set_var("MEMVID_FILE_PATHS", "v12=/data/v12.mv2,v11=/data/v11.mv2");
+
+    use ai_resume_memvid::config::Config;
+
+    // The first named index is the default one
+    let config = Config::from_env().expect("Config should load with named indexes");
+    assert_eq!(config.memvid_file_path, "/data/v12.mv2");
+    assert_eq!(config.memvid_file_paths.len(), 2);
+    assert_eq!(config.memvid_file_paths[1].0, "v11");
+
+    env.set_var("MEMVID_FILE_PATHS", "v12=/data/v12.mv2,v12=/data/v11.mv2");
+    assert!(Config::from_env().is_err());
+}
+
 #[tokio::test]
 #[serial]
 async fn test_config_default_bind_address() {
@@ -162,6 +183,94 @@ async fn test_config_ipv6_bind_address() {
     assert!(!config.bind_address.is_empty());
 }
 
+#[tokio::test]
+#[serial]
+async fn test_config_reload_schedule() {
+    let mut env = TestEnv::new();
+    env.set_var("MOCK_MEMVID", "true");
+    env.set_var("RELOAD_SCHEDULE", "0 3 * * *");
+    env.set_var("RELOAD_JITTER_SECS", "60");
+
+    use ai_resume_memvid::config::Config;
+
+    let config = Config::from_env().expect("Config should load");
+
+    assert_eq!(config.reload_schedule.as_deref(), Some("0 3 * * *"));
+    assert_eq!(config.reload_jitter_secs, 60);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_config_rejects_invalid_reload_schedule() {
+    let mut env = TestEnv::new();
+    env.set_var("MOCK_MEMVID", "true");
+    env.set_var("RELOAD_SCHEDULE", "every night");
+
+    use ai_resume_memvid::config::Config;
+
+    let result = Config::from_env();
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_config_requires_tls_key_with_cert() {
+    let mut env = TestEnv::new();
+    env.set_var("MOCK_MEMVID", "true");
+    env.set_var("TLS_CERT_PATH", "/etc/memvid/tls/tls.crt");
+
+    use ai_resume_memvid::config::Config;
+
+    assert!(Config::from_env().is_err());
+
+    env.set_var("TLS_KEY_PATH", "/etc/memvid/tls/tls.key");
+    let config = Config::from_env().expect("Config should load");
+    assert_eq!(
+        config.tls_key_path.as_deref(),
+        Some("/etc/memvid/tls/tls.key")
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn test_config_requires_jwks_url_with_issuer() {
+    let mut env = TestEnv::new();
+    env.set_var("MOCK_MEMVID", "true");
+    env.set_var("JWT_ISSUER", "https://auth.example.com/");
+
+    use ai_resume_memvid::config::Config;
+
+    assert!(Config::from_env().is_err());
+
+    env.set_var(
+        "JWT_JWKS_URL",
+        "https://auth.example.com/.well-known/jwks.json",
+    );
+    env.set_var("JWT_REQUIRED", "true");
+    let config = Config::from_env().expect("Config should load");
+    assert!(config.jwt_required);
+    assert_eq!(config.jwt_jwks_refresh_secs, 600);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_config_rate_limit_rps_and_burst() {
+    let mut env = TestEnv::new();
+    env.set_var("MOCK_MEMVID", "true");
+    env.remove_var("RATE_LIMIT_PER_MINUTE");
+    env.remove_var("RATE_LIMIT_BURST");
+    env.set_var("RATE_LIMIT_RPS", "2.5");
+
+    use ai_resume_memvid::config::Config;
+
+    let config = Config::from_env().expect("Config should load");
+    assert_eq!(config.rate_limit_per_minute, 150);
+    assert_eq!(config.rate_limit_burst, Some(3));
+
+    env.set_var("RATE_LIMIT_PER_MINUTE", "60");
+    assert!(Config::from_env().is_err());
+}
+
 #[tokio::test]
 async fn test_mock_searcher_initialization() {
     use ai_resume_memvid::memvid::{MockSearcher, Searcher};
@@ -176,12 +285,12 @@ async fn test_mock_searcher_initialization() {
 
 #[tokio::test]
 async fn test_mock_searcher_basic_search() {
-    use ai_resume_memvid::memvid::{MockSearcher, Searcher};
+    use ai_resume_memvid::memvid::{MockSearcher, SearchRequest, Searcher};
 
     let searcher = MockSearcher::new();
 
     let response = searcher
-        .search("Python experience", 5, 200)
+        .search(SearchRequest::new("Python experience", 5, 200))
         .await
         .expect("Search should succeed");
 
@@ -221,6 +330,250 @@ async fn test_grpc_service_creation_with_mock() {
     let _health_service = HealthService::new(Arc::clone(&searcher));
 }
 
+#[tokio::test]
+async fn test_v2_export_frames_paced_by_acks() {
+    use ai_resume_memvid::generated::memvid::v2::{
+        export_frames_request::Request as ExportRequest,
+        memvid_service_client::MemvidServiceClient, memvid_service_server::MemvidServiceServer,
+        ExportAck, ExportFramesRequest, ExportFramesStart,
+    };
+    use ai_resume_memvid::grpc::MemvidV2Service;
+    use ai_resume_memvid::memvid::MockSearcher;
+    use std::sync::Arc;
+    use tokio_stream::wrappers::{ReceiverStream, TcpListenerStream};
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let service = MemvidV2Service::new(Arc::new(MockSearcher::synthetic(1, 10)))
+        .with_export_limits(0, Duration::from_secs(5));
+    tokio::spawn(
+        tonic::transport::Server::builder()
+            .add_service(MemvidServiceServer::new(service))
+            .serve_with_incoming(TcpListenerStream::new(listener)),
+    );
+
+    let mut client = MemvidServiceClient::connect(format!("http://{}", addr))
+        .await
+        .unwrap();
+    let (tx, rx) = tokio::sync::mpsc::channel(8);
+    tx.send(ExportFramesRequest {
+        request: Some(ExportRequest::Start(ExportFramesStart {
+            after_frame_id: None,
+            batch_size: 4,
+            window: 1,
+            ..Default::default()
+        })),
+    })
+    .await
+    .unwrap();
+
+    let mut batches = client
+        .export_frames(ReceiverStream::new(rx))
+        .await
+        .unwrap()
+        .into_inner();
+
+    let mut frame_ids = Vec::new();
+    let mut cursors = Vec::new();
+    while let Some(batch) = timeout(Duration::from_secs(5), batches.message())
+        .await
+        .expect("batch should arrive once acked")
+        .unwrap()
+    {
+        frame_ids.extend(batch.frames.iter().map(|f| f.frame_id));
+        cursors.push(batch.next_cursor.clone());
+        if batch.done {
+            break;
+        }
+        tx.send(ExportFramesRequest {
+            request: Some(ExportRequest::Ack(ExportAck {
+                sequence: batch.sequence,
+            })),
+        })
+        .await
+        .unwrap();
+    }
+
+    assert_eq!(frame_ids, (1..=10).collect::<Vec<u64>>());
+    assert!(cursors.last().unwrap().is_empty());
+
+    // Resume a new export from the first batch's cursor
+    let (tx, rx) = tokio::sync::mpsc::channel(8);
+    tx.send(ExportFramesRequest {
+        request: Some(ExportRequest::Start(ExportFramesStart {
+            batch_size: 4,
+            cursor: cursors[0].clone(),
+            ..Default::default()
+        })),
+    })
+    .await
+    .unwrap();
+    let resumed = client
+        .export_frames(ReceiverStream::new(rx))
+        .await
+        .unwrap()
+        .into_inner()
+        .message()
+        .await
+        .unwrap()
+        .unwrap();
+    let resumed_ids: Vec<u64> = resumed.frames.iter().map(|f| f.frame_id).collect();
+    assert_eq!(resumed_ids, vec![5, 6, 7, 8]);
+}
+
+#[tokio::test]
+async fn test_v2_exports_refused_under_acl_enforcement() {
+    use ai_resume_memvid::generated::memvid::v2::{
+        export_frames_request, export_state_request, memvid_service_client::MemvidServiceClient,
+        memvid_service_server::MemvidServiceServer, ExportFramesRequest, ExportFramesStart,
+        ExportStateRequest, ExportStateStart,
+    };
+    use ai_resume_memvid::grpc::{AclInterceptor, MemvidV2Service};
+    use ai_resume_memvid::memvid::{AclMode, MockSearcher};
+    use std::sync::Arc;
+    use tokio_stream::wrappers::TcpListenerStream;
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let service = MemvidV2Service::new(Arc::new(MockSearcher::new()));
+    tokio::spawn(
+        tonic::transport::Server::builder()
+            .add_service(MemvidServiceServer::with_interceptor(
+                service,
+                AclInterceptor::new(AclMode::Enforce),
+            ))
+            .serve_with_incoming(TcpListenerStream::new(listener)),
+    );
+
+    let mut client = MemvidServiceClient::connect(format!("http://{}", addr))
+        .await
+        .unwrap();
+    let frames = tokio_stream::iter(vec![ExportFramesRequest {
+        request: Some(export_frames_request::Request::Start(
+            ExportFramesStart::default(),
+        )),
+    }]);
+    let status = client.export_frames(frames).await.unwrap_err();
+    assert_eq!(status.code(), tonic::Code::PermissionDenied);
+
+    let state = tokio_stream::iter(vec![ExportStateRequest {
+        request: Some(export_state_request::Request::Start(
+            ExportStateStart::default(),
+        )),
+    }]);
+    let status = client.export_state(state).await.unwrap_err();
+    assert_eq!(status.code(), tonic::Code::PermissionDenied);
+}
+
+#[tokio::test]
+async fn test_admin_rpcs_require_admin_token() {
+    use ai_resume_memvid::capabilities::CapabilityReport;
+    use ai_resume_memvid::config::Config;
+    use ai_resume_memvid::generated::memvid::v1::{
+        admin_client::AdminClient, admin_server::AdminServer, ExportIndexRequest, FrameVisibility,
+        GetCapabilitiesRequest, PurgeDataRequest, SetFrameVisibilityRequest, SetLogLevelRequest,
+        StageIndexRequest, WarmCacheRequest,
+    };
+    use ai_resume_memvid::grpc::{AdminAuth, AdminService};
+    use ai_resume_memvid::memvid::MockSearcher;
+    use std::sync::Arc;
+    use tokio_stream::wrappers::TcpListenerStream;
+    use tonic::Code;
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let searcher = Arc::new(MockSearcher::new());
+    let report = Arc::new(CapabilityReport::new(
+        &Config::default(),
+        searcher.as_ref(),
+        Vec::new(),
+    ));
+    let service = AdminService::new(report, searcher);
+    let auth = AdminAuth::new(Some("s3cret".to_string()));
+    tokio::spawn(
+        tonic::transport::Server::builder()
+            .add_service(AdminServer::with_interceptor(service, auth))
+            .serve_with_incoming(TcpListenerStream::new(listener)),
+    );
+
+    let channel = tonic::transport::Endpoint::from_shared(format!("http://{}", addr))
+        .unwrap()
+        .connect()
+        .await
+        .unwrap();
+    let mut client = AdminClient::new(channel);
+    fn as_admin<T>(message: T) -> tonic::Request<T> {
+        let mut request = tonic::Request::new(message);
+        request
+            .metadata_mut()
+            .insert("authorization", "Bearer s3cret".parse().unwrap());
+        request
+    }
+
+    let status = client
+        .get_capabilities(GetCapabilitiesRequest {})
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::Unauthenticated);
+    client
+        .get_capabilities(as_admin(GetCapabilitiesRequest {}))
+        .await
+        .unwrap();
+
+    // Hiding frames needs the token too
+    let status = client
+        .set_frame_visibility(SetFrameVisibilityRequest {
+            frame_ids: vec![1],
+            visibility: FrameVisibility::Hidden as i32,
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::Unauthenticated);
+
+    // As does purging retained data
+    let status = client
+        .purge_data(PurgeDataRequest::default())
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::Unauthenticated);
+
+    // And raising log verbosity
+    let status = client
+        .set_log_level(SetLogLevelRequest {
+            level: "trace".to_string(),
+            duration_secs: 60,
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::Unauthenticated);
+
+    // And warming the cache, which runs LLM calls
+    let status = client
+        .warm_cache(WarmCacheRequest {
+            questions: vec!["What is the current role?".to_string()],
+            use_llm: true,
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::Unauthenticated);
+
+    // And switching the serving index
+    let status = client
+        .stage_index(StageIndexRequest {
+            memvid_file: "/tmp/next.mv2".to_string(),
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::Unauthenticated);
+
+    // And dumping the index
+    let status = client
+        .export_index(ExportIndexRequest { omit_text: false })
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::Unauthenticated);
+}
+
 #[tokio::test]
 #[serial]
 async fn test_server_startup_and_shutdown_simulation() {